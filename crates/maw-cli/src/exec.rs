@@ -1,9 +1,13 @@
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use anyhow::{Context, Result, bail};
 use clap::Args;
+use maw_core::config::{ExecSandboxConfig, ManifoldConfig};
+use maw_core::model::layout::LayoutFlavor;
 use tracing::instrument;
 
+use crate::exec_sandbox::{self, SandboxPaths};
 use crate::workspace;
 
 /// Error indicating the child process exited with a non-zero status.
@@ -29,15 +33,30 @@ impl std::error::Error for ExitCodeError {}
 /// stale workspaces before execution; other commands run against the workspace
 /// as-is.
 ///
+/// With the sandbox on (`[workspace.exec_sandbox] enabled = true`, the
+/// workspace's `exec_sandbox` metadata, or `--sandbox`), the command runs in
+/// Linux user/mount namespaces via bubblewrap: only the workspace, tool caches
+/// and read-only system paths are visible. Blocked operations are listed after
+/// the command exits.
+///
 /// Examples:
 ///   maw exec alice -- cargo test
 ///   maw exec alice -- br list
 ///   maw exec alice -- ls -la src/
+///   maw exec --sandbox alice -- ./build.sh
 #[derive(Args, Debug)]
 pub struct ExecArgs {
     /// Workspace name
     pub workspace: String,
 
+    /// Run in the namespace sandbox even if it is not enabled
+    #[arg(long, conflicts_with = "no_sandbox")]
+    pub sandbox: bool,
+
+    /// Run without the namespace sandbox even if it is enabled
+    #[arg(long)]
+    pub no_sandbox: bool,
+
     /// Command and arguments to run (after --)
     #[arg(last = true, required = true)]
     pub cmd: Vec<String>,
@@ -91,17 +110,46 @@ pub fn run(args: &ExecArgs) -> Result<()> {
         workspace::auto_sync_if_stale(&args.workspace, &path)?;
     }
 
-    let mut cmd = Command::new(&args.cmd[0]);
-    cmd.args(&args.cmd[1..]).current_dir(&path);
+    // Warn when the workspace is over its quota.
+    if let Ok(root) = workspace::repo_root() {
+        workspace::disk::check_exec(&root, &args.workspace);
+    }
+
+    let sandbox = workspace::repo_root()
+        .ok()
+        .map(|root| sandbox_plan(args, &root, &path))
+        .transpose()?
+        .flatten();
+
+    let mut cmd = if let Some((config, paths)) = &sandbox {
+        exec_sandbox::command(config, paths, &args.cmd)?
+    } else {
+        let mut cmd = Command::new(&args.cmd[0]);
+        cmd.args(&args.cmd[1..]);
+        cmd
+    };
+    cmd.current_dir(&path);
 
     // Propagate trace context to child process so it joins the same trace
     if let Some(traceparent) = crate::telemetry::current_traceparent() {
         cmd.env("TRACEPARENT", traceparent);
     }
 
-    let status = cmd
-        .status()
-        .context(format!("Failed to run '{}'", args.cmd[0]))?;
+    let status = if let Some((config, _)) = &sandbox {
+        let (status, violations) = exec_sandbox::run(cmd, config.network)?;
+        if !violations.is_empty() {
+            tracing::warn!(
+                workspace = %args.workspace,
+                count = violations.len(),
+                "sandbox blocked operations"
+            );
+        }
+        exec_sandbox::report(&violations);
+        status
+    } else {
+        cmd.status()
+            .context(format!("Failed to run '{}'", args.cmd[0]))?
+    };
 
     if !status.success() {
         return Err(ExitCodeError(status.code().unwrap_or(1)).into());
@@ -110,9 +158,87 @@ pub fn run(args: &ExecArgs) -> Result<()> {
     Ok(())
 }
 
+/// Decide whether to sandbox this run and, if so, what to mount.
+///
+/// `--sandbox` / `--no-sandbox` win over the workspace's `exec_sandbox`
+/// metadata, which wins over `[workspace.exec_sandbox] enabled`.
+fn sandbox_plan(
+    args: &ExecArgs,
+    root: &Path,
+    ws_path: &Path,
+) -> Result<Option<(ExecSandboxConfig, SandboxPaths)>> {
+    let layout = LayoutFlavor::detect_with_env(root);
+    let config = ManifoldConfig::load(&layout.bootstrap_config_path(root))
+        .unwrap_or_default()
+        .workspace
+        .exec_sandbox;
+    let from_metadata = workspace::metadata::read(root, &args.workspace)
+        .ok()
+        .and_then(|meta| meta.exec_sandbox);
+    if !sandbox_enabled(args, from_metadata, config.enabled) {
+        return Ok(None);
+    }
+
+    let workspace = ws_path
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve workspace path {}", ws_path.display()))?;
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    // When the workspace is the repo root (the consolidated layout's default
+    // workspace), hide the other workspaces and Manifold metadata under it.
+    let masked = [layout.workspaces_dir(&root), layout.manifold_dir(&root)]
+        .into_iter()
+        .filter(|dir| dir.starts_with(&workspace) && dir.exists())
+        .collect();
+    let mut paths = SandboxPaths {
+        workspace,
+        masked,
+        home: std::env::var_os("HOME").map(PathBuf::from),
+        ..SandboxPaths::default()
+    };
+    let git_dir = root.join(".git");
+    if git_dir.exists() {
+        paths.expose_git_dir(git_dir);
+    }
+    Ok(Some((config, paths)))
+}
+
+const fn sandbox_enabled(args: &ExecArgs, from_metadata: Option<bool>, from_config: bool) -> bool {
+    if args.sandbox {
+        true
+    } else if args.no_sandbox {
+        false
+    } else if let Some(enabled) = from_metadata {
+        enabled
+    } else {
+        from_config
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::should_auto_sync;
+    use super::{ExecArgs, sandbox_enabled, should_auto_sync};
+
+    fn exec_args(sandbox: bool, no_sandbox: bool) -> ExecArgs {
+        ExecArgs {
+            workspace: "alice".to_owned(),
+            sandbox,
+            no_sandbox,
+            cmd: vec!["true".to_owned()],
+        }
+    }
+
+    #[test]
+    fn sandbox_flags_override_metadata_and_config() {
+        assert!(sandbox_enabled(&exec_args(true, false), Some(false), false));
+        assert!(!sandbox_enabled(&exec_args(false, true), Some(true), true));
+        assert!(!sandbox_enabled(
+            &exec_args(false, false),
+            Some(false),
+            true
+        ));
+        assert!(sandbox_enabled(&exec_args(false, false), None, true));
+        assert!(!sandbox_enabled(&exec_args(false, false), None, false));
+    }
 
     #[test]
     fn auto_syncs_git_commands() {
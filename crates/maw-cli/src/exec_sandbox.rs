@@ -0,0 +1,430 @@
+//! Namespace sandbox for `maw exec`.
+//!
+//! `maw exec <ws> -- cmd` normally only changes directory, so an agent's
+//! `rm -rf ..` or a build writing into a sibling workspace is unprotected.
+//! With the sandbox on, the command runs under bubblewrap (`bwrap`) in fresh
+//! user, PID and mount namespaces:
+//!
+//! * the workspace directory is the only writable project path;
+//! * tool caches (`~/.cargo`, `~/.cache`, ...) stay writable;
+//! * system paths (`/usr`, `/etc`, ...) are mounted read-only, and so is the
+//!   repository's `.git` apart from the workspace's own worktree admin dir
+//!   and the object store, so `git add` / `git commit` keep working;
+//! * everything else — sibling workspaces, the rest of `$HOME` — is absent,
+//!   and the root is read-only, so writes outside fail with `EROFS`;
+//! * with `network = false` the command also gets an empty network namespace.
+//!
+//! `unsafe_code` is forbidden in this workspace, so the namespaces are set up
+//! by the external `bwrap` binary rather than raw `unshare(2)` calls.
+//!
+//! Blocked operations show up as ordinary errors in the command's stderr.
+//! [`run`] forwards stderr unchanged while recognising those errors — `EROFS`
+//! writes, and network failures when the network is off — and [`report`]
+//! summarises them after the command exits.
+
+use std::ffi::OsString;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+
+use anyhow::{Context, Result, bail};
+use maw_core::config::ExecSandboxConfig;
+
+/// Environment variable set inside the sandbox so tools can detect it.
+pub const SANDBOX_ENV: &str = "MAW_SANDBOX";
+
+/// Most violations listed individually by [`report`].
+const REPORT_LIMIT: usize = 10;
+
+// ---------------------------------------------------------------------------
+// Mount plan
+// ---------------------------------------------------------------------------
+
+/// Paths the sandbox exposes besides the system paths in the config.
+#[derive(Clone, Debug, Default)]
+pub struct SandboxPaths {
+    /// The workspace directory (bound read-write, and the working directory).
+    pub workspace: PathBuf,
+    /// The repository's git directory, bound read-only so `git` can read
+    /// history.
+    pub git_dir: Option<PathBuf>,
+    /// Parts of the git directory bound read-write over it: what git
+    /// commands in this workspace write (see [`SandboxPaths::expose_git_dir`]).
+    pub git_writable: Vec<PathBuf>,
+    /// Directories inside the workspace hidden behind an empty tmpfs — the
+    /// other workspaces and Manifold metadata when the workspace is the repo
+    /// root itself.
+    pub masked: Vec<PathBuf>,
+    /// `$HOME`, used to expand `~/` in cache directories.
+    pub home: Option<PathBuf>,
+}
+
+impl SandboxPaths {
+    /// Expose the repository's git directory `git_dir` to the workspace.
+    ///
+    /// A linked worktree writes only its admin dir (`worktrees/<name>`:
+    /// `HEAD`, `index`, logs) and the object store, so just those are
+    /// writable. A workspace that is the main worktree keeps its `HEAD` and
+    /// index at the top of `git_dir`, so it gets the whole directory with the
+    /// other worktrees' admin dirs masked.
+    pub fn expose_git_dir(&mut self, git_dir: PathBuf) {
+        let dot_git = self.workspace.join(".git");
+        if dot_git.is_dir() {
+            let worktrees = git_dir.join("worktrees");
+            if worktrees.exists() {
+                self.masked.push(worktrees);
+            }
+            self.git_writable.push(git_dir.clone());
+        } else if let Some(admin) = std::fs::read_to_string(&dot_git)
+            .ok()
+            .and_then(|text| {
+                text.strip_prefix("gitdir:")
+                    .map(|dir| self.workspace.join(dir.trim()))
+            })
+            .and_then(|dir| dir.canonicalize().ok())
+            .filter(|dir| dir.starts_with(git_dir.join("worktrees")))
+        {
+            self.git_writable.push(admin);
+            self.git_writable.push(git_dir.join("objects"));
+        }
+        self.git_dir = Some(git_dir);
+    }
+}
+
+/// Expand a leading `~/` against `home`. Returns `None` when the path needs
+/// a home directory and there is none.
+fn expand_home(path: &str, home: Option<&Path>) -> Option<PathBuf> {
+    path.strip_prefix("~/").map_or_else(
+        || Some(PathBuf::from(path)),
+        |rest| home.map(|h| h.join(rest)),
+    )
+}
+
+fn push_mount(args: &mut Vec<OsString>, flag: &str, path: &Path) {
+    args.push(flag.into());
+    args.push(path.into());
+    args.push(path.into());
+}
+
+/// Build the `bwrap` arguments (everything before the command itself).
+///
+/// Later mounts shadow earlier ones, so the order matters: the workspace is
+/// bound after `/tmp` (workspaces may live under it), the read-only git
+/// directory and masks come after the workspace they may be nested in, and
+/// the writable parts of the git directory come after the git directory.
+#[must_use]
+pub fn bwrap_args(config: &ExecSandboxConfig, paths: &SandboxPaths) -> Vec<OsString> {
+    let mut args: Vec<OsString> = ["--unshare-user", "--unshare-pid", "--die-with-parent"]
+        .into_iter()
+        .map(OsString::from)
+        .collect();
+    if !config.network {
+        args.push("--unshare-net".into());
+    }
+    for path in &config.read_only {
+        push_mount(&mut args, "--ro-bind-try", Path::new(path));
+    }
+    for (flag, path) in [("--dev", "/dev"), ("--proc", "/proc"), ("--tmpfs", "/tmp")] {
+        args.push(flag.into());
+        args.push(path.into());
+    }
+    for dir in &config.cache_dirs {
+        if let Some(dir) = expand_home(dir, paths.home.as_deref()) {
+            push_mount(&mut args, "--bind-try", &dir);
+        }
+    }
+    push_mount(&mut args, "--bind", &paths.workspace);
+    if let Some(git_dir) = &paths.git_dir {
+        push_mount(&mut args, "--ro-bind-try", git_dir);
+    }
+    for dir in &paths.git_writable {
+        push_mount(&mut args, "--bind-try", dir);
+    }
+    for masked in &paths.masked {
+        args.push("--tmpfs".into());
+        args.push(masked.into());
+    }
+    args.push("--remount-ro".into());
+    args.push("/".into());
+    args.push("--chdir".into());
+    args.push(paths.workspace.as_os_str().to_owned());
+    args.push("--setenv".into());
+    args.push(SANDBOX_ENV.into());
+    args.push("1".into());
+    args.push("--".into());
+    args
+}
+
+/// Build the sandboxed command for `cmd` (program followed by its arguments).
+///
+/// # Errors
+///
+/// Returns an error on platforms other than Linux.
+pub fn command(
+    config: &ExecSandboxConfig,
+    paths: &SandboxPaths,
+    cmd: &[String],
+) -> Result<Command> {
+    if !cfg!(target_os = "linux") {
+        bail!(
+            "The maw exec sandbox needs Linux namespaces.\n  \
+             To fix: run without it (maw exec --no-sandbox ...) or set \
+             `enabled = false` under [workspace.exec_sandbox]"
+        );
+    }
+    let mut command = Command::new("bwrap");
+    command.args(bwrap_args(config, paths)).args(cmd);
+    Ok(command)
+}
+
+// ---------------------------------------------------------------------------
+// Violations
+// ---------------------------------------------------------------------------
+
+/// What a blocked operation tried to do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ViolationKind {
+    /// A write outside the paths the sandbox allows (`EROFS`).
+    Write,
+    /// Network access with the network disabled.
+    Network,
+}
+
+impl std::fmt::Display for ViolationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Self::Write => "write",
+            Self::Network => "network",
+        })
+    }
+}
+
+/// One blocked operation, as reported on the command's stderr.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// What was blocked.
+    pub kind: ViolationKind,
+    /// The stderr line reporting it.
+    pub line: String,
+}
+
+/// Recognise a stderr line reporting an operation the sandbox blocked.
+///
+/// Only `EROFS` comes from the sandbox's read-only root; a plain "permission
+/// denied" is ordinary file permissions. Network errors only count when the
+/// network is disabled — with it on they are the command's own business.
+#[must_use]
+pub fn classify(line: &str, network: bool) -> Option<ViolationKind> {
+    const WRITE: &[&str] = &["read-only file system"];
+    const NETWORK: &[&str] = &[
+        "network is unreachable",
+        "could not resolve host",
+        "temporary failure in name resolution",
+        "name or service not known",
+        "failed to lookup address",
+    ];
+    let lower = line.to_ascii_lowercase();
+    if WRITE.iter().any(|p| lower.contains(p)) {
+        Some(ViolationKind::Write)
+    } else if !network && NETWORK.iter().any(|p| lower.contains(p)) {
+        Some(ViolationKind::Network)
+    } else {
+        None
+    }
+}
+
+/// Run a sandboxed command, forwarding its stderr and collecting the
+/// violations reported there. Stdin and stdout stay attached to the terminal.
+///
+/// # Errors
+///
+/// Returns an error if `bwrap` is missing or the command cannot be run.
+pub fn run(mut command: Command, network: bool) -> Result<(ExitStatus, Vec<Violation>)> {
+    let mut child = match command.stderr(Stdio::piped()).spawn() {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => bail!(
+            "The maw exec sandbox needs bubblewrap, but `bwrap` is not on PATH.\n  \
+             To fix: install bubblewrap (e.g. apt install bubblewrap), or run \
+             without the sandbox: maw exec --no-sandbox ..."
+        ),
+        Err(e) => return Err(e).context("Failed to start the sandboxed command"),
+    };
+
+    let mut violations = Vec::new();
+    if let Some(stderr) = child.stderr.take() {
+        let mut out = std::io::stderr().lock();
+        let mut reader = BufReader::new(stderr);
+        let mut line = Vec::new();
+        while reader.read_until(b'\n', &mut line).unwrap_or(0) > 0 {
+            // Forwarding is best-effort: a closed stderr must not kill the run.
+            let _ = out.write_all(&line);
+            let text = String::from_utf8_lossy(&line);
+            if let Some(kind) = classify(&text, network) {
+                violations.push(Violation {
+                    kind,
+                    line: text.trim_end().to_owned(),
+                });
+            }
+            line.clear();
+        }
+    }
+
+    let status = child
+        .wait()
+        .context("Failed to wait for the sandboxed command")?;
+    Ok((status, violations))
+}
+
+/// Summarise `violations` on stderr. Prints nothing when there are none.
+pub fn report(violations: &[Violation]) {
+    if violations.is_empty() {
+        return;
+    }
+    eprintln!();
+    eprintln!(
+        "sandbox: blocked {} operation(s) outside the workspace:",
+        violations.len()
+    );
+    for v in violations.iter().take(REPORT_LIMIT) {
+        eprintln!("  {:<7}  {}", v.kind, v.line);
+    }
+    if violations.len() > REPORT_LIMIT {
+        eprintln!("  ... and {} more", violations.len() - REPORT_LIMIT);
+    }
+    eprintln!("  To run unsandboxed: maw exec --no-sandbox <workspace> -- <command>");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args_of(config: &ExecSandboxConfig, paths: &SandboxPaths) -> Vec<String> {
+        bwrap_args(config, paths)
+            .into_iter()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn position(args: &[String], window: &[&str]) -> usize {
+        args.windows(window.len())
+            .position(|w| w == window)
+            .unwrap_or_else(|| panic!("{window:?} missing from {args:?}"))
+    }
+
+    #[test]
+    fn bwrap_args_bind_workspace_after_tmp_and_mask_nested_dirs() {
+        let config = ExecSandboxConfig {
+            network: false,
+            cache_dirs: vec!["~/.cargo".to_owned(), "/opt/cache".to_owned()],
+            read_only: vec!["/usr".to_owned()],
+            ..ExecSandboxConfig::default()
+        };
+        let paths = SandboxPaths {
+            workspace: PathBuf::from("/tmp/repo"),
+            git_dir: Some(PathBuf::from("/tmp/repo/.git")),
+            git_writable: vec![PathBuf::from("/tmp/repo/.git/objects")],
+            masked: vec![PathBuf::from("/tmp/repo/.maw/workspaces")],
+            home: Some(PathBuf::from("/home/agent")),
+        };
+        let args = args_of(&config, &paths);
+
+        assert!(args.contains(&"--unshare-net".to_owned()));
+        position(&args, &["--ro-bind-try", "/usr", "/usr"]);
+        position(
+            &args,
+            &["--bind-try", "/home/agent/.cargo", "/home/agent/.cargo"],
+        );
+        position(&args, &["--bind-try", "/opt/cache", "/opt/cache"]);
+        let tmp = position(&args, &["--tmpfs", "/tmp"]);
+        let ws = position(&args, &["--bind", "/tmp/repo", "/tmp/repo"]);
+        let git = position(
+            &args,
+            &["--ro-bind-try", "/tmp/repo/.git", "/tmp/repo/.git"],
+        );
+        let objects = position(
+            &args,
+            &[
+                "--bind-try",
+                "/tmp/repo/.git/objects",
+                "/tmp/repo/.git/objects",
+            ],
+        );
+        let mask = position(&args, &["--tmpfs", "/tmp/repo/.maw/workspaces"]);
+        let ro = position(&args, &["--remount-ro", "/"]);
+        assert!(tmp < ws && ws < git && git < objects && objects < mask && mask < ro);
+        position(&args, &["--chdir", "/tmp/repo"]);
+        assert_eq!(args.last().map(String::as_str), Some("--"));
+    }
+
+    #[test]
+    fn bwrap_args_keep_network_and_skip_home_caches_without_home() {
+        let config = ExecSandboxConfig::default();
+        let paths = SandboxPaths {
+            workspace: PathBuf::from("/repo/ws/alice"),
+            ..SandboxPaths::default()
+        };
+        let args = args_of(&config, &paths);
+        assert!(!args.contains(&"--unshare-net".to_owned()));
+        assert!(!args.iter().any(|a| a.contains(".cargo")));
+    }
+
+    #[test]
+    fn classify_recognises_blocked_writes_and_network() {
+        assert_eq!(
+            classify("rm: cannot remove '../bob/x': Read-only file system", true),
+            Some(ViolationKind::Write)
+        );
+        assert_eq!(
+            classify("curl: (6) Could not resolve host: example.com", false),
+            Some(ViolationKind::Network)
+        );
+        assert_eq!(
+            classify("curl: (6) Could not resolve host: example.com", true),
+            None
+        );
+        assert_eq!(classify("error[E0308]: mismatched types", false), None);
+        assert_eq!(classify("open /etc/shadow: Permission denied", false), None);
+    }
+
+    #[test]
+    fn linked_worktree_writes_only_its_admin_dir_and_objects() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path().canonicalize().expect("canonicalize");
+        let git_dir = root.join(".git");
+        let admin = git_dir.join("worktrees").join("alice");
+        std::fs::create_dir_all(&admin).expect("admin dir");
+        std::fs::create_dir_all(git_dir.join("worktrees").join("bob")).expect("admin dir");
+        let workspace = root.join("ws").join("alice");
+        std::fs::create_dir_all(&workspace).expect("workspace");
+        std::fs::write(
+            workspace.join(".git"),
+            format!("gitdir: {}\n", admin.display()),
+        )
+        .expect("gitfile");
+
+        let mut paths = SandboxPaths {
+            workspace,
+            ..SandboxPaths::default()
+        };
+        paths.expose_git_dir(git_dir.clone());
+        assert_eq!(paths.git_dir.as_deref(), Some(git_dir.as_path()));
+        assert_eq!(paths.git_writable, vec![admin, git_dir.join("objects")]);
+        assert!(paths.masked.is_empty());
+    }
+
+    #[test]
+    fn main_worktree_writes_git_dir_without_other_worktrees() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path().canonicalize().expect("canonicalize");
+        let git_dir = root.join(".git");
+        std::fs::create_dir_all(git_dir.join("worktrees").join("bob")).expect("admin dir");
+
+        let mut paths = SandboxPaths {
+            workspace: root,
+            ..SandboxPaths::default()
+        };
+        paths.expose_git_dir(git_dir.clone());
+        assert_eq!(paths.git_writable, vec![git_dir.clone()]);
+        assert_eq!(paths.masked, vec![git_dir.join("worktrees")]);
+    }
+}
@@ -13,6 +13,7 @@ pub mod epoch_lock;
 #[allow(dead_code)]
 pub mod error;
 pub mod exec;
+pub mod exec_sandbox;
 pub mod format;
 pub mod fsck;
 pub mod init;
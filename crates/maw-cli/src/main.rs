@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use clap_complete::Shell;
@@ -477,6 +477,20 @@ enum Commands {
     /// `maw --help`. `crib` is a hidden alias (the former name).
     #[command(alias = "crib")]
     Tldr,
+
+    /// Serve a FUSE workspace until it is unmounted (internal)
+    ///
+    /// Started in the background by the fuse backend; not meant to be run
+    /// by hand.
+    #[command(hide = true, name = workspace::FUSE_SERVE_COMMAND)]
+    FuseServe {
+        /// Repository root
+        #[arg(long)]
+        root: PathBuf,
+
+        /// Workspace name
+        name: String,
+    },
 }
 
 /// Subcommands under `maw ops`.
@@ -572,6 +586,28 @@ fn parse_cli_with_vocab_hints() -> Cli {
     }
 }
 
+/// Top-level `maw ls`: a plain `maw ws list`.
+fn run_ls(names: bool) -> anyhow::Result<()> {
+    workspace::run(workspace::WorkspaceCommands::List {
+        verbose: false,
+        check: false,
+        disk: false,
+        format: None,
+        json: false,
+        names,
+    })
+}
+
+/// Hidden `maw fuse-serve`: run the FUSE daemon of one workspace.
+fn run_fuse_serve(root: &Path, name: &str) -> anyhow::Result<()> {
+    use maw_core::backend::fuse::FuseBackend;
+    use maw_core::model::types::WorkspaceId;
+
+    let name = WorkspaceId::new(name).map_err(|e| anyhow::anyhow!("{e}"))?;
+    FuseBackend::new(root.to_path_buf(), workspace::fuse_daemon()?)?.serve(&name)?;
+    Ok(())
+}
+
 fn main() {
     let _telemetry = telemetry::init();
     // bn-263u: seed the failpoint registry from `MAW_FP` so the *shipped*
@@ -588,13 +624,7 @@ fn main() {
             println!("{}", path.display());
         }),
         Commands::Workspace(cmd) => workspace::run(cmd),
-        Commands::Ls { names } => workspace::run(workspace::WorkspaceCommands::List {
-            verbose: false,
-            check: false,
-            format: None,
-            json: false,
-            names,
-        }),
+        Commands::Ls { names } => run_ls(names),
         Commands::Agents(ref cmd) => agents::run(cmd),
         Commands::Changes(ref cmd) => changes::run(cmd),
         Commands::Init { legacy_ws } => init::run_with(&init::InitRunOptions {
@@ -675,6 +705,7 @@ fn main() {
             ops_log::run(format::OutputFormat::with_json_flag(format, json))
         }
         Commands::Tldr => tldr::run(),
+        Commands::FuseServe { ref root, ref name } => run_fuse_serve(root, name),
     };
 
     if let Err(e) = result {
@@ -831,6 +862,58 @@ mod tests {
         assert!(err.contains("--into"), "error should mention --into: {err}");
     }
 
+    #[test]
+    fn ws_merge_all_ready_schedule_parses_without_workspaces() {
+        use maw_cli::workspace::WorkspaceCommands;
+
+        let parsed = Cli::try_parse_from([
+            "maw",
+            "ws",
+            "merge",
+            "--all-ready",
+            "--schedule",
+            "--into",
+            "default",
+        ])
+        .expect("`maw ws merge --all-ready --schedule --into default` must parse");
+        let Commands::Workspace(WorkspaceCommands::Merge {
+            workspaces,
+            all_ready,
+            schedule,
+            ..
+        }) = parsed.command
+        else {
+            panic!("must route to Commands::Workspace(Merge)");
+        };
+        assert!(workspaces.is_empty());
+        assert!(all_ready && schedule);
+
+        let result = Cli::try_parse_from([
+            "maw",
+            "ws",
+            "merge",
+            "alice",
+            "--all-ready",
+            "--into",
+            "default",
+        ]);
+        assert!(
+            result.is_err(),
+            "--all-ready conflicts with explicit workspaces"
+        );
+        let result = Cli::try_parse_from([
+            "maw",
+            "ws",
+            "merge",
+            "alice",
+            "--schedule",
+            "--check",
+            "--into",
+            "default",
+        ]);
+        assert!(result.is_err(), "--schedule conflicts with --check");
+    }
+
     /// bn-2to8: `maw ws recover --to`, `--into`, and `--restore-as` must all
     /// resolve to the same `WorkspaceCommands::Recover { to, .. }` field. The
     /// SG3 R6 friction was agents reaching for `--into` (merge's verb) on
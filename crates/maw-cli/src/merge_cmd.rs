@@ -1,6 +1,7 @@
 //! Top-level `maw merge` subcommand — quarantine lifecycle management.
 //!
-//! Provides `maw merge promote <merge_id>` and `maw merge abandon <merge_id>`.
+//! Provides `maw merge promote <merge_id>` and `maw merge abandon <merge_id>`,
+//! plus the local merge queue (`maw merge queue ...`).
 //!
 //! These commands manage quarantine workspaces created when post-merge
 //! validation fails with `on_failure = "quarantine"` or `on_failure =
@@ -140,6 +141,59 @@ pub enum MergeCommands {
         #[arg(long)]
         dry_run: bool,
     },
+
+    /// Local merge queue with speculative validation.
+    ///
+    /// Queued merges are committed in order by `maw merge queue run`, which
+    /// builds the candidate epoch for each of the next K entries on top of
+    /// the entries ahead of it and validates all of them in parallel. An
+    /// entry whose candidate fails validation or conflicts is ejected; the
+    /// entries ahead of it still merge.
+    ///
+    /// Examples:
+    ///   maw merge queue add alice --message "feat: parser"
+    ///   maw merge queue add bob carol --message "feat: lexer"
+    ///   maw merge queue list
+    ///   maw merge queue run --depth 8
+    #[command(subcommand, verbatim_doc_comment)]
+    Queue(QueueCommands),
+}
+
+/// `maw merge queue` subcommands.
+#[derive(Subcommand)]
+pub enum QueueCommands {
+    /// Enqueue a merge of one or more workspaces.
+    Add {
+        /// Workspaces to merge together.
+        #[arg(required = true)]
+        workspaces: Vec<String>,
+        /// Commit message for the merge.
+        #[arg(short, long)]
+        message: String,
+    },
+
+    /// List queued merges in merge order.
+    List {
+        /// Output format: text or json (default text).
+        #[arg(long)]
+        format: Option<OutputFormat>,
+    },
+
+    /// Remove a queued merge without merging it.
+    Remove {
+        /// Queue entry id (e.g. `q-3`).
+        id: String,
+    },
+
+    /// Process the queue until it is empty or an entry cannot merge.
+    Run {
+        /// Number of entries to speculate on (and validate in parallel) per round.
+        #[arg(long, default_value_t = crate::workspace::queue::DEFAULT_DEPTH)]
+        depth: usize,
+        /// Output format: text or json (default text).
+        #[arg(long)]
+        format: Option<OutputFormat>,
+    },
 }
 
 /// # Errors
@@ -161,6 +215,20 @@ pub fn run(cmd: &MergeCommands) -> Result<()> {
             resolve_all,
             dry_run,
         } => resume_cmd(resolve, resolve_all.as_deref(), *dry_run),
+        MergeCommands::Queue(cmd) => queue_cmd(cmd),
+    }
+}
+
+fn queue_cmd(cmd: &QueueCommands) -> Result<()> {
+    use crate::workspace::queue;
+    match cmd {
+        QueueCommands::Add {
+            workspaces,
+            message,
+        } => queue::add(workspaces, message),
+        QueueCommands::List { format } => queue::list(OutputFormat::resolve(*format)),
+        QueueCommands::Remove { id } => queue::remove(id),
+        QueueCommands::Run { depth, format } => queue::run(*depth, OutputFormat::resolve(*format)),
     }
 }
 
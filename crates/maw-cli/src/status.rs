@@ -14,6 +14,7 @@ use crate::changes::store::ChangesStore;
 use crate::doctor;
 use crate::format::OutputFormat;
 use crate::push::{SyncStatus, main_sync_status_inner};
+use crate::workspace::disk::RepoDisk;
 use crate::workspace::lifecycle::{LifecycleSignals, LifecycleState};
 use crate::workspace::{self, MawConfig, get_backend};
 use maw_core::backend::WorkspaceBackend;
@@ -105,6 +106,7 @@ pub fn run(args: &StatusArgs) -> Result<()> {
             current_workspace_state: summary.current_workspace_state,
             stale_workspaces: summary.stale_workspaces.clone(),
             integrate_ready: summary.integrate_ready,
+            disk: summary.disk,
         };
         println!("{}", format.serialize(&envelope)?);
         return Ok(());
@@ -197,6 +199,10 @@ struct StatusEnvelope {
     /// is not yet on the integration branch. Mirrors the "what to
     /// integrate" leg of the mitigation class.
     integrate_ready: Vec<IntegrateReady>,
+    /// Summed workspace disk usage against the `[workspace.quota]` budget.
+    /// Absent unless quotas are configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disk: Option<RepoDisk>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -310,6 +316,8 @@ struct StatusSummary {
     stale_workspaces: Vec<StaleWorkspace>,
     /// bn-221b: workspaces with committed work ready to integrate.
     integrate_ready: Vec<IntegrateReady>,
+    /// Workspace disk usage; only measured when quotas are configured.
+    disk: Option<RepoDisk>,
 }
 
 impl StatusSummary {
@@ -458,6 +466,20 @@ impl StatusSummary {
             !self.is_stale,
         ));
 
+        if let Some(disk) = &self.disk {
+            out.push_str(&text_status_line(
+                "Workspace disk",
+                &disk.describe(),
+                !disk.over_budget() && disk.over_quota.is_empty(),
+            ));
+            for name in &disk.over_quota {
+                let _ = writeln!(
+                    out,
+                    "  - {name} over quota (fix: maw ws clean-build {name})"
+                );
+            }
+        }
+
         out
     }
 
@@ -592,6 +614,20 @@ impl StatusSummary {
             !self.is_stale,
         ));
 
+        if let Some(disk) = &self.disk {
+            out.push_str(&status_line(
+                "Workspace disk",
+                &disk.describe(),
+                !disk.over_budget() && disk.over_quota.is_empty(),
+            ));
+            for name in &disk.over_quota {
+                let _ = writeln!(
+                    out,
+                    "  - {name} over quota (fix: maw ws clean-build {name})"
+                );
+            }
+        }
+
         out
     }
 
@@ -808,6 +844,17 @@ fn collect_status() -> Result<StatusSummary> {
     // Check for stray files at repo root
     let stray_root_files = doctor::stray_root_entries(&root);
 
+    // Disk accounting walks every workspace, so only do it when quotas
+    // make the numbers actionable.
+    let quota = workspace::disk::load_quota(&root);
+    let disk = if quota.is_configured() {
+        workspace::disk::measure_all(&root, &quota)
+            .ok()
+            .map(|(_, repo)| repo)
+    } else {
+        None
+    };
+
     Ok(StatusSummary {
         workspace_names,
         workspace_details,
@@ -821,6 +868,7 @@ fn collect_status() -> Result<StatusSummary> {
         current_workspace_state,
         stale_workspaces,
         integrate_ready,
+        disk,
     })
 }
 
@@ -1068,6 +1116,7 @@ fn collect_status_fast() -> Result<StatusSummary> {
         current_workspace_state: None,
         stale_workspaces: Vec::new(),
         integrate_ready: Vec::new(),
+        disk: None,
     })
 }
 
@@ -1136,6 +1185,7 @@ mod tests {
             current_workspace_state: None,
             stale_workspaces: Vec::new(),
             integrate_ready: Vec::new(),
+            disk: None,
         }
     }
 
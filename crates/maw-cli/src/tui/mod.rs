@@ -1,10 +1,21 @@
 //! TUI -- re-exported from maw-tui crate.
 
-use std::path::PathBuf;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
+use maw::merge::diff3::{Diff3Segment, diff3_segments};
 use maw_core::backend::WorkspaceBackend;
-use maw_tui::{RepoDataSource, WorkspaceEntry};
+use maw_core::model::conflict::Conflict;
+use maw_git::GixRepo;
+use maw_tui::{
+    ConflictFile, ConflictHunk, ConflictSegment, HunkChoice, HunkSide, RepoDataSource,
+    WorkspaceEntry,
+};
+
+use crate::workspace::resolve_structured::{
+    ConflictContents, conflict_contents, read_conflict_tree_sidecar, resolve_with_content,
+};
 
 /// Bridge from maw-cli workspace subsystem to maw-tui's `RepoDataSource` trait.
 struct CliDataSource;
@@ -39,6 +50,130 @@ impl RepoDataSource for CliDataSource {
         }
         Ok(entries)
     }
+
+    fn conflicted_files(&self, workspace: &str) -> Result<Vec<ConflictFile>> {
+        let root = crate::workspace::repo_root()?;
+        let Some(tree) = read_conflict_tree_sidecar(&root, workspace) else {
+            return Ok(Vec::new());
+        };
+        let ws_path = crate::workspace::workspace_path(workspace)?;
+        let repo = GixRepo::open(&ws_path).map_err(|e| {
+            anyhow::anyhow!("Failed to open git repo at {}: {e}", ws_path.display())
+        })?;
+        Ok(tree
+            .conflicts
+            .iter()
+            .filter_map(|(path, conflict)| conflict_file(&repo, path, conflict))
+            .collect())
+    }
+
+    fn write_resolution(&self, workspace: &str, path: &str, content: &str) -> Result<String> {
+        let root = crate::workspace::repo_root()?;
+        let ws_path = crate::workspace::workspace_path(workspace)?;
+        let outcome = resolve_with_content(
+            &root,
+            workspace,
+            &ws_path,
+            Path::new(path),
+            content.as_bytes(),
+        )?;
+        let mut summary = format!("Resolved {path}");
+        if let Some(failure) = &outcome.sanity_failure {
+            let _ = write!(summary, " (sanity check failed: {failure}; not committed)");
+        } else if let Some(sha) = &outcome.auto_committed {
+            let _ = write!(summary, " — committed {}", &sha[..sha.len().min(12)]);
+        }
+        if outcome.conflicts_remaining > 0 {
+            let _ = write!(
+                summary,
+                "; {} conflict(s) remaining",
+                outcome.conflicts_remaining
+            );
+        }
+        Ok(summary)
+    }
+}
+
+/// Split a content or add/add conflict into context and hunks for the
+/// resolver. Two-sided conflicts are split per diff3 hunk; N-way conflicts
+/// (and anything diff3 can't split) become one whole-file hunk. Other
+/// conflict kinds and non-UTF-8 content are left to `maw ws resolve`.
+fn conflict_file(repo: &GixRepo, path: &Path, conflict: &Conflict) -> Option<ConflictFile> {
+    let ConflictContents { base, sides } = conflict_contents(repo, conflict)?;
+    let base = base.map(String::from_utf8).transpose().ok()?;
+    let texts = sides
+        .into_iter()
+        .map(String::from_utf8)
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    let (names, atoms) = match conflict {
+        Conflict::Content { sides, atoms, .. } => (sides, atoms.as_slice()),
+        Conflict::AddAdd { sides, .. } => (sides, &[][..]),
+        Conflict::ModifyDelete { .. } | Conflict::DivergentRename { .. } => return None,
+    };
+    let names: Vec<String> = names.iter().map(|s| s.workspace.clone()).collect();
+    let hunk_sides = |parts: Vec<String>| -> Vec<HunkSide> {
+        names
+            .iter()
+            .zip(parts)
+            .map(|(name, text)| HunkSide {
+                name: name.clone(),
+                text,
+            })
+            .collect()
+    };
+
+    let split = match texts.as_slice() {
+        [ours, theirs] => diff3_segments(base.as_deref().unwrap_or_default(), ours, theirs),
+        _ => None,
+    };
+    let segments = match split {
+        Some(split) => {
+            let hunk_count = split
+                .iter()
+                .filter(|s| matches!(s, Diff3Segment::Conflict(_)))
+                .count();
+            let mut n = 0;
+            split
+                .into_iter()
+                .map(|segment| match segment {
+                    Diff3Segment::Common(lines) => ConflictSegment::Context(lines.concat()),
+                    Diff3Segment::Conflict(hunk) => {
+                        let label = if atoms.len() == hunk_count {
+                            atoms[n].to_string()
+                        } else {
+                            format!("conflict {} of {hunk_count}", n + 1)
+                        };
+                        n += 1;
+                        ConflictSegment::Hunk(ConflictHunk {
+                            label,
+                            base: base.as_ref().map(|_| hunk.base.concat()),
+                            sides: hunk_sides(vec![hunk.ours.concat(), hunk.theirs.concat()]),
+                            choice: HunkChoice::Unresolved,
+                        })
+                    }
+                })
+                .collect()
+        }
+        None => vec![ConflictSegment::Hunk(ConflictHunk {
+            label: if atoms.is_empty() {
+                format!("whole file ({})", conflict.variant_name())
+            } else {
+                atoms
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            },
+            base,
+            sides: hunk_sides(texts),
+            choice: HunkChoice::Unresolved,
+        })],
+    };
+    Some(ConflictFile {
+        path: path.display().to_string(),
+        segments,
+    })
 }
 
 /// Run the TUI application.
@@ -0,0 +1,397 @@
+//! Shared build artifacts between workspaces (`[artifacts]` in `.maw.toml`).
+//!
+//! Build directories such as `target/` and `node_modules/` live inside each
+//! workspace, so every new workspace starts cold. With `[artifacts]`
+//! configured, `maw ws create` seeds the new workspace's build directories
+//! from the warmest workspace on the same epoch, or from the per-epoch
+//! artifact cache when no such workspace has them:
+//!
+//! ```toml
+//! [artifacts.paths]
+//! "target" = "reflink"
+//! "packages/*/node_modules" = "auto"
+//! ```
+//!
+//! Each path glob (relative to the workspace root) names how its directories
+//! are cloned:
+//!
+//! - `auto` — reflink where the filesystem supports it, otherwise copy;
+//! - `reflink` — reflink only; skipped when the filesystem cannot;
+//! - `copy` — a full copy.
+//!
+//! Every mode gives each workspace its own files, so workspaces never share
+//! mutable state. Hard links are deliberately not offered: a tool that edits
+//! a linked file in place would change it in every workspace (and the cache).
+//!
+//! The cache lives in `LayoutFlavor::cache_dir`
+//! (`.maw/cache/artifacts/e-<epoch>/`, consolidated layout only). It is
+//! filled when `maw ws merge --destroy` removes a workspace: its build
+//! directories are moved (not copied) there, keyed by the new epoch, and
+//! caches for older epochs are dropped.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result, bail};
+use maw_core::backend::{AnyBackend, WorkspaceBackend};
+use maw_core::model::layout::LayoutFlavor;
+use maw_core::model::types::{EpochId, WorkspaceInfo};
+use serde::Deserialize;
+
+use super::MawConfig;
+
+/// `[artifacts]` section of `.maw.toml`.
+#[derive(Debug, Deserialize)]
+pub struct ArtifactsConfig {
+    /// Build-directory globs, relative to the workspace root, and how to
+    /// clone the directories they match.
+    #[serde(default)]
+    pub(crate) paths: BTreeMap<String, LinkMode>,
+    /// Keep the per-epoch artifact cache (default `true`).
+    #[serde(default = "ArtifactsConfig::default_cache")]
+    pub(crate) cache: bool,
+}
+
+impl Default for ArtifactsConfig {
+    fn default() -> Self {
+        Self {
+            paths: BTreeMap::new(),
+            cache: Self::default_cache(),
+        }
+    }
+}
+
+impl ArtifactsConfig {
+    const fn default_cache() -> bool {
+        true
+    }
+}
+
+/// How a build directory is cloned into a new workspace.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkMode {
+    /// Reflink where supported, otherwise copy.
+    #[default]
+    Auto,
+    /// Reflink only.
+    Reflink,
+    /// Full copy.
+    Copy,
+}
+
+impl LinkMode {
+    const fn cp_args(self) -> &'static [&'static str] {
+        match self {
+            Self::Auto => &["-a", "--reflink=auto"],
+            Self::Reflink => &["-a", "--reflink=always"],
+            Self::Copy => &["-a"],
+        }
+    }
+}
+
+impl std::fmt::Display for LinkMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Self::Auto => "auto",
+            Self::Reflink => "reflink",
+            Self::Copy => "copy",
+        })
+    }
+}
+
+/// One build directory cloned into a new workspace.
+#[derive(Clone, Debug)]
+pub struct Seeded {
+    /// Directory relative to the workspace root.
+    pub(crate) path: PathBuf,
+    /// Where it came from: a workspace name or `cache`.
+    pub(crate) source: String,
+    pub(crate) mode: LinkMode,
+}
+
+/// Directories under `root` matching `pattern`, relative to `root`.
+///
+/// Matches inside `.git` or Manifold's own directories (the repo root is a
+/// source in the consolidated layout) and matches nested in another match
+/// (e.g. `node_modules/x/node_modules` for `**/node_modules`) are dropped.
+fn matching_dirs(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let full = root.join(pattern);
+    let Ok(paths) = glob::glob(&full.to_string_lossy()) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = paths
+        .flatten()
+        .filter(|p| p.is_dir() && !p.is_symlink())
+        .filter_map(|p| p.strip_prefix(root).ok().map(Path::to_path_buf))
+        .filter(|rel| {
+            !rel.components().any(|c| c.as_os_str() == ".git")
+                && !rel.starts_with(".maw")
+                && !rel.starts_with(".manifold")
+        })
+        .collect();
+    dirs.sort();
+    let mut kept: Vec<PathBuf> = Vec::new();
+    for dir in dirs {
+        if !kept.iter().any(|k| dir.starts_with(k)) {
+            kept.push(dir);
+        }
+    }
+    kept
+}
+
+/// Most recent modification time among `dirs` under `root`.
+fn warmth(root: &Path, dirs: &[PathBuf]) -> Option<SystemTime> {
+    dirs.iter()
+        .filter_map(|d| {
+            std::fs::metadata(root.join(d))
+                .and_then(|m| m.modified())
+                .ok()
+        })
+        .max()
+}
+
+/// Clone directory `src` to `dst` (which must not exist).
+fn clone_dir(src: &Path, dst: &Path, mode: LinkMode) -> Result<()> {
+    if let Some(parent) = dst.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let output = Command::new("cp")
+        .args(mode.cp_args())
+        .arg(src)
+        .arg(dst)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .context("Failed to run cp")?;
+    if !output.status.success() {
+        // Don't leave a half-cloned directory behind for the build to trip on.
+        let _ = std::fs::remove_dir_all(dst);
+        bail!(
+            "cp {} {} {} failed: {}",
+            mode.cp_args().join(" "),
+            src.display(),
+            dst.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Per-epoch cache directory, or `None` in the v2 layout (no cache dir).
+fn cache_root(root: &Path, epoch: &EpochId) -> Option<PathBuf> {
+    LayoutFlavor::detect_with_env(root)
+        .cache_dir(root)
+        .map(|dir| dir.join("artifacts").join(format!("e-{}", epoch.as_str())))
+}
+
+/// A place build directories can be seeded from.
+struct Source {
+    name: String,
+    root: PathBuf,
+}
+
+/// Candidate sources for a workspace on `epoch`: other workspaces on the
+/// same epoch (including the consolidated default, the repo root, when it is
+/// on that epoch), then the per-epoch cache.
+fn sources(root: &Path, backend: &AnyBackend, target: &WorkspaceInfo, cache: bool) -> Vec<Source> {
+    let mut sources: Vec<Source> = backend
+        .list()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|ws| ws.id != target.id && ws.epoch == target.epoch && ws.path.exists())
+        .map(|ws| Source {
+            name: ws.id.as_str().to_owned(),
+            root: ws.path,
+        })
+        .collect();
+    let layout = LayoutFlavor::detect_with_env(root);
+    if layout == LayoutFlavor::ConsolidatedMawDir
+        && maw_core::refs::read_epoch_current(root)
+            .ok()
+            .flatten()
+            .is_some_and(|current| current.as_str() == target.epoch.as_str())
+    {
+        sources.push(Source {
+            name: super::DEFAULT_WORKSPACE.to_owned(),
+            root: root.to_path_buf(),
+        });
+    }
+    if cache && let Some(dir) = cache_root(root, &target.epoch) {
+        sources.push(Source {
+            name: "cache".to_owned(),
+            root: dir,
+        });
+    }
+    sources
+}
+
+/// Seed a freshly created workspace's build directories.
+///
+/// For each configured glob, the warmest source that has matching
+/// directories wins (workspaces before the cache on a tie). Directories that
+/// already exist in the new workspace are left alone. Failures for one
+/// directory are reported and skipped — seeding only ever saves time.
+///
+/// # Errors
+///
+/// Returns an error if `.maw.toml` cannot be loaded.
+pub fn seed(root: &Path, backend: &AnyBackend, target: &WorkspaceInfo) -> Result<Vec<Seeded>> {
+    let config = MawConfig::load(root)?;
+    let config = config.artifacts();
+    if config.paths.is_empty() {
+        return Ok(Vec::new());
+    }
+    let sources = sources(root, backend, target, config.cache);
+
+    let mut seeded = Vec::new();
+    for (pattern, &mode) in &config.paths {
+        let best = sources
+            .iter()
+            .filter_map(|source| {
+                let dirs = matching_dirs(&source.root, pattern);
+                warmth(&source.root, &dirs).map(|at| (at, source, dirs))
+            })
+            .fold(
+                None,
+                |best: Option<(SystemTime, &Source, Vec<PathBuf>)>, cand| match best {
+                    Some(b) if b.0 >= cand.0 => Some(b),
+                    _ => Some(cand),
+                },
+            );
+        let Some((_, source, dirs)) = best else {
+            continue;
+        };
+        for rel in dirs {
+            let dst = target.path.join(&rel);
+            if dst.exists() {
+                continue;
+            }
+            match clone_dir(&source.root.join(&rel), &dst, mode) {
+                Ok(()) => seeded.push(Seeded {
+                    path: rel,
+                    source: source.name.clone(),
+                    mode,
+                }),
+                Err(e) => eprintln!(
+                    "WARNING: could not seed {} from {}: {e:#}",
+                    rel.display(),
+                    source.name
+                ),
+            }
+        }
+    }
+    Ok(seeded)
+}
+
+/// Move a workspace's build directories into the per-epoch cache before the
+/// workspace is destroyed, keyed by `epoch` (the epoch its work now lives
+/// in). Caches for other epochs are dropped. Best-effort: moves that cannot
+/// be done with a rename (e.g. across filesystems) are skipped.
+///
+/// Returns the number of directories cached.
+pub fn stash(root: &Path, ws_path: &Path, epoch: &EpochId) -> usize {
+    let Ok(config) = MawConfig::load(root) else {
+        return 0;
+    };
+    let config = config.artifacts();
+    if config.paths.is_empty() || !config.cache {
+        return 0;
+    }
+    let Some(cache) = cache_root(root, epoch) else {
+        return 0;
+    };
+    if let Some(parent) = cache.parent()
+        && let Ok(entries) = std::fs::read_dir(parent)
+    {
+        for entry in entries.flatten() {
+            if entry.path() != cache {
+                let _ = std::fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+
+    let mut stashed = 0;
+    for pattern in config.paths.keys() {
+        for rel in matching_dirs(ws_path, pattern) {
+            let dst = cache.join(&rel);
+            if let Some(parent) = dst.parent()
+                && std::fs::create_dir_all(parent).is_err()
+            {
+                continue;
+            }
+            // The newest build wins.
+            let _ = std::fs::remove_dir_all(&dst);
+            if std::fs::rename(ws_path.join(&rel), &dst).is_ok() {
+                stashed += 1;
+            }
+        }
+    }
+    stashed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn matching_dirs_skips_files_git_and_nested_matches() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        fs::create_dir_all(root.join("node_modules/a/node_modules")).expect("mkdir");
+        fs::create_dir_all(root.join("web/node_modules")).expect("mkdir");
+        fs::create_dir_all(root.join(".git/node_modules")).expect("mkdir");
+        fs::create_dir_all(root.join("notes")).expect("mkdir");
+        fs::write(root.join("notes/node_modules"), "file").expect("write");
+
+        let found = matching_dirs(root, "**/node_modules");
+        assert_eq!(
+            found,
+            vec![
+                PathBuf::from("node_modules"),
+                PathBuf::from("web/node_modules")
+            ]
+        );
+        assert!(matching_dirs(root, "target").is_empty());
+    }
+
+    #[test]
+    fn clone_dir_copies_tree_and_reports_failures() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let src = dir.path().join("src/target");
+        fs::create_dir_all(src.join("debug")).expect("mkdir");
+        fs::write(src.join("debug/app"), "bin").expect("write");
+
+        let dst = dir.path().join("ws/target");
+        clone_dir(&src, &dst, LinkMode::Auto).expect("clone");
+        assert_eq!(
+            fs::read_to_string(dst.join("debug/app")).expect("read"),
+            "bin"
+        );
+
+        let missing = dir.path().join("missing");
+        assert!(clone_dir(&missing, &dir.path().join("ws/other"), LinkMode::Copy).is_err());
+        assert!(!dir.path().join("ws/other").exists());
+    }
+
+    #[test]
+    fn artifacts_config_parses_per_glob_modes() {
+        let config: ArtifactsConfig = toml::from_str(
+            r#"
+[paths]
+"target" = "reflink"
+"**/node_modules" = "copy"
+"#,
+        )
+        .expect("parse");
+        assert!(config.cache);
+        assert_eq!(config.paths["target"], LinkMode::Reflink);
+        assert_eq!(config.paths["**/node_modules"], LinkMode::Copy);
+        assert!(toml::from_str::<ArtifactsConfig>("[paths]\ntarget = \"symlink\"\n").is_err());
+        assert!(toml::from_str::<ArtifactsConfig>("[paths]\ntarget = \"hardlink\"\n").is_err());
+    }
+}
@@ -6,7 +6,8 @@ use maw_git::GitRepo as _;
 use serde::Serialize;
 use tracing::instrument;
 
-use maw_core::backend::WorkspaceBackend;
+use maw_core::backend::sparse::SparseSpec;
+use maw_core::backend::{AnyBackend, WorkspaceBackend};
 use maw_core::model::diff::compute_patchset;
 use maw_core::model::types::{EpochId, WorkspaceId, WorkspaceMode};
 use maw_core::oplog::read::read_head;
@@ -32,7 +33,16 @@ pub fn create(
     template: Option<WorkspaceTemplate>,
     description: Option<&str>,
 ) -> Result<()> {
-    create_with_output(name, from, change, persistent, template, description, true)
+    create_with_output(
+        name,
+        from,
+        change,
+        persistent,
+        template,
+        description,
+        &[],
+        true,
+    )
 }
 
 #[instrument(skip(template), fields(workspace = name))]
@@ -44,12 +54,46 @@ pub fn create_quiet(
     template: Option<WorkspaceTemplate>,
     description: Option<&str>,
 ) -> Result<()> {
-    create_with_output(name, from, change, persistent, template, description, false)
+    create_with_output(
+        name,
+        from,
+        change,
+        persistent,
+        template,
+        description,
+        &[],
+        false,
+    )
+}
+
+/// Create a workspace, sparse when `paths` is non-empty (`maw ws create
+/// --paths`): only those paths (plus top-level files) are materialized.
+#[instrument(skip(template), fields(workspace = name))]
+pub fn create_with_paths(
+    name: &str,
+    from: Option<&str>,
+    change: Option<&str>,
+    persistent: bool,
+    template: Option<WorkspaceTemplate>,
+    description: Option<&str>,
+    paths: &[String],
+) -> Result<()> {
+    create_with_output(
+        name,
+        from,
+        change,
+        persistent,
+        template,
+        description,
+        paths,
+        true,
+    )
 }
 
 #[instrument(skip(template), fields(workspace = name, emit_output))]
 #[expect(
     clippy::too_many_lines,
+    clippy::too_many_arguments,
     reason = "workspace creation has ordered validation, backend, and metadata steps"
 )]
 fn create_with_output(
@@ -59,10 +103,35 @@ fn create_with_output(
     persistent: bool,
     template: Option<WorkspaceTemplate>,
     description: Option<&str>,
+    paths: &[String],
     emit_output: bool,
 ) -> Result<()> {
     let root = ensure_repo_root()?;
     let backend = get_backend()?;
+    let sparse = if paths.is_empty() {
+        None
+    } else {
+        let spec = SparseSpec::new(paths).map_err(|e| {
+            anyhow::anyhow!(
+                "Invalid --paths: {e}\n  \
+                 Give directories or globs relative to the repo root, e.g. --paths services/api"
+            )
+        })?;
+        let lazy = match backend {
+            AnyBackend::Overlay(_) => Some("overlay"),
+            AnyBackend::Fuse(_) => Some("fuse"),
+            _ => None,
+        };
+        // Overlay and FUSE workspaces copy nothing up front, so there is
+        // nothing to save; keep them whole rather than pretend.
+        lazy.map_or(Some(spec), |kind| {
+            eprintln!(
+                "NOTE: the {kind} backend already materializes files lazily; \
+                 creating a full workspace and ignoring --paths."
+            );
+            None
+        })
+    };
     // `workspace_path` validates the name; do this before locking so an
     // invalid name fails fast without touching the lock directory.
     let path = workspace_path(name)?;
@@ -96,6 +165,9 @@ fn create_with_output(
         );
     }
 
+    // Refuse to add a workspace while the repo-wide disk budget is exceeded.
+    super::disk::check_create_budget(&root)?;
+
     // Ensure ws directory exists
     let ws_dir = workspaces_dir()?;
     std::fs::create_dir_all(&ws_dir)
@@ -167,7 +239,11 @@ fn create_with_output(
         WorkspaceId::new(name).map_err(|e| anyhow::anyhow!("Invalid workspace name: {e}"))?;
 
     // Create the workspace via backend
-    let info = backend.create(&ws_id, &epoch)
+    let created = sparse.as_ref().map_or_else(
+        || backend.create(&ws_id, &epoch),
+        |spec| backend.create_sparse(&ws_id, &epoch, spec),
+    );
+    let info = created
         .map_err(|e| anyhow::anyhow!(
             "Failed to create workspace: {e}\n  Check: maw doctor\n  Verify name is not already used: maw ws list"
         ))?;
@@ -176,6 +252,13 @@ fn create_with_output(
         tracing::warn!("Failed to record workspace create in history: {e}");
     }
 
+    // Warm the new workspace's build directories from a sibling on the same
+    // epoch or the artifact cache (`[artifacts]` in .maw.toml).
+    let seeded = super::artifacts::seed(&root, &backend, &info).unwrap_or_else(|e| {
+        tracing::warn!("Failed to seed build artifacts: {e}");
+        Vec::new()
+    });
+
     // Write workspace metadata (mode + optional template defaults + description).
     // Keep the common case lean: if mode is ephemeral and no template is set
     // and no description, metadata is omitted and defaults are inferred.
@@ -184,6 +267,7 @@ fn create_with_output(
         || bound_change_id.is_some()
         || attached_branch.is_some()
         || description.is_some()
+        || sparse.is_some()
     {
         let meta = metadata::WorkspaceMetadata {
             mode,
@@ -192,6 +276,8 @@ fn create_with_output(
             change_id: bound_change_id.clone(),
             branch: attached_branch.clone(),
             description: description.map(str::to_owned),
+            exec_sandbox: None,
+            sparse_paths: sparse.as_ref().map(|spec| spec.patterns().to_vec()),
         };
         metadata::write(&root, name, &meta)
             .with_context(|| format!("Failed to write metadata for workspace '{name}'"))?;
@@ -234,6 +320,20 @@ fn create_with_output(
         if let Some(branch) = attached_branch.as_deref() {
             println!("  Branch: {branch}");
         }
+        if let Some(spec) = &sparse {
+            println!(
+                "  Paths:  {} (sparse: other directories are not checked out)",
+                spec.patterns().join(", ")
+            );
+        }
+        for seed in &seeded {
+            println!(
+                "  Seeded: {}/ from {} ({})",
+                seed.path.display(),
+                seed.source,
+                seed.mode
+            );
+        }
         println!("  Epoch:  {short_oid} (base commit for this workspace)");
         println!("  Path:   {}/", info.path.display());
         println!();
@@ -0,0 +1,435 @@
+//! Workspace disk accounting and quotas (`[workspace.quota]`).
+//!
+//! Usage is measured by [`AnyBackend::disk_usage`], which separates the
+//! bytes a workspace owns from those it shares with the epoch snapshot.
+//! Quotas and the repo-wide budget are checked against the owned
+//! (exclusive) bytes only — that is what destroying or cleaning a workspace
+//! gives back. The default workspace is the trunk checkout and is never
+//! charged.
+//!
+//! Measuring walks the workspace tree, so it only happens on request
+//! (`maw ws list --disk`) or when a quota applies. The automatic checks on
+//! `maw exec`, `maw ws create` and `maw status` reuse measurements from
+//! `.manifold/disk-usage` for a few minutes, as long as the workspace is
+//! still on the epoch it was measured at.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Result, bail};
+use maw_core::backend::disk_usage::{self, DiskUsage};
+use maw_core::backend::platform::{self, PlatformCapabilities};
+use maw_core::backend::{AnyBackend, WorkspaceBackend};
+use maw_core::config::{ByteSize, ManifoldConfig, QuotaConfig, QuotaLimits};
+use maw_core::model::layout::LayoutFlavor;
+use maw_core::model::types::WorkspaceInfo;
+use serde::{Deserialize, Serialize};
+
+use super::{DEFAULT_WORKSPACE, get_backend, metadata};
+
+/// How long a cached measurement stands in for walking the workspace again.
+const CACHE_TTL: Duration = Duration::from_mins(5);
+
+/// Load `[workspace.quota]` from the bootstrap config (defaults on error).
+pub fn load_quota(root: &Path) -> QuotaConfig {
+    let layout = LayoutFlavor::detect_with_env(root);
+    ManifoldConfig::load(&layout.bootstrap_config_path(root))
+        .unwrap_or_default()
+        .workspace
+        .quota
+}
+
+/// Effective limits for a workspace, taking its template into account.
+pub fn limits_for(root: &Path, quota: &QuotaConfig, name: &str) -> QuotaLimits {
+    let template = metadata::read(root, name)
+        .ok()
+        .and_then(|meta| meta.template)
+        .map(|t| t.to_string());
+    quota.limits_for(name, template.as_deref())
+}
+
+/// Measure one workspace.
+///
+/// The consolidated layout's default workspace is the repo root itself;
+/// the git directory and the other workspaces under it are not its own.
+pub fn measure(
+    root: &Path,
+    backend: &AnyBackend,
+    caps: &PlatformCapabilities,
+    ws: &WorkspaceInfo,
+) -> DiskUsage {
+    if ws.path == root {
+        let layout = LayoutFlavor::detect_with_env(root);
+        let skip: Vec<PathBuf> = vec![
+            root.join(".git"),
+            layout.workspaces_dir(root),
+            layout.manifold_dir(root),
+        ];
+        return disk_usage::exclusive_usage(root, &skip);
+    }
+    backend.disk_usage(ws, caps)
+}
+
+/// Measurements reused by the automatic quota checks.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct UsageCache {
+    #[serde(default)]
+    workspaces: BTreeMap<String, CachedUsage>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CachedUsage {
+    /// The workspace's epoch when measured; syncing invalidates the entry.
+    epoch: String,
+    /// Unix seconds.
+    measured_at: u64,
+    exclusive_bytes: u64,
+    shared_bytes: u64,
+}
+
+impl UsageCache {
+    fn path(root: &Path) -> PathBuf {
+        LayoutFlavor::detect_with_env(root)
+            .manifold_dir(root)
+            .join("disk-usage")
+    }
+
+    fn load(root: &Path) -> Self {
+        std::fs::read(Self::path(root))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Best-effort: a lost write only means measuring again next time.
+    fn save(&self, root: &Path) {
+        let path = Self::path(root);
+        let Ok(payload) = serde_json::to_vec_pretty(self) else {
+            return;
+        };
+        let tmp = path.with_extension(format!("tmp.{}", std::process::id()));
+        if std::fs::write(&tmp, payload).is_ok() && std::fs::rename(&tmp, &path).is_err() {
+            let _ = std::fs::remove_file(&tmp);
+        }
+    }
+
+    /// Measure `ws`, reusing a fresh enough cached measurement.
+    fn measure(
+        &mut self,
+        root: &Path,
+        backend: &AnyBackend,
+        caps: &PlatformCapabilities,
+        ws: &WorkspaceInfo,
+    ) -> DiskUsage {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let name = ws.id.as_str();
+        if let Some(cached) = self.workspaces.get(name)
+            && cached.epoch == ws.epoch.as_str()
+            && now.saturating_sub(cached.measured_at) < CACHE_TTL.as_secs()
+        {
+            return DiskUsage {
+                exclusive_bytes: cached.exclusive_bytes,
+                shared_bytes: cached.shared_bytes,
+            };
+        }
+        let usage = measure(root, backend, caps, ws);
+        self.workspaces.insert(
+            name.to_owned(),
+            CachedUsage {
+                epoch: ws.epoch.as_str().to_owned(),
+                measured_at: now,
+                exclusive_bytes: usage.exclusive_bytes,
+                shared_bytes: usage.shared_bytes,
+            },
+        );
+        usage
+    }
+}
+
+/// Where a workspace stands against its limits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuotaState {
+    /// Within its limits (or none apply).
+    Ok,
+    /// Above the soft limit.
+    OverSoft,
+    /// Above the hard limit.
+    OverHard,
+}
+
+impl QuotaState {
+    /// Classify `bytes` of exclusive usage against `limits`.
+    pub fn classify(bytes: u64, limits: QuotaLimits) -> Self {
+        if limits.hard.is_some_and(|hard| bytes > hard.0) {
+            Self::OverHard
+        } else if limits.soft.is_some_and(|soft| bytes > soft.0) {
+            Self::OverSoft
+        } else {
+            Self::Ok
+        }
+    }
+}
+
+/// Disk usage of a workspace as shown by `maw ws list --disk` and
+/// `maw status`.
+#[derive(Clone, Debug, Serialize)]
+pub struct DiskInfo {
+    pub(crate) exclusive_bytes: u64,
+    pub(crate) shared_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) soft_limit_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) hard_limit_bytes: Option<u64>,
+    pub(crate) quota: QuotaState,
+}
+
+impl DiskInfo {
+    pub fn new(usage: DiskUsage, limits: QuotaLimits) -> Self {
+        Self {
+            exclusive_bytes: usage.exclusive_bytes,
+            shared_bytes: usage.shared_bytes,
+            soft_limit_bytes: limits.soft.map(|s| s.0),
+            hard_limit_bytes: limits.hard.map(|h| h.0),
+            quota: QuotaState::classify(usage.exclusive_bytes, limits),
+        }
+    }
+
+    /// `1.2 GiB (+3.0 GiB shared) [over soft quota 1.0 GiB]`.
+    pub fn describe(&self) -> String {
+        let mut out = ByteSize(self.exclusive_bytes).to_string();
+        if self.shared_bytes > 0 {
+            let _ = write!(out, " (+{} shared)", ByteSize(self.shared_bytes));
+        }
+        match self.quota {
+            QuotaState::Ok => {}
+            QuotaState::OverSoft => {
+                let soft = ByteSize(self.soft_limit_bytes.unwrap_or_default());
+                let _ = write!(out, " [over soft quota {soft}]");
+            }
+            QuotaState::OverHard => {
+                let hard = ByteSize(self.hard_limit_bytes.unwrap_or_default());
+                let _ = write!(out, " [over hard quota {hard}]");
+            }
+        }
+        out
+    }
+}
+
+/// Summed usage of all non-default workspaces against the budget.
+#[derive(Clone, Debug, Serialize)]
+pub struct RepoDisk {
+    pub(crate) total_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) budget_bytes: Option<u64>,
+    /// Workspaces over a soft or hard quota.
+    pub(crate) over_quota: Vec<String>,
+}
+
+impl RepoDisk {
+    pub fn over_budget(&self) -> bool {
+        self.budget_bytes.is_some_and(|b| self.total_bytes > b)
+    }
+
+    /// `12.3 GiB of 100.0 GiB budget` (or just the total without a budget).
+    pub fn describe(&self) -> String {
+        self.budget_bytes.map_or_else(
+            || ByteSize(self.total_bytes).to_string(),
+            |budget| {
+                format!(
+                    "{} of {} budget",
+                    ByteSize(self.total_bytes),
+                    ByteSize(budget)
+                )
+            },
+        )
+    }
+}
+
+/// Measure every non-default workspace, reusing cached measurements.
+///
+/// # Errors
+///
+/// Returns an error if the workspaces cannot be listed.
+pub fn measure_all(
+    root: &Path,
+    quota: &QuotaConfig,
+) -> Result<(Vec<(String, DiskInfo)>, RepoDisk)> {
+    let backend = get_backend()?;
+    let caps = platform::detect_or_load(root);
+    let workspaces = backend.list().map_err(|e| anyhow::anyhow!("{e}"))?;
+    let mut cache = UsageCache::load(root);
+    let infos: Vec<(String, DiskInfo)> = workspaces
+        .iter()
+        .filter(|ws| ws.id.as_str() != DEFAULT_WORKSPACE)
+        .map(|ws| {
+            let name = ws.id.as_str().to_owned();
+            let limits = limits_for(root, quota, &name);
+            let info = DiskInfo::new(cache.measure(root, &backend, &caps, ws), limits);
+            (name, info)
+        })
+        .collect();
+    cache
+        .workspaces
+        .retain(|name, _| infos.iter().any(|(n, _)| n == name));
+    cache.save(root);
+    let repo = RepoDisk {
+        total_bytes: infos.iter().map(|(_, i)| i.exclusive_bytes).sum(),
+        budget_bytes: quota.budget.map(|b| b.0),
+        over_quota: infos
+            .iter()
+            .filter(|(_, i)| i.quota != QuotaState::Ok)
+            .map(|(n, _)| n.clone())
+            .collect(),
+    };
+    Ok((infos, repo))
+}
+
+/// Warn before `maw exec` runs in a workspace that is over its quota.
+///
+/// Quotas never block a command — only the repo-wide budget blocks
+/// `maw ws create`. Only this workspace is measured, and a recent
+/// measurement is reused.
+pub fn check_exec(root: &Path, name: &str) {
+    if name == DEFAULT_WORKSPACE {
+        return;
+    }
+    let quota = load_quota(root);
+    let limits = limits_for(root, &quota, name);
+    if limits.soft.is_none() && limits.hard.is_none() {
+        return;
+    }
+    let Ok(backend) = get_backend() else {
+        return;
+    };
+    let Some(ws) = backend
+        .list()
+        .ok()
+        .and_then(|list| list.into_iter().find(|ws| ws.id.as_str() == name))
+    else {
+        return;
+    };
+    let caps = platform::detect_or_load(root);
+    let mut cache = UsageCache::load(root);
+    let info = DiskInfo::new(cache.measure(root, &backend, &caps, &ws), limits);
+    cache.save(root);
+    let used = ByteSize(info.exclusive_bytes);
+    let (level, limit) = match info.quota {
+        QuotaState::Ok => return,
+        QuotaState::OverSoft => ("soft", info.soft_limit_bytes),
+        QuotaState::OverHard => ("hard", info.hard_limit_bytes),
+    };
+    eprintln!(
+        "WARNING: workspace '{name}' uses {used}, over its {level} quota of {}.\n  \
+         To free space: maw ws clean-build {name}",
+        ByteSize(limit.unwrap_or_default())
+    );
+}
+
+/// Refuse to create a workspace while the repo-wide budget is exceeded.
+///
+/// # Errors
+///
+/// Returns an error when the summed workspace usage is over the budget.
+pub fn check_create_budget(root: &Path) -> Result<()> {
+    let quota = load_quota(root);
+    if quota.budget.is_none() {
+        return Ok(());
+    }
+    let (infos, repo) = measure_all(root, &quota)?;
+    if !repo.over_budget() {
+        return Ok(());
+    }
+    let mut largest: Vec<&(String, DiskInfo)> = infos.iter().collect();
+    largest.sort_by_key(|(_, info)| std::cmp::Reverse(info.exclusive_bytes));
+    let top: Vec<String> = largest
+        .iter()
+        .take(3)
+        .map(|(name, info)| format!("{name} ({})", ByteSize(info.exclusive_bytes)))
+        .collect();
+    bail!(
+        "Workspaces use {} — over the workspace disk budget.\n  \
+         Largest: {}\n  \
+         To free space: maw ws clean-build <name>, or merge/destroy finished workspaces\n  \
+         Or raise `budget` under [workspace.quota] in the repo config",
+        repo.describe(),
+        top.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(soft: Option<u64>, hard: Option<u64>) -> QuotaLimits {
+        QuotaLimits {
+            soft: soft.map(ByteSize),
+            hard: hard.map(ByteSize),
+        }
+    }
+
+    #[test]
+    fn quota_state_prefers_hard_over_soft() {
+        assert_eq!(QuotaState::classify(50, limits(None, None)), QuotaState::Ok);
+        assert_eq!(
+            QuotaState::classify(50, limits(Some(100), Some(200))),
+            QuotaState::Ok
+        );
+        assert_eq!(
+            QuotaState::classify(150, limits(Some(100), Some(200))),
+            QuotaState::OverSoft
+        );
+        assert_eq!(
+            QuotaState::classify(250, limits(Some(100), Some(200))),
+            QuotaState::OverHard
+        );
+        assert_eq!(
+            QuotaState::classify(250, limits(None, Some(200))),
+            QuotaState::OverHard
+        );
+    }
+
+    #[test]
+    fn disk_info_describes_shared_bytes_and_quota() {
+        let usage = DiskUsage {
+            exclusive_bytes: 3 << 30,
+            shared_bytes: 1 << 30,
+        };
+        let info = DiskInfo::new(usage, limits(Some(2 << 30), None));
+        assert_eq!(
+            info.describe(),
+            "3.0 GiB (+1.0 GiB shared) [over soft quota 2.0 GiB]"
+        );
+
+        let repo = RepoDisk {
+            total_bytes: 3 << 30,
+            budget_bytes: Some(2 << 30),
+            over_quota: vec![],
+        };
+        assert!(repo.over_budget());
+        assert_eq!(repo.describe(), "3.0 GiB of 2.0 GiB budget");
+    }
+
+    #[test]
+    fn usage_cache_round_trips() {
+        let mut cache = UsageCache::default();
+        cache.workspaces.insert(
+            "alice".to_owned(),
+            CachedUsage {
+                epoch: "a".repeat(40),
+                measured_at: 1_700_000_000,
+                exclusive_bytes: 42,
+                shared_bytes: 7,
+            },
+        );
+        let json = serde_json::to_vec(&cache).expect("serialize");
+        let back: UsageCache = serde_json::from_slice(&json).expect("deserialize");
+        let alice = &back.workspaces["alice"];
+        assert_eq!(alice.exclusive_bytes, 42);
+        assert_eq!(alice.shared_bytes, 7);
+    }
+}
@@ -11,6 +11,7 @@ use maw_core::model::types::WorkspaceState;
 
 use maw::merge::quarantine::QUARANTINE_NAME_PREFIX;
 
+use super::disk::{DiskInfo, QuotaState, RepoDisk};
 use super::{DEFAULT_WORKSPACE, get_backend, metadata, repo_root};
 
 #[derive(Serialize)]
@@ -76,6 +77,9 @@ pub struct WorkspaceInfo {
     /// marker + fix hint. Read-only — never re-runs the hook.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) post_sync_hook: Option<super::post_sync_hook::PostSyncHookInfo>,
+    /// Disk usage and quota state (only present when --disk is used).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) disk: Option<DiskInfo>,
 }
 
 /// Compact merge-check result for ws list output.
@@ -107,6 +111,9 @@ const fn is_false(b: &bool) -> bool {
 pub struct WorkspaceListEnvelope {
     pub(crate) workspaces: Vec<WorkspaceInfo>,
     pub(crate) advice: Vec<Advice>,
+    /// Summed usage against the budget (only present when --disk is used).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) disk: Option<RepoDisk>,
 }
 
 /// A single advisory message (warning, info) embedded in structured output.
@@ -181,7 +188,7 @@ fn inject_consolidated_default(
     clippy::too_many_lines,
     reason = "list command combines data collection, optional checks, and rendering"
 )]
-pub fn list(verbose: bool, check: bool, format: OutputFormat) -> Result<()> {
+pub fn list(verbose: bool, check: bool, disk: bool, format: OutputFormat) -> Result<()> {
     let backend = get_backend()?;
     let mut backend_workspaces = backend.list().map_err(|e| anyhow::anyhow!("{e}"))?;
 
@@ -204,6 +211,7 @@ pub fn list(verbose: bool, check: bool, format: OutputFormat) -> Result<()> {
                 let envelope = WorkspaceListEnvelope {
                     workspaces: vec![],
                     advice: vec![],
+                    disk: None,
                 };
                 println!("{}", format.serialize(&envelope)?);
             }
@@ -258,6 +266,21 @@ pub fn list(verbose: bool, check: bool, format: OutputFormat) -> Result<()> {
         HashMap::new()
     };
 
+    // If --disk requested, measure every workspace against its quota.
+    let quota = super::disk::load_quota(&root);
+    let caps = disk.then(|| maw_core::backend::platform::detect_or_load(&root));
+    let measure = |ws: &maw_core::model::types::WorkspaceInfo| {
+        caps.as_ref().filter(|_| ws.path.exists()).map(|caps| {
+            let name = ws.id.as_str();
+            let limits = if name == DEFAULT_WORKSPACE {
+                maw_core::config::QuotaLimits::default()
+            } else {
+                super::disk::limits_for(&root, &quota, name)
+            };
+            DiskInfo::new(super::disk::measure(&root, &backend, caps, ws), limits)
+        })
+    };
+
     // Convert backend workspace info to display structs
     let mut workspaces: Vec<WorkspaceInfo> = backend_workspaces
         .iter()
@@ -377,6 +400,7 @@ pub fn list(verbose: bool, check: bool, format: OutputFormat) -> Result<()> {
                 } else {
                     super::post_sync_hook::latest_info(&root, &name)
                 },
+                disk: measure(ws),
                 name,
             }
         })
@@ -429,6 +453,21 @@ pub fn list(verbose: bool, check: bool, format: OutputFormat) -> Result<()> {
         .map(|ws| ws.name.clone())
         .collect();
 
+    let repo_disk = disk.then(|| RepoDisk {
+        total_bytes: workspaces
+            .iter()
+            .filter(|ws| !ws.is_default)
+            .filter_map(|ws| ws.disk.as_ref())
+            .map(|d| d.exclusive_bytes)
+            .sum(),
+        budget_bytes: quota.budget.map(|b| b.0),
+        over_quota: workspaces
+            .iter()
+            .filter(|ws| ws.disk.as_ref().is_some_and(|d| d.quota != QuotaState::Ok))
+            .map(|ws| ws.name.clone())
+            .collect(),
+    });
+
     match format {
         OutputFormat::Text => print_list_text(
             &workspaces,
@@ -447,14 +486,21 @@ pub fn list(verbose: bool, check: bool, format: OutputFormat) -> Result<()> {
             format,
             verbose,
         ),
-        OutputFormat::Json => print_list_json(
-            workspaces,
-            stale_workspaces,
-            stale_persistent,
-            stale_ephemeral,
-            missing_workspaces,
-            format,
-        ),
+        OutputFormat::Json => {
+            print_list_json(
+                workspaces,
+                stale_workspaces,
+                stale_persistent,
+                stale_ephemeral,
+                missing_workspaces,
+                repo_disk,
+                format,
+            );
+            return Ok(());
+        }
+    }
+    if let Some(repo_disk) = &repo_disk {
+        print_disk_summary_text(repo_disk);
     }
 
     Ok(())
@@ -592,9 +638,14 @@ fn print_list_text(
             .as_deref()
             .map(|branch| format!("\tbranch={branch}"))
             .unwrap_or_default();
+        let disk_suffix = ws
+            .disk
+            .as_ref()
+            .map(|d| format!("\tdisk={}", d.describe()))
+            .unwrap_or_default();
         println!(
-            "{}\t{}{}{}{}",
-            ws.name, path, annotation, branch_suffix, desc_suffix
+            "{}\t{}{}{}{}{}",
+            ws.name, path, annotation, branch_suffix, disk_suffix, desc_suffix
         );
     }
 
@@ -757,8 +808,19 @@ fn print_list_pretty(
                 }
             })
             .unwrap_or_default();
+        let disk_tag = ws
+            .disk
+            .as_ref()
+            .map(|d| {
+                if use_color && d.quota != QuotaState::Ok {
+                    format!(" \x1b[33m[disk: {}]\x1b[0m", d.describe())
+                } else {
+                    format!(" [disk: {}]", d.describe())
+                }
+            })
+            .unwrap_or_default();
         println!(
-            "{} {}{}{} {} {}{}{}{}{}",
+            "{} {}{}{} {} {}{}{}{}{}{}",
             glyph,
             name_style,
             ws.name,
@@ -768,7 +830,8 @@ fn print_list_pretty(
             mode_tag,
             branch_tag,
             check_tag,
-            lifecycle_tag
+            lifecycle_tag,
+            disk_tag
         );
 
         if let Some(desc) = &ws.description {
@@ -915,6 +978,36 @@ fn print_list_pretty(
     }
 }
 
+/// Structured advice for a workspace disk budget or quota overrun.
+fn disk_advice(disk: Option<&RepoDisk>) -> Vec<Advice> {
+    let mut advice = vec![];
+    if let Some(disk) = disk.filter(|d| d.over_budget()) {
+        advice.push(Advice {
+            level: "warn",
+            message: format!(
+                "workspaces use {} — over the disk budget; maw ws create is blocked",
+                disk.describe()
+            ),
+            details: None,
+        });
+    }
+    if let Some(disk) = disk.filter(|d| !d.over_quota.is_empty()) {
+        advice.push(Advice {
+            level: "warn",
+            message: format!(
+                "{} workspace(s) over their disk quota: {}",
+                disk.over_quota.len(),
+                disk.over_quota.join(", ")
+            ),
+            details: Some(AdviceDetails {
+                workspaces: disk.over_quota.clone(),
+                fix: "maw ws clean-build <name>".to_string(),
+            }),
+        });
+    }
+    advice
+}
+
 /// Print workspace list as JSON with stale-workspace advice.
 fn print_list_json(
     workspaces: Vec<WorkspaceInfo>,
@@ -922,6 +1015,7 @@ fn print_list_json(
     stale_persistent: Vec<String>,
     stale_ephemeral: Vec<String>,
     missing: Vec<String>,
+    disk: Option<RepoDisk>,
     format: OutputFormat,
 ) {
     let mut advice = vec![];
@@ -1013,7 +1107,13 @@ fn print_list_json(
         });
     }
 
-    let envelope = WorkspaceListEnvelope { workspaces, advice };
+    advice.extend(disk_advice(disk.as_ref()));
+
+    let envelope = WorkspaceListEnvelope {
+        workspaces,
+        advice,
+        disk,
+    };
 
     match format.serialize(&envelope) {
         Ok(output) => println!("{output}"),
@@ -1023,6 +1123,18 @@ fn print_list_json(
     }
 }
 
+/// Print the summed workspace disk usage and any quota/budget warnings.
+fn print_disk_summary_text(disk: &RepoDisk) {
+    println!();
+    println!("Workspace disk: {}", disk.describe());
+    if disk.over_budget() {
+        println!("  Over budget — maw ws create is blocked until space is freed.");
+    }
+    for name in &disk.over_quota {
+        println!("  Over quota: {name} — fix: maw ws clean-build {name}");
+    }
+}
+
 /// Print stale workspace warnings for text output mode.
 fn print_stale_warning_text(
     stale: &[String],
@@ -1117,6 +1229,7 @@ mod tests {
             lifecycle_state: Some(lifecycle_state),
             fix_command,
             post_sync_hook: None,
+            disk: None,
         }
     }
 
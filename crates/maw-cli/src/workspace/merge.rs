@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::collections::{BTreeMap, BTreeSet};
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
@@ -10,6 +11,8 @@ use sha2::{Digest, Sha256};
 
 use crate::changes::store::ChangesStore;
 use crate::format::OutputFormat;
+use maw::merge::auto_try::{AutoTryReport, ConflictOptions, auto_try, conflict_options};
+use maw::merge::binary::{self, BinaryInfo};
 use maw::merge::build_phase::{BuildPhaseOutput, run_build_phase};
 use maw::merge::collect::collect_snapshots;
 use maw::merge::commit::{
@@ -21,13 +24,16 @@ use maw::merge::last_conflict::{self as merge_last_conflict, LastConflict, LastC
 use maw::merge::prepare::run_prepare_phase;
 use maw::merge::quarantine::create_quarantine_workspace;
 use maw::merge::resolve::{ConflictReason, ConflictRecord};
-use maw::merge::validate::{ValidateOutcome, run_validate_phase, write_validation_artifact};
+use maw::merge::validate::{
+    ValidateOutcome, run_validate_phase, write_merge_artifact, write_validation_artifact,
+};
 use maw_core::backend::WorkspaceBackend;
 use maw_core::config::{ManifoldConfig, MergeDriverKind};
 use maw_core::merge::partition::partition_by_path;
 use maw_core::merge::plan::{
-    DriverInfo, MergePlan, PredictedConflict, ValidationInfo, WorkspaceChange, WorkspaceReport,
-    compute_merge_id, write_plan_artifact, write_workspace_report_artifact,
+    AutoResolutionInfo, DriverInfo, MergePlan, PlanWarning, PredictedConflict, ValidationInfo,
+    WorkspaceChange, WorkspaceReport, compute_merge_id, write_plan_artifact,
+    write_workspace_report_artifact,
 };
 use maw_core::merge::types::{ChangeKind, PatchSet as CollectedPatchSet};
 use maw_core::merge_state::{
@@ -68,6 +74,10 @@ pub struct ConflictSideJson {
     pub content: Option<String>,
     /// `true` if the content could not be decoded as UTF-8 (binary file).
     pub is_binary: bool,
+    /// Size, sniffed content type, image dimensions, and LFS pointer details
+    /// for binary or LFS-tracked content. Absent for plain text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary: Option<BinaryInfo>,
 }
 
 /// Structured conflict information for one file — agent-parseable.
@@ -134,6 +144,10 @@ pub struct ConflictJson {
     /// `true` if the base content is binary (not representable as UTF-8).
     pub base_is_binary: bool,
 
+    /// Binary/LFS metadata for the base content, as for each side's `binary`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_binary: Option<BinaryInfo>,
+
     /// Each workspace's contribution to the conflict.
     pub sides: Vec<ConflictSideJson>,
 
@@ -159,6 +173,13 @@ pub struct ConflictJson {
 
     /// Plain-language description of the recommended resolution approach.
     pub suggested_resolution: String,
+
+    /// Which commits on each side last touched each conflict region, with
+    /// their messages and the workspace's `ws describe` text.
+    ///
+    /// Filled in by `maw ws conflicts --format json` only.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub provenance: Vec<super::provenance::RegionProvenance>,
 }
 
 /// JSON output when `maw ws merge` succeeds.
@@ -338,6 +359,10 @@ pub struct MergeConflictOutput {
     /// Template resolve command with all conflict IDs defaulting to the first workspace.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub resolve_command: Option<String>,
+    /// `[merge] auto_try_resolutions`: validation result of every candidate
+    /// side combination, when auto-try ran but found no unique winner.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_try: Option<AutoTryReport>,
 }
 
 /// JSON output for `maw ws merge --dry-run --format json`.
@@ -395,6 +420,21 @@ struct ConflictWithId {
     atom_ids: Vec<String>,
 }
 
+/// File-level conflict ID for `path`: `cf-{hash(path, 4)}`.
+pub(super) fn conflict_id(path: &str) -> String {
+    format!("cf-{}", terseid::hash(path.as_bytes(), 4))
+}
+
+/// Atom-level IDs for a conflict with `atom_count` atoms: `cf-{hash}.{index}`.
+pub(super) fn atom_ids(file_id: &str, atom_count: usize) -> Vec<String> {
+    (0..atom_count)
+        .map(|i| {
+            let idx = u32::try_from(i).unwrap_or(u32::MAX);
+            terseid::child_id(file_id, idx)
+        })
+        .collect()
+}
+
 /// Assign deterministic terseid-based IDs to each conflict.
 ///
 /// File-level ID: `cf-{hash(path, 4)}`.
@@ -405,15 +445,8 @@ fn assign_conflict_ids(conflicts: &[ConflictRecord]) -> Vec<ConflictWithId> {
     conflicts
         .iter()
         .map(|record| {
-            let path_str = record.path.to_string_lossy();
-            let hash = terseid::hash(path_str.as_bytes(), 4);
-            let file_id = format!("cf-{hash}");
-            let atom_ids: Vec<String> = (0..record.atoms.len())
-                .map(|i| {
-                    let idx = u32::try_from(i).unwrap_or(u32::MAX);
-                    terseid::child_id(&file_id, idx)
-                })
-                .collect();
+            let file_id = conflict_id(&record.path.to_string_lossy());
+            let atom_ids = atom_ids(&file_id, record.atoms.len());
             ConflictWithId {
                 id: file_id,
                 record: record.clone(),
@@ -633,7 +666,7 @@ fn resolve_file_content(
 
 /// Resolve individual atoms within a file, reconstructing the complete content.
 /// Extract byte range from a Region, converting line-based regions to byte offsets.
-fn region_byte_range(region: &Region, content: &[u8]) -> (u32, u32) {
+pub(super) fn region_byte_range(region: &Region, content: &[u8]) -> (u32, u32) {
     let to_u32 = |n: usize| u32::try_from(n).unwrap_or(u32::MAX);
     match region {
         Region::AstNode {
@@ -746,6 +779,23 @@ fn resolve_atoms(
     Ok(result)
 }
 
+/// Tree OID of a candidate merge commit.
+fn candidate_tree(root: &Path, candidate: &GitOid) -> Result<GitOid> {
+    let repo = maw_git::GixRepo::open(root)
+        .map_err(|e| anyhow::anyhow!("failed to open repo at {}: {e}", root.display()))?;
+    let oid: maw_git::GitOid =
+        candidate
+            .as_str()
+            .parse()
+            .map_err(|e: maw_git::OidParseError| {
+                anyhow::anyhow!("invalid candidate OID '{}': {e}", candidate.as_str())
+            })?;
+    let commit = repo
+        .read_commit(oid)
+        .map_err(|e| anyhow::anyhow!("failed to read candidate commit {candidate}: {e}"))?;
+    GitOid::new(&commit.tree_oid.to_string()).map_err(|e| anyhow::anyhow!("{e}"))
+}
+
 /// Patch the candidate tree with resolved file contents, producing a new commit OID.
 ///
 /// Pure-gix pipeline (no `read-tree`/`update-index`/`write-tree`):
@@ -885,7 +935,7 @@ fn workspace_display_name(ws_id: &WorkspaceId) -> String {
 /// use with `--resolve`.
 #[cfg(test)]
 fn conflict_record_to_json(record: &ConflictRecord) -> ConflictJson {
-    conflict_record_to_json_with_id(record, None, &[])
+    conflict_record_to_json_with_id(record, None, &[], None)
 }
 
 /// Map a `ConflictReason` to its JSON output fields:
@@ -962,14 +1012,59 @@ fn conflict_reason_json_fields(
                 dir_child_example.display()
             ),
         ),
+        ConflictReason::MigrationOrder {
+            sequence: Some(sequence),
+            taken_by,
+        } => (
+            "migration_order",
+            "migration_sequence",
+            vec![
+                "renumber_migration".to_string(),
+                "drop_migration".to_string(),
+            ],
+            format!(
+                "Migration number {sequence} is already used by {}. Renumber this \
+                 migration after the newest one (and update anything that references \
+                 it), or set `renumber = true` on the `[[merge.migrations]]` entry.",
+                taken_by
+                    .iter()
+                    .map(|path| format!("'{}'", path.display()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        ),
+        ConflictReason::MigrationOrder { sequence: None, .. } => (
+            "migration_order",
+            "migration_latest",
+            vec!["keep_one_side".to_string(), "manual_resolution".to_string()],
+            "Workspaces point the latest-migration file at different migrations. \
+             Keep the side naming the migration that ends the merged chain."
+                .to_string(),
+        ),
     }
 }
 
+/// Open the repository's local LFS object store, for describing LFS pointer
+/// sides of conflicts. `None` if the repository has no LFS store (one is not
+/// created just to report a conflict).
+pub(super) fn open_lfs_store(root: &Path) -> Option<maw_lfs::Store> {
+    let repo = maw_git::GixRepo::open(root).ok()?;
+    let git_dir = repo.common_dir();
+    if !git_dir.join("lfs").is_dir() {
+        return None;
+    }
+    maw_lfs::Store::open(git_dir).ok()
+}
+
 /// Convert with optional conflict ID and atom IDs.
+///
+/// `lfs_store` is consulted for LFS pointer sides so their objects can be
+/// sniffed when present locally; nothing is fetched.
 fn conflict_record_to_json_with_id(
     record: &ConflictRecord,
     id: Option<&str>,
     atom_ids: &[String],
+    lfs_store: Option<&maw_lfs::Store>,
 ) -> ConflictJson {
     let (conflict_type, reason_key, resolution_strategies, suggested_resolution) =
         conflict_reason_json_fields(&record.reason);
@@ -995,6 +1090,10 @@ fn conflict_record_to_json_with_id(
                 change: s.kind.to_string(),
                 content,
                 is_binary,
+                binary: s
+                    .content
+                    .as_ref()
+                    .and_then(|bytes| binary::describe(bytes, lfs_store)),
             }
         })
         .collect();
@@ -1013,11 +1112,16 @@ fn conflict_record_to_json_with_id(
         workspaces,
         base_content,
         base_is_binary,
+        base_binary: record
+            .base
+            .as_ref()
+            .and_then(|bytes| binary::describe(bytes, lfs_store)),
         sides,
         atoms: record.atoms.clone(),
         atom_ids: atom_ids.to_vec(),
         resolution_strategies,
         suggested_resolution,
+        provenance: Vec::new(),
     }
 }
 
@@ -1067,8 +1171,74 @@ fn conflict_record_to_info(record: &ConflictRecord) -> ConflictInfo {
 }
 
 /// Print detailed conflict information with terseid IDs and resolve commands.
-fn print_conflict_report(conflicts_with_ids: &[ConflictWithId], ws_names: &[String], into: &str) {
-    print_conflict_report_with_resolve(conflicts_with_ids, ws_names, into, None);
+fn print_conflict_report(
+    conflicts_with_ids: &[ConflictWithId],
+    ws_names: &[String],
+    into: &str,
+    lfs_store: Option<&maw_lfs::Store>,
+) {
+    print_conflict_report_with_resolve(conflicts_with_ids, ws_names, into, None, lfs_store);
+}
+
+/// `[merge] auto_try_resolutions`: validate every side combination of
+/// `conflicts` against the merge candidate.
+///
+/// Returns `None` when the mode is off or there is nothing to try (no
+/// validation commands, or more combinations than
+/// `auto_try_max_candidates`).
+fn auto_try_conflicts(
+    root: &Path,
+    manifold_dir: &Path,
+    candidate: &GitOid,
+    conflicts: &[ConflictWithId],
+) -> Option<AutoTryReport> {
+    let config = ManifoldConfig::load(&manifold_dir.join("config.toml")).ok()?;
+    if !config.merge.auto_try_resolutions {
+        return None;
+    }
+    let options: Vec<ConflictOptions> = conflicts
+        .iter()
+        .map(|c| conflict_options(&c.id, &c.record))
+        .collect();
+    auto_try(
+        root,
+        &options,
+        &config.merge.validation,
+        config.merge.auto_try_max_candidates,
+        |resolved| patch_candidate_tree(root, candidate, resolved).map_err(|e| format!("{e:#}")),
+    )
+}
+
+/// Render auto-try choices as `cf-k7mx=alice, cf-p2qd=alice+bob` (one name
+/// per diff3 hunk).
+fn format_auto_try_choices(choices: &BTreeMap<String, Vec<String>>) -> String {
+    choices
+        .iter()
+        .map(|(id, sides)| format!("{id}={}", sides.join("+")))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Print the per-candidate results of an auto-try run with no unique winner.
+fn print_auto_try_matrix(report: &AutoTryReport) {
+    println!(
+        "  AUTO-TRY: {} of {} candidate(s) passed validation — not choosing automatically:",
+        report.passed_count(),
+        report.candidates.len()
+    );
+    for candidate in &report.candidates {
+        let status = if candidate.passed { "PASS" } else { "FAIL" };
+        let detail = candidate
+            .error
+            .as_deref()
+            .or(candidate.failed_command.as_deref())
+            .map(|d| format!("  ({d})"))
+            .unwrap_or_default();
+        println!(
+            "    {status}  {}{detail}",
+            format_auto_try_choices(&candidate.choices)
+        );
+    }
 }
 
 /// bn-yyx: persist the conflict surface + emit a `ConflictDetected` event.
@@ -1207,6 +1377,7 @@ fn print_conflict_report_with_resolve(
     ws_names: &[String],
     into: &str,
     prebuilt_resolve_args: Option<&[String]>,
+    lfs_store: Option<&maw_lfs::Store>,
 ) {
     println!();
     println!("BUILD: {} conflict(s) detected.", conflicts_with_ids.len());
@@ -1236,9 +1407,17 @@ fn print_conflict_report_with_resolve(
         println!("  {:<10} {:<40} {}", c.id, c.record.path.display(), reason);
         println!("           Workspaces: {}", ws_list.join(", "));
 
-        // Show content snippets from each side (up to 5 lines each)
+        // Show content snippets from each side (up to 5 lines each);
+        // binary and LFS sides get a one-line description instead.
         for side in &c.record.sides {
-            if let Some(ref content) = side.content {
+            if let Some(info) = side
+                .content
+                .as_deref()
+                .and_then(|content| binary::describe(content, lfs_store))
+            {
+                let label = workspace_display_name(&side.workspace_id);
+                println!("           [{label}]: {}", binary::summary(&info));
+            } else if let Some(ref content) = side.content {
                 let text = String::from_utf8_lossy(content);
                 let lines: Vec<&str> = text.lines().collect();
                 let preview_lines = 5;
@@ -1570,7 +1749,7 @@ pub fn check_merge(
                 if c.path.is_empty() {
                     "cf-check".to_string()
                 } else {
-                    format!("cf-{}", terseid::hash(c.path.as_bytes(), 4))
+                    conflict_id(&c.path)
                 }
             })
             .collect();
@@ -2147,6 +2326,8 @@ pub fn plan_merge(
     let merge_id = compute_merge_id(&merge_base_epoch, &sources, &frozen.heads);
     let driver_infos = build_driver_infos(&touched_paths, &manifold_config);
     let predicted_conflicts = build_predicted_conflicts(&build_output);
+    let warnings = build_plan_warnings(&build_output);
+    let auto_resolutions = build_auto_resolutions(&build_output);
     let validation_info = build_validation_info(&manifold_config);
 
     // VALIDATE (optional): run and write artifact, but don't block
@@ -2172,6 +2353,8 @@ pub fn plan_merge(
         touched_paths,
         overlaps,
         predicted_conflicts,
+        warnings,
+        auto_resolutions,
         drivers: driver_infos,
         validation: validation_info,
     };
@@ -2261,9 +2444,12 @@ fn build_driver_infos(touched_paths: &[PathBuf], config: &ManifoldConfig) -> Vec
                 .ok()
                 .is_some_and(|p| p.matches_path(path));
             if matches {
-                let command = matches!(driver.kind, MergeDriverKind::Regenerate)
-                    .then(|| driver.command.clone())
-                    .flatten();
+                let command = matches!(
+                    driver.kind,
+                    MergeDriverKind::Regenerate | MergeDriverKind::External
+                )
+                .then(|| driver.command.clone())
+                .flatten();
                 infos.push(DriverInfo {
                     path: path.clone(),
                     kind: driver.kind.to_string(),
@@ -2281,19 +2467,63 @@ fn build_predicted_conflicts(build_output: &BuildPhaseOutput) -> Vec<PredictedCo
     build_output
         .conflicts
         .iter()
-        .map(|conflict| {
-            let mut sides: Vec<String> = conflict
-                .sides
+        .map(predicted_conflict)
+        .collect()
+}
+
+/// Summarize one conflict record as a `PredictedConflict`.
+pub(super) fn predicted_conflict(conflict: &ConflictRecord) -> PredictedConflict {
+    let mut sides: Vec<String> = conflict
+        .sides
+        .iter()
+        .map(|s| workspace_display_name(&s.workspace_id))
+        .collect();
+    sides.sort();
+    sides.dedup();
+    PredictedConflict {
+        path: conflict.path.clone(),
+        kind: conflict.reason.to_string(),
+        sides,
+        regions: conflict
+            .atoms
+            .iter()
+            .map(|atom| atom.base_region.summary())
+            .collect(),
+    }
+}
+
+/// Build `PlanWarning` entries from the BUILD output.
+fn build_plan_warnings(build_output: &BuildPhaseOutput) -> Vec<PlanWarning> {
+    build_output
+        .warnings
+        .iter()
+        .map(|warning| PlanWarning {
+            path: warning.path.clone(),
+            kind: warning.reason.variant_name().to_owned(),
+            sides: warning
+                .workspaces
                 .iter()
-                .map(|s| workspace_display_name(&s.workspace_id))
-                .collect();
-            sides.sort();
-            sides.dedup();
-            PredictedConflict {
-                path: conflict.path.clone(),
-                kind: conflict.reason.to_string(),
-                sides,
-            }
+                .map(workspace_display_name)
+                .collect(),
+            message: warning.reason.description().to_owned(),
+        })
+        .collect()
+}
+
+/// Build `AutoResolutionInfo` entries from the BUILD output.
+fn build_auto_resolutions(build_output: &BuildPhaseOutput) -> Vec<AutoResolutionInfo> {
+    build_output
+        .auto_resolutions
+        .iter()
+        .map(|resolution| AutoResolutionInfo {
+            path: resolution.path.clone(),
+            kind: resolution.kind.to_string(),
+            sides: resolution
+                .workspaces
+                .iter()
+                .map(workspace_display_name)
+                .collect(),
+            details: resolution.details.clone(),
         })
         .collect()
 }
@@ -2420,12 +2650,45 @@ fn print_plan_text(plan: &MergePlan) {
                 conflict.kind,
                 sides
             );
+            for region in &conflict.regions {
+                println!("      {region}");
+            }
         }
     } else if !plan.overlaps.is_empty() {
         println!();
         println!("  (all overlapping paths resolved cleanly via diff3 or drivers)");
     }
 
+    if !plan.warnings.is_empty() {
+        println!();
+        println!("Warnings ({}):", plan.warnings.len());
+        for warning in &plan.warnings {
+            println!(
+                "  W {} — {} (sides: {})",
+                warning.path.display(),
+                warning.kind,
+                warning.sides.join(", ")
+            );
+            println!("      {}", warning.message);
+        }
+    }
+
+    if !plan.auto_resolutions.is_empty() {
+        println!();
+        println!("Auto-resolved ({}):", plan.auto_resolutions.len());
+        for resolution in &plan.auto_resolutions {
+            println!(
+                "  A {} — {} (sides: {})",
+                resolution.path.display(),
+                resolution.kind,
+                resolution.sides.join(", ")
+            );
+            for detail in &resolution.details {
+                println!("      {detail}");
+            }
+        }
+    }
+
     if !plan.drivers.is_empty() {
         println!();
         println!("Merge drivers:");
@@ -2728,9 +2991,23 @@ pub fn show_conflicts(workspaces: &[String], format: OutputFormat) -> Result<()>
     let conflicts_with_ids = assign_conflict_ids(&build_output.conflicts);
 
     if format == OutputFormat::Json {
+        let lfs_store = open_lfs_store(&root);
         let conflict_jsons: Vec<ConflictJson> = conflicts_with_ids
             .iter()
-            .map(|c| conflict_record_to_json_with_id(&c.record, Some(&c.id), &c.atom_ids))
+            .map(|c| ConflictJson {
+                provenance: super::provenance::conflict_provenance(
+                    &root,
+                    &backend,
+                    &c.record,
+                    &c.atom_ids,
+                ),
+                ..conflict_record_to_json_with_id(
+                    &c.record,
+                    Some(&c.id),
+                    &c.atom_ids,
+                    lfs_store.as_ref(),
+                )
+            })
             .collect();
         let ws_args = workspaces.join(" ");
         let resolve_default_ws = workspaces.first().map_or("WORKSPACE", |s| s.as_str());
@@ -2760,7 +3037,12 @@ pub fn show_conflicts(workspaces: &[String], format: OutputFormat) -> Result<()>
         println!("{}", serde_json::to_string_pretty(&out)?);
     } else {
         // Reuse the same format as merge conflict output
-        print_conflict_report(&conflicts_with_ids, workspaces, default_ws);
+        print_conflict_report(
+            &conflicts_with_ids,
+            workspaces,
+            default_ws,
+            open_lfs_store(&root).as_ref(),
+        );
     }
 
     Ok(())
@@ -2997,6 +3279,29 @@ pub struct MergeOptions<'a> {
     /// `Some(false)` forces it off (matches `--no-auto-rebase`).
     /// `None` defers to `MawConfig::merge.auto_rebase_siblings`.
     pub auto_rebase_siblings: Option<bool>,
+    /// Tree of a candidate the merge queue already validated speculatively.
+    ///
+    /// When BUILD produces a candidate with exactly this tree, VALIDATE is
+    /// skipped; any other tree is validated as usual.
+    pub prevalidated_tree: Option<&'a GitOid>,
+    /// Collect the JSON documents the merge would print instead of printing
+    /// them.
+    ///
+    /// Callers that run several merges (the merge queue, scheduled batches)
+    /// embed each merge's result in their own single JSON document.
+    pub json_sink: Option<&'a RefCell<Vec<serde_json::Value>>>,
+}
+
+/// Print a JSON document, or hand it to `sink` when the caller collects them.
+fn emit_json<T: Serialize>(
+    sink: Option<&RefCell<Vec<serde_json::Value>>>,
+    value: &T,
+) -> Result<()> {
+    match sink {
+        Some(sink) => sink.borrow_mut().push(serde_json::to_value(value)?),
+        None => println!("{}", serde_json::to_string_pretty(value)?),
+    }
+    Ok(())
 }
 
 // ---------------------------------------------------------------------------
@@ -4176,9 +4481,38 @@ fn classify_post_prepare_divergence(
 /// Run the merge state machine: PREPARE → BUILD → VALIDATE → COMMIT → CLEANUP.
 ///
 /// This uses the Manifold merge engine and state machine.
-#[allow(clippy::too_many_lines)]
 #[instrument(skip(opts), fields(workspaces = ?workspaces))]
 pub fn merge(workspaces: &[String], opts: &MergeOptions<'_>) -> Result<()> {
+    if workspaces.is_empty() {
+        if opts.format != OutputFormat::Json {
+            println!("No workspaces to merge.");
+        }
+        return Ok(());
+    }
+
+    let root = repo_root()?;
+    // bn-13rc: hold the repo-level epoch lock for the WHOLE merge — the
+    // FF-absorb reconcile below, PREPARE→BUILD→COMMIT, sibling auto-rebase, and
+    // cleanup all read-modify-write shared epoch state. Acquired here (before
+    // reconcile_epoch_with_branch, which mutates the epoch even under
+    // --dry-run's FF-absorb path) and held via RAII until the function returns.
+    // Epoch lock FIRST; the sibling auto-rebase takes per-workspace locks under
+    // it (see epoch_lock ordering rule). `--check`/`--plan` never reach here.
+    let _epoch_lock = crate::epoch_lock::EpochLock::acquire(&root, "ws merge")?;
+    merge_with_epoch_lock_held(&root, workspaces, opts)
+}
+
+/// The body of [`merge`] for callers that already hold the epoch lock.
+///
+/// The merge queue holds the lock across speculation and all of its
+/// commits, so it cannot go through [`merge`] (a second `flock` from the
+/// same process would contend with the first).
+#[allow(clippy::too_many_lines)]
+pub(super) fn merge_with_epoch_lock_held(
+    root: &Path,
+    workspaces: &[String],
+    opts: &MergeOptions<'_>,
+) -> Result<()> {
     let MergeOptions {
         destroy_after,
         confirm,
@@ -4194,6 +4528,8 @@ pub fn merge(workspaces: &[String], opts: &MergeOptions<'_>) -> Result<()> {
         verbose,
         force: _force,
         auto_rebase_siblings: auto_rebase_override,
+        prevalidated_tree,
+        json_sink,
     } = *opts;
     // `_force` is not used here — the check site reads `opts.force` directly
     // to avoid a shadowing conflict with the local `force` elsewhere.
@@ -4208,20 +4544,7 @@ pub fn merge(workspaces: &[String], opts: &MergeOptions<'_>) -> Result<()> {
         };
     }
 
-    if ws_to_merge.is_empty() {
-        textln!("No workspaces to merge.");
-        return Ok(());
-    }
-
-    let root = repo_root()?;
-    // bn-13rc: hold the repo-level epoch lock for the WHOLE merge — the
-    // FF-absorb reconcile below, PREPARE→BUILD→COMMIT, sibling auto-rebase, and
-    // cleanup all read-modify-write shared epoch state. Acquired here (before
-    // reconcile_epoch_with_branch, which mutates the epoch even under
-    // --dry-run's FF-absorb path) and held via RAII until the function returns.
-    // Epoch lock FIRST; the sibling auto-rebase takes per-workspace locks under
-    // it (see epoch_lock ordering rule). `--check`/`--plan` never reach here.
-    let _epoch_lock = crate::epoch_lock::EpochLock::acquire(&root, "ws merge")?;
+    let root = root.to_path_buf();
     // bn-2rnq: snapshot every sibling's committed HEAD BEFORE any epoch mutation
     // (FF-absorb reconcile, PREPARE→COMMIT, sibling auto-rebase). We are inside
     // the epoch lock, so no sibling can move under us between here and the audit.
@@ -4508,7 +4831,7 @@ pub fn merge(workspaces: &[String], opts: &MergeOptions<'_>) -> Result<()> {
                     "cwd_destroyed": destroy_outcome.cwd_destroyed_ws.is_some(),
                     "message": format!("No changes detected in workspace(s): {ws_list}. Workspace(s) destroyed."),
                 });
-                println!("{}", serde_json::to_string_pretty(&output)?);
+                emit_json(json_sink, &output)?;
             } else if let Some(ref ws) = destroy_outcome.cwd_destroyed_ws {
                 // bn-20fp (item 4): tail-visible destroy-cwd warning as the
                 // final line of the empty-merge-destroy path.
@@ -4526,7 +4849,7 @@ pub fn merge(workspaces: &[String], opts: &MergeOptions<'_>) -> Result<()> {
                 "workspaces": ws_to_merge,
                 "message": format!("No changes detected in workspace(s): {ws_list}"),
             });
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            emit_json(json_sink, &output)?;
         } else {
             textln!();
             textln!("No changes detected in workspace(s): {ws_list}");
@@ -4537,11 +4860,56 @@ pub fn merge(workspaces: &[String], opts: &MergeOptions<'_>) -> Result<()> {
     }
 
     // Check for unresolved conflicts
+    let mut auto_tried = false;
     if !build_output.conflicts.is_empty() {
         let conflicts_with_ids = assign_conflict_ids(&build_output.conflicts);
 
         let has_resolutions = !resolve.is_empty() || resolve_all.is_some();
-        if has_resolutions {
+        let auto_try_report = if has_resolutions {
+            None
+        } else {
+            auto_try_conflicts(
+                &root,
+                &manifold_dir,
+                &build_output.candidate,
+                &conflicts_with_ids,
+            )
+        };
+        if let Some((report, winner)) = auto_try_report
+            .as_ref()
+            .and_then(|report| Some((report, report.winner()?)))
+        {
+            // Exactly one combination passed validation — commit it.
+            textln!(
+                "  AUTO-TRY: 1 of {} candidate(s) passed validation; using {}.",
+                report.candidates.len(),
+                format_auto_try_choices(&winner.choices)
+            );
+            let patched =
+                match patch_candidate_tree(&root, &build_output.candidate, &winner.resolved) {
+                    Ok(oid) => oid,
+                    Err(e) => {
+                        abort_merge(&manifold_dir, &format!("patch tree failed: {e:#}"));
+                        bail!("Failed to patch candidate tree with auto-tried resolution: {e:#}");
+                    }
+                };
+            let merge_id = &patched.as_str()[..12];
+            if let Err(e) = write_merge_artifact(&manifold_dir, merge_id, "auto-try.json", report) {
+                tracing::warn!(error = %e, "auto-try: could not write evidence artifact");
+            }
+            textln!("  Evidence: .manifold/artifacts/merge/{merge_id}/auto-try.json");
+            build_output = BuildPhaseOutput {
+                candidate: patched,
+                unique_count: build_output.unique_count,
+                shared_count: build_output.shared_count,
+                resolved_count: build_output.resolved_count + conflicts_with_ids.len(),
+                conflicts: vec![],
+                resolved_paths: build_output.resolved_paths.clone(),
+                auto_resolutions: build_output.auto_resolutions.clone(),
+                warnings: build_output.warnings.clone(),
+            };
+            auto_tried = true;
+        } else if has_resolutions {
             // --resolve / --resolve-all mode: apply stateless resolutions
             let mut parsed = match parse_resolutions(resolve) {
                 Ok(p) => p,
@@ -4597,6 +4965,8 @@ pub fn merge(workspaces: &[String], opts: &MergeOptions<'_>) -> Result<()> {
                     resolved_count: build_output.resolved_count + conflicts_with_ids.len(),
                     conflicts: vec![],
                     resolved_paths: build_output.resolved_paths.clone(),
+                    auto_resolutions: build_output.auto_resolutions.clone(),
+                    warnings: build_output.warnings.clone(),
                 };
             } else {
                 // Some conflicts remain unresolved — report them with IDs
@@ -4638,10 +5008,16 @@ pub fn merge(workspaces: &[String], opts: &MergeOptions<'_>) -> Result<()> {
                 }
 
                 if format == OutputFormat::Json {
+                    let lfs_store = open_lfs_store(&root);
                     let conflict_jsons: Vec<ConflictJson> = remaining
                         .iter()
                         .map(|c| {
-                            conflict_record_to_json_with_id(&c.record, Some(&c.id), &c.atom_ids)
+                            conflict_record_to_json_with_id(
+                                &c.record,
+                                Some(&c.id),
+                                &c.atom_ids,
+                                lfs_store.as_ref(),
+                            )
                         })
                         .collect();
                     let retry_message = conflict_retry_message(into_target);
@@ -4661,8 +5037,9 @@ pub fn merge(workspaces: &[String], opts: &MergeOptions<'_>) -> Result<()> {
                         ),
                         to_fix: to_fix.clone(),
                         resolve_command: Some(to_fix),
+                        auto_try: None,
                     };
-                    println!("{}", serde_json::to_string_pretty(&output)?);
+                    emit_json(json_sink, &output)?;
                 } else {
                     textln!(
                         "  {} of {} conflict(s) resolved, {} remaining:",
@@ -4675,6 +5052,7 @@ pub fn merge(workspaces: &[String], opts: &MergeOptions<'_>) -> Result<()> {
                         &ws_to_merge,
                         into_target,
                         Some(&resolve_args),
+                        open_lfs_store(&root).as_ref(),
                     );
                 }
 
@@ -4711,9 +5089,17 @@ pub fn merge(workspaces: &[String], opts: &MergeOptions<'_>) -> Result<()> {
                 .collect();
 
             if format == OutputFormat::Json {
+                let lfs_store = open_lfs_store(&root);
                 let conflict_jsons: Vec<ConflictJson> = conflicts_with_ids
                     .iter()
-                    .map(|c| conflict_record_to_json_with_id(&c.record, Some(&c.id), &c.atom_ids))
+                    .map(|c| {
+                        conflict_record_to_json_with_id(
+                            &c.record,
+                            Some(&c.id),
+                            &c.atom_ids,
+                            lfs_store.as_ref(),
+                        )
+                    })
                     .collect();
                 let retry_message = conflict_retry_message(into_target);
                 let to_fix = format!(
@@ -4732,11 +5118,20 @@ pub fn merge(workspaces: &[String], opts: &MergeOptions<'_>) -> Result<()> {
                     ),
                     to_fix: to_fix.clone(),
                     resolve_command: Some(to_fix),
+                    auto_try: auto_try_report,
                 };
-                println!("{}", serde_json::to_string_pretty(&output)?);
+                emit_json(json_sink, &output)?;
             } else {
                 textln!("  {} unresolved conflict(s)", build_output.conflicts.len());
-                print_conflict_report(&conflicts_with_ids, &ws_to_merge, into_target);
+                if let Some(report) = &auto_try_report {
+                    print_auto_try_matrix(report);
+                }
+                print_conflict_report(
+                    &conflicts_with_ids,
+                    &ws_to_merge,
+                    into_target,
+                    open_lfs_store(&root).as_ref(),
+                );
 
                 if destroy_after {
                     textln!();
@@ -4759,7 +5154,16 @@ pub fn merge(workspaces: &[String], opts: &MergeOptions<'_>) -> Result<()> {
     let validation_config = &manifold_config.merge.validation;
 
     textln!();
-    if validation_config.has_commands() {
+    let prevalidated = prevalidated_tree.is_some_and(|tree| {
+        candidate_tree(&root, &build_output.candidate).is_ok_and(|candidate| candidate == *tree)
+    });
+    if validation_config.has_commands() && prevalidated {
+        textln!("VALIDATE: Candidate already validated speculatively by the merge queue.");
+        advance_merge_state(&manifold_dir, MergePhase::Validate)?;
+    } else if validation_config.has_commands() && auto_tried {
+        textln!("VALIDATE: Candidate already validated by auto-try.");
+        advance_merge_state(&manifold_dir, MergePhase::Validate)?;
+    } else if validation_config.has_commands() {
         textln!("VALIDATE: Running post-merge validation...");
 
         // Advance merge-state to Validate phase
@@ -5377,7 +5781,7 @@ pub fn merge(workspaces: &[String], opts: &MergeOptions<'_>) -> Result<()> {
                 pinned_refs: destroy_outcome.pinned_refs.clone(),
             },
         };
-        println!("{}", serde_json::to_string_pretty(&success)?);
+        emit_json(json_sink, &success)?;
     } else {
         textln!();
         textln!("Merged to {branch}: {msg} from {}", ws_to_merge.join(", "));
@@ -6972,6 +7376,17 @@ fn handle_post_merge_destroy(
         // root-guard above.
         let cwd_was_inside = super::cwd_is_inside(&canonical_ws);
 
+        // Keep the workspace's build directories warm for the next
+        // `maw ws create` on the epoch its work now lives in.
+        if let Ok(Some(epoch)) = maw_core::refs::read_epoch_current(root)
+            .map(|oid| oid.and_then(|oid| EpochId::new(oid.as_str()).ok()))
+        {
+            let stashed = super::artifacts::stash(root, &ws_path, &epoch);
+            if verbose && text_mode && stashed > 0 {
+                println!("    Cached {stashed} build dir(s) from '{ws_name}'");
+            }
+        }
+
         // --- Step 4: Destroy the workspace ---
         match backend.destroy(&ws_id) {
             Ok(()) => {
@@ -7333,6 +7748,38 @@ mod tests {
         assert!(json.base_content.is_none());
     }
 
+    #[test]
+    fn binary_and_lfs_sides_carry_metadata() {
+        let mut record = content_record("art/logo.png", "base", "alice", "bob");
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&[0, 0, 0, 64, 0, 0, 0, 32]);
+        record.sides[0].content = Some(png);
+        let pointer = maw_lfs::Pointer {
+            oid: [7; 32],
+            size: 2048,
+            extensions: vec![],
+        };
+        record.sides[1].content = Some(pointer.write());
+
+        let json = conflict_record_to_json(&record);
+
+        let alice = json.sides[0].binary.as_ref().expect("alice is binary");
+        assert_eq!(alice.content_type, "image/png");
+        let image = alice.image.expect("dimensions sniffed");
+        assert_eq!((image.width, image.height), (64, 32));
+
+        // bob's blob is an LFS pointer: text, but described by its object.
+        assert!(!json.sides[1].is_binary);
+        let bob = json.sides[1].binary.as_ref().expect("bob is LFS");
+        assert_eq!(bob.size, 2048);
+        let lfs = bob.lfs.as_ref().expect("pointer details");
+        assert_eq!(lfs.oid, pointer.oid_hex());
+        assert!(!lfs.present);
+
+        // Plain text base carries no binary metadata.
+        assert!(json.base_binary.is_none());
+    }
+
     // -----------------------------------------------------------------------
     // JSON roundtrip: agent can parse the output
     // -----------------------------------------------------------------------
@@ -7410,6 +7857,7 @@ mod tests {
             message: "Merge has 2 unresolved conflict(s). Resolve them and retry.".to_string(),
             to_fix: "maw ws merge alice bob --into default".to_string(),
             resolve_command: None,
+            auto_try: None,
         };
 
         let json_str = serde_json::to_string_pretty(&output).expect("operation should succeed");
@@ -7946,8 +8394,12 @@ mod tests {
     #[test]
     fn conflict_json_includes_id_when_provided() {
         let record = content_record("src/lib.rs", "base", "alice", "bob");
-        let json =
-            conflict_record_to_json_with_id(&record, Some("cf-test"), &["cf-test.0".to_string()]);
+        let json = conflict_record_to_json_with_id(
+            &record,
+            Some("cf-test"),
+            &["cf-test.0".to_string()],
+            None,
+        );
 
         assert_eq!(json.id.as_deref(), Some("cf-test"));
         assert_eq!(json.atom_ids, vec!["cf-test.0"]);
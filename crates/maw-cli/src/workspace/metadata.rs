@@ -43,6 +43,14 @@ pub struct WorkspaceMetadata {
     /// Human-readable description of the workspace's purpose.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Run `maw exec` commands in this workspace sandboxed (`true`) or not
+    /// (`false`), overriding `[workspace.exec_sandbox] enabled`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec_sandbox: Option<bool>,
+    /// Paths (or globs) checked out in a sparse workspace, as given to
+    /// `maw ws create --paths`. `None` for a full workspace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse_paths: Option<Vec<String>>,
 }
 
 // ---------------------------------------------------------------------------
@@ -165,6 +173,24 @@ mod tests {
         assert_eq!(decoded.branch.as_deref(), Some("feature/long-lived"));
     }
 
+    #[test]
+    fn roundtrip_sparse_paths() {
+        let meta = WorkspaceMetadata {
+            sparse_paths: Some(vec!["services/api".to_string(), "libs/*".to_string()]),
+            ..WorkspaceMetadata::default()
+        };
+        let decoded = write_and_read(&meta);
+        assert_eq!(
+            decoded.sparse_paths.as_deref(),
+            Some(&["services/api".to_string(), "libs/*".to_string()][..])
+        );
+        assert!(
+            write_and_read(&WorkspaceMetadata::default())
+                .sparse_paths
+                .is_none()
+        );
+    }
+
     #[test]
     fn creates_directory() {
         let dir = tempdir().expect("operation should succeed");
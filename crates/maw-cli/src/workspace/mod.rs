@@ -8,6 +8,7 @@ use serde::Deserialize;
 
 use crate::changes::store::ChangesStore;
 use crate::format::OutputFormat;
+use maw_core::backend::fuse::DaemonCommand;
 use maw_core::backend::platform;
 use maw_core::backend::{AnyBackend, WorkspaceBackend};
 use maw_core::config::{BackendKind, ManifoldConfig};
@@ -16,6 +17,7 @@ use maw_core::model::types::WorkspaceId;
 
 mod advance;
 mod annotate;
+pub(crate) mod artifacts;
 pub(crate) mod capture;
 mod clean;
 mod clean_build;
@@ -27,6 +29,7 @@ pub(crate) mod destroy_guidance;
 pub(crate) mod destroy_preview;
 pub(crate) mod destroy_record;
 mod diff;
+pub(crate) mod disk;
 pub(crate) mod epoch_drift;
 pub(crate) mod ff_absorb;
 mod history;
@@ -39,16 +42,21 @@ mod names;
 pub(crate) mod oplog_runtime;
 mod overlap;
 pub(crate) mod post_sync_hook;
+mod provenance;
 mod prune;
+pub(crate) mod queue;
 pub(crate) mod recover;
 pub(crate) mod resolve;
-mod resolve_structured;
+mod resolve_apply;
+pub(crate) mod resolve_structured;
 mod restore;
+mod schedule;
 mod status;
 pub mod sync;
 mod templates;
 mod touched;
 mod undo;
+mod weather;
 pub(crate) mod working_copy;
 
 // Re-export public API used by other modules
@@ -82,6 +90,8 @@ pub struct MawConfig {
     lock: LockConfig,
     #[serde(default)]
     invariant: InvariantConfig,
+    #[serde(default)]
+    artifacts: artifacts::ArtifactsConfig,
 }
 
 /// Repo-level epoch lock configuration (bn-13rc, `[lock]` in `.maw.toml`).
@@ -282,6 +292,11 @@ impl MawConfig {
     pub(crate) const fn hook_timeout_seconds(&self) -> u64 {
         self.hooks.hook_timeout_seconds
     }
+
+    /// Build directories shared between workspaces (`[artifacts]`).
+    pub(crate) const fn artifacts(&self) -> &artifacts::ArtifactsConfig {
+        &self.artifacts
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -495,6 +510,15 @@ pub enum WorkspaceCommands {
         ///   maw ws create fix-123 --from main --description "fixing login timeout bug"
         #[arg(short = 'd', long = "description", value_name = "TEXT")]
         description: Option<String>,
+
+        /// Create a sparse workspace with only these paths checked out.
+        ///
+        /// Comma-separated directories or globs relative to the repo root
+        /// (e.g. --paths services/api,libs/*). Top-level files are always
+        /// included. Paths left out are absent from the workspace and treated
+        /// as unchanged at merge time.
+        #[arg(long, value_delimiter = ',', value_name = "PATHS")]
+        paths: Vec<String>,
     },
 
     /// Describe (label) the current workspace state
@@ -774,6 +798,15 @@ pub enum WorkspaceCommands {
         #[arg(long)]
         check: bool,
 
+        /// Show each workspace's disk usage.
+        ///
+        /// Usage is split into bytes the workspace owns and bytes it shares
+        /// with the epoch snapshot (overlay and reflink backends), and checked
+        /// against any `[workspace.quota]` limits. Walks every workspace, so
+        /// this is slower than a plain list.
+        #[arg(long)]
+        disk: bool,
+
         /// Output format: text, json, or pretty
         ///
         /// If not specified, auto-detects: pretty for TTY, text for pipes.
@@ -804,6 +837,8 @@ pub enum WorkspaceCommands {
         #[arg(long)]
         check: bool,
         #[arg(long)]
+        disk: bool,
+        #[arg(long)]
         format: Option<OutputFormat>,
         #[arg(long, hide = true, conflicts_with = "format")]
         json: bool,
@@ -931,6 +966,31 @@ pub enum WorkspaceCommands {
         json: bool,
     },
 
+    /// Forecast merge conflicts across all active workspaces
+    ///
+    /// Runs the real partition + resolve pipeline (diff3, AST merge,
+    /// merge drivers) in memory over every pair of active workspaces and
+    /// over all of them together, against the current epoch. Nothing is
+    /// committed and the epoch does not move.
+    ///
+    /// Reports a matrix of predicted conflict counts plus the conflicting
+    /// hunks or AST nodes per pair, so a coordinator can merge in a
+    /// low-conflict order. Stale workspaces are skipped.
+    ///
+    /// Examples:
+    ///   maw ws weather
+    ///   maw ws weather --format json
+    #[command(verbatim_doc_comment)]
+    Weather {
+        /// Output format: text, json, or pretty
+        #[arg(long)]
+        format: Option<OutputFormat>,
+
+        /// Shorthand for --format json
+        #[arg(long, hide = true, conflicts_with = "format")]
+        json: bool,
+    },
+
     /// Sync workspace with repository (handle stale working copy)
     ///
     /// Run this at the start of every session. If the working copy is stale
@@ -1319,9 +1379,29 @@ pub enum WorkspaceCommands {
     #[command(verbatim_doc_comment)]
     Merge {
         /// Workspace names to merge
-        #[arg(required_unless_present = "abort")]
+        #[arg(required_unless_present_any = ["abort", "all_ready"])]
         workspaces: Vec<String>,
 
+        /// Merge every ready workspace: active (not stale), with changes,
+        /// and not the merge target.
+        #[arg(long, conflicts_with = "workspaces")]
+        all_ready: bool,
+
+        /// Merge in conflict-aware batches instead of one N-way merge.
+        ///
+        /// Forecasts conflicts between the workspaces (see `maw ws
+        /// weather`), groups them into pairwise conflict-free batches, and
+        /// merges the batches in order — workspaces that conflict with the
+        /// most others go last. Stops at the first batch whose merge fails.
+        /// With --dry-run, prints the schedule only.
+        ///
+        ///   maw ws merge --all-ready --schedule --into default --message "..."
+        #[arg(
+            long,
+            conflicts_with_all = ["plan", "check", "resolve", "resolve_all", "no_auto_rebase"]
+        )]
+        schedule: bool,
+
         /// Explicit merge target: default workspace, branch-attached workspace, or active change id.
         ///
         /// Use ws:<name> or change:<id> when a bare target is ambiguous.
@@ -1474,6 +1554,8 @@ pub enum WorkspaceCommands {
     ///   - Base (common ancestor) content for reference
     ///   - Localized conflict atoms (exact line ranges / AST regions)
     ///   - Suggested resolution strategies
+    ///   - JSON only: per-region provenance -- each side's commits since the
+    ///     epoch that touched the region, plus its `ws describe` text
     ///
     /// Note: this command detects pre-merge conflicts between workspaces.
     /// For working-copy conflicts after a merge (local-vs-merge), use:
@@ -1522,6 +1604,13 @@ pub enum WorkspaceCommands {
     /// Per-block resolution via `cf-N=NAME` is not currently supported.
     /// Use --list to see conflicted files.
     ///
+    /// --apply takes a JSON patch of exact replacement content keyed by
+    /// conflict ID (cf-xxxx, whole file) or atom ID (cf-xxxx.N, one region;
+    /// an optional "region" guards against stale atoms). The patch is
+    /// validated against the current conflicts, sanity-checked, written
+    /// all-or-nothing and committed:
+    ///   {"resolutions": [{"id": "cf-k7mx.0", "content": "x = 4\n"}]}
+    ///
     /// Examples:
     ///   maw ws resolve default --list                          # list all conflicts
     ///   maw ws resolve default --list src/main.rs              # list one file
@@ -1531,6 +1620,7 @@ pub enum WorkspaceCommands {
     ///   maw ws resolve default --keep both                     # keep both sides concatenated
     ///   maw ws resolve default --keep union                    # keep both sides, deduped
     ///   maw ws resolve default --keep src/main.rs=bn-2sc3      # resolve one file
+    ///   maw ws resolve alice --apply resolutions.json          # apply exact content
     #[command(verbatim_doc_comment)]
     Resolve {
         /// Workspace containing conflicts
@@ -1550,6 +1640,10 @@ pub enum WorkspaceCommands {
         #[arg(long)]
         list: bool,
 
+        /// Apply a JSON resolution patch (`-` reads stdin)
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["keep", "list", "paths"])]
+        apply: Option<PathBuf>,
+
         /// Output format: text or json
         #[arg(long)]
         format: Option<OutputFormat>,
@@ -1574,6 +1668,7 @@ pub fn run(cmd: WorkspaceCommands) -> Result<()> {
             persistent,
             template,
             description,
+            paths,
         } => {
             // bn-21qy: resolve the name early so we can validate it before
             // anything else (including the source-required check whose hint
@@ -1618,13 +1713,14 @@ pub fn run(cmd: WorkspaceCommands) -> Result<()> {
             } else {
                 resolved_name.expect("name is required unless --random is set")
             };
-            create::create(
+            create::create_with_paths(
                 &name,
                 from.as_deref(),
                 change.as_deref(),
                 persistent,
                 template,
                 description.as_deref(),
+                &paths,
             )
         }
         WorkspaceCommands::Describe { name, message } => describe::describe(&name, &message),
@@ -1783,6 +1879,7 @@ pub fn run(cmd: WorkspaceCommands) -> Result<()> {
         WorkspaceCommands::List {
             verbose,
             check,
+            disk,
             format,
             json,
             names,
@@ -1790,6 +1887,7 @@ pub fn run(cmd: WorkspaceCommands) -> Result<()> {
         | WorkspaceCommands::Ls {
             verbose,
             check,
+            disk,
             format,
             json,
             names,
@@ -1800,6 +1898,7 @@ pub fn run(cmd: WorkspaceCommands) -> Result<()> {
                 list::list(
                     verbose,
                     check,
+                    disk,
                     OutputFormat::resolve(OutputFormat::with_json_flag(format, json)),
                 )
             }
@@ -1849,6 +1948,10 @@ pub fn run(cmd: WorkspaceCommands) -> Result<()> {
             let fmt = OutputFormat::resolve(OutputFormat::with_json_flag(format, json));
             overlap::overlap(&ws1, &ws2, fmt)
         }
+        WorkspaceCommands::Weather { format, json } => {
+            let fmt = OutputFormat::resolve(OutputFormat::with_json_flag(format, json));
+            weather::weather(fmt)
+        }
         WorkspaceCommands::Sync {
             name,
             all,
@@ -1897,6 +2000,8 @@ pub fn run(cmd: WorkspaceCommands) -> Result<()> {
         }
         WorkspaceCommands::Merge {
             workspaces,
+            all_ready,
+            schedule,
             into,
             abort,
             destroy,
@@ -1928,6 +2033,21 @@ pub fn run(cmd: WorkspaceCommands) -> Result<()> {
                      To clear a stuck merge instead: maw ws merge --abort"
                 )
             })?;
+            let workspaces = if all_ready {
+                let target = resolve_merge_target(&root, &into)?;
+                let config = MawConfig::load(&root)?;
+                let ready = schedule::ready_workspaces(
+                    &root,
+                    &[config.default_workspace(), target.workspace.as_str()],
+                )?;
+                if ready.is_empty() {
+                    println!("No ready workspaces to merge.");
+                    return Ok(());
+                }
+                ready
+            } else {
+                workspaces
+            };
             if check {
                 let target = match resolve_merge_target(&root, &into) {
                     Ok(target) => target,
@@ -2014,29 +2134,33 @@ pub fn run(cmd: WorkspaceCommands) -> Result<()> {
                 );
             };
 
-            merge::merge(
-                &workspaces,
-                &merge::MergeOptions {
-                    destroy_after: destroy,
-                    confirm,
-                    message: if dry_run {
-                        None
-                    } else {
-                        Some(&resolved_message)
-                    },
-                    dry_run,
-                    format: fmt,
-                    target_workspace: &target.workspace,
-                    target_branch: &target.branch,
-                    target_change_id: target.change_id.as_deref(),
-                    target_updates_epoch: target.updates_epoch,
-                    resolve,
-                    resolve_all,
-                    verbose,
-                    force,
-                    auto_rebase_siblings: if no_auto_rebase { Some(false) } else { None },
+            let opts = merge::MergeOptions {
+                destroy_after: destroy,
+                confirm,
+                message: if dry_run {
+                    None
+                } else {
+                    Some(&resolved_message)
                 },
-            )
+                dry_run,
+                format: fmt,
+                target_workspace: &target.workspace,
+                target_branch: &target.branch,
+                target_change_id: target.change_id.as_deref(),
+                target_updates_epoch: target.updates_epoch,
+                resolve,
+                resolve_all,
+                verbose,
+                force,
+                auto_rebase_siblings: if no_auto_rebase { Some(false) } else { None },
+                prevalidated_tree: None,
+                json_sink: None,
+            };
+            if schedule {
+                schedule::merge_scheduled(&root, &workspaces, &opts)
+            } else {
+                merge::merge(&workspaces, &opts)
+            }
         }
         WorkspaceCommands::Conflicts {
             workspaces,
@@ -2051,9 +2175,13 @@ pub fn run(cmd: WorkspaceCommands) -> Result<()> {
             paths,
             keep,
             list,
+            apply,
             format,
         } => {
             let fmt = OutputFormat::resolve(format);
+            if let Some(patch) = apply {
+                return resolve_apply::run(&workspace, &patch, fmt);
+            }
             resolve::run(&workspace, &paths, &keep, list, fmt)
         }
     }
@@ -2217,11 +2345,26 @@ pub fn get_backend() -> Result<AnyBackend> {
     let resolved = platform::resolve_backend_kind(configured_kind, file_count, &caps);
 
     // Construct and return the backend.
-    AnyBackend::from_kind(resolved, root).or_else(|e| {
+    AnyBackend::from_kind(resolved, root, fuse_daemon().ok()).or_else(|e| {
         // If the resolved backend fails to initialize (e.g., overlay not
         // available despite detection), fall back to git-worktree and warn.
         tracing::warn!("Backend init failed ({e}), falling back to git-worktree");
-        AnyBackend::from_kind(BackendKind::GitWorktree, repo_root()?)
+        AnyBackend::from_kind(BackendKind::GitWorktree, repo_root()?, None)
+    })
+}
+
+/// The hidden subcommand that runs a FUSE workspace's daemon.
+pub const FUSE_SERVE_COMMAND: &str = "fuse-serve";
+
+/// Start FUSE daemons as `<this maw> fuse-serve`.
+///
+/// # Errors
+///
+/// Returns an error if the running executable cannot be located.
+pub fn fuse_daemon() -> Result<DaemonCommand> {
+    Ok(DaemonCommand {
+        program: std::env::current_exe().context("locate the maw executable")?,
+        args: vec![FUSE_SERVE_COMMAND.into()],
     })
 }
 
@@ -0,0 +1,140 @@
+//! Conflict provenance for `maw ws conflicts --format json`.
+//!
+//! For every conflict atom (or the whole file when the conflict has no
+//! atoms), lists each side's commits in `epoch..HEAD` that touched the
+//! region, with their messages and the workspace's `ws describe` text, so an
+//! agent resolving the conflict can see what each side was trying to do.
+//!
+//! Provenance is best-effort: a side whose history cannot be read is left
+//! out rather than failing the report.
+
+use std::ops::Range;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use maw_git::GitRepo as _;
+use serde::Serialize;
+
+use maw::merge::provenance::{CommitRef, blame_regions, region_lines};
+use maw::merge::resolve::ConflictRecord;
+use maw_core::backend::WorkspaceBackend;
+use maw_core::model::conflict::Region;
+use maw_core::model::types::WorkspaceId;
+
+use super::metadata;
+
+/// Provenance of one conflict region.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegionProvenance {
+    /// Atom ID (e.g. "cf-k7mx.0"); absent for whole-file conflicts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub atom_id: Option<String>,
+    /// The region in the base version.
+    pub region: Region,
+    /// Each workspace side's commits touching the region.
+    pub sides: Vec<SideProvenance>,
+}
+
+/// One workspace's history for a conflict region.
+#[derive(Debug, Clone, Serialize)]
+pub struct SideProvenance {
+    /// Workspace name.
+    pub workspace: String,
+    /// The workspace's `ws describe` text, if set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Commits that touched the region, newest first.
+    pub commits: Vec<CommitRef>,
+}
+
+/// Build provenance for `record`, one entry per atom (or one whole-file
+/// entry when the conflict has no atoms).
+pub fn conflict_provenance<B: WorkspaceBackend>(
+    root: &Path,
+    backend: &B,
+    record: &ConflictRecord,
+    atom_ids: &[String],
+) -> Vec<RegionProvenance> {
+    let base = record.base.as_deref().unwrap_or_default();
+    let regions: Vec<Region> = if record.atoms.is_empty() {
+        vec![Region::WholeFile]
+    } else {
+        record
+            .atoms
+            .iter()
+            .map(|atom| atom.base_region.clone())
+            .collect()
+    };
+    let line_ranges: Vec<Range<u32>> = regions
+        .iter()
+        .map(|region| region_lines(region, base))
+        .collect();
+    let path = record.path.to_string_lossy().replace('\\', "/");
+
+    let mut per_side: Vec<(String, Option<String>, Vec<Vec<CommitRef>>)> = Vec::new();
+    for side in &record.sides {
+        let ws_id = &side.workspace_id;
+        if ws_id.is_epoch_delta() || !backend.exists(ws_id) {
+            continue;
+        }
+        match side_history(root, backend, ws_id, &path, &line_ranges) {
+            Ok(commits) => {
+                let description = metadata::read(root, ws_id.as_str())
+                    .ok()
+                    .and_then(|meta| meta.description);
+                per_side.push((ws_id.as_str().to_owned(), description, commits));
+            }
+            Err(e) => tracing::warn!(
+                workspace = %ws_id,
+                path = %path,
+                error = %e,
+                "ws conflicts: could not compute provenance"
+            ),
+        }
+    }
+
+    regions
+        .into_iter()
+        .enumerate()
+        .map(|(i, region)| RegionProvenance {
+            atom_id: atom_ids.get(i).cloned(),
+            region,
+            sides: per_side
+                .iter()
+                .map(|(workspace, description, commits)| SideProvenance {
+                    workspace: workspace.clone(),
+                    description: description.clone(),
+                    commits: commits[i].clone(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Commits in the workspace's `epoch..HEAD` touching each of `regions`.
+///
+/// Starts at the current epoch when the workspace is based on it, else at
+/// the merge base (a stale workspace's own commits still start there).
+fn side_history<B: WorkspaceBackend>(
+    root: &Path,
+    backend: &B,
+    ws_id: &WorkspaceId,
+    path: &str,
+    regions: &[Range<u32>],
+) -> Result<Vec<Vec<CommitRef>>> {
+    let ws_path = backend.workspace_path(ws_id);
+    let repo = maw_git::GixRepo::open(&ws_path)
+        .with_context(|| format!("failed to open repo at {}", ws_path.display()))?;
+    let head = repo.rev_parse("HEAD")?;
+    let epoch: maw_git::GitOid = maw_core::refs::read_epoch_current(root)?
+        .context("no current epoch")?
+        .as_str()
+        .parse()?;
+    let from = if repo.is_ancestor(epoch, head)? {
+        epoch
+    } else {
+        repo.merge_base(epoch, head)?
+            .context("workspace shares no history with the epoch")?
+    };
+    Ok(blame_regions(&repo, from, head, path, regions)?)
+}
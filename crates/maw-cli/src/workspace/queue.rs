@@ -0,0 +1,311 @@
+//! `maw merge queue` — local merge queue with speculative validation.
+//!
+//! The queue model (cumulative candidates, parallel validation, bisection on
+//! failure) lives in [`maw::merge::queue`]; this module persists requests and
+//! drives a run: speculate on the head of the queue, commit the passing
+//! prefix through the regular merge path, eject failures, repeat.
+//!
+//! Entries merge exactly like `maw ws merge`: a source that went stale
+//! because an earlier entry landed (a dirty sibling is not auto-rebased)
+//! stops the run with the usual sync hint, and the entry stays queued.
+
+use std::cell::RefCell;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Result, bail};
+use serde::Serialize;
+
+use crate::format::OutputFormat;
+use maw::merge::queue::{MergeQueue, QueueEntry, Speculation, Verdict, decide, speculate};
+use maw_core::backend::WorkspaceBackend;
+use maw_core::config::ManifoldConfig;
+use maw_core::model::types::{EpochId, GitOid};
+
+use super::merge::{MergeOptions, merge_with_epoch_lock_held};
+use super::{MawConfig, get_backend, repo_root, resolve_merge_target};
+
+/// Default number of entries speculated on per round.
+pub const DEFAULT_DEPTH: usize = 4;
+
+fn manifold_dir(root: &Path) -> std::path::PathBuf {
+    maw_core::model::layout::LayoutFlavor::detect_with_env(root).manifold_dir(root)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// `maw merge queue add` — enqueue a merge of `workspaces`.
+pub fn add(workspaces: &[String], message: &str) -> Result<()> {
+    if message.trim().is_empty() {
+        bail!("A merge queue entry needs a commit message.\n  Fix: pass --message \"...\"");
+    }
+    let root = repo_root()?;
+    let maw_config = MawConfig::load(&root)?;
+    let backend = get_backend()?;
+    let manifold_dir = manifold_dir(&root);
+    let mut queue = MergeQueue::load(&manifold_dir)?;
+
+    for ws in workspaces {
+        if ws == maw_config.default_workspace() {
+            bail!("Cannot queue the default workspace '{ws}' for merge.");
+        }
+        let id = maw_core::model::types::WorkspaceId::new(ws)
+            .map_err(|e| anyhow::anyhow!("invalid workspace name '{ws}': {e}"))?;
+        if !backend.exists(&id) {
+            bail!("Workspace '{ws}' does not exist.\n  Check: maw ws list");
+        }
+        if let Some(entry) = queue.entry_for_workspace(ws) {
+            bail!(
+                "Workspace '{ws}' is already queued in {}.\n  Remove it first: maw merge queue remove {}",
+                entry.id,
+                entry.id
+            );
+        }
+    }
+
+    let id = queue.push(workspaces.to_vec(), message.to_owned(), now_secs());
+    queue.save(&manifold_dir)?;
+    println!(
+        "Queued {id}: {} (position {})",
+        workspaces.join(", "),
+        queue.entries.len()
+    );
+    println!("Next: maw merge queue run");
+    Ok(())
+}
+
+/// `maw merge queue list` — print pending entries in merge order.
+pub fn list(format: OutputFormat) -> Result<()> {
+    let root = repo_root()?;
+    let queue = MergeQueue::load(&manifold_dir(&root))?;
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&queue.entries)?);
+        }
+        OutputFormat::Text | OutputFormat::Pretty => {
+            if queue.entries.is_empty() {
+                println!("Merge queue is empty.");
+                return Ok(());
+            }
+            println!("Merge queue ({} entries):", queue.entries.len());
+            for entry in &queue.entries {
+                println!(
+                    "  {}  {}  — {}",
+                    entry.id,
+                    entry.workspaces.join(", "),
+                    entry.message
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `maw merge queue remove` — drop an entry without merging it.
+pub fn remove(id: &str) -> Result<()> {
+    let root = repo_root()?;
+    let manifold_dir = manifold_dir(&root);
+    let mut queue = MergeQueue::load(&manifold_dir)?;
+    let Some(entry) = queue.remove(id) else {
+        bail!("No merge queue entry '{id}'.\n  Check: maw merge queue list");
+    };
+    queue.save(&manifold_dir)?;
+    println!("Removed {id} ({}).", entry.workspaces.join(", "));
+    Ok(())
+}
+
+#[derive(Debug, Default, Serialize)]
+struct RunOutput {
+    merged: Vec<MergedOutput>,
+    ejected: Vec<EjectedOutput>,
+    remaining: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct MergedOutput {
+    id: String,
+    workspaces: Vec<String>,
+    /// The entry's `maw ws merge --format json` document.
+    merge: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+struct EjectedOutput {
+    id: String,
+    workspaces: Vec<String>,
+    reason: String,
+}
+
+/// `maw merge queue run` — process the queue until it is empty or stuck.
+///
+/// Each round speculates on the first `depth` entries, commits the prefix
+/// whose candidates validated, and ejects the first failure (plus any entry
+/// that conflicts). The epoch lock is held for the whole run.
+pub fn run(depth: usize, format: OutputFormat) -> Result<()> {
+    let root = repo_root()?;
+    let manifold_dir = manifold_dir(&root);
+    let maw_config = MawConfig::load(&root)?;
+    let backend = get_backend()?;
+    let text_mode = format != OutputFormat::Json;
+    let depth = depth.max(1);
+
+    let _epoch_lock = crate::epoch_lock::EpochLock::acquire(&root, "merge queue")?;
+    let target = resolve_merge_target(&root, maw_config.default_workspace())?;
+    let mut output = RunOutput::default();
+
+    loop {
+        let queue = MergeQueue::load(&manifold_dir)?;
+        if queue.entries.is_empty() {
+            break;
+        }
+        let batch: Vec<QueueEntry> = queue.entries.iter().take(depth).cloned().collect();
+        let Some(epoch_oid) = maw_core::refs::read_epoch_current(&root)? else {
+            bail!("No current epoch (refs/manifold/epoch/current)\n  Fix: maw init");
+        };
+        let epoch = EpochId::new(epoch_oid.as_str())
+            .map_err(|e| anyhow::anyhow!("invalid epoch OID '{}': {e}", epoch_oid.as_str()))?;
+        let manifold_config = ManifoldConfig::load(&manifold_dir.join("config.toml"))
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+        if text_mode {
+            println!(
+                "Speculating on {} entr{}: {}",
+                batch.len(),
+                if batch.len() == 1 { "y" } else { "ies" },
+                batch
+                    .iter()
+                    .map(|e| e.id.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        let speculations = speculate(
+            &root,
+            &manifold_dir,
+            &backend,
+            &epoch,
+            &batch,
+            &manifold_config.merge.validation,
+        )?;
+        if text_mode {
+            print_speculations(&speculations);
+        }
+        let decision = decide(&speculations);
+
+        let mut progressed = false;
+        for (id, tree) in &decision.commit {
+            let Some(entry) = batch.iter().find(|e| &e.id == id) else {
+                continue;
+            };
+            let merge = commit_entry(&root, &target, entry, tree, format)?;
+            let mut queue = MergeQueue::load(&manifold_dir)?;
+            queue.remove(id);
+            queue.save(&manifold_dir)?;
+            output.merged.push(MergedOutput {
+                id: id.clone(),
+                workspaces: entry.workspaces.clone(),
+                merge,
+            });
+            progressed = true;
+        }
+        for (id, reason) in &decision.eject {
+            let mut queue = MergeQueue::load(&manifold_dir)?;
+            if let Some(entry) = queue.remove(id) {
+                queue.save(&manifold_dir)?;
+                if text_mode {
+                    println!("Ejected {id} ({}): {reason}", entry.workspaces.join(", "));
+                }
+                output.ejected.push(EjectedOutput {
+                    id: id.clone(),
+                    workspaces: entry.workspaces,
+                    reason: reason.clone(),
+                });
+                progressed = true;
+            }
+        }
+        if !progressed {
+            break;
+        }
+    }
+
+    output.remaining = MergeQueue::load(&manifold_dir)?
+        .entries
+        .into_iter()
+        .map(|e| e.id)
+        .collect();
+    if text_mode {
+        println!();
+        println!(
+            "Merge queue: {} merged, {} ejected, {} remaining.",
+            output.merged.len(),
+            output.ejected.len(),
+            output.remaining.len()
+        );
+    } else {
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    }
+    Ok(())
+}
+
+fn print_speculations(speculations: &[Speculation]) {
+    for speculation in speculations {
+        let verdict = match &speculation.verdict {
+            Verdict::Passed(_) => "passed".to_owned(),
+            Verdict::Failed(_) => "FAILED validation".to_owned(),
+            Verdict::Conflicted(n) => format!("{n} conflict(s)"),
+        };
+        println!("  {}: {verdict}", speculation.entry_id);
+    }
+}
+
+/// Merge one entry whose speculative candidate validated as `tree`.
+///
+/// In JSON mode the merge's own document is returned instead of printed, so
+/// the run prints a single document; in text mode the merge prints as usual
+/// and `Null` is returned.
+fn commit_entry(
+    root: &Path,
+    target: &super::MergeTarget,
+    entry: &QueueEntry,
+    tree: &GitOid,
+    format: OutputFormat,
+) -> Result<serde_json::Value> {
+    let json_sink = RefCell::new(Vec::new());
+    if format != OutputFormat::Json {
+        println!();
+        println!("Merging {} ({})", entry.id, entry.workspaces.join(", "));
+    }
+    let opts = MergeOptions {
+        destroy_after: false,
+        confirm: false,
+        message: Some(&entry.message),
+        dry_run: false,
+        format,
+        target_workspace: &target.workspace,
+        target_branch: &target.branch,
+        target_change_id: target.change_id.as_deref(),
+        target_updates_epoch: target.updates_epoch,
+        resolve: Vec::new(),
+        resolve_all: None,
+        verbose: false,
+        force: false,
+        auto_rebase_siblings: None,
+        prevalidated_tree: Some(tree),
+        json_sink: (format == OutputFormat::Json).then_some(&json_sink),
+    };
+    merge_with_epoch_lock_held(root, &entry.workspaces, &opts).map_err(|e| {
+        anyhow::anyhow!(
+            "Merge queue stopped at {} ({}): {e}\n  The entry stays queued; fix it and rerun: maw merge queue run",
+            entry.id,
+            entry.workspaces.join(", ")
+        )
+    })?;
+    Ok(json_sink
+        .into_inner()
+        .pop()
+        .unwrap_or(serde_json::Value::Null))
+}
@@ -23,6 +23,7 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{Result, bail};
 
+use maw::merge::rerere::ResolutionMemory;
 use maw_git::GitRepo as _;
 
 use crate::format::OutputFormat;
@@ -750,6 +751,7 @@ pub fn run(
     let mut resolved_count = 0;
     let mut partially_resolved = Vec::new();
     let mut skipped = Vec::new();
+    let memory = ResolutionMemory::for_repo(&root);
 
     for rel_path in &target_files {
         let full_path = ws_path.join(rel_path);
@@ -778,9 +780,21 @@ pub fn run(
             .filter(|c| matches!(c, FileChunk::Conflict(_)))
             .count();
 
-        match resolve_chunks(&chunks, file_side, &block_sides) {
+        let mut resolved_blocks: Vec<(&ConflictBlock, String)> = Vec::new();
+        match resolve_chunks_with(&chunks, file_side, &block_sides, &mut |block, text| {
+            resolved_blocks.push((block, text.to_owned()));
+        }) {
             Ok(resolved) => {
                 std::fs::write(&full_path, resolved.as_bytes())?;
+                for (block, text) in &resolved_blocks {
+                    let sides = [block.left_content.as_str(), block.right_content.as_str()];
+                    if let Err(e) = memory.record_hunk(rel_path, &block.base_content, sides, text) {
+                        eprintln!(
+                            "warning: could not record resolution of {}: {e}",
+                            rel_path.display()
+                        );
+                    }
+                }
 
                 // Count blocks that were NOT resolved (re-emitted as markers).
                 // This catches the bn-2wnt case: --keep with per-block flags
@@ -1512,13 +1526,16 @@ fn parse_file_conflicts(content: &str) -> Vec<FileChunk> {
                 chunks.push(FileChunk::Context(std::mem::take(&mut context_text)));
             }
 
-            let left_name = extract_name_from_marker(line);
+            let mut left_name = extract_name_from_marker(line);
             let mut left_content = String::new();
             let mut base_content = String::new();
             let mut right_content = String::new();
             let mut right_name = String::new();
             let mut in_base = false;
             let mut in_right = false;
+            // jj snapshot blocks (`[merge] conflict_style = "jj"`) name their
+            // sides on `+++++++` lines and put the base after `-------`.
+            let mut jj = false;
             // Track nested conflict markers so that <<<<<<< / >>>>>>>
             // pairs inside the content (e.g. from previously unresolved
             // conflicts in stashed files) don't cause premature break.
@@ -1539,7 +1556,9 @@ fn parse_file_conflicts(content: &str) -> Vec<FileChunk> {
                 } else if inner.starts_with(">>>>>>>") {
                     if nested_depth == 0 {
                         // This is our actual closing marker.
-                        right_name = extract_name_from_marker(inner);
+                        if !jj {
+                            right_name = extract_name_from_marker(inner);
+                        }
                         break;
                     }
                     // Closing a nested marker — treat as content.
@@ -1552,9 +1571,22 @@ fn parse_file_conflicts(content: &str) -> Vec<FileChunk> {
                         &mut base_content,
                         &mut left_content,
                     );
-                } else if nested_depth == 0 && inner.starts_with("|||||||") {
+                } else if nested_depth == 0
+                    && inner.starts_with("+++++++")
+                    && !jj
+                    && left_content.is_empty()
+                {
+                    jj = true;
+                    left_name = extract_name_from_marker(inner);
+                } else if nested_depth == 0 && jj && inner.starts_with("-------") {
                     in_base = true;
-                } else if nested_depth == 0 && inner.starts_with("=======") {
+                } else if nested_depth == 0 && jj && inner.starts_with("+++++++") {
+                    in_base = false;
+                    in_right = true;
+                    right_name = extract_name_from_marker(inner);
+                } else if nested_depth == 0 && !jj && inner.starts_with("|||||||") {
+                    in_base = true;
+                } else if nested_depth == 0 && !jj && inner.starts_with("=======") {
                     in_base = false;
                     in_right = true;
                 } else if in_right {
@@ -1608,11 +1640,13 @@ fn parse_file_conflicts(content: &str) -> Vec<FileChunk> {
 /// - `block_sides`: per-block overrides keyed by "cf-N"
 ///
 /// A block is resolved if it has an entry in `block_sides` or if `file_side`
-/// is set. Unresolved blocks are left as conflict markers.
-fn resolve_chunks(
-    chunks: &[FileChunk],
+/// is set. Unresolved blocks are left as conflict markers. Each resolved
+/// block and the text that replaced it are reported to `on_resolved`.
+fn resolve_chunks_with<'a>(
+    chunks: &'a [FileChunk],
     file_side: Option<&str>,
     block_sides: &BTreeMap<String, String>,
+    on_resolved: &mut dyn FnMut(&'a ConflictBlock, &str),
 ) -> Result<String> {
     let mut output = String::new();
     let mut block_idx = 0;
@@ -1635,6 +1669,7 @@ fn resolve_chunks(
                     .or(file_side);
 
                 if let Some(side_name) = side {
+                    let start = output.len();
                     if side_name == "both" {
                         // Concatenate both sides (left then right)
                         output.push_str(&block.left_content);
@@ -1660,6 +1695,7 @@ fn resolve_chunks(
                         output.push_str(chosen);
                     }
                     output.push('\n');
+                    on_resolved(block, &output[start..]);
                     any_resolved = true;
                 } else {
                     // No resolution for this block — re-emit the markers
@@ -1696,6 +1732,15 @@ fn resolve_chunks(
     Ok(output)
 }
 
+#[cfg(test)]
+fn resolve_chunks(
+    chunks: &[FileChunk],
+    file_side: Option<&str>,
+    block_sides: &BTreeMap<String, String>,
+) -> Result<String> {
+    resolve_chunks_with(chunks, file_side, block_sides, &mut |_, _| {})
+}
+
 // ---------------------------------------------------------------------------
 // Marker helpers
 // ---------------------------------------------------------------------------
@@ -1705,11 +1750,14 @@ fn resolve_chunks(
 /// `<<<<<<< bn-2sc3 (merged workspace)` → `bn-2sc3`
 /// `>>>>>>> default (local edits)` → `default`
 /// `<<<<<<< bn-2sc3, bn-4xyz (merged workspaces)` → `bn-2sc3, bn-4xyz`
+/// `+++++++ epoch (current)` → `epoch` (jj snapshot side)
 fn extract_name_from_marker(line: &str) -> String {
     let trimmed = line
         .trim_start_matches('<')
         .trim_start_matches('>')
         .trim_start_matches('|')
+        .trim_start_matches('+')
+        .trim_start_matches('-')
         .trim();
 
     trimmed.find('(').map_or_else(
@@ -1731,25 +1779,14 @@ fn name_matches(keep_name: &str, label: &str) -> bool {
 
 /// Extract the two side names from the first conflict block in a file.
 fn extract_side_names(content: &str) -> Option<(String, String)> {
-    let mut left = None;
-    let mut right = None;
-
-    for line in content.lines() {
-        if line.starts_with("<<<<<<<") && left.is_none() {
-            left = Some(extract_name_from_marker(line));
-        }
-        if line.starts_with(">>>>>>>") && right.is_none() {
-            right = Some(extract_name_from_marker(line));
-        }
-        if left.is_some() && right.is_some() {
-            break;
-        }
-    }
-
-    match (left, right) {
-        (Some(l), Some(r)) => Some((l, r)),
-        _ => None,
-    }
+    parse_file_conflicts(content)
+        .into_iter()
+        .find_map(|chunk| match chunk {
+            FileChunk::Conflict(block) if !block.right_name.is_empty() => {
+                Some((block.left_name, block.right_name))
+            }
+            _ => None,
+        })
 }
 
 // ---------------------------------------------------------------------------
@@ -1975,6 +2012,37 @@ more code";
         assert_eq!(right, "default");
     }
 
+    #[test]
+    fn parse_jj_snapshot_markers() {
+        let content = "\
+before
+<<<<<<< Conflict 1 of 1
++++++++ epoch (current)
+E
+------- base
+B
++++++++ alice (workspace changes)
+W
+>>>>>>> Conflict 1 of 1 ends
+after";
+        let (left, right) = extract_side_names(content).expect("operation should succeed");
+        assert_eq!((left.as_str(), right.as_str()), ("epoch", "alice"));
+
+        let chunks = parse_file_conflicts(content);
+        let result = resolve_chunks(&chunks, Some("alice"), &BTreeMap::new())
+            .expect("operation should succeed");
+        assert_eq!(result, "before\nW\nafter\n");
+    }
+
+    #[test]
+    fn parse_merge_style_markers_without_base() {
+        let content = "<<<<<<< epoch (current)\nE\n=======\nW\n>>>>>>> alice\n";
+        let chunks = parse_file_conflicts(content);
+        let result = resolve_chunks(&chunks, Some("epoch"), &BTreeMap::new())
+            .expect("operation should succeed");
+        assert_eq!(result, "E\n");
+    }
+
     #[test]
     fn resolve_both_concatenates() {
         let content = "\
@@ -0,0 +1,617 @@
+//! `maw ws resolve --apply` — machine-applicable resolution patches.
+//!
+//! Agents resolve structured conflicts by submitting exact bytes instead of
+//! picking a side. The patch names conflicts by the deterministic IDs that
+//! `maw ws merge` and `maw ws resolve --list` print — `cf-k7mx` for a whole
+//! file, `cf-k7mx.0` for one conflict atom:
+//!
+//! ```json
+//! {
+//!   "resolutions": [
+//!     { "id": "cf-k7mx", "content": "whole file\n" },
+//!     { "id": "cf-q2rn.0", "region": {"kind": "lines", "start": 3, "end": 5}, "content": "x = 4\n" },
+//!     { "id": "cf-q2rn.1", "content": "y = 4\n" }
+//!   ]
+//! }
+//! ```
+//!
+//! The patch is validated against the current `ConflictTree` before anything
+//! is written: unknown or stale IDs, an atom whose `region` no longer matches,
+//! and files whose atoms are only partly covered are all rejected. Atom
+//! contents are spliced into the base in place of each atom's base region.
+//! Every resulting file must pass the post-merge sanity checks; then all files
+//! are written together (restored on a write failure), committed, and each
+//! path gets a `ConflictResolved` op in the workspace history.
+
+use std::collections::BTreeMap;
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+use maw::merge::rerere::ResolutionMemory;
+use maw_core::backend::WorkspaceBackend;
+use maw_core::config::ManifoldConfig;
+use maw_core::merge::types::ConflictTree;
+use maw_core::model::conflict::{Conflict, ConflictAtom, Region};
+use maw_core::model::types::{EpochId, GitOid, WorkspaceId};
+use maw_core::oplog::read::read_head;
+use maw_core::oplog::types::{OpPayload, Operation};
+use maw_git as git;
+
+use crate::format::OutputFormat;
+use crate::workspace::sync::sanity::{PostMergeSanityConfig, run_post_merge_sanity};
+
+use super::merge::{atom_ids, conflict_id, region_byte_range};
+use super::resolve_structured::{
+    ConflictContents, auto_commit_resolution, conflict_contents, persist_remaining_conflicts,
+    read_conflict_tree_sidecar, record_resolution, write_resolved_content,
+};
+use super::{get_backend, oplog_runtime::append_operation_with_runtime_checkpoint, repo_root};
+
+/// A resolution patch document.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ResolutionPatch {
+    resolutions: Vec<PatchEntry>,
+}
+
+/// Replacement content for one conflict (`cf-xxxx`) or atom (`cf-xxxx.N`).
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PatchEntry {
+    id: String,
+    /// Expected base region of the atom; rejects the entry when the conflict
+    /// was re-recorded with a different region.
+    #[serde(default)]
+    region: Option<Region>,
+    content: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ApplyOutput {
+    workspace: String,
+    resolved: Vec<ResolvedPath>,
+    conflicts_remaining: usize,
+    commit: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ResolvedPath {
+    id: String,
+    path: String,
+    atoms: usize,
+}
+
+/// A validated, sanity-checked file write.
+struct PlannedWrite {
+    id: String,
+    path: PathBuf,
+    conflict: Conflict,
+    bytes: Vec<u8>,
+    atoms: usize,
+}
+
+/// What the patch asks for on one conflicted path.
+enum PathPatch<'a> {
+    WholeFile(&'a str),
+    Atoms(BTreeMap<usize, &'a str>),
+}
+
+/// `maw ws resolve <workspace> --apply <file.json>` (`-` reads stdin).
+pub fn run(workspace: &str, patch_file: &Path, format: OutputFormat) -> Result<()> {
+    let root = repo_root()?;
+    let ws_path = maw_core::model::layout::LayoutFlavor::detect_with_env(&root)
+        .workspace_path(&root, workspace);
+    if !ws_path.exists() {
+        bail!(
+            "Workspace '{workspace}' not found at {}\n  To fix: check workspace name with `maw ws list`",
+            ws_path.display()
+        );
+    }
+
+    let raw = if patch_file == Path::new("-") {
+        let mut buf = String::new();
+        std::io::stdin()
+            .read_to_string(&mut buf)
+            .context("Failed to read resolution patch from stdin")?;
+        buf
+    } else {
+        std::fs::read_to_string(patch_file)
+            .with_context(|| format!("Failed to read resolution patch {}", patch_file.display()))?
+    };
+    let document: ResolutionPatch =
+        serde_json::from_str(&raw).context("Resolution patch is not valid JSON")?;
+    if document.resolutions.is_empty() {
+        bail!("Resolution patch has no entries.");
+    }
+
+    // Same staleness gate as `maw ws resolve`: a sidecar whose conflicts were
+    // all resolved by hand is cleared before we validate against it.
+    let _ = super::conflict_state::effective_conflict_state(&root, workspace, &ws_path);
+    let Some(mut tree) = read_conflict_tree_sidecar(&root, workspace) else {
+        bail!(
+            "Workspace '{workspace}' has no structured conflicts (conflict-tree.json).\n  \
+             Check: maw ws resolve {workspace} --list"
+        );
+    };
+
+    let repo = git::GixRepo::open(&ws_path)
+        .map_err(|e| anyhow::anyhow!("Failed to open git repo at {}: {e}", ws_path.display()))?;
+    let planned = plan_writes(&root, workspace, &repo, &tree, &document.resolutions)?;
+
+    write_all(&ws_path, &planned)?;
+
+    let memory = ResolutionMemory::for_repo(&root);
+    for write in &planned {
+        record_resolution(&repo, &memory, &write.conflict, &write.path, &write.bytes);
+        tree.conflicts.remove(&write.path);
+    }
+    persist_remaining_conflicts(&root, workspace, &tree)?;
+
+    let paths: Vec<PathBuf> = planned.iter().map(|write| write.path.clone()).collect();
+    let commit = auto_commit_resolution(&ws_path, workspace, &paths)?;
+    if let Some(sha) = &commit
+        && let Err(e) = record_resolved_ops(&root, workspace, &paths, sha)
+    {
+        eprintln!("warning: could not record conflict-resolved ops for '{workspace}': {e}");
+    }
+
+    let output = ApplyOutput {
+        workspace: workspace.to_owned(),
+        resolved: planned
+            .iter()
+            .map(|write| ResolvedPath {
+                id: write.id.clone(),
+                path: write.path.display().to_string(),
+                atoms: write.atoms,
+            })
+            .collect(),
+        conflicts_remaining: tree.conflicts.len(),
+        commit,
+    };
+    print_output(&output, format)
+}
+
+fn print_output(output: &ApplyOutput, format: OutputFormat) -> Result<()> {
+    let workspace = &output.workspace;
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(output)?);
+        return Ok(());
+    }
+    println!(
+        "Applied {} resolution(s) in '{workspace}':",
+        output.resolved.len()
+    );
+    for resolved in &output.resolved {
+        if resolved.atoms == 0 {
+            println!("  {}  {}", resolved.id, resolved.path);
+        } else {
+            println!(
+                "  {}  {}  ({} atom(s))",
+                resolved.id, resolved.path, resolved.atoms
+            );
+        }
+    }
+    if let Some(sha) = &output.commit {
+        println!("Committed: {}", &sha[..sha.len().min(12)]);
+    }
+    if output.conflicts_remaining > 0 {
+        println!(
+            "{} conflict(s) remaining.\n  Check: maw ws resolve {workspace} --list",
+            output.conflicts_remaining
+        );
+    }
+    Ok(())
+}
+
+/// Validate the patch against `tree` and build every file's new content.
+/// Nothing is written; sanity failures reject the whole patch.
+fn plan_writes(
+    root: &Path,
+    workspace: &str,
+    repo: &git::GixRepo,
+    tree: &ConflictTree,
+    entries: &[PatchEntry],
+) -> Result<Vec<PlannedWrite>> {
+    let by_id: BTreeMap<String, (&PathBuf, &Conflict)> = tree
+        .conflicts
+        .iter()
+        .map(|(path, conflict)| (conflict_id(&path.to_string_lossy()), (path, conflict)))
+        .collect();
+    let patches = group_entries(entries, &by_id, workspace)?;
+
+    let manifold_config = ManifoldConfig::load(
+        &maw_core::model::layout::LayoutFlavor::detect_with_env(root).bootstrap_config_path(root),
+    )
+    .unwrap_or_default();
+    let sanity_cfg = PostMergeSanityConfig::from_merge(&manifold_config.merge);
+
+    // Build every file's new content before touching the worktree.
+    let mut planned = Vec::new();
+    let mut failures = Vec::new();
+    for (id, path_patch) in &patches {
+        let (path, conflict) = by_id[id];
+        let contents = conflict_contents(repo, conflict).ok_or_else(|| {
+            anyhow::anyhow!(
+                "{id} ({}): {} conflicts cannot take replacement content.\n  \
+                 Resolve with: maw ws resolve {workspace} --keep {}=<side>",
+                path.display(),
+                conflict.variant_name(),
+                path.display()
+            )
+        })?;
+        let (bytes, atoms) = match path_patch {
+            PathPatch::WholeFile(content) => (content.as_bytes().to_vec(), 0),
+            PathPatch::Atoms(atoms) => (
+                splice_atoms(id, path, conflict, &contents, atoms)?,
+                atoms.len(),
+            ),
+        };
+        if let [ours, theirs, ..] = contents.sides.as_slice()
+            && let Err(failure) = run_post_merge_sanity(
+                path,
+                contents.base.as_deref().unwrap_or_default(),
+                ours,
+                theirs,
+                &bytes,
+                sanity_cfg,
+            )
+        {
+            failures.push(format!("  {id} ({}): {failure}", path.display()));
+        }
+        planned.push(PlannedWrite {
+            id: id.clone(),
+            path: path.clone(),
+            conflict: conflict.clone(),
+            bytes,
+            atoms,
+        });
+    }
+    if !failures.is_empty() {
+        bail!(
+            "Resolution patch failed post-merge sanity checks; nothing was written.\n{}",
+            failures.join("\n")
+        );
+    }
+    Ok(planned)
+}
+
+/// Validate patch entries against the current conflicts and group them by
+/// file ID.
+fn group_entries<'a>(
+    entries: &'a [PatchEntry],
+    by_id: &BTreeMap<String, (&PathBuf, &Conflict)>,
+    workspace: &str,
+) -> Result<BTreeMap<String, PathPatch<'a>>> {
+    let mut patches: BTreeMap<String, PathPatch<'a>> = BTreeMap::new();
+    for entry in entries {
+        let (file_id, atom) = match entry.id.split_once('.') {
+            Some((file_id, index)) => {
+                let index: usize = index.parse().map_err(|_| {
+                    anyhow::anyhow!("Invalid atom ID '{}' (expected cf-xxxx.N)", entry.id)
+                })?;
+                (file_id, Some(index))
+            }
+            None => (entry.id.as_str(), None),
+        };
+        let Some((path, conflict)) = by_id.get(file_id) else {
+            bail!(
+                "Unknown or stale conflict ID '{}' — no current conflict in '{workspace}' has it.\n  \
+                 Check: maw ws resolve {workspace} --list --format json",
+                entry.id
+            );
+        };
+
+        let Some(index) = atom else {
+            if entry.region.is_some() {
+                bail!(
+                    "'{}' is a whole-file ID; `region` only applies to atom IDs (cf-xxxx.N).",
+                    entry.id
+                );
+            }
+            if patches
+                .insert(file_id.to_owned(), PathPatch::WholeFile(&entry.content))
+                .is_some()
+            {
+                bail!(
+                    "{file_id} ({}) appears more than once in the patch.",
+                    path.display()
+                );
+            }
+            continue;
+        };
+
+        let atoms: &[ConflictAtom] = match conflict {
+            Conflict::Content { atoms, .. } => atoms,
+            _ => &[],
+        };
+        if atoms.is_empty() {
+            bail!(
+                "'{}': {file_id} ({}) has no atoms; resolve the whole file with \"{file_id}\".",
+                entry.id,
+                path.display()
+            );
+        }
+        let Some(atom) = atoms.get(index) else {
+            bail!(
+                "Unknown or stale atom ID '{}' — {} has {} atom(s) ({}).\n  \
+                 Check: maw ws resolve {workspace} --list --format json",
+                entry.id,
+                path.display(),
+                atoms.len(),
+                atom_ids(file_id, atoms.len()).join(", ")
+            );
+        };
+        if let Some(region) = &entry.region
+            && *region != atom.base_region
+        {
+            bail!(
+                "Stale atom '{}': expected region {region}, but the current conflict is at {}.",
+                entry.id,
+                atom.base_region
+            );
+        }
+        match patches
+            .entry(file_id.to_owned())
+            .or_insert_with(|| PathPatch::Atoms(BTreeMap::new()))
+        {
+            PathPatch::WholeFile(_) => bail!(
+                "{file_id} ({}) has both a whole-file and an atom resolution.",
+                path.display()
+            ),
+            PathPatch::Atoms(chosen) => {
+                if chosen.insert(index, &entry.content).is_some() {
+                    bail!("{} appears more than once in the patch.", entry.id);
+                }
+            }
+        }
+    }
+    Ok(patches)
+}
+
+/// Rebuild a file from its base, replacing each atom's base region with the
+/// patch content. Every atom must be covered.
+fn splice_atoms(
+    id: &str,
+    path: &Path,
+    conflict: &Conflict,
+    contents: &ConflictContents,
+    chosen: &BTreeMap<usize, &str>,
+) -> Result<Vec<u8>> {
+    let Conflict::Content { atoms, .. } = conflict else {
+        bail!("{id} ({}) has no atoms; use the file ID.", path.display());
+    };
+    if chosen.len() != atoms.len() {
+        let missing: Vec<String> = atom_ids(id, atoms.len())
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !chosen.contains_key(i))
+            .map(|(_, atom_id)| atom_id)
+            .collect();
+        bail!(
+            "{id} ({}): atoms {} are not covered by the patch.\n  \
+             Give every atom, or resolve the whole file with \"{id}\".",
+            path.display(),
+            missing.join(", ")
+        );
+    }
+    let Some(base) = &contents.base else {
+        bail!(
+            "{id} ({}) has no base content for atom-level resolution; use the file ID.",
+            path.display()
+        );
+    };
+
+    let mut regions: Vec<(usize, usize, &str)> = atoms
+        .iter()
+        .enumerate()
+        .map(|(i, atom)| {
+            let (start, end) = region_byte_range(&atom.base_region, base);
+            (start as usize, end as usize, chosen[&i])
+        })
+        .collect();
+    regions.sort_by_key(|(start, ..)| *start);
+
+    let mut out = Vec::with_capacity(base.len());
+    let mut pos = 0;
+    for (start, end, content) in regions {
+        if start < pos || end > base.len() {
+            bail!(
+                "{id} ({}): atom regions overlap or exceed the base; use the file ID.",
+                path.display()
+            );
+        }
+        out.extend_from_slice(&base[pos..start]);
+        out.extend_from_slice(content.as_bytes());
+        pos = end;
+    }
+    out.extend_from_slice(&base[pos..]);
+    Ok(out)
+}
+
+/// Write every planned file; on failure, restore the ones already written so
+/// the patch lands all-or-nothing.
+fn write_all(ws_path: &Path, planned: &[PlannedWrite]) -> Result<()> {
+    let mut written: Vec<(&Path, Option<Vec<u8>>)> = Vec::new();
+    for write in planned {
+        let previous = std::fs::read(ws_path.join(&write.path)).ok();
+        if let Err(e) = write_resolved_content(ws_path, &write.path, &write.conflict, &write.bytes)
+        {
+            for (done, previous) in written.iter().rev() {
+                let full = ws_path.join(done);
+                let _ = previous.as_ref().map_or_else(
+                    || std::fs::remove_file(&full),
+                    |bytes| std::fs::write(&full, bytes),
+                );
+            }
+            return Err(e.context(format!(
+                "Failed to write {} ({}); restored the files already written",
+                write.id,
+                write.path.display()
+            )));
+        }
+        written.push((&write.path, previous));
+    }
+    Ok(())
+}
+
+/// Record one `ConflictResolved` op per path in the workspace history.
+fn record_resolved_ops(root: &Path, workspace: &str, paths: &[PathBuf], sha: &str) -> Result<()> {
+    let ws_id = WorkspaceId::new(workspace)
+        .map_err(|e| anyhow::anyhow!("invalid workspace name '{workspace}': {e}"))?;
+    let resolution_commit = GitOid::new(sha).map_err(|e| anyhow::anyhow!("{e}"))?;
+    let backend = get_backend()?;
+    let status = backend.status(&ws_id).map_err(|e| anyhow::anyhow!("{e}"))?;
+    let mut head = ensure_workspace_oplog_head(root, &ws_id, &status.base_epoch.to_epoch_id())?;
+    for path in paths {
+        let op = Operation {
+            parent_ids: vec![head.clone()],
+            workspace_id: ws_id.clone(),
+            timestamp: super::now_timestamp_iso8601(),
+            payload: OpPayload::ConflictResolved {
+                path: path.display().to_string(),
+                resolution_commit: resolution_commit.clone(),
+            },
+        };
+        head = append_operation_with_runtime_checkpoint(root, &ws_id, &op, Some(&head))
+            .context("Failed to append conflict-resolved operation")?;
+    }
+    Ok(())
+}
+
+fn ensure_workspace_oplog_head(
+    root: &Path,
+    ws_id: &WorkspaceId,
+    base_epoch: &EpochId,
+) -> Result<GitOid> {
+    if let Some(head) = read_head(root, ws_id).context("Failed to read workspace op log head")? {
+        return Ok(head);
+    }
+
+    let create_op = Operation {
+        parent_ids: vec![],
+        workspace_id: ws_id.clone(),
+        timestamp: super::now_timestamp_iso8601(),
+        payload: OpPayload::Create {
+            epoch: base_epoch.clone(),
+        },
+    };
+
+    append_operation_with_runtime_checkpoint(root, ws_id, &create_op, None)
+        .context("Failed to bootstrap workspace op log for resolve")
+}
+
+#[cfg(test)]
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+mod tests {
+    use super::*;
+    use maw_core::model::conflict::ConflictSide;
+    use maw_core::model::ordering::OrderingKey;
+    use maw_core::model::patch::FileId;
+
+    fn epoch() -> EpochId {
+        EpochId::new(&"e".repeat(40)).expect("operation should succeed")
+    }
+
+    fn side(ws: &str, c: char) -> ConflictSide {
+        let key = OrderingKey::new(
+            epoch(),
+            WorkspaceId::new(ws).expect("operation should succeed"),
+            1,
+            1_700_000_000_000,
+        );
+        ConflictSide::new(
+            ws.to_owned(),
+            GitOid::new(&c.to_string().repeat(40)).expect("operation should succeed"),
+            key,
+        )
+    }
+
+    /// A two-atom content conflict on lines 2..3 and 4..5 of `base`.
+    fn two_atom_conflict() -> Conflict {
+        Conflict::Content {
+            path: PathBuf::from("f.txt"),
+            file_id: FileId::new(1),
+            base: None,
+            sides: vec![side("alice", 'a'), side("bob", 'b')],
+            atoms: vec![
+                ConflictAtom::line_overlap(2, 3, vec![], "x"),
+                ConflictAtom::line_overlap(4, 5, vec![], "y"),
+            ],
+        }
+    }
+
+    fn entry(id: &str, content: &str) -> PatchEntry {
+        PatchEntry {
+            id: id.to_owned(),
+            region: None,
+            content: content.to_owned(),
+        }
+    }
+
+    #[test]
+    fn splice_atoms_replaces_base_regions() {
+        let conflict = two_atom_conflict();
+        let contents = ConflictContents {
+            base: Some(b"a\nx = 1\nb\ny = 1\nc\n".to_vec()),
+            sides: vec![],
+        };
+        let chosen = BTreeMap::from([(0, "x = 4\n"), (1, "y = 4\n")]);
+        let out = splice_atoms("cf-test", Path::new("f.txt"), &conflict, &contents, &chosen)
+            .expect("operation should succeed");
+        assert_eq!(out, b"a\nx = 4\nb\ny = 4\nc\n");
+
+        let partial = BTreeMap::from([(0, "x = 4\n")]);
+        let err = splice_atoms(
+            "cf-test",
+            Path::new("f.txt"),
+            &conflict,
+            &contents,
+            &partial,
+        )
+        .expect_err("uncovered atom should be rejected");
+        assert!(err.to_string().contains("cf-test.1"), "{err}");
+    }
+
+    #[test]
+    fn group_entries_rejects_stale_ids_and_regions() {
+        let path = PathBuf::from("f.txt");
+        let conflict = two_atom_conflict();
+        let id = conflict_id("f.txt");
+        let by_id = BTreeMap::from([(id.clone(), (&path, &conflict))]);
+
+        let err = group_entries(&[entry("cf-zzzz", "x")], &by_id, "ws")
+            .err()
+            .expect("unknown id should be rejected");
+        assert!(err.to_string().contains("stale conflict ID"), "{err}");
+
+        let err = group_entries(&[entry(&format!("{id}.2"), "x")], &by_id, "ws")
+            .err()
+            .expect("out-of-range atom should be rejected");
+        assert!(err.to_string().contains("stale atom ID"), "{err}");
+
+        let mut moved = entry(&format!("{id}.0"), "x");
+        moved.region = Some(Region::lines(7, 8));
+        let err = group_entries(&[moved], &by_id, "ws")
+            .err()
+            .expect("region mismatch should be rejected");
+        assert!(err.to_string().contains("Stale atom"), "{err}");
+
+        let err = group_entries(
+            &[entry(&id, "whole"), entry(&format!("{id}.0"), "x")],
+            &by_id,
+            "ws",
+        )
+        .err()
+        .expect("mixing whole-file and atom entries should be rejected");
+        assert!(err.to_string().contains("both a whole-file"), "{err}");
+
+        let mut current = entry(&format!("{id}.0"), "x");
+        current.region = Some(Region::lines(2, 3));
+        let entries = [current, entry(&format!("{id}.1"), "y")];
+        let patches = group_entries(&entries, &by_id, "ws").expect("operation should succeed");
+        assert!(matches!(&patches[&id], PathPatch::Atoms(atoms) if atoms.len() == 2));
+    }
+}
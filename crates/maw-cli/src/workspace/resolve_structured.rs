@@ -41,6 +41,8 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{Result, bail};
 
+use maw::merge::binary;
+use maw::merge::rerere::ResolutionMemory;
 use maw_core::config::ManifoldConfig;
 use maw_core::merge::materialize::looks_text;
 use maw_core::merge::types::ConflictTree;
@@ -136,9 +138,16 @@ fn write_conflict_tree_sidecar(root: &Path, ws_name: &str, tree: &ConflictTree)
 /// and JSON output but driven by `ConflictTree.conflicts`.
 ///
 /// Only paths in `filter_paths` (if non-empty) are shown; otherwise all.
+/// Binary and LFS-pointer sides are described (size, sniffed type, image
+/// dimensions, LFS object) from the workspace repo at `ws_path`.
+#[expect(
+    clippy::too_many_lines,
+    reason = "text and JSON renderings of the same listing share the entry/binary gathering"
+)]
 pub fn list_conflicts(
     tree: &ConflictTree,
     workspace: &str,
+    ws_path: &Path,
     filter_paths: &[String],
     format: OutputFormat,
 ) -> Result<()> {
@@ -155,6 +164,14 @@ pub fn list_conflicts(
         .filter(|(p, _)| filter.as_ref().is_none_or(|f| f.contains(*p)))
         .collect();
 
+    let repo = git::GixRepo::open(ws_path).ok();
+    let lfs_store = super::merge::open_lfs_store(ws_path);
+    let binary_of = |conflict: &Conflict| {
+        repo.as_ref().map_or_else(Vec::new, |repo| {
+            binary_sides(repo, conflict, lfs_store.as_ref())
+        })
+    };
+
     if format == OutputFormat::Json {
         let items: Vec<String> = entries
             .iter()
@@ -170,13 +187,31 @@ pub fn list_conflicts(
                     Conflict::Content { atoms, .. } => atoms.len(),
                     _ => 0,
                 };
+                let id = super::merge::conflict_id(&path.to_string_lossy());
+                let atom_ids: Vec<String> = super::merge::atom_ids(&id, atom_count)
+                    .iter()
+                    .map(|a| format!("\"{a}\""))
+                    .collect();
+                let binary = binary_of(conflict);
+                let binary_field = if binary.is_empty() {
+                    String::new()
+                } else {
+                    let map: BTreeMap<String, binary::BinaryInfo> = binary.into_iter().collect();
+                    format!(
+                        r#","binary":{}"#,
+                        serde_json::to_string(&map).unwrap_or_else(|_| "{}".to_owned())
+                    )
+                };
                 format!(
-                    r#"{{"path":"{}","shape":"{}","sides":{},"atoms":{},"workspaces":[{}]}}"#,
+                    r#"{{"id":"{}","path":"{}","shape":"{}","sides":{},"atoms":{},"atom_ids":[{}],"workspaces":[{}]{}}}"#,
+                    id,
                     path.display(),
                     shape,
                     side_count,
                     atom_count,
+                    atom_ids.join(","),
                     workspaces.join(","),
+                    binary_field,
                 )
             })
             .collect();
@@ -197,13 +232,14 @@ pub fn list_conflicts(
     for (path, conflict) in &entries {
         let shape = conflict.variant_name();
         let sides_desc = conflict.workspaces().join(", ");
+        let id = super::merge::conflict_id(&path.to_string_lossy());
         match conflict {
             Conflict::Content { atoms, .. } => {
                 if atoms.is_empty() {
-                    println!("  {}  [{shape}] sides=[{sides_desc}]", path.display());
+                    println!("  {id}  {}  [{shape}] sides=[{sides_desc}]", path.display());
                 } else {
                     println!(
-                        "  {}  [{shape}] sides=[{sides_desc}] atoms={}",
+                        "  {id}  {}  [{shape}] sides=[{sides_desc}] atoms={}",
                         path.display(),
                         atoms.len()
                     );
@@ -216,7 +252,7 @@ pub fn list_conflicts(
                 ..
             } => {
                 println!(
-                    "  {}  [{shape}] sides=[{sides_desc}] (D/F clash: '{}' is a file on one \
+                    "  {id}  {}  [{shape}] sides=[{sides_desc}] (D/F clash: '{}' is a file on one \
                      side, a directory on the other)",
                     path.display(),
                     region.display()
@@ -229,15 +265,18 @@ pub fn list_conflicts(
                 ..
             } => {
                 println!(
-                    "  {}  [{shape}] sides=[{sides_desc}] (renamed to {})",
+                    "  {id}  {}  [{shape}] sides=[{sides_desc}] (renamed to {})",
                     path.display(),
                     new_path.display()
                 );
             }
             _ => {
-                println!("  {}  [{shape}] sides=[{sides_desc}]", path.display());
+                println!("  {id}  {}  [{shape}] sides=[{sides_desc}]", path.display());
             }
         }
+        for (side, info) in binary_of(conflict) {
+            println!("        {side}: {}", binary::summary(&info));
+        }
     }
 
     print_resolve_hint(workspace);
@@ -245,6 +284,27 @@ pub fn list_conflicts(
     Ok(())
 }
 
+/// Binary/LFS descriptions of a conflict's content sides, keyed by side name.
+/// Text sides (and sides whose blob cannot be read) are omitted.
+fn binary_sides(
+    repo: &dyn GitRepo,
+    conflict: &Conflict,
+    lfs_store: Option<&maw_lfs::Store>,
+) -> Vec<(String, binary::BinaryInfo)> {
+    let (Conflict::Content { sides, .. } | Conflict::AddAdd { sides, .. }) = conflict else {
+        return Vec::new();
+    };
+    sides
+        .iter()
+        .filter_map(|side| {
+            let oid: git::GitOid = side.content.as_str().parse().ok()?;
+            let bytes = repo.read_blob(oid).ok()?;
+            let info = binary::describe(&bytes, lfs_store)?;
+            Some((side.workspace.clone(), info))
+        })
+        .collect()
+}
+
 /// Print the "To resolve:" `--keep` option summary shared by the `--list`
 /// text output above.
 fn print_resolve_hint(workspace: &str) {
@@ -260,6 +320,9 @@ fn print_resolve_hint(workspace: &str) {
     println!(
         "  maw ws resolve {workspace} --keep union            # keep all sides (deduped, ours-first)"
     );
+    println!(
+        "  maw ws resolve {workspace} --apply <file.json>     # submit exact bytes per conflict/atom ID"
+    );
 }
 
 // ---------------------------------------------------------------------------
@@ -500,6 +563,12 @@ enum ResolveKind {
     /// Sidecar lacked `base_content` for the picked side — fell back to
     /// blob-replace and emitted a warning.
     LegacyBlobReplaceWarned,
+    /// LFS pointer conflict — the chosen side's object was written
+    /// wholesale from the local LFS store (no 3-way merge of pointers).
+    LfsObject,
+    /// LFS pointer conflict whose chosen object is not in the local store —
+    /// the pointer itself was written so the commit still records the side.
+    LfsPointer,
 }
 
 // ---------------------------------------------------------------------------
@@ -583,6 +652,101 @@ fn effective_base_oid<'a>(
     side.base_content.as_ref().or(conflict_base)
 }
 
+/// Resolve a conflict whose sides are Git LFS pointers.
+///
+/// Pointer text must never go through a line merge — a 3-way merge of two
+/// pointers is a pointer to nothing. The chosen side is taken wholesale and
+/// smudged from the local LFS store when its object is present; otherwise
+/// the pointer itself is written (the commit still records the right
+/// object). Only the chosen side's object is read — the others are never
+/// fetched. `both`/`union` cannot combine binary objects and are skipped.
+///
+/// Returns `None` when no side is an LFS pointer.
+fn apply_lfs_decision(
+    repo: &dyn GitRepo,
+    conflict: &Conflict,
+    target: &str,
+    rel_path: &Path,
+    lfs_store: Option<&maw_lfs::Store>,
+) -> Result<Option<PathOutcome>> {
+    let (Conflict::Content { sides, .. } | Conflict::AddAdd { sides, .. }) = conflict else {
+        return Ok(None);
+    };
+    let mut pointers = Vec::with_capacity(sides.len());
+    for side in sides {
+        let oid: git::GitOid = side
+            .content
+            .as_str()
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid blob oid {}: {e}", side.content))?;
+        let bytes = repo
+            .read_blob(oid)
+            .map_err(|e| anyhow::anyhow!("read_blob({}) failed: {e}", side.content))?;
+        pointers.push((binary::lfs_pointer(&bytes), bytes));
+    }
+    if pointers.iter().all(|(pointer, _)| pointer.is_none()) {
+        return Ok(None);
+    }
+    if target == "both" || target == "union" {
+        return Ok(Some(PathOutcome::Skipped(format!(
+            "LFS objects cannot be combined with --keep {target}; pick one side"
+        ))));
+    }
+
+    let SideMatch::One(chosen) = match_sides(sides, target) else {
+        // Ambiguous / unknown side — let the regular path report it.
+        return Ok(None);
+    };
+    let index = sides
+        .iter()
+        .position(|side| std::ptr::eq(side, chosen))
+        .unwrap_or_default();
+    let (pointer, pointer_bytes) = pointers.swap_remove(index);
+    let mode = chosen.mode;
+    let Some(pointer) = pointer else {
+        // The chosen side is a real file (e.g. the other side moved it to
+        // LFS): take it as-is.
+        return Ok(Some(PathOutcome::Wrote {
+            bytes: pointer_bytes,
+            mode,
+            kind: ResolveKind::BlobReplace,
+            sanity_failure: None,
+        }));
+    };
+
+    let object = match lfs_store.map(|store| store.open_object(&pointer.oid)) {
+        Some(Ok(Some(mut reader))) => {
+            let mut bytes = Vec::new();
+            std::io::Read::read_to_end(&mut reader, &mut bytes)
+                .map_err(|e| anyhow::anyhow!("read LFS object {}: {e}", pointer.oid_hex()))?;
+            Some(bytes)
+        }
+        Some(Err(e)) => bail!("open LFS object {}: {e}", pointer.oid_hex()),
+        Some(Ok(None)) | None => None,
+    };
+    if let Some(bytes) = object {
+        return Ok(Some(PathOutcome::Wrote {
+            bytes,
+            mode,
+            kind: ResolveKind::LfsObject,
+            sanity_failure: None,
+        }));
+    }
+    eprintln!(
+        "warning: LFS object {} for {} is not in the local store; wrote the \
+         pointer instead. The commit records {target}'s object — fetch it to \
+         check out the real file.",
+        pointer.oid_hex(),
+        rel_path.display(),
+    );
+    Ok(Some(PathOutcome::Wrote {
+        bytes: pointer_bytes,
+        mode,
+        kind: ResolveKind::LfsPointer,
+        sanity_failure: None,
+    }))
+}
+
 /// Apply a resolution for a single `(path, conflict)` and produce the output.
 ///
 /// `sanity_cfg` is used to run the bn-c5ui post-merge sanity check on
@@ -601,7 +765,11 @@ fn apply_decision(
     rel_path: &Path,
     workspace: &str,
     sanity_cfg: PostMergeSanityConfig,
+    lfs_store: Option<&maw_lfs::Store>,
 ) -> Result<PathOutcome> {
+    if let Some(outcome) = apply_lfs_decision(repo, conflict, target, rel_path, lfs_store)? {
+        return Ok(outcome);
+    }
     if target == "both" || target == "union" {
         // bn-nmu7: `--keep union` is `--keep both` with per-hunk line dedup
         // and stable ordering (ours' hunk-relative order first, then
@@ -1339,6 +1507,191 @@ fn dedup_conflict_markers(
     out
 }
 
+/// Blob contents of a content or add/add conflict.
+pub struct ConflictContents {
+    /// Base content; `None` for add/add.
+    pub base: Option<Vec<u8>>,
+    /// Side contents, in sidecar order.
+    pub sides: Vec<Vec<u8>>,
+}
+
+/// Read the base and side blobs of a content or add/add conflict. `None`
+/// for other conflict kinds or when a blob cannot be read.
+pub fn conflict_contents(repo: &dyn GitRepo, conflict: &Conflict) -> Option<ConflictContents> {
+    let (base, sides) = match conflict {
+        Conflict::Content { base, sides, .. } => (base.as_ref(), sides),
+        Conflict::AddAdd { sides, .. } => (None, sides),
+        Conflict::ModifyDelete { .. } | Conflict::DivergentRename { .. } => return None,
+    };
+    let read = |oid: &GitOid| -> Option<Vec<u8>> {
+        let oid: git::GitOid = oid.as_str().parse().ok()?;
+        repo.read_blob(oid).ok()
+    };
+    let base = match base {
+        Some(oid) => Some(read(oid)?),
+        None => None,
+    };
+    let sides = sides
+        .iter()
+        .map(|side| read(&side.content))
+        .collect::<Option<Vec<_>>>()?;
+    Some(ConflictContents { base, sides })
+}
+
+/// Remember how a content or add/add conflict was resolved so the merge
+/// engine and sync rebase can replay it. Failures only warn: the resolution
+/// itself already succeeded.
+pub(super) fn record_resolution(
+    repo: &dyn GitRepo,
+    memory: &ResolutionMemory,
+    conflict: &Conflict,
+    rel: &Path,
+    resolved: &[u8],
+) {
+    let Some(ConflictContents { base, sides }) = conflict_contents(repo, conflict) else {
+        return;
+    };
+    let side_refs: Vec<&[u8]> = sides.iter().map(Vec::as_slice).collect();
+    if let Err(e) = memory.record(rel, base.as_deref(), &side_refs, resolved) {
+        eprintln!(
+            "warning: could not record resolution of {}: {e}",
+            rel.display()
+        );
+    }
+}
+
+/// Result of [`resolve_with_content`].
+#[derive(Debug)]
+pub struct ContentResolution {
+    /// Structured conflicts left in the workspace.
+    pub conflicts_remaining: usize,
+    /// Commit created once the last conflict was resolved.
+    pub auto_committed: Option<String>,
+    /// Post-merge sanity failure of the written content; suppresses the
+    /// auto-commit.
+    pub sanity_failure: Option<SanityFailure>,
+}
+
+/// Resolve `rel` with caller-supplied `content` (an interactive or scripted
+/// resolution) through the same write path as `--keep`: post-merge sanity
+/// check, worktree write honoring the side's mode, resolution memory,
+/// sidecar update, and auto-commit once no conflict remains.
+///
+/// Only content and add/add conflicts accept free-form content; the other
+/// kinds are resolved with `--keep`.
+pub fn resolve_with_content(
+    root: &Path,
+    workspace: &str,
+    ws_path: &Path,
+    rel: &Path,
+    content: &[u8],
+) -> Result<ContentResolution> {
+    let Some(mut tree) = read_conflict_tree_sidecar(root, workspace) else {
+        bail!("Workspace '{workspace}' has no structured conflicts (conflict-tree.json).");
+    };
+    let Some(conflict) = tree.conflicts.get(rel).cloned() else {
+        bail!(
+            "'{}' is not conflicted in '{workspace}'.\n  Check: maw ws resolve {workspace} --list",
+            rel.display()
+        );
+    };
+    if !matches!(conflict, Conflict::Content { .. } | Conflict::AddAdd { .. }) {
+        bail!(
+            "{} conflict at '{}' cannot take edited content.\n  Resolve with: maw ws resolve {workspace} --keep {}=<side>",
+            conflict.variant_name(),
+            rel.display(),
+            rel.display()
+        );
+    }
+
+    let repo = git::GixRepo::open(ws_path)
+        .map_err(|e| anyhow::anyhow!("Failed to open git repo at {}: {e}", ws_path.display()))?;
+    let manifold_config = ManifoldConfig::load(
+        &maw_core::model::layout::LayoutFlavor::detect_with_env(root).bootstrap_config_path(root),
+    )
+    .unwrap_or_default();
+    let sanity_cfg = PostMergeSanityConfig::from_merge(&manifold_config.merge);
+
+    let sanity_failure = match conflict_contents(&repo, &conflict) {
+        Some(ConflictContents { base, sides }) if sides.len() >= 2 => run_post_merge_sanity(
+            rel,
+            base.as_deref().unwrap_or_default(),
+            &sides[0],
+            &sides[1],
+            content,
+            sanity_cfg,
+        )
+        .err(),
+        _ => None,
+    };
+    if sanity_failure.is_none() {
+        record_resolution(
+            &repo,
+            &ResolutionMemory::for_repo(root),
+            &conflict,
+            rel,
+            content,
+        );
+    }
+    write_resolved_content(ws_path, rel, &conflict, content)?;
+
+    tree.conflicts.remove(rel);
+    persist_remaining_conflicts(root, workspace, &tree)?;
+
+    let auto_committed = if tree.conflicts.is_empty() && sanity_failure.is_none() {
+        auto_commit_resolution(ws_path, workspace, &[rel.to_path_buf()]).unwrap_or_else(|e| {
+            tracing::warn!("auto-commit after resolve failed in '{workspace}': {e}");
+            None
+        })
+    } else {
+        None
+    };
+    Ok(ContentResolution {
+        conflicts_remaining: tree.conflicts.len(),
+        auto_committed,
+        sanity_failure,
+    })
+}
+
+/// Write caller-supplied resolution bytes for `conflict` to the worktree,
+/// honoring the sides' symlink / executable mode.
+pub(super) fn write_resolved_content(
+    ws_path: &Path,
+    rel: &Path,
+    conflict: &Conflict,
+    content: &[u8],
+) -> Result<()> {
+    apply_outcome(
+        ws_path,
+        rel,
+        PathOutcome::Wrote {
+            bytes: content.to_vec(),
+            mode: any_side_mode(conflict),
+            kind: ResolveKind::BlobReplace,
+            sanity_failure: None,
+        },
+    )
+    .map(|_| ())
+}
+
+/// Persist the remaining conflicts; once none are left, also sweep the
+/// legacy sidecar so later `find_conflicted_files` runs don't see stale
+/// state.
+pub(super) fn persist_remaining_conflicts(
+    root: &Path,
+    workspace: &str,
+    tree: &ConflictTree,
+) -> Result<()> {
+    write_conflict_tree_sidecar(root, workspace, tree)?;
+    if tree.conflicts.is_empty() {
+        let legacy = legacy_sidecar_path(root, workspace);
+        if legacy.exists() {
+            let _ = std::fs::remove_file(&legacy);
+        }
+    }
+    Ok(())
+}
+
 /// Apply `PathOutcome` to the worktree at `ws_path.join(rel)`.
 ///
 /// Returns `Ok((true, kind, sanity_failure))` when the worktree was updated,
@@ -1621,7 +1974,7 @@ pub fn run_structured(
     mut tree: ConflictTree,
 ) -> Result<bool> {
     if list {
-        list_conflicts(&tree, workspace, paths, format)?;
+        list_conflicts(&tree, workspace, ws_path, paths, format)?;
         return Ok(true);
     }
 
@@ -1668,6 +2021,8 @@ pub fn run_structured(
     )
     .unwrap_or_default();
     let sanity_cfg = PostMergeSanityConfig::from_merge(&manifold_config.merge);
+    let memory = ResolutionMemory::for_repo(root);
+    let lfs_store = super::merge::open_lfs_store(ws_path);
 
     // Determine the set of paths to process.
     let target_paths: Vec<PathBuf> = if !file_sides.is_empty() && all_side.is_none() {
@@ -1697,6 +2052,9 @@ pub fn run_structured(
     let mut three_way_union = Vec::<PathBuf>::new();
     // bn-nmu7: track paths resolved via per-hunk union merge with line dedup.
     let mut three_way_union_dedup = Vec::<PathBuf>::new();
+    // LFS pointer conflicts resolved by taking one side's object wholesale.
+    let mut lfs_objects = Vec::<PathBuf>::new();
+    let mut lfs_pointers = Vec::<PathBuf>::new();
     let mut skipped = Vec::<(PathBuf, String)>::new();
     // bn-c5ui: paths whose driver-produced output failed the post-merge sanity
     // check. The file is still written but auto-commit is suppressed for the
@@ -1729,7 +2087,15 @@ pub fn run_structured(
             } => Some(region.clone()),
             _ => None,
         };
-        match apply_decision(repo_dyn, &conflict, &target, rel, workspace, sanity_cfg) {
+        match apply_decision(
+            repo_dyn,
+            &conflict,
+            &target,
+            rel,
+            workspace,
+            sanity_cfg,
+            lfs_store.as_ref(),
+        ) {
             Ok(outcome) => {
                 // A Deleted outcome on a D/F conflict means the user kept the
                 // epoch side — schedule the epoch region restore.
@@ -1738,6 +2104,18 @@ pub fn run_structured(
                 {
                     df_regions_to_restore.insert(region);
                 }
+                // LFS objects are not remembered: they can be large and the
+                // pick is a whole-file side choice anyway.
+                if let PathOutcome::Wrote {
+                    bytes,
+                    kind,
+                    sanity_failure: None,
+                    ..
+                } = &outcome
+                    && !matches!(kind, ResolveKind::LfsObject | ResolveKind::LfsPointer)
+                {
+                    record_resolution(repo_dyn, &memory, &conflict, rel, bytes);
+                }
                 match apply_outcome(ws_path, rel, outcome)? {
                     (true, kind, maybe_failure) => {
                         tree.conflicts.remove(rel);
@@ -1755,6 +2133,8 @@ pub fn run_structured(
                             ResolveKind::ThreeWayUnionDedup => {
                                 three_way_union_dedup.push(rel.clone());
                             }
+                            ResolveKind::LfsObject => lfs_objects.push(rel.clone()),
+                            ResolveKind::LfsPointer => lfs_pointers.push(rel.clone()),
                             ResolveKind::BlobReplace | ResolveKind::LegacyBlobReplaceWarned => {}
                         }
                     }
@@ -1809,16 +2189,7 @@ pub fn run_structured(
     }
 
     // Persist updated sidecar (or delete if tree is fully empty).
-    write_conflict_tree_sidecar(root, workspace, &tree)?;
-
-    // If the sidecar now has no more conflicts, also sweep the legacy
-    // sidecar so later `find_conflicted_files` runs don't see stale state.
-    if tree.conflicts.is_empty() {
-        let legacy = legacy_sidecar_path(root, workspace);
-        if legacy.exists() {
-            let _ = std::fs::remove_file(&legacy);
-        }
-    }
+    persist_remaining_conflicts(root, workspace, &tree)?;
 
     // bn-c5ui: emit loud warnings for any sanity-flagged paths BEFORE the
     // auto-commit decision so agents and users see them on every non-JSON
@@ -1936,6 +2307,16 @@ pub fn run_structured(
                     "  resolved: {} (kept both sides in conflicted hunk(s), deduped identical lines; preserved cleanly-merged changes from both sides)",
                     p.display()
                 );
+            } else if lfs_objects.contains(p) {
+                println!(
+                    "  resolved: {} (LFS: took the chosen side's object from the local store)",
+                    p.display()
+                );
+            } else if lfs_pointers.contains(p) {
+                println!(
+                    "  resolved: {} (LFS: object not fetched; wrote the chosen side's pointer)",
+                    p.display()
+                );
             } else {
                 println!("  resolved: {}", p.display());
             }
@@ -2057,7 +2438,7 @@ pub fn run_structured(
 // codepath. Migrating requires either porting hook/signing config plumbing
 // into maw-git or accepting that resolve auto-commits skip user hooks —
 // neither is in scope for bn-15wt.
-fn auto_commit_resolution(
+pub(super) fn auto_commit_resolution(
     ws_path: &Path,
     workspace: &str,
     resolved: &[PathBuf],
@@ -2100,7 +2481,7 @@ fn auto_commit_resolution(
         format!("resolve: {} (bn-gjm8 auto-commit)", resolved[0].display())
     } else {
         format!(
-            "resolve: apply structured --keep decisions for {} path(s) in '{workspace}' (bn-gjm8 auto-commit)",
+            "resolve: apply structured resolutions for {} path(s) in '{workspace}' (bn-gjm8 auto-commit)",
             resolved.len()
         )
     };
@@ -2762,6 +3143,37 @@ mod tests {
         assert!(body.contains("EPOCH"));
     }
 
+    #[test]
+    fn resolve_with_content_writes_file_and_updates_sidecar() {
+        let (_td, root, ws_path, repo) = setup_ws_repo("ws-edit");
+        let (rel_a, conflict_a) =
+            make_content_conflict("a.txt", b"EPOCH A\n", b"WS A\n", "ws-edit", &repo);
+        let (rel_b, conflict_b) =
+            make_content_conflict("b.txt", b"EPOCH B\n", b"WS B\n", "ws-edit", &repo);
+        let mut tree = ConflictTree::new(epoch());
+        tree.conflicts.insert(rel_a.clone(), conflict_a);
+        tree.conflicts.insert(rel_b.clone(), conflict_b);
+        write_conflict_tree_sidecar(&root, "ws-edit", &tree).expect("operation should succeed");
+
+        let outcome = resolve_with_content(&root, "ws-edit", &ws_path, &rel_a, b"EPOCH A\nWS A\n")
+            .expect("operation should succeed");
+
+        assert_eq!(outcome.conflicts_remaining, 1);
+        assert!(outcome.auto_committed.is_none());
+        assert_eq!(
+            std::fs::read(ws_path.join(&rel_a)).expect("operation should succeed"),
+            b"EPOCH A\nWS A\n"
+        );
+        let remaining =
+            read_conflict_tree_sidecar(&root, "ws-edit").expect("sidecar should remain");
+        assert!(!remaining.conflicts.contains_key(&rel_a));
+        assert!(remaining.conflicts.contains_key(&rel_b));
+
+        let err = resolve_with_content(&root, "ws-edit", &ws_path, &rel_a, b"again\n")
+            .expect_err("resolved path is no longer conflicted");
+        assert!(err.to_string().contains("is not conflicted"));
+    }
+
     /// When the resolve doesn't fully clear the tree, nothing gets
     /// auto-committed (partial progress is left for the user to review).
     #[test]
@@ -3541,6 +3953,132 @@ mod tests {
         );
     }
 
+    // -----------------------------------------------------------------------
+    // LFS pointer conflicts: pick a side wholesale, never 3-way merge pointers
+    // -----------------------------------------------------------------------
+
+    /// An LFS pointer to an object that is not in any store.
+    fn missing_pointer(byte: u8) -> Vec<u8> {
+        maw_lfs::Pointer {
+            oid: [byte; 32],
+            size: 1234,
+            extensions: vec![],
+        }
+        .write()
+    }
+
+    fn lfs_conflict_tree(
+        repo: &maw_git::GixRepo,
+        ws_name: &str,
+        epoch_pointer: &[u8],
+        ws_pointer: &[u8],
+    ) -> (PathBuf, ConflictTree) {
+        let (rel, conflict) = make_content_conflict_with_base(
+            "art/logo.png",
+            &missing_pointer(1),
+            epoch_pointer,
+            ws_pointer,
+            ws_name,
+            repo,
+        );
+        let mut tree = ConflictTree::new(epoch());
+        tree.conflicts.insert(rel.clone(), conflict);
+        (rel, tree)
+    }
+
+    #[test]
+    fn resolve_keep_ws_lfs_pointer_writes_chosen_object_from_store() {
+        let (_td, root, ws_path, repo) = setup_ws_repo("ws-lfs");
+        seed_initial_commit(&ws_path);
+        let store = maw_lfs::Store::open(repo.common_dir()).expect("open store");
+        let (ws_pointer, _) = store
+            .insert_from_reader(&b"\x89PNG\r\n\x1a\nws-image"[..])
+            .expect("insert object");
+        // The epoch's object is deliberately absent: picking the workspace
+        // side must not need it.
+        let (rel, tree) =
+            lfs_conflict_tree(&repo, "ws-lfs", &missing_pointer(2), &ws_pointer.write());
+        std::fs::create_dir_all(ws_path.join("art")).expect("mkdir");
+        std::fs::write(ws_path.join(&rel), b"placeholder\n").expect("write placeholder");
+
+        run_structured(
+            &root,
+            "ws-lfs",
+            &ws_path,
+            &[],
+            &["ws-lfs".into()],
+            false,
+            OutputFormat::Text,
+            tree,
+        )
+        .expect("LFS side pick should succeed");
+
+        let after = std::fs::read(ws_path.join(&rel)).expect("read resolved file");
+        assert_eq!(after, b"\x89PNG\r\n\x1a\nws-image");
+    }
+
+    #[test]
+    fn resolve_keep_epoch_lfs_pointer_without_object_writes_pointer() {
+        let (_td, root, ws_path, repo) = setup_ws_repo("ws-lfs-missing");
+        seed_initial_commit(&ws_path);
+        let epoch_pointer = missing_pointer(2);
+        let (rel, tree) =
+            lfs_conflict_tree(&repo, "ws-lfs-missing", &epoch_pointer, &missing_pointer(3));
+        std::fs::create_dir_all(ws_path.join("art")).expect("mkdir");
+        std::fs::write(ws_path.join(&rel), b"placeholder\n").expect("write placeholder");
+
+        run_structured(
+            &root,
+            "ws-lfs-missing",
+            &ws_path,
+            &[],
+            &[EPOCH_LABEL.into()],
+            false,
+            OutputFormat::Text,
+            tree,
+        )
+        .expect("LFS side pick should succeed");
+
+        // No merged pointer — exactly the epoch's pointer.
+        let after = std::fs::read(ws_path.join(&rel)).expect("read resolved file");
+        assert_eq!(after, epoch_pointer);
+    }
+
+    #[test]
+    fn resolve_keep_both_lfs_pointer_is_skipped() {
+        let (_td, root, ws_path, repo) = setup_ws_repo("ws-lfs-both");
+        seed_initial_commit(&ws_path);
+        let (rel, tree) = lfs_conflict_tree(
+            &repo,
+            "ws-lfs-both",
+            &missing_pointer(2),
+            &missing_pointer(3),
+        );
+        std::fs::create_dir_all(ws_path.join("art")).expect("mkdir");
+        std::fs::write(ws_path.join(&rel), b"placeholder\n").expect("write placeholder");
+
+        run_structured(
+            &root,
+            "ws-lfs-both",
+            &ws_path,
+            &[],
+            &["both".into()],
+            false,
+            OutputFormat::Text,
+            tree,
+        )
+        .expect("skipped paths are not an error");
+
+        let after = std::fs::read(ws_path.join(&rel)).expect("read file");
+        assert_eq!(
+            after, b"placeholder\n",
+            "LFS objects must not be concatenated"
+        );
+        let remaining =
+            read_conflict_tree_sidecar(&root, "ws-lfs-both").expect("sidecar still present");
+        assert!(remaining.conflicts.contains_key(&rel));
+    }
+
     // -----------------------------------------------------------------------
     // bn-c5ui — post-merge sanity check before auto-commit
     //
@@ -0,0 +1,183 @@
+use std::cell::RefCell;
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use serde::Serialize;
+
+use crate::format::OutputFormat;
+use maw::merge::collect::collect_snapshots;
+use maw::merge::schedule::{MergeSchedule, schedule};
+use maw_core::backend::WorkspaceBackend;
+use maw_core::model::types::WorkspaceId;
+
+use super::get_backend;
+use super::merge::{MergeOptions, merge};
+use super::weather::forecast_patch_sets;
+
+#[derive(Debug, Serialize)]
+struct ScheduleOutput {
+    batches: Vec<BatchOutput>,
+    predicted_conflicts: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct BatchOutput {
+    workspaces: Vec<String>,
+    conflicts_with_earlier: usize,
+    /// The batch's `maw ws merge --format json` document; absent for
+    /// `--dry-run` and for batches after the one that stopped the run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    merge: Option<serde_json::Value>,
+    /// Why the batch's merge failed, for the batch that stopped the run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl ScheduleOutput {
+    fn new(schedule: &MergeSchedule) -> Self {
+        Self {
+            batches: schedule
+                .batches
+                .iter()
+                .map(|batch| BatchOutput {
+                    workspaces: names(&batch.workspaces),
+                    conflicts_with_earlier: batch.conflicts_with_earlier,
+                    merge: None,
+                    error: None,
+                })
+                .collect(),
+            predicted_conflicts: schedule.predicted_conflicts(),
+        }
+    }
+}
+
+fn names(ids: &[WorkspaceId]) -> Vec<String> {
+    ids.iter().map(|ws| ws.as_str().to_owned()).collect()
+}
+
+/// Workspaces ready to merge: active (not stale), with changes, and neither
+/// the default workspace nor the merge target.
+pub fn ready_workspaces(root: &Path, exclude: &[&str]) -> Result<Vec<String>> {
+    let backend = get_backend()?;
+    let ids: Vec<WorkspaceId> = backend
+        .list()
+        .map_err(|e| anyhow::anyhow!("Failed to list workspaces: {e}"))?
+        .into_iter()
+        .filter(|info| info.state.is_active() && !exclude.contains(&info.id.as_str()))
+        .map(|info| info.id)
+        .collect();
+    let patch_sets = collect_snapshots(root, &backend, &ids)
+        .map_err(|e| anyhow::anyhow!("COLLECT failed: {e}"))?;
+    let mut ready: Vec<String> = patch_sets
+        .iter()
+        .filter(|ps| !ps.is_empty())
+        .map(|ps| ps.workspace_id.as_str().to_owned())
+        .collect();
+    ready.sort();
+    Ok(ready)
+}
+
+/// Merge `workspaces` in scheduled batches, stopping at the first batch
+/// whose merge fails (conflicts, validation, or a conflicted auto-rebase).
+///
+/// Each batch is a regular `maw ws merge` of its members with `opts`; with
+/// several batches the commit message gets a `[batch i/n]` suffix. With
+/// `--dry-run` only the schedule is printed. In JSON mode a single document
+/// is printed once the run ends: the schedule, with each merged batch's
+/// merge output, and the error of the batch that stopped the run.
+pub fn merge_scheduled(root: &Path, workspaces: &[String], opts: &MergeOptions<'_>) -> Result<()> {
+    let ids = workspaces
+        .iter()
+        .map(|ws| {
+            WorkspaceId::new(ws).map_err(|e| anyhow::anyhow!("invalid workspace name '{ws}': {e}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let backend = get_backend()?;
+    let patch_sets = collect_snapshots(root, &backend, &ids)
+        .map_err(|e| anyhow::anyhow!("COLLECT failed: {e}"))?;
+    let weather = forecast_patch_sets(root, &patch_sets)?;
+    let schedule = schedule(&weather);
+
+    let text_mode = opts.format != OutputFormat::Json;
+    let mut output = ScheduleOutput::new(&schedule);
+    if text_mode {
+        print_schedule_text(&schedule);
+    }
+    if opts.dry_run {
+        if !text_mode {
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        return Ok(());
+    }
+
+    let total = schedule.batches.len();
+    for (index, batch) in schedule.batches.iter().enumerate() {
+        let members = names(&batch.workspaces);
+        if text_mode {
+            println!();
+            println!("Batch {}/{total}: {}", index + 1, members.join(", "));
+        }
+        let message = opts.message.map(|message| {
+            if total > 1 {
+                format!("{message} [batch {}/{total}]", index + 1)
+            } else {
+                message.to_owned()
+            }
+        });
+        let json_sink = RefCell::new(Vec::new());
+        let batch_opts = MergeOptions {
+            message: message.as_deref(),
+            resolve: Vec::new(),
+            resolve_all: None,
+            json_sink: (!text_mode).then_some(&json_sink),
+            ..*opts
+        };
+        let result = merge(&members, &batch_opts);
+        output.batches[index].merge = json_sink.into_inner().pop();
+        if let Err(e) = result {
+            let remaining: Vec<String> = schedule.batches[index + 1..]
+                .iter()
+                .map(|b| names(&b.workspaces).join(", "))
+                .collect();
+            if !text_mode {
+                output.batches[index].error = Some(format!("{e:#}"));
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else if !remaining.is_empty() {
+                println!();
+                println!("Not merged (later batches):");
+                for batch in &remaining {
+                    println!("  {batch}");
+                }
+            }
+            bail!(
+                "Scheduled merge stopped at batch {}/{total} ({}): {e}",
+                index + 1,
+                members.join(", ")
+            );
+        }
+    }
+
+    if !text_mode {
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    }
+    Ok(())
+}
+
+fn print_schedule_text(schedule: &MergeSchedule) {
+    println!("Merge schedule ({} batch(es)):", schedule.batches.len());
+    for (index, batch) in schedule.batches.iter().enumerate() {
+        let note = if batch.conflicts_with_earlier == 0 {
+            String::new()
+        } else {
+            format!(
+                "  ({} predicted conflict(s) with earlier batches)",
+                batch.conflicts_with_earlier
+            )
+        };
+        println!(
+            "  {}. {}{note}",
+            index + 1,
+            names(&batch.workspaces).join(", ")
+        );
+    }
+}
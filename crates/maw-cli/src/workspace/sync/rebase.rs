@@ -42,11 +42,12 @@ use std::path::{Path, PathBuf};
 use anyhow::{Result, bail};
 use serde::{Deserialize, Serialize};
 
+use maw::merge::rerere::ResolutionMemory;
 use maw_core::config::ManifoldConfig;
 use maw_core::merge::apply::apply_unilateral_patchset;
 use maw_core::merge::diff_extract::diff_patchset;
 use maw_core::merge::materialize::{
-    looks_text, materialize, write_legacy_sidecar, write_structured_sidecar,
+    looks_text, materialize_with_style, write_legacy_sidecar, write_structured_sidecar,
 };
 use maw_core::merge::types::{ConflictTree, EntryMode, MaterializedEntry};
 use maw_core::model::conflict::{Conflict, ConflictSide};
@@ -621,6 +622,11 @@ pub(super) fn rebase_workspace_run(
     )
     .unwrap_or_default();
     let sanity_cfg = PostRebaseSanityConfig::from_merge(&manifold_config.merge);
+    let conflict_style = manifold_config.merge.conflict_style.into();
+    // Overlaps that conflict textually are replayed from recorded
+    // resolutions (`maw ws resolve`, merge RESOLVE) before becoming
+    // conflicts.
+    let memory = ResolutionMemory::for_repo(root);
     let mut sanity_flagged_steps = 0usize;
     let mut sanity_flagged_paths_total: Vec<PathBuf> = Vec::new();
 
@@ -675,6 +681,7 @@ pub(super) fn rebase_workspace_run(
         // rename pairs that were resolved into a pre-installed clean entry
         // at `to` — see `promote_overlaps_to_conflicts` for the rationale).
         let mut sanity_flagged_this_step: Vec<PathBuf> = Vec::new();
+        let mut from_history_this_step: Vec<PathBuf> = Vec::new();
         promote_overlaps_to_conflicts(
             repo_dyn,
             &mut state,
@@ -682,10 +689,20 @@ pub(super) fn rebase_workspace_run(
             &epoch_delta,
             ws_name,
             &base_epoch_id,
-            sanity_cfg,
+            OverlapResolution {
+                sanity_cfg,
+                memory: &memory,
+            },
             &mut sanity_flagged_this_step,
+            &mut from_history_this_step,
         )
         .map_err(|e| anyhow::anyhow!("{e} (while replaying {short_sha})"))?;
+        for path in &from_history_this_step {
+            say!(
+                "  {short_sha}: {} auto-resolved from history",
+                path.display()
+            );
+        }
         if !sanity_flagged_this_step.is_empty() {
             sanity_flagged_steps += 1;
             sanity_flagged_paths_total.extend(sanity_flagged_this_step);
@@ -756,8 +773,8 @@ pub(super) fn rebase_workspace_run(
         // `materialize` reads each conflict side's blob via `repo.read_blob`
         // (bn-324m), so we thread the `&dyn GitRepo` through here — same
         // handle that `write_blobs_and_build_tree` uses below to write the
-        // rendered marker blobs back.
-        let output = materialize(&state, repo_dyn)
+        // rendered marker blobs back. Markers follow `[merge] conflict_style`.
+        let output = materialize_with_style(&state, repo_dyn, conflict_style)
             .map_err(|e| anyhow::anyhow!("materialize failed after replaying {short_sha}: {e}"))?;
         let tree_oid = write_blobs_and_build_tree(repo_dyn, new_epoch_tree, output)
             .map_err(|e| anyhow::anyhow!("failed to build tree for {short_sha}: {e}"))?;
@@ -1308,8 +1325,9 @@ fn promote_overlaps_to_conflicts(
     epoch_delta: &EpochDelta,
     ws_name: &str,
     base_epoch_id: &EpochId,
-    sanity_cfg: PostRebaseSanityConfig,
+    resolution: OverlapResolution<'_>,
     sanity_flagged: &mut Vec<PathBuf>,
+    from_history: &mut Vec<PathBuf>,
 ) -> Result<()> {
     use maw_core::merge::types::ChangeKind;
 
@@ -1672,8 +1690,9 @@ fn promote_overlaps_to_conflicts(
                     tree.clean.get(&change.path).map(|e| e.mode),
                     change.mode,
                     ws_name,
-                    sanity_cfg,
+                    resolution,
                     sanity_flagged,
+                    from_history,
                 )? {
                     tree.conflicts.remove(&change.path);
                     tree.clean.insert(change.path.clone(), resolved);
@@ -1808,6 +1827,16 @@ fn promote_overlaps_to_conflicts(
     Ok(())
 }
 
+/// How a textual overlap between the epoch and a replayed commit may be
+/// resolved before it becomes a conflict.
+#[derive(Clone, Copy, Debug)]
+struct OverlapResolution<'a> {
+    /// Sanity checks applied to any merged result.
+    sanity_cfg: PostRebaseSanityConfig,
+    /// Recorded resolutions replayed when the text merge conflicts.
+    memory: &'a ResolutionMemory,
+}
+
 /// Configuration for the post-rebase sanity check (bn-2upt).
 ///
 /// Built from `MergeConfig` and passed through the rebase machinery so the
@@ -1896,9 +1925,11 @@ fn try_clean_three_way_overlap(
     epoch_mode: Option<EntryMode>,
     workspace_mode: Option<EntryMode>,
     ws_name: &str,
-    sanity_cfg: PostRebaseSanityConfig,
+    resolution: OverlapResolution<'_>,
     sanity_flagged: &mut Vec<PathBuf>,
+    from_history: &mut Vec<PathBuf>,
 ) -> Result<Option<MaterializedEntry>> {
+    let sanity_cfg = resolution.sanity_cfg;
     let Some(base_blob) = base_blob else {
         return Ok(None);
     };
@@ -1933,7 +1964,22 @@ fn try_clean_three_way_overlap(
             anyhow::anyhow!("three-way overlap merge failed for {}: {e}", path.display())
         })? {
             MergeResult::Clean(bytes) => bytes,
-            MergeResult::Conflict(_) => return Ok(None),
+            MergeResult::Conflict(_) => {
+                let Some(replay) = resolution
+                    .memory
+                    .replay(Some(&base), &[epoch.as_slice(), workspace.as_slice()])
+                else {
+                    return Ok(None);
+                };
+                tracing::info!(
+                    workspace = %ws_name,
+                    path = %path.display(),
+                    hunks = replay.hunks,
+                    "overlap conflict auto-resolved from history"
+                );
+                from_history.push(path.to_path_buf());
+                replay.content
+            }
         };
 
     // bn-2upt — defense-in-depth: even when `merge_text` reports clean,
@@ -0,0 +1,205 @@
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use serde::Serialize;
+
+use crate::format::OutputFormat;
+use maw::merge::collect::collect_snapshots;
+use maw::merge::weather::{Forecast, MergeWeather, conflict_matrix, forecast};
+use maw_core::backend::WorkspaceBackend;
+use maw_core::config::ManifoldConfig;
+use maw_core::merge::plan::PredictedConflict;
+use maw_core::merge::types::PatchSet;
+use maw_core::model::types::{EpochId, WorkspaceId};
+
+use super::merge::predicted_conflict;
+use super::{MawConfig, get_backend, repo_root};
+
+#[derive(Debug, Serialize)]
+struct WeatherOutput {
+    epoch: String,
+    workspaces: Vec<String>,
+    /// Workspaces left out because they are behind the current epoch.
+    skipped_stale: Vec<String>,
+    /// Conflict counts per pair, indexed like `workspaces`.
+    matrix: Vec<Vec<usize>>,
+    pairs: Vec<ForecastOutput>,
+    all: Option<ForecastOutput>,
+}
+
+#[derive(Debug, Serialize)]
+struct ForecastOutput {
+    workspaces: Vec<String>,
+    shared_paths: Vec<String>,
+    auto_resolved: usize,
+    conflicts: Vec<PredictedConflict>,
+}
+
+impl ForecastOutput {
+    fn new(forecast: &Forecast) -> Self {
+        Self {
+            workspaces: forecast
+                .workspaces
+                .iter()
+                .map(|ws| ws.as_str().to_owned())
+                .collect(),
+            shared_paths: forecast
+                .shared_paths
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect(),
+            auto_resolved: forecast.auto_resolutions.len(),
+            conflicts: forecast.conflicts.iter().map(predicted_conflict).collect(),
+        }
+    }
+}
+
+pub fn weather(format: OutputFormat) -> Result<()> {
+    let root = repo_root()?;
+    let maw_config = MawConfig::load(&root)?;
+    let backend = get_backend()?;
+
+    let mut ids: Vec<WorkspaceId> = Vec::new();
+    let mut skipped_stale = Vec::new();
+    for info in backend
+        .list()
+        .map_err(|e| anyhow::anyhow!("Failed to list workspaces: {e}"))?
+    {
+        if info.id.as_str() == maw_config.default_workspace() {
+            continue;
+        }
+        if info.state.is_active() {
+            ids.push(info.id);
+        } else if info.state.is_stale() {
+            skipped_stale.push(info.id.as_str().to_owned());
+        }
+    }
+    skipped_stale.sort();
+
+    let patch_sets = collect_snapshots(&root, &backend, &ids)
+        .map_err(|e| anyhow::anyhow!("COLLECT failed: {e}"))?;
+    let weather = forecast_patch_sets(&root, &patch_sets)?;
+
+    let output = build_output(&weather, skipped_stale);
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        OutputFormat::Text | OutputFormat::Pretty => {
+            print_weather_text(&output);
+        }
+    }
+
+    Ok(())
+}
+
+/// Forecast merges of collected workspace snapshots onto the current epoch.
+pub(super) fn forecast_patch_sets(root: &Path, patch_sets: &[PatchSet]) -> Result<MergeWeather> {
+    let Some(epoch_oid) = maw_core::refs::read_epoch_current(root)? else {
+        bail!("No current epoch (refs/manifold/epoch/current)\n  Fix: maw init");
+    };
+    let epoch = EpochId::new(epoch_oid.as_str())
+        .map_err(|e| anyhow::anyhow!("invalid epoch OID '{}': {e}", epoch_oid.as_str()))?;
+
+    let manifold_dir =
+        maw_core::model::layout::LayoutFlavor::detect_with_env(root).manifold_dir(root);
+    let manifold_config = ManifoldConfig::load(&manifold_dir.join("config.toml"))
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+    forecast(root, &epoch, patch_sets, &manifold_config.merge)
+        .map_err(|e| anyhow::anyhow!("forecast failed: {e}"))
+}
+
+fn build_output(weather: &MergeWeather, skipped_stale: Vec<String>) -> WeatherOutput {
+    WeatherOutput {
+        epoch: weather.epoch.as_str().to_owned(),
+        workspaces: weather
+            .workspaces
+            .iter()
+            .map(|ws| ws.as_str().to_owned())
+            .collect(),
+        skipped_stale,
+        matrix: conflict_matrix(weather),
+        pairs: weather.pairs.iter().map(ForecastOutput::new).collect(),
+        all: weather.all.as_ref().map(ForecastOutput::new),
+    }
+}
+
+fn print_weather_text(output: &WeatherOutput) {
+    let short_epoch = &output.epoch[..output.epoch.len().min(12)];
+    println!(
+        "Merge weather for {} workspace(s) at epoch {short_epoch}",
+        output.workspaces.len()
+    );
+    if !output.skipped_stale.is_empty() {
+        println!(
+            "Skipped (stale, run maw ws sync): {}",
+            output.skipped_stale.join(", ")
+        );
+    }
+
+    if output.workspaces.len() < 2 {
+        println!("  (need at least two active workspaces to forecast)");
+        return;
+    }
+
+    // Conflict-count matrix.
+    let width = output
+        .workspaces
+        .iter()
+        .map(String::len)
+        .max()
+        .unwrap_or(0)
+        .max(3);
+    println!();
+    print!("{:width$}", "");
+    for ws in &output.workspaces {
+        print!("  {ws:>width$}");
+    }
+    println!();
+    for (ws, row) in output.workspaces.iter().zip(&output.matrix) {
+        print!("{ws:width$}");
+        for (j, count) in row.iter().enumerate() {
+            let cell = if output.workspaces[j] == *ws {
+                "-".to_owned()
+            } else {
+                count.to_string()
+            };
+            print!("  {cell:>width$}");
+        }
+        println!();
+    }
+
+    let stormy: Vec<&ForecastOutput> = output
+        .pairs
+        .iter()
+        .filter(|pair| !pair.conflicts.is_empty())
+        .collect();
+    println!();
+    if stormy.is_empty() {
+        println!("Clear: no pair of workspaces is predicted to conflict.");
+    } else {
+        println!("Predicted conflicts:");
+        for pair in &stormy {
+            println!("  {}:", pair.workspaces.join(" + "));
+            for conflict in &pair.conflicts {
+                println!("    C {} — {}", conflict.path.display(), conflict.kind);
+                for region in &conflict.regions {
+                    println!("        {region}");
+                }
+            }
+        }
+    }
+
+    if let Some(all) = &output.all {
+        println!(
+            "All together: {} conflict(s) across {} shared path(s), {} auto-resolved",
+            all.conflicts.len(),
+            all.shared_paths.len(),
+            all.auto_resolved
+        );
+    }
+
+    println!();
+    println!("Next: maw ws merge <workspace>... --check --format json");
+}
@@ -17,13 +17,16 @@
 //!
 //! Every workspace create is an O(repo-size) operation. For repos with fewer
 //! than 30k files this is acceptable; for larger repos prefer the `reflink`
-//! or `overlay` backend.
+//! or `overlay` backend, or create sparse workspaces
+//! ([`WorkspaceBackend::create_sparse`]), which extract only the selected
+//! paths.
 
 use std::collections::HashSet;
 use std::fmt;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
+use super::sparse::{SPARSE_FILE, SparseSpec};
 use super::{SnapshotResult, WorkspaceBackend, WorkspaceStatus};
 use crate::model::types::{EpochId, WorkspaceId, WorkspaceInfo, WorkspaceMode, WorkspaceState};
 
@@ -166,15 +169,85 @@ impl CopyBackend {
         Ok(())
     }
 
+    /// Shared implementation of [`WorkspaceBackend::create`] and
+    /// [`WorkspaceBackend::create_sparse`].
+    fn create_with(
+        &self,
+        name: &WorkspaceId,
+        epoch: &EpochId,
+        sparse: Option<&SparseSpec>,
+    ) -> Result<WorkspaceInfo, CopyBackendError> {
+        let ws_path = self.workspace_path(name);
+
+        // Idempotency: workspace with correct epoch already exists.
+        if ws_path.exists() {
+            if let Ok(existing_epoch) = Self::read_epoch_file(&ws_path, name.as_str())
+                && existing_epoch == *epoch
+            {
+                return Ok(WorkspaceInfo {
+                    id: name.clone(),
+                    path: ws_path,
+                    epoch: epoch.clone(),
+                    state: WorkspaceState::Active,
+                    mode: WorkspaceMode::default(),
+                    commits_ahead: 0,
+                });
+            }
+            // Partial or mismatched workspace — remove and recreate.
+            std::fs::remove_dir_all(&ws_path)?;
+        }
+
+        std::fs::create_dir_all(self.workspaces_dir())?;
+
+        // Extract the epoch tree into the workspace directory.
+        self.extract_epoch(epoch, &ws_path, sparse)?;
+
+        // Write the epoch marker (and the sparse spec).
+        Self::write_epoch_file(&ws_path, epoch)?;
+        if let Some(spec) = sparse {
+            spec.write(&ws_path)?;
+        }
+
+        Ok(WorkspaceInfo {
+            id: name.clone(),
+            path: ws_path,
+            epoch: epoch.clone(),
+            state: WorkspaceState::Active,
+            mode: WorkspaceMode::default(),
+            commits_ahead: 0,
+        })
+    }
+
     /// Extract the epoch's tree into `dest` using `git archive | tar -x`.
     ///
-    /// This creates a full copy of all tracked files at the epoch commit.
-    fn extract_epoch(&self, epoch: &EpochId, dest: &Path) -> Result<(), CopyBackendError> {
+    /// This creates a full copy of all tracked files at the epoch commit, or
+    /// only the paths inside `sparse` when given.
+    fn extract_epoch(
+        &self,
+        epoch: &EpochId,
+        dest: &Path,
+        sparse: Option<&SparseSpec>,
+    ) -> Result<(), CopyBackendError> {
         std::fs::create_dir_all(dest)?;
 
-        // Run `git archive <oid> | tar -x -C <dest>`
+        // A sparse extraction archives the outermost selected paths only.
+        let mut pathspecs = Vec::new();
+        if let Some(spec) = sparse {
+            let tracked = self.tracked_files_at_epoch(epoch);
+            pathspecs = spec
+                .roots(tracked.iter().map(Path::new))
+                .into_iter()
+                .map(PathBuf::into_os_string)
+                .collect();
+            if pathspecs.is_empty() {
+                return Ok(());
+            }
+        }
+
+        // Run `git archive <oid> [-- <paths>] | tar -x -C <dest>`
         let mut archive = Command::new("git")
-            .args(["archive", epoch.as_str()])
+            .args(["--literal-pathspecs", "archive", epoch.as_str(), "--"])
+            .args(&pathspecs)
             .current_dir(&self.root)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -225,42 +298,19 @@ impl WorkspaceBackend for CopyBackend {
     type Error = CopyBackendError;
 
     fn create(&self, name: &WorkspaceId, epoch: &EpochId) -> Result<WorkspaceInfo, Self::Error> {
-        let ws_path = self.workspace_path(name);
-
-        // Idempotency: workspace with correct epoch already exists.
-        if ws_path.exists() {
-            if let Ok(existing_epoch) = Self::read_epoch_file(&ws_path, name.as_str())
-                && existing_epoch == *epoch
-            {
-                return Ok(WorkspaceInfo {
-                    id: name.clone(),
-                    path: ws_path,
-                    epoch: epoch.clone(),
-                    state: WorkspaceState::Active,
-                    mode: WorkspaceMode::default(),
-                    commits_ahead: 0,
-                });
-            }
-            // Partial or mismatched workspace — remove and recreate.
-            std::fs::remove_dir_all(&ws_path)?;
-        }
-
-        std::fs::create_dir_all(self.workspaces_dir())?;
-
-        // Extract the epoch tree into the workspace directory.
-        self.extract_epoch(epoch, &ws_path)?;
-
-        // Write the epoch marker.
-        Self::write_epoch_file(&ws_path, epoch)?;
+        self.create_with(name, epoch, None)
+    }
 
-        Ok(WorkspaceInfo {
-            id: name.clone(),
-            path: ws_path,
-            epoch: epoch.clone(),
-            state: WorkspaceState::Active,
-            mode: WorkspaceMode::default(),
-            commits_ahead: 0,
-        })
+    /// Create a sparse workspace by extracting only the paths inside
+    /// `sparse`. The spec is recorded in [`SPARSE_FILE`] so snapshots treat
+    /// the missing paths as unchanged.
+    fn create_sparse(
+        &self,
+        name: &WorkspaceId,
+        epoch: &EpochId,
+        sparse: &SparseSpec,
+    ) -> Result<WorkspaceInfo, Self::Error> {
+        self.create_with(name, epoch, Some(sparse))
     }
 
     fn destroy(&self, name: &WorkspaceId) -> Result<(), Self::Error> {
@@ -361,7 +411,8 @@ impl WorkspaceBackend for CopyBackend {
         let tracked = self.tracked_files_at_epoch(&base_epoch);
         let workspace_files = Self::walk_workspace(&ws_path);
 
-        let is_excluded = |name: &str| name == EPOCH_FILE;
+        let is_excluded = |name: &str| name == EPOCH_FILE || name == SPARSE_FILE;
+        let sparse = SparseSpec::load(&ws_path);
 
         let mut added = Vec::new();
         let mut modified = Vec::new();
@@ -376,7 +427,10 @@ impl WorkspaceBackend for CopyBackend {
             }
             let abs = ws_path.join(rel);
             if !abs.exists() {
-                deleted.push(rel.to_path_buf());
+                // Paths a sparse workspace never materialized are unchanged.
+                if sparse.as_ref().is_none_or(|spec| spec.contains(rel)) {
+                    deleted.push(rel.to_path_buf());
+                }
             } else if self.file_differs_from_epoch(rel, &base_epoch) {
                 modified.push(rel.to_path_buf());
             }
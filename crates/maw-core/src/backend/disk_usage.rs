@@ -0,0 +1,244 @@
+//! Disk accounting for workspaces.
+//!
+//! A workspace's footprint depends on the backend that created it:
+//!
+//! - **git-worktree / copy**: every file is the workspace's own, so all of
+//!   it is exclusive.
+//! - **overlay**: the workspace's own bytes are the upper (writable) layer;
+//!   the epoch snapshot in the lower layer is shared with every workspace on
+//!   the same epoch.
+//! - **reflink**: files still identical to the epoch snapshot share their
+//!   blocks with it (when the filesystem really supports reflinks — see
+//!   [`PlatformCapabilities::reflink_supported`]); everything written since
+//!   creation is exclusive.
+//!
+//! Sizes are allocated blocks, not apparent lengths, so sparse files and
+//! small files count what they actually occupy. Hard links are counted once.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use super::platform::PlatformCapabilities;
+
+/// Disk usage of one workspace.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DiskUsage {
+    /// Bytes only this workspace occupies — what destroying it would free.
+    pub exclusive_bytes: u64,
+    /// Bytes shared with the epoch snapshot (and with other workspaces on
+    /// the same epoch).
+    pub shared_bytes: u64,
+}
+
+impl DiskUsage {
+    /// Exclusive and shared bytes together.
+    #[must_use]
+    pub const fn total_bytes(&self) -> u64 {
+        self.exclusive_bytes + self.shared_bytes
+    }
+}
+
+/// Allocated size of a file, from its metadata.
+#[cfg(unix)]
+fn allocated(meta: &std::fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt as _;
+    meta.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn allocated(meta: &std::fs::Metadata) -> u64 {
+    meta.len()
+}
+
+/// Identity of a file for hard-link deduplication.
+#[cfg(unix)]
+fn file_key(meta: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt as _;
+    (meta.nlink() > 1).then(|| (meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+const fn file_key(_meta: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Walk `dir` without following symlinks, calling `visit` with the path
+/// (relative to `root`) and metadata of every entry not under `skip`.
+fn walk(
+    root: &Path,
+    dir: &Path,
+    skip: &[PathBuf],
+    visit: &mut dyn FnMut(&Path, &std::fs::Metadata),
+) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if skip.contains(&path) {
+            continue;
+        }
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if let Ok(rel) = path.strip_prefix(root) {
+            visit(rel, &meta);
+        }
+        if meta.is_dir() {
+            walk(root, &path, skip, visit);
+        }
+    }
+}
+
+/// Allocated bytes under `dir`, skipping the absolute paths in `skip`.
+///
+/// Missing directories measure as zero.
+#[must_use]
+pub fn allocated_bytes(dir: &Path, skip: &[PathBuf]) -> u64 {
+    let mut seen = HashSet::new();
+    let mut total = 0;
+    walk(dir, dir, skip, &mut |_, meta| {
+        if file_key(meta).is_none_or(|key| seen.insert(key)) {
+            total += allocated(meta);
+        }
+    });
+    total
+}
+
+/// Usage of an overlay workspace: its upper layer is exclusive, the lower
+/// epoch snapshot is shared.
+#[must_use]
+pub fn overlay_usage(upper: &Path, lower: &Path) -> DiskUsage {
+    DiskUsage {
+        exclusive_bytes: allocated_bytes(upper, &[]),
+        shared_bytes: allocated_bytes(lower, &[]),
+    }
+}
+
+/// Usage of a reflink workspace cloned from `snapshot`.
+///
+/// The backend writes `marker` (the `.maw-epoch` file) right after cloning,
+/// so a regular file that is no newer than the marker and has the same size
+/// as its snapshot counterpart is still an untouched clone: its blocks count
+/// as shared. Everything else — edits, new files, build output — is
+/// exclusive. Only metadata is read.
+#[must_use]
+pub fn reflink_usage(workspace: &Path, snapshot: &Path, marker: &Path) -> DiskUsage {
+    let cloned_at = std::fs::metadata(marker).and_then(|m| m.modified()).ok();
+    let mut seen = HashSet::new();
+    let mut usage = DiskUsage::default();
+    walk(workspace, workspace, &[], &mut |rel, meta| {
+        if file_key(meta).is_some_and(|key| !seen.insert(key)) {
+            return;
+        }
+        let bytes = allocated(meta);
+        let untouched = meta.is_file()
+            && cloned_at.is_some_and(|at| meta.modified().is_ok_and(|m| m <= at))
+            && std::fs::symlink_metadata(snapshot.join(rel))
+                .is_ok_and(|orig| orig.is_file() && orig.len() == meta.len());
+        if untouched {
+            usage.shared_bytes += bytes;
+        } else {
+            usage.exclusive_bytes += bytes;
+        }
+    });
+    usage
+}
+
+/// Usage of a workspace whose backend shares nothing.
+#[must_use]
+pub fn exclusive_usage(workspace: &Path, skip: &[PathBuf]) -> DiskUsage {
+    DiskUsage {
+        exclusive_bytes: allocated_bytes(workspace, skip),
+        shared_bytes: 0,
+    }
+}
+
+/// Usage of a reflink workspace when the platform may not support reflinks.
+///
+/// Without real reflinks `cp --reflink=auto` falls back to a full copy, so
+/// nothing is shared.
+#[must_use]
+pub fn reflink_usage_for(
+    caps: &PlatformCapabilities,
+    workspace: &Path,
+    snapshot: &Path,
+    marker: &Path,
+) -> DiskUsage {
+    if caps.reflink_supported {
+        reflink_usage(workspace, snapshot, marker)
+    } else {
+        exclusive_usage(workspace, &[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn allocated_bytes_skips_paths_and_counts_hard_links_once() {
+        let dir = TempDir::new().expect("operation should succeed");
+        let root = dir.path();
+        fs::write(root.join("a.bin"), vec![1_u8; 64 * 1024]).expect("operation should succeed");
+        fs::create_dir(root.join("target")).expect("operation should succeed");
+        fs::write(root.join("target/big.bin"), vec![2_u8; 256 * 1024])
+            .expect("operation should succeed");
+
+        let all = allocated_bytes(root, &[]);
+        let skipped = allocated_bytes(root, &[root.join("target")]);
+        assert!(all >= skipped + 256 * 1024, "{all} vs {skipped}");
+
+        fs::hard_link(root.join("a.bin"), root.join("b.bin")).expect("operation should succeed");
+        assert_eq!(allocated_bytes(root, &[]), all);
+        assert_eq!(allocated_bytes(&root.join("missing"), &[]), 0);
+    }
+
+    #[test]
+    fn reflink_usage_splits_untouched_clones_from_new_writes() {
+        let dir = TempDir::new().expect("operation should succeed");
+        let snapshot = dir.path().join("snapshot");
+        let ws = dir.path().join("ws");
+        fs::create_dir_all(&snapshot).expect("operation should succeed");
+        fs::create_dir_all(&ws).expect("operation should succeed");
+        for base in [&snapshot, &ws] {
+            fs::write(base.join("same.txt"), vec![b'x'; 32 * 1024])
+                .expect("operation should succeed");
+            fs::write(base.join("edited.txt"), vec![b'y'; 32 * 1024])
+                .expect("operation should succeed");
+        }
+        let marker = ws.join(".maw-epoch");
+        fs::write(&marker, "epoch\n").expect("operation should succeed");
+        // Edit after the clone, and add build output.
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(ws.join("edited.txt"), vec![b'z'; 48 * 1024]).expect("operation should succeed");
+        fs::write(ws.join("out.bin"), vec![0_u8; 64 * 1024]).expect("operation should succeed");
+
+        let usage = reflink_usage(&ws, &snapshot, &marker);
+        assert_eq!(usage.total_bytes(), allocated_bytes(&ws, &[]));
+        assert!(usage.shared_bytes >= 32 * 1024);
+        assert!(usage.exclusive_bytes >= (48 + 64) * 1024);
+
+        let caps = PlatformCapabilities::default();
+        let copied = reflink_usage_for(&caps, &ws, &snapshot, &marker);
+        assert_eq!(copied.shared_bytes, 0);
+        assert_eq!(copied.total_bytes(), usage.total_bytes());
+    }
+
+    #[test]
+    fn overlay_usage_charges_only_the_upper_layer() {
+        let dir = TempDir::new().expect("operation should succeed");
+        let upper = dir.path().join("upper");
+        let lower = dir.path().join("lower");
+        fs::create_dir_all(&upper).expect("operation should succeed");
+        fs::create_dir_all(&lower).expect("operation should succeed");
+        fs::write(lower.join("big.bin"), vec![1_u8; 128 * 1024]).expect("operation should succeed");
+        fs::write(upper.join("small.txt"), b"hi").expect("operation should succeed");
+
+        let usage = overlay_usage(&upper, &lower);
+        assert!(usage.shared_bytes >= 128 * 1024);
+        assert!(usage.exclusive_bytes < usage.shared_bytes);
+    }
+}
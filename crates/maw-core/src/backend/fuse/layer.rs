@@ -0,0 +1,974 @@
+//! The merged view served over FUSE: the upper layer over the epoch tree.
+//!
+//! Lookups consult the upper layer first and fall through to the epoch tree
+//! unless a whiteout or opaque marker hides it (see [`super::lower`]). The
+//! epoch tree is never written: the first write to a lower file copies it
+//! up, and deleting a lower entry leaves a whiteout behind.
+//!
+//! Like overlayfs without `redirect_dir`, renaming a directory that has
+//! lower content fails with `EXDEV`; `mv` and friends fall back to copying.
+//! Hard links are not supported.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ffi::{OsStr, OsString};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write as _};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::os::unix::fs::{FileExt, MetadataExt, OpenOptionsExt, PermissionsExt, symlink};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use maw_git::EntryMode;
+
+use super::lower::{LowerEntry, LowerTree, OPAQUE_MARKER, WHITEOUT_PREFIX};
+use super::proto::{
+    self, Args, Attr, DirBuf, Request, S_IFDIR, S_IFLNK, S_IFMT, S_IFREG, StatFs, Timestamp, errno,
+    fattr, opcode,
+};
+
+const O_ACCMODE: u32 = 0o3;
+const O_WRONLY: u32 = 0o1;
+const O_TRUNC: u32 = 0o1000;
+/// `FUSE_GETATTR_FH`: the request names an open file handle.
+const GETATTR_FH: u32 = 1 << 0;
+
+fn err(code: i32) -> io::Error {
+    io::Error::from_raw_os_error(code)
+}
+
+fn errno_of(e: &io::Error) -> i32 {
+    e.raw_os_error().unwrap_or(errno::EIO)
+}
+
+/// Names the upper layer uses for its own bookkeeping.
+fn reserved(name: &OsStr) -> bool {
+    name.as_bytes().starts_with(WHITEOUT_PREFIX.as_bytes())
+}
+
+/// A node of the merged view.
+enum Node {
+    Upper(fs::Metadata),
+    Lower(LowerEntry),
+}
+
+impl Node {
+    fn is_dir(&self) -> bool {
+        match self {
+            Self::Upper(meta) => meta.is_dir(),
+            Self::Lower(entry) => entry.is_dir(),
+        }
+    }
+}
+
+/// An open file or directory.
+enum Handle {
+    /// A file in the upper layer.
+    File(File),
+    /// A lower file opened read-only, read whole from the object database.
+    Blob(Vec<u8>),
+    /// A directory listing taken at `opendir`.
+    Dir(Vec<(u64, u32, OsString)>),
+}
+
+/// Node ids ↔ workspace-relative paths.
+///
+/// Ids are handed out on first sight and kept for the life of the mount;
+/// a path that is removed and re-created gets a fresh id.
+struct Inodes {
+    paths: HashMap<u64, PathBuf>,
+    ids: HashMap<PathBuf, u64>,
+    next: u64,
+}
+
+impl Inodes {
+    fn new() -> Self {
+        let mut inodes = Self {
+            paths: HashMap::new(),
+            ids: HashMap::new(),
+            next: proto::ROOT_ID + 1,
+        };
+        inodes.paths.insert(proto::ROOT_ID, PathBuf::new());
+        inodes.ids.insert(PathBuf::new(), proto::ROOT_ID);
+        inodes
+    }
+
+    fn path(&self, ino: u64) -> io::Result<PathBuf> {
+        self.paths
+            .get(&ino)
+            .cloned()
+            .ok_or_else(|| err(errno::ENOENT))
+    }
+
+    fn id(&mut self, path: &Path) -> u64 {
+        if let Some(id) = self.ids.get(path) {
+            return *id;
+        }
+        let id = self.next;
+        self.next += 1;
+        self.paths.insert(id, path.to_path_buf());
+        self.ids.insert(path.to_path_buf(), id);
+        id
+    }
+
+    /// Forget `path` and everything below it.
+    fn remove(&mut self, path: &Path) {
+        self.ids.retain(|p, _| !p.starts_with(path));
+    }
+
+    /// Move `from` and everything below it to `to`.
+    fn rename(&mut self, from: &Path, to: &Path) {
+        self.remove(to);
+        let moved: Vec<(PathBuf, u64)> = self
+            .ids
+            .iter()
+            .filter(|(p, _)| p.starts_with(from))
+            .map(|(p, id)| (p.clone(), *id))
+            .collect();
+        for (old, id) in moved {
+            // `join("")` would add a trailing slash.
+            let new = match old.strip_prefix(from) {
+                Ok(rest) if !rest.as_os_str().is_empty() => to.join(rest),
+                _ => to.to_path_buf(),
+            };
+            self.ids.remove(&old);
+            self.ids.insert(new.clone(), id);
+            self.paths.insert(id, new);
+        }
+    }
+}
+
+/// The layered filesystem behind one workspace mount.
+pub struct Layer {
+    lower: LowerTree,
+    upper: PathBuf,
+    uid: u32,
+    gid: u32,
+    inodes: Inodes,
+    handles: HashMap<u64, Handle>,
+    next_fh: u64,
+}
+
+impl Layer {
+    /// Serve `lower` with writes going to the directory `upper`.
+    ///
+    /// Lower entries are owned by the owner of `upper`.
+    ///
+    /// # Errors
+    /// Returns an error if `upper` cannot be read.
+    pub fn new(lower: LowerTree, upper: PathBuf) -> io::Result<Self> {
+        let meta = fs::metadata(&upper)?;
+        Ok(Self {
+            lower,
+            uid: meta.uid(),
+            gid: meta.gid(),
+            upper,
+            inodes: Inodes::new(),
+            handles: HashMap::new(),
+            next_fh: 1,
+        })
+    }
+
+    /// Handle one request; `None` for requests that take no reply.
+    pub fn dispatch(&mut self, req: Request<'_>) -> Option<Vec<u8>> {
+        let header = req.header;
+        let mut args = req.args;
+        let ino = header.nodeid;
+        let result = match header.opcode {
+            opcode::FORGET | opcode::BATCH_FORGET | opcode::INTERRUPT => return None,
+            opcode::INIT => Self::init(&mut args),
+            opcode::DESTROY | opcode::FLUSH | opcode::ACCESS | opcode::FSYNC | opcode::FSYNCDIR => {
+                Ok(Vec::new())
+            }
+            opcode::LOOKUP => self.lookup(ino, &mut args),
+            opcode::GETATTR => self.getattr(ino, &mut args),
+            opcode::SETATTR => self.setattr(ino, &mut args),
+            opcode::READLINK => self.readlink(ino),
+            opcode::SYMLINK => self.symlink(ino, &mut args),
+            opcode::MKNOD => self.mknod(ino, &mut args),
+            opcode::MKDIR => self.mkdir(ino, &mut args),
+            opcode::UNLINK => self.unlink(ino, &mut args, false),
+            opcode::RMDIR => self.unlink(ino, &mut args, true),
+            opcode::RENAME => self.rename(ino, &mut args, false),
+            opcode::RENAME2 => self.rename(ino, &mut args, true),
+            opcode::LINK => Err(err(errno::EPERM)),
+            opcode::OPEN => self.open(ino, &mut args),
+            opcode::CREATE => self.create(ino, &mut args),
+            opcode::READ => self.read(&mut args),
+            opcode::WRITE => self.write(&mut args),
+            opcode::RELEASE | opcode::RELEASEDIR => self.release(&mut args),
+            opcode::STATFS => self.statfs(),
+            opcode::OPENDIR => self.opendir(ino),
+            opcode::READDIR => self.readdir(&mut args),
+            _ => Err(err(errno::ENOSYS)),
+        };
+        Some(match result {
+            Ok(body) => proto::reply(header.unique, &body),
+            Err(e) => proto::error(header.unique, errno_of(&e)),
+        })
+    }
+
+    // --- the merged view ---------------------------------------------------
+
+    /// The lower entry at `rel`, unless the upper layer hides it with a
+    /// whiteout, an opaque directory, or a non-directory ancestor.
+    ///
+    /// An upper entry at `rel` itself does not hide it: callers use this to
+    /// ask whether removing `rel` needs a whiteout.
+    fn lower_visible(&mut self, rel: &Path) -> io::Result<Option<LowerEntry>> {
+        let mut current = self.lower.root();
+        let mut upper_dir = Some(self.upper.clone());
+        let components: Vec<&OsStr> = rel.iter().collect();
+        for (i, name) in components.iter().enumerate() {
+            let Some(name_str) = name.to_str() else {
+                return Ok(None);
+            };
+            if let Some(dir) = &upper_dir {
+                let whiteout = dir.join(format!("{WHITEOUT_PREFIX}{name_str}"));
+                if dir.join(OPAQUE_MARKER).exists() || whiteout.exists() {
+                    return Ok(None);
+                }
+            }
+            match self.lower.child(current, name_str)? {
+                Some(next) => current = next,
+                None => return Ok(None),
+            }
+            if let Some(dir) = upper_dir.take() {
+                let next = dir.join(name);
+                match fs::symlink_metadata(&next) {
+                    Ok(meta) if meta.is_dir() => upper_dir = Some(next),
+                    // A file shadows whatever the lower layer has below it.
+                    Ok(_) if i + 1 < components.len() => return Ok(None),
+                    _ => {}
+                }
+            }
+        }
+        Ok(Some(current))
+    }
+
+    fn resolve(&mut self, rel: &Path) -> io::Result<Option<Node>> {
+        match fs::symlink_metadata(self.upper.join(rel)) {
+            Ok(meta) => return Ok(Some(Node::Upper(meta))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) if e.raw_os_error() == Some(errno::ENOTDIR) => {}
+            Err(e) => return Err(e),
+        }
+        Ok(self.lower_visible(rel)?.map(Node::Lower))
+    }
+
+    fn resolve_existing(&mut self, rel: &Path) -> io::Result<Node> {
+        self.resolve(rel)?.ok_or_else(|| err(errno::ENOENT))
+    }
+
+    /// The merged listing of the directory `rel`, sorted by name.
+    fn list(&mut self, rel: &Path) -> io::Result<BTreeMap<OsString, u32>> {
+        let mut entries = BTreeMap::new();
+        let mut whiteouts = BTreeSet::new();
+        let mut opaque = false;
+        match fs::read_dir(self.upper.join(rel)) {
+            Ok(read_dir) => {
+                for entry in read_dir {
+                    let entry = entry?;
+                    let name = entry.file_name();
+                    if name == OPAQUE_MARKER {
+                        opaque = true;
+                    } else if let Some(target) =
+                        name.as_bytes().strip_prefix(WHITEOUT_PREFIX.as_bytes())
+                    {
+                        whiteouts.insert(OsStr::from_bytes(target).to_os_string());
+                    } else {
+                        let file_type = entry.file_type()?;
+                        let mode = if file_type.is_dir() {
+                            S_IFDIR
+                        } else if file_type.is_symlink() {
+                            S_IFLNK
+                        } else {
+                            S_IFREG
+                        };
+                        entries.insert(name, mode);
+                    }
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        if !opaque && let Some(dir) = self.lower_visible(rel)? {
+            for child in self.lower.children(dir)?.iter() {
+                let name = OsString::from(&child.name);
+                if !whiteouts.contains(&name) && !entries.contains_key(&name) {
+                    entries.insert(name, lower_mode(child.mode) & S_IFMT);
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    fn attr(&mut self, ino: u64, node: &Node) -> io::Result<Attr> {
+        match node {
+            Node::Upper(meta) => Ok(upper_attr(ino, meta)),
+            Node::Lower(entry) => {
+                let size = if entry.is_dir() {
+                    4096
+                } else {
+                    self.lower.blob_size(entry.oid)?
+                };
+                let time = Timestamp {
+                    secs: self.lower.time(),
+                    nanos: 0,
+                };
+                Ok(Attr {
+                    ino,
+                    size,
+                    blocks: size.div_ceil(512),
+                    atime: time,
+                    mtime: time,
+                    ctime: time,
+                    mode: lower_mode(entry.mode),
+                    nlink: if entry.is_dir() { 2 } else { 1 },
+                    uid: self.uid,
+                    gid: self.gid,
+                    rdev: 0,
+                    blksize: 4096,
+                })
+            }
+        }
+    }
+
+    fn entry_reply(&mut self, rel: &Path) -> io::Result<Vec<u8>> {
+        let node = self.resolve_existing(rel)?;
+        let ino = self.inodes.id(rel);
+        Ok(proto::entry_out(&self.attr(ino, &node)?))
+    }
+
+    // --- writing the upper layer ---------------------------------------------
+
+    /// Make `rel` present in the upper layer, copying it from the lower
+    /// layer if needed. Directories are created empty: their lower entries
+    /// still show through.
+    fn copy_up(&mut self, rel: &Path) -> io::Result<()> {
+        let path = self.upper.join(rel);
+        if rel.as_os_str().is_empty() || fs::symlink_metadata(&path).is_ok() {
+            return Ok(());
+        }
+        let entry = self.lower_visible(rel)?.ok_or_else(|| err(errno::ENOENT))?;
+        if let Some(parent) = rel.parent() {
+            self.copy_up(parent)?;
+        }
+        match entry.mode {
+            EntryMode::Tree | EntryMode::Commit => {
+                fs::create_dir(&path)?;
+                fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+            }
+            EntryMode::Link => {
+                let target = self.lower.blob(entry.oid)?;
+                symlink(OsStr::from_bytes(&target), &path)?;
+            }
+            EntryMode::Blob | EntryMode::BlobExecutable => {
+                let content = self.lower.blob(entry.oid)?;
+                let mode = lower_mode(entry.mode) & 0o7777;
+                let mut file = OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .mode(mode)
+                    .open(&path)?;
+                file.write_all(&content)?;
+                file.set_permissions(fs::Permissions::from_mode(mode))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn whiteout_path(&self, rel: &Path) -> PathBuf {
+        let name = rel.file_name().unwrap_or_default();
+        let mut whiteout = OsString::from(WHITEOUT_PREFIX);
+        whiteout.push(name);
+        self.upper
+            .join(rel.parent().unwrap_or_else(|| Path::new("")))
+            .join(whiteout)
+    }
+
+    /// Record a new upper entry at `rel`: drop its whiteout, and make a new
+    /// directory opaque where the lower layer has (hidden) content.
+    fn created(&mut self, rel: &Path, is_dir: bool) -> io::Result<()> {
+        let whiteout = self.whiteout_path(rel);
+        let had_whiteout = whiteout.exists();
+        if had_whiteout {
+            fs::remove_file(&whiteout)?;
+        }
+        if is_dir && (had_whiteout || self.lower.entry(rel)?.is_some()) {
+            File::create(self.upper.join(rel).join(OPAQUE_MARKER))?;
+        }
+        Ok(())
+    }
+
+    /// The path for a new entry `name` in directory `parent`, with the parent
+    /// copied up. Fails if the name is taken.
+    fn new_child(&mut self, parent: u64, name: Option<&OsStr>) -> io::Result<PathBuf> {
+        let name = name.ok_or_else(|| err(errno::EINVAL))?;
+        if reserved(name) {
+            return Err(err(errno::EINVAL));
+        }
+        let parent_rel = self.inodes.path(parent)?;
+        if !self.resolve_existing(&parent_rel)?.is_dir() {
+            return Err(err(errno::ENOTDIR));
+        }
+        let rel = parent_rel.join(name);
+        if self.resolve(&rel)?.is_some() {
+            return Err(err(errno::EEXIST));
+        }
+        self.copy_up(&parent_rel)?;
+        Ok(rel)
+    }
+
+    fn child(&self, parent: u64, name: Option<&OsStr>) -> io::Result<PathBuf> {
+        let name = name.ok_or_else(|| err(errno::EINVAL))?;
+        if reserved(name) {
+            return Err(err(errno::ENOENT));
+        }
+        Ok(self.inodes.path(parent)?.join(name))
+    }
+
+    fn new_handle(&mut self, handle: Handle) -> u64 {
+        let fh = self.next_fh;
+        self.next_fh += 1;
+        self.handles.insert(fh, handle);
+        fh
+    }
+
+    // --- operations ----------------------------------------------------------
+
+    fn init(args: &mut Args<'_>) -> io::Result<Vec<u8>> {
+        let _major = args.u32();
+        let minor = args.u32().ok_or_else(|| err(errno::EINVAL))?;
+        let max_readahead = args.u32().unwrap_or(0);
+        let flags = args.u32().unwrap_or(0);
+        let wanted = proto::init_flags::ASYNC_READ | proto::init_flags::BIG_WRITES;
+        Ok(proto::init_out(minor, max_readahead, flags & wanted))
+    }
+
+    fn lookup(&mut self, parent: u64, args: &mut Args<'_>) -> io::Result<Vec<u8>> {
+        let rel = self.child(parent, args.name())?;
+        self.entry_reply(&rel)
+    }
+
+    fn getattr(&mut self, ino: u64, args: &mut Args<'_>) -> io::Result<Vec<u8>> {
+        let flags = args.u32().unwrap_or(0);
+        let _dummy = args.u32();
+        let fh = args.u64().unwrap_or(0);
+        if flags & GETATTR_FH != 0
+            && let Some(Handle::File(file)) = self.handles.get(&fh)
+        {
+            return Ok(proto::attr_out(&upper_attr(ino, &file.metadata()?)));
+        }
+        let rel = self.inodes.path(ino)?;
+        let node = self.resolve_existing(&rel)?;
+        Ok(proto::attr_out(&self.attr(ino, &node)?))
+    }
+
+    fn setattr(&mut self, ino: u64, args: &mut Args<'_>) -> io::Result<Vec<u8>> {
+        let missing = || err(errno::EINVAL);
+        let valid = args.u32().ok_or_else(missing)?;
+        let _padding = args.u32();
+        let fh = args.u64().ok_or_else(missing)?;
+        let size = args.u64().ok_or_else(missing)?;
+        let _lock_owner = args.u64();
+        let atime = args.u64().ok_or_else(missing)?;
+        let mtime = args.u64().ok_or_else(missing)?;
+        let _ctime = args.u64();
+        let atime_nsec = args.u32().ok_or_else(missing)?;
+        let mtime_nsec = args.u32().ok_or_else(missing)?;
+        let _ctime_nsec = args.u32();
+        let mode = args.u32().ok_or_else(missing)?;
+
+        let rel = self.inodes.path(ino)?;
+        self.resolve_existing(&rel)?;
+        self.copy_up(&rel)?;
+        let path = self.upper.join(&rel);
+        let is_symlink = fs::symlink_metadata(&path)?.file_type().is_symlink();
+
+        if valid & fattr::MODE != 0 && !is_symlink {
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode & 0o7777))?;
+        }
+        if valid & fattr::SIZE != 0 {
+            match self.handles.get(&fh) {
+                Some(Handle::File(file)) if valid & fattr::FH != 0 => file.set_len(size)?,
+                _ => OpenOptions::new().write(true).open(&path)?.set_len(size)?,
+            }
+        }
+        if valid & (fattr::ATIME | fattr::MTIME) != 0 && !is_symlink {
+            let mut times = fs::FileTimes::new();
+            if valid & fattr::ATIME != 0 {
+                times =
+                    times.set_accessed(time_arg(valid & fattr::ATIME_NOW != 0, atime, atime_nsec));
+            }
+            if valid & fattr::MTIME != 0 {
+                times =
+                    times.set_modified(time_arg(valid & fattr::MTIME_NOW != 0, mtime, mtime_nsec));
+            }
+            File::open(&path)?.set_times(times)?;
+        }
+        let meta = fs::symlink_metadata(&path)?;
+        Ok(proto::attr_out(&upper_attr(ino, &meta)))
+    }
+
+    fn readlink(&mut self, ino: u64) -> io::Result<Vec<u8>> {
+        let rel = self.inodes.path(ino)?;
+        match self.resolve_existing(&rel)? {
+            Node::Upper(meta) if meta.file_type().is_symlink() => {
+                Ok(fs::read_link(self.upper.join(&rel))?
+                    .into_os_string()
+                    .into_vec())
+            }
+            Node::Lower(entry) if entry.mode == EntryMode::Link => self.lower.blob(entry.oid),
+            _ => Err(err(errno::EINVAL)),
+        }
+    }
+
+    fn symlink(&mut self, parent: u64, args: &mut Args<'_>) -> io::Result<Vec<u8>> {
+        let name = args.name();
+        let target = args.name().ok_or_else(|| err(errno::EINVAL))?;
+        let rel = self.new_child(parent, name)?;
+        symlink(target, self.upper.join(&rel))?;
+        self.created(&rel, false)?;
+        self.entry_reply(&rel)
+    }
+
+    fn mknod(&mut self, parent: u64, args: &mut Args<'_>) -> io::Result<Vec<u8>> {
+        let mode = args.u32().ok_or_else(|| err(errno::EINVAL))?;
+        let _rdev = args.u32();
+        let _umask = args.u32();
+        let _padding = args.u32();
+        if mode & S_IFMT != S_IFREG {
+            return Err(err(errno::EPERM));
+        }
+        let rel = self.new_child(parent, args.name())?;
+        self.create_file(&rel, mode, false)?;
+        self.entry_reply(&rel)
+    }
+
+    fn mkdir(&mut self, parent: u64, args: &mut Args<'_>) -> io::Result<Vec<u8>> {
+        let mode = args.u32().ok_or_else(|| err(errno::EINVAL))?;
+        let _umask = args.u32();
+        let rel = self.new_child(parent, args.name())?;
+        let path = self.upper.join(&rel);
+        fs::create_dir(&path)?;
+        fs::set_permissions(&path, fs::Permissions::from_mode(mode & 0o7777))?;
+        self.created(&rel, true)?;
+        self.entry_reply(&rel)
+    }
+
+    fn create_file(&mut self, rel: &Path, mode: u32, read: bool) -> io::Result<File> {
+        let path = self.upper.join(rel);
+        let file = OpenOptions::new()
+            .read(read)
+            .write(true)
+            .create_new(true)
+            .mode(mode & 0o7777)
+            .open(&path)?;
+        file.set_permissions(fs::Permissions::from_mode(mode & 0o7777))?;
+        self.created(rel, false)?;
+        Ok(file)
+    }
+
+    fn unlink(&mut self, parent: u64, args: &mut Args<'_>, dir: bool) -> io::Result<Vec<u8>> {
+        let rel = self.child(parent, args.name())?;
+        let node = self.resolve_existing(&rel)?;
+        match (dir, node.is_dir()) {
+            (true, false) => return Err(err(errno::ENOTDIR)),
+            (false, true) => return Err(err(errno::EISDIR)),
+            (true, true) if !self.list(&rel)?.is_empty() => return Err(err(errno::ENOTEMPTY)),
+            _ => {}
+        }
+        let below = self.lower_visible(&rel)?;
+        if let Node::Upper(meta) = node {
+            let path = self.upper.join(&rel);
+            if meta.is_dir() {
+                // Only whiteouts and markers are left inside.
+                fs::remove_dir_all(&path)?;
+            } else {
+                fs::remove_file(&path)?;
+            }
+        }
+        if below.is_some() {
+            if let Some(parent_rel) = rel.parent() {
+                self.copy_up(parent_rel)?;
+            }
+            File::create(self.whiteout_path(&rel))?;
+        }
+        self.inodes.remove(&rel);
+        Ok(Vec::new())
+    }
+
+    fn rename(
+        &mut self,
+        parent: u64,
+        args: &mut Args<'_>,
+        with_flags: bool,
+    ) -> io::Result<Vec<u8>> {
+        let new_parent = args.u64().ok_or_else(|| err(errno::EINVAL))?;
+        let flags = if with_flags {
+            let flags = args.u32().unwrap_or(0);
+            let _padding = args.u32();
+            flags
+        } else {
+            0
+        };
+        let from = self.child(parent, args.name())?;
+        let to = self.child(new_parent, args.name())?;
+        if flags & !proto::RENAME_NOREPLACE != 0 {
+            return Err(err(errno::EINVAL));
+        }
+
+        let src = self.resolve_existing(&from)?;
+        let dst = self.resolve(&to)?;
+        if from == to {
+            return Ok(Vec::new());
+        }
+        if dst.is_some() && flags & proto::RENAME_NOREPLACE != 0 {
+            return Err(err(errno::EEXIST));
+        }
+        let src_is_dir = src.is_dir();
+        if src_is_dir && to.starts_with(&from) {
+            return Err(err(errno::EINVAL));
+        }
+        let src_below = self.lower_visible(&from)?.is_some();
+        if src_is_dir && src_below {
+            return Err(err(errno::EXDEV));
+        }
+        if let Some(dst) = &dst {
+            match (src_is_dir, dst.is_dir()) {
+                (false, true) => return Err(err(errno::EISDIR)),
+                (true, false) => return Err(err(errno::ENOTDIR)),
+                (true, true) if !self.list(&to)?.is_empty() => {
+                    return Err(err(errno::ENOTEMPTY));
+                }
+                _ => {}
+            }
+        }
+
+        self.copy_up(&from)?;
+        if let Some(to_parent) = to.parent() {
+            if !self.resolve_existing(to_parent)?.is_dir() {
+                return Err(err(errno::ENOTDIR));
+            }
+            self.copy_up(to_parent)?;
+        }
+        let dst_upper = self.upper.join(&to);
+        if dst.as_ref().is_some_and(Node::is_dir) && dst_upper.exists() {
+            fs::remove_dir_all(&dst_upper)?;
+        }
+        fs::rename(self.upper.join(&from), &dst_upper)?;
+        self.created(&to, src_is_dir)?;
+        if src_below {
+            File::create(self.whiteout_path(&from))?;
+        }
+        self.inodes.rename(&from, &to);
+        Ok(Vec::new())
+    }
+
+    fn open(&mut self, ino: u64, args: &mut Args<'_>) -> io::Result<Vec<u8>> {
+        let flags = args.u32().unwrap_or(0);
+        let rel = self.inodes.path(ino)?;
+        let node = self.resolve_existing(&rel)?;
+        if node.is_dir() {
+            return Err(err(errno::EISDIR));
+        }
+        let access = flags & O_ACCMODE;
+        let handle = if access != 0 || flags & O_TRUNC != 0 {
+            self.copy_up(&rel)?;
+            let file = OpenOptions::new()
+                .read(access != O_WRONLY)
+                .write(true)
+                .truncate(flags & O_TRUNC != 0)
+                .open(self.upper.join(&rel))?;
+            Handle::File(file)
+        } else {
+            match node {
+                Node::Upper(_) => Handle::File(File::open(self.upper.join(&rel))?),
+                Node::Lower(entry) => Handle::Blob(self.lower.blob(entry.oid)?),
+            }
+        };
+        Ok(proto::open_out(self.new_handle(handle)))
+    }
+
+    fn create(&mut self, parent: u64, args: &mut Args<'_>) -> io::Result<Vec<u8>> {
+        let flags = args.u32().ok_or_else(|| err(errno::EINVAL))?;
+        let mode = args.u32().ok_or_else(|| err(errno::EINVAL))?;
+        let _umask = args.u32();
+        let _open_flags = args.u32();
+        let rel = self.new_child(parent, args.name())?;
+        let file = self.create_file(&rel, mode, flags & O_ACCMODE != O_WRONLY)?;
+        let mut body = self.entry_reply(&rel)?;
+        body.extend(proto::open_out(self.new_handle(Handle::File(file))));
+        Ok(body)
+    }
+
+    fn read(&self, args: &mut Args<'_>) -> io::Result<Vec<u8>> {
+        let fh = args.u64().ok_or_else(|| err(errno::EINVAL))?;
+        let offset = args.u64().ok_or_else(|| err(errno::EINVAL))?;
+        let size = args.u32().ok_or_else(|| err(errno::EINVAL))?;
+        let size = usize::try_from(size).unwrap_or(usize::MAX);
+        match self.handles.get(&fh) {
+            Some(Handle::File(file)) => {
+                let mut buf = vec![0; size];
+                let mut filled = 0;
+                while filled < size {
+                    let n = file.read_at(&mut buf[filled..], offset + filled as u64)?;
+                    if n == 0 {
+                        break;
+                    }
+                    filled += n;
+                }
+                buf.truncate(filled);
+                Ok(buf)
+            }
+            Some(Handle::Blob(blob)) => {
+                let start = usize::try_from(offset)
+                    .unwrap_or(usize::MAX)
+                    .min(blob.len());
+                let end = start.saturating_add(size).min(blob.len());
+                Ok(blob[start..end].to_vec())
+            }
+            _ => Err(err(errno::EBADF)),
+        }
+    }
+
+    fn write(&self, args: &mut Args<'_>) -> io::Result<Vec<u8>> {
+        let fh = args.u64().ok_or_else(|| err(errno::EINVAL))?;
+        let offset = args.u64().ok_or_else(|| err(errno::EINVAL))?;
+        let size = args.u32().ok_or_else(|| err(errno::EINVAL))?;
+        let _write_flags = args.u32();
+        let _lock_owner = args.u64();
+        let _flags = args.u32();
+        let _padding = args.u32();
+        let data = args
+            .bytes(usize::try_from(size).unwrap_or(usize::MAX))
+            .ok_or_else(|| err(errno::EINVAL))?;
+        match self.handles.get(&fh) {
+            Some(Handle::File(file)) => {
+                file.write_all_at(data, offset)?;
+                Ok(proto::write_out(size))
+            }
+            _ => Err(err(errno::EBADF)),
+        }
+    }
+
+    fn release(&mut self, args: &mut Args<'_>) -> io::Result<Vec<u8>> {
+        if let Some(fh) = args.u64() {
+            self.handles.remove(&fh);
+        }
+        Ok(Vec::new())
+    }
+
+    fn statfs(&self) -> io::Result<Vec<u8>> {
+        let st = rustix::fs::statvfs(&self.upper)?;
+        Ok(proto::statfs_out(&StatFs {
+            blocks: st.f_blocks,
+            bfree: st.f_bfree,
+            bavail: st.f_bavail,
+            files: st.f_files,
+            ffree: st.f_ffree,
+            bsize: u32::try_from(st.f_bsize).unwrap_or(4096),
+            namelen: u32::try_from(st.f_namemax).unwrap_or(255),
+            frsize: u32::try_from(st.f_frsize).unwrap_or(4096),
+        }))
+    }
+
+    fn opendir(&mut self, ino: u64) -> io::Result<Vec<u8>> {
+        let rel = self.inodes.path(ino)?;
+        if !self.resolve_existing(&rel)?.is_dir() {
+            return Err(err(errno::ENOTDIR));
+        }
+        let parent = rel.parent().map_or(proto::ROOT_ID, |p| self.inodes.id(p));
+        let mut entries = vec![
+            (ino, S_IFDIR, OsString::from(".")),
+            (parent, S_IFDIR, OsString::from("..")),
+        ];
+        for (name, mode) in self.list(&rel)? {
+            let child_ino = self.inodes.id(&rel.join(&name));
+            entries.push((child_ino, mode, name));
+        }
+        Ok(proto::open_out(self.new_handle(Handle::Dir(entries))))
+    }
+
+    fn readdir(&self, args: &mut Args<'_>) -> io::Result<Vec<u8>> {
+        let fh = args.u64().ok_or_else(|| err(errno::EINVAL))?;
+        let offset = args.u64().ok_or_else(|| err(errno::EINVAL))?;
+        let size = args.u32().ok_or_else(|| err(errno::EINVAL))?;
+        let Some(Handle::Dir(entries)) = self.handles.get(&fh) else {
+            return Err(err(errno::EBADF));
+        };
+        let mut buf = DirBuf::new(usize::try_from(size).unwrap_or(usize::MAX));
+        let skip = usize::try_from(offset).unwrap_or(usize::MAX);
+        for (i, (ino, mode, name)) in entries.iter().enumerate().skip(skip) {
+            if !buf.push(*ino, i as u64 + 1, *mode, name) {
+                break;
+            }
+        }
+        Ok(buf.into_inner())
+    }
+}
+
+/// The mode a lower entry is served with.
+const fn lower_mode(mode: EntryMode) -> u32 {
+    match mode {
+        EntryMode::Blob => S_IFREG | 0o644,
+        EntryMode::BlobExecutable => S_IFREG | 0o755,
+        EntryMode::Link => S_IFLNK | 0o777,
+        EntryMode::Tree | EntryMode::Commit => S_IFDIR | 0o755,
+    }
+}
+
+fn timestamp(secs: i64, nanos: i64) -> Timestamp {
+    Timestamp {
+        secs,
+        nanos: u32::try_from(nanos).unwrap_or(0),
+    }
+}
+
+fn upper_attr(ino: u64, meta: &fs::Metadata) -> Attr {
+    Attr {
+        ino,
+        size: meta.size(),
+        blocks: meta.blocks(),
+        atime: timestamp(meta.atime(), meta.atime_nsec()),
+        mtime: timestamp(meta.mtime(), meta.mtime_nsec()),
+        ctime: timestamp(meta.ctime(), meta.ctime_nsec()),
+        mode: meta.mode(),
+        nlink: u32::try_from(meta.nlink()).unwrap_or(u32::MAX),
+        uid: meta.uid(),
+        gid: meta.gid(),
+        rdev: u32::try_from(meta.rdev()).unwrap_or(0),
+        blksize: u32::try_from(meta.blksize()).unwrap_or(4096),
+    }
+}
+
+/// A `SETATTR` time: now, or `secs`/`nanos` since the Unix epoch.
+fn time_arg(now: bool, secs: u64, nanos: u32) -> SystemTime {
+    if now {
+        return SystemTime::now();
+    }
+    // The kernel sends a signed 64-bit value in an unsigned field.
+    let secs = i64::from_ne_bytes(secs.to_ne_bytes());
+    let offset = Duration::new(secs.unsigned_abs(), nanos);
+    if secs < 0 {
+        SystemTime::UNIX_EPOCH - offset
+    } else {
+        SystemTime::UNIX_EPOCH + offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fuse::fixture_repo as repo;
+
+    fn layer(dir: &Path, epoch: &crate::model::types::EpochId) -> Layer {
+        let upper = dir.join("upper");
+        fs::create_dir_all(&upper).expect("mkdir upper");
+        Layer::new(LowerTree::open(dir, epoch).expect("open"), upper).expect("layer")
+    }
+
+    fn names(layer: &mut Layer, dir: &str) -> Vec<String> {
+        layer
+            .list(Path::new(dir))
+            .expect("list")
+            .into_keys()
+            .map(|n| n.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn whiteouts_and_opaque_dirs_hide_lower_entries() {
+        let (dir, epoch) = repo();
+        let mut layer = layer(dir.path(), &epoch);
+        assert_eq!(
+            names(&mut layer, ""),
+            ["README.md", "docs", "run.sh", "src"]
+        );
+
+        fs::create_dir_all(layer.upper.join("src")).expect("mkdir");
+        File::create(layer.upper.join("src/.wh.util.rs")).expect("whiteout");
+        fs::write(layer.upper.join("src/new.rs"), "new\n").expect("write");
+        assert_eq!(names(&mut layer, "src"), ["lib.rs", "new.rs"]);
+        assert!(
+            layer
+                .resolve(Path::new("src/util.rs"))
+                .expect("resolve")
+                .is_none()
+        );
+
+        fs::create_dir_all(layer.upper.join("docs")).expect("mkdir");
+        File::create(layer.upper.join("docs").join(OPAQUE_MARKER)).expect("marker");
+        assert!(names(&mut layer, "docs").is_empty());
+
+        // A file in the upper layer shadows the lower directory below it.
+        fs::remove_dir_all(layer.upper.join("src")).expect("rm");
+        fs::write(layer.upper.join("src"), "now a file\n").expect("write");
+        assert!(
+            layer
+                .lower_visible(Path::new("src/lib.rs"))
+                .expect("resolve")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn copy_up_materialises_parents_content_and_mode() {
+        let (dir, epoch) = repo();
+        let mut layer = layer(dir.path(), &epoch);
+
+        layer.copy_up(Path::new("src/lib.rs")).expect("copy up");
+        assert_eq!(
+            fs::read_to_string(layer.upper.join("src/lib.rs")).expect("read"),
+            "pub fn lib() {}\n"
+        );
+        // The parent is created empty: its other entries still come from below.
+        assert!(!layer.upper.join("src/util.rs").exists());
+        assert_eq!(names(&mut layer, "src"), ["lib.rs", "util.rs"]);
+
+        layer.copy_up(Path::new("run.sh")).expect("copy up");
+        let mode = fs::metadata(layer.upper.join("run.sh"))
+            .expect("stat")
+            .mode();
+        assert_eq!(mode & 0o777, 0o755);
+
+        assert_eq!(
+            layer
+                .copy_up(Path::new("missing"))
+                .expect_err("no such entry")
+                .raw_os_error(),
+            Some(errno::ENOENT)
+        );
+    }
+
+    #[test]
+    fn lower_attrs_use_git_modes_and_the_commit_time() {
+        let (dir, epoch) = repo();
+        let mut layer = layer(dir.path(), &epoch);
+        let time = layer.lower.time();
+
+        let node = layer
+            .resolve_existing(Path::new("run.sh"))
+            .expect("resolve");
+        let attr = layer.attr(7, &node).expect("attr");
+        assert_eq!(attr.mode, S_IFREG | 0o755);
+        assert_eq!(attr.size, 10);
+        assert_eq!(attr.mtime.secs, time);
+        assert_eq!(attr.uid, layer.uid);
+
+        let node = layer.resolve_existing(Path::new("docs")).expect("resolve");
+        assert_eq!(layer.attr(8, &node).expect("attr").mode, S_IFDIR | 0o755);
+    }
+
+    #[test]
+    fn inode_renames_carry_descendants() {
+        let mut inodes = Inodes::new();
+        let dir = inodes.id(Path::new("a"));
+        let file = inodes.id(Path::new("a/f"));
+        inodes.rename(Path::new("a"), Path::new("b"));
+        assert_eq!(inodes.path(dir).expect("dir").as_os_str(), "b");
+        assert_eq!(inodes.path(file).expect("file"), Path::new("b/f"));
+        assert_eq!(inodes.id(Path::new("b/f")), file);
+        assert_ne!(inodes.id(Path::new("a/f")), file);
+    }
+}
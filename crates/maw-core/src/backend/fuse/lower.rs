@@ -0,0 +1,450 @@
+//! The read-only lower layer (the epoch tree, read from the object database)
+//! and the upper-layer bookkeeping shared by the filesystem and snapshots.
+//!
+//! The upper layer is a plain directory holding every file the workspace
+//! wrote. Deletions of lower entries are recorded the way OCI image layers
+//! record them:
+//!
+//! - `.wh.<name>` — a whiteout: `<name>` from the lower layer is deleted.
+//! - `.wh..wh..opq` — an opaque marker: the directory holding it hides the
+//!   lower directory of the same path entirely (it was deleted and
+//!   re-created).
+//!
+//! Names starting with `.wh.` are therefore reserved inside the workspace.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use maw_git::{EntryMode, GitOid, GitRepo as _, GixRepo, TreeEntry};
+
+use crate::backend::SnapshotResult;
+use crate::model::types::EpochId;
+
+/// Prefix of whiteout files in the upper layer.
+pub const WHITEOUT_PREFIX: &str = ".wh.";
+
+/// Opaque-directory marker in the upper layer.
+pub const OPAQUE_MARKER: &str = ".wh..wh..opq";
+
+/// The worktree's `.git` gitfile, kept at the root of the upper layer.
+pub const GITFILE: &str = ".git";
+
+/// A lower-layer entry: a blob, symlink, tree, or submodule commit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LowerEntry {
+    pub mode: EntryMode,
+    pub oid: GitOid,
+}
+
+impl LowerEntry {
+    /// Trees and submodules show up as directories.
+    pub const fn is_dir(&self) -> bool {
+        matches!(self.mode, EntryMode::Tree | EntryMode::Commit)
+    }
+}
+
+/// The epoch tree, read lazily from the object database.
+///
+/// Tree listings and blob sizes are cached: the epoch is immutable, so a
+/// cached answer never goes stale.
+pub struct LowerTree {
+    repo: GixRepo,
+    root: GitOid,
+    /// Committer time of the epoch, used as the timestamp of lower entries.
+    time: i64,
+    trees: HashMap<GitOid, Arc<[TreeEntry]>>,
+    sizes: HashMap<GitOid, u64>,
+}
+
+impl LowerTree {
+    /// Open the tree of `epoch` in the repository at `root`.
+    ///
+    /// # Errors
+    /// Returns an error if the repository cannot be opened or the epoch is
+    /// not a commit.
+    pub fn open(root: &Path, epoch: &EpochId) -> io::Result<Self> {
+        let repo = GixRepo::open(root).map_err(io::Error::other)?;
+        let commit_oid: GitOid = epoch.as_str().parse().map_err(io::Error::other)?;
+        let commit = repo.read_commit(commit_oid).map_err(io::Error::other)?;
+        Ok(Self {
+            repo,
+            root: commit.tree_oid,
+            time: commit.committer_time,
+            trees: HashMap::new(),
+            sizes: HashMap::new(),
+        })
+    }
+
+    /// Committer time of the epoch (Unix seconds).
+    pub const fn time(&self) -> i64 {
+        self.time
+    }
+
+    /// The root tree.
+    pub const fn root(&self) -> LowerEntry {
+        LowerEntry {
+            mode: EntryMode::Tree,
+            oid: self.root,
+        }
+    }
+
+    /// The entries of a tree. Submodule commits have none.
+    ///
+    /// # Errors
+    /// Returns an error if the tree cannot be read.
+    pub fn children(&mut self, dir: LowerEntry) -> io::Result<Arc<[TreeEntry]>> {
+        if dir.mode != EntryMode::Tree {
+            return Ok(Arc::from([]));
+        }
+        if let Some(entries) = self.trees.get(&dir.oid) {
+            return Ok(Arc::clone(entries));
+        }
+        let entries: Arc<[TreeEntry]> = self
+            .repo
+            .read_tree(dir.oid)
+            .map_err(io::Error::other)?
+            .into();
+        self.trees.insert(dir.oid, Arc::clone(&entries));
+        Ok(entries)
+    }
+
+    /// The entry `name` inside the tree `dir`.
+    ///
+    /// # Errors
+    /// Returns an error if the tree cannot be read.
+    pub fn child(&mut self, dir: LowerEntry, name: &str) -> io::Result<Option<LowerEntry>> {
+        Ok(self
+            .children(dir)?
+            .iter()
+            .find(|e| e.name == name)
+            .map(|e| LowerEntry {
+                mode: e.mode,
+                oid: e.oid,
+            }))
+    }
+
+    /// The entry at `path` (relative to the workspace root), if any.
+    ///
+    /// # Errors
+    /// Returns an error if a tree on the way cannot be read.
+    pub fn entry(&mut self, path: &Path) -> io::Result<Option<LowerEntry>> {
+        let mut current = self.root();
+        for component in path.components() {
+            let Some(name) = component.as_os_str().to_str() else {
+                return Ok(None);
+            };
+            match self.child(current, name)? {
+                Some(next) => current = next,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(current))
+    }
+
+    /// The content of a blob (or a symlink target).
+    ///
+    /// # Errors
+    /// Returns an error if the blob cannot be read.
+    pub fn blob(&self, oid: GitOid) -> io::Result<Vec<u8>> {
+        self.repo.read_blob(oid).map_err(io::Error::other)
+    }
+
+    /// The size of a blob.
+    ///
+    /// # Errors
+    /// Returns an error if the blob cannot be read.
+    pub fn blob_size(&mut self, oid: GitOid) -> io::Result<u64> {
+        if let Some(size) = self.sizes.get(&oid) {
+            return Ok(*size);
+        }
+        let size = self.blob(oid)?.len() as u64;
+        self.sizes.insert(oid, size);
+        Ok(size)
+    }
+
+    /// Every non-directory path at or below `path`, for reporting a deleted
+    /// lower entry file by file.
+    ///
+    /// # Errors
+    /// Returns an error if a tree cannot be read.
+    pub fn files_under(&mut self, path: &Path, entry: LowerEntry) -> io::Result<Vec<PathBuf>> {
+        if !entry.is_dir() {
+            return Ok(vec![path.to_path_buf()]);
+        }
+        let mut files = Vec::new();
+        for child in self.children(entry)?.iter() {
+            let child_entry = LowerEntry {
+                mode: child.mode,
+                oid: child.oid,
+            };
+            files.extend(self.files_under(&path.join(&child.name), child_entry)?);
+        }
+        Ok(files)
+    }
+}
+
+/// Compare the upper layer against the epoch tree.
+///
+/// - **Added**: a file in the upper layer with no lower counterpart.
+/// - **Modified**: a file in both whose content, type, or executable bit
+///   differs. Files copied up but never changed are not reported.
+/// - **Deleted**: every lower file under a whiteout or hidden by an opaque
+///   directory, or replaced by an entry of another kind.
+///
+/// Only the upper layer and the object database are read, so this works
+/// whether or not the workspace is mounted.
+///
+/// # Errors
+/// Returns an error if the upper layer or the epoch tree cannot be read.
+pub fn diff_upper(upper: &Path, lower: &mut LowerTree) -> io::Result<SnapshotResult> {
+    let mut diff = Diff::default();
+    if upper.is_dir() {
+        let root = lower.root();
+        diff.walk(upper, Path::new(""), Some(root), lower)?;
+    }
+    Ok(SnapshotResult::new(
+        diff.added.into_iter().collect(),
+        diff.modified.into_iter().collect(),
+        diff.deleted.into_iter().collect(),
+    ))
+}
+
+#[derive(Default)]
+struct Diff {
+    added: BTreeSet<PathBuf>,
+    modified: BTreeSet<PathBuf>,
+    deleted: BTreeSet<PathBuf>,
+}
+
+impl Diff {
+    /// Walk the upper directory `rel`, whose lower counterpart is `lower_dir`
+    /// (`None` when the lower layer has no directory there).
+    fn walk(
+        &mut self,
+        upper: &Path,
+        rel: &Path,
+        lower_dir: Option<LowerEntry>,
+        lower: &mut LowerTree,
+    ) -> io::Result<()> {
+        let dir = upper.join(rel);
+        let mut names = BTreeMap::new();
+        let mut whiteouts = BTreeSet::new();
+        let mut opaque = false;
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let Ok(name) = entry.file_name().into_string() else {
+                continue;
+            };
+            if name == GITFILE && rel.as_os_str().is_empty() {
+                // The worktree's gitfile, not workspace content.
+                continue;
+            }
+            if name == OPAQUE_MARKER {
+                opaque = true;
+            } else if let Some(target) = name.strip_prefix(WHITEOUT_PREFIX) {
+                whiteouts.insert(target.to_owned());
+            } else {
+                names.insert(name, entry.file_type()?);
+            }
+        }
+
+        let lower_children = match lower_dir {
+            Some(d) => lower.children(d)?,
+            None => Arc::from([]),
+        };
+        for child in lower_children.iter() {
+            let hidden =
+                whiteouts.contains(&child.name) || (opaque && !names.contains_key(&child.name));
+            if hidden {
+                let child_entry = LowerEntry {
+                    mode: child.mode,
+                    oid: child.oid,
+                };
+                let path = rel.join(&child.name);
+                self.deleted.extend(lower.files_under(&path, child_entry)?);
+            }
+        }
+
+        for (name, file_type) in names {
+            let path = rel.join(&name);
+            let below = lower_children
+                .iter()
+                .find(|c| c.name == name)
+                .map(|c| LowerEntry {
+                    mode: c.mode,
+                    oid: c.oid,
+                });
+            if file_type.is_dir() {
+                match below {
+                    Some(entry) if entry.is_dir() => {
+                        self.walk(upper, &path, Some(entry), lower)?;
+                    }
+                    Some(_) => {
+                        self.deleted.insert(path.clone());
+                        self.walk(upper, &path, None, lower)?;
+                    }
+                    None => self.walk(upper, &path, None, lower)?,
+                }
+                continue;
+            }
+            match below {
+                Some(entry) if entry.is_dir() => {
+                    self.deleted.extend(lower.files_under(&path, entry)?);
+                    self.added.insert(path);
+                }
+                Some(entry) => {
+                    if differs(&upper.join(&path), file_type, entry, lower)? {
+                        self.modified.insert(path);
+                    }
+                }
+                None => {
+                    self.added.insert(path);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether an upper file differs from the lower blob or symlink it shadows.
+fn differs(
+    file: &Path,
+    file_type: fs::FileType,
+    lower_entry: LowerEntry,
+    lower: &LowerTree,
+) -> io::Result<bool> {
+    let content = if file_type.is_symlink() {
+        if lower_entry.mode != EntryMode::Link {
+            return Ok(true);
+        }
+        path_bytes(&fs::read_link(file)?)
+    } else {
+        let mode = if is_executable(&fs::metadata(file)?) {
+            EntryMode::BlobExecutable
+        } else {
+            EntryMode::Blob
+        };
+        if mode != lower_entry.mode {
+            return Ok(true);
+        }
+        fs::read(file)?
+    };
+    Ok(content != lower.blob(lower_entry.oid)?)
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+const fn is_executable(_meta: &fs::Metadata) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().into_owned().into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fuse::fixture_repo as repo;
+
+    #[test]
+    fn entry_walks_the_epoch_tree() {
+        let (dir, epoch) = repo();
+        let mut lower = LowerTree::open(dir.path(), &epoch).expect("open");
+
+        let src = lower.entry(Path::new("src")).expect("read").expect("src");
+        assert!(src.is_dir());
+        let lib = lower
+            .entry(Path::new("src/lib.rs"))
+            .expect("read")
+            .expect("lib.rs");
+        assert_eq!(lib.mode, EntryMode::Blob);
+        assert_eq!(lower.blob_size(lib.oid).expect("size"), 16);
+        assert!(
+            lower
+                .entry(Path::new("src/nope.rs"))
+                .expect("read")
+                .is_none()
+        );
+        assert!(
+            lower
+                .entry(Path::new("README.md/x"))
+                .expect("read")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn diff_upper_reports_changes_against_the_epoch() {
+        let (dir, epoch) = repo();
+        let mut lower = LowerTree::open(dir.path(), &epoch).expect("open");
+        let upper = dir.path().join("upper");
+        fs::create_dir_all(upper.join("src")).expect("mkdir");
+        fs::create_dir_all(upper.join("docs")).expect("mkdir");
+
+        // Copied up but unchanged: not reported.
+        fs::write(upper.join("README.md"), "readme\n").expect("write");
+        fs::write(upper.join("src/lib.rs"), "pub fn lib() { 1 }\n").expect("write");
+        fs::write(upper.join("src/new.rs"), "new\n").expect("write");
+        fs::write(upper.join("src/.wh.util.rs"), "").expect("write");
+        // docs/ deleted and re-created with a different file.
+        fs::write(upper.join("docs").join(OPAQUE_MARKER), "").expect("write");
+        fs::write(upper.join("docs/other.md"), "other\n").expect("write");
+        // Same content, executable bit dropped.
+        fs::write(upper.join("run.sh"), "#!/bin/sh\n").expect("write");
+        // The worktree gitfile is not content.
+        fs::write(upper.join(GITFILE), "gitdir: x\n").expect("write");
+
+        let result = diff_upper(&upper, &mut lower).expect("diff");
+        assert_eq!(
+            result.added,
+            [PathBuf::from("docs/other.md"), PathBuf::from("src/new.rs")]
+        );
+        assert_eq!(
+            result.modified,
+            [PathBuf::from("run.sh"), PathBuf::from("src/lib.rs")]
+        );
+        assert_eq!(
+            result.deleted,
+            [PathBuf::from("docs/guide.md"), PathBuf::from("src/util.rs")]
+        );
+    }
+
+    #[test]
+    fn diff_upper_whiteout_of_a_directory_deletes_its_files() {
+        let (dir, epoch) = repo();
+        let mut lower = LowerTree::open(dir.path(), &epoch).expect("open");
+        let upper = dir.path().join("upper");
+        fs::create_dir_all(&upper).expect("mkdir");
+        fs::write(upper.join(".wh.src"), "").expect("write");
+        // A file where the lower layer has a directory.
+        fs::write(upper.join("docs"), "now a file\n").expect("write");
+
+        let result = diff_upper(&upper, &mut lower).expect("diff");
+        assert_eq!(result.added, [PathBuf::from("docs")]);
+        assert!(result.modified.is_empty());
+        assert_eq!(
+            result.deleted,
+            [
+                PathBuf::from("docs/guide.md"),
+                PathBuf::from("src/lib.rs"),
+                PathBuf::from("src/util.rs")
+            ]
+        );
+    }
+}
@@ -0,0 +1,768 @@
+//! FUSE workspace backend (Linux only).
+//!
+//! Serves each workspace as a FUSE mount whose lower layer is the epoch tree
+//! read lazily from the git object database: nothing is checked out, so
+//! creating a workspace costs the same for a ten-file repo as for a
+//! million-file one. Writes go to a plain upper directory:
+//!
+//! - **lower**:  the epoch commit's tree, straight from `.git/objects`
+//! - **upper**:  `.manifold/cow/<name>/upper/` — per-workspace changes, with
+//!   OCI-style whiteouts for deletions
+//! - **merged**: `ws/<name>/` — the mount point
+//!
+//! [`snapshot`](WorkspaceBackend::snapshot) reads the upper layer directly and
+//! compares it against the epoch tree, without going through the mount.
+//!
+//! # Git
+//! The mount point is registered as a linked worktree with HEAD and index at
+//! the epoch, but nothing checked out; its `.git` gitfile sits at the root of
+//! the upper layer. `git` run inside the workspace works as usual, though a
+//! full `git status` reads every file through the mount the first time.
+//!
+//! # Daemon
+//! Each mount is served by a daemon process started by `create()` and
+//! detached from the caller. The host supplies the command as a
+//! [`DaemonCommand`]; it must end up calling [`FuseBackend::serve`] (the `maw`
+//! CLI runs itself as `maw fuse-serve`). The daemon exits when the workspace
+//! is unmounted.
+//! Like the overlay backend, `status()` and `snapshot()` restart it if the
+//! mount is gone (after a reboot, say), and its stderr goes to
+//! `.manifold/cow/<name>/fuse.log`.
+//!
+//! # Platform requirements
+//! - Linux with `/dev/fuse`.
+//! - Root, or the `fusermount3` helper from fuse3 (`fusermount` also works).
+
+mod lower;
+
+#[cfg(target_os = "linux")]
+mod layer;
+#[cfg(target_os = "linux")]
+mod proto;
+#[cfg(target_os = "linux")]
+mod session;
+
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufRead as _, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use maw_git::{GitOid, GitRepo as _};
+
+use super::{SnapshotResult, WorkspaceBackend, WorkspaceStatus};
+use crate::model::types::{EpochId, WorkspaceId, WorkspaceInfo, WorkspaceMode, WorkspaceState};
+use crate::refs as manifold_refs;
+
+/// How to start a workspace's FUSE daemon.
+///
+/// `create()` runs `program args... --root <root> <name>`, which must call
+/// [`FuseBackend::serve`] for that workspace.
+#[derive(Clone, Debug)]
+pub struct DaemonCommand {
+    /// The executable to run.
+    pub program: PathBuf,
+    /// Arguments passed before `--root <root> <name>`.
+    pub args: Vec<OsString>,
+}
+
+impl fmt::Display for DaemonCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.program.display())?;
+        for arg in &self.args {
+            write!(f, " {}", arg.to_string_lossy())?;
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Error type
+// ---------------------------------------------------------------------------
+
+/// Errors produced by the FUSE workspace backend.
+#[derive(Debug)]
+pub enum FuseBackendError {
+    /// The FUSE backend is Linux-only.
+    NotLinux,
+    /// FUSE mounts are not available to this process.
+    NotSupported { reason: String },
+    /// An I/O error occurred.
+    Io(std::io::Error),
+    /// The FUSE daemon failed.
+    Command {
+        command: String,
+        stderr: String,
+        exit_code: Option<i32>,
+    },
+    /// The workspace does not exist.
+    NotFound { name: String },
+}
+
+impl fmt::Display for FuseBackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotLinux => write!(
+                f,
+                "FUSE backend is Linux-only. \
+                 Use the git-worktree or reflink backend on this platform."
+            ),
+            Self::NotSupported { reason } => write!(
+                f,
+                "FUSE not available on this system: {reason}\n\
+                 Install fuse3 (for fusermount3) and make sure /dev/fuse exists."
+            ),
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::Command {
+                command,
+                stderr,
+                exit_code,
+            } => {
+                write!(f, "`{command}` failed")?;
+                if let Some(code) = exit_code {
+                    write!(f, " (exit {code})")?;
+                }
+                if !stderr.is_empty() {
+                    write!(f, ": {stderr}")?;
+                }
+                Ok(())
+            }
+            Self::NotFound { name } => write!(f, "workspace '{name}' not found"),
+        }
+    }
+}
+
+impl std::error::Error for FuseBackendError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for FuseBackendError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// FuseBackend
+// ---------------------------------------------------------------------------
+
+/// Whether this process can mount FUSE workspaces.
+#[must_use]
+pub fn is_available() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        session::available()
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        false
+    }
+}
+
+/// FUSE workspace backend.
+///
+/// Serves workspaces from the git object database through per-workspace
+/// FUSE daemons.
+pub struct FuseBackend {
+    /// Repository root (where `.git` and `.manifold/` live).
+    root: PathBuf,
+    /// The command that runs the daemons.
+    daemon: DaemonCommand,
+}
+
+impl FuseBackend {
+    /// Create a new `FuseBackend` for the given repository root, starting
+    /// workspace daemons with `daemon`.
+    ///
+    /// # Errors
+    /// - `FuseBackendError::NotLinux` on non-Linux platforms.
+    /// - `FuseBackendError::NotSupported` if FUSE cannot be mounted.
+    pub fn new(root: PathBuf, daemon: DaemonCommand) -> Result<Self, FuseBackendError> {
+        if !cfg!(target_os = "linux") {
+            return Err(FuseBackendError::NotLinux);
+        }
+        if !is_available() {
+            return Err(FuseBackendError::NotSupported {
+                reason: "/dev/fuse is missing, or not root and no fusermount3 on PATH".to_owned(),
+            });
+        }
+        Ok(Self { root, daemon })
+    }
+
+    // --- directory helpers --------------------------------------------------
+
+    /// `ws/<name>/` — FUSE mount point (the workspace working copy).
+    fn mount_point(&self, name: &WorkspaceId) -> PathBuf {
+        crate::model::layout::LayoutFlavor::detect_with_env(&self.root)
+            .workspaces_dir(&self.root)
+            .join(name.as_str())
+    }
+
+    /// `.../cow/` — per-workspace state for all FUSE workspaces.
+    fn cow_root(&self) -> PathBuf {
+        crate::model::layout::LayoutFlavor::detect_with_env(&self.root)
+            .manifold_dir(&self.root)
+            .join("cow")
+    }
+
+    /// `.../cow/<name>/upper/` — per-workspace writable layer.
+    pub(crate) fn upper_dir(&self, name: &WorkspaceId) -> PathBuf {
+        self.cow_root().join(name.as_str()).join("upper")
+    }
+
+    /// `.../cow/<name>/epoch` — records which epoch this workspace uses.
+    fn workspace_epoch_file(&self, name: &WorkspaceId) -> PathBuf {
+        self.cow_root().join(name.as_str()).join("epoch")
+    }
+
+    /// `.../cow/<name>/fuse.log` — the daemon's stderr.
+    fn log_file(&self, name: &WorkspaceId) -> PathBuf {
+        self.cow_root().join(name.as_str()).join("fuse.log")
+    }
+
+    fn read_workspace_epoch(&self, name: &WorkspaceId) -> Result<EpochId, FuseBackendError> {
+        let content = fs::read_to_string(self.workspace_epoch_file(name))?;
+        EpochId::new(content.trim()).map_err(|e| FuseBackendError::Command {
+            command: format!("read epoch file for workspace '{}'", name.as_str()),
+            stderr: format!("invalid OID in epoch file: {e}"),
+            exit_code: None,
+        })
+    }
+
+    /// Register the mount point as a linked git worktree at `epoch`, so
+    /// `git` (and merge) work inside the workspace.
+    ///
+    /// Only the admin files and index are written, never the files
+    /// themselves. The `.git` gitfile is moved into the upper layer, where
+    /// the mount serves it from.
+    fn register_worktree(
+        &self,
+        name: &WorkspaceId,
+        epoch: &EpochId,
+    ) -> Result<(), FuseBackendError> {
+        let mount_point = self.mount_point(name);
+        let git_err = |stderr: String| FuseBackendError::Command {
+            command: format!("git worktree add --no-checkout {}", mount_point.display()),
+            stderr,
+            exit_code: None,
+        };
+        let repo = maw_git::GixRepo::open(&self.root).map_err(|e| git_err(e.to_string()))?;
+        let target = epoch
+            .as_str()
+            .parse::<GitOid>()
+            .map_err(|e| git_err(format!("invalid epoch OID: {e}")))?;
+        let _ = repo.worktree_prune();
+        repo.worktree_add_no_checkout(name.as_str(), target, &mount_point)
+            .map_err(|e| git_err(e.to_string()))?;
+        fs::rename(
+            mount_point.join(lower::GITFILE),
+            self.upper_dir(name).join(lower::GITFILE),
+        )?;
+        Ok(())
+    }
+
+    // --- daemon ---------------------------------------------------------------
+
+    /// Start the daemon for `name` and wait until its mount is up.
+    fn start_daemon(&self, name: &WorkspaceId) -> Result<(), FuseBackendError> {
+        let log = File::create(self.log_file(name))?;
+        let mut command = Command::new(&self.daemon.program);
+        command
+            .args(&self.daemon.args)
+            .arg("--root")
+            .arg(&self.root)
+            .arg(name.as_str())
+            .current_dir("/")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(log);
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        let mut child = command.spawn()?;
+
+        let mut line = String::new();
+        if let Some(stdout) = child.stdout.take() {
+            BufReader::new(stdout).read_line(&mut line)?;
+        }
+        if line.trim() == "ready" {
+            // The daemon outlives us; it exits when the workspace is unmounted.
+            return Ok(());
+        }
+        let status = child.wait()?;
+        let stderr = fs::read_to_string(self.log_file(name)).unwrap_or_default();
+        Err(FuseBackendError::Command {
+            command: format!("{} {}", self.daemon, name.as_str()),
+            stderr: stderr.trim().to_owned(),
+            exit_code: status.code(),
+        })
+    }
+
+    /// Start the daemon unless the mount is already being served.
+    fn ensure_mounted(&self, name: &WorkspaceId) -> Result<(), FuseBackendError> {
+        if is_live(&self.mount_point(name)) {
+            return Ok(());
+        }
+        fs::create_dir_all(self.mount_point(name))?;
+        self.start_daemon(name)
+    }
+
+    /// Mount workspace `name` and serve it until it is unmounted.
+    ///
+    /// This is the body of the [`DaemonCommand`]. It prints `ready` on stdout
+    /// once the mount is up.
+    ///
+    /// # Errors
+    /// Returns an error if the workspace does not exist, its epoch cannot be
+    /// read from the object database, or the mount fails.
+    pub fn serve(&self, name: &WorkspaceId) -> Result<(), FuseBackendError> {
+        if !self.upper_dir(name).exists() {
+            return Err(FuseBackendError::NotFound {
+                name: name.as_str().to_owned(),
+            });
+        }
+        #[cfg(target_os = "linux")]
+        {
+            use std::io::Write as _;
+
+            let epoch = self.read_workspace_epoch(name)?;
+            let lower = lower::LowerTree::open(&self.root, &epoch)?;
+            let mut layer = layer::Layer::new(lower, self.upper_dir(name))?;
+            let dev = session::mount(&self.mount_point(name))?;
+            let mut stdout = std::io::stdout().lock();
+            writeln!(stdout, "ready")?;
+            stdout.flush()?;
+            drop(stdout);
+            session::serve(&dev, &mut layer)?;
+            Ok(())
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            Err(FuseBackendError::NotLinux)
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// WorkspaceBackend impl
+// ---------------------------------------------------------------------------
+
+impl WorkspaceBackend for FuseBackend {
+    type Error = FuseBackendError;
+
+    fn create(&self, name: &WorkspaceId, epoch: &EpochId) -> Result<WorkspaceInfo, Self::Error> {
+        let mount_point = self.mount_point(name);
+
+        // Idempotent: if already mounted, return info.
+        if !is_live(&mount_point) {
+            // An existing upper layer holds the agent's uncommitted writes
+            // against its recorded epoch: remount it, never reset it.
+            let fresh = !self.upper_dir(name).exists();
+            if fresh {
+                fs::create_dir_all(self.upper_dir(name))?;
+                fs::write(self.workspace_epoch_file(name), epoch.as_str())?;
+            }
+            fs::create_dir_all(&mount_point)?;
+            let started = if fresh {
+                self.register_worktree(name, epoch)
+            } else {
+                Ok(())
+            }
+            .and_then(|()| self.start_daemon(name));
+            if let Err(err) = started {
+                if fresh {
+                    let _ = fs::remove_file(mount_point.join(lower::GITFILE));
+                    let _ = fs::remove_dir(&mount_point);
+                    let _ = fs::remove_dir_all(self.cow_root().join(name.as_str()));
+                    if let Ok(repo) = maw_git::GixRepo::open(&self.root) {
+                        let _ = repo.worktree_prune();
+                    }
+                }
+                // A failed remount leaves everything in place: without the
+                // mount point the worktree registration looks stale and the
+                // next prune would drop the workspace's HEAD and index.
+                return Err(err);
+            }
+        }
+
+        Ok(WorkspaceInfo {
+            id: name.clone(),
+            path: mount_point,
+            epoch: self
+                .read_workspace_epoch(name)
+                .unwrap_or_else(|_| epoch.clone()),
+            state: WorkspaceState::Active,
+            mode: WorkspaceMode::default(),
+            commits_ahead: 0,
+        })
+    }
+
+    fn destroy(&self, name: &WorkspaceId) -> Result<(), Self::Error> {
+        let mount_point = self.mount_point(name);
+        if is_mounted(&mount_point) {
+            unmount(&mount_point)?;
+        }
+        if mount_point.exists() {
+            fs::remove_dir_all(&mount_point)?;
+        }
+        let cow_dir = self.cow_root().join(name.as_str());
+        if cow_dir.exists() {
+            fs::remove_dir_all(&cow_dir)?;
+        }
+        // With the mount point gone the worktree registration is stale.
+        if let Ok(repo) = maw_git::GixRepo::open(&self.root) {
+            let _ = repo.worktree_prune();
+        }
+        for ref_name in manifold_refs::workspace_owned_refs(name.as_str()) {
+            let _ = manifold_refs::delete_ref(&self.root, &ref_name);
+        }
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Vec<WorkspaceInfo>, Self::Error> {
+        let cow_dir = self.cow_root();
+        if !cow_dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut infos = Vec::new();
+        for entry in fs::read_dir(&cow_dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let Some(Ok(name)) = file_name.to_str().map(WorkspaceId::new) else {
+                continue;
+            };
+            let Ok(epoch) = self.read_workspace_epoch(&name) else {
+                continue;
+            };
+            let mount_point = self.mount_point(&name);
+            let state = if is_live(&mount_point) {
+                WorkspaceState::Active
+            } else {
+                // Not served but the upper layer exists: needs a remount.
+                WorkspaceState::Stale { behind_epochs: 0 }
+            };
+            infos.push(WorkspaceInfo {
+                id: name,
+                path: mount_point,
+                epoch,
+                state,
+                mode: WorkspaceMode::default(),
+                commits_ahead: 0,
+            });
+        }
+        Ok(infos)
+    }
+
+    fn status(&self, name: &WorkspaceId) -> Result<WorkspaceStatus, Self::Error> {
+        let snapshot = self.snapshot(name)?;
+        let epoch = self.read_workspace_epoch(name)?;
+        let dirty_files = snapshot.all_changed().into_iter().cloned().collect();
+        Ok(WorkspaceStatus::new(epoch.into(), dirty_files, false))
+    }
+
+    fn snapshot(&self, name: &WorkspaceId) -> Result<SnapshotResult, Self::Error> {
+        if !self.upper_dir(name).exists() {
+            return Err(FuseBackendError::NotFound {
+                name: name.as_str().to_owned(),
+            });
+        }
+        let epoch = self.read_workspace_epoch(name)?;
+
+        // Auto-remount so callers reading the working copy see a live mount.
+        self.ensure_mounted(name)?;
+
+        let mut lower = lower::LowerTree::open(&self.root, &epoch)?;
+        Ok(lower::diff_upper(&self.upper_dir(name), &mut lower)?)
+    }
+
+    fn workspace_path(&self, name: &WorkspaceId) -> PathBuf {
+        self.mount_point(name)
+    }
+
+    fn exists(&self, name: &WorkspaceId) -> bool {
+        self.upper_dir(name).exists()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helper functions
+// ---------------------------------------------------------------------------
+
+/// Whether `mount_point` is mounted and its daemon is answering.
+fn is_live(mount_point: &Path) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        session::mount_state(mount_point) == session::MountState::Live
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = mount_point;
+        false
+    }
+}
+
+/// Whether `mount_point` is mounted, live or not.
+fn is_mounted(mount_point: &Path) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        session::mount_state(mount_point) != session::MountState::Absent
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = mount_point;
+        false
+    }
+}
+
+fn unmount(mount_point: &Path) -> std::io::Result<()> {
+    #[cfg(target_os = "linux")]
+    {
+        session::unmount(mount_point)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = mount_point;
+        Ok(())
+    }
+}
+
+/// A committed repo shared by the FUSE tests:
+///
+/// - `README.md`, `run.sh` (executable)
+/// - `src/lib.rs`, `src/util.rs`
+/// - `docs/guide.md`
+#[cfg(test)]
+fn fixture_repo() -> (tempfile::TempDir, EpochId) {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let (dir, root) = maw_git::test_support::init_test_repo();
+    fs::create_dir_all(root.join("src")).expect("mkdir src");
+    fs::create_dir_all(root.join("docs")).expect("mkdir docs");
+    fs::write(root.join("README.md"), "readme\n").expect("write");
+    fs::write(root.join("src/lib.rs"), "pub fn lib() {}\n").expect("write");
+    fs::write(root.join("src/util.rs"), "pub fn util() {}\n").expect("write");
+    fs::write(root.join("docs/guide.md"), "guide\n").expect("write");
+    fs::write(root.join("run.sh"), "#!/bin/sh\n").expect("write");
+    fs::set_permissions(root.join("run.sh"), fs::Permissions::from_mode(0o755)).expect("chmod");
+    let oid = maw_git::test_support::commit_all(&root, "init");
+    (dir, EpochId::new(&oid).expect("epoch"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serves a mount from a thread of the test process; unmounts on drop.
+    #[cfg(target_os = "linux")]
+    struct Mounted {
+        mount_point: PathBuf,
+        server: Option<std::thread::JoinHandle<std::io::Result<()>>>,
+    }
+
+    #[cfg(target_os = "linux")]
+    impl Mounted {
+        fn new(root: &Path, epoch: &EpochId, upper: PathBuf, mount_point: PathBuf) -> Self {
+            fs::create_dir_all(&upper).expect("mkdir upper");
+            fs::create_dir_all(&mount_point).expect("mkdir mount point");
+            let dev = session::mount(&mount_point).expect("mount");
+            // The object database handle is not `Send`: open it on the server.
+            let (root, epoch) = (root.to_path_buf(), epoch.clone());
+            let server = std::thread::spawn(move || {
+                let lower = lower::LowerTree::open(&root, &epoch)?;
+                let mut layer = layer::Layer::new(lower, upper)?;
+                session::serve(&dev, &mut layer)
+            });
+            Self {
+                mount_point,
+                server: Some(server),
+            }
+        }
+
+        fn unmount(mut self) {
+            session::unmount(&self.mount_point).expect("unmount");
+            if let Some(server) = self.server.take() {
+                server.join().expect("join").expect("serve");
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    impl Drop for Mounted {
+        fn drop(&mut self) {
+            if self.server.is_some() {
+                let _ = session::unmount(&self.mount_point);
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn mount_serves_the_epoch_and_records_writes() {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        if !is_available() {
+            eprintln!("skipping: FUSE is not available");
+            return;
+        }
+        let (dir, epoch) = fixture_repo();
+        let upper = dir.path().join("upper");
+        let mnt = dir.path().join("mnt");
+        let mount = Mounted::new(dir.path(), &epoch, upper.clone(), mnt.clone());
+
+        assert_eq!(
+            fs::read_to_string(mnt.join("src/lib.rs")).expect("read"),
+            "pub fn lib() {}\n"
+        );
+        let mode = fs::metadata(mnt.join("run.sh"))
+            .expect("stat")
+            .permissions();
+        assert_eq!(mode.mode() & 0o777, 0o755);
+        let mut top: Vec<String> = fs::read_dir(&mnt)
+            .expect("readdir")
+            .map(|e| e.expect("entry").file_name().to_string_lossy().into_owned())
+            .collect();
+        top.sort();
+        assert_eq!(top, ["README.md", "docs", "run.sh", "src"]);
+
+        fs::write(mnt.join("src/lib.rs"), "pub fn lib() { 1 }\n").expect("write");
+        fs::remove_file(mnt.join("src/util.rs")).expect("rm");
+        fs::create_dir(mnt.join("src/new")).expect("mkdir");
+        fs::write(mnt.join("src/new/mod.rs"), "new\n").expect("create");
+        fs::rename(mnt.join("README.md"), mnt.join("README")).expect("rename");
+        fs::remove_dir_all(mnt.join("docs")).expect("rm -r");
+        assert!(!mnt.join("src/util.rs").exists());
+        assert_eq!(
+            fs::read_to_string(mnt.join("README")).expect("read"),
+            "readme\n"
+        );
+        assert_eq!(fs::read_dir(mnt.join("src")).expect("readdir").count(), 2);
+
+        mount.unmount();
+        assert!(upper.join("src/.wh.util.rs").exists());
+
+        let mut lower = lower::LowerTree::open(dir.path(), &epoch).expect("open");
+        let diff = lower::diff_upper(&upper, &mut lower).expect("diff");
+        let paths = |v: &[PathBuf]| -> Vec<String> {
+            v.iter().map(|p| p.to_string_lossy().into_owned()).collect()
+        };
+        assert_eq!(paths(&diff.added), ["README", "src/new/mod.rs"]);
+        assert_eq!(paths(&diff.modified), ["src/lib.rs"]);
+        assert_eq!(
+            paths(&diff.deleted),
+            ["README.md", "docs/guide.md", "src/util.rs"]
+        );
+    }
+
+    /// A daemon that runs `script` under `sh` instead of serving the mount.
+    fn stub_daemon(script: &str) -> DaemonCommand {
+        DaemonCommand {
+            program: PathBuf::from("/bin/sh"),
+            args: vec!["-c".into(), script.into(), "stub".into()],
+        }
+    }
+
+    #[test]
+    fn create_runs_the_given_daemon_command() {
+        if !is_available() {
+            eprintln!("skipping: FUSE is not available");
+            return;
+        }
+        let (dir, epoch) = fixture_repo();
+        let args = dir.path().join("args");
+        let script = format!("echo \"$@\" > {}; echo broken >&2; exit 3", args.display());
+        let backend =
+            FuseBackend::new(dir.path().to_path_buf(), stub_daemon(&script)).expect("backend");
+        let name = WorkspaceId::new("agent").expect("name");
+
+        let err = backend
+            .create(&name, &epoch)
+            .expect_err("stub never mounts");
+        let FuseBackendError::Command {
+            command,
+            stderr,
+            exit_code,
+        } = err
+        else {
+            panic!("expected a daemon failure, got {err}");
+        };
+        assert!(command.starts_with("/bin/sh -c"), "{command}");
+        assert_eq!(stderr, "broken");
+        assert_eq!(exit_code, Some(3));
+        assert_eq!(
+            fs::read_to_string(&args).expect("args"),
+            format!("--root {} agent\n", dir.path().display())
+        );
+    }
+
+    #[test]
+    fn failed_remount_keeps_the_upper_layer() {
+        if !is_available() {
+            eprintln!("skipping: FUSE is not available");
+            return;
+        }
+        let (dir, epoch) = fixture_repo();
+        let backend =
+            FuseBackend::new(dir.path().to_path_buf(), stub_daemon("exit 1")).expect("backend");
+        let name = WorkspaceId::new("agent").expect("name");
+
+        // A fresh workspace is rolled back entirely.
+        backend
+            .create(&name, &epoch)
+            .expect_err("stub never mounts");
+        assert!(!backend.cow_root().join("agent").exists());
+        assert!(!backend.mount_point(&name).exists());
+        assert!(!dir.path().join(".git/worktrees/agent").exists());
+
+        // An existing one keeps its writes, its recorded epoch and its
+        // worktree registration.
+        let upper = backend.upper_dir(&name);
+        fs::create_dir_all(&upper).expect("mkdir upper");
+        fs::create_dir_all(backend.mount_point(&name)).expect("mkdir mount");
+        backend.register_worktree(&name, &epoch).expect("register");
+        fs::write(upper.join("work.txt"), "uncommitted\n").expect("write");
+        fs::write(backend.workspace_epoch_file(&name), epoch.as_str()).expect("epoch");
+        let other = EpochId::new(&"b".repeat(40)).expect("epoch");
+        backend
+            .create(&name, &other)
+            .expect_err("stub never mounts");
+        assert_eq!(
+            fs::read_to_string(upper.join("work.txt")).expect("read"),
+            "uncommitted\n"
+        );
+        assert_eq!(backend.read_workspace_epoch(&name).expect("epoch"), epoch);
+        maw_git::GixRepo::open(dir.path())
+            .expect("open")
+            .worktree_prune()
+            .expect("prune");
+        assert!(dir.path().join(".git/worktrees/agent/HEAD").exists());
+    }
+
+    #[test]
+    fn error_display_not_supported() {
+        let err = FuseBackendError::NotSupported {
+            reason: "no /dev/fuse".to_owned(),
+        };
+        let msg = format!("{err}");
+        assert!(msg.contains("FUSE not available"));
+        assert!(msg.contains("no /dev/fuse"));
+    }
+
+    #[test]
+    fn error_display_command() {
+        let err = FuseBackendError::Command {
+            command: "maw fuse-serve a".to_owned(),
+            stderr: "mount failed".to_owned(),
+            exit_code: Some(1),
+        };
+        assert_eq!(
+            format!("{err}"),
+            "`maw fuse-serve a` failed (exit 1): mount failed"
+        );
+    }
+}
@@ -0,0 +1,440 @@
+//! FUSE wire protocol: the subset of `<linux/fuse.h>` (ABI 7.31) that the
+//! layered filesystem speaks.
+//!
+//! A request is a 40-byte [`InHeader`] followed by an opcode-specific body;
+//! a reply is a 16-byte out header followed by an opcode-specific struct.
+//! Integers are native-endian and the kernel structs have no implicit
+//! padding, so encoding is a flat sequence of `to_ne_bytes` calls.
+
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+
+/// Protocol major version.
+pub const KERNEL_VERSION: u32 = 7;
+/// Highest protocol minor version implemented.
+pub const KERNEL_MINOR_VERSION: u32 = 31;
+/// Node id of the filesystem root.
+pub const ROOT_ID: u64 = 1;
+/// Largest WRITE payload accepted.
+pub const MAX_WRITE: u32 = 128 * 1024;
+/// Read buffer size: one maximal WRITE plus room for its headers.
+pub const BUFFER_SIZE: usize = MAX_WRITE as usize + 4096;
+
+/// Request opcodes.
+pub mod opcode {
+    pub const LOOKUP: u32 = 1;
+    pub const FORGET: u32 = 2;
+    pub const GETATTR: u32 = 3;
+    pub const SETATTR: u32 = 4;
+    pub const READLINK: u32 = 5;
+    pub const SYMLINK: u32 = 6;
+    pub const MKNOD: u32 = 8;
+    pub const MKDIR: u32 = 9;
+    pub const UNLINK: u32 = 10;
+    pub const RMDIR: u32 = 11;
+    pub const RENAME: u32 = 12;
+    pub const LINK: u32 = 13;
+    pub const OPEN: u32 = 14;
+    pub const READ: u32 = 15;
+    pub const WRITE: u32 = 16;
+    pub const STATFS: u32 = 17;
+    pub const RELEASE: u32 = 18;
+    pub const FSYNC: u32 = 20;
+    pub const FLUSH: u32 = 25;
+    pub const INIT: u32 = 26;
+    pub const OPENDIR: u32 = 27;
+    pub const READDIR: u32 = 28;
+    pub const RELEASEDIR: u32 = 29;
+    pub const FSYNCDIR: u32 = 30;
+    pub const ACCESS: u32 = 34;
+    pub const CREATE: u32 = 35;
+    pub const INTERRUPT: u32 = 36;
+    pub const DESTROY: u32 = 38;
+    pub const BATCH_FORGET: u32 = 42;
+    pub const RENAME2: u32 = 45;
+}
+
+/// `errno` values returned to the kernel.
+pub mod errno {
+    pub const EPERM: i32 = 1;
+    pub const ENOENT: i32 = 2;
+    pub const EIO: i32 = 5;
+    pub const EBADF: i32 = 9;
+    pub const EEXIST: i32 = 17;
+    pub const EXDEV: i32 = 18;
+    pub const ENOTDIR: i32 = 20;
+    pub const EISDIR: i32 = 21;
+    pub const EINVAL: i32 = 22;
+    pub const ENOSYS: i32 = 38;
+    pub const ENOTEMPTY: i32 = 39;
+}
+
+/// `SETATTR` validity bits.
+pub mod fattr {
+    pub const MODE: u32 = 1 << 0;
+    pub const SIZE: u32 = 1 << 3;
+    pub const ATIME: u32 = 1 << 4;
+    pub const MTIME: u32 = 1 << 5;
+    pub const FH: u32 = 1 << 6;
+    pub const ATIME_NOW: u32 = 1 << 7;
+    pub const MTIME_NOW: u32 = 1 << 8;
+}
+
+/// `INIT` capability flags.
+pub mod init_flags {
+    pub const ASYNC_READ: u32 = 1 << 0;
+    pub const BIG_WRITES: u32 = 1 << 5;
+}
+
+/// `RENAME2` flags.
+pub const RENAME_NOREPLACE: u32 = 1 << 0;
+
+/// File type bits of a mode.
+pub const S_IFMT: u32 = 0o170_000;
+pub const S_IFREG: u32 = 0o100_000;
+pub const S_IFDIR: u32 = 0o040_000;
+pub const S_IFLNK: u32 = 0o120_000;
+
+/// The header common to every request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InHeader {
+    pub opcode: u32,
+    pub unique: u64,
+    pub nodeid: u64,
+    pub uid: u32,
+    pub gid: u32,
+}
+
+/// One request read from the device.
+#[derive(Clone, Copy, Debug)]
+pub struct Request<'a> {
+    pub header: InHeader,
+    pub args: Args<'a>,
+}
+
+impl<'a> Request<'a> {
+    /// Parse a request; `None` if it is truncated.
+    pub fn parse(buf: &'a [u8]) -> Option<Self> {
+        let mut args = Args { buf };
+        let len = usize::try_from(args.u32()?).ok()?;
+        let opcode = args.u32()?;
+        let unique = args.u64()?;
+        let nodeid = args.u64()?;
+        let uid = args.u32()?;
+        let gid = args.u32()?;
+        let _pid = args.u32()?;
+        let _padding = args.u32()?;
+        let body = buf.get(40..len)?;
+        Some(Self {
+            header: InHeader {
+                opcode,
+                unique,
+                nodeid,
+                uid,
+                gid,
+            },
+            args: Args { buf: body },
+        })
+    }
+}
+
+/// A cursor over a request body.
+#[derive(Clone, Copy, Debug)]
+pub struct Args<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Args<'a> {
+    /// The next `n` bytes.
+    pub const fn bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.buf.len() < n {
+            return None;
+        }
+        let (head, tail) = self.buf.split_at(n);
+        self.buf = tail;
+        Some(head)
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        self.bytes(4)?.try_into().ok().map(u32::from_ne_bytes)
+    }
+
+    pub fn u64(&mut self) -> Option<u64> {
+        self.bytes(8)?.try_into().ok().map(u64::from_ne_bytes)
+    }
+
+    /// A NUL-terminated name.
+    pub fn name(&mut self) -> Option<&'a OsStr> {
+        let end = self.buf.iter().position(|&b| b == 0)?;
+        let name = self.bytes(end)?;
+        self.bytes(1)?;
+        Some(OsStr::from_bytes(name))
+    }
+}
+
+/// A timestamp as the kernel sees it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Timestamp {
+    pub secs: i64,
+    pub nanos: u32,
+}
+
+/// File attributes (`struct fuse_attr`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Attr {
+    pub ino: u64,
+    pub size: u64,
+    pub blocks: u64,
+    pub atime: Timestamp,
+    pub mtime: Timestamp,
+    pub ctime: Timestamp,
+    pub mode: u32,
+    pub nlink: u32,
+    pub uid: u32,
+    pub gid: u32,
+    pub rdev: u32,
+    pub blksize: u32,
+}
+
+impl Attr {
+    fn encode(&self, out: &mut Vec<u8>) {
+        put_u64(out, self.ino);
+        put_u64(out, self.size);
+        put_u64(out, self.blocks);
+        out.extend_from_slice(&self.atime.secs.to_ne_bytes());
+        out.extend_from_slice(&self.mtime.secs.to_ne_bytes());
+        out.extend_from_slice(&self.ctime.secs.to_ne_bytes());
+        put_u32(out, self.atime.nanos);
+        put_u32(out, self.mtime.nanos);
+        put_u32(out, self.ctime.nanos);
+        put_u32(out, self.mode);
+        put_u32(out, self.nlink);
+        put_u32(out, self.uid);
+        put_u32(out, self.gid);
+        put_u32(out, self.rdev);
+        put_u32(out, self.blksize);
+        put_u32(out, 0); // flags
+    }
+}
+
+/// Filesystem statistics (`struct fuse_kstatfs`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StatFs {
+    pub blocks: u64,
+    pub bfree: u64,
+    pub bavail: u64,
+    pub files: u64,
+    pub ffree: u64,
+    pub bsize: u32,
+    pub namelen: u32,
+    pub frsize: u32,
+}
+
+/// How long the kernel may cache entries and attributes, in seconds.
+const TTL_SECS: u64 = 1;
+
+fn put_u32(out: &mut Vec<u8>, v: u32) {
+    out.extend_from_slice(&v.to_ne_bytes());
+}
+
+fn put_u64(out: &mut Vec<u8>, v: u64) {
+    out.extend_from_slice(&v.to_ne_bytes());
+}
+
+/// A successful reply carrying `body`.
+pub fn reply(unique: u64, body: &[u8]) -> Vec<u8> {
+    let len = u32::try_from(16 + body.len()).unwrap_or(u32::MAX);
+    let mut out = Vec::with_capacity(16 + body.len());
+    put_u32(&mut out, len);
+    out.extend_from_slice(&0_i32.to_ne_bytes());
+    put_u64(&mut out, unique);
+    out.extend_from_slice(body);
+    out
+}
+
+/// An error reply.
+pub fn error(unique: u64, errno: i32) -> Vec<u8> {
+    let mut out = Vec::with_capacity(16);
+    put_u32(&mut out, 16);
+    out.extend_from_slice(&(-errno).to_ne_bytes());
+    put_u64(&mut out, unique);
+    out
+}
+
+/// `struct fuse_entry_out`.
+pub fn entry_out(attr: &Attr) -> Vec<u8> {
+    let mut out = Vec::with_capacity(128);
+    put_u64(&mut out, attr.ino); // nodeid
+    put_u64(&mut out, 0); // generation
+    put_u64(&mut out, TTL_SECS); // entry_valid
+    put_u64(&mut out, TTL_SECS); // attr_valid
+    put_u32(&mut out, 0);
+    put_u32(&mut out, 0);
+    attr.encode(&mut out);
+    out
+}
+
+/// `struct fuse_attr_out`.
+pub fn attr_out(attr: &Attr) -> Vec<u8> {
+    let mut out = Vec::with_capacity(104);
+    put_u64(&mut out, TTL_SECS);
+    put_u32(&mut out, 0);
+    put_u32(&mut out, 0);
+    attr.encode(&mut out);
+    out
+}
+
+/// `struct fuse_open_out`.
+pub fn open_out(fh: u64) -> Vec<u8> {
+    let mut out = Vec::with_capacity(16);
+    put_u64(&mut out, fh);
+    put_u32(&mut out, 0);
+    put_u32(&mut out, 0);
+    out
+}
+
+/// `struct fuse_write_out`.
+pub fn write_out(size: u32) -> Vec<u8> {
+    let mut out = Vec::with_capacity(8);
+    put_u32(&mut out, size);
+    put_u32(&mut out, 0);
+    out
+}
+
+/// `struct fuse_statfs_out`.
+pub fn statfs_out(st: &StatFs) -> Vec<u8> {
+    let mut out = Vec::with_capacity(80);
+    put_u64(&mut out, st.blocks);
+    put_u64(&mut out, st.bfree);
+    put_u64(&mut out, st.bavail);
+    put_u64(&mut out, st.files);
+    put_u64(&mut out, st.ffree);
+    put_u32(&mut out, st.bsize);
+    put_u32(&mut out, st.namelen);
+    put_u32(&mut out, st.frsize);
+    out.resize(80, 0);
+    out
+}
+
+/// `struct fuse_init_out` for a kernel speaking `minor`.
+pub fn init_out(minor: u32, max_readahead: u32, flags: u32) -> Vec<u8> {
+    let mut out = Vec::with_capacity(64);
+    put_u32(&mut out, KERNEL_VERSION);
+    put_u32(&mut out, minor.min(KERNEL_MINOR_VERSION));
+    put_u32(&mut out, max_readahead);
+    put_u32(&mut out, flags);
+    out.extend_from_slice(&16_u16.to_ne_bytes()); // max_background
+    out.extend_from_slice(&12_u16.to_ne_bytes()); // congestion_threshold
+    put_u32(&mut out, MAX_WRITE);
+    // Kernels before 7.23 expect the 24-byte layout.
+    if minor < 23 {
+        return out;
+    }
+    put_u32(&mut out, 1); // time_gran
+    out.resize(64, 0);
+    out
+}
+
+/// Directory entries for a `READDIR` reply, capped at the requested size.
+pub struct DirBuf {
+    buf: Vec<u8>,
+    max: usize,
+}
+
+impl DirBuf {
+    pub const fn new(max: usize) -> Self {
+        Self {
+            buf: Vec::new(),
+            max,
+        }
+    }
+
+    /// Append an entry; `false` (and nothing appended) once full. `offset`
+    /// is the cookie the kernel passes back to continue after this entry.
+    pub fn push(&mut self, ino: u64, offset: u64, mode: u32, name: &OsStr) -> bool {
+        let name = name.as_bytes();
+        let entry_len = (24 + name.len() + 7) & !7;
+        if self.buf.len() + entry_len > self.max {
+            return false;
+        }
+        put_u64(&mut self.buf, ino);
+        put_u64(&mut self.buf, offset);
+        put_u32(&mut self.buf, u32::try_from(name.len()).unwrap_or(0));
+        put_u32(&mut self.buf, (mode & S_IFMT) >> 12);
+        self.buf.extend_from_slice(name);
+        let padded = self.buf.len().next_multiple_of(8);
+        self.buf.resize(padded, 0);
+        true
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(opcode: u32, body: &[u8]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        put_u32(&mut buf, u32::try_from(40 + body.len()).expect("len"));
+        put_u32(&mut buf, opcode);
+        put_u64(&mut buf, 7); // unique
+        put_u64(&mut buf, ROOT_ID);
+        put_u32(&mut buf, 1000);
+        put_u32(&mut buf, 100);
+        put_u32(&mut buf, 42);
+        put_u32(&mut buf, 0);
+        buf.extend_from_slice(body);
+        buf
+    }
+
+    #[test]
+    fn parses_header_and_names() {
+        let buf = request(opcode::RENAME, b"\x05\0\0\0\0\0\0\0old\0new\0");
+        let mut req = Request::parse(&buf).expect("parse");
+        assert_eq!(
+            req.header,
+            InHeader {
+                opcode: opcode::RENAME,
+                unique: 7,
+                nodeid: ROOT_ID,
+                uid: 1000,
+                gid: 100,
+            }
+        );
+        assert_eq!(req.args.u64(), Some(5));
+        assert_eq!(req.args.name(), Some(OsStr::new("old")));
+        assert_eq!(req.args.name(), Some(OsStr::new("new")));
+        assert_eq!(req.args.name(), None);
+        assert!(Request::parse(&buf[..30]).is_none());
+    }
+
+    #[test]
+    fn reply_structs_have_kernel_sizes() {
+        let attr = Attr::default();
+        assert_eq!(entry_out(&attr).len(), 128);
+        assert_eq!(attr_out(&attr).len(), 104);
+        assert_eq!(open_out(1).len(), 16);
+        assert_eq!(statfs_out(&StatFs::default()).len(), 80);
+        assert_eq!(init_out(31, 0, 0).len(), 64);
+        assert_eq!(init_out(22, 0, 0).len(), 24);
+
+        let err = error(9, errno::ENOENT);
+        assert_eq!(err.len(), 16);
+        assert_eq!(i32::from_ne_bytes(err[4..8].try_into().expect("i32")), -2);
+        assert_eq!(reply(9, b"abc").len(), 19);
+    }
+
+    #[test]
+    fn dirbuf_pads_entries_and_stops_when_full() {
+        let mut dir = DirBuf::new(64);
+        assert!(dir.push(1, 1, S_IFDIR, OsStr::new(".")));
+        assert!(dir.push(2, 2, S_IFREG, OsStr::new("file.rs")));
+        assert!(!dir.push(3, 3, S_IFREG, OsStr::new("x")));
+        let buf = dir.into_inner();
+        assert_eq!(buf.len(), 32 + 32);
+        assert_eq!(u32::from_ne_bytes(buf[52..56].try_into().expect("u32")), 8);
+    }
+}
@@ -0,0 +1,239 @@
+//! Mounting `/dev/fuse` and running the request loop.
+//!
+//! As root the mount is made directly with `mount(2)`. Otherwise the setuid
+//! `fusermount3` (or `fusermount`) helper mounts it and hands the
+//! `/dev/fuse` descriptor back over a socket, the way libfuse does.
+
+use std::ffi::CString;
+use std::fmt::Write as _;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, IoSliceMut, Read as _, Write as _};
+use std::mem::MaybeUninit;
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use rustix::io::Errno;
+use rustix::mount::{MountFlags, UnmountFlags};
+use rustix::net::{
+    AddressFamily, RecvAncillaryBuffer, RecvAncillaryMessage, RecvFlags, SocketFlags, SocketType,
+};
+
+use super::layer::Layer;
+use super::proto::{self, Request, opcode};
+
+/// Filesystem type the mounts show up as in `/proc/mounts`.
+const FS_TYPE: &str = "fuse.maw";
+
+/// Whether a workspace mount point is being served.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MountState {
+    /// Mounted, with a daemon answering requests.
+    Live,
+    /// Mounted, but the daemon is gone (`ENOTCONN`).
+    Dead,
+    /// Not mounted.
+    Absent,
+}
+
+/// Whether this process can mount FUSE filesystems.
+pub fn available() -> bool {
+    Path::new("/dev/fuse").exists()
+        && (rustix::process::geteuid().is_root() || fusermount().is_some())
+}
+
+/// The state of the mount at `mount_point`.
+pub fn mount_state(mount_point: &Path) -> MountState {
+    let target =
+        escape(&fs::canonicalize(mount_point).unwrap_or_else(|_| mount_point.to_path_buf()));
+    let mounted = fs::read_to_string("/proc/mounts").is_ok_and(|mounts| {
+        mounts.lines().any(|line| {
+            let mut fields = line.split(' ');
+            fields.nth(1) == Some(target.as_str()) && fields.next() == Some(FS_TYPE)
+        })
+    });
+    if !mounted {
+        return MountState::Absent;
+    }
+    match fs::metadata(mount_point) {
+        Err(e) if e.raw_os_error() == Some(Errno::NOTCONN.raw_os_error()) => MountState::Dead,
+        _ => MountState::Live,
+    }
+}
+
+/// `/proc/mounts` escapes whitespace and backslashes as octal.
+fn escape(path: &Path) -> String {
+    let mut out = String::new();
+    for c in path.to_string_lossy().chars() {
+        match c {
+            ' ' | '\t' | '\n' | '\\' => {
+                let _ = write!(out, "\\{:03o}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Mount an empty FUSE filesystem at `mount_point` and return the
+/// `/dev/fuse` descriptor to serve it from.
+///
+/// # Errors
+/// Returns an error if the mount fails.
+pub fn mount(mount_point: &Path) -> io::Result<File> {
+    if mount_state(mount_point) != MountState::Absent {
+        // A dead mount from a crashed daemon: clear it before remounting.
+        unmount(mount_point)?;
+    }
+    if rustix::process::geteuid().is_root() {
+        mount_direct(mount_point)
+    } else {
+        mount_with_helper(mount_point)
+    }
+}
+
+fn mount_direct(mount_point: &Path) -> io::Result<File> {
+    let dev = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/fuse")?;
+    let options = format!(
+        "fd={},rootmode=40000,user_id={},group_id={},default_permissions",
+        dev.as_raw_fd(),
+        rustix::process::getuid().as_raw(),
+        rustix::process::getgid().as_raw(),
+    );
+    let options = CString::new(options).map_err(io::Error::other)?;
+    rustix::mount::mount(
+        "maw",
+        mount_point,
+        FS_TYPE,
+        MountFlags::NOSUID | MountFlags::NODEV,
+        options.as_c_str(),
+    )?;
+    Ok(dev)
+}
+
+fn mount_with_helper(mount_point: &Path) -> io::Result<File> {
+    let helper = fusermount().ok_or_else(|| io::Error::other("fusermount3 not found on PATH"))?;
+    // The helper's end must survive exec; ours must not leak to it.
+    let (ours, theirs) = rustix::net::socketpair(
+        AddressFamily::UNIX,
+        SocketType::STREAM,
+        SocketFlags::empty(),
+        None,
+    )?;
+    rustix::io::fcntl_setfd(&ours, rustix::io::FdFlags::CLOEXEC)?;
+    let child = Command::new(&helper)
+        .args([
+            "-o",
+            "nosuid,nodev,default_permissions,fsname=maw,subtype=maw",
+            "--",
+        ])
+        .arg(mount_point)
+        .env("_FUSE_COMMFD", theirs.as_raw_fd().to_string())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    drop(theirs);
+
+    let mut space = [MaybeUninit::uninit(); rustix::cmsg_space!(ScmRights(1))];
+    let mut control = RecvAncillaryBuffer::new(&mut space);
+    let mut byte = [0_u8; 1];
+    let received = rustix::net::recvmsg(
+        &ours,
+        &mut [IoSliceMut::new(&mut byte)],
+        &mut control,
+        RecvFlags::CMSG_CLOEXEC,
+    );
+    let mut dev = None;
+    for message in control.drain() {
+        if let RecvAncillaryMessage::ScmRights(mut fds) = message {
+            dev = dev.or_else(|| fds.next());
+        }
+    }
+    let output = child.wait_with_output()?;
+    match dev {
+        Some(fd) if output.status.success() => Ok(File::from(fd)),
+        _ => {
+            received?;
+            Err(io::Error::other(format!(
+                "{} failed: {}",
+                helper.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )))
+        }
+    }
+}
+
+/// Lazily unmount `mount_point`; the daemon exits once the kernel lets go.
+///
+/// # Errors
+/// Returns an error if the unmount fails.
+pub fn unmount(mount_point: &Path) -> io::Result<()> {
+    if rustix::process::geteuid().is_root() {
+        return Ok(rustix::mount::unmount(mount_point, UnmountFlags::DETACH)?);
+    }
+    let helper = fusermount().ok_or_else(|| io::Error::other("fusermount3 not found on PATH"))?;
+    let output = Command::new(&helper)
+        .args(["-u", "-z", "--"])
+        .arg(mount_point)
+        .stdin(Stdio::null())
+        .output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "{} -u failed: {}",
+            helper.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// The `fusermount3` or `fusermount` binary on `PATH`.
+fn fusermount() -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    ["fusermount3", "fusermount"].iter().find_map(|name| {
+        std::env::split_paths(&path)
+            .map(|dir| dir.join(name))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// Answer requests from `dev` until the filesystem is unmounted.
+///
+/// # Errors
+/// Returns an error if `/dev/fuse` cannot be read or written.
+pub fn serve(dev: &File, layer: &mut Layer) -> io::Result<()> {
+    let mut buf = vec![0; proto::BUFFER_SIZE];
+    let mut dev = dev;
+    loop {
+        let n = match dev.read(&mut buf) {
+            Ok(n) => n,
+            Err(e) => match Errno::from_io_error(&e) {
+                // The request was interrupted before we read it.
+                Some(Errno::NOENT | Errno::INTR | Errno::AGAIN) => continue,
+                // Unmounted.
+                Some(Errno::NODEV) => return Ok(()),
+                _ => return Err(e),
+            },
+        };
+        let Some(request) = Request::parse(&buf[..n]) else {
+            continue;
+        };
+        let done = request.header.opcode == opcode::DESTROY;
+        if let Some(reply) = layer.dispatch(request) {
+            match dev.write(&reply) {
+                // The request was interrupted while we answered it.
+                Err(e) if Errno::from_io_error(&e) == Some(Errno::NOENT) => {}
+                Err(e) => return Err(e),
+                Ok(_) => {}
+            }
+        }
+        if done {
+            return Ok(());
+        }
+    }
+}
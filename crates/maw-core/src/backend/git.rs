@@ -12,6 +12,7 @@ use std::path::{Path, PathBuf};
 #[cfg(test)]
 use std::process::Command;
 
+use super::sparse::SparseSpec;
 use super::{SnapshotResult, WorkspaceBackend, WorkspaceStatus};
 use crate::config::ManifoldConfig;
 use crate::model::types::{
@@ -278,12 +279,15 @@ impl GitWorktreeBackend {
             }
         })
     }
-}
-
-impl WorkspaceBackend for GitWorktreeBackend {
-    type Error = GitBackendError;
 
-    fn create(&self, name: &WorkspaceId, epoch: &EpochId) -> Result<WorkspaceInfo, Self::Error> {
+    /// Shared implementation of [`WorkspaceBackend::create`] and
+    /// [`WorkspaceBackend::create_sparse`].
+    fn create_with(
+        &self,
+        name: &WorkspaceId,
+        epoch: &EpochId,
+        sparse: Option<&SparseSpec>,
+    ) -> Result<WorkspaceInfo, GitBackendError> {
         let path = self.workspace_path(name);
 
         // Idempotency: if valid workspace exists, return it
@@ -323,13 +327,33 @@ impl WorkspaceBackend for GitWorktreeBackend {
                     exit_code: None,
                 })?;
 
-        if let Err(e) = self.repo.worktree_add(name.as_str(), target_oid, &path) {
+        let added = sparse.map_or_else(
+            || self.repo.worktree_add(name.as_str(), target_oid, &path),
+            |spec| {
+                let patterns = spec.sparse_checkout_lines();
+                let include = |p: &str| spec.contains(Path::new(p));
+                self.repo.worktree_add_sparse(
+                    name.as_str(),
+                    target_oid,
+                    &path,
+                    &maw_git::SparseCheckout {
+                        cone: spec.is_cone(),
+                        patterns: &patterns,
+                        include: &include,
+                    },
+                )
+            },
+        );
+        if let Err(e) = added {
             // Clean up partial state
             if path.exists() {
                 let _ = std::fs::remove_dir_all(&path);
             }
             return Err(map_git_error("worktree add", &e));
         }
+        if let Some(spec) = sparse {
+            spec.write_for_worktree(&path)?;
+        }
 
         // Record the creation epoch so status() can distinguish
         // "HEAD advanced because the agent committed" from "HEAD is the epoch".
@@ -356,6 +380,26 @@ impl WorkspaceBackend for GitWorktreeBackend {
             commits_ahead: 0,
         })
     }
+}
+
+impl WorkspaceBackend for GitWorktreeBackend {
+    type Error = GitBackendError;
+
+    fn create(&self, name: &WorkspaceId, epoch: &EpochId) -> Result<WorkspaceInfo, Self::Error> {
+        self.create_with(name, epoch, None)
+    }
+
+    /// Create a sparse workspace: entries outside `sparse` are marked
+    /// skip-worktree and not checked out, and the worktree gets git's
+    /// sparse-checkout config so `git` run inside it keeps the selection.
+    fn create_sparse(
+        &self,
+        name: &WorkspaceId,
+        epoch: &EpochId,
+        sparse: &SparseSpec,
+    ) -> Result<WorkspaceInfo, Self::Error> {
+        self.create_with(name, epoch, Some(sparse))
+    }
 
     /// Destroy a workspace by removing its git worktree.
     ///
@@ -1163,6 +1207,51 @@ mod tests {
         assert_eq!(snap.deleted[0], PathBuf::from("README.md"));
     }
 
+    #[test]
+    fn test_create_sparse_skips_unselected_paths() {
+        let (temp_dir, _epoch) = setup_git_repo();
+        let root = temp_dir.path().to_path_buf();
+        for file in ["app/main.rs", "docs/guide.md"] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().expect("has parent")).expect("mkdir");
+            fs::write(&path, "x\n").expect("write");
+        }
+        GitWorktreeBackend::git_stdout_in(&root, &["add", "-A"]).expect("git add");
+        GitWorktreeBackend::git_stdout_in(&root, &["commit", "-qm", "dirs"]).expect("commit");
+        let head = GitWorktreeBackend::git_stdout_in(&root, &["rev-parse", "HEAD"]).expect("head");
+        let epoch = EpochId::new(head.trim()).expect("operation should succeed");
+
+        let backend = GitWorktreeBackend::new(root.clone());
+        let ws_name = WorkspaceId::new("sparse-ws").expect("operation should succeed");
+        let spec = SparseSpec::new(["app"]).expect("valid spec");
+        let info = backend
+            .create_sparse(&ws_name, &epoch, &spec)
+            .expect("operation should succeed");
+        assert!(info.path.join("README.md").exists());
+        assert!(info.path.join("app/main.rs").exists());
+        assert!(!info.path.join("docs").exists());
+        assert_eq!(SparseSpec::load(&info.path), Some(spec));
+
+        // git itself agrees the missing paths are unchanged.
+        let status = GitWorktreeBackend::git_stdout_in(&info.path, &["status", "--porcelain"])
+            .expect("git status");
+        assert!(status.trim().is_empty(), "clean status: {status}");
+
+        let snap = backend
+            .snapshot(&ws_name)
+            .expect("operation should succeed");
+        assert!(
+            snap.is_empty(),
+            "missing sparse paths are unchanged: {snap:?}"
+        );
+
+        fs::remove_file(info.path.join("app/main.rs")).expect("operation should succeed");
+        let snap = backend
+            .snapshot(&ws_name)
+            .expect("operation should succeed");
+        assert_eq!(snap.deleted, vec![PathBuf::from("app/main.rs")]);
+    }
+
     #[test]
     fn test_snapshot_mixed_changes() {
         let (temp_dir, epoch) = setup_git_repo();
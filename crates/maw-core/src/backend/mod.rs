@@ -51,6 +51,26 @@ pub trait WorkspaceBackend {
     /// - The workspace is isolated from all other workspaces
     fn create(&self, name: &WorkspaceId, epoch: &EpochId) -> Result<WorkspaceInfo, Self::Error>;
 
+    /// Create a sparse workspace.
+    ///
+    /// Like [`create`](Self::create), but only the paths inside `sparse` are
+    /// materialized. Paths outside it are absent from the working copy and
+    /// count as unchanged — [`snapshot`](Self::snapshot) never reports them
+    /// as deleted. See [`sparse`] for the path rules.
+    ///
+    /// The default creates a full workspace, which is always a superset of
+    /// the requested paths. Backends that materialize nothing up front
+    /// (overlay) keep it.
+    fn create_sparse(
+        &self,
+        name: &WorkspaceId,
+        epoch: &EpochId,
+        sparse: &sparse::SparseSpec,
+    ) -> Result<WorkspaceInfo, Self::Error> {
+        let _ = sparse;
+        self.create(name, epoch)
+    }
+
     /// Destroy a workspace.
     ///
     /// Removes the workspace from the system. The workspace directory and all
@@ -340,14 +360,18 @@ mod tests {
         assert!(all.contains(&&PathBuf::from("deprecated.rs")));
     }
 }
+pub mod disk_usage;
+pub mod fuse;
 pub mod overlay;
 pub mod platform;
+pub mod sparse;
 
 // ---------------------------------------------------------------------------
 // AnyBackend — polymorphic backend enum
 // ---------------------------------------------------------------------------
 
 use copy::CopyBackend;
+use fuse::{DaemonCommand, FuseBackend};
 use git::GitWorktreeBackend;
 use overlay::OverlayBackend;
 use reflink::RefLinkBackend;
@@ -390,6 +414,8 @@ pub enum AnyBackend {
     Reflink(RefLinkBackend),
     /// `OverlayFS` backend — Linux only.
     Overlay(OverlayBackend),
+    /// FUSE backend serving the epoch from the object database — Linux only.
+    Fuse(FuseBackend),
     /// Plain recursive-copy backend — universal fallback.
     Copy(CopyBackend),
 }
@@ -398,12 +424,18 @@ impl AnyBackend {
     /// Construct the appropriate backend for the resolved (non-Auto) kind and repo root.
     ///
     /// If `kind` is `BackendKind::Auto` (which should be resolved before calling
-    /// this function), falls back to `GitWorktree`.
+    /// this function), falls back to `GitWorktree`. `fuse_daemon` is the
+    /// command the FUSE backend starts its daemons with; other kinds ignore it.
     ///
     /// # Errors
-    /// Returns an error if the overlay backend is selected but is not supported
-    /// on this platform (not Linux, or no mount strategy available).
-    pub fn from_kind(kind: BackendKind, root: PathBuf) -> anyhow::Result<Self> {
+    /// Returns an error if the overlay or FUSE backend is selected but is not
+    /// supported on this platform (not Linux, or no way to mount), or the FUSE
+    /// backend is selected without a `fuse_daemon`.
+    pub fn from_kind(
+        kind: BackendKind,
+        root: PathBuf,
+        fuse_daemon: Option<DaemonCommand>,
+    ) -> anyhow::Result<Self> {
         match kind {
             BackendKind::GitWorktree | BackendKind::Auto => {
                 Ok(Self::GitWorktree(GitWorktreeBackend::new(root)))
@@ -413,9 +445,46 @@ impl AnyBackend {
                 let backend = OverlayBackend::new(root).map_err(|e| anyhow::anyhow!("{e}"))?;
                 Ok(Self::Overlay(backend))
             }
+            BackendKind::Fuse => {
+                let daemon = fuse_daemon.ok_or_else(|| {
+                    anyhow::anyhow!("the FUSE backend needs a daemon command to mount workspaces")
+                })?;
+                let backend = FuseBackend::new(root, daemon).map_err(|e| anyhow::anyhow!("{e}"))?;
+                Ok(Self::Fuse(backend))
+            }
             BackendKind::Copy => Ok(Self::Copy(CopyBackend::new(root))),
         }
     }
+
+    /// Disk usage of a workspace, split into exclusive and shared bytes.
+    ///
+    /// Overlay workspaces share their epoch snapshot; reflink workspaces
+    /// share untouched clones when `caps` reports working reflinks; FUSE
+    /// workspaces count only their upper layer. The other backends share
+    /// nothing. See [`disk_usage`] for the details.
+    #[must_use]
+    pub fn disk_usage(
+        &self,
+        info: &WorkspaceInfo,
+        caps: &platform::PlatformCapabilities,
+    ) -> disk_usage::DiskUsage {
+        match self {
+            Self::GitWorktree(_) | Self::Copy(_) => disk_usage::exclusive_usage(&info.path, &[]),
+            Self::Reflink(b) => disk_usage::reflink_usage_for(
+                caps,
+                &info.path,
+                &b.epoch_snapshot_path(&info.epoch),
+                &info.path.join(reflink::EPOCH_FILE),
+            ),
+            Self::Overlay(b) => disk_usage::overlay_usage(
+                &b.upper_dir(&info.id),
+                &b.epoch_snapshot_dir(&info.epoch),
+            ),
+            // The epoch lives in the object database; only the upper layer
+            // takes space of its own.
+            Self::Fuse(b) => disk_usage::exclusive_usage(&b.upper_dir(&info.id), &[]),
+        }
+    }
 }
 
 /// Helper: convert a backend-specific error into [`AnyBackendError`].
@@ -434,15 +503,32 @@ impl WorkspaceBackend for AnyBackend {
             Self::GitWorktree(b) => b.create(name, epoch).map_err(wrap_err),
             Self::Reflink(b) => b.create(name, epoch).map_err(wrap_err),
             Self::Overlay(b) => b.create(name, epoch).map_err(wrap_err),
+            Self::Fuse(b) => b.create(name, epoch).map_err(wrap_err),
             Self::Copy(b) => b.create(name, epoch).map_err(wrap_err),
         }
     }
 
+    fn create_sparse(
+        &self,
+        name: &WorkspaceId,
+        epoch: &EpochId,
+        sparse: &sparse::SparseSpec,
+    ) -> Result<WorkspaceInfo, Self::Error> {
+        match self {
+            Self::GitWorktree(b) => b.create_sparse(name, epoch, sparse).map_err(wrap_err),
+            Self::Reflink(b) => b.create_sparse(name, epoch, sparse).map_err(wrap_err),
+            Self::Overlay(b) => b.create_sparse(name, epoch, sparse).map_err(wrap_err),
+            Self::Fuse(b) => b.create_sparse(name, epoch, sparse).map_err(wrap_err),
+            Self::Copy(b) => b.create_sparse(name, epoch, sparse).map_err(wrap_err),
+        }
+    }
+
     fn destroy(&self, name: &WorkspaceId) -> Result<(), Self::Error> {
         match self {
             Self::GitWorktree(b) => b.destroy(name).map_err(wrap_err),
             Self::Reflink(b) => b.destroy(name).map_err(wrap_err),
             Self::Overlay(b) => b.destroy(name).map_err(wrap_err),
+            Self::Fuse(b) => b.destroy(name).map_err(wrap_err),
             Self::Copy(b) => b.destroy(name).map_err(wrap_err),
         }
     }
@@ -452,6 +538,7 @@ impl WorkspaceBackend for AnyBackend {
             Self::GitWorktree(b) => b.list().map_err(wrap_err),
             Self::Reflink(b) => b.list().map_err(wrap_err),
             Self::Overlay(b) => b.list().map_err(wrap_err),
+            Self::Fuse(b) => b.list().map_err(wrap_err),
             Self::Copy(b) => b.list().map_err(wrap_err),
         }
     }
@@ -461,6 +548,7 @@ impl WorkspaceBackend for AnyBackend {
             Self::GitWorktree(b) => b.status(name).map_err(wrap_err),
             Self::Reflink(b) => b.status(name).map_err(wrap_err),
             Self::Overlay(b) => b.status(name).map_err(wrap_err),
+            Self::Fuse(b) => b.status(name).map_err(wrap_err),
             Self::Copy(b) => b.status(name).map_err(wrap_err),
         }
     }
@@ -470,6 +558,7 @@ impl WorkspaceBackend for AnyBackend {
             Self::GitWorktree(b) => b.snapshot(name).map_err(wrap_err),
             Self::Reflink(b) => b.snapshot(name).map_err(wrap_err),
             Self::Overlay(b) => b.snapshot(name).map_err(wrap_err),
+            Self::Fuse(b) => b.snapshot(name).map_err(wrap_err),
             Self::Copy(b) => b.snapshot(name).map_err(wrap_err),
         }
     }
@@ -479,6 +568,7 @@ impl WorkspaceBackend for AnyBackend {
             Self::GitWorktree(b) => b.workspace_path(name),
             Self::Reflink(b) => b.workspace_path(name),
             Self::Overlay(b) => b.workspace_path(name),
+            Self::Fuse(b) => b.workspace_path(name),
             Self::Copy(b) => b.workspace_path(name),
         }
     }
@@ -488,6 +578,7 @@ impl WorkspaceBackend for AnyBackend {
             Self::GitWorktree(b) => b.exists(name),
             Self::Reflink(b) => b.exists(name),
             Self::Overlay(b) => b.exists(name),
+            Self::Fuse(b) => b.exists(name),
             Self::Copy(b) => b.exists(name),
         }
     }
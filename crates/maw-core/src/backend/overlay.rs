@@ -203,14 +203,14 @@ impl OverlayBackend {
     }
 
     /// `.../epochs/e-{hash}/` — immutable epoch snapshot (lowerdir).
-    fn epoch_snapshot_dir(&self, epoch: &EpochId) -> PathBuf {
+    pub(crate) fn epoch_snapshot_dir(&self, epoch: &EpochId) -> PathBuf {
         self.manifold_root()
             .join("epochs")
             .join(format!("e-{}", epoch.as_str()))
     }
 
     /// `.../cow/<name>/upper/` — per-workspace writable layer.
-    fn upper_dir(&self, name: &WorkspaceId) -> PathBuf {
+    pub(crate) fn upper_dir(&self, name: &WorkspaceId) -> PathBuf {
         self.manifold_root()
             .join("cow")
             .join(name.as_str())
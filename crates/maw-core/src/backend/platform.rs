@@ -11,11 +11,15 @@ use serde::{Deserialize, Serialize};
 
 use crate::config::BackendKind;
 
-const CACHE_SCHEMA_VERSION: u32 = 1;
+const CACHE_SCHEMA_VERSION: u32 = 2;
 const REF_LINK_THRESHOLD_FILES: usize = 30_000;
 const OVERLAY_THRESHOLD_FILES: usize = 100_000;
 
 /// Detected host/platform capabilities for workspace backend selection.
+///
+/// Each bool is an independent probe result, so `struct_excessive_bools` is
+/// silenced.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PlatformCapabilities {
     /// Cache schema version for future migrations.
@@ -26,6 +30,9 @@ pub struct PlatformCapabilities {
     pub overlay_userns_supported: bool,
     /// `fuse-overlayfs` binary availability on compatible Linux kernels.
     pub fuse_overlayfs_available: bool,
+    /// `/dev/fuse` is present and this user can mount it (root, or a
+    /// `fusermount3` helper on `PATH`). Gates the `fuse` backend.
+    pub fuse_available: bool,
     /// Parsed kernel major.minor (Linux only).
     pub kernel_major: Option<u32>,
     pub kernel_minor: Option<u32>,
@@ -38,6 +45,7 @@ impl Default for PlatformCapabilities {
             reflink_supported: false,
             overlay_userns_supported: false,
             fuse_overlayfs_available: false,
+            fuse_available: false,
             kernel_major: None,
             kernel_minor: None,
         }
@@ -96,12 +104,14 @@ pub fn detect_platform_capabilities() -> PlatformCapabilities {
     let reflink_supported = detect_reflink_support();
     let overlay_userns_supported = detect_overlay_userns_support(kernel_major, kernel_minor);
     let fuse_overlayfs_available = detect_fuse_overlayfs(kernel_major, kernel_minor);
+    let fuse_available = super::fuse::is_available();
 
     PlatformCapabilities {
         schema_version: CACHE_SCHEMA_VERSION,
         reflink_supported,
         overlay_userns_supported,
         fuse_overlayfs_available,
+        fuse_available,
         kernel_major,
         kernel_minor,
     }
@@ -114,6 +124,9 @@ pub fn detect_platform_capabilities() -> PlatformCapabilities {
 /// 2. reflink (when supported and repo > 30k files)
 /// 3. overlay (when supported and repo > 100k files)
 /// 4. copy fallback
+///
+/// `fuse` is never auto-selected; when configured without FUSE support it
+/// falls back to copy, like reflink and overlay.
 #[must_use]
 pub const fn resolve_backend_kind(
     configured: BackendKind,
@@ -136,6 +149,13 @@ pub const fn resolve_backend_kind(
                 BackendKind::Copy
             }
         }
+        BackendKind::Fuse => {
+            if caps.fuse_available {
+                BackendKind::Fuse
+            } else {
+                BackendKind::Copy
+            }
+        }
         other => other,
     }
 }
@@ -360,6 +380,7 @@ mod tests {
             reflink_supported: true,
             overlay_userns_supported: false,
             fuse_overlayfs_available: true,
+            fuse_available: false,
             kernel_major: Some(6),
             kernel_minor: Some(8),
         };
@@ -376,6 +397,7 @@ mod tests {
             reflink_supported: false,
             overlay_userns_supported: false,
             fuse_overlayfs_available: false,
+            fuse_available: false,
             kernel_major: Some(6),
             kernel_minor: Some(8),
         };
@@ -397,6 +419,7 @@ mod tests {
             reflink_supported: true,
             overlay_userns_supported: true,
             fuse_overlayfs_available: true,
+            fuse_available: false,
             kernel_major: Some(6),
             kernel_minor: Some(8),
         };
@@ -418,6 +441,7 @@ mod tests {
             reflink_supported: true,
             overlay_userns_supported: false,
             fuse_overlayfs_available: false,
+            fuse_available: false,
             kernel_major: Some(6),
             kernel_minor: Some(8),
         };
@@ -433,6 +457,7 @@ mod tests {
             reflink_supported: true,
             overlay_userns_supported: true,
             fuse_overlayfs_available: true,
+            fuse_available: false,
             kernel_major: Some(6),
             kernel_minor: Some(8),
         };
@@ -448,6 +473,7 @@ mod tests {
             reflink_supported: true,
             overlay_userns_supported: false,
             fuse_overlayfs_available: false,
+            fuse_available: false,
             kernel_major: Some(6),
             kernel_minor: Some(8),
         };
@@ -462,6 +488,7 @@ mod tests {
             reflink_supported: false,
             overlay_userns_supported: false,
             fuse_overlayfs_available: false,
+            fuse_available: false,
             kernel_major: None,
             kernel_minor: None,
         };
@@ -548,6 +575,12 @@ mod tests {
             BackendKind::Copy
         );
 
+        // fuse: falls back to copy when not supported.
+        assert_eq!(
+            resolve_backend_kind(BackendKind::Fuse, 0, &caps_none),
+            BackendKind::Copy
+        );
+
         // reflink: passes through when supported.
         let caps_reflink = PlatformCapabilities {
             reflink_supported: true,
@@ -567,5 +600,19 @@ mod tests {
             resolve_backend_kind(BackendKind::Overlay, 0, &caps_overlay),
             BackendKind::Overlay
         );
+
+        // fuse: passes through when supported, and is never auto-selected.
+        let caps_fuse = PlatformCapabilities {
+            fuse_available: true,
+            ..PlatformCapabilities::default()
+        };
+        assert_eq!(
+            resolve_backend_kind(BackendKind::Fuse, 0, &caps_fuse),
+            BackendKind::Fuse
+        );
+        assert_ne!(
+            resolve_backend_kind(BackendKind::Auto, 1_000_000, &caps_fuse),
+            BackendKind::Fuse
+        );
     }
 }
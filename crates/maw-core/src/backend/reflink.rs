@@ -28,6 +28,7 @@ use std::fmt;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
+use super::sparse::{SPARSE_FILE, SparseSpec};
 use super::{SnapshotResult, WorkspaceBackend, WorkspaceStatus};
 use crate::model::types::{EpochId, WorkspaceId, WorkspaceInfo, WorkspaceMode, WorkspaceState};
 
@@ -39,7 +40,7 @@ use crate::model::types::{EpochId, WorkspaceId, WorkspaceInfo, WorkspaceMode, Wo
 ///
 /// Contains the base epoch OID (exactly 40 lowercase hex characters) followed
 /// by a newline. This file is excluded from snapshot comparisons.
-const EPOCH_FILE: &str = ".maw-epoch";
+pub(crate) const EPOCH_FILE: &str = ".maw-epoch";
 
 // ---------------------------------------------------------------------------
 // Error type
@@ -165,7 +166,7 @@ impl RefLinkBackend {
     /// Path to the epoch snapshot directory for a given epoch.
     ///
     /// e.g. `/repo/.manifold/epochs/e-abc123.../`
-    fn epoch_snapshot_path(&self, epoch: &EpochId) -> PathBuf {
+    pub(crate) fn epoch_snapshot_path(&self, epoch: &EpochId) -> PathBuf {
         crate::model::layout::LayoutFlavor::detect_with_env(&self.root)
             .manifold_dir(&self.root)
             .join("epochs")
@@ -215,6 +216,76 @@ impl RefLinkBackend {
         }
     }
 
+    /// Shared implementation of [`WorkspaceBackend::create`] and
+    /// [`WorkspaceBackend::create_sparse`].
+    fn create_with(
+        &self,
+        name: &WorkspaceId,
+        epoch: &EpochId,
+        sparse: Option<&SparseSpec>,
+    ) -> Result<WorkspaceInfo, ReflinkBackendError> {
+        let ws_path = self.workspace_path(name);
+
+        // Idempotency: if workspace already exists with correct epoch, return it.
+        if ws_path.exists() {
+            if let Ok(existing_epoch) = Self::read_epoch_file(&ws_path, name.as_str())
+                && existing_epoch == *epoch
+            {
+                return Ok(WorkspaceInfo {
+                    id: name.clone(),
+                    path: ws_path,
+                    epoch: epoch.clone(),
+                    state: WorkspaceState::Active,
+                    mode: WorkspaceMode::default(),
+                    commits_ahead: 0,
+                });
+            }
+            // Partial/mismatched workspace: remove and recreate.
+            std::fs::remove_dir_all(&ws_path)?;
+        }
+
+        // Verify the epoch snapshot exists.
+        let snapshot_path = self.epoch_snapshot_path(epoch);
+        if !snapshot_path.exists() {
+            return Err(ReflinkBackendError::EpochSnapshotMissing {
+                epoch: epoch.as_str().to_owned(),
+            });
+        }
+
+        // Ensure the ws/ parent directory exists.
+        let ws_dir = self.workspaces_dir();
+        std::fs::create_dir_all(&ws_dir)?;
+
+        // Reflink-copy the snapshot (or its sparse selection) into the
+        // workspace directory.
+        if let Some(spec) = sparse {
+            std::fs::create_dir_all(&ws_path)?;
+            let files = collect_files(&snapshot_path, &[]);
+            for root in spec.roots(files.iter().map(PathBuf::as_path)) {
+                let dst = ws_path.join(&root);
+                if let Some(parent) = dst.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                Self::reflink_copy(&snapshot_path.join(&root), &dst)?;
+            }
+            spec.write(&ws_path)?;
+        } else {
+            Self::reflink_copy(&snapshot_path, &ws_path)?;
+        }
+
+        // Write the base epoch identifier into the workspace.
+        Self::write_epoch_file(&ws_path, epoch)?;
+
+        Ok(WorkspaceInfo {
+            id: name.clone(),
+            path: ws_path,
+            epoch: epoch.clone(),
+            state: WorkspaceState::Active,
+            mode: WorkspaceMode::default(),
+            commits_ahead: 0,
+        })
+    }
+
     /// Copy `src` into `dst` using `cp --reflink=auto -r`.
     ///
     /// On `CoW` filesystems (Btrfs, XFS, APFS) this is nearly instant.
@@ -299,52 +370,19 @@ impl WorkspaceBackend for RefLinkBackend {
     ///
     /// If a valid workspace already exists (idempotency), returns its info.
     fn create(&self, name: &WorkspaceId, epoch: &EpochId) -> Result<WorkspaceInfo, Self::Error> {
-        let ws_path = self.workspace_path(name);
-
-        // Idempotency: if workspace already exists with correct epoch, return it.
-        if ws_path.exists() {
-            if let Ok(existing_epoch) = Self::read_epoch_file(&ws_path, name.as_str())
-                && existing_epoch == *epoch
-            {
-                return Ok(WorkspaceInfo {
-                    id: name.clone(),
-                    path: ws_path,
-                    epoch: epoch.clone(),
-                    state: WorkspaceState::Active,
-                    mode: WorkspaceMode::default(),
-                    commits_ahead: 0,
-                });
-            }
-            // Partial/mismatched workspace: remove and recreate.
-            std::fs::remove_dir_all(&ws_path)?;
-        }
-
-        // Verify the epoch snapshot exists.
-        let snapshot_path = self.epoch_snapshot_path(epoch);
-        if !snapshot_path.exists() {
-            return Err(ReflinkBackendError::EpochSnapshotMissing {
-                epoch: epoch.as_str().to_owned(),
-            });
-        }
-
-        // Ensure the ws/ parent directory exists.
-        let ws_dir = self.workspaces_dir();
-        std::fs::create_dir_all(&ws_dir)?;
-
-        // Reflink-copy the snapshot into the workspace directory.
-        Self::reflink_copy(&snapshot_path, &ws_path)?;
-
-        // Write the base epoch identifier into the workspace.
-        Self::write_epoch_file(&ws_path, epoch)?;
+        self.create_with(name, epoch, None)
+    }
 
-        Ok(WorkspaceInfo {
-            id: name.clone(),
-            path: ws_path,
-            epoch: epoch.clone(),
-            state: WorkspaceState::Active,
-            mode: WorkspaceMode::default(),
-            commits_ahead: 0,
-        })
+    /// Create a sparse workspace by cloning only the paths of the epoch
+    /// snapshot inside `sparse`. The spec is recorded in [`SPARSE_FILE`] so
+    /// snapshots treat the missing paths as unchanged.
+    fn create_sparse(
+        &self,
+        name: &WorkspaceId,
+        epoch: &EpochId,
+        sparse: &SparseSpec,
+    ) -> Result<WorkspaceInfo, Self::Error> {
+        self.create_with(name, epoch, Some(sparse))
     }
 
     /// Destroy a workspace by removing its directory.
@@ -453,6 +491,7 @@ impl WorkspaceBackend for RefLinkBackend {
     ///
     /// Files excluded from comparison:
     /// - `.maw-epoch` (backend metadata)
+    /// - `.maw-sparse` (sparse spec; paths outside it are never deleted)
     fn snapshot(&self, name: &WorkspaceId) -> Result<SnapshotResult, Self::Error> {
         let ws_path = self.workspace_path(name);
         if !ws_path.exists() {
@@ -485,7 +524,7 @@ impl WorkspaceBackend for RefLinkBackend {
 ///
 /// These are backend-internal metadata files that should never appear in the
 /// diff output, even though they live inside the workspace directory.
-const EXCLUDED_NAMES: &[&str] = &[EPOCH_FILE];
+const EXCLUDED_NAMES: &[&str] = &[EPOCH_FILE, SPARSE_FILE];
 
 /// Diff two directory trees.
 ///
@@ -493,8 +532,12 @@ const EXCLUDED_NAMES: &[&str] = &[EPOCH_FILE];
 /// Returns a `SnapshotResult` with paths relative to `ws_dir`.
 ///
 /// If `base_dir` does not exist (epoch snapshot missing or not yet created),
-/// all files in `ws_dir` are treated as additions.
+/// all files in `ws_dir` are treated as additions. In a sparse workspace,
+/// base files outside its [`SparseSpec`] were never cloned and are not
+/// reported as deleted.
 fn diff_dirs(base_dir: &Path, ws_dir: &Path) -> SnapshotResult {
+    let sparse = SparseSpec::load(ws_dir);
+
     // Collect all files in the base snapshot (relative paths).
     let base_files: HashSet<PathBuf> = if base_dir.exists() {
         collect_files(base_dir, &[]).into_iter().collect()
@@ -525,7 +568,7 @@ fn diff_dirs(base_dir: &Path, ws_dir: &Path) -> SnapshotResult {
 
     // Files in base but not workspace: deleted
     for rel in &base_files {
-        if !ws_files.contains(rel) {
+        if !ws_files.contains(rel) && sparse.as_ref().is_none_or(|spec| spec.contains(rel)) {
             deleted.push(rel.clone());
         }
     }
@@ -956,6 +999,39 @@ mod tests {
         assert_eq!(snap.deleted[0], PathBuf::from("README.md"));
     }
 
+    #[test]
+    fn test_create_sparse_clones_selected_paths_only() {
+        let (_temp, root, epoch) = setup_repo_with_snapshot();
+        let backend = RefLinkBackend::new(root.clone());
+        let snap_dir = backend.epoch_snapshot_path(&epoch);
+        fs::create_dir_all(snap_dir.join("docs")).expect("operation should succeed");
+        fs::write(snap_dir.join("docs").join("guide.md"), "# Guide")
+            .expect("operation should succeed");
+        let ws_name = WorkspaceId::new("sparse-ws").expect("operation should succeed");
+        let spec = SparseSpec::new(["src"]).expect("valid spec");
+
+        let info = backend
+            .create_sparse(&ws_name, &epoch, &spec)
+            .expect("operation should succeed");
+        assert!(info.path.join("README.md").exists());
+        assert!(info.path.join("src").join("lib.rs").exists());
+        assert!(!info.path.join("docs").exists());
+
+        let snap = backend
+            .snapshot(&ws_name)
+            .expect("operation should succeed");
+        assert!(
+            snap.is_empty(),
+            "missing sparse paths are unchanged: {snap:?}"
+        );
+
+        fs::remove_file(info.path.join("src").join("lib.rs")).expect("operation should succeed");
+        let snap = backend
+            .snapshot(&ws_name)
+            .expect("operation should succeed");
+        assert_eq!(snap.deleted, vec![PathBuf::from("src/lib.rs")]);
+    }
+
     #[test]
     fn test_snapshot_nested_file_modified() {
         let (_temp, root, epoch) = setup_repo_with_snapshot();
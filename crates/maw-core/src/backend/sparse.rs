@@ -0,0 +1,310 @@
+//! Sparse workspaces: materialize only part of the epoch tree.
+//!
+//! A [`SparseSpec`] is a list of repo-relative paths or glob patterns
+//! (`services/api`, `libs/*/src`). A path is *inside* the spec when it is a
+//! top-level file, or when it or one of its ancestor directories matches a
+//! pattern — the same rule as git's cone-mode sparse checkout, which always
+//! keeps the files at the root.
+//!
+//! Backends materialize only the inside paths:
+//!
+//! - **git-worktree**: excluded index entries get the skip-worktree bit
+//!   and the worktree gets git's sparse-checkout config (cone mode for plain
+//!   directories, non-cone patterns for globs), so `git` inside it agrees.
+//! - **copy**: `git archive` restricted to the inside paths.
+//! - **reflink**: only the inside paths of the epoch snapshot are cloned.
+//!
+//! Outside paths are absent from the workspace, and an absent outside path
+//! is *unchanged*, not deleted: snapshots and patch sets skip them, so a
+//! merge never removes what a sparse workspace could not see. A file the
+//! agent writes outside the spec anyway is still collected as usual.
+
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+
+/// File recording the spec of a copy or reflink workspace, next to its
+/// `.maw-epoch` marker. Git worktrees keep it in their private git dir.
+pub const SPARSE_FILE: &str = ".maw-sparse";
+
+/// Name of the spec file inside a git worktree's private git dir.
+const GIT_SPARSE_FILE: &str = "maw-sparse";
+
+/// The paths a sparse workspace materializes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseSpec {
+    /// Normalized patterns: no leading `./` or `/`, no trailing `/`.
+    patterns: Vec<String>,
+    compiled: Vec<glob::Pattern>,
+}
+
+impl SparseSpec {
+    /// Build a spec from paths or glob patterns.
+    ///
+    /// # Errors
+    /// Returns an error if there are no patterns, a pattern is empty or
+    /// leaves the repository (`..`), or a glob is malformed.
+    pub fn new<I, S>(patterns: I) -> anyhow::Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut normalized = Vec::new();
+        for raw in patterns {
+            let raw = raw.as_ref().trim();
+            let pattern = raw
+                .trim_start_matches("./")
+                .trim_start_matches('/')
+                .trim_end_matches('/');
+            if pattern.is_empty() || pattern == "." {
+                anyhow::bail!("empty sparse path '{raw}'");
+            }
+            if Path::new(pattern)
+                .components()
+                .any(|c| matches!(c, Component::ParentDir))
+            {
+                anyhow::bail!("sparse path '{raw}' leaves the repository");
+            }
+            if !normalized.iter().any(|p| p == pattern) {
+                normalized.push(pattern.to_owned());
+            }
+        }
+        if normalized.is_empty() {
+            anyhow::bail!("a sparse workspace needs at least one path");
+        }
+        let compiled = normalized
+            .iter()
+            .map(|p| glob::Pattern::new(p))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow::anyhow!("invalid sparse pattern: {e}"))?;
+        Ok(Self {
+            patterns: normalized,
+            compiled,
+        })
+    }
+
+    /// The normalized patterns, in the order given.
+    #[must_use]
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Whether every pattern is a plain directory path, so git can use its
+    /// faster cone mode.
+    #[must_use]
+    pub fn is_cone(&self) -> bool {
+        self.patterns
+            .iter()
+            .all(|p| !p.contains(['*', '?', '[', ']']))
+    }
+
+    /// The shortest prefix of `path` that puts it inside the spec: the path
+    /// itself for a top-level file, otherwise the first ancestor (or the path)
+    /// matching a pattern. `None` when `path` is outside.
+    #[must_use]
+    pub fn root_of(&self, path: &Path) -> Option<PathBuf> {
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..glob::MatchOptions::new()
+        };
+        let components: Vec<Component<'_>> = path.components().collect();
+        if components.len() == 1 {
+            return Some(path.to_path_buf());
+        }
+        let mut prefix = PathBuf::new();
+        for component in components {
+            prefix.push(component);
+            if self
+                .compiled
+                .iter()
+                .any(|p| p.matches_path_with(&prefix, options))
+            {
+                return Some(prefix);
+            }
+        }
+        None
+    }
+
+    /// Whether `path` (relative to the workspace root) is inside the spec.
+    #[must_use]
+    pub fn contains(&self, path: &Path) -> bool {
+        self.root_of(path).is_some()
+    }
+
+    /// The minimal set of paths to materialize so that every inside path in
+    /// `paths` is present: top-level files plus the outermost matching
+    /// directories.
+    #[must_use]
+    pub fn roots<'a>(&self, paths: impl IntoIterator<Item = &'a Path>) -> BTreeSet<PathBuf> {
+        paths.into_iter().filter_map(|p| self.root_of(p)).collect()
+    }
+
+    /// The lines of git's `info/sparse-checkout` file for this spec, as
+    /// `git sparse-checkout set` would write them.
+    ///
+    /// Cone mode lists each selected directory plus its parents (whose
+    /// direct files only are kept). Non-cone patterns are anchored at the
+    /// root, with `/*` + `!/*/` keeping the top-level files the way cone mode
+    /// does.
+    #[must_use]
+    pub fn sparse_checkout_lines(&self) -> Vec<String> {
+        let mut lines = vec!["/*".to_owned(), "!/*/".to_owned()];
+        if !self.is_cone() {
+            lines.extend(self.patterns.iter().map(|p| format!("/{p}")));
+            return lines;
+        }
+        let dirs: BTreeSet<&Path> = self.patterns.iter().map(Path::new).collect();
+        let covered = |p: &Path| p.ancestors().skip(1).any(|a| dirs.contains(a));
+        let dirs: Vec<&Path> = dirs.iter().copied().filter(|d| !covered(d)).collect();
+        let parents: BTreeSet<&Path> = dirs
+            .iter()
+            .flat_map(|d| d.ancestors().skip(1))
+            .filter(|a| !a.as_os_str().is_empty())
+            .collect();
+        for parent in parents {
+            lines.push(format!("/{}/", parent.display()));
+            lines.push(format!("!/{}/*/", parent.display()));
+        }
+        lines.extend(dirs.iter().map(|d| format!("/{}/", d.display())));
+        lines
+    }
+
+    /// Record the spec of a copy or reflink workspace in [`SPARSE_FILE`].
+    ///
+    /// # Errors
+    /// Returns an I/O error if the file cannot be written.
+    pub fn write(&self, workspace: &Path) -> std::io::Result<()> {
+        self.write_file(&workspace.join(SPARSE_FILE))
+    }
+
+    fn write_file(&self, file: &Path) -> std::io::Result<()> {
+        let mut body = self.patterns.join("\n");
+        body.push('\n');
+        std::fs::write(file, body)
+    }
+
+    /// Record the spec of a git worktree in its private git dir.
+    ///
+    /// # Errors
+    /// Returns an I/O error if the worktree's `.git` file cannot be read or
+    /// the spec cannot be written.
+    pub fn write_for_worktree(&self, worktree: &Path) -> std::io::Result<()> {
+        let git_dir = worktree_git_dir(worktree).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{} is not a linked worktree", worktree.display()),
+            )
+        })?;
+        self.write_file(&git_dir.join(GIT_SPARSE_FILE))
+    }
+
+    /// The spec of a workspace, or `None` when it is not sparse.
+    ///
+    /// Looks for [`SPARSE_FILE`] in the workspace, then for the spec in a
+    /// linked worktree's private git dir.
+    #[must_use]
+    pub fn load(workspace: &Path) -> Option<Self> {
+        let file = if workspace.join(SPARSE_FILE).is_file() {
+            workspace.join(SPARSE_FILE)
+        } else {
+            worktree_git_dir(workspace)?.join(GIT_SPARSE_FILE)
+        };
+        let body = std::fs::read_to_string(file).ok()?;
+        Self::new(body.lines().filter(|l| !l.trim().is_empty())).ok()
+    }
+}
+
+/// The private git dir of a linked worktree, from its `.git` file.
+fn worktree_git_dir(worktree: &Path) -> Option<PathBuf> {
+    let gitfile = worktree.join(".git");
+    if !gitfile.is_file() {
+        return None;
+    }
+    let body = std::fs::read_to_string(gitfile).ok()?;
+    let dir = PathBuf::from(body.trim().strip_prefix("gitdir:")?.trim());
+    Some(if dir.is_absolute() {
+        dir
+    } else {
+        worktree.join(dir)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(patterns: &[&str]) -> SparseSpec {
+        SparseSpec::new(patterns).expect("valid spec")
+    }
+
+    #[test]
+    fn contains_top_level_files_and_matched_subtrees() {
+        let s = spec(&["./services/api/", "libs/*/src"]);
+        assert_eq!(s.patterns(), ["services/api", "libs/*/src"]);
+        assert!(!s.is_cone());
+
+        assert!(s.contains(Path::new("Cargo.toml")));
+        assert!(s.contains(Path::new("services/api/src/main.rs")));
+        assert!(s.contains(Path::new("libs/core/src/lib.rs")));
+        assert!(!s.contains(Path::new("libs/core/Cargo.toml")));
+        assert!(!s.contains(Path::new("services/web/index.ts")));
+        assert!(!s.contains(Path::new("services/apiary/x")));
+    }
+
+    #[test]
+    fn roots_are_outermost_matches() {
+        let s = spec(&["services/api", "docs"]);
+        assert!(s.is_cone());
+        let files = [
+            "README.md",
+            "docs/a.md",
+            "docs/deep/b.md",
+            "services/api/main.rs",
+            "services/web/main.ts",
+        ];
+        let roots = s.roots(files.iter().map(Path::new));
+        let roots: Vec<&str> = roots.iter().filter_map(|p| p.to_str()).collect();
+        assert_eq!(roots, ["README.md", "docs", "services/api"]);
+    }
+
+    #[test]
+    fn rejects_empty_and_escaping_patterns() {
+        assert!(SparseSpec::new(Vec::<String>::new()).is_err());
+        assert!(SparseSpec::new(["/"]).is_err());
+        assert!(SparseSpec::new(["../other"]).is_err());
+    }
+
+    #[test]
+    fn sparse_checkout_lines_match_git() {
+        assert_eq!(
+            spec(&["a/x", "c", "b/q/r", "a"]).sparse_checkout_lines(),
+            [
+                "/*", "!/*/", "/b/", "!/b/*/", "/b/q/", "!/b/q/*/", "/a/", "/b/q/r/", "/c/"
+            ]
+        );
+        assert_eq!(
+            spec(&["libs/*"]).sparse_checkout_lines(),
+            ["/*", "!/*/", "/libs/*"]
+        );
+    }
+
+    #[test]
+    fn load_round_trips_through_workspace_and_worktree_files() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let ws = dir.path().join("ws");
+        std::fs::create_dir_all(&ws).expect("mkdir");
+        assert!(SparseSpec::load(&ws).is_none());
+
+        let s = spec(&["src", "tests/*"]);
+        s.write(&ws).expect("write");
+        assert_eq!(SparseSpec::load(&ws), Some(s.clone()));
+
+        let wt = dir.path().join("wt");
+        let admin = dir.path().join("admin");
+        std::fs::create_dir_all(&wt).expect("mkdir");
+        std::fs::create_dir_all(&admin).expect("mkdir");
+        std::fs::write(wt.join(".git"), format!("gitdir: {}\n", admin.display()))
+            .expect("write gitfile");
+        s.write_for_worktree(&wt).expect("write");
+        assert_eq!(SparseSpec::load(&wt), Some(s));
+    }
+}
@@ -3,6 +3,7 @@
 //! Defines the typed configuration for `.manifold/config.toml`, including
 //! workspace backend selection, merge validation, and merge drivers.
 
+use std::collections::BTreeMap;
 use std::fmt;
 use std::path::Path;
 
@@ -76,6 +77,14 @@ pub struct WorkspaceConfig {
     /// e.g. `git diff refs/manifold/ws/alice..main`.
     #[serde(default = "default_git_compat_refs")]
     pub git_compat_refs: bool,
+
+    /// Namespace sandbox for `maw exec` commands.
+    #[serde(default)]
+    pub exec_sandbox: ExecSandboxConfig,
+
+    /// Disk quotas for workspaces and the repo-wide workspace budget.
+    #[serde(default)]
+    pub quota: QuotaConfig,
 }
 
 impl Default for WorkspaceConfig {
@@ -83,6 +92,8 @@ impl Default for WorkspaceConfig {
         Self {
             backend: BackendKind::default(),
             git_compat_refs: default_git_compat_refs(),
+            exec_sandbox: ExecSandboxConfig::default(),
+            quota: QuotaConfig::default(),
         }
     }
 }
@@ -91,6 +102,231 @@ const fn default_git_compat_refs() -> bool {
     true
 }
 
+/// Sandbox for `maw exec` (Linux, via bubblewrap).
+///
+/// The command runs in its own user, PID and mount namespaces: the workspace
+/// and the tool caches are writable, the system paths are read-only, and
+/// nothing else (sibling workspaces, the rest of `$HOME`) is visible.
+///
+/// ```toml
+/// [workspace.exec_sandbox]
+/// enabled = true
+/// network = false
+/// cache_dirs = ["~/.cargo", "~/.cache"]
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExecSandboxConfig {
+    /// Sandbox every `maw exec` command. Off by default; a workspace can
+    /// also opt in through its metadata, and `maw exec --sandbox` /
+    /// `--no-sandbox` override both.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Allow network access inside the sandbox (default `true`).
+    #[serde(default = "default_sandbox_network")]
+    pub network: bool,
+
+    /// Writable tool cache directories. `~/` expands to `$HOME`; missing
+    /// directories are skipped.
+    #[serde(default = "default_sandbox_cache_dirs")]
+    pub cache_dirs: Vec<String>,
+
+    /// System paths mounted read-only. Missing paths are skipped.
+    #[serde(default = "default_sandbox_read_only")]
+    pub read_only: Vec<String>,
+}
+
+impl Default for ExecSandboxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            network: default_sandbox_network(),
+            cache_dirs: default_sandbox_cache_dirs(),
+            read_only: default_sandbox_read_only(),
+        }
+    }
+}
+
+const fn default_sandbox_network() -> bool {
+    true
+}
+
+fn default_sandbox_cache_dirs() -> Vec<String> {
+    ["~/.cargo", "~/.rustup", "~/.cache", "~/.npm"]
+        .map(str::to_owned)
+        .to_vec()
+}
+
+fn default_sandbox_read_only() -> Vec<String> {
+    [
+        "/usr", "/bin", "/sbin", "/lib", "/lib32", "/lib64", "/etc", "/opt", "/nix",
+    ]
+    .map(str::to_owned)
+    .to_vec()
+}
+
+/// Disk quotas for workspaces.
+///
+/// Usage is what a workspace costs on disk beyond the blocks it shares with
+/// the epoch snapshot (see `maw ws list --disk`). Crossing `soft` or `hard`
+/// makes `maw exec` warn and marks the workspace in `maw ws list --disk`;
+/// neither blocks a command. `budget` caps the summed usage of all
+/// workspaces: `maw ws create` refuses to add a workspace once it is
+/// exceeded.
+///
+/// ```toml
+/// [workspace.quota]
+/// soft = "5G"
+/// hard = "20G"
+/// budget = "100G"
+///
+/// [workspace.quota.templates.eval]
+/// hard = "2G"
+///
+/// [workspace.quota.workspaces.release]
+/// soft = "40G"
+/// hard = "80G"
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuotaConfig {
+    /// Default soft limit per workspace.
+    pub soft: Option<ByteSize>,
+
+    /// Default hard limit per workspace.
+    pub hard: Option<ByteSize>,
+
+    /// Limit on the summed usage of all workspaces.
+    pub budget: Option<ByteSize>,
+
+    /// Limits for workspaces created from a template, keyed by template name.
+    #[serde(default)]
+    pub templates: BTreeMap<String, QuotaLimits>,
+
+    /// Limits for individual workspaces, keyed by workspace name.
+    #[serde(default)]
+    pub workspaces: BTreeMap<String, QuotaLimits>,
+}
+
+/// Soft and hard limits for one workspace.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuotaLimits {
+    /// Usage above which `maw exec` warns.
+    pub soft: Option<ByteSize>,
+    /// Usage above which `maw exec` warns that the workspace is over its
+    /// hard quota.
+    pub hard: Option<ByteSize>,
+}
+
+impl QuotaConfig {
+    /// Whether any limit or budget is set.
+    #[must_use]
+    pub fn is_configured(&self) -> bool {
+        self.soft.is_some()
+            || self.hard.is_some()
+            || self.budget.is_some()
+            || !self.templates.is_empty()
+            || !self.workspaces.is_empty()
+    }
+
+    /// Effective limits for `workspace`, created from `template` if any.
+    ///
+    /// Each limit is resolved on its own: a per-workspace entry wins over the
+    /// template entry, which wins over the defaults.
+    #[must_use]
+    pub fn limits_for(&self, workspace: &str, template: Option<&str>) -> QuotaLimits {
+        let by_workspace = self.workspaces.get(workspace);
+        let by_template = template.and_then(|t| self.templates.get(t));
+        let pick = |get: fn(&QuotaLimits) -> Option<ByteSize>, default: Option<ByteSize>| {
+            by_workspace
+                .and_then(get)
+                .or_else(|| by_template.and_then(get))
+                .or(default)
+        };
+        QuotaLimits {
+            soft: pick(|l| l.soft, self.soft),
+            hard: pick(|l| l.hard, self.hard),
+        }
+    }
+}
+
+/// A size in bytes.
+///
+/// Written in config as a plain integer or as a string with a binary unit:
+/// `"512M"`, `"20G"`, `"1.5GiB"` (`K`, `M`, `G`, `T`, with optional `iB`/`B`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(try_from = "RawByteSize")]
+pub struct ByteSize(pub u64);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawByteSize {
+    Bytes(u64),
+    Text(String),
+}
+
+impl TryFrom<RawByteSize> for ByteSize {
+    type Error = String;
+
+    fn try_from(raw: RawByteSize) -> Result<Self, Self::Error> {
+        match raw {
+            RawByteSize::Bytes(n) => Ok(Self(n)),
+            RawByteSize::Text(s) => s.parse(),
+        }
+    }
+}
+
+impl std::str::FromStr for ByteSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(s.len());
+        let (number, unit) = s.split_at(split);
+        let number: f64 = number
+            .parse()
+            .map_err(|_| format!("invalid size '{s}': expected e.g. \"512M\" or \"20G\""))?;
+        let shift = match unit.trim().to_ascii_uppercase().as_str() {
+            "" | "B" => 0,
+            "K" | "KB" | "KIB" => 10,
+            "M" | "MB" | "MIB" => 20,
+            "G" | "GB" | "GIB" => 30,
+            "T" | "TB" | "TIB" => 40,
+            other => return Err(format!("invalid size unit '{other}' in '{s}'")),
+        };
+        #[expect(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            reason = "the number is non-negative; fractional bytes are dropped"
+        )]
+        Ok(Self((number * 2_f64.powi(shift)) as u64))
+    }
+}
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+        if self.0 < 1024 {
+            return f.pad(&format!("{} B", self.0));
+        }
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "one decimal place of a human-readable size"
+        )]
+        let mut value = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+        while value >= 1024.0 && unit < UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+        f.pad(&format!("{value:.1} {}", UNITS[unit]))
+    }
+}
+
 /// The workspace isolation backend.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -104,6 +340,8 @@ pub enum BackendKind {
     Reflink,
     /// `OverlayFS` backend (Linux only).
     Overlay,
+    /// FUSE backend serving the epoch from the object database (Linux only).
+    Fuse,
     /// Plain copy backend (universal fallback).
     Copy,
 }
@@ -115,6 +353,7 @@ impl fmt::Display for BackendKind {
             Self::GitWorktree => write!(f, "git-worktree"),
             Self::Reflink => write!(f, "reflink"),
             Self::Overlay => write!(f, "overlay"),
+            Self::Fuse => write!(f, "fuse"),
             Self::Copy => write!(f, "copy"),
         }
     }
@@ -125,6 +364,10 @@ impl fmt::Display for BackendKind {
 // ---------------------------------------------------------------------------
 
 /// Merge behaviour settings.
+///
+/// The bool fields are independent `[merge]` switches that users set by
+/// name, so `struct_excessive_bools` is silenced.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Clone, Debug, PartialEq, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct MergeConfig {
@@ -140,6 +383,15 @@ pub struct MergeConfig {
     #[serde(default)]
     pub ast: AstConfig,
 
+    /// Whitespace, line-ending and formatter normalization of shared paths
+    /// before they are compared and merged. Off by default.
+    #[serde(default)]
+    pub normalize: NormalizeConfig,
+
+    /// Numbered-migration directories checked for sequence collisions.
+    #[serde(default)]
+    pub migrations: Vec<MigrationConfig>,
+
     /// When `true` (default), `maw ws merge` transparently absorbs
     /// fast-forward branch commits into the epoch when no in-flight
     /// workspace's touched paths intersect the FF range.
@@ -182,6 +434,29 @@ pub struct MergeConfig {
     /// triplication pattern (~2.83×).
     #[serde(default = "default_post_rebase_size_ratio_max")]
     pub post_rebase_size_ratio_max: f64,
+
+    /// Opt-in validation-guided conflict resolution. When a merge conflicts
+    /// and no `--resolve` flags were given, `maw ws merge` builds every
+    /// combination of side choices (per diff3 hunk where possible, else per
+    /// file), runs the `[merge.validation]` commands against each in a
+    /// scratch worktree, and commits the one combination that passes. With
+    /// zero or several passing combinations the merge still stops, and the
+    /// per-candidate results are reported alongside the conflicts.
+    ///
+    /// Off by default; requires validation commands.
+    #[serde(default)]
+    pub auto_try_resolutions: bool,
+
+    /// Upper bound on the candidate combinations `auto_try_resolutions`
+    /// will validate. Conflicts that would need more are left for manual
+    /// resolution. Default `8`.
+    #[serde(default = "default_auto_try_max_candidates")]
+    pub auto_try_max_candidates: usize,
+
+    /// Marker layout for conflicts written into files (sync replay and the
+    /// rendered conflict blobs). Default `diff3`.
+    #[serde(default)]
+    pub conflict_style: ConflictStyle,
 }
 
 impl Default for MergeConfig {
@@ -190,10 +465,15 @@ impl Default for MergeConfig {
             validation: ValidationConfig::default(),
             drivers: Vec::new(),
             ast: AstConfig::default(),
+            normalize: NormalizeConfig::default(),
+            migrations: Vec::new(),
             auto_absorb_ff: default_auto_absorb_ff(),
             auto_rebase_siblings: default_auto_rebase_siblings(),
             strict_post_rebase_check: default_strict_post_rebase_check(),
             post_rebase_size_ratio_max: default_post_rebase_size_ratio_max(),
+            auto_try_resolutions: false,
+            auto_try_max_candidates: default_auto_try_max_candidates(),
+            conflict_style: ConflictStyle::default(),
         }
     }
 }
@@ -214,6 +494,140 @@ const fn default_post_rebase_size_ratio_max() -> f64 {
     1.5
 }
 
+const fn default_auto_try_max_candidates() -> usize {
+    8
+}
+
+// ---------------------------------------------------------------------------
+// NormalizeConfig — merge normalization settings
+// ---------------------------------------------------------------------------
+
+/// Normalization used to compare the sides of a shared path (and its base)
+/// when the normal merge conflicts.
+///
+/// Agents running different editors or formatters often make the same edit
+/// with different whitespace; comparing normalized sides keeps those from
+/// conflicting. Paths that merge cleanly as written are never rewritten.
+/// Normalized paths are listed under the merge plan's auto-resolutions.
+///
+/// ```toml
+/// [merge.normalize]
+/// line_endings = true
+/// whitespace = true
+/// formatter_timeout_seconds = 30
+///
+/// [[merge.normalize.formatters]]
+/// match = "*.rs"
+/// command = "rustfmt --edition 2024 --emit stdout"
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NormalizeConfig {
+    /// Convert every side to the base's line endings (CRLF or LF).
+    #[serde(default)]
+    pub line_endings: bool,
+
+    /// Strip trailing spaces and tabs from every line.
+    #[serde(default)]
+    pub whitespace: bool,
+
+    /// Formatters run on every side of a conflicted path. The first
+    /// matching glob wins.
+    #[serde(default)]
+    pub formatters: Vec<FormatterConfig>,
+
+    /// How long one formatter run may take before it is killed and the
+    /// formatter is skipped for that path (default: 30).
+    #[serde(default = "default_formatter_timeout")]
+    pub formatter_timeout_seconds: u32,
+}
+
+impl Default for NormalizeConfig {
+    fn default() -> Self {
+        Self {
+            line_endings: false,
+            whitespace: false,
+            formatters: Vec::new(),
+            formatter_timeout_seconds: default_formatter_timeout(),
+        }
+    }
+}
+
+const fn default_formatter_timeout() -> u32 {
+    30
+}
+
+impl NormalizeConfig {
+    /// Returns `true` if any normalization is configured.
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.line_endings || self.whitespace || !self.formatters.is_empty()
+    }
+}
+
+/// A formatter applied to matching paths during merge normalization.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FormatterConfig {
+    /// Glob pattern for matching file paths (e.g. `"*.rs"`, `"web/**/*.ts"`).
+    #[serde(rename = "match")]
+    pub match_glob: String,
+
+    /// Shell command that reads the file on stdin and writes the formatted
+    /// file to stdout. `%P` expands to the repo-relative path.
+    pub command: String,
+}
+
+// ---------------------------------------------------------------------------
+// MigrationConfig — migration numbering checks
+// ---------------------------------------------------------------------------
+
+/// A directory of numbered migrations that must stay a single chain.
+///
+/// Two workspaces that each add `migrations/0042_*.sql` merge without any
+/// path overlap, but the result has two migrations numbered 42. Matching
+/// paths added by different workspaces (or already in the epoch) with the
+/// same sequence number are reported as migration-order conflicts — or,
+/// with `renumber = true`, the later workspace's migrations are renumbered
+/// past the highest existing number.
+///
+/// ```toml
+/// [[merge.migrations]]
+/// match = "migrations/*.sql"
+/// numbering = "{n}_*"                      # file-name pattern; {n} = digits
+/// latest = "migrations/max_migration.txt"  # optional "latest" pointer file
+/// renumber = true
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MigrationConfig {
+    /// Glob pattern selecting the migration files (e.g. `"migrations/*.sql"`).
+    #[serde(rename = "match")]
+    pub match_glob: String,
+
+    /// File-name pattern holding the sequence number: `{n}` matches the
+    /// digits, the rest is a glob (`"{n}_*"`, `"V{n}__*.sql"`). Default
+    /// `"{n}_*"`.
+    #[serde(default = "default_migration_numbering")]
+    pub numbering: String,
+
+    /// Repo-relative file naming the newest migration (e.g. Django's
+    /// `max_migration.txt`). Workspaces that point it at different
+    /// migrations conflict.
+    #[serde(default)]
+    pub latest: Option<String>,
+
+    /// Renumber the later workspace's migrations instead of reporting a
+    /// conflict. References to the renamed migrations inside the
+    /// workspace's migrations and the `latest` file are rewritten too.
+    #[serde(default)]
+    pub renumber: bool,
+}
+
+fn default_migration_numbering() -> String {
+    "{n}_*".to_owned()
+}
+
 // ---------------------------------------------------------------------------
 // AstConfig — AST-aware merge settings
 // ---------------------------------------------------------------------------
@@ -226,7 +640,8 @@ const fn default_post_rebase_size_ratio_max() -> f64 {
 /// ```toml
 /// [merge.ast]
 /// languages = ["rust", "python", "typescript", "javascript", "go"]
-/// packs = ["core", "web", "backend"]
+/// packs = ["core", "web", "backend", "jvm", "native", "dotnet"]
+/// structured = ["json", "yaml", "toml"]
 /// semantic_false_positive_budget_pct = 5
 /// semantic_min_confidence = 70
 /// ```
@@ -235,7 +650,8 @@ const fn default_post_rebase_size_ratio_max() -> f64 {
 pub struct AstConfig {
     /// Languages for which AST merge is enabled.
     ///
-    /// Supported values: `"rust"`, `"python"`, `"typescript"`, `"javascript"`, `"go"`.
+    /// Supported values: `"rust"`, `"python"`, `"typescript"`, `"javascript"`, `"go"`,
+    /// `"java"`, `"c"`, `"cpp"`, `"csharp"`.
     /// Empty by default; language packs control baseline enablement.
     #[serde(default)]
     pub languages: Vec<AstConfigLanguage>,
@@ -246,6 +662,12 @@ pub struct AstConfig {
     #[serde(default = "default_ast_packs")]
     pub packs: Vec<AstLanguagePack>,
 
+    /// Structured data formats merged key-by-key instead of line-by-line.
+    ///
+    /// Supported values: `"json"`, `"yaml"`, `"toml"`. All enabled by default.
+    #[serde(default = "default_structured_formats")]
+    pub structured: Vec<StructuredFormatConfig>,
+
     /// Maximum allowed semantic false-positive rate percentage (0-100).
     ///
     /// Semantic rules with confidence below `min_confidence` are downgraded to
@@ -263,6 +685,7 @@ impl Default for AstConfig {
         Self {
             languages: Vec::new(),
             packs: default_ast_packs(),
+            structured: default_structured_formats(),
             semantic_false_positive_budget_pct: default_semantic_false_positive_budget_pct(),
             semantic_min_confidence: default_semantic_min_confidence(),
         }
@@ -274,6 +697,17 @@ fn default_ast_packs() -> Vec<AstLanguagePack> {
         AstLanguagePack::Core,
         AstLanguagePack::Web,
         AstLanguagePack::Backend,
+        AstLanguagePack::Jvm,
+        AstLanguagePack::Native,
+        AstLanguagePack::Dotnet,
+    ]
+}
+
+fn default_structured_formats() -> Vec<StructuredFormatConfig> {
+    vec![
+        StructuredFormatConfig::Json,
+        StructuredFormatConfig::Yaml,
+        StructuredFormatConfig::Toml,
     ]
 }
 
@@ -285,6 +719,33 @@ const fn default_semantic_min_confidence() -> u8 {
     70
 }
 
+/// Conflict-marker style, as in git's `merge.conflictStyle` plus jj's
+/// snapshot markers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictStyle {
+    /// `<<<<<<<` / `=======` / `>>>>>>>`, no base section.
+    Merge,
+    /// Base section between `|||||||` and `=======`.
+    #[default]
+    Diff3,
+    /// diff3 with lines shared by both sides moved outside the block.
+    Zdiff3,
+    /// jj snapshot markers (`+++++++` per side, `-------` for the base).
+    Jj,
+}
+
+impl From<ConflictStyle> for maw_git::merge::MarkerStyle {
+    fn from(style: ConflictStyle) -> Self {
+        match style {
+            ConflictStyle::Merge => Self::Merge,
+            ConflictStyle::Diff3 => Self::Diff3,
+            ConflictStyle::Zdiff3 => Self::Zdiff3,
+            ConflictStyle::Jj => Self::Jj,
+        }
+    }
+}
+
 /// A language supported by the AST merge layer.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -300,6 +761,16 @@ pub enum AstConfigLanguage {
     JavaScript,
     /// Go (.go files).
     Go,
+    /// Java (.java files).
+    Java,
+    /// C (.c, .h files).
+    C,
+    /// C++ (.cc, .cpp, .cxx, .hh, .hpp, .hxx files).
+    #[serde(alias = "c++")]
+    Cpp,
+    /// C# (.cs files).
+    #[serde(alias = "cs", alias = "c#")]
+    CSharp,
 }
 
 /// A predefined pack of AST grammars that can be enabled together.
@@ -312,6 +783,25 @@ pub enum AstLanguagePack {
     Web,
     /// Backend language family (Rust/Go/Python).
     Backend,
+    /// JVM language family (Java).
+    Jvm,
+    /// Native language family (C/C++).
+    Native,
+    /// .NET language family (C#).
+    Dotnet,
+}
+
+/// A structured data format supported by the key-path merge layer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StructuredFormatConfig {
+    /// JSON (.json files).
+    Json,
+    /// YAML (.yaml, .yml files).
+    #[serde(alias = "yml")]
+    Yaml,
+    /// TOML (.toml files).
+    Toml,
 }
 
 impl MergeConfig {
@@ -336,12 +826,14 @@ fn default_merge_drivers() -> Vec<MergeDriver> {
             kind: MergeDriverKind::Regenerate,
             command: Some("cargo generate-lockfile".to_owned()),
             required: false,
+            timeout_seconds: default_driver_timeout(),
         },
         MergeDriver {
             match_glob: "package-lock.json".to_owned(),
             kind: MergeDriverKind::Regenerate,
             command: Some("npm install --package-lock-only".to_owned()),
             required: false,
+            timeout_seconds: default_driver_timeout(),
         },
     ]
 }
@@ -544,7 +1036,8 @@ pub struct MergeDriver {
     /// The driver kind.
     pub kind: MergeDriverKind,
 
-    /// External command for `regenerate` drivers. Ignored for `ours/theirs`.
+    /// External command for `regenerate` and `external` drivers. Ignored for
+    /// `ours`/`theirs` and the union drivers.
     pub command: Option<String>,
 
     /// Whether a failure in this driver should block the merge.
@@ -554,12 +1047,22 @@ pub struct MergeDriver {
     /// failures emit a warning and fall back to the normal merge resolution.
     #[serde(default = "default_driver_required")]
     pub required: bool,
+
+    /// How long an `external` driver may run on one path before it is
+    /// killed (default: 60). A timeout is handled like any other driver
+    /// failure.
+    #[serde(default = "default_driver_timeout")]
+    pub timeout_seconds: u32,
 }
 
 const fn default_driver_required() -> bool {
     true
 }
 
+const fn default_driver_timeout() -> u32 {
+    60
+}
+
 /// Built-in merge driver kinds.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -574,6 +1077,20 @@ pub enum MergeDriverKind {
     ///
     /// Only valid when exactly one workspace touched the path.
     Theirs,
+    /// Hand shared paths to an external merge tool (e.g. mergiraf).
+    ///
+    /// Requires `command`; see `maw::merge::external_driver` for the
+    /// base/ours/theirs + manifest protocol.
+    External,
+    /// Keep every workspace's lines in conflicting hunks, sorted and
+    /// de-duplicated (migration lists, registries).
+    UnionSorted,
+    /// Keep every workspace's lines in conflicting hunks in workspace order,
+    /// dropping lines already present in the hunk.
+    UnionDedupe,
+    /// Keep every workspace's lines in conflicting hunks in workspace order
+    /// (changelogs and other append-only files).
+    Append,
 }
 
 impl fmt::Display for MergeDriverKind {
@@ -582,6 +1099,10 @@ impl fmt::Display for MergeDriverKind {
             Self::Regenerate => write!(f, "regenerate"),
             Self::Ours => write!(f, "ours"),
             Self::Theirs => write!(f, "theirs"),
+            Self::External => write!(f, "external"),
+            Self::UnionSorted => write!(f, "union-sorted"),
+            Self::UnionDedupe => write!(f, "union-dedupe"),
+            Self::Append => write!(f, "append"),
         }
     }
 }
@@ -744,6 +1265,7 @@ kind = "theirs"
         assert_eq!(cfg.merge.drivers[1].match_glob, "generated/**");
         assert_eq!(cfg.merge.drivers[1].kind, MergeDriverKind::Theirs);
         assert!(cfg.merge.drivers[1].command.is_none());
+        assert_eq!(cfg.merge.drivers[0].timeout_seconds, 60);
     }
 
     #[test]
@@ -917,6 +1439,7 @@ branch = "release"
         assert_eq!(format!("{}", BackendKind::GitWorktree), "git-worktree");
         assert_eq!(format!("{}", BackendKind::Reflink), "reflink");
         assert_eq!(format!("{}", BackendKind::Overlay), "overlay");
+        assert_eq!(format!("{}", BackendKind::Fuse), "fuse");
         assert_eq!(format!("{}", BackendKind::Copy), "copy");
     }
 
@@ -940,6 +1463,10 @@ branch = "release"
         assert_eq!(format!("{}", MergeDriverKind::Regenerate), "regenerate");
         assert_eq!(format!("{}", MergeDriverKind::Ours), "ours");
         assert_eq!(format!("{}", MergeDriverKind::Theirs), "theirs");
+        assert_eq!(format!("{}", MergeDriverKind::External), "external");
+        assert_eq!(format!("{}", MergeDriverKind::UnionSorted), "union-sorted");
+        assert_eq!(format!("{}", MergeDriverKind::UnionDedupe), "union-dedupe");
+        assert_eq!(format!("{}", MergeDriverKind::Append), "append");
     }
 
     // -- All BackendKind variants parse --
@@ -951,6 +1478,7 @@ branch = "release"
             ("git-worktree", BackendKind::GitWorktree),
             ("reflink", BackendKind::Reflink),
             ("overlay", BackendKind::Overlay),
+            ("fuse", BackendKind::Fuse),
             ("copy", BackendKind::Copy),
         ] {
             let toml = format!("[workspace]\nbackend = \"{input}\"");
@@ -1145,10 +1673,173 @@ on_failure = "block"
             cfg.merge.ast.packs.contains(&AstLanguagePack::Backend),
             "AST backend pack should be enabled by default"
         );
+        assert!(
+            cfg.merge.ast.packs.contains(&AstLanguagePack::Jvm),
+            "AST jvm pack should be enabled by default"
+        );
+        assert!(
+            cfg.merge.ast.packs.contains(&AstLanguagePack::Native),
+            "AST native pack should be enabled by default"
+        );
+        assert!(
+            cfg.merge.ast.packs.contains(&AstLanguagePack::Dotnet),
+            "AST dotnet pack should be enabled by default"
+        );
         assert_eq!(cfg.merge.ast.semantic_false_positive_budget_pct, 5);
         assert_eq!(cfg.merge.ast.semantic_min_confidence, 70);
     }
 
+    #[test]
+    fn parse_migration_config() {
+        let toml = r#"
+[[merge.migrations]]
+match = "migrations/*.sql"
+
+[[merge.migrations]]
+match = "db/*.sql"
+numbering = "V{n}__*.sql"
+latest = "db/LATEST"
+renumber = true
+"#;
+        let cfg = ManifoldConfig::parse(toml).expect("operation should succeed");
+        let migrations = &cfg.merge.migrations;
+        assert_eq!(migrations.len(), 2);
+        assert_eq!(migrations[0].numbering, "{n}_*");
+        assert_eq!(migrations[0].latest, None);
+        assert!(!migrations[0].renumber);
+        assert_eq!(migrations[1].numbering, "V{n}__*.sql");
+        assert_eq!(migrations[1].latest.as_deref(), Some("db/LATEST"));
+        assert!(migrations[1].renumber);
+    }
+
+    #[test]
+    fn parse_exec_sandbox_config() {
+        let cfg = ManifoldConfig::parse("").expect("operation should succeed");
+        assert!(!cfg.workspace.exec_sandbox.enabled);
+        assert!(cfg.workspace.exec_sandbox.network);
+        assert!(
+            cfg.workspace
+                .exec_sandbox
+                .read_only
+                .contains(&"/usr".to_owned())
+        );
+
+        let toml = r#"
+[workspace.exec_sandbox]
+enabled = true
+network = false
+cache_dirs = ["~/.cargo"]
+"#;
+        let cfg = ManifoldConfig::parse(toml).expect("operation should succeed");
+        let sandbox = &cfg.workspace.exec_sandbox;
+        assert!(sandbox.enabled);
+        assert!(!sandbox.network);
+        assert_eq!(sandbox.cache_dirs, ["~/.cargo"]);
+        assert_eq!(sandbox.read_only, default_sandbox_read_only());
+    }
+
+    #[test]
+    fn parse_quota_config_and_resolve_limits() {
+        let cfg = ManifoldConfig::parse("").expect("operation should succeed");
+        assert!(!cfg.workspace.quota.is_configured());
+
+        let toml = r#"
+[workspace.quota]
+soft = "5G"
+hard = 1048576
+budget = "1.5TiB"
+
+[workspace.quota.templates.eval]
+hard = "2G"
+
+[workspace.quota.workspaces.alice]
+soft = "512M"
+"#;
+        let cfg = ManifoldConfig::parse(toml).expect("operation should succeed");
+        let quota = &cfg.workspace.quota;
+        assert!(quota.is_configured());
+        assert_eq!(quota.soft, Some(ByteSize(5 << 30)));
+        assert_eq!(quota.hard, Some(ByteSize(1 << 20)));
+        assert_eq!(quota.budget, Some(ByteSize(3 << 39)));
+
+        let alice = quota.limits_for("alice", Some("eval"));
+        assert_eq!(alice.soft, Some(ByteSize(512 << 20)));
+        assert_eq!(alice.hard, Some(ByteSize(2 << 30)));
+        let bob = quota.limits_for("bob", None);
+        assert_eq!(bob.soft, Some(ByteSize(5 << 30)));
+        assert_eq!(bob.hard, Some(ByteSize(1 << 20)));
+
+        assert!(ManifoldConfig::parse("[workspace.quota]\nsoft = \"5X\"\n").is_err());
+    }
+
+    #[test]
+    fn byte_size_display_is_human_readable() {
+        assert_eq!(ByteSize(512).to_string(), "512 B");
+        assert_eq!(ByteSize(1536).to_string(), "1.5 KiB");
+        assert_eq!(ByteSize(5 << 30).to_string(), "5.0 GiB");
+    }
+
+    #[test]
+    fn parse_conflict_style() {
+        let cfg = ManifoldConfig::parse("").expect("operation should succeed");
+        assert_eq!(cfg.merge.conflict_style, ConflictStyle::Diff3);
+        let cfg = ManifoldConfig::parse("[merge]\nconflict_style = \"zdiff3\"\n")
+            .expect("operation should succeed");
+        assert_eq!(cfg.merge.conflict_style, ConflictStyle::Zdiff3);
+        assert!(ManifoldConfig::parse("[merge]\nconflict_style = \"nope\"\n").is_err());
+    }
+
+    #[test]
+    fn normalize_config_defaults_to_off() {
+        let cfg = ManifoldConfig::parse("").expect("operation should succeed");
+        assert_eq!(cfg.merge.normalize, NormalizeConfig::default());
+        assert!(!cfg.merge.normalize.is_enabled());
+        assert_eq!(cfg.merge.normalize.formatter_timeout_seconds, 30);
+    }
+
+    #[test]
+    fn parse_normalize_config() {
+        let toml = r#"
+[merge.normalize]
+line_endings = true
+whitespace = true
+formatter_timeout_seconds = 5
+
+[[merge.normalize.formatters]]
+match = "*.rs"
+command = "rustfmt --emit stdout"
+"#;
+        let cfg = ManifoldConfig::parse(toml).expect("operation should succeed");
+        let normalize = &cfg.merge.normalize;
+        assert!(normalize.is_enabled());
+        assert!(normalize.line_endings);
+        assert!(normalize.whitespace);
+        assert_eq!(normalize.formatter_timeout_seconds, 5);
+        assert_eq!(
+            normalize.formatters,
+            vec![FormatterConfig {
+                match_glob: "*.rs".to_owned(),
+                command: "rustfmt --emit stdout".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_auto_try_resolutions() {
+        let cfg = ManifoldConfig::parse("").expect("operation should succeed");
+        assert!(!cfg.merge.auto_try_resolutions);
+        assert_eq!(cfg.merge.auto_try_max_candidates, 8);
+
+        let toml = r"
+[merge]
+auto_try_resolutions = true
+auto_try_max_candidates = 4
+";
+        let cfg = ManifoldConfig::parse(toml).expect("operation should succeed");
+        assert!(cfg.merge.auto_try_resolutions);
+        assert_eq!(cfg.merge.auto_try_max_candidates, 4);
+    }
+
     #[test]
     fn parse_ast_config_all_languages() {
         let toml = r#"
@@ -1206,6 +1897,49 @@ languages = ["javascript", "go"]
         assert!(cfg.merge.ast.languages.contains(&AstConfigLanguage::Go));
     }
 
+    #[test]
+    fn parse_ast_config_jvm_and_native_languages() {
+        let toml = r#"
+[merge.ast]
+languages = ["java", "c", "c++", "csharp"]
+packs = ["jvm", "native", "dotnet"]
+"#;
+        let cfg = ManifoldConfig::parse(toml).expect("operation should succeed");
+        assert_eq!(
+            cfg.merge.ast.languages,
+            vec![
+                AstConfigLanguage::Java,
+                AstConfigLanguage::C,
+                AstConfigLanguage::Cpp,
+                AstConfigLanguage::CSharp,
+            ]
+        );
+        assert_eq!(
+            cfg.merge.ast.packs,
+            vec![
+                AstLanguagePack::Jvm,
+                AstLanguagePack::Native,
+                AstLanguagePack::Dotnet,
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_ast_config_structured_formats() {
+        let cfg = ManifoldConfig::parse("").expect("operation should succeed");
+        assert_eq!(cfg.merge.ast.structured.len(), 3);
+
+        let toml = r#"
+[merge.ast]
+structured = ["yml", "toml"]
+"#;
+        let cfg = ManifoldConfig::parse(toml).expect("operation should succeed");
+        assert_eq!(
+            cfg.merge.ast.structured,
+            vec![StructuredFormatConfig::Yaml, StructuredFormatConfig::Toml]
+        );
+    }
+
     #[test]
     fn parse_ast_config_packs_and_semantic_thresholds() {
         let toml = r#"
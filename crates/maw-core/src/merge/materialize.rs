@@ -61,6 +61,7 @@ use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use maw_git::GitRepo;
+use maw_git::merge::{MarkerStyle, restyle_markers};
 use serde::{Deserialize, Serialize};
 
 use super::types::{ConflictTree, EntryMode, MaterializedEntry};
@@ -966,6 +967,24 @@ fn render_modify_delete_conflict(
 pub fn materialize(
     tree: &ConflictTree,
     repo: &dyn GitRepo,
+) -> Result<MaterializedOutput, MaterializeError> {
+    materialize_with_style(tree, repo, MarkerStyle::Diff3)
+}
+
+/// [`materialize`], rendering text conflicts with `style` markers
+/// (`[merge] conflict_style`).
+///
+/// Conflicts are rendered as diff3 and then rewritten by
+/// [`maw_git::merge::restyle_markers`]. Binary conflict stubs keep their
+/// diff3 block: their verbatim payload must not be touched.
+///
+/// # Errors
+///
+/// Same as [`materialize`].
+pub fn materialize_with_style(
+    tree: &ConflictTree,
+    repo: &dyn GitRepo,
+    style: MarkerStyle,
 ) -> Result<MaterializedOutput, MaterializeError> {
     let mut entries: BTreeMap<PathBuf, FinalEntry> = BTreeMap::new();
 
@@ -1002,6 +1021,11 @@ pub fn materialize(
                 return Err(MaterializeError::UnsupportedDivergentRename { path: path.clone() });
             }
         };
+        let content = if looks_text(&content) {
+            restyle_markers(&content, style)
+        } else {
+            content
+        };
 
         entries.insert(
             path.clone(),
@@ -1616,6 +1640,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn materialize_with_style_renders_jj_markers() {
+        let fx = Fx::new();
+        let epoch_oid = fx.blob(b"epoch\n");
+        let ws_oid = fx.blob(b"workspace\n");
+        let base_oid = fx.blob(b"base\n");
+
+        let mut tree = ConflictTree::new(epoch());
+        tree.conflicts.insert(
+            PathBuf::from("notes.txt"),
+            Conflict::Content {
+                path: PathBuf::from("notes.txt"),
+                file_id: FileId::new(1),
+                base: Some(base_oid),
+                sides: vec![side("epoch", epoch_oid), side("feature", ws_oid)],
+                atoms: vec![],
+            },
+        );
+
+        let out = materialize_with_style(&tree, fx.repo.as_ref(), MarkerStyle::Jj)
+            .expect("operation should succeed");
+        let Some(FinalEntry::Rendered { content, .. }) =
+            out.entries.get(&PathBuf::from("notes.txt"))
+        else {
+            panic!("expected rendered");
+        };
+        let text = std::str::from_utf8(content).expect("operation should succeed");
+        assert!(
+            text.starts_with("# structured conflict at notes.txt\n"),
+            "header must stay byte-exact; got:\n{text}"
+        );
+        assert!(
+            text.contains(
+                "<<<<<<< Conflict 1 of 1\n+++++++ epoch (current)\nepoch\n------- base\nbase\n\
+                 +++++++ feature (workspace changes)\nworkspace\n>>>>>>> Conflict 1 of 1 ends\n"
+            ),
+            "expected jj snapshot block; got:\n{text}"
+        );
+    }
+
     /// bn-ad5z: a content conflict whose blobs are binary must not produce a
     /// marker block with NUL bytes spliced between the marker lines.
     #[test]
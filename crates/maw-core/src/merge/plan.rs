@@ -39,6 +39,47 @@ pub struct PredictedConflict {
     pub kind: String,
     /// The workspace IDs involved in this conflict.
     pub sides: Vec<String>,
+    /// The conflicting hunks or AST nodes (e.g., `"lines 12..18"`,
+    /// `"function_item `parse`"`). Empty when the whole path conflicts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<String>,
+}
+
+// ---------------------------------------------------------------------------
+// PlanWarning
+// ---------------------------------------------------------------------------
+
+/// A problem the merge engine found that does not block the merge (e.g. a
+/// call site written against a signature another workspace changed).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanWarning {
+    /// Path relative to the repo root.
+    pub path: PathBuf,
+    /// Warning kind (e.g., `"signature_drift"`).
+    pub kind: String,
+    /// The workspace IDs involved.
+    pub sides: Vec<String>,
+    /// Human-readable description.
+    pub message: String,
+}
+
+// ---------------------------------------------------------------------------
+// AutoResolutionInfo
+// ---------------------------------------------------------------------------
+
+/// An overlapping path that diff3 could not merge but a structural resolver
+/// did (e.g. imports added on both sides were unioned).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutoResolutionInfo {
+    /// Path relative to the repo root.
+    pub path: PathBuf,
+    /// Resolver kind (e.g., `"import_union"`).
+    pub kind: String,
+    /// The workspace IDs whose edits were combined.
+    pub sides: Vec<String>,
+    /// What was combined, one entry per element (e.g. each import).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
 }
 
 // ---------------------------------------------------------------------------
@@ -132,6 +173,14 @@ pub struct MergePlan {
     /// Conflicts predicted by the merge engine.
     pub predicted_conflicts: Vec<PredictedConflict>,
 
+    /// Non-blocking problems found by the merge engine.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<PlanWarning>,
+
+    /// Overlaps the merge engine resolved structurally after diff3 conflicted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub auto_resolutions: Vec<AutoResolutionInfo>,
+
     /// Merge drivers that apply to touched paths.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub drivers: Vec<DriverInfo>,
@@ -398,6 +447,19 @@ mod tests {
                 path: PathBuf::from("README.md"),
                 kind: "Diff3Conflict".to_owned(),
                 sides: vec!["ws-a".to_owned(), "ws-b".to_owned()],
+                regions: vec!["lines 3..7".to_owned()],
+            }],
+            warnings: vec![PlanWarning {
+                path: PathBuf::from("src/main.rs"),
+                kind: "signature_drift".to_owned(),
+                sides: vec!["ws-b".to_owned()],
+                message: "call to `parse` targets an old signature".to_owned(),
+            }],
+            auto_resolutions: vec![AutoResolutionInfo {
+                path: PathBuf::from("src/main.rs"),
+                kind: "import_union".to_owned(),
+                sides: vec!["ws-a".to_owned(), "ws-b".to_owned()],
+                details: vec!["use std::fmt; (ws-a)".to_owned()],
             }],
             drivers: vec![DriverInfo {
                 path: PathBuf::from("Cargo.lock"),
@@ -437,12 +499,16 @@ mod tests {
             touched_paths: Vec::new(),
             overlaps: Vec::new(),
             predicted_conflicts: Vec::new(),
+            warnings: Vec::new(),
+            auto_resolutions: Vec::new(),
             drivers: Vec::new(),
             validation: None,
         };
         let json = serde_json::to_string_pretty(&plan).expect("operation should succeed");
-        // drivers is skip_serializing_if = "Vec::is_empty"
+        // drivers, warnings and auto_resolutions are skip_serializing_if = "Vec::is_empty"
         assert!(!json.contains("\"drivers\""));
+        assert!(!json.contains("\"warnings\""));
+        assert!(!json.contains("\"auto_resolutions\""));
         // validation is skip_serializing_if = "Option::is_none"
         assert!(!json.contains("\"validation\""));
     }
@@ -14,6 +14,9 @@
 //! 3. For each change, looks up or computes the relevant blob OID(s) using
 //!    `git hash-object -w` and `git rev-parse <epoch>:<path>`.
 //!
+//! In a sparse workspace (see [`crate::backend::sparse`]) paths outside the
+//! spec were never checked out; they are unchanged, never deletions.
+//!
 //! # `FileId` allocation
 //!
 //! File identity is resolved in this order:
@@ -37,6 +40,7 @@ use std::process::Command;
 
 use sha2::{Digest, Sha256};
 
+use crate::backend::sparse::SparseSpec;
 use crate::model::file_id::FileIdMap;
 use crate::model::patch::{FileId, PatchSet, PatchValue};
 use crate::model::types::{EpochId, GitOid};
@@ -315,7 +319,14 @@ pub fn compute_patchset(
 ) -> Result<PatchSet, DiffError> {
     let mut patches: BTreeMap<PathBuf, PatchValue> = BTreeMap::new();
     let file_id_map = load_file_id_map(workspace_path)?;
-    collect_tracked_changes(workspace_path, base_epoch, &file_id_map, &mut patches)?;
+    let sparse = SparseSpec::load(workspace_path);
+    collect_tracked_changes(
+        workspace_path,
+        base_epoch,
+        &file_id_map,
+        sparse.as_ref(),
+        &mut patches,
+    )?;
     collect_untracked_changes(workspace_path, &file_id_map, &mut patches)?;
 
     Ok(PatchSet {
@@ -328,6 +339,7 @@ fn collect_tracked_changes(
     workspace_path: &Path,
     base_epoch: &EpochId,
     file_id_map: &FileIdMap,
+    sparse: Option<&SparseSpec>,
     patches: &mut BTreeMap<PathBuf, PatchValue>,
 ) -> Result<(), DiffError> {
     let diff_out = git_cmd(
@@ -372,6 +384,7 @@ fn collect_tracked_changes(
                     },
                 );
             }
+            DiffEntry::Deleted(path) if sparse.is_some_and(|spec| !spec.contains(&path)) => {}
             DiffEntry::Deleted(path) => {
                 let previous_blob = epoch_blob_oid(workspace_path, base_epoch, &path)?;
                 let file_id = file_id_map
@@ -640,6 +653,44 @@ mod tests {
         assert_eq!(ps.base_epoch, epoch);
     }
 
+    #[test]
+    fn compute_patchset_sparse_workspace_skips_unselected_paths() {
+        use crate::backend::WorkspaceBackend as _;
+        use crate::backend::git::GitWorktreeBackend;
+        use crate::model::types::WorkspaceId;
+
+        let dir = tempfile::tempdir().expect("operation should succeed");
+        let root = dir.path();
+        git_init(root);
+        let epoch = make_epoch(
+            root,
+            &[
+                ("README.md", "# repo"),
+                ("app/main.rs", "fn main() {}"),
+                ("docs/guide.md", "# guide"),
+            ],
+        );
+
+        let backend = GitWorktreeBackend::new(root.to_path_buf());
+        let spec = SparseSpec::new(["app"]).expect("valid spec");
+        let ws = WorkspaceId::new("sparse").expect("operation should succeed");
+        let info = backend
+            .create_sparse(&ws, &epoch, &spec)
+            .expect("operation should succeed");
+        assert!(!info.path.join("docs").exists());
+
+        let ps = compute_patchset(&info.path, &epoch).expect("operation should succeed");
+        assert!(ps.is_empty(), "unselected paths are unchanged: {ps:?}");
+
+        std::fs::remove_file(info.path.join("app/main.rs")).expect("operation should succeed");
+        let ps = compute_patchset(&info.path, &epoch).expect("operation should succeed");
+        assert_eq!(ps.len(), 1);
+        assert!(matches!(
+            ps.patches.get(&PathBuf::from("app/main.rs")),
+            Some(PatchValue::Delete { .. })
+        ));
+    }
+
     #[test]
     fn compute_patchset_added_file() {
         let dir = tempfile::tempdir().expect("operation should succeed");
@@ -6,8 +6,8 @@ use crate::diff_impl::NameStatusPairs;
 use crate::error::GitError;
 use crate::repo::GitRepo;
 use crate::types::{
-    CommitInfo, DiffEntry, EntryMode, GitOid, IndexEntry, RefEdit, RefName, StatusEntry, TreeEdit,
-    TreeEntry, WorktreeInfo,
+    CommitInfo, DiffEntry, EntryMode, GitOid, IndexEntry, RefEdit, RefName, SparseCheckout,
+    StatusEntry, TreeEdit, TreeEntry, WorktreeInfo,
 };
 
 /// A [`GitRepo`] implementation backed by [gix](https://github.com/GitoxideLabs/gitoxide).
@@ -393,6 +393,25 @@ impl GitRepo for GixRepo {
         crate::worktree_impl::worktree_add(self, name, target, path)
     }
 
+    fn worktree_add_no_checkout(
+        &self,
+        name: &str,
+        target: GitOid,
+        path: &Path,
+    ) -> Result<(), GitError> {
+        crate::worktree_impl::worktree_add_no_checkout(self, name, target, path)
+    }
+
+    fn worktree_add_sparse(
+        &self,
+        name: &str,
+        target: GitOid,
+        path: &Path,
+        sparse: &SparseCheckout<'_>,
+    ) -> Result<(), GitError> {
+        crate::worktree_impl::worktree_add_sparse(self, name, target, path, sparse)
+    }
+
     fn worktree_remove(&self, name: &str) -> Result<(), GitError> {
         crate::worktree_impl::worktree_remove(self, name)
     }
@@ -66,5 +66,5 @@ pub use error::GitError;
 pub use repo::GitRepo;
 pub use types::{
     ChangeType, CommitInfo, DiffEntry, EntryMode, FileStatus, GitOid, IndexEntry, OidParseError,
-    RefEdit, RefName, RefNameError, StatusEntry, TreeEdit, TreeEntry, WorktreeInfo,
+    RefEdit, RefName, RefNameError, SparseCheckout, StatusEntry, TreeEdit, TreeEntry, WorktreeInfo,
 };
@@ -6,7 +6,13 @@
 //!
 //! Also provides [`merge_text_with_style`] for selecting the conflict
 //! resolution strategy per-file, honoring `.gitattributes` merge drivers
-//! (e.g., `merge=union` for append-only files like logs and CHANGELOGs).
+//! (e.g., `merge=union` for append-only files like logs and CHANGELOGs),
+//! and [`line_changes`], the two-way line diff underneath.
+//!
+//! Conflicts are always rendered with diff3 markers; [`restyle_markers`]
+//! rewrites them into the other [`MarkerStyle`]s.
+
+use std::ops::Range;
 
 use gix::bstr::ByteSlice;
 use gix::diff::blob::intern::InternedInput;
@@ -158,10 +164,241 @@ pub fn merge_text_with_style(
     }
 }
 
+/// Layout of the conflict-marker blocks written into conflicted files.
+///
+/// Selected per repo with `[merge] conflict_style`. Every style opens a block
+/// with `<<<<<<<` at the start of a line, so marker gates that only look for
+/// that line work unchanged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MarkerStyle {
+    /// git's `merge` style: `<<<<<<<` / `=======` / `>>>>>>>`, no base section.
+    Merge,
+    /// git's `diff3` style: the base sits between `|||||||` and `=======`.
+    #[default]
+    Diff3,
+    /// git's `zdiff3` style: diff3, with lines both sides share at the start
+    /// or end of a block moved outside the markers.
+    Zdiff3,
+    /// jj's snapshot style: each side follows a `+++++++ <label>` line and the
+    /// base a `------- <label>` line, inside
+    /// `<<<<<<< Conflict i of n` / `>>>>>>> Conflict i of n ends`.
+    Jj,
+}
+
+/// One parsed diff3 conflict block. Lines keep their terminators.
+struct MarkerBlock<'a> {
+    ours_label: &'a [u8],
+    ours: Vec<&'a [u8]>,
+    base_label: Option<&'a [u8]>,
+    base: Vec<&'a [u8]>,
+    theirs_label: &'a [u8],
+    theirs: Vec<&'a [u8]>,
+}
+
+enum MarkerSegment<'a> {
+    Text(&'a [u8]),
+    Block(MarkerBlock<'a>),
+}
+
+/// Label following a 7-character marker, without the line terminator.
+fn marker_label(line: &[u8]) -> &[u8] {
+    line[7..].trim_ascii()
+}
+
+/// Split diff3 marker output into plain lines and conflict blocks. A block
+/// without its `>>>>>>>` line is kept as plain text.
+fn split_marker_blocks(diff3: &[u8]) -> Vec<MarkerSegment<'_>> {
+    let lines: Vec<&[u8]> = diff3.split_inclusive(|&b| b == b'\n').collect();
+    let mut segments = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if !line.starts_with(b"<<<<<<<") {
+            segments.push(MarkerSegment::Text(line));
+            i += 1;
+            continue;
+        }
+        let mut block = MarkerBlock {
+            ours_label: marker_label(line),
+            ours: Vec::new(),
+            base_label: None,
+            base: Vec::new(),
+            theirs_label: b"",
+            theirs: Vec::new(),
+        };
+        let mut section = 0;
+        let mut end = None;
+        for (j, inner) in lines.iter().enumerate().skip(i + 1) {
+            if section == 0 && inner.starts_with(b"|||||||") {
+                block.base_label = Some(marker_label(inner));
+                section = 1;
+            } else if section < 2 && inner.trim_ascii_end() == b"=======" {
+                section = 2;
+            } else if section == 2 && inner.starts_with(b">>>>>>>") {
+                block.theirs_label = marker_label(inner);
+                end = Some(j);
+                break;
+            } else {
+                match section {
+                    0 => block.ours.push(inner),
+                    1 => block.base.push(inner),
+                    _ => block.theirs.push(inner),
+                }
+            }
+        }
+        if let Some(end) = end {
+            segments.push(MarkerSegment::Block(block));
+            i = end + 1;
+        } else {
+            segments.extend(lines[i..].iter().map(|l| MarkerSegment::Text(l)));
+            break;
+        }
+    }
+    segments
+}
+
+fn push_marker(out: &mut Vec<u8>, marker: &str, label: &[u8]) {
+    out.extend_from_slice(marker.as_bytes());
+    if !label.is_empty() {
+        out.push(b' ');
+        out.extend_from_slice(label);
+    }
+    out.push(b'\n');
+}
+
+fn push_lines(out: &mut Vec<u8>, lines: &[&[u8]]) {
+    for line in lines {
+        out.extend_from_slice(line);
+        if !line.ends_with(b"\n") {
+            out.push(b'\n');
+        }
+    }
+}
+
+/// Rewrite the diff3 conflict blocks in `diff3` into `style`.
+///
+/// Text outside `<<<<<<<` … `>>>>>>>` blocks is copied unchanged, so the
+/// input may carry headers or clean regions around its conflicts. With
+/// [`MarkerStyle::Diff3`] the input is returned as is.
+#[must_use]
+pub fn restyle_markers(diff3: &[u8], style: MarkerStyle) -> Vec<u8> {
+    if style == MarkerStyle::Diff3 {
+        return diff3.to_vec();
+    }
+    let segments = split_marker_blocks(diff3);
+    let total = segments
+        .iter()
+        .filter(|s| matches!(s, MarkerSegment::Block(_)))
+        .count();
+    let mut out = Vec::with_capacity(diff3.len());
+    let mut index = 0;
+    for segment in &segments {
+        let block = match segment {
+            MarkerSegment::Text(line) => {
+                out.extend_from_slice(line);
+                continue;
+            }
+            MarkerSegment::Block(block) => block,
+        };
+        index += 1;
+        match style {
+            MarkerStyle::Merge => {
+                push_marker(&mut out, "<<<<<<<", block.ours_label);
+                push_lines(&mut out, &block.ours);
+                push_marker(&mut out, "=======", b"");
+                push_lines(&mut out, &block.theirs);
+                push_marker(&mut out, ">>>>>>>", block.theirs_label);
+            }
+            MarkerStyle::Diff3 | MarkerStyle::Zdiff3 => {
+                let (ours, theirs) = (&block.ours, &block.theirs);
+                let prefix = ours
+                    .iter()
+                    .zip(theirs.iter())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                let suffix = ours[prefix..]
+                    .iter()
+                    .rev()
+                    .zip(theirs[prefix..].iter().rev())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                push_lines(&mut out, &ours[..prefix]);
+                push_marker(&mut out, "<<<<<<<", block.ours_label);
+                push_lines(&mut out, &ours[prefix..ours.len() - suffix]);
+                push_marker(&mut out, "|||||||", block.base_label.unwrap_or(b"base"));
+                push_lines(&mut out, &block.base);
+                push_marker(&mut out, "=======", b"");
+                push_lines(&mut out, &theirs[prefix..theirs.len() - suffix]);
+                push_marker(&mut out, ">>>>>>>", block.theirs_label);
+                push_lines(&mut out, &ours[ours.len() - suffix..]);
+            }
+            MarkerStyle::Jj => {
+                let header = format!("Conflict {index} of {total}");
+                push_marker(&mut out, "<<<<<<<", header.as_bytes());
+                push_marker(&mut out, "+++++++", block.ours_label);
+                push_lines(&mut out, &block.ours);
+                push_marker(&mut out, "-------", block.base_label.unwrap_or(b"base"));
+                push_lines(&mut out, &block.base);
+                push_marker(&mut out, "+++++++", block.theirs_label);
+                push_lines(&mut out, &block.theirs);
+                push_marker(&mut out, ">>>>>>>", format!("{header} ends").as_bytes());
+            }
+        }
+    }
+    out
+}
+
+/// One changed region of a two-way line diff.
+///
+/// Ranges are 0-indexed line numbers: lines `before` of the old text were
+/// replaced by lines `after` of the new text. Either range may be empty
+/// (pure insertion / deletion).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineChange {
+    /// Replaced lines of the old text.
+    pub before: Range<u32>,
+    /// Replacement lines of the new text.
+    pub after: Range<u32>,
+}
+
+/// Line-level diff of `before` → `after` (histogram algorithm), in order.
+#[must_use]
+pub fn line_changes(before: &[u8], after: &[u8]) -> Vec<LineChange> {
+    let input = InternedInput::new(
+        gix::diff::blob::sources::byte_lines(before),
+        gix::diff::blob::sources::byte_lines(after),
+    );
+    let mut changes = Vec::new();
+    gix::diff::blob::diff(
+        gix::diff::blob::Algorithm::Histogram,
+        &input,
+        |before: Range<u32>, after: Range<u32>| changes.push(LineChange { before, after }),
+    );
+    changes
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn line_changes_reports_replaced_and_inserted_lines() {
+        let changes = line_changes(b"a\nb\nc\n", b"a\nB\nc\nd\n");
+        assert_eq!(
+            changes,
+            vec![
+                LineChange {
+                    before: 1..2,
+                    after: 1..2
+                },
+                LineChange {
+                    before: 3..3,
+                    after: 3..4
+                },
+            ]
+        );
+    }
+
     #[test]
     fn non_overlapping_edits_merge_cleanly() {
         let base = b"line1\nline2\nline3\n";
@@ -337,4 +574,48 @@ mod tests {
             "unknown driver should fall back to diff3"
         );
     }
+
+    const DIFF3_BLOCK: &[u8] =
+        b"head\n<<<<<<< ours\nsame\nA\n||||||| base\nB\n=======\nsame\nC\n>>>>>>> theirs\ntail\n";
+
+    #[test]
+    fn restyle_merge_drops_base_section() {
+        let out = restyle_markers(DIFF3_BLOCK, MarkerStyle::Merge);
+        assert_eq!(
+            String::from_utf8_lossy(&out),
+            "head\n<<<<<<< ours\nsame\nA\n=======\nsame\nC\n>>>>>>> theirs\ntail\n"
+        );
+        assert_eq!(
+            restyle_markers(DIFF3_BLOCK, MarkerStyle::Diff3),
+            DIFF3_BLOCK
+        );
+    }
+
+    #[test]
+    fn restyle_zdiff3_moves_shared_lines_out_of_the_block() {
+        let out = restyle_markers(DIFF3_BLOCK, MarkerStyle::Zdiff3);
+        assert_eq!(
+            String::from_utf8_lossy(&out),
+            "head\nsame\n<<<<<<< ours\nA\n||||||| base\nB\n=======\nC\n>>>>>>> theirs\ntail\n"
+        );
+    }
+
+    #[test]
+    fn restyle_jj_numbers_snapshot_blocks() {
+        let mut input = DIFF3_BLOCK.to_vec();
+        input.extend_from_slice(b"<<<<<<< ours\nx\n||||||| base\n=======\ny\n>>>>>>> theirs\n");
+        let out = String::from_utf8(restyle_markers(&input, MarkerStyle::Jj)).unwrap();
+        assert!(out.starts_with(
+            "head\n<<<<<<< Conflict 1 of 2\n+++++++ ours\nsame\nA\n------- base\nB\n+++++++ theirs\nsame\nC\n>>>>>>> Conflict 1 of 2 ends\ntail\n"
+        ));
+        assert!(out.ends_with(
+            "<<<<<<< Conflict 2 of 2\n+++++++ ours\nx\n------- base\n+++++++ theirs\ny\n>>>>>>> Conflict 2 of 2 ends\n"
+        ));
+    }
+
+    #[test]
+    fn restyle_leaves_unterminated_blocks_alone() {
+        let input = b"<<<<<<< ours\nA\n=======\nB\n";
+        assert_eq!(restyle_markers(input, MarkerStyle::Jj), input);
+    }
 }
@@ -28,8 +28,8 @@ use std::path::Path;
 use crate::diff_impl::NameStatusPairs;
 use crate::error::GitError;
 use crate::types::{
-    CommitInfo, DiffEntry, GitOid, IndexEntry, RefEdit, RefName, StatusEntry, TreeEdit, TreeEntry,
-    WorktreeInfo,
+    CommitInfo, DiffEntry, GitOid, IndexEntry, RefEdit, RefName, SparseCheckout, StatusEntry,
+    TreeEdit, TreeEntry, WorktreeInfo,
 };
 
 /// The git abstraction trait used by all maw crates.
@@ -434,6 +434,44 @@ pub trait GitRepo {
     /// Returns a `GitError` if the backend operation fails.
     fn worktree_add(&self, name: &str, target: GitOid, path: &Path) -> Result<(), GitError>;
 
+    /// Register a linked worktree without checking anything out.
+    ///
+    /// Like [`worktree_add`](Self::worktree_add), but only the admin files,
+    /// the `.git` gitfile and an index matching `target` are written; the
+    /// caller provides the files some other way.
+    ///
+    /// Replaces: `git worktree add --no-checkout --detach <path> <target>`
+    /// + `git read-tree HEAD`.
+    ///
+    /// # Errors
+    /// Returns a `GitError` if the backend operation fails.
+    fn worktree_add_no_checkout(
+        &self,
+        name: &str,
+        target: GitOid,
+        path: &Path,
+    ) -> Result<(), GitError>;
+
+    /// Create a new linked worktree that checks out only part of the tree.
+    ///
+    /// Like [`worktree_add`](Self::worktree_add), but index entries rejected
+    /// by `sparse.include` are marked skip-worktree and never written, and
+    /// the worktree gets its own sparse-checkout config so git commands run
+    /// inside it keep the same selection.
+    ///
+    /// Replaces: `git worktree add --no-checkout` + `git sparse-checkout set`
+    /// + `git read-tree -mu HEAD`.
+    ///
+    /// # Errors
+    /// Returns a `GitError` if the backend operation fails.
+    fn worktree_add_sparse(
+        &self,
+        name: &str,
+        target: GitOid,
+        path: &Path,
+        sparse: &SparseCheckout<'_>,
+    ) -> Result<(), GitError>;
+
     /// Remove a linked worktree by name.
     ///
     /// Replaces: `git worktree remove <name>`.
@@ -369,6 +369,29 @@ pub struct WorktreeInfo {
     pub is_detached: bool,
 }
 
+/// Which paths a sparse linked worktree checks out.
+///
+/// Consumed by [`GitRepo::worktree_add_sparse`](crate::GitRepo::worktree_add_sparse).
+pub struct SparseCheckout<'a> {
+    /// Whether `patterns` are in cone mode (`core.sparseCheckoutCone`).
+    pub cone: bool,
+    /// The lines of the worktree's `info/sparse-checkout` file, so that git
+    /// keeps honouring the selection after creation.
+    pub patterns: &'a [String],
+    /// Index paths to check out. Every other entry gets the skip-worktree
+    /// bit and is left off disk.
+    pub include: &'a dyn Fn(&str) -> bool,
+}
+
+impl std::fmt::Debug for SparseCheckout<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SparseCheckout")
+            .field("cone", &self.cone)
+            .field("patterns", &self.patterns)
+            .finish_non_exhaustive()
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Commit types
 // ---------------------------------------------------------------------------
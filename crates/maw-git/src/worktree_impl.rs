@@ -11,17 +11,86 @@ use gix::bstr::ByteSlice;
 
 use crate::error::GitError;
 use crate::gix_repo::GixRepo;
-use crate::types::{GitOid, WorktreeInfo};
+use crate::types::{GitOid, SparseCheckout, WorktreeInfo};
+
+pub fn worktree_add(
+    repo: &GixRepo,
+    name: &str,
+    target: GitOid,
+    path: &Path,
+) -> Result<(), GitError> {
+    add(repo, name, target, path, None, true)
+}
+
+pub fn worktree_add_no_checkout(
+    repo: &GixRepo,
+    name: &str,
+    target: GitOid,
+    path: &Path,
+) -> Result<(), GitError> {
+    add(repo, name, target, path, None, false)
+}
+
+pub fn worktree_add_sparse(
+    repo: &GixRepo,
+    name: &str,
+    target: GitOid,
+    path: &Path,
+    sparse: &SparseCheckout<'_>,
+) -> Result<(), GitError> {
+    add(repo, name, target, path, Some(sparse), true)?;
+    let git_dir = repo.repo.git_dir().join("worktrees").join(name);
+    write_sparse_config(repo, &git_dir, sparse)
+}
+
+/// Mark every entry `include` rejects as skip-worktree.
+fn mark_skip_worktree(index: &mut gix::index::File, include: &dyn Fn(&str) -> bool) {
+    for (entry, path) in index.entries_mut_with_paths() {
+        if !path.to_str().is_ok_and(include) {
+            entry.flags |=
+                gix::index::entry::Flags::SKIP_WORKTREE | gix::index::entry::Flags::EXTENDED;
+        }
+    }
+}
+
+/// Persist the sparse selection the way `git sparse-checkout set` does:
+/// per-worktree config (which needs `extensions.worktreeConfig`) plus the
+/// worktree's `info/sparse-checkout` patterns.
+fn write_sparse_config(
+    repo: &GixRepo,
+    admin_dir: &Path,
+    sparse: &SparseCheckout<'_>,
+) -> Result<(), GitError> {
+    let io_err = |what: &str, e: std::io::Error| GitError::BackendError {
+        message: format!("failed to write worktree {what}: {e}"),
+    };
+    crate::config_impl::write_config(repo, "extensions.worktreeConfig", "true")?;
+    std::fs::write(
+        admin_dir.join("config.worktree"),
+        format!(
+            "[core]\n\tsparseCheckout = true\n\tsparseCheckoutCone = {}\n",
+            sparse.cone
+        ),
+    )
+    .map_err(|e| io_err("config", e))?;
+    let info = admin_dir.join("info");
+    std::fs::create_dir_all(&info).map_err(|e| io_err("info dir", e))?;
+    let mut patterns = sparse.patterns.join("\n");
+    patterns.push('\n');
+    std::fs::write(info.join("sparse-checkout"), patterns).map_err(|e| io_err("sparse-checkout", e))
+}
 
 #[expect(
     clippy::too_many_lines,
     reason = "worktree creation writes git admin files then checks out"
 )]
-pub fn worktree_add(
+fn add(
     repo: &GixRepo,
     name: &str,
     target: GitOid,
     path: &Path,
+    sparse: Option<&SparseCheckout<'_>>,
+    checkout: bool,
 ) -> Result<(), GitError> {
     // Reject names with path separators or .. components (path traversal protection).
     if name.contains('/') || name.contains('\\') || name == ".." || name.contains("/../") {
@@ -110,12 +179,15 @@ pub fn worktree_add(
     };
 
     // 8. Build index from tree and write to admin dir
-    let index_state = repo
-        .repo
-        .index_from_tree(&tree_oid)
-        .map_err(|e| GitError::BackendError {
-            message: format!("failed to create index from tree {tree_oid}: {e}"),
-        })?;
+    let mut index_state =
+        repo.repo
+            .index_from_tree(&tree_oid)
+            .map_err(|e| GitError::BackendError {
+                message: format!("failed to create index from tree {tree_oid}: {e}"),
+            })?;
+    if let Some(sparse) = sparse {
+        mark_skip_worktree(&mut index_state, sparse.include);
+    }
 
     let index_path = admin_dir.join("index");
     let mut index_file = gix::index::File::from_state(index_state.into(), index_path);
@@ -124,6 +196,9 @@ pub fn worktree_add(
         .map_err(|e| GitError::BackendError {
             message: format!("failed to write worktree index: {e}"),
         })?;
+    if !checkout {
+        return Ok(());
+    }
 
     // 9. Checkout the tree to the worktree path
     let mut checkout_index =
@@ -132,6 +207,10 @@ pub fn worktree_add(
             .map_err(|e| GitError::BackendError {
                 message: format!("failed to create index for checkout: {e}"),
             })?;
+    // Skip-worktree entries are left off disk by the checkout.
+    if let Some(sparse) = sparse {
+        mark_skip_worktree(&mut checkout_index, sparse.include);
+    }
 
     let mut opts = repo
         .repo
@@ -740,6 +740,29 @@ fn worktree_has_files_checked_out() {
     assert!(file.exists(), "hello.txt should be checked out in worktree");
 }
 
+#[test]
+fn worktree_add_no_checkout_registers_without_files() {
+    let (dir, repo, commit_oid, _) = setup_repo_with_commit();
+    let wt_path = dir.path().join("wt-bare");
+    repo.worktree_add_no_checkout("wt-bare", commit_oid, &wt_path)
+        .expect("test setup should succeed");
+    assert!(wt_path.join(".git").is_file(), ".git gitfile should exist");
+    assert!(
+        !wt_path.join("hello.txt").exists(),
+        "nothing should be checked out"
+    );
+    assert!(
+        dir.path().join(".git/worktrees/wt-bare/index").is_file(),
+        "the index should still be written"
+    );
+    let list = repo.worktree_list().expect("test setup should succeed");
+    let wt = list
+        .iter()
+        .find(|w| w.name == "wt-bare")
+        .expect("worktree should be listed");
+    assert_eq!(wt.head_oid, Some(commit_oid));
+}
+
 // ===========================================================================
 // 8. Ancestry
 // ===========================================================================
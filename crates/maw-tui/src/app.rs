@@ -11,6 +11,7 @@ use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEventKind};
 use ratatui::{Terminal, layout::Rect, prelude::CrosstermBackend};
 
 use crate::event::{self, AppEvent};
+use crate::resolver::{ConflictFile, HunkChoice, Resolver};
 use crate::ui;
 
 // ---------------------------------------------------------------------------
@@ -47,6 +48,20 @@ pub trait RepoDataSource {
     /// # Errors
     /// Returns an error if workspace metadata cannot be queried.
     fn list_workspaces(&self) -> Result<Vec<WorkspaceEntry>>;
+
+    /// Structured conflicts of `workspace` (from its `conflict-tree.json`),
+    /// split into hunks for the resolver view.
+    ///
+    /// # Errors
+    /// Returns an error if the sidecar or conflict blobs cannot be read.
+    fn conflicted_files(&self, workspace: &str) -> Result<Vec<ConflictFile>>;
+
+    /// Write the resolved `content` of `path` in `workspace` through the
+    /// structured resolver. Returns a one-line summary for the status bar.
+    ///
+    /// # Errors
+    /// Returns an error if the path is not conflicted or the write fails.
+    fn write_resolution(&self, workspace: &str, path: &str, content: &str) -> Result<String>;
 }
 
 // ---------------------------------------------------------------------------
@@ -265,6 +280,10 @@ pub struct App {
     last_refresh: Instant,
     /// Pane areas for mouse hit testing (updated each frame).
     pub pane_areas: Vec<Rect>,
+    /// Conflict resolver, when open (replaces the pane grid).
+    pub resolver: Option<Resolver>,
+    /// Last status message shown in the footer.
+    pub status: Option<String>,
     /// Data source for repo/workspace queries.
     data_source: Box<dyn RepoDataSource>,
 }
@@ -289,6 +308,8 @@ impl App {
             overlap_paths: HashMap::new(),
             last_refresh: Instant::now(),
             pane_areas: Vec::new(),
+            resolver: None,
+            status: None,
             data_source,
         };
         app.refresh()?;
@@ -312,11 +333,16 @@ impl App {
                 AppEvent::Mouse(mouse) => {
                     self.handle_mouse(mouse.kind, mouse.column, mouse.row);
                 }
-                AppEvent::Resize { .. } | AppEvent::Tick | AppEvent::Paste(_) => {}
+                AppEvent::Paste(text) => {
+                    if let Some(editor) = self.resolver.as_mut().and_then(|r| r.editor.as_mut()) {
+                        editor.buffer.push_str(&text);
+                    }
+                }
+                AppEvent::Resize { .. } | AppEvent::Tick => {}
             }
 
-            // Periodic refresh every 2 seconds
-            if self.last_refresh.elapsed() > Duration::from_secs(2) {
+            // Periodic refresh every 2 seconds (paused while resolving)
+            if self.resolver.is_none() && self.last_refresh.elapsed() > Duration::from_secs(2) {
                 self.refresh()?;
             }
         }
@@ -329,6 +355,9 @@ impl App {
             self.show_help = false;
             return Ok(());
         }
+        if self.resolver.is_some() {
+            return self.handle_resolver_key(code, modifiers);
+        }
 
         match code {
             // Quit
@@ -359,11 +388,116 @@ impl App {
             // Toggle collapse
             KeyCode::Enter | KeyCode::Char(' ') => self.toggle_collapse(),
 
+            // Conflict resolver for the focused workspace
+            KeyCode::Char('c') => self.open_resolver()?,
+
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn open_resolver(&mut self) -> Result<()> {
+        let Some(name) = self
+            .workspaces
+            .get(self.focused_pane)
+            .map(|ws| ws.name.clone())
+        else {
+            return Ok(());
+        };
+        let files = self.data_source.conflicted_files(&name)?;
+        if files.is_empty() {
+            self.status = Some(format!("No structured conflicts in '{name}'."));
+        } else {
+            self.status = None;
+            self.resolver = Some(Resolver::new(name, files));
+        }
+        Ok(())
+    }
+
+    fn close_resolver(&mut self) -> Result<()> {
+        self.resolver = None;
+        self.refresh()
+    }
+
+    fn handle_resolver_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+        let Some(resolver) = self.resolver.as_mut() else {
+            return Ok(());
+        };
+
+        if let Some(editor) = resolver.editor.as_mut() {
+            match code {
+                KeyCode::Char('s') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    resolver.finish_edit();
+                }
+                KeyCode::Esc => resolver.cancel_edit(),
+                KeyCode::Enter => editor.buffer.push('\n'),
+                KeyCode::Tab => editor.buffer.push_str("    "),
+                KeyCode::Backspace => {
+                    editor.buffer.pop();
+                }
+                KeyCode::Char(c) => editor.buffer.push(c),
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.close_resolver()?,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.should_quit = true;
+            }
+            KeyCode::Char('?') => self.show_help = true,
+            KeyCode::Char('j') | KeyCode::Down => resolver.move_hunk(1),
+            KeyCode::Char('k') | KeyCode::Up => resolver.move_hunk(-1),
+            KeyCode::Tab => resolver.move_file(1),
+            KeyCode::BackTab => resolver.move_file(-1),
+            KeyCode::Char(c @ '1'..='9') => {
+                resolver.choose(HunkChoice::Side(c as usize - '1' as usize));
+            }
+            KeyCode::Char('b') => resolver.choose(HunkChoice::Combined),
+            KeyCode::Char('u') => resolver.choose(HunkChoice::Unresolved),
+            KeyCode::Char('e') => resolver.start_edit(),
+            KeyCode::Char('w') => self.write_current_file()?,
             _ => {}
         }
         Ok(())
     }
 
+    /// Write the selected file once every hunk has a choice.
+    fn write_current_file(&mut self) -> Result<()> {
+        let Some(resolver) = self.resolver.as_mut() else {
+            return Ok(());
+        };
+        let Some(file) = resolver.current_file() else {
+            return Ok(());
+        };
+        let Some(content) = file.render() else {
+            resolver.status = Some(format!(
+                "{}: {} of {} hunk(s) still unresolved.",
+                file.path,
+                file.hunk_count() - file.resolved_count(),
+                file.hunk_count()
+            ));
+            return Ok(());
+        };
+        let path = file.path.clone();
+        match self
+            .data_source
+            .write_resolution(&resolver.workspace, &path, &content)
+        {
+            Ok(summary) => {
+                resolver.remove_current_file();
+                if resolver.files.is_empty() {
+                    self.status = Some(summary);
+                    return self.close_resolver();
+                }
+                resolver.status = Some(summary);
+            }
+            Err(e) => resolver.status = Some(format!("{path}: {e}")),
+        }
+        Ok(())
+    }
+
     fn handle_mouse(&mut self, kind: MouseEventKind, x: u16, y: u16) {
         if self.show_help {
             return;
@@ -2,10 +2,12 @@
 
 pub mod app;
 pub mod event;
+pub mod resolver;
 pub mod theme;
 pub mod ui;
 
 pub use app::{App, RepoDataSource, WorkspaceEntry};
+pub use resolver::{ConflictFile, ConflictHunk, ConflictSegment, HunkChoice, HunkSide};
 
 use std::io;
 
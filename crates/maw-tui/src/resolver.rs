@@ -0,0 +1,349 @@
+//! Conflict resolver view state — hunk-by-hunk resolution of a workspace's
+//! structured conflicts.
+//!
+//! The data source turns `conflict-tree.json` into [`ConflictFile`]s: each
+//! file is a sequence of context text and [`ConflictHunk`]s, one per conflict
+//! atom, carrying the base and every workspace side. The user picks a side,
+//! combines all sides, or edits the hunk inline; once every hunk of a file
+//! has a choice, the rendered file is handed back to the data source, which
+//! writes it through the structured resolver (sanity checks, sidecar update,
+//! auto-commit).
+
+/// One side of a conflict hunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HunkSide {
+    /// Workspace label (`epoch` for the rebase target side).
+    pub name: String,
+    /// The side's text for this hunk.
+    pub text: String,
+}
+
+/// How the user resolved a hunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HunkChoice {
+    /// Not resolved yet.
+    Unresolved,
+    /// Keep side `n`.
+    Side(usize),
+    /// Keep every side, in order.
+    Combined,
+    /// Replace the hunk with hand-edited text.
+    Edited(String),
+}
+
+/// One conflicted region of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictHunk {
+    /// Short description (conflict reason / region), shown above the sides.
+    pub label: String,
+    /// Base text, when the conflict has a common ancestor.
+    pub base: Option<String>,
+    /// Every workspace side, in sidecar order.
+    pub sides: Vec<HunkSide>,
+    /// The user's decision.
+    pub choice: HunkChoice,
+}
+
+impl ConflictHunk {
+    /// The text this hunk resolves to, or `None` while unresolved.
+    #[must_use]
+    pub fn resolution(&self) -> Option<String> {
+        match &self.choice {
+            HunkChoice::Unresolved => None,
+            HunkChoice::Side(i) => self.sides.get(*i).map(|side| side.text.clone()),
+            HunkChoice::Combined => {
+                let mut out = String::new();
+                for side in &self.sides {
+                    out.push_str(&side.text);
+                    if !out.is_empty() && !out.ends_with('\n') {
+                        out.push('\n');
+                    }
+                }
+                Some(out)
+            }
+            HunkChoice::Edited(text) => Some(text.clone()),
+        }
+    }
+}
+
+/// A region of a conflicted file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictSegment {
+    /// Text both sides agree on.
+    Context(String),
+    /// A conflicted region.
+    Hunk(ConflictHunk),
+}
+
+/// A conflicted file, split into context and hunks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictFile {
+    /// Path relative to the workspace root.
+    pub path: String,
+    /// Context and hunks in file order.
+    pub segments: Vec<ConflictSegment>,
+}
+
+impl ConflictFile {
+    /// Hunks in file order.
+    pub fn hunks(&self) -> impl Iterator<Item = &ConflictHunk> {
+        self.segments.iter().filter_map(|segment| match segment {
+            ConflictSegment::Hunk(hunk) => Some(hunk),
+            ConflictSegment::Context(_) => None,
+        })
+    }
+
+    /// Number of hunks.
+    #[must_use]
+    pub fn hunk_count(&self) -> usize {
+        self.hunks().count()
+    }
+
+    /// Number of hunks with a choice.
+    #[must_use]
+    pub fn resolved_count(&self) -> usize {
+        self.hunks()
+            .filter(|hunk| hunk.choice != HunkChoice::Unresolved)
+            .count()
+    }
+
+    /// The `n`th hunk.
+    #[must_use]
+    pub fn hunk(&self, n: usize) -> Option<&ConflictHunk> {
+        self.hunks().nth(n)
+    }
+
+    fn hunk_mut(&mut self, n: usize) -> Option<&mut ConflictHunk> {
+        self.segments
+            .iter_mut()
+            .filter_map(|segment| match segment {
+                ConflictSegment::Hunk(hunk) => Some(hunk),
+                ConflictSegment::Context(_) => None,
+            })
+            .nth(n)
+    }
+
+    /// The resolved file content, or `None` while any hunk is unresolved.
+    #[must_use]
+    pub fn render(&self) -> Option<String> {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                ConflictSegment::Context(text) => out.push_str(text),
+                ConflictSegment::Hunk(hunk) => out.push_str(&hunk.resolution()?),
+            }
+        }
+        Some(out)
+    }
+}
+
+/// Move `index` by `direction` (±1) within `0..count`, wrapping.
+const fn step(index: usize, count: usize, direction: isize) -> usize {
+    (index + count).saturating_add_signed(direction) % count
+}
+
+/// Inline editor state for the selected hunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HunkEditor {
+    /// Text being edited.
+    pub buffer: String,
+}
+
+/// Resolver view state for one workspace.
+#[derive(Debug, Clone)]
+pub struct Resolver {
+    /// Workspace whose conflicts are shown.
+    pub workspace: String,
+    /// Conflicted files still to resolve.
+    pub files: Vec<ConflictFile>,
+    /// Selected file.
+    pub file: usize,
+    /// Selected hunk within the file.
+    pub hunk: usize,
+    /// Inline editor, when editing the selected hunk.
+    pub editor: Option<HunkEditor>,
+    /// Last status line (write results, errors).
+    pub status: Option<String>,
+}
+
+impl Resolver {
+    /// Open the resolver on `files`.
+    #[must_use]
+    pub const fn new(workspace: String, files: Vec<ConflictFile>) -> Self {
+        Self {
+            workspace,
+            files,
+            file: 0,
+            hunk: 0,
+            editor: None,
+            status: None,
+        }
+    }
+
+    /// The selected file.
+    #[must_use]
+    pub fn current_file(&self) -> Option<&ConflictFile> {
+        self.files.get(self.file)
+    }
+
+    /// The selected hunk.
+    #[must_use]
+    pub fn current_hunk(&self) -> Option<&ConflictHunk> {
+        self.current_file()?.hunk(self.hunk)
+    }
+
+    /// Move to the next (`+1`) or previous (`-1`) hunk, wrapping.
+    pub fn move_hunk(&mut self, direction: isize) {
+        let count = self.current_file().map_or(0, ConflictFile::hunk_count);
+        if count > 0 {
+            self.hunk = step(self.hunk, count, direction);
+        }
+    }
+
+    /// Move to the next (`+1`) or previous (`-1`) file, wrapping.
+    pub const fn move_file(&mut self, direction: isize) {
+        let count = self.files.len();
+        if count > 0 {
+            self.file = step(self.file, count, direction);
+            self.hunk = 0;
+        }
+    }
+
+    /// Record a choice for the selected hunk and advance to the next
+    /// unresolved one.
+    pub fn choose(&mut self, choice: HunkChoice) {
+        let hunk = self.hunk;
+        let Some(file) = self.files.get_mut(self.file) else {
+            return;
+        };
+        if let HunkChoice::Side(i) = choice
+            && file.hunk(hunk).is_none_or(|h| i >= h.sides.len())
+        {
+            return;
+        }
+        if let Some(target) = file.hunk_mut(hunk) {
+            target.choice = choice;
+        }
+        if let Some(next) = (0..file.hunk_count())
+            .map(|offset| (hunk + offset) % file.hunk_count())
+            .find(|&n| {
+                file.hunk(n)
+                    .is_some_and(|h| h.choice == HunkChoice::Unresolved)
+            })
+        {
+            self.hunk = next;
+        }
+    }
+
+    /// Start editing the selected hunk, seeded with its current resolution
+    /// (or the first side).
+    pub fn start_edit(&mut self) {
+        let Some(hunk) = self.current_hunk() else {
+            return;
+        };
+        let buffer = hunk
+            .resolution()
+            .or_else(|| hunk.sides.first().map(|side| side.text.clone()))
+            .unwrap_or_default();
+        self.editor = Some(HunkEditor { buffer });
+    }
+
+    /// Finish editing: store the buffer as the hunk's resolution.
+    pub fn finish_edit(&mut self) {
+        if let Some(editor) = self.editor.take() {
+            let mut text = editor.buffer;
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            self.choose(HunkChoice::Edited(text));
+        }
+    }
+
+    /// Abandon the inline edit.
+    pub fn cancel_edit(&mut self) {
+        self.editor = None;
+    }
+
+    /// Remove the selected file after it was written.
+    pub fn remove_current_file(&mut self) {
+        if self.file < self.files.len() {
+            self.files.remove(self.file);
+        }
+        if self.file >= self.files.len() {
+            self.file = self.files.len().saturating_sub(1);
+        }
+        self.hunk = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn side(name: &str, text: &str) -> HunkSide {
+        HunkSide {
+            name: name.to_owned(),
+            text: text.to_owned(),
+        }
+    }
+
+    fn hunk(ours: &str, theirs: &str) -> ConflictSegment {
+        ConflictSegment::Hunk(ConflictHunk {
+            label: "lines".to_owned(),
+            base: Some("x = 1\n".to_owned()),
+            sides: vec![side("epoch", ours), side("alice", theirs)],
+            choice: HunkChoice::Unresolved,
+        })
+    }
+
+    fn file() -> ConflictFile {
+        ConflictFile {
+            path: "src/lib.rs".to_owned(),
+            segments: vec![
+                ConflictSegment::Context("a\n".to_owned()),
+                hunk("x = 2\n", "x = 3\n"),
+                ConflictSegment::Context("b\n".to_owned()),
+                hunk("y = 2\n", "y = 3\n"),
+            ],
+        }
+    }
+
+    #[test]
+    fn render_requires_every_hunk_resolved() {
+        let mut resolver = Resolver::new("alice".to_owned(), vec![file()]);
+        resolver.choose(HunkChoice::Side(1));
+        assert_eq!(resolver.hunk, 1, "advances to the next unresolved hunk");
+        assert!(resolver.files[0].render().is_none());
+
+        resolver.choose(HunkChoice::Combined);
+        assert_eq!(
+            resolver.files[0].render().as_deref(),
+            Some("a\nx = 3\nb\ny = 2\ny = 3\n")
+        );
+    }
+
+    #[test]
+    fn inline_edit_replaces_hunk_text() {
+        let mut resolver = Resolver::new("alice".to_owned(), vec![file()]);
+        resolver.start_edit();
+        assert_eq!(
+            resolver.editor.as_ref().map(|e| e.buffer.as_str()),
+            Some("x = 2\n")
+        );
+        resolver.editor.as_mut().expect("editing").buffer = "x = 5".to_owned();
+        resolver.finish_edit();
+        assert_eq!(
+            resolver.files[0].hunk(0).and_then(ConflictHunk::resolution),
+            Some("x = 5\n".to_owned())
+        );
+    }
+
+    #[test]
+    fn out_of_range_side_is_ignored() {
+        let mut resolver = Resolver::new("alice".to_owned(), vec![file()]);
+        resolver.choose(HunkChoice::Side(5));
+        assert_eq!(resolver.files[0].resolved_count(), 0);
+        resolver.move_hunk(-1);
+        assert_eq!(resolver.hunk, 1);
+    }
+}
@@ -12,7 +12,6 @@ pub const BORDER_TYPE: BorderType = BorderType::Rounded;
 #[allow(dead_code)]
 pub const CURRENT: Color = Color::Green;
 pub const STALE: Color = Color::Yellow;
-#[allow(dead_code)]
 pub const CONFLICT: Color = Color::Red;
 
 // File status colors
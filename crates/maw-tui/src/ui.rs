@@ -7,6 +7,7 @@ use ratatui::{
 };
 
 use crate::app::{App, TreeNode, flatten_tree, format_time_ago};
+use crate::resolver::{ConflictHunk, HunkChoice, Resolver};
 use crate::theme;
 
 /// Create a styled block with rounded corners
@@ -23,6 +24,14 @@ fn styled_block(title: &str, is_focused: bool) -> Block<'_> {
 }
 
 pub fn draw(frame: &mut Frame, app: &mut App) {
+    if let Some(resolver) = &app.resolver {
+        draw_resolver(frame, resolver);
+        if app.show_help {
+            draw_help_popup(frame);
+        }
+        return;
+    }
+
     let has_overlaps = !app.overlaps.is_empty();
 
     let mut constraints = vec![Constraint::Length(1)];
@@ -62,7 +71,7 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
     }
 
     draw_pane_grid(frame, app, grid_area);
-    draw_footer(frame, footer_area);
+    draw_footer(frame, footer_area, app.status.as_deref());
 
     if app.show_help {
         draw_help_popup(frame);
@@ -326,7 +335,21 @@ fn workspace_pane_title(ws: &crate::app::WorkspacePane) -> String {
     title_parts.join("")
 }
 
-fn draw_footer(frame: &mut Frame, area: Rect) {
+fn key_hint(key: &str, label: &str) -> [Span<'static>; 2] {
+    [
+        Span::styled(
+            key.to_owned(),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!(" {label}  ")),
+    ]
+}
+
+fn draw_footer(frame: &mut Frame, area: Rect, status: Option<&str>) {
+    if let Some(status) = status {
+        frame.render_widget(Paragraph::new(format!("  {status}")), area);
+        return;
+    }
     let line = Line::from(vec![
         Span::raw("  "),
         Span::styled("r", Style::default().add_modifier(Modifier::BOLD)),
@@ -337,6 +360,8 @@ fn draw_footer(frame: &mut Frame, area: Rect) {
         Span::raw(" navigate  "),
         Span::styled("tab", Style::default().add_modifier(Modifier::BOLD)),
         Span::raw(" cycle pane  "),
+        Span::styled("c", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(" resolve conflicts  "),
         Span::styled("?", Style::default().add_modifier(Modifier::BOLD)),
         Span::raw(" help"),
     ]);
@@ -347,7 +372,7 @@ fn draw_help_popup(frame: &mut Frame) {
     let area = frame.area();
 
     let popup_width = 50.min(area.width.saturating_sub(4));
-    let popup_height = 16.min(area.height.saturating_sub(4));
+    let popup_height = 23.min(area.height.saturating_sub(4));
     let popup_x = (area.width.saturating_sub(popup_width)) / 2;
     let popup_y = (area.height.saturating_sub(popup_height)) / 2;
 
@@ -364,6 +389,16 @@ fn draw_help_popup(frame: &mut Frame) {
         Line::from("  g/G            Go to top/bottom"),
         Line::from("  Tab/Shift-Tab  Cycle panes"),
         Line::from("  Enter/Space    Toggle dir collapse"),
+        Line::from("  c              Resolve workspace conflicts"),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Resolver",
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from("  j/k, Tab       Next hunk / next file"),
+        Line::from("  1-9, b         Keep side N / combine all"),
+        Line::from("  e, Ctrl-S      Edit hunk / finish edit"),
+        Line::from("  u, w, Esc      Undo / write file / back"),
         Line::from(""),
         Line::from(Span::styled(
             "General",
@@ -390,3 +425,124 @@ fn draw_help_popup(frame: &mut Frame) {
     let paragraph = Paragraph::new(help_text).block(block);
     frame.render_widget(paragraph, popup_area);
 }
+
+// ---------------------------------------------------------------------------
+// Conflict resolver
+// ---------------------------------------------------------------------------
+
+fn draw_resolver(frame: &mut Frame, resolver: &Resolver) {
+    let outer = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(4),
+            Constraint::Length(8),
+            Constraint::Length(1),
+        ])
+        .split(frame.area());
+
+    let Some(file) = resolver.current_file() else {
+        return;
+    };
+    let header = Line::from(vec![
+        Span::styled(" resolve ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(format!("{}  ", resolver.workspace)),
+        Span::styled(file.path.clone(), Style::default().fg(theme::CONFLICT)),
+        Span::raw(format!(
+            "  file {}/{}  hunk {}/{}  ({} resolved)",
+            resolver.file + 1,
+            resolver.files.len(),
+            resolver.hunk + 1,
+            file.hunk_count(),
+            file.resolved_count()
+        )),
+    ]);
+    frame.render_widget(Paragraph::new(header), outer[0]);
+
+    let Some(hunk) = resolver.current_hunk() else {
+        return;
+    };
+    frame.render_widget(
+        Paragraph::new(format!("  {}", hunk.label)).style(Style::default().fg(theme::STALE)),
+        outer[1],
+    );
+
+    draw_hunk_sides(frame, hunk, outer[2]);
+
+    // Resolution preview, or the inline editor.
+    let (title, body) = resolver.editor.as_ref().map_or_else(
+        || {
+            (
+                "resolution",
+                hunk.resolution()
+                    .unwrap_or_else(|| "(unresolved)".to_owned()),
+            )
+        },
+        |editor| {
+            (
+                "edit (Ctrl-S done, Esc cancel)",
+                format!("{}_", editor.buffer),
+            )
+        },
+    );
+    frame.render_widget(
+        Paragraph::new(body).block(styled_block(title, resolver.editor.is_some())),
+        outer[3],
+    );
+
+    if let Some(status) = &resolver.status {
+        frame.render_widget(Paragraph::new(format!("  {status}")), outer[4]);
+    } else {
+        let mut spans = vec![Span::raw("  ")];
+        for (key, label) in [
+            ("1-9", "keep side"),
+            ("b", "both"),
+            ("e", "edit"),
+            ("u", "undo"),
+            ("j/k", "hunk"),
+            ("tab", "file"),
+            ("w", "write"),
+            ("esc", "back"),
+        ] {
+            spans.extend(key_hint(key, label));
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)), outer[4]);
+    }
+}
+
+/// Base and every side of `hunk`, side-by-side; chosen sides are highlighted.
+fn draw_hunk_sides(frame: &mut Frame, hunk: &ConflictHunk, area: Rect) {
+    let mut columns: Vec<(String, &str, bool)> = Vec::new();
+    if let Some(base) = &hunk.base {
+        columns.push(("base".to_owned(), base.as_str(), false));
+    }
+    for (i, side) in hunk.sides.iter().enumerate() {
+        let chosen = match hunk.choice {
+            HunkChoice::Side(n) => n == i,
+            HunkChoice::Combined => true,
+            HunkChoice::Unresolved | HunkChoice::Edited(_) => false,
+        };
+        columns.push((
+            format!("{} {}", i + 1, side.name),
+            side.text.as_str(),
+            chosen,
+        ));
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    let constraints: Vec<Constraint> = columns
+        .iter()
+        .map(|_| Constraint::Ratio(1, columns.len() as u32))
+        .collect();
+    let column_areas = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(constraints)
+        .split(area);
+    for ((title, text, chosen), column) in columns.iter().zip(column_areas.iter()) {
+        let text = if text.is_empty() { "(empty)" } else { text };
+        frame.render_widget(
+            Paragraph::new(text.to_owned()).block(styled_block(title, *chosen)),
+            *column,
+        );
+    }
+}
@@ -15,8 +15,29 @@
 //!
 //! # Supported languages
 //!
-//! Initially supports Rust, Python, and TypeScript. Languages are detected
-//! from file extensions and must be enabled via config.
+//! Supports Rust, Python, TypeScript, JavaScript, Go, Java, C, C++, and C#.
+//! Languages are detected from file extensions and must be enabled via config.
+//!
+//! # Member-level merging
+//!
+//! Containers present in the base — Rust `impl`/`trait`/inline `mod` blocks,
+//! Python/TS/JS/Java/C# classes and interfaces, C++ namespaces and classes —
+//! are descended into, so members of the same container merge
+//! independently. Members are keyed by their scope path (e.g.
+//! `impl Foo::bar`, `class A::f`), which is also the name carried by
+//! conflict regions. Go methods are grouped under their receiver type
+//! (`type Foo::String`). Added members are spliced in before the container's
+//! closing brace, after the last statement of an indented body, or after the
+//! last method of the same Go receiver.
+//!
+//! # Import union
+//!
+//! Imports (`use`, `import`, `#include`, `using`, Go import specs) added by
+//! several variants are unioned into the base import group each variant put
+//! them next to, de-duplicated, and placed at their sorted position when the
+//! group is already sorted. The merged imports are reported through
+//! [`try_ast_merge_with_report`] so the auto-resolution shows up in the
+//! merge plan.
 //!
 //! # Determinism guarantee
 //!
@@ -25,11 +46,12 @@
 //! - Conflicts are sorted by base byte position
 //! - New items are appended in variant order (lexicographic by workspace ID)
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::Path;
 
-use tree_sitter::{Language, Parser, Tree};
+use tree_sitter::{Language, Node, Parser, Tree};
 
+use super::structured_merge::StructuredFormat;
 use crate::model::conflict::{
     AtomEdit, ConflictAtom, ConflictReason, Region, SemanticConflictExplanation,
 };
@@ -47,6 +69,10 @@ pub enum AstLanguage {
     TypeScript,
     JavaScript,
     Go,
+    Java,
+    C,
+    Cpp,
+    CSharp,
 }
 
 impl AstLanguage {
@@ -61,22 +87,31 @@ impl AstLanguage {
             "ts" | "tsx" => Some(Self::TypeScript),
             "js" | "jsx" | "mjs" | "cjs" => Some(Self::JavaScript),
             "go" => Some(Self::Go),
+            "java" => Some(Self::Java),
+            "c" | "h" => Some(Self::C),
+            "cc" | "cpp" | "cxx" | "c++" | "hh" | "hpp" | "hxx" | "h++" => Some(Self::Cpp),
+            "cs" => Some(Self::CSharp),
             _ => None,
         }
     }
 
     /// Get the tree-sitter `Language` for this language.
-    fn tree_sitter_language(self) -> Language {
+    pub(crate) fn tree_sitter_language(self) -> Language {
         match self {
             Self::Rust => tree_sitter_rust::LANGUAGE.into(),
             Self::Python => tree_sitter_python::LANGUAGE.into(),
             Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
             Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
             Self::Go => tree_sitter_go::LANGUAGE.into(),
+            Self::Java => tree_sitter_java::LANGUAGE.into(),
+            Self::C => tree_sitter_c::LANGUAGE.into(),
+            Self::Cpp => tree_sitter_cpp::LANGUAGE.into(),
+            Self::CSharp => tree_sitter_c_sharp::LANGUAGE.into(),
         }
     }
 
     /// Node kinds that represent top-level named items for this language.
+    #[allow(clippy::too_many_lines)]
     const fn named_item_kinds(self) -> &'static [&'static str] {
         match self {
             Self::Rust => &[
@@ -93,26 +128,182 @@ impl AstLanguage {
                 "macro_definition",
                 "use_declaration",
                 "extern_crate_declaration",
+                "function_signature_item",
+                "associated_type",
             ],
             Self::Python => &[
+                "future_import_statement",
+                "import_statement",
+                "import_from_statement",
                 "function_definition",
                 "class_definition",
                 "decorated_definition",
             ],
             Self::TypeScript | Self::JavaScript => &[
+                "import_statement",
+                "export_statement",
                 "function_declaration",
                 "class_declaration",
+                "abstract_class_declaration",
                 "interface_declaration",
                 "type_alias_declaration",
                 "enum_declaration",
                 "method_definition",
+                "public_field_definition",
+                "field_definition",
+                "method_signature",
+                "abstract_method_signature",
+                "property_signature",
             ],
             Self::Go => &[
+                "import_declaration",
+                "import_spec",
                 "function_declaration",
                 "method_declaration",
                 "type_declaration",
             ],
+            Self::Java => &[
+                "package_declaration",
+                "import_declaration",
+                "class_declaration",
+                "interface_declaration",
+                "enum_declaration",
+                "record_declaration",
+                "annotation_type_declaration",
+                "method_declaration",
+                "constructor_declaration",
+                "field_declaration",
+                "constant_declaration",
+            ],
+            Self::C => &[
+                "preproc_include",
+                "preproc_def",
+                "preproc_function_def",
+                "function_definition",
+                "declaration",
+                "type_definition",
+                "struct_specifier",
+                "enum_specifier",
+                "union_specifier",
+            ],
+            Self::Cpp => &[
+                "preproc_include",
+                "preproc_def",
+                "preproc_function_def",
+                "namespace_definition",
+                "class_specifier",
+                "struct_specifier",
+                "enum_specifier",
+                "union_specifier",
+                "function_definition",
+                "declaration",
+                "field_declaration",
+                "type_definition",
+                "template_declaration",
+                "alias_declaration",
+                "using_declaration",
+            ],
+            Self::CSharp => &[
+                "using_directive",
+                "namespace_declaration",
+                "class_declaration",
+                "struct_declaration",
+                "interface_declaration",
+                "record_declaration",
+                "enum_declaration",
+                "delegate_declaration",
+                "method_declaration",
+                "constructor_declaration",
+                "destructor_declaration",
+                "property_declaration",
+                "indexer_declaration",
+                "operator_declaration",
+                "field_declaration",
+                "event_declaration",
+                "event_field_declaration",
+            ],
+        }
+    }
+
+    /// Node kinds whose body members are extracted as individual items
+    /// instead of treating the whole node as one opaque item.
+    const fn container_kinds(self) -> &'static [&'static str] {
+        match self {
+            Self::Rust => &["impl_item", "trait_item", "mod_item"],
+            Self::Python => &["class_definition"],
+            Self::TypeScript | Self::JavaScript => &[
+                "class_declaration",
+                "abstract_class_declaration",
+                "interface_declaration",
+            ],
+            Self::Java => &[
+                "class_declaration",
+                "interface_declaration",
+                "record_declaration",
+            ],
+            Self::Cpp => &[
+                "namespace_definition",
+                "class_specifier",
+                "struct_specifier",
+            ],
+            Self::CSharp => &[
+                "namespace_declaration",
+                "class_declaration",
+                "struct_declaration",
+                "interface_declaration",
+                "record_declaration",
+            ],
+            Self::Go | Self::C => &[],
+        }
+    }
+
+    /// The container declaration and its member list, if `node` is (or
+    /// wraps) a container. Looks through Python decorators and TS/JS
+    /// `export` statements; Go `import (...)` blocks hold their specs.
+    fn container_parts(self, node: Node<'_>) -> Option<(Node<'_>, Node<'_>)> {
+        match node.kind() {
+            "decorated_definition" => self.container_parts(node.child_by_field_name("definition")?),
+            "export_statement" => self.container_parts(node.child_by_field_name("declaration")?),
+            "import_declaration" if self == Self::Go => {
+                let mut cursor = node.walk();
+                let specs = node
+                    .named_children(&mut cursor)
+                    .find(|c| c.kind() == "import_spec_list")?;
+                Some((node, specs))
+            }
+            kind if self.container_kinds().contains(&kind) => {
+                Some((node, node.child_by_field_name("body")?))
+            }
+            _ => None,
+        }
+    }
+
+    /// Scope segment for items that belong to a type without being nested
+    /// in it — Go methods are grouped under their receiver type.
+    fn detached_scope(self, node: Node<'_>, source: &[u8]) -> Option<String> {
+        if self != Self::Go || node.kind() != "method_declaration" {
+            return None;
+        }
+        let receiver = node.child_by_field_name("receiver")?;
+        let mut cursor = receiver.walk();
+        let param = receiver
+            .named_children(&mut cursor)
+            .find(|c| c.kind() == "parameter_declaration")?;
+        let mut ty = param.child_by_field_name("type")?;
+        loop {
+            match ty.kind() {
+                "pointer_type" => ty = ty.named_child(0)?,
+                "generic_type" => ty = ty.child_by_field_name("type")?,
+                _ => break,
+            }
         }
+        Some(format!("type {}", node_text(source, ty)))
+    }
+
+    /// Whether items in this language are named through `declarator` chains
+    /// (C-family declarations, Java/C# fields) rather than a `name` field.
+    const fn uses_declarators(self) -> bool {
+        matches!(self, Self::Java | Self::C | Self::Cpp | Self::CSharp)
     }
 
     /// Field name used to extract the identifier from a named item node.
@@ -122,6 +313,7 @@ impl AstLanguage {
     fn name_field(self, node_kind: &str) -> &'static str {
         match (self, node_kind) {
             (Self::Rust, "impl_item") => "type",
+            (Self::JavaScript | Self::TypeScript, "field_definition") => "property",
             _ => "name",
         }
     }
@@ -135,6 +327,10 @@ impl AstLanguage {
             AstConfigLanguage::TypeScript => Self::TypeScript,
             AstConfigLanguage::JavaScript => Self::JavaScript,
             AstConfigLanguage::Go => Self::Go,
+            AstConfigLanguage::Java => Self::Java,
+            AstConfigLanguage::C => Self::C,
+            AstConfigLanguage::Cpp => Self::Cpp,
+            AstConfigLanguage::CSharp => Self::CSharp,
         }
     }
 
@@ -145,6 +341,9 @@ impl AstLanguage {
             AstLanguagePack::Core => &[Self::Rust, Self::Python, Self::TypeScript],
             AstLanguagePack::Web => &[Self::TypeScript, Self::JavaScript],
             AstLanguagePack::Backend => &[Self::Rust, Self::Go, Self::Python],
+            AstLanguagePack::Jvm => &[Self::Java],
+            AstLanguagePack::Native => &[Self::C, Self::Cpp],
+            AstLanguagePack::Dotnet => &[Self::CSharp],
         }
     }
 }
@@ -157,6 +356,10 @@ impl std::fmt::Display for AstLanguage {
             Self::TypeScript => write!(f, "typescript"),
             Self::JavaScript => write!(f, "javascript"),
             Self::Go => write!(f, "go"),
+            Self::Java => write!(f, "java"),
+            Self::C => write!(f, "c"),
+            Self::Cpp => write!(f, "cpp"),
+            Self::CSharp => write!(f, "csharp"),
         }
     }
 }
@@ -171,6 +374,9 @@ pub struct AstMergeConfig {
     /// Languages for which AST merge is enabled.
     /// Empty = disabled for all languages.
     pub enabled_languages: Vec<AstLanguage>,
+    /// Structured data formats merged by key path (see
+    /// [`super::structured_merge`]). Empty = disabled.
+    pub structured_formats: Vec<StructuredFormat>,
     /// Maximum semantic false-positive budget in percent.
     pub semantic_false_positive_budget_pct: u8,
     /// Minimum confidence required for semantic-specific conflict reasons.
@@ -181,6 +387,7 @@ impl Default for AstMergeConfig {
     fn default() -> Self {
         Self {
             enabled_languages: Vec::new(),
+            structured_formats: Vec::new(),
             semantic_false_positive_budget_pct: 5,
             semantic_min_confidence: 70,
         }
@@ -199,6 +406,17 @@ impl AstMergeConfig {
         }
     }
 
+    /// Check if key-path merge is enabled for a given structured data file.
+    #[must_use]
+    pub fn structured_format_for(&self, path: &Path) -> Option<StructuredFormat> {
+        let format = StructuredFormat::from_path(path)?;
+        if self.structured_formats.contains(&format) {
+            Some(format)
+        } else {
+            None
+        }
+    }
+
     /// Create a config with all supported languages enabled.
     #[must_use]
     pub fn all_languages() -> Self {
@@ -209,6 +427,15 @@ impl AstMergeConfig {
                 AstLanguage::TypeScript,
                 AstLanguage::JavaScript,
                 AstLanguage::Go,
+                AstLanguage::Java,
+                AstLanguage::C,
+                AstLanguage::Cpp,
+                AstLanguage::CSharp,
+            ],
+            structured_formats: vec![
+                StructuredFormat::Json,
+                StructuredFormat::Yaml,
+                StructuredFormat::Toml,
             ],
             semantic_false_positive_budget_pct: 5,
             semantic_min_confidence: 70,
@@ -232,8 +459,18 @@ impl AstMergeConfig {
         enabled_languages.sort();
         enabled_languages.dedup();
 
+        let mut structured_formats: Vec<StructuredFormat> = config
+            .structured
+            .iter()
+            .copied()
+            .map(StructuredFormat::from_config_format)
+            .collect();
+        structured_formats.sort();
+        structured_formats.dedup();
+
         Self {
             enabled_languages,
+            structured_formats,
             semantic_false_positive_budget_pct: config.semantic_false_positive_budget_pct,
             semantic_min_confidence: config.semantic_min_confidence,
         }
@@ -246,8 +483,8 @@ impl AstMergeConfig {
 
 /// A top-level item extracted from a parsed AST.
 ///
-/// Items are identified by their (kind, name) pair. Items without a name
-/// (e.g., anonymous impl blocks with complex types) use a position-based
+/// Items are identified by their (scope, kind, name) triple. Items without a
+/// name (e.g., anonymous impl blocks with complex types) use a position-based
 /// identity fallback.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TopLevelItem {
@@ -255,6 +492,9 @@ pub struct TopLevelItem {
     pub kind: String,
     /// Item name if extractable (function name, struct name, etc.).
     pub name: Option<String>,
+    /// Enclosing containers, outermost first (e.g., `["class Foo"]` for a
+    /// Java method). Empty for items at file scope.
+    pub scope: Vec<String>,
     /// Byte range in the source: [`start_byte`, `end_byte`).
     pub start_byte: usize,
     pub end_byte: usize,
@@ -263,44 +503,198 @@ pub struct TopLevelItem {
 }
 
 impl TopLevelItem {
-    /// A stable identity key for matching items across versions.
-    ///
-    /// Named items use (kind, name). Unnamed items use (kind, position-index).
-    fn identity_key(&self, index: usize) -> ItemKey {
-        self.name.as_ref().map_or_else(
-            || ItemKey::Positional {
-                kind: self.kind.clone(),
-                index,
-            },
-            |name| ItemKey::Named {
-                kind: self.kind.clone(),
-                name: name.clone(),
-            },
-        )
+    /// The item name qualified by its scope, e.g. `impl Foo::bar`.
+    #[must_use]
+    pub fn qualified_name(&self) -> Option<String> {
+        let name = self.name.as_deref()?;
+        if self.scope.is_empty() {
+            return Some(name.to_owned());
+        }
+        Some(format!("{}::{name}", self.scope.join("::")))
     }
 }
 
 /// Identity key for matching items across base and variant ASTs.
+///
+/// `occurrence` disambiguates items sharing a name within one scope (e.g.,
+/// Java/C# overloads): the first `bar` is occurrence 0, the second 1, ...
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 enum ItemKey {
-    Named { kind: String, name: String },
-    Positional { kind: String, index: usize },
+    Named {
+        scope: Vec<String>,
+        kind: String,
+        name: String,
+        occurrence: usize,
+    },
+    Positional {
+        scope: Vec<String>,
+        kind: String,
+        index: usize,
+    },
 }
 
 impl std::fmt::Display for ItemKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Named { kind, name } => write!(f, "{kind} `{name}`"),
-            Self::Positional { kind, index } => write!(f, "{kind} #{index}"),
+            Self::Named {
+                scope,
+                kind,
+                name,
+                occurrence,
+            } => {
+                write!(f, "{kind} `")?;
+                for segment in scope {
+                    write!(f, "{segment}::")?;
+                }
+                write!(f, "{name}`")?;
+                if *occurrence > 0 {
+                    write!(f, " #{occurrence}")?;
+                }
+                Ok(())
+            }
+            Self::Positional { kind, index, .. } => write!(f, "{kind} #{index}"),
         }
     }
 }
 
+/// Compute stable identity keys for a list of extracted items.
+///
+/// Named items use (scope, kind, name, occurrence). Unnamed items use
+/// (scope, kind, position-index).
+fn item_keys(items: &[TopLevelItem]) -> Vec<ItemKey> {
+    let mut seen: BTreeMap<(&[String], &str, &str), usize> = BTreeMap::new();
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let Some(name) = &item.name else {
+                return ItemKey::Positional {
+                    scope: item.scope.clone(),
+                    kind: item.kind.clone(),
+                    index,
+                };
+            };
+            let count = seen
+                .entry((item.scope.as_slice(), item.kind.as_str(), name.as_str()))
+                .or_default();
+            let occurrence = *count;
+            *count += 1;
+            ItemKey::Named {
+                scope: item.scope.clone(),
+                kind: item.kind.clone(),
+                name: name.clone(),
+                occurrence,
+            }
+        })
+        .collect()
+}
+
+/// A class-like node whose members were extracted as individual items.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Container {
+    /// Scope path of the container, including its own segment.
+    scope: Vec<String>,
+    /// Source text from the container's start up to its body (e.g.
+    /// `impl<T> Display for Foo<T>`). Variants must leave it untouched.
+    header: Vec<u8>,
+    /// Where members added to the container are spliced in.
+    anchor: MemberAnchor,
+}
+
+/// Insertion point for members added to a container or type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MemberAnchor {
+    /// Byte offset of the `}` (or Go import-list `)`) closing a body.
+    ClosingBrace(usize),
+    /// End of the last statement of an indentation-delimited body (Python).
+    BodyEnd(usize),
+    /// End of the last base item sharing the added item's detached scope
+    /// (Go methods of the same receiver).
+    AfterItem(usize),
+}
+
+/// Items and containers extracted from one parsed source file.
+#[derive(Clone, Debug, Default)]
+struct ParsedSource {
+    items: Vec<TopLevelItem>,
+    containers: Vec<Container>,
+    /// Containers seen per scope path, descended into or not. Repeated
+    /// paths (e.g. two `impl Foo` blocks) get an ` #n` occurrence suffix.
+    seen_scopes: BTreeMap<Vec<String>, usize>,
+}
+
+impl ParsedSource {
+    fn container_scopes(&self) -> BTreeSet<Vec<String>> {
+        self.containers.iter().map(|c| c.scope.clone()).collect()
+    }
+
+    /// Scope paths and headers of all descended containers, for checking
+    /// that a variant kept the base's container structure.
+    fn container_headers(&self) -> BTreeMap<&[String], &[u8]> {
+        self.containers
+            .iter()
+            .map(|c| (c.scope.as_slice(), c.header.as_slice()))
+            .collect()
+    }
+}
+
+/// Node kinds that declare imports (unioned into the file's import groups).
+pub(crate) fn is_import_kind(kind: &str) -> bool {
+    matches!(
+        kind,
+        "use_declaration"
+            | "extern_crate_declaration"
+            | "import_declaration"
+            | "import_spec"
+            | "import_statement"
+            | "import_from_statement"
+            | "future_import_statement"
+            | "using_directive"
+            | "preproc_include"
+    )
+}
+
+fn node_text<'a>(source: &'a [u8], node: Node<'_>) -> &'a str {
+    std::str::from_utf8(&source[node.start_byte()..node.end_byte()]).unwrap_or("")
+}
+
 /// Parse a source file and extract top-level items.
+#[cfg(test)]
 fn parse_and_extract(
     source: &[u8],
     lang: AstLanguage,
 ) -> Result<(Tree, Vec<TopLevelItem>), AstMergeError> {
+    let (tree, parsed) = parse_source(source, lang, None)?;
+    Ok((tree, parsed.items))
+}
+
+/// Parse a source file and extract its file-scope items.
+///
+/// Containers are not descended into: an `impl` block or class is returned
+/// as a single item, like any other top-level declaration.
+///
+/// # Errors
+///
+/// Returns [`AstMergeError`] if the parser cannot be set up or fails.
+pub fn extract_top_level_items(
+    source: &[u8],
+    lang: AstLanguage,
+) -> Result<Vec<TopLevelItem>, AstMergeError> {
+    let (_, parsed) = parse_source(source, lang, Some(&BTreeSet::new()))?;
+    Ok(parsed.items)
+}
+
+/// Parse a source file and extract items, descending into containers.
+///
+/// With `descend_into = None` every container is descended into (used for
+/// the base). Otherwise only containers whose scope appears in the set are;
+/// any other container is kept as a single opaque item, so a class added
+/// wholesale by a variant still merges as one unit.
+fn parse_source(
+    source: &[u8],
+    lang: AstLanguage,
+    descend_into: Option<&BTreeSet<Vec<String>>>,
+) -> Result<(Tree, ParsedSource), AstMergeError> {
     let mut parser = Parser::new();
     parser
         .set_language(&lang.tree_sitter_language())
@@ -310,51 +704,223 @@ fn parse_and_extract(
         .parse(source, None)
         .ok_or(AstMergeError::ParseFailed)?;
 
-    let root = tree.root_node();
+    let mut parsed = ParsedSource::default();
+    collect_items(
+        tree.root_node(),
+        source,
+        lang,
+        &[],
+        descend_into,
+        &mut parsed,
+    );
+
+    Ok((tree, parsed))
+}
+
+fn collect_items(
+    parent: Node<'_>,
+    source: &[u8],
+    lang: AstLanguage,
+    scope: &[String],
+    descend_into: Option<&BTreeSet<Vec<String>>>,
+    out: &mut ParsedSource,
+) {
     let named_kinds = lang.named_item_kinds();
-    let mut items = Vec::new();
 
-    let child_count = root.child_count();
-    for i in 0..child_count {
-        let Some(child) = root.child(i) else { continue };
+    for i in 0..parent.child_count() {
+        let Some(child) = parent.child(i) else {
+            continue;
+        };
         let kind = child.kind();
 
         if !named_kinds.contains(&kind) {
             continue;
         }
 
-        // Extract the item name from the appropriate field.
-        // use_declaration and extern_crate_declaration don't have a simple "name"
-        // field — use the full text of the first named child as the identity key.
-        let name = if kind == "use_declaration" || kind == "extern_crate_declaration" {
-            // Use the full source text (minus the `use`/`extern crate` keyword and
-            // semicolon) as the identity. This gives us e.g. "std::io" for
-            // `use std::io;` which is unique enough for matching.
-            let text =
-                std::str::from_utf8(&source[child.start_byte()..child.end_byte()]).unwrap_or("");
-            Some(text.to_owned())
-        } else {
-            let name_field = lang.name_field(kind);
-            child.child_by_field_name(name_field).map(|n| {
-                std::str::from_utf8(&source[n.start_byte()..n.end_byte()])
-                    .unwrap_or("")
-                    .to_owned()
-            })
-        };
+        let name = item_name(child, source, lang);
+
+        if let Some((decl, body)) = lang.container_parts(child) {
+            let mut member_scope = scope.to_vec();
+            // Import blocks are named by their text; their scope is just `import`.
+            let decl_name = (!is_import_kind(decl.kind()))
+                .then(|| item_name(decl, source, lang))
+                .flatten();
+            let segment = container_segment(decl.kind(), decl_name.as_deref());
+            member_scope.push(segment.clone());
+            let seen = out.seen_scopes.entry(member_scope.clone()).or_default();
+            if *seen > 0 {
+                member_scope.pop();
+                member_scope.push(format!("{segment} #{seen}"));
+            }
+            *seen += 1;
+
+            let wanted = descend_into.is_none_or(|set| set.contains(&member_scope));
+            if wanted && let Some(anchor) = body_anchor(decl, body) {
+                let header = source[child.start_byte()..body.start_byte()].trim_ascii_end();
+                out.containers.push(Container {
+                    scope: member_scope.clone(),
+                    header: header.to_vec(),
+                    anchor,
+                });
+                collect_items(body, source, lang, &member_scope, descend_into, out);
+                continue;
+            }
+        }
 
+        // Some nodes (e.g. preprocessor directives) include their trailing
+        // newline; keep item ranges tight so splicing stays line-aligned.
+        // TS/JS class fields leave their `;` as a sibling token — keep it
+        // with the item.
         let start = child.start_byte();
-        let end = child.end_byte();
+        let mut end = child.end_byte();
+        if let Some(next) = child.next_sibling()
+            && next.kind() == ";"
+            && next.start_byte() == end
+        {
+            end = next.end_byte();
+        }
+        while end > start && source[end - 1].is_ascii_whitespace() {
+            end -= 1;
+        }
 
-        items.push(TopLevelItem {
+        let mut item_scope = scope.to_vec();
+        item_scope.extend(lang.detached_scope(child, source));
+
+        out.items.push(TopLevelItem {
             kind: kind.to_owned(),
             name,
+            scope: item_scope,
             start_byte: start,
             end_byte: end,
             content: source[start..end].to_vec(),
         });
     }
+}
+
+/// Extract the identity name of an item node.
+fn item_name(node: Node<'_>, source: &[u8], lang: AstLanguage) -> Option<String> {
+    let kind = node.kind();
+
+    // Imports don't have a simple "name" field — use the full source text
+    // (e.g. `use std::io;`) as the identity, which is unique enough for
+    // matching.
+    if is_import_kind(kind) || kind == "package_declaration" {
+        return Some(node_text(source, node).trim_end().to_owned());
+    }
+
+    // Wrappers are named after what they wrap.
+    let wrapped = match kind {
+        "decorated_definition" => node.child_by_field_name("definition"),
+        "export_statement" => node.child_by_field_name("declaration"),
+        _ => None,
+    };
+    if let Some(inner) = wrapped {
+        return item_name(inner, source, lang);
+    }
+
+    // `impl Display for Foo` is distinct from the inherent `impl Foo`.
+    if kind == "impl_item"
+        && let (Some(tr), Some(ty)) = (
+            node.child_by_field_name("trait"),
+            node.child_by_field_name("type"),
+        )
+    {
+        return Some(format!(
+            "{} for {}",
+            node_text(source, tr),
+            node_text(source, ty)
+        ));
+    }
+
+    // Go `type Foo struct {...}` names its type through a `type_spec`.
+    if kind == "type_declaration" {
+        let mut cursor = node.walk();
+        let spec = node
+            .named_children(&mut cursor)
+            .find(|c| c.kind() == "type_spec")?;
+        return Some(node_text(source, spec.child_by_field_name("name")?).to_owned());
+    }
+
+    // `template <...> T f()` is named after the templated declaration.
+    if kind == "template_declaration" {
+        let mut cursor = node.walk();
+        return node
+            .named_children(&mut cursor)
+            .filter(|c| c.kind() != "template_parameter_list")
+            .find_map(|c| item_name(c, source, lang));
+    }
+
+    if let Some(n) = node.child_by_field_name(lang.name_field(kind)) {
+        return Some(node_text(source, n).to_owned());
+    }
+
+    if lang.uses_declarators() {
+        return declarator_name(node, source);
+    }
+
+    None
+}
+
+/// Resolve the declared name of a C-family declaration.
+///
+/// Follows the `declarator` field chain (pointer → function → identifier)
+/// and falls back to the first `variable_declarator` for C# fields.
+fn declarator_name(node: Node<'_>, source: &[u8]) -> Option<String> {
+    let mut current = node;
+    let mut descended = false;
+    while let Some(next) = current.child_by_field_name("declarator") {
+        current = next;
+        descended = true;
+    }
+
+    if !descended {
+        // C#: field_declaration → variable_declaration → variable_declarator.
+        let mut cursor = node.walk();
+        let declaration = node
+            .named_children(&mut cursor)
+            .find(|c| c.kind() == "variable_declaration")?;
+        let mut cursor = declaration.walk();
+        current = declaration
+            .named_children(&mut cursor)
+            .find(|c| c.kind() == "variable_declarator")?;
+    }
+
+    if current.kind() == "variable_declarator" {
+        let name = current
+            .child_by_field_name("name")
+            .or_else(|| current.named_child(0))?;
+        return Some(node_text(source, name).to_owned());
+    }
+
+    Some(node_text(source, current).to_owned())
+}
 
-    Ok((tree, items))
+/// Scope segment for a container, e.g. `class Foo` or `namespace demo`.
+fn container_segment(kind: &str, name: Option<&str>) -> String {
+    let label = ["_declaration", "_definition", "_specifier", "_item"]
+        .iter()
+        .find_map(|suffix| kind.strip_suffix(suffix))
+        .unwrap_or(kind);
+    name.map_or_else(|| label.to_owned(), |name| format!("{label} {name}"))
+}
+
+/// Where members added to a container body are spliced in.
+///
+/// Braced bodies (and Go's parenthesised import lists) anchor on their
+/// closing delimiter. Indentation-delimited bodies (Python) anchor after
+/// their last statement, but only when the body starts on its own line —
+/// `class A: pass` can't take new members.
+fn body_anchor(decl: Node<'_>, body: Node<'_>) -> Option<MemberAnchor> {
+    let closing = body
+        .child_count()
+        .checked_sub(1)
+        .and_then(|last| body.child(last))
+        .filter(|last| matches!(last.kind(), "}" | ")"));
+    if let Some(close) = closing {
+        return Some(MemberAnchor::ClosingBrace(close.start_byte()));
+    }
+    (body.kind() == "block" && body.start_position().row > decl.start_position().row)
+        .then_some(MemberAnchor::BodyEnd(body.end_byte()))
 }
 
 /// Outcome of a quick "does this blob parse?" check (bn-2upt).
@@ -434,16 +1000,12 @@ fn compute_edit_script(
     variant_items: &[TopLevelItem],
 ) -> Vec<ItemChange> {
     // Build keyed maps for lookup.
-    let base_map: BTreeMap<ItemKey, &TopLevelItem> = base_items
-        .iter()
-        .enumerate()
-        .map(|(i, item)| (item.identity_key(i), item))
-        .collect();
+    let base_map: BTreeMap<ItemKey, &TopLevelItem> =
+        item_keys(base_items).into_iter().zip(base_items).collect();
 
-    let variant_map: BTreeMap<ItemKey, &TopLevelItem> = variant_items
-        .iter()
-        .enumerate()
-        .map(|(i, item)| (item.identity_key(i), item))
+    let variant_map: BTreeMap<ItemKey, &TopLevelItem> = item_keys(variant_items)
+        .into_iter()
+        .zip(variant_items)
         .collect();
 
     let mut changes = Vec::new();
@@ -497,6 +1059,24 @@ pub enum AstMergeResult {
     Unsupported,
 }
 
+/// Auto-resolutions a clean AST merge applied, surfaced so merges that
+/// would otherwise have conflicted stay auditable (e.g. in the merge plan).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AstMergeReport {
+    /// Imports added by variants and unioned into the merged file.
+    pub merged_imports: Vec<MergedImport>,
+}
+
+/// An import statement added by one or more variants.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergedImport {
+    /// The import as written, e.g. `use std::fmt;` or `"strings"`.
+    pub statement: String,
+    /// Workspaces that added it. More than one means the additions were
+    /// de-duplicated into a single import.
+    pub workspaces: Vec<WorkspaceId>,
+}
+
 /// Errors from AST merge operations.
 #[derive(Debug)]
 pub enum AstMergeError {
@@ -551,38 +1131,73 @@ pub fn try_ast_merge_with_config(
     lang: AstLanguage,
     config: &AstMergeConfig,
 ) -> AstMergeResult {
+    try_ast_merge_with_report(base, variants, lang, config).0
+}
+
+/// Attempt AST-aware merge, also reporting the auto-resolutions applied.
+///
+/// The report is empty unless the result is [`AstMergeResult::Clean`].
+#[must_use]
+pub fn try_ast_merge_with_report(
+    base: &[u8],
+    variants: &[(WorkspaceId, Vec<u8>)],
+    lang: AstLanguage,
+    config: &AstMergeConfig,
+) -> (AstMergeResult, AstMergeReport) {
+    let unsupported = (AstMergeResult::Unsupported, AstMergeReport::default());
+
     // Parse base.
-    let Ok((_base_tree, base_items)) = parse_and_extract(base, lang) else {
-        return AstMergeResult::Unsupported;
+    let Ok((_base_tree, base_parsed)) = parse_source(base, lang, None) else {
+        return unsupported;
     };
+    let base_items = &base_parsed.items;
 
     // If no top-level items were found, AST merge can't help.
     if base_items.is_empty() {
-        return AstMergeResult::Unsupported;
+        return unsupported;
     }
 
+    // Containers are matched by scope path (with occurrence suffixes for
+    // repeated paths such as two `impl Foo` blocks).
+    let base_scopes = base_parsed.container_scopes();
+    let base_headers = base_parsed.container_headers();
+
     // Parse variants and compute edit scripts.
     let mut all_constraints: Vec<ItemConstraint> = Vec::new();
+    let mut parsed_variants: Vec<ParsedSource> = Vec::with_capacity(variants.len());
 
     for (ws_id, variant_content) in variants {
-        let Ok((_variant_tree, variant_items)) = parse_and_extract(variant_content, lang) else {
-            return AstMergeResult::Unsupported;
+        let Ok((_variant_tree, variant_parsed)) =
+            parse_source(variant_content, lang, Some(&base_scopes))
+        else {
+            return unsupported;
         };
 
-        let edit_script = compute_edit_script(&base_items, &variant_items);
+        // A variant that removed, restructured or re-headed a container
+        // can't be expressed as member-level edits — the container's header
+        // and closing brace live in the base's interstitial text.
+        if variant_parsed.container_headers() != base_headers {
+            return unsupported;
+        }
+
+        let edit_script = compute_edit_script(base_items, &variant_parsed.items);
         for change in edit_script {
             all_constraints.push(ItemConstraint {
                 workspace_id: ws_id.clone(),
                 change,
             });
         }
+        parsed_variants.push(variant_parsed);
     }
 
     // If no constraints (no changes at AST level), all variants are identical
     // at the item level. This shouldn't normally happen since diff3 already
     // handled identical content, but handle gracefully.
     if all_constraints.is_empty() {
-        return AstMergeResult::Clean(base.to_vec());
+        return (
+            AstMergeResult::Clean(base.to_vec()),
+            AstMergeReport::default(),
+        );
     }
 
     // Group constraints by item key.
@@ -641,33 +1256,54 @@ pub fn try_ast_merge_with_config(
             Region::Lines { start, .. } => *start,
             Region::WholeFile => 0,
         });
-        return AstMergeResult::Conflict {
-            atoms: conflict_atoms,
-        };
+        return (
+            AstMergeResult::Conflict {
+                atoms: conflict_atoms,
+            },
+            AstMergeReport::default(),
+        );
     }
 
     // Before reconstructing, check that no variant changed interstitial text
     // (comments, whitespace, or other content between named items). The
     // reconstruction copies interstitial regions from base verbatim, so any
     // variant changes to those regions would be silently lost.
-    for (_ws_id, variant_content) in variants {
-        let Ok((_vt, variant_items)) = parse_and_extract(variant_content, lang) else {
-            return AstMergeResult::Unsupported;
-        };
-        if interstitial_differs(base, &base_items, variant_content, &variant_items) {
+    for ((_ws_id, variant_content), variant_parsed) in variants.iter().zip(&parsed_variants) {
+        if interstitial_differs(base, base_items, variant_content, &variant_parsed.items) {
             // Fall back to diff3 conflict — interstitial changes can't be
             // merged at the AST item level.
-            return AstMergeResult::Unsupported;
+            return unsupported;
         }
     }
 
     // All changes are to disjoint items — reconstruct the merged file.
-    AstMergeResult::Clean(reconstruct_merged_file(
-        base,
-        &base_items,
-        &resolutions,
-        variants,
-    ))
+    let merged =
+        reconstruct_merged_file(base, &base_parsed, &resolutions, variants, &parsed_variants);
+    let report = AstMergeReport {
+        merged_imports: merged_imports(&constraints_by_item),
+    };
+    (AstMergeResult::Clean(merged), report)
+}
+
+/// Imports added by variants, with every workspace that added each one.
+fn merged_imports(
+    constraints_by_item: &BTreeMap<ItemKey, Vec<&ItemConstraint>>,
+) -> Vec<MergedImport> {
+    constraints_by_item
+        .values()
+        .filter_map(|constraints| {
+            let ItemChange::Added { variant_item, .. } = &constraints.first()?.change else {
+                return None;
+            };
+            if !is_import_kind(&variant_item.kind) {
+                return None;
+            }
+            Some(MergedImport {
+                statement: variant_item.name.clone()?,
+                workspaces: constraints.iter().map(|c| c.workspace_id.clone()).collect(),
+            })
+        })
+        .collect()
 }
 
 /// Check if all constraints make the same effective change.
@@ -732,14 +1368,14 @@ fn conflict_base_region(constraints: &[&ItemConstraint]) -> Region {
             ItemChange::Modified { base_item, .. } | ItemChange::Deleted { base_item, .. } => {
                 Region::ast_node(
                     &base_item.kind,
-                    base_item.name.clone(),
+                    base_item.qualified_name(),
                     narrow_u32(base_item.start_byte),
                     narrow_u32(base_item.end_byte),
                 )
             }
             ItemChange::Added { variant_item, .. } => Region::ast_node(
                 &variant_item.kind,
-                variant_item.name.clone(),
+                variant_item.qualified_name(),
                 narrow_u32(variant_item.start_byte),
                 narrow_u32(variant_item.end_byte),
             ),
@@ -757,7 +1393,7 @@ fn conflict_edits(constraints: &[&ItemConstraint]) -> Vec<AtomEdit> {
                 | ItemChange::Added { variant_item, .. } => (
                     Region::ast_node(
                         &variant_item.kind,
-                        variant_item.name.clone(),
+                        variant_item.qualified_name(),
                         narrow_u32(variant_item.start_byte),
                         narrow_u32(variant_item.end_byte),
                     ),
@@ -766,7 +1402,7 @@ fn conflict_edits(constraints: &[&ItemConstraint]) -> Vec<AtomEdit> {
                 ItemChange::Deleted { base_item, .. } => (
                     Region::ast_node(
                         &base_item.kind,
-                        base_item.name.clone(),
+                        base_item.qualified_name(),
                         narrow_u32(base_item.start_byte),
                         narrow_u32(base_item.end_byte),
                     ),
@@ -903,6 +1539,17 @@ fn extract_signature(item: &TopLevelItem, lang: AstLanguage) -> Option<String> {
             .unwrap_or(first_line)
             .trim()
             .to_string(),
+        // Skip Java annotations / C# attributes on their own lines.
+        AstLanguage::Java | AstLanguage::C | AstLanguage::Cpp | AstLanguage::CSharp => text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.starts_with('@') && !line.starts_with('['))
+            .unwrap_or(first_line)
+            .split('{')
+            .next()
+            .unwrap_or(first_line)
+            .trim()
+            .to_string(),
     };
 
     if signature.is_empty() {
@@ -979,28 +1626,58 @@ fn interstitial_differs(
 ///    - Modified → substitute the variant's content
 ///    - Deleted → skip the item (preserve inter-item gaps)
 ///    - Unchanged → keep base content
-/// 3. After all base items, append any added items
+/// 3. Added imports join their base import group, and added container
+///    members are spliced in before the container's closing brace, while
+///    walking
+/// 4. After all base items, append any added top-level items
 fn reconstruct_merged_file(
     base: &[u8],
-    base_items: &[TopLevelItem],
+    base_parsed: &ParsedSource,
     resolutions: &BTreeMap<ItemKey, &ItemConstraint>,
     variants: &[(WorkspaceId, Vec<u8>)],
+    parsed_variants: &[ParsedSource],
 ) -> Vec<u8> {
+    let base_items = &base_parsed.items;
+    let base_keys = item_keys(base_items);
+    let surviving: Vec<bool> = base_keys
+        .iter()
+        .map(|key| {
+            !resolutions
+                .get(key)
+                .is_some_and(|c| matches!(c.change, ItemChange::Deleted { .. }))
+        })
+        .collect();
+    let variant_items: BTreeMap<&WorkspaceId, &[TopLevelItem]> = variants
+        .iter()
+        .zip(parsed_variants)
+        .map(|((ws_id, _), parsed)| (ws_id, parsed.items.as_slice()))
+        .collect();
+
+    let mut added_items = added_resolution_items(resolutions);
+    let (mut insertions, imports_placed) =
+        import_insertions(base, base_items, &surviving, &added_items, &variant_items);
+    added_items.retain(|(ws_id, item)| !imports_placed.contains(&(*ws_id, item.start_byte)));
+    for (at, buf) in member_insertions(base, base_parsed, &added_items, variants) {
+        insertions.entry(at).or_default().extend(buf);
+    }
+
     let mut result = Vec::with_capacity(base.len());
     let mut cursor = 0_usize;
 
     // Process base items in order (they're already sorted by position
     // since we extracted them in tree order).
-    for (i, base_item) in base_items.iter().enumerate() {
-        let key = base_item.identity_key(i);
-
+    for (base_item, key) in base_items.iter().zip(&base_keys) {
         // Copy inter-item gap (whitespace, comments, imports, etc.)
         // from cursor to start of this item.
-        if base_item.start_byte > cursor {
-            result.extend_from_slice(&base[cursor..base_item.start_byte]);
-        }
+        copy_gap(
+            base,
+            cursor,
+            base_item.start_byte,
+            &mut insertions,
+            &mut result,
+        );
 
-        match resolutions.get(&key) {
+        match resolutions.get(key) {
             Some(constraint) => match &constraint.change {
                 ItemChange::Modified { variant_item, .. } => {
                     result.extend_from_slice(&variant_item.content);
@@ -1027,65 +1704,17 @@ fn reconstruct_merged_file(
     }
 
     // Copy any trailing content after the last item.
-    if cursor < base.len() {
-        result.extend_from_slice(&base[cursor..]);
-    }
-
-    let added_items = added_resolution_items(resolutions);
-
-    // Split added items: use_declarations should be inserted near existing
-    // uses (not appended at EOF), everything else is appended.
-    let mut uses_inserted = std::collections::BTreeSet::new();
-
-    // Find the last surviving (not deleted) use_declaration in base items.
-    let last_surviving_use = base_items
-        .iter()
-        .enumerate()
-        .rfind(|(idx, item)| {
-            (item.kind == "use_declaration" || item.kind == "extern_crate_declaration") && {
-                // Only consider uses that are NOT deleted (still in the result).
-                let key = item.identity_key(*idx);
-                !resolutions.contains_key(&key)
-                    || !matches!(resolutions[&key].change, ItemChange::Deleted { .. })
-            }
-        })
-        .map(|(_, item)| item);
-
-    if let Some(anchor_use) = last_surviving_use {
-        let use_adds: Vec<_> = added_items
-            .iter()
-            .enumerate()
-            .filter(|(_, (_, item))| {
-                item.kind == "use_declaration" || item.kind == "extern_crate_declaration"
-            })
-            .collect();
-
-        if !use_adds.is_empty() {
-            // Find where the anchor use declaration ends in the result.
-            let insert_pos = result
-                .windows(anchor_use.content.len())
-                .position(|w| w == anchor_use.content.as_slice())
-                .map(|start| start + anchor_use.content.len());
-
-            if let Some(pos) = insert_pos {
-                let mut insert_buf = Vec::new();
-                for (idx, (_, item)) in &use_adds {
-                    insert_buf.push(b'\n');
-                    insert_buf.extend_from_slice(&item.content);
-                    uses_inserted.insert(*idx);
-                }
-                result.splice(pos..pos, insert_buf);
-            }
-        }
-    }
+    copy_gap(base, cursor, base.len(), &mut insertions, &mut result);
 
-    // Append remaining added items at EOF (non-use items, or use items that
-    // couldn't be inserted near existing uses).
-    for (idx, (ws_id, item)) in added_items.iter().enumerate() {
-        if uses_inserted.contains(&idx) {
-            continue; // Already inserted near existing uses above.
-        }
+    // Imports and anchored members were spliced in above; only file-scope
+    // additions (and scoped items with nothing to anchor to) remain.
+    let added_items: Vec<_> = added_items
+        .into_iter()
+        .filter(|(_, item)| member_anchor(item, &base_parsed.containers, base_items).is_none())
+        .collect();
 
+    // Append remaining added items at EOF.
+    for (ws_id, item) in &added_items {
         // Ensure there's a newline before the added item.
         if !result.is_empty() && !result.ends_with(b"\n") {
             result.push(b'\n');
@@ -1135,41 +1764,377 @@ fn added_resolution_items<'a>(
     added_items
 }
 
-/// Extract leading trivia (doc comments, attributes, blank lines) that precede
-/// an item in the source. Scans backwards from `item.start_byte` to find
-/// contiguous comment lines (`///`, `//!`, `#[`) and blank lines.
+/// Where an added scoped item is spliced into the base, if anywhere.
 ///
-/// Returns a byte slice from the source that should be prepended when the item
-/// is spliced into the merged file.
-fn leading_trivia<'a>(source: &'a [u8], item: &TopLevelItem) -> &'a [u8] {
-    let Ok(text) = std::str::from_utf8(source) else {
-        return &[];
-    };
+/// Members of a descended container go into its body; items with a detached
+/// scope (Go methods) go after the last base item of the same scope. Items
+/// with no anchor are appended at end of file.
+fn member_anchor(
+    item: &TopLevelItem,
+    containers: &[Container],
+    base_items: &[TopLevelItem],
+) -> Option<MemberAnchor> {
+    if item.scope.is_empty() {
+        return None;
+    }
+    if let Some(container) = containers.iter().find(|c| c.scope == item.scope) {
+        return Some(container.anchor);
+    }
+    base_items
+        .iter()
+        .rfind(|base_item| base_item.scope == item.scope)
+        .map(|base_item| MemberAnchor::AfterItem(base_item.end_byte))
+}
 
-    // Work backwards from item start, line by line.
-    let before = &text[..item.start_byte];
-    let mut trivia_start = item.start_byte;
+/// Bytes to splice into the base while reconstructing, keyed by base offset.
+type Insertions = BTreeMap<usize, Vec<u8>>;
 
-    for line in before.lines().rev() {
-        let trimmed = line.trim();
-        if trimmed.starts_with("///")
-            || trimmed.starts_with("//!")
-            || trimmed.starts_with("#[")
-            || trimmed.starts_with("#![")
-        {
-            // This line is trivia — include it.
-            // Find this line's byte offset in the source.
-            let line_offset = line.as_ptr() as usize - text.as_ptr() as usize;
-            trivia_start = line_offset;
-        } else if trimmed.is_empty() && trivia_start < item.start_byte {
-            // Blank line between trivia and the item — include it.
-            let line_offset = line.as_ptr() as usize - text.as_ptr() as usize;
+/// Where an added import goes, relative to a base import (by item index).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum ImportSlot {
+    Before(usize),
+    After(usize),
+}
+
+/// Group base imports into runs of adjacent imports in one scope with no
+/// blank line between them (e.g. `std` vs crate-local `use` blocks). Returns
+/// item indices per group, in source order.
+fn import_groups(source: &[u8], items: &[TopLevelItem]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut previous: Option<usize> = None;
+    for (index, item) in items.iter().enumerate() {
+        if !is_import_kind(&item.kind) {
+            previous = None;
+            continue;
+        }
+        let adjacent = previous.is_some_and(|prev| {
+            let gap = &source[items[prev].end_byte..item.start_byte];
+            items[prev].scope == item.scope
+                && gap.iter().all(u8::is_ascii_whitespace)
+                && gap.iter().position(|&b| b == b'\n') == gap.iter().rposition(|&b| b == b'\n')
+        });
+        match groups.last_mut() {
+            Some(group) if adjacent => group.push(index),
+            _ => groups.push(vec![index]),
+        }
+        previous = Some(index);
+    }
+    groups
+}
+
+/// Key imports are sorted by: their text, minus any Go import alias.
+fn import_sort_key(item: &TopLevelItem) -> &str {
+    let text = item.name.as_deref().unwrap_or_default();
+    if item.kind == "import_spec" {
+        return text.find(['"', '`']).map_or(text, |quote| &text[quote..]);
+    }
+    text
+}
+
+/// Build the splice buffers for imports added by variants.
+///
+/// Each added import joins the base import group its variant placed it next
+/// to (falling back to the last group in the same scope). Within the group
+/// it lands among the surviving imports of its own kind — at its sorted
+/// position when those are sorted, otherwise after them. Imports the merged
+/// group already contains are dropped.
+///
+/// Returns the buffers keyed by base offset, plus the `(workspace, start)`
+/// of every added import accounted for. Imports outside any base group are
+/// left to the caller.
+fn import_insertions<'a>(
+    base: &[u8],
+    base_items: &[TopLevelItem],
+    surviving: &[bool],
+    added_items: &[(&'a WorkspaceId, &'a TopLevelItem)],
+    variant_items: &BTreeMap<&WorkspaceId, &[TopLevelItem]>,
+) -> (Insertions, BTreeSet<(&'a WorkspaceId, usize)>) {
+    let groups = import_groups(base, base_items);
+    let group_of: BTreeMap<usize, usize> = groups
+        .iter()
+        .enumerate()
+        .flat_map(|(group, members)| members.iter().map(move |&index| (index, group)))
+        .collect();
+
+    let mut placed = BTreeSet::new();
+    let mut slots: BTreeMap<ImportSlot, (bool, Vec<&TopLevelItem>)> = BTreeMap::new();
+
+    for &(ws_id, item) in added_items {
+        if !is_import_kind(&item.kind) {
+            continue;
+        }
+        let in_scope = |candidate: &TopLevelItem| {
+            candidate.scope == item.scope && is_import_kind(&candidate.kind)
+        };
+        let duplicate = base_items
+            .iter()
+            .zip(surviving)
+            .any(|(base_item, &kept)| kept && in_scope(base_item) && base_item.name == item.name);
+        if duplicate {
+            placed.insert((ws_id, item.start_byte));
+            continue;
+        }
+
+        // The group holding the nearest base import the variant kept around
+        // this one, preferring the import just above it.
+        let siblings = variant_items.get(ws_id).copied().unwrap_or_default();
+        let position = siblings
+            .iter()
+            .position(|s| s.start_byte == item.start_byte)
+            .unwrap_or(siblings.len());
+        let neighbour_group = siblings[..position]
+            .iter()
+            .rev()
+            .chain(siblings.iter().skip(position + 1))
+            .filter(|sibling| in_scope(sibling))
+            .find_map(|sibling| {
+                let index = base_items
+                    .iter()
+                    .position(|b| in_scope(b) && b.name == sibling.name)?;
+                group_of.get(&index).copied()
+            });
+        let Some(group) = neighbour_group.or_else(|| {
+            groups
+                .iter()
+                .rposition(|members| base_items[members[0]].scope == item.scope)
+        }) else {
+            continue;
+        };
+
+        let members = &groups[group];
+        let kept: Vec<usize> = members.iter().copied().filter(|&i| surviving[i]).collect();
+        let same_kind: Vec<usize> = kept
+            .iter()
+            .copied()
+            .filter(|&i| base_items[i].kind == item.kind)
+            .collect();
+        let sorted = !same_kind.is_empty()
+            && same_kind.windows(2).all(|pair| {
+                import_sort_key(&base_items[pair[0]]) <= import_sort_key(&base_items[pair[1]])
+            });
+
+        let slot = if sorted {
+            let key = import_sort_key(item);
+            same_kind
+                .iter()
+                .find(|&&i| import_sort_key(&base_items[i]) > key)
+                .map_or_else(
+                    || ImportSlot::After(same_kind[same_kind.len() - 1]),
+                    |&i| ImportSlot::Before(i),
+                )
+        } else if let Some(&last) = same_kind.last().or_else(|| kept.last()) {
+            ImportSlot::After(last)
+        } else {
+            // Every import in the group was deleted; take the group's place.
+            ImportSlot::Before(members[0])
+        };
+
+        let entry = slots.entry(slot).or_insert((sorted, Vec::new()));
+        entry.1.push(item);
+        placed.insert((ws_id, item.start_byte));
+    }
+
+    let mut insertions = Insertions::new();
+    for (slot, (sorted, mut imports)) in slots {
+        if sorted {
+            imports.sort_by(|a, b| import_sort_key(a).cmp(import_sort_key(b)));
+        }
+        imports.dedup_by(|a, b| a.name == b.name);
+
+        let (at, anchor) = match slot {
+            ImportSlot::Before(index) => (base_items[index].start_byte, &base_items[index]),
+            ImportSlot::After(index) => (base_items[index].end_byte, &base_items[index]),
+        };
+        let indent = line_indent(base, anchor.start_byte);
+        let buf = insertions.entry(at).or_default();
+        for import in imports {
+            if matches!(slot, ImportSlot::Before(_)) {
+                buf.extend_from_slice(&import.content);
+                buf.push(b'\n');
+                buf.extend_from_slice(indent);
+            } else {
+                buf.push(b'\n');
+                buf.extend_from_slice(indent);
+                buf.extend_from_slice(&import.content);
+            }
+        }
+    }
+
+    (insertions, placed)
+}
+
+/// Build the splice buffers for members added inside base containers.
+///
+/// Returns a map from base byte offset to the bytes to insert there. Members
+/// go on their own line just before the container's closing brace (or after
+/// the last statement of an indented body, or after the last sibling
+/// method), indented as in the variant and separated from existing members
+/// by a blank line.
+fn member_insertions(
+    base: &[u8],
+    base_parsed: &ParsedSource,
+    added_items: &[(&WorkspaceId, &TopLevelItem)],
+    variants: &[(WorkspaceId, Vec<u8>)],
+) -> Insertions {
+    let mut insertions = Insertions::new();
+
+    for (ws_id, item) in added_items {
+        let Some(anchor) = member_anchor(item, &base_parsed.containers, &base_parsed.items) else {
+            continue;
+        };
+        let variant_source = variants
+            .iter()
+            .find_map(|(id, src)| (id == *ws_id).then_some(src.as_slice()))
+            .unwrap_or_default();
+
+        let buf = match anchor {
+            MemberAnchor::ClosingBrace(close) => {
+                // Insert at the start of the closing-brace line when the
+                // brace sits on its own line; otherwise right before it.
+                let line_start = base[..close]
+                    .iter()
+                    .rposition(|&b| b == b'\n')
+                    .map_or(0, |pos| pos + 1);
+                let brace_on_own_line = base[line_start..close]
+                    .iter()
+                    .all(|b| matches!(b, b' ' | b'\t'));
+                let at = if brace_on_own_line { line_start } else { close };
+
+                let buf = insertions.entry(at).or_default();
+                if !brace_on_own_line && buf.is_empty() {
+                    buf.push(b'\n');
+                }
+                let preceding = base[..at].trim_ascii_end();
+                if !preceding.ends_with(b"{") || !buf.is_empty() {
+                    buf.push(b'\n');
+                }
+                buf
+            }
+            MemberAnchor::BodyEnd(at) | MemberAnchor::AfterItem(at) => {
+                let buf = insertions.entry(at).or_default();
+                buf.extend_from_slice(b"\n\n");
+                buf
+            }
+        };
+
+        let trivia = leading_trivia(variant_source, item);
+        if trivia.is_empty() {
+            buf.extend_from_slice(line_indent(variant_source, item.start_byte));
+        } else {
+            buf.extend_from_slice(trivia);
+        }
+        buf.extend_from_slice(&item.content);
+        if matches!(anchor, MemberAnchor::ClosingBrace(_)) {
+            buf.push(b'\n');
+        }
+    }
+
+    insertions
+}
+
+/// Copy `base[from..to]` into `result`, splicing in pending insertions whose
+/// offset falls at or before `to`.
+///
+/// Insertions anchored inside a region that was skipped (e.g. the trailing
+/// whitespace of a deleted item) are emitted at `from`.
+fn copy_gap(
+    base: &[u8],
+    from: usize,
+    to: usize,
+    insertions: &mut Insertions,
+    result: &mut Vec<u8>,
+) {
+    let to = to.max(from);
+    let mut pos = from;
+    while let Some(entry) = insertions.first_entry() {
+        if *entry.key() > to {
+            break;
+        }
+        let at = (*entry.key()).max(pos);
+        result.extend_from_slice(&base[pos..at]);
+        result.extend_from_slice(&entry.remove());
+        pos = at;
+    }
+    result.extend_from_slice(&base[pos..to]);
+}
+
+/// The whitespace indentation preceding `offset` on its line.
+fn line_indent(source: &[u8], offset: usize) -> &[u8] {
+    let line_start = source[..offset]
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |pos| pos + 1);
+    let indent = &source[line_start..offset];
+    if indent.iter().all(|b| matches!(b, b' ' | b'\t')) {
+        indent
+    } else {
+        &[]
+    }
+}
+
+/// Extract leading trivia (doc comments, attributes, blank lines) that precede
+/// an item in the source. Scans backwards from `item.start_byte` to find
+/// contiguous comment lines (`///`, `//!`, `#[`), `/** ... */` doc blocks,
+/// and blank lines.
+///
+/// Returns a byte slice from the source that should be prepended when the item
+/// is spliced into the merged file.
+fn leading_trivia<'a>(source: &'a [u8], item: &TopLevelItem) -> &'a [u8] {
+    let Ok(text) = std::str::from_utf8(source) else {
+        return &[];
+    };
+
+    // Work backwards from the start of the item's line, line by line, so
+    // indentation before an indented member doesn't stop the scan.
+    let indent = line_indent(source, item.start_byte);
+    let before = &text[..item.start_byte - indent.len()];
+    let mut trivia_start = item.start_byte;
+
+    let mut in_doc_block = false;
+    for line in before.lines().rev() {
+        let trimmed = line.trim();
+        let doc_block_line = if in_doc_block {
+            // Inside a `/** ... */` block: keep going until its opener.
+            in_doc_block = trimmed.starts_with('*');
+            in_doc_block || trimmed.starts_with("/*")
+        } else if trimmed.ends_with("*/") && (trimmed.starts_with("/*") || trimmed.starts_with('*'))
+        {
+            in_doc_block = !trimmed.starts_with("/*");
+            true
+        } else {
+            false
+        };
+        if doc_block_line
+            || trimmed.starts_with("///")
+            || trimmed.starts_with("//!")
+            || trimmed.starts_with("#[")
+            || trimmed.starts_with("#![")
+        {
+            // This line is trivia — include it.
+            // Find this line's byte offset in the source.
+            let line_offset = line.as_ptr() as usize - text.as_ptr() as usize;
+            trivia_start = line_offset;
+        } else if trimmed.is_empty() && trivia_start < item.start_byte {
+            // Blank line between trivia and the item — include it.
+            let line_offset = line.as_ptr() as usize - text.as_ptr() as usize;
             trivia_start = line_offset;
         } else {
             break;
         }
     }
 
+    // Blank lines above the topmost trivia line separate it from the
+    // previous item; they aren't part of this item's trivia.
+    while let Some(newline) = source[trivia_start..item.start_byte]
+        .iter()
+        .position(|&b| b == b'\n')
+        && source[trivia_start..trivia_start + newline]
+            .iter()
+            .all(u8::is_ascii_whitespace)
+    {
+        trivia_start += newline + 1;
+    }
+
     &source[trivia_start..item.start_byte]
 }
 
@@ -1240,6 +2205,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn detect_jvm_and_native_languages_from_extension() {
+        assert_eq!(
+            AstLanguage::from_path(Path::new("src/main/java/App.java")),
+            Some(AstLanguage::Java)
+        );
+        assert_eq!(
+            AstLanguage::from_path(Path::new("lib/util.c")),
+            Some(AstLanguage::C)
+        );
+        assert_eq!(
+            AstLanguage::from_path(Path::new("lib/util.h")),
+            Some(AstLanguage::C)
+        );
+        assert_eq!(
+            AstLanguage::from_path(Path::new("src/widget.cpp")),
+            Some(AstLanguage::Cpp)
+        );
+        assert_eq!(
+            AstLanguage::from_path(Path::new("include/widget.hpp")),
+            Some(AstLanguage::Cpp)
+        );
+        assert_eq!(
+            AstLanguage::from_path(Path::new("Services/Foo.cs")),
+            Some(AstLanguage::CSharp)
+        );
+    }
+
     #[test]
     fn unsupported_extension_returns_none() {
         assert_eq!(AstLanguage::from_path(Path::new("data.json")), None);
@@ -1297,8 +2290,10 @@ def goodbye():
         assert_eq!(items.len(), 3);
         assert_eq!(items[0].kind, "function_definition");
         assert_eq!(items[0].name.as_deref(), Some("hello"));
-        assert_eq!(items[1].kind, "class_definition");
-        assert_eq!(items[1].name.as_deref(), Some("Point"));
+        // Classes are descended into: their methods are items of their own.
+        assert_eq!(items[1].kind, "function_definition");
+        assert_eq!(items[1].name.as_deref(), Some("__init__"));
+        assert_eq!(items[1].scope, vec!["class Point".to_owned()]);
         assert_eq!(items[2].kind, "function_definition");
         assert_eq!(items[2].name.as_deref(), Some("goodbye"));
     }
@@ -1323,8 +2318,9 @@ function goodbye(): void {
         assert_eq!(items.len(), 3);
         assert_eq!(items[0].kind, "function_declaration");
         assert_eq!(items[0].name.as_deref(), Some("hello"));
-        assert_eq!(items[1].kind, "class_declaration");
-        assert_eq!(items[1].name.as_deref(), Some("Point"));
+        assert_eq!(items[1].kind, "method_definition");
+        assert_eq!(items[1].name.as_deref(), Some("constructor"));
+        assert_eq!(items[1].scope, vec!["class Point".to_owned()]);
         assert_eq!(items[2].kind, "function_declaration");
         assert_eq!(items[2].name.as_deref(), Some("goodbye"));
     }
@@ -1367,6 +2363,153 @@ type Point struct {
         assert_eq!(items[0].name.as_deref(), Some("hello"));
     }
 
+    #[test]
+    fn parse_java_file_extracts_class_members() {
+        let source = br#"package com.example;
+
+import java.util.List;
+
+public class Foo {
+    private int x = 1;
+
+    public void bar() {
+        x++;
+    }
+
+    public void bar(int n) {
+        x += n;
+    }
+}
+"#;
+        let (_tree, items) =
+            parse_and_extract(source, AstLanguage::Java).expect("operation should succeed");
+        let summary: Vec<_> = items
+            .iter()
+            .map(|i| (i.kind.as_str(), i.name.as_deref(), i.scope.clone()))
+            .collect();
+        let class_scope = vec!["class Foo".to_owned()];
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "package_declaration",
+                    Some("package com.example;"),
+                    Vec::new()
+                ),
+                (
+                    "import_declaration",
+                    Some("import java.util.List;"),
+                    Vec::new()
+                ),
+                ("field_declaration", Some("x"), class_scope.clone()),
+                ("method_declaration", Some("bar"), class_scope.clone()),
+                ("method_declaration", Some("bar"), class_scope),
+            ]
+        );
+
+        // Overloads get distinct identities.
+        let keys = item_keys(&items);
+        assert_ne!(keys[3], keys[4]);
+    }
+
+    #[test]
+    fn parse_c_file_extracts_declarator_names() {
+        let source = br#"#include <stdio.h>
+#define MAX 10
+
+struct point { int x; int y; };
+
+static int counter = 0;
+
+static char *name(void) { return "x"; }
+
+int add(int a, int b) {
+    return a + b;
+}
+"#;
+        let (_tree, items) =
+            parse_and_extract(source, AstLanguage::C).expect("operation should succeed");
+        let names: Vec<_> = items
+            .iter()
+            .map(|i| (i.kind.as_str(), i.name.as_deref()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("preproc_include", Some("#include <stdio.h>")),
+                ("preproc_def", Some("MAX")),
+                ("struct_specifier", Some("point")),
+                ("declaration", Some("counter")),
+                ("function_definition", Some("name")),
+                ("function_definition", Some("add")),
+            ]
+        );
+        // Item ranges exclude the directive's trailing newline.
+        assert_eq!(items[0].content, b"#include <stdio.h>");
+    }
+
+    #[test]
+    fn parse_cpp_file_descends_into_namespaces_and_classes() {
+        let source = br"namespace demo {
+
+class Widget {
+public:
+    int size() const { return 1; }
+};
+
+template <typename T>
+T ident(T v) { return v; }
+
+}
+";
+        let (_tree, items) =
+            parse_and_extract(source, AstLanguage::Cpp).expect("operation should succeed");
+        let names: Vec<_> = items
+            .iter()
+            .map(|i| (i.name.as_deref(), i.scope.join("::")))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (Some("size"), "namespace demo::class Widget".to_owned()),
+                (Some("ident"), "namespace demo".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_csharp_file_extracts_members() {
+        let source = br"using System;
+
+namespace Demo
+{
+    public class Foo
+    {
+        private int _x, _y;
+        public int X { get; set; }
+
+        public void Bar() {}
+    }
+}
+";
+        let (_tree, items) =
+            parse_and_extract(source, AstLanguage::CSharp).expect("operation should succeed");
+        let names: Vec<_> = items
+            .iter()
+            .map(|i| (i.kind.as_str(), i.name.as_deref()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("using_directive", Some("using System;")),
+                ("field_declaration", Some("_x")),
+                ("property_declaration", Some("X")),
+                ("method_declaration", Some("Bar")),
+            ]
+        );
+        assert_eq!(items[3].scope, vec!["namespace Demo", "class Foo"]);
+    }
+
     // -----------------------------------------------------------------------
     // Edit script computation
     // -----------------------------------------------------------------------
@@ -1572,6 +2715,7 @@ type Point struct {
         ];
         let config = AstMergeConfig {
             enabled_languages: vec![AstLanguage::Rust],
+            structured_formats: Vec::new(),
             semantic_false_positive_budget_pct: 15,
             semantic_min_confidence: 70,
         };
@@ -1601,6 +2745,7 @@ type Point struct {
         ];
         let config = AstMergeConfig {
             enabled_languages: vec![AstLanguage::Rust],
+            structured_formats: Vec::new(),
             semantic_false_positive_budget_pct: 2,
             semantic_min_confidence: 90,
         };
@@ -1737,6 +2882,7 @@ type Point struct {
     fn config_partial_languages() {
         let config = AstMergeConfig {
             enabled_languages: vec![AstLanguage::Rust],
+            structured_formats: Vec::new(),
             semantic_false_positive_budget_pct: 5,
             semantic_min_confidence: 70,
         };
@@ -1798,6 +2944,331 @@ type Point struct {
         );
     }
 
+    // -----------------------------------------------------------------------
+    // JVM / native / .NET member-level merging
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn java_methods_added_to_same_class_merge_cleanly() {
+        let base = b"package app;\n\npublic class Service {\n    public void start() {\n        run();\n    }\n}\n";
+        let variant_a = b"package app;\n\npublic class Service {\n    public void start() {\n        run();\n    }\n\n    /** Stops the service. */\n    public void stop() {\n        halt();\n    }\n}\n";
+        let variant_b = b"package app;\n\npublic class Service {\n    public void start() {\n        run();\n    }\n\n    public int status() {\n        return 0;\n    }\n}\n";
+
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+        match try_ast_merge(base, &variants, AstLanguage::Java) {
+            AstMergeResult::Clean(merged) => {
+                let merged = String::from_utf8(merged).expect("merged output should be UTF-8");
+                assert_eq!(
+                    merged,
+                    "package app;\n\npublic class Service {\n    public void start() {\n        run();\n    }\n\n    /** Stops the service. */\n    public void stop() {\n        halt();\n    }\n\n    public int status() {\n        return 0;\n    }\n}\n"
+                );
+                assert_eq!(
+                    parse_status(merged.as_bytes(), AstLanguage::Java),
+                    AstParseStatus::Clean
+                );
+            }
+            other => panic!("expected clean merge, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn java_edits_to_different_methods_merge_cleanly() {
+        let base = b"class A {\n    void f() { one(); }\n\n    void g() { two(); }\n}\n";
+        let variant_a = b"class A {\n    void f() { uno(); }\n\n    void g() { two(); }\n}\n";
+        let variant_b = b"class A {\n    void f() { one(); }\n\n    void g() { dos(); }\n}\n";
+
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+        match try_ast_merge(base, &variants, AstLanguage::Java) {
+            AstMergeResult::Clean(merged) => assert_eq!(
+                merged,
+                b"class A {\n    void f() { uno(); }\n\n    void g() { dos(); }\n}\n"
+            ),
+            other => panic!("expected clean merge, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn java_same_method_edit_conflicts_with_member_scope() {
+        let base = b"class A {\n    void f() { one(); }\n}\n";
+        let variant_a = b"class A {\n    void f() { uno(); }\n}\n";
+        let variant_b = b"class A {\n    void f() { eins(); }\n}\n";
+
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+        match try_ast_merge(base, &variants, AstLanguage::Java) {
+            AstMergeResult::Conflict { atoms } => {
+                assert_eq!(atoms.len(), 1);
+                assert!(
+                    atoms[0].reason.to_string().contains("class A::f"),
+                    "reason should name the member: {}",
+                    atoms[0].reason
+                );
+            }
+            other => panic!("expected conflict, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn java_class_removed_by_variant_is_unsupported() {
+        let base = b"class A {\n    void f() {}\n}\n\nclass B {}\n";
+        let variant_a = b"class B {}\n";
+        let variant_b = b"class A {\n    void f() { x(); }\n}\n\nclass B {}\n";
+
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+        assert!(matches!(
+            try_ast_merge(base, &variants, AstLanguage::Java),
+            AstMergeResult::Unsupported
+        ));
+    }
+
+    #[test]
+    fn c_functions_and_includes_merge_cleanly() {
+        let base = b"#include <stdio.h>\n\nint add(int a, int b) {\n    return a + b;\n}\n";
+        let variant_a = b"#include <stdio.h>\n#include <stdlib.h>\n\nint add(int a, int b) {\n    return a + b;\n}\n";
+        let variant_b = b"#include <stdio.h>\n\nint add(int a, int b) {\n    return b + a;\n}\n\nint sub(int a, int b) {\n    return a - b;\n}\n";
+
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+        match try_ast_merge(base, &variants, AstLanguage::C) {
+            AstMergeResult::Clean(merged) => assert_eq!(
+                String::from_utf8(merged).expect("merged output should be UTF-8"),
+                "#include <stdio.h>\n#include <stdlib.h>\n\nint add(int a, int b) {\n    return b + a;\n}\n\nint sub(int a, int b) {\n    return a - b;\n}\n"
+            ),
+            other => panic!("expected clean merge, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn cpp_class_members_added_in_namespace_merge_cleanly() {
+        let base = b"namespace demo {\n\nclass Widget {\npublic:\n    int size() const { return 1; }\n};\n\n}\n";
+        let variant_a = b"namespace demo {\n\nclass Widget {\npublic:\n    int size() const { return 1; }\n\n    int width() const { return 2; }\n};\n\n}\n";
+        let variant_b = b"namespace demo {\n\nclass Widget {\npublic:\n    int size() const { return 3; }\n};\n\n}\n";
+
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+        match try_ast_merge(base, &variants, AstLanguage::Cpp) {
+            AstMergeResult::Clean(merged) => assert_eq!(
+                String::from_utf8(merged).expect("merged output should be UTF-8"),
+                "namespace demo {\n\nclass Widget {\npublic:\n    int size() const { return 3; }\n\n    int width() const { return 2; }\n};\n\n}\n"
+            ),
+            other => panic!("expected clean merge, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn csharp_methods_added_to_same_class_merge_cleanly() {
+        let base = b"namespace Demo\n{\n    public class Foo\n    {\n        public void Bar() {}\n    }\n}\n";
+        let variant_a = b"namespace Demo\n{\n    public class Foo\n    {\n        public void Bar() {}\n\n        [Obsolete]\n        public void Baz() {}\n    }\n}\n";
+        let variant_b = b"namespace Demo\n{\n    public class Foo\n    {\n        public void Bar() {}\n\n        public int Qux => 1;\n    }\n}\n";
+
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+        match try_ast_merge(base, &variants, AstLanguage::CSharp) {
+            AstMergeResult::Clean(merged) => assert_eq!(
+                String::from_utf8(merged).expect("merged output should be UTF-8"),
+                "namespace Demo\n{\n    public class Foo\n    {\n        public void Bar() {}\n\n        [Obsolete]\n        public void Baz() {}\n\n        public int Qux => 1;\n    }\n}\n"
+            ),
+            other => panic!("expected clean merge, got: {other:?}"),
+        }
+    }
+
+    fn expect_clean(result: AstMergeResult) -> String {
+        match result {
+            AstMergeResult::Clean(merged) => {
+                String::from_utf8(merged).expect("merged output should be UTF-8")
+            }
+            other => panic!("expected clean merge, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rust_methods_added_to_same_impl_merge_cleanly() {
+        let base = b"struct Foo;\n\nimpl Foo {\n    fn a(&self) {}\n}\n";
+        let variant_a = b"struct Foo;\n\nimpl Foo {\n    fn a(&self) {}\n\n    /// Docs for b.\n    fn b(&self) {}\n}\n";
+        let variant_b = b"struct Foo;\n\nimpl Foo {\n    fn a(&self) {}\n\n    #[inline]\n    fn c(&self) {}\n}\n";
+
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+        assert_eq!(
+            expect_clean(try_ast_merge(base, &variants, AstLanguage::Rust)),
+            "struct Foo;\n\nimpl Foo {\n    fn a(&self) {}\n\n    /// Docs for b.\n    fn b(&self) {}\n\n    #[inline]\n    fn c(&self) {}\n}\n"
+        );
+    }
+
+    #[test]
+    fn rust_same_method_edit_conflict_carries_nested_path() {
+        let base = b"impl Foo {\n    fn bar(&self) { one(); }\n    fn baz(&self) {}\n}\n";
+        let variant_a = b"impl Foo {\n    fn bar(&self) { uno(); }\n    fn baz(&self) {}\n}\n";
+        let variant_b = b"impl Foo {\n    fn bar(&self) { eins(); }\n    fn baz(&self) {}\n}\n";
+
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+        match try_ast_merge(base, &variants, AstLanguage::Rust) {
+            AstMergeResult::Conflict { atoms } => {
+                assert_eq!(atoms.len(), 1);
+                match &atoms[0].base_region {
+                    Region::AstNode { name, .. } => {
+                        assert_eq!(name.as_deref(), Some("impl Foo::bar"));
+                    }
+                    other => panic!("expected AstNode region, got {other:?}"),
+                }
+                for edit in &atoms[0].edits {
+                    assert!(matches!(
+                        &edit.region,
+                        Region::AstNode { name: Some(name), .. } if name == "impl Foo::bar"
+                    ));
+                }
+            }
+            other => panic!("expected conflict, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rust_trait_impl_and_inherent_impl_are_distinct_containers() {
+        let base = b"impl Foo {\n    fn new() -> Self { Foo }\n}\n\nimpl Display for Foo {\n    fn fmt(&self) {}\n}\n";
+        let variant_a = b"impl Foo {\n    fn new() -> Self { Foo }\n\n    fn a() {}\n}\n\nimpl Display for Foo {\n    fn fmt(&self) {}\n}\n";
+        let variant_b = b"impl Foo {\n    fn new() -> Self { Foo }\n}\n\nimpl Display for Foo {\n    fn fmt(&self) { write(); }\n}\n";
+
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+        assert_eq!(
+            expect_clean(try_ast_merge(base, &variants, AstLanguage::Rust)),
+            "impl Foo {\n    fn new() -> Self { Foo }\n\n    fn a() {}\n}\n\nimpl Display for Foo {\n    fn fmt(&self) { write(); }\n}\n"
+        );
+    }
+
+    #[test]
+    fn rust_repeated_impl_blocks_merge_by_occurrence() {
+        let base = b"impl Foo {\n    fn a() {}\n}\n\nimpl Foo {\n    fn b() {}\n}\n";
+        let variant_a = b"impl Foo {\n    fn a() { 1 }\n}\n\nimpl Foo {\n    fn b() {}\n}\n";
+        let variant_b = b"impl Foo {\n    fn a() {}\n}\n\nimpl Foo {\n    fn b() { 2 }\n}\n";
+
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+        assert_eq!(
+            expect_clean(try_ast_merge(base, &variants, AstLanguage::Rust)),
+            "impl Foo {\n    fn a() { 1 }\n}\n\nimpl Foo {\n    fn b() { 2 }\n}\n"
+        );
+    }
+
+    #[test]
+    fn rust_impl_header_change_is_unsupported() {
+        let base = b"impl Foo {\n    fn a() {}\n}\n";
+        let variant_a = b"impl<T> Foo<T> {\n    fn a() {}\n\n    fn b() {}\n}\n";
+        let variant_b = b"impl Foo {\n    fn a() {}\n\n    fn c() {}\n}\n";
+
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+        assert!(matches!(
+            try_ast_merge(base, &variants, AstLanguage::Rust),
+            AstMergeResult::Unsupported
+        ));
+    }
+
+    #[test]
+    fn rust_trait_and_test_module_members_merge_cleanly() {
+        let base =
+            b"trait Tr {\n    fn f(&self);\n}\n\nmod tests {\n    #[test]\n    fn t1() {}\n}\n";
+        let variant_a = b"trait Tr {\n    fn f(&self);\n\n    fn g(&self);\n}\n\nmod tests {\n    #[test]\n    fn t1() {}\n\n    #[test]\n    fn t2() {}\n}\n";
+        let variant_b = b"trait Tr {\n    fn f(&self);\n}\n\nmod tests {\n    #[test]\n    fn t1() {}\n\n    #[test]\n    fn t3() {}\n}\n";
+
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+        assert_eq!(
+            expect_clean(try_ast_merge(base, &variants, AstLanguage::Rust)),
+            "trait Tr {\n    fn f(&self);\n\n    fn g(&self);\n}\n\nmod tests {\n    #[test]\n    fn t1() {}\n\n    #[test]\n    fn t2() {}\n\n    #[test]\n    fn t3() {}\n}\n"
+        );
+    }
+
+    #[test]
+    fn python_methods_added_to_same_class_merge_cleanly() {
+        let base = b"class Foo:\n    def a(self):\n        return 1\n\n\ndef main():\n    pass\n";
+        let variant_a = b"class Foo:\n    def a(self):\n        return 1\n\n    def b(self):\n        return 2\n\n\ndef main():\n    pass\n";
+        let variant_b = b"class Foo:\n    def a(self):\n        return 1\n\n    @property\n    def c(self):\n        return 3\n\n\ndef main():\n    pass\n";
+
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+        assert_eq!(
+            expect_clean(try_ast_merge(base, &variants, AstLanguage::Python)),
+            "class Foo:\n    def a(self):\n        return 1\n\n    def b(self):\n        return 2\n\n    @property\n    def c(self):\n        return 3\n\n\ndef main():\n    pass\n"
+        );
+    }
+
+    #[test]
+    fn typescript_exported_class_members_merge_cleanly() {
+        let base = b"export class Foo {\n  x: number = 1;\n\n  a(): void {}\n}\n";
+        let variant_a =
+            b"export class Foo {\n  x: number = 1;\n  y: string = \"\";\n\n  a(): void {}\n}\n";
+        let variant_b = b"export class Foo {\n  x: number = 1;\n\n  a(): void {}\n\n  b(): number {\n    return 2;\n  }\n}\n";
+
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+        assert_eq!(
+            expect_clean(try_ast_merge(base, &variants, AstLanguage::TypeScript)),
+            "export class Foo {\n  x: number = 1;\n\n  a(): void {}\n\n  y: string = \"\";\n\n  b(): number {\n    return 2;\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn go_methods_are_grouped_by_receiver() {
+        let base = b"package main\n\nfunc (f *Foo) String() string { return \"foo\" }\n\nfunc (b Bar) String() string { return \"bar\" }\n\nfunc main() {}\n";
+        let variant_a = b"package main\n\nfunc (f *Foo) String() string { return \"FOO\" }\n\nfunc (b Bar) String() string { return \"bar\" }\n\nfunc main() {}\n";
+        let variant_b = b"package main\n\nfunc (f *Foo) String() string { return \"foo\" }\n\nfunc (f *Foo) Len() int { return 0 }\n\nfunc (b Bar) String() string { return \"BAR\" }\n\nfunc main() {}\n";
+
+        let (_tree, items) =
+            parse_and_extract(base, AstLanguage::Go).expect("operation should succeed");
+        assert_eq!(
+            items[0].qualified_name().as_deref(),
+            Some("type Foo::String")
+        );
+        assert_eq!(
+            items[1].qualified_name().as_deref(),
+            Some("type Bar::String")
+        );
+
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+        assert_eq!(
+            expect_clean(try_ast_merge(base, &variants, AstLanguage::Go)),
+            "package main\n\nfunc (f *Foo) String() string { return \"FOO\" }\n\nfunc (f *Foo) Len() int { return 0 }\n\nfunc (b Bar) String() string { return \"BAR\" }\n\nfunc main() {}\n"
+        );
+    }
+
     // -----------------------------------------------------------------------
     // use_declaration extraction and merging
     // -----------------------------------------------------------------------
@@ -1888,6 +3359,147 @@ type Point struct {
         }
     }
 
+    // -----------------------------------------------------------------------
+    // Import union
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn rust_imports_added_by_both_sides_keep_sort_order() {
+        let base = b"use std::fs;\nuse std::path::Path;\n\nuse crate::config;\n\nfn main() {}\n";
+        let variant_a =
+            b"use std::fs;\nuse std::io;\nuse std::path::Path;\n\nuse crate::config;\n\nfn main() {}\n";
+        let variant_b = b"use std::collections::BTreeMap;\nuse std::fs;\nuse std::path::Path;\n\nuse crate::config;\nuse crate::model;\n\nfn main() {}\n";
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+
+        let merged = expect_clean(try_ast_merge(base, &variants, AstLanguage::Rust));
+        assert_eq!(
+            merged,
+            "use std::collections::BTreeMap;\nuse std::fs;\nuse std::io;\nuse std::path::Path;\n\nuse crate::config;\nuse crate::model;\n\nfn main() {}\n"
+        );
+    }
+
+    #[test]
+    fn unsorted_import_group_appends_additions() {
+        let base = b"use std::io;\nuse std::fs;\n\nfn main() {}\n";
+        let variant_a = b"use std::io;\nuse std::fs;\nuse std::env;\n\nfn main() {}\n";
+        let variant_b = b"use std::io;\nuse std::fs;\nuse std::borrow::Cow;\n\nfn main() {}\n";
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+
+        let merged = expect_clean(try_ast_merge(base, &variants, AstLanguage::Rust));
+        assert_eq!(
+            merged,
+            "use std::io;\nuse std::fs;\nuse std::env;\nuse std::borrow::Cow;\n\nfn main() {}\n"
+        );
+    }
+
+    #[test]
+    fn same_import_added_by_both_sides_is_deduplicated_and_reported() {
+        let base = b"use std::fs;\n\nfn a() {}\n\nfn b() {}\n";
+        let variant_a = b"use std::fs;\nuse std::io;\n\nfn a() { io(); }\n\nfn b() {}\n";
+        let variant_b = b"use std::fs;\nuse std::io;\n\nfn a() {}\n\nfn b() { io(); }\n";
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+
+        let (result, report) = try_ast_merge_with_report(
+            base,
+            &variants,
+            AstLanguage::Rust,
+            &AstMergeConfig::default(),
+        );
+        let merged = expect_clean(result);
+        assert_eq!(
+            merged,
+            "use std::fs;\nuse std::io;\n\nfn a() { io(); }\n\nfn b() { io(); }\n"
+        );
+        assert_eq!(
+            report.merged_imports,
+            vec![MergedImport {
+                statement: "use std::io;".to_owned(),
+                workspaces: vec![ws("ws-a"), ws("ws-b")],
+            }]
+        );
+    }
+
+    #[test]
+    fn python_imports_merge_into_their_isort_sections() {
+        let base = b"import os\nimport sys\nfrom pathlib import Path\n\n\ndef main():\n    pass\n";
+        let variant_a =
+            b"import json\nimport os\nimport sys\nfrom pathlib import Path\n\n\ndef main():\n    pass\n";
+        let variant_b = b"import os\nimport sys\nfrom pathlib import Path\nfrom typing import Any\n\n\ndef main():\n    pass\n";
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+
+        let merged = expect_clean(try_ast_merge(base, &variants, AstLanguage::Python));
+        assert_eq!(
+            merged,
+            "import json\nimport os\nimport sys\nfrom pathlib import Path\nfrom typing import Any\n\n\ndef main():\n    pass\n"
+        );
+    }
+
+    #[test]
+    fn go_import_block_specs_merge_sorted_by_path() {
+        let base = b"package main\n\nimport (\n\t\"fmt\"\n\t\"os\"\n)\n\nfunc main() {}\n";
+        let variant_a =
+            b"package main\n\nimport (\n\t\"fmt\"\n\t\"os\"\n\tstr \"strings\"\n)\n\nfunc main() {}\n";
+        let variant_b =
+            b"package main\n\nimport (\n\t\"bytes\"\n\t\"fmt\"\n\t\"os\"\n)\n\nfunc main() {}\n";
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+
+        let merged = expect_clean(try_ast_merge(base, &variants, AstLanguage::Go));
+        assert_eq!(
+            merged,
+            "package main\n\nimport (\n\t\"bytes\"\n\t\"fmt\"\n\t\"os\"\n\tstr \"strings\"\n)\n\nfunc main() {}\n"
+        );
+    }
+
+    #[test]
+    fn typescript_imports_merge_cleanly() {
+        let base =
+            b"import { a } from \"./a\";\nimport { c } from \"./c\";\n\nexport function f() {}\n";
+        let variant_a = b"import { a } from \"./a\";\nimport { b } from \"./b\";\nimport { c } from \"./c\";\n\nexport function f() {}\n";
+        let variant_b = b"import { a } from \"./a\";\nimport { c } from \"./c\";\nimport { d } from \"./d\";\n\nexport function f() {}\n";
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+
+        let merged = expect_clean(try_ast_merge(base, &variants, AstLanguage::TypeScript));
+        assert_eq!(
+            merged,
+            "import { a } from \"./a\";\nimport { b } from \"./b\";\nimport { c } from \"./c\";\nimport { d } from \"./d\";\n\nexport function f() {}\n"
+        );
+    }
+
+    #[test]
+    fn cpp_includes_merge_within_their_group() {
+        let base = b"#include <string>\n#include <vector>\n\n#include \"app.h\"\n\nint main() { return 0; }\n";
+        let variant_a = b"#include <map>\n#include <string>\n#include <vector>\n\n#include \"app.h\"\n\nint main() { return 0; }\n";
+        let variant_b = b"#include <string>\n#include <vector>\n\n#include \"app.h\"\n#include \"util.h\"\n\nint main() { return 0; }\n";
+        let variants = vec![
+            (ws("ws-a"), variant_a.to_vec()),
+            (ws("ws-b"), variant_b.to_vec()),
+        ];
+
+        let merged = expect_clean(try_ast_merge(base, &variants, AstLanguage::Cpp));
+        assert_eq!(
+            merged,
+            "#include <map>\n#include <string>\n#include <vector>\n\n#include \"app.h\"\n#include \"util.h\"\n\nint main() { return 0; }\n"
+        );
+    }
+
     #[test]
     fn parse_rust_union_item() {
         let source = b"union MyUnion {\n    i: i32,\n    f: f32,\n}\n";
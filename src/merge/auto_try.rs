@@ -0,0 +1,347 @@
+//! Validation-guided conflict resolution (`[merge] auto_try_resolutions`).
+//!
+//! For small conflicts the quickest resolution is often "try each side and
+//! see which one builds". [`conflict_options`] turns a [`ConflictRecord`]
+//! into its candidate resolutions — one per combination of sides across the
+//! diff3 hunks of a two-sided text conflict, or one per side for anything
+//! else — and [`auto_try`] materializes every combination across all
+//! conflicts of a merge, runs the configured validation commands against
+//! each in a scratch worktree, and reports which passed.
+//!
+//! Exactly one passing combination is an unambiguous answer and the caller
+//! may commit it; zero or several are reported alongside the conflicts as a
+//! matrix of results. The number of combinations is bounded up front so a
+//! large conflict never turns into an unbounded build loop.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::config::ValidationConfig;
+use crate::model::types::GitOid;
+
+use super::diff3::{Diff3Segment, diff3_segments};
+use super::resolve::ConflictRecord;
+use super::validate::run_validate_phase;
+
+/// One way to resolve a conflicted file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConflictOption {
+    /// The side chosen for each hunk (a single entry for whole-file choices).
+    pub choice: Vec<String>,
+    /// The resolved file content.
+    pub content: Vec<u8>,
+}
+
+/// The candidate resolutions of one conflict.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConflictOptions {
+    /// Conflict ID (e.g. "cf-k7mx").
+    pub id: String,
+    /// Conflicted path.
+    pub path: PathBuf,
+    /// Candidate resolutions, in a deterministic order.
+    pub options: Vec<ConflictOption>,
+}
+
+/// The validation outcome of one candidate combination.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CandidateResult {
+    /// Conflict ID → side chosen for each hunk.
+    pub choices: BTreeMap<String, Vec<String>>,
+    /// Candidate commit the combination was validated at.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub candidate: Option<String>,
+    /// Whether every validation command passed.
+    pub passed: bool,
+    /// Total validation time in milliseconds.
+    pub duration_ms: u64,
+    /// First failing command, when validation failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed_command: Option<String>,
+    /// Why the candidate could not be validated at all.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Resolved contents of every conflicted path.
+    #[serde(skip)]
+    pub resolved: BTreeMap<PathBuf, Vec<u8>>,
+}
+
+/// Results of trying every candidate combination.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AutoTryReport {
+    /// One entry per combination, in enumeration order.
+    pub candidates: Vec<CandidateResult>,
+    /// Index of the only passing candidate, if exactly one passed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected: Option<usize>,
+}
+
+impl AutoTryReport {
+    /// The only passing candidate, if exactly one passed.
+    #[must_use]
+    pub fn winner(&self) -> Option<&CandidateResult> {
+        self.selected.and_then(|i| self.candidates.get(i))
+    }
+
+    /// Number of candidates that passed validation.
+    #[must_use]
+    pub fn passed_count(&self) -> usize {
+        self.candidates.iter().filter(|c| c.passed).count()
+    }
+}
+
+/// Candidate resolutions for `record`.
+///
+/// A two-sided text conflict with a base is split into diff3 hunks and
+/// yields every combination of per-hunk side choices, keeping the regions
+/// both sides agree on (up to [`MAX_HUNKS`] hunks). Anything else yields one whole-file option per side
+/// that has content (deletions are not candidates).
+#[must_use]
+pub fn conflict_options(id: &str, record: &ConflictRecord) -> ConflictOptions {
+    let names: Vec<String> = record
+        .sides
+        .iter()
+        .map(|side| side.workspace_id.to_string())
+        .collect();
+    let options = hunk_options(record, &names).unwrap_or_else(|| {
+        record
+            .sides
+            .iter()
+            .zip(&names)
+            .filter_map(|(side, name)| {
+                side.content.as_ref().map(|content| ConflictOption {
+                    choice: vec![name.clone()],
+                    content: content.clone(),
+                })
+            })
+            .collect()
+    });
+    ConflictOptions {
+        id: id.to_owned(),
+        path: record.path.clone(),
+        options,
+    }
+}
+
+/// Beyond this many hunks a conflict falls back to whole-file choices, so
+/// enumeration stays small no matter how fragmented the conflict is.
+const MAX_HUNKS: usize = 8;
+
+/// Per-hunk combinations for a two-sided text conflict, or `None` when the
+/// conflict does not split into diff3 hunks.
+fn hunk_options(record: &ConflictRecord, names: &[String]) -> Option<Vec<ConflictOption>> {
+    let [ours, theirs] = record.sides.as_slice() else {
+        return None;
+    };
+    let segments = diff3_segments(
+        std::str::from_utf8(record.base.as_deref()?).ok()?,
+        std::str::from_utf8(ours.content.as_deref()?).ok()?,
+        std::str::from_utf8(theirs.content.as_deref()?).ok()?,
+    )?;
+    let hunks = segments
+        .iter()
+        .filter(|segment| matches!(segment, Diff3Segment::Conflict(_)))
+        .count();
+    if hunks > MAX_HUNKS {
+        return None;
+    }
+    let combos = 1usize << hunks;
+
+    Some(
+        (0..combos)
+            .map(|mask| {
+                let mut choice = Vec::with_capacity(hunks);
+                let mut content = String::new();
+                let mut hunk = 0;
+                for segment in &segments {
+                    match segment {
+                        Diff3Segment::Common(lines) => content.extend(lines.iter().cloned()),
+                        Diff3Segment::Conflict(conflict) => {
+                            let pick_theirs = mask & (1 << hunk) != 0;
+                            let (name, lines) = if pick_theirs {
+                                (&names[1], &conflict.theirs)
+                            } else {
+                                (&names[0], &conflict.ours)
+                            };
+                            choice.push(name.clone());
+                            content.extend(lines.iter().cloned());
+                            hunk += 1;
+                        }
+                    }
+                }
+                ConflictOption {
+                    choice,
+                    content: content.into_bytes(),
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Number of combinations across `conflicts` (saturating).
+#[must_use]
+pub fn candidate_count(conflicts: &[ConflictOptions]) -> usize {
+    conflicts
+        .iter()
+        .fold(1usize, |acc, c| acc.saturating_mul(c.options.len()))
+}
+
+/// Try every combination of `conflicts`' options.
+///
+/// `materialize` builds a candidate commit from the resolved contents of
+/// every conflicted path (the merge candidate with those paths patched in);
+/// each commit is then validated with `config` in a scratch worktree.
+///
+/// Returns `None` when there is nothing to try: no validation commands are
+/// configured, some conflict has no candidate resolution, or there are more
+/// than `max_candidates` combinations.
+pub fn auto_try<F>(
+    repo_root: &Path,
+    conflicts: &[ConflictOptions],
+    config: &ValidationConfig,
+    max_candidates: usize,
+    mut materialize: F,
+) -> Option<AutoTryReport>
+where
+    F: FnMut(&BTreeMap<PathBuf, Vec<u8>>) -> Result<GitOid, String>,
+{
+    let count = candidate_count(conflicts);
+    if !config.has_commands() || conflicts.is_empty() || count == 0 || count > max_candidates {
+        return None;
+    }
+
+    let candidates: Vec<CandidateResult> = (0..count)
+        .map(|n| {
+            let mut choices = BTreeMap::new();
+            let mut resolved = BTreeMap::new();
+            let mut rest = n;
+            for conflict in conflicts {
+                let option = &conflict.options[rest % conflict.options.len()];
+                rest /= conflict.options.len();
+                choices.insert(conflict.id.clone(), option.choice.clone());
+                resolved.insert(conflict.path.clone(), option.content.clone());
+            }
+            let mut result = CandidateResult {
+                choices,
+                candidate: None,
+                passed: false,
+                duration_ms: 0,
+                failed_command: None,
+                error: None,
+                resolved,
+            };
+            let oid = match materialize(&result.resolved) {
+                Ok(oid) => oid,
+                Err(e) => {
+                    result.error = Some(e);
+                    return result;
+                }
+            };
+            result.candidate = Some(oid.as_str().to_owned());
+            match run_validate_phase(repo_root, &oid, config) {
+                Ok(outcome) => {
+                    if let Some(validation) = outcome.result() {
+                        result.passed = validation.passed;
+                        result.duration_ms = validation.duration_ms;
+                        result.failed_command = validation
+                            .command_results
+                            .iter()
+                            .find(|c| !c.passed)
+                            .map(|c| c.command.clone());
+                    }
+                }
+                Err(e) => result.error = Some(e.to_string()),
+            }
+            result
+        })
+        .collect();
+
+    let mut passing = candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.passed)
+        .map(|(i, _)| i);
+    let selected = match (passing.next(), passing.next()) {
+        (Some(i), None) => Some(i),
+        _ => None,
+    };
+    Some(AutoTryReport {
+        candidates,
+        selected,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+mod tests {
+    use super::*;
+    use crate::merge::resolve::{ConflictReason, ConflictSide};
+    use crate::merge::types::ChangeKind;
+    use crate::model::types::WorkspaceId;
+
+    fn side(name: &str, content: &str) -> ConflictSide {
+        ConflictSide {
+            workspace_id: WorkspaceId::new(name).unwrap(),
+            kind: ChangeKind::Modified,
+            content: Some(content.as_bytes().to_vec()),
+        }
+    }
+
+    fn record(base: &str, ours: &str, theirs: &str) -> ConflictRecord {
+        ConflictRecord {
+            path: PathBuf::from("src/lib.rs"),
+            base: Some(base.as_bytes().to_vec()),
+            sides: vec![side("alice", ours), side("bob", theirs)],
+            reason: ConflictReason::Diff3Conflict,
+            atoms: vec![],
+        }
+    }
+
+    #[test]
+    fn two_sided_conflicts_combine_per_hunk() {
+        let rec = record(
+            "a\nx = 1\nb\ny = 1\nc\n",
+            "a\nx = 2\nb\ny = 2\nc\n",
+            "a\nx = 3\nb\ny = 3\nc\n",
+        );
+        let opts = conflict_options("cf-abcd", &rec);
+        assert_eq!(opts.options.len(), 4);
+        assert_eq!(opts.options[0].choice, vec!["alice", "alice"]);
+        assert_eq!(opts.options[0].content, b"a\nx = 2\nb\ny = 2\nc\n");
+        assert_eq!(opts.options[1].choice, vec!["bob", "alice"]);
+        assert_eq!(opts.options[1].content, b"a\nx = 3\nb\ny = 2\nc\n");
+    }
+
+    #[test]
+    fn other_conflicts_offer_each_side_with_content() {
+        let mut rec = record("a\n", "b\n", "c\n");
+        rec.base = None;
+        rec.sides[1].content = None;
+        let opts = conflict_options("cf-abcd", &rec);
+        assert_eq!(opts.options.len(), 1);
+        assert_eq!(opts.options[0].choice, vec!["alice"]);
+    }
+
+    #[test]
+    fn too_many_candidates_are_not_tried() {
+        let opts = conflict_options(
+            "cf-abcd",
+            &record(
+                "a\nx = 1\nb\ny = 1\nc\n",
+                "a\nx = 2\nb\ny = 2\nc\n",
+                "a\nx = 3\nb\ny = 3\nc\n",
+            ),
+        );
+        assert_eq!(candidate_count(std::slice::from_ref(&opts)), 4);
+        let config = ValidationConfig {
+            command: Some("true".to_owned()),
+            ..ValidationConfig::default()
+        };
+        let tried = auto_try(Path::new("."), &[opts], &config, 3, |_| {
+            panic!("nothing should be materialized")
+        });
+        assert!(tried.is_none());
+    }
+}
@@ -0,0 +1,317 @@
+//! Binary and LFS asset metadata for conflict reports.
+//!
+//! A conflict on a binary file carries no readable content, so reports used
+//! to say nothing more than "binary". [`describe`] summarizes one side of
+//! such a conflict — size, a sniffed content type, image dimensions, and for
+//! Git LFS pointers the pointed-to object (and whether it is in the local
+//! store) — so an agent can choose a side without opening either file.
+//!
+//! Sniffing only looks at leading magic bytes; LFS objects are read from the
+//! local store when present and never fetched.
+
+use std::fmt::Write as _;
+use std::io::Read;
+
+use serde::Serialize;
+
+use maw_lfs::{Pointer, Store, looks_like_pointer};
+
+/// How many leading bytes of an LFS object are read for sniffing.
+const SNIFF_BYTES: u64 = 64 * 1024;
+
+/// Metadata for one binary (or LFS-tracked) side of a conflict.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BinaryInfo {
+    /// Size of the real content in bytes (the LFS object size for pointers).
+    pub size: u64,
+    /// Sniffed MIME type; `application/octet-stream` when unrecognized.
+    pub content_type: String,
+    /// LFS pointer details, when the blob is a Git LFS pointer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lfs: Option<LfsInfo>,
+    /// Pixel dimensions, for recognized image formats.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<ImageInfo>,
+}
+
+/// The object a Git LFS pointer refers to.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LfsInfo {
+    /// sha256 of the object (hex).
+    pub oid: String,
+    /// Object size in bytes.
+    pub size: u64,
+    /// Whether the object is present in the local LFS store.
+    pub present: bool,
+}
+
+/// Image dimensions in pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct ImageInfo {
+    /// Width in pixels.
+    pub width: u32,
+    /// Height in pixels.
+    pub height: u32,
+}
+
+/// Parse `bytes` as an LFS pointer, if they are one.
+#[must_use]
+pub fn lfs_pointer(bytes: &[u8]) -> Option<Pointer> {
+    if looks_like_pointer(bytes) {
+        Pointer::parse(bytes).ok()
+    } else {
+        None
+    }
+}
+
+/// Whether `bytes` should be treated as binary: not UTF-8, or containing NUL.
+#[must_use]
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.contains(&0) || std::str::from_utf8(bytes).is_err()
+}
+
+/// Describe `bytes` for a conflict report.
+///
+/// Returns `None` for plain text. LFS pointers are described by the object
+/// they point to; when `store` holds that object its leading bytes are
+/// sniffed for a content type and image dimensions.
+#[must_use]
+pub fn describe(bytes: &[u8], store: Option<&Store>) -> Option<BinaryInfo> {
+    if let Some(pointer) = lfs_pointer(bytes) {
+        let head = store.and_then(|store| read_head(store, &pointer.oid));
+        let (content_type, image) = head
+            .as_deref()
+            .map_or(("application/octet-stream", None), |head| {
+                (sniff(head), image_dimensions(head))
+            });
+        return Some(BinaryInfo {
+            size: pointer.size,
+            content_type: content_type.to_owned(),
+            lfs: Some(LfsInfo {
+                oid: pointer.oid_hex(),
+                size: pointer.size,
+                present: head.is_some(),
+            }),
+            image,
+        });
+    }
+    if !is_binary(bytes) {
+        return None;
+    }
+    Some(BinaryInfo {
+        size: bytes.len() as u64,
+        content_type: sniff(bytes).to_owned(),
+        lfs: None,
+        image: image_dimensions(bytes),
+    })
+}
+
+/// One-line summary, e.g. `image/png 640x480, 12345 bytes (LFS 3f2a9c1b, missing)`.
+#[must_use]
+pub fn summary(info: &BinaryInfo) -> String {
+    let mut out = info.content_type.clone();
+    if let Some(image) = info.image {
+        let _ = write!(out, " {}x{}", image.width, image.height);
+    }
+    let _ = write!(out, ", {} bytes", info.size);
+    if let Some(lfs) = &info.lfs {
+        let state = if lfs.present { "local" } else { "missing" };
+        let _ = write!(out, " (LFS {}, {state})", &lfs.oid[..8]);
+    }
+    out
+}
+
+fn read_head(store: &Store, oid: &[u8; 32]) -> Option<Vec<u8>> {
+    let reader = store.open_object(oid).ok()??;
+    let mut head = Vec::new();
+    reader.take(SNIFF_BYTES).read_to_end(&mut head).ok()?;
+    Some(head)
+}
+
+/// Sniff a MIME type from leading magic bytes.
+fn sniff(bytes: &[u8]) -> &'static str {
+    const MAGIC: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"BM", "image/bmp"),
+        (b"II*\0", "image/tiff"),
+        (b"MM\0*", "image/tiff"),
+        (b"\0\0\x01\0", "image/x-icon"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+        (b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+        (b"\x7fELF", "application/x-elf"),
+        (b"\0asm", "application/wasm"),
+        (b"OggS", "audio/ogg"),
+        (b"fLaC", "audio/flac"),
+        (b"ID3", "audio/mpeg"),
+        (b"glTF", "model/gltf-binary"),
+        (b"wOFF", "font/woff"),
+        (b"wOF2", "font/woff2"),
+    ];
+    if bytes.len() >= 12 && &bytes[..4] == b"RIFF" {
+        match &bytes[8..12] {
+            b"WEBP" => return "image/webp",
+            b"WAVE" => return "audio/wav",
+            _ => {}
+        }
+    }
+    if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" {
+        return "video/mp4";
+    }
+    MAGIC
+        .iter()
+        .find(|(magic, _)| bytes.starts_with(magic))
+        .map_or("application/octet-stream", |(_, mime)| mime)
+}
+
+/// Pixel dimensions of a PNG, GIF, JPEG, BMP, or WebP image.
+fn image_dimensions(bytes: &[u8]) -> Option<ImageInfo> {
+    let be32 = |at: usize| Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+    let le16 = |at: usize| {
+        Some(u32::from(u16::from_le_bytes(
+            bytes.get(at..at + 2)?.try_into().ok()?,
+        )))
+    };
+    let be16 = |at: usize| {
+        Some(u32::from(u16::from_be_bytes(
+            bytes.get(at..at + 2)?.try_into().ok()?,
+        )))
+    };
+    let le24 = |at: usize| {
+        let b = bytes.get(at..at + 3)?;
+        Some(u32::from(b[0]) | u32::from(b[1]) << 8 | u32::from(b[2]) << 16)
+    };
+    let (width, height) = match sniff(bytes) {
+        "image/png" if bytes.get(12..16) == Some(b"IHDR") => (be32(16)?, be32(20)?),
+        "image/gif" => (le16(6)?, le16(8)?),
+        "image/bmp" => {
+            let w = i32::from_le_bytes(bytes.get(18..22)?.try_into().ok()?);
+            let h = i32::from_le_bytes(bytes.get(22..26)?.try_into().ok()?);
+            (w.unsigned_abs(), h.unsigned_abs())
+        }
+        "image/webp" => match bytes.get(12..16)? {
+            b"VP8 " => (vp8_dimension(le16(26)?), vp8_dimension(le16(28)?)),
+            b"VP8L" => {
+                let bits = u32::from_le_bytes(bytes.get(21..25)?.try_into().ok()?);
+                ((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1)
+            }
+            b"VP8X" => (le24(24)? + 1, le24(27)? + 1),
+            _ => return None,
+        },
+        "image/jpeg" => {
+            // Walk the segments to the first start-of-frame marker.
+            let mut at = 2;
+            loop {
+                if *bytes.get(at)? != 0xff {
+                    return None;
+                }
+                let marker = *bytes.get(at + 1)?;
+                let is_sof = matches!(marker, 0xc0..=0xcf) && !matches!(marker, 0xc4 | 0xc8 | 0xcc);
+                if is_sof {
+                    break (be16(at + 7)?, be16(at + 5)?);
+                }
+                at += 2 + usize::try_from(be16(at + 2)?).ok()?;
+            }
+        }
+        _ => return None,
+    };
+    Some(ImageInfo { width, height })
+}
+
+/// VP8 frame dimensions keep a 2-bit scale in their top bits.
+const fn vp8_dimension(n: u32) -> u32 {
+    n & 0x3fff
+}
+
+#[cfg(test)]
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&[8, 6, 0, 0, 0]);
+        bytes
+    }
+
+    #[test]
+    fn text_is_not_described() {
+        assert_eq!(describe(b"fn main() {}\n", None), None);
+    }
+
+    #[test]
+    fn images_report_type_and_dimensions() {
+        let info = describe(&png(640, 480), None).unwrap();
+        assert_eq!(info.content_type, "image/png");
+        assert_eq!(
+            info.image,
+            Some(ImageInfo {
+                width: 640,
+                height: 480
+            })
+        );
+        assert_eq!(summary(&info), "image/png 640x480, 29 bytes");
+
+        let gif = b"GIF89a\x20\x00\x10\x00\x80\0\0";
+        let info = describe(gif, None).unwrap();
+        assert_eq!(
+            info.image,
+            Some(ImageInfo {
+                width: 32,
+                height: 16
+            })
+        );
+
+        let jpeg = b"\xff\xd8\xff\xe0\x00\x04JF\xff\xc0\x00\x11\x08\x00\x0a\x00\x14\x03";
+        let info = describe(jpeg, None).unwrap();
+        assert_eq!(info.content_type, "image/jpeg");
+        assert_eq!(
+            info.image,
+            Some(ImageInfo {
+                width: 20,
+                height: 10
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_binary_is_octet_stream() {
+        let info = describe(b"\0\x01\x02garbage", None).unwrap();
+        assert_eq!(info.content_type, "application/octet-stream");
+        assert_eq!(info.image, None);
+        assert_eq!(info.size, 10);
+    }
+
+    #[test]
+    fn lfs_pointers_describe_the_object() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open(dir.path()).unwrap();
+        let (pointer, _) = store.insert_from_reader(&png(2, 3)[..]).unwrap();
+        let bytes = pointer.write();
+
+        let info = describe(&bytes, Some(&store)).unwrap();
+        assert_eq!(info.content_type, "image/png");
+        assert_eq!(
+            info.image,
+            Some(ImageInfo {
+                width: 2,
+                height: 3
+            })
+        );
+        let lfs = info.lfs.as_ref().unwrap();
+        assert!(lfs.present);
+        assert_eq!(lfs.oid, pointer.oid_hex());
+
+        // Without the object, the pointer still yields its size and oid.
+        let info = describe(&bytes, None).unwrap();
+        assert_eq!(info.size, pointer.size);
+        assert_eq!(info.content_type, "application/octet-stream");
+        assert!(!info.lfs.unwrap().present);
+    }
+}
@@ -20,9 +20,12 @@
 //! 1. **Collect** — snapshot each source workspace via the backend.
 //! 2. **Partition** — group changed paths into unique (single workspace) vs
 //!    shared (multiple workspaces).
-//! 3. **Resolve** — auto-merge shared paths via hash equality / diff3.
+//! 3. **Resolve** — auto-merge shared paths via hash equality / diff3
+//!    (after optional `[merge.normalize]` normalization), or hand them to a
+//!    configured `external` merge driver.
 //! 4. **Drivers** — apply deterministic merge drivers (`regenerate`, `ours`,
-//!    `theirs`) for configured path globs.
+//!    `theirs`) for configured path globs, then the `[[merge.migrations]]`
+//!    numbering checks.
 //! 5. **Build** — apply resolved changes to the epoch tree, produce a new
 //!    git tree + commit.
 
@@ -43,12 +46,16 @@ use crate::backend::WorkspaceBackend;
 use crate::config::{ConfigError, ManifoldConfig, MergeConfig, MergeDriver, MergeDriverKind};
 use crate::merge::build::{BuildError, ResolvedChange, build_merge_commit};
 use crate::merge::collect::{CollectError, collect_snapshots};
+use crate::merge::external_driver::ExternalDriverSet;
+use crate::merge::migrations::MigrationChecks;
+use crate::merge::normalize::Normalizer;
 use crate::merge::partition::{PartitionResult, PathEntry, partition_by_path};
-#[cfg(not(feature = "ast-merge"))]
-use crate::merge::resolve::resolve_partition;
-#[cfg(feature = "ast-merge")]
-use crate::merge::resolve::{ConflictRecord, ResolveError, ResolveResult};
+use crate::merge::rerere::{ResolutionMemory, apply_recorded_resolutions};
+use crate::merge::resolve::{
+    AutoResolution, ConflictRecord, MergeWarning, ResolveError, ResolveResult,
+};
 use crate::merge::types::{ChangeKind, FileChange, PatchSet};
+use crate::merge::union_driver::UnionDriverSet;
 use crate::merge_state::{MergePhase, MergeStateError, MergeStateFile};
 use crate::model::types::{EpochId, GitOid, WorkspaceId};
 
@@ -76,6 +83,12 @@ pub struct BuildPhaseOutput {
     /// uncommitted edits in the target workspace, preventing silent data
     /// loss during stash replay (see bn-43bc / bn-23zf).
     pub resolved_paths: Vec<PathBuf>,
+    /// Shared paths resolved structurally after diff3 conflicted (e.g.
+    /// unioned imports), surfaced in the merge plan.
+    pub auto_resolutions: Vec<AutoResolution>,
+    /// Problems that do not block the merge (e.g. signature drift between
+    /// workspaces), surfaced in the merge plan.
+    pub warnings: Vec<MergeWarning>,
 }
 
 // ---------------------------------------------------------------------------
@@ -240,7 +253,14 @@ pub fn run_build_phase<B: WorkspaceBackend>(
     // stays in Build phase — recovery will abort it.
     crate::fp!("FP_BUILD_BEFORE_MERGE_COMPUTE")
         .map_err(|e| BuildPhaseError::Driver(e.to_string()))?;
-    let output = run_pipeline(repo_root, backend, &state, &config.merge)?;
+    let output = run_pipeline(
+        repo_root,
+        backend,
+        &state.epoch_before,
+        &state.sources,
+        state.commit_message.as_deref(),
+        &config.merge,
+    )?;
     crate::fp!("FP_BUILD_AFTER_MERGE_COMPUTE")
         .map_err(|e| BuildPhaseError::Driver(e.to_string()))?;
 
@@ -287,11 +307,18 @@ pub fn run_build_phase_with_inputs<B: WorkspaceBackend>(
     let attrs = load_attrs_at_epoch(repo_root, epoch);
 
     // 4. Resolve shared paths via hash equality / diff3 / AST merge fallback
-    let resolve_result =
-        resolve_partition_for_build(&partition, &base_contents, &merge_config, Some(&attrs))?;
+    let mut resolve_result = resolve_partition_for_build(
+        repo_root,
+        &partition,
+        &base_contents,
+        &merge_config,
+        Some(&attrs),
+        &ResolutionMemory::for_repo(repo_root),
+    )?;
+    let mut auto_resolutions = std::mem::take(&mut resolve_result.auto_resolutions);
 
-    // 5. Apply deterministic merge drivers
-    let (resolved, conflicts) = apply_merge_drivers(
+    // 5. Apply deterministic merge drivers and migration checks
+    let (mut resolved, mut conflicts) = apply_merge_drivers(
         repo_root,
         epoch,
         sources,
@@ -300,6 +327,17 @@ pub fn run_build_phase_with_inputs<B: WorkspaceBackend>(
         resolve_result,
         &merge_config,
     )?;
+    auto_resolutions.extend(apply_migration_checks(
+        repo_root,
+        epoch,
+        &partition,
+        &base_contents,
+        &mut resolved,
+        &mut conflicts,
+        &merge_config,
+    )?);
+
+    let warnings = detect_warnings(&partition, &resolved, &base_contents, &merge_config);
 
     // 6. Build candidate commit
     let mut repo = open_gix_repo(repo_root)?;
@@ -315,9 +353,33 @@ pub fn run_build_phase_with_inputs<B: WorkspaceBackend>(
         unique_count,
         shared_count,
         resolved_paths,
+        auto_resolutions,
+        warnings,
     })
 }
 
+/// Build a speculative candidate for `sources` onto `epoch` without
+/// reading or writing merge-state.
+///
+/// Runs the same pipeline as [`run_build_phase`] — epoch-delta injection,
+/// resolve, merge drivers — with the repository's merge config. The merge
+/// queue uses it to build candidate epochs ahead of the real merges; the
+/// candidate commit is an unreferenced object until something commits it.
+///
+/// # Errors
+///
+/// Returns [`BuildPhaseError`] if config loading or any pipeline step fails.
+pub fn run_speculative_build<B: WorkspaceBackend>(
+    repo_root: &Path,
+    manifold_dir: &Path,
+    backend: &B,
+    epoch: &EpochId,
+    sources: &[WorkspaceId],
+) -> Result<BuildPhaseOutput, BuildPhaseError> {
+    let config = ManifoldConfig::load(&manifold_dir.join("config.toml"))?;
+    run_pipeline(repo_root, backend, epoch, sources, None, &config.merge)
+}
+
 // ---------------------------------------------------------------------------
 // Internal helpers
 // ---------------------------------------------------------------------------
@@ -633,17 +695,19 @@ fn inject_epoch_delta(
 fn run_pipeline<B: WorkspaceBackend>(
     repo_root: &Path,
     backend: &B,
-    state: &MergeStateFile,
+    epoch_before: &EpochId,
+    sources: &[WorkspaceId],
+    commit_message: Option<&str>,
     merge_config: &MergeConfig,
 ) -> Result<BuildPhaseOutput, BuildPhaseError> {
     // Collect snapshots from all source workspaces (enriched with FileId + blob OID)
-    let mut patch_sets = collect_snapshots(repo_root, backend, &state.sources)?;
+    let mut patch_sets = collect_snapshots(repo_root, backend, sources)?;
 
     // Inject a synthetic epoch-delta PatchSet for stale workspaces.
     // If any workspace's base epoch differs from the current epoch, files that
     // changed in the epoch delta AND overlap with workspace changes must go
     // through conflict resolution instead of being silently overwritten.
-    let epoch_delta = inject_epoch_delta(repo_root, &state.epoch_before, &mut patch_sets)?;
+    let epoch_delta = inject_epoch_delta(repo_root, epoch_before, &mut patch_sets)?;
 
     // Partition changed paths into unique vs shared
     let partition = partition_by_path(&patch_sets);
@@ -651,7 +715,7 @@ fn run_pipeline<B: WorkspaceBackend>(
     let shared_count = partition.shared_count();
 
     // Read base (epoch) content for all shared paths
-    let mut base_contents = read_base_contents(repo_root, &state.epoch_before, &partition)?;
+    let mut base_contents = read_base_contents(repo_root, epoch_before, &partition)?;
 
     // Apply epoch-delta base overrides: for paths where the epoch-delta
     // synthetic PatchSet overlaps with a stale workspace, the base must be
@@ -662,23 +726,44 @@ fn run_pipeline<B: WorkspaceBackend>(
     }
 
     // Load .gitattributes at the merge base for merge-driver selection.
-    let attrs = load_attrs_at_epoch(repo_root, &state.epoch_before);
+    let attrs = load_attrs_at_epoch(repo_root, epoch_before);
 
     // Resolve shared paths via hash equality / diff3 / AST merge fallback
-    let resolve_result =
-        resolve_partition_for_build(&partition, &base_contents, merge_config, Some(&attrs))?;
+    let mut resolve_result = resolve_partition_for_build(
+        repo_root,
+        &partition,
+        &base_contents,
+        merge_config,
+        Some(&attrs),
+        &ResolutionMemory::for_repo(repo_root),
+    )?;
+    let mut auto_resolutions = std::mem::take(&mut resolve_result.auto_resolutions);
 
     // Apply deterministic merge drivers
-    let (resolved, conflicts) = apply_merge_drivers(
+    let (mut resolved, mut conflicts) = apply_merge_drivers(
         repo_root,
-        &state.epoch_before,
-        &state.sources,
+        epoch_before,
+        sources,
         &partition,
         &base_contents,
         resolve_result,
         merge_config,
     )?;
 
+    // Check migration numbering (renumbering where configured)
+    auto_resolutions.extend(apply_migration_checks(
+        repo_root,
+        epoch_before,
+        &partition,
+        &base_contents,
+        &mut resolved,
+        &mut conflicts,
+        merge_config,
+    )?);
+
+    // Cross-workspace checks that only warn
+    let warnings = detect_warnings(&partition, &resolved, &base_contents, merge_config);
+
     // Build the candidate git tree + commit from resolved changes
     let mut repo = open_gix_repo(repo_root)?;
     set_pending_attrs_from_resolved(&mut repo, &resolved);
@@ -686,11 +771,11 @@ fn run_pipeline<B: WorkspaceBackend>(
     let modes = modes_from_partition(&partition);
     let candidate = build_merge_commit(
         &repo,
-        &state.epoch_before,
-        &state.sources,
+        epoch_before,
+        sources,
         &resolved,
         &modes,
-        state.commit_message.as_deref(),
+        commit_message,
     )?;
 
     Ok(BuildPhaseOutput {
@@ -700,31 +785,98 @@ fn run_pipeline<B: WorkspaceBackend>(
         unique_count,
         shared_count,
         resolved_paths,
+        auto_resolutions,
+        warnings,
     })
 }
 
-fn resolve_partition_for_build(
+pub(crate) fn resolve_partition_for_build(
+    repo_root: &Path,
+    partition: &PartitionResult,
+    base_contents: &BTreeMap<PathBuf, Vec<u8>>,
+    merge_config: &MergeConfig,
+    attrs: Option<&maw_lfs::AttrsMatcher>,
+    memory: &ResolutionMemory,
+) -> Result<ResolveResult, BuildPhaseError> {
+    let mut result =
+        resolve_partition_uncached(repo_root, partition, base_contents, merge_config, attrs)?;
+    apply_recorded_resolutions(&mut result, partition, memory);
+    Ok(result)
+}
+
+fn resolve_partition_uncached(
+    repo_root: &Path,
     partition: &PartitionResult,
     base_contents: &BTreeMap<PathBuf, Vec<u8>>,
     merge_config: &MergeConfig,
     attrs: Option<&maw_lfs::AttrsMatcher>,
 ) -> Result<ResolveResult, BuildPhaseError> {
+    let normalizer = Normalizer::from_config(&merge_config.normalize, repo_root);
+
+    let drivers = merge_config.effective_drivers();
+    let external_drivers = ExternalDriverSet::from_drivers(&drivers);
+    let union_drivers = UnionDriverSet::from_drivers(&drivers);
+
     #[cfg(feature = "ast-merge")]
     {
         use crate::merge::resolve::resolve_partition_with_ast_and_attrs;
         let ast_config = crate::merge::ast_merge::AstMergeConfig::from_config(&merge_config.ast);
-        resolve_partition_with_ast_and_attrs(partition, base_contents, &ast_config, attrs)
-            .map_err(BuildPhaseError::from)
+        resolve_partition_with_ast_and_attrs(
+            partition,
+            base_contents,
+            &ast_config,
+            attrs,
+            &external_drivers,
+            &union_drivers,
+            &normalizer,
+        )
+        .map_err(BuildPhaseError::from)
     }
 
     #[cfg(not(feature = "ast-merge"))]
     {
         use crate::merge::resolve::resolve_partition_with_attrs;
-        let _ = merge_config;
-        resolve_partition_with_attrs(partition, base_contents, attrs).map_err(BuildPhaseError::from)
+        resolve_partition_with_attrs(
+            partition,
+            base_contents,
+            attrs,
+            &external_drivers,
+            &union_drivers,
+            &normalizer,
+        )
+        .map_err(BuildPhaseError::from)
     }
 }
 
+/// Non-blocking cross-workspace checks on the merged tree (`resolved`):
+/// currently Rust signature drift (see [`crate::merge::signature_drift`]).
+#[cfg(feature = "ast-merge")]
+fn detect_warnings(
+    partition: &PartitionResult,
+    resolved: &[ResolvedChange],
+    base_contents: &BTreeMap<PathBuf, Vec<u8>>,
+    merge_config: &MergeConfig,
+) -> Vec<MergeWarning> {
+    let ast_config = crate::merge::ast_merge::AstMergeConfig::from_config(&merge_config.ast);
+    crate::merge::signature_drift::detect_signature_drift(
+        partition,
+        resolved,
+        base_contents,
+        &ast_config,
+    )
+}
+
+/// Without AST merge there is nothing to parse, so nothing to warn about.
+#[cfg(not(feature = "ast-merge"))]
+const fn detect_warnings(
+    _partition: &PartitionResult,
+    _resolved: &[ResolvedChange],
+    _base_contents: &BTreeMap<PathBuf, Vec<u8>>,
+    _merge_config: &MergeConfig,
+) -> Vec<MergeWarning> {
+    Vec::new()
+}
+
 /// Load a `.gitattributes` matcher at the given epoch commit.
 ///
 /// The matcher reflects the `.gitattributes` state *at the merge base*, which
@@ -735,7 +887,7 @@ fn resolve_partition_for_build(
 ///
 /// Returns an empty matcher on any error — a failed attrs load should never
 /// break a merge, just degrade to the default diff3 behavior.
-fn load_attrs_at_epoch(repo_root: &Path, epoch: &EpochId) -> maw_lfs::AttrsMatcher {
+pub(crate) fn load_attrs_at_epoch(repo_root: &Path, epoch: &EpochId) -> maw_lfs::AttrsMatcher {
     let repo = match maw_git::GixRepo::open(repo_root) {
         Ok(r) => r,
         Err(e) => {
@@ -895,11 +1047,56 @@ fn maybe_apply_driver(
                 .insert(path.to_path_buf());
             remove_conflict_path(conflicts, path);
         }
+        // Already applied per shared path during RESOLVE.
+        MergeDriverKind::External
+        | MergeDriverKind::UnionSorted
+        | MergeDriverKind::UnionDedupe
+        | MergeDriverKind::Append => {}
     }
 
     Ok(())
 }
 
+/// Run the `[[merge.migrations]]` checks (see [`crate::merge::migrations`])
+/// over the driver output, against the migrations already in `epoch`.
+fn apply_migration_checks(
+    repo_root: &Path,
+    epoch: &EpochId,
+    partition: &PartitionResult,
+    base_contents: &BTreeMap<PathBuf, Vec<u8>>,
+    resolved: &mut Vec<ResolvedChange>,
+    conflicts: &mut Vec<ConflictRecord>,
+    merge_config: &MergeConfig,
+) -> Result<Vec<AutoResolution>, BuildPhaseError> {
+    let checks =
+        MigrationChecks::from_config(&merge_config.migrations).map_err(BuildPhaseError::Driver)?;
+    if checks.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mk_err = |stderr: String| {
+        BuildPhaseError::Build(BuildError::GitCommand {
+            command: format!("ls-tree -r {}", epoch.as_str()),
+            stderr,
+            exit_code: None,
+        })
+    };
+    let repo = open_gix_repo(repo_root)?;
+    let commit: maw_git::GitOid = epoch
+        .as_str()
+        .parse()
+        .map_err(|e: maw_git::OidParseError| mk_err(e.to_string()))?;
+    let base_paths: Vec<PathBuf> = repo
+        .walk_tree_blob_paths(commit)
+        .map_err(|e| mk_err(e.to_string()))?
+        .into_iter()
+        .map(|entry| PathBuf::from(entry.path))
+        .filter(|path| checks.matches(path))
+        .collect();
+
+    Ok(checks.apply(partition, &base_paths, base_contents, resolved, conflicts))
+}
+
 fn select_driver<'a>(path: &Path, compiled: &'a [CompiledDriver]) -> Option<&'a CompiledDriver> {
     compiled
         .iter()
@@ -1138,7 +1335,7 @@ fn remove_temp_worktree(repo_root: &Path, worktree_path: &Path) -> Result<(), Bu
 /// `partition.shared`, reads file content from the epoch commit via
 /// `git show <epoch>:<path>`. Paths that don't exist at the epoch are omitted
 /// from the result.
-fn read_base_contents(
+pub(crate) fn read_base_contents(
     repo_root: &Path,
     epoch: &EpochId,
     partition: &PartitionResult,
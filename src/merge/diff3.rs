@@ -0,0 +1,137 @@
+//! Two-sided diff3 merges split into common regions and conflict hunks.
+//!
+//! [`maw_git::merge::merge_text`] renders a conflicted merge as one buffer
+//! with `<<<<<<<` / `|||||||` / `=======` / `>>>>>>>` markers. Consumers that
+//! reason about individual hunks — resolution memory, the interactive
+//! resolver — need the structure back: the lines both sides agree on, and for
+//! each conflict the base, ours and theirs lines. [`diff3_segments`] runs the
+//! merge and parses its markers into that form.
+
+/// One conflict hunk: the lines each side and the base have in the region.
+///
+/// Lines keep their terminators, so concatenating them reproduces the text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diff3Hunk {
+    /// Base (common ancestor) lines.
+    pub base: Vec<String>,
+    /// "Ours" lines.
+    pub ours: Vec<String>,
+    /// "Theirs" lines.
+    pub theirs: Vec<String>,
+}
+
+/// A region of a two-sided merge.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Diff3Segment {
+    /// Lines merged cleanly (identical on both sides after the merge).
+    Common(Vec<String>),
+    /// A region both sides changed differently.
+    Conflict(Diff3Hunk),
+}
+
+const OURS_MARKER: &str = "<<<<<<< ";
+const BASE_MARKER: &str = "||||||| ";
+const SPLIT_MARKER: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>> ";
+
+fn is_marker(line: &str) -> bool {
+    line.starts_with(OURS_MARKER)
+        || line.starts_with(BASE_MARKER)
+        || line.starts_with(THEIRS_MARKER)
+        || line.trim_end() == SPLIT_MARKER
+}
+
+/// Merge `ours` and `theirs` against `base` and split the result.
+///
+/// Returns `None` when the merge is clean, or when its marker output cannot
+/// be parsed unambiguously (e.g. an input already contains marker lines).
+#[must_use]
+pub fn diff3_segments(base: &str, ours: &str, theirs: &str) -> Option<Vec<Diff3Segment>> {
+    if [base, ours, theirs]
+        .iter()
+        .any(|text| text.lines().any(is_marker))
+    {
+        return None;
+    }
+    let merged = match maw_git::merge::merge_text(
+        base.as_bytes(),
+        ours.as_bytes(),
+        theirs.as_bytes(),
+        "ours",
+        "base",
+        "theirs",
+    )
+    .ok()?
+    {
+        maw_git::merge::MergeResult::Clean(_) => return None,
+        maw_git::merge::MergeResult::Conflict(out) => String::from_utf8(out).ok()?,
+    };
+
+    let mut segments = Vec::new();
+    let mut common = Vec::new();
+    let mut lines = merged.split_inclusive('\n');
+    while let Some(line) = lines.next() {
+        if !line.starts_with(OURS_MARKER) {
+            common.push(line.to_owned());
+            continue;
+        }
+        let mut sections: [Vec<String>; 3] = Default::default();
+        let mut section = 0;
+        let mut closed = false;
+        for inner in lines.by_ref() {
+            if section == 0 && inner.starts_with(BASE_MARKER) {
+                section = 1;
+            } else if section == 1 && inner.trim_end() == SPLIT_MARKER {
+                section = 2;
+            } else if section == 2 && inner.starts_with(THEIRS_MARKER) {
+                closed = true;
+                break;
+            } else {
+                sections[section].push(inner.to_owned());
+            }
+        }
+        if !closed {
+            return None;
+        }
+        if !common.is_empty() {
+            segments.push(Diff3Segment::Common(std::mem::take(&mut common)));
+        }
+        let [ours, base, theirs] = sections;
+        segments.push(Diff3Segment::Conflict(Diff3Hunk { base, ours, theirs }));
+    }
+    if !common.is_empty() {
+        segments.push(Diff3Segment::Common(common));
+    }
+    Some(segments)
+}
+
+#[cfg(test)]
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_conflicts_between_common_regions() {
+        let base = "a\nx = 1\nb\ny = 1\nc\n";
+        let ours = "a\nx = 2\nb\ny = 2\nc\n";
+        let theirs = "a\nx = 3\nb\ny = 3\nc\n";
+        let segments = diff3_segments(base, ours, theirs).expect("conflicted merge");
+        assert_eq!(segments.len(), 5);
+        assert_eq!(segments[0], Diff3Segment::Common(vec!["a\n".to_owned()]));
+        assert_eq!(
+            segments[1],
+            Diff3Segment::Conflict(Diff3Hunk {
+                base: vec!["x = 1\n".to_owned()],
+                ours: vec!["x = 2\n".to_owned()],
+                theirs: vec!["x = 3\n".to_owned()],
+            })
+        );
+        assert_eq!(segments[4], Diff3Segment::Common(vec!["c\n".to_owned()]));
+    }
+
+    #[test]
+    fn clean_merges_and_marker_inputs_have_no_segments() {
+        assert!(diff3_segments("a\nb\n", "A\nb\n", "a\nB\n").is_none());
+        assert!(diff3_segments("a\n", "=======\n", "b\n").is_none());
+    }
+}
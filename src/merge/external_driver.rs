@@ -0,0 +1,623 @@
+//! External merge drivers (`kind = "external"` in `[[merge.drivers]]`).
+//!
+//! An external driver lets a third-party tool (e.g. mergiraf, or an
+//! in-house schema merger) take part in the deterministic merge. It runs
+//! during RESOLVE for shared paths matching its glob, in place of diff3 and
+//! the AST layer.
+//!
+//! # Protocol
+//!
+//! The driver runs once per path, with every workspace side at once, in a
+//! scratch directory containing:
+//!
+//! - `base` — the merge-base content (empty when the path is new)
+//! - `sides/<workspace>` — each workspace's content
+//! - `ours` — the first workspace's content; the driver writes its result here
+//! - `theirs` — the second workspace's content, only when exactly two
+//!   workspaces touched the path
+//! - `manifest.json` — a description of the invocation (see below)
+//!
+//! `command` runs through `sh -c` in the scratch directory, with git-style
+//! placeholders substituted (shell-quoted): `%O` base, `%A` ours, `%B`
+//! theirs, `%P` the repo-relative path, `%M` the manifest, `%%` a literal
+//! `%`. The manifest path is also exported as `MAW_MERGE_MANIFEST`.
+//!
+//! Git-style three-way tools work unchanged for two sides. With more than
+//! two, `theirs` is absent so such a tool cannot silently drop a side;
+//! N-way drivers read every side from the manifest:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "path": "db/schema.sql",
+//!   "base": "/tmp/maw-merge-driver.x/base",
+//!   "sides": [
+//!     { "workspace": "alice", "file": "/tmp/.../sides/alice" },
+//!     { "workspace": "bob", "file": "/tmp/.../sides/bob" },
+//!     { "workspace": "carol", "file": "/tmp/.../sides/carol" }
+//!   ],
+//!   "result": "/tmp/.../ours",
+//!   "conflicts": "/tmp/.../conflicts.json"
+//! }
+//! ```
+//!
+//! `base` is `null` when the path has no merge base. Exit status `0` means
+//! the merged bytes are in `result`. Exit status `1` means the sides
+//! conflict; the driver may write a JSON array of [`ConflictAtom`]s to
+//! `conflicts` to describe them. Any other exit status, or running past the
+//! driver's `timeout_seconds`, is a driver failure.
+
+use std::fmt;
+use std::fs;
+use std::io::{Read, Write as _};
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use glob::Pattern;
+use tempfile::Builder;
+
+use crate::config::{MergeDriver, MergeDriverKind};
+use crate::model::conflict::ConflictAtom;
+use crate::model::types::WorkspaceId;
+
+/// Protocol version written to the manifest.
+const PROTOCOL_VERSION: u32 = 1;
+
+// ---------------------------------------------------------------------------
+// Driver selection
+// ---------------------------------------------------------------------------
+
+/// An `external` merge driver from `[[merge.drivers]]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExternalDriver {
+    /// The driver's glob, for diagnostics.
+    pub match_glob: String,
+    /// Shell command with `%O`/`%A`/`%B`/`%P`/`%M` placeholders.
+    pub command: String,
+    /// Whether a driver failure blocks the merge (otherwise the path falls
+    /// back to the normal merge pipeline).
+    pub required: bool,
+    /// How long one invocation may run before it is killed.
+    pub timeout: Duration,
+}
+
+/// The configured merge drivers, as seen by RESOLVE.
+///
+/// Drivers are matched in order and the first match wins — an `external`
+/// driver only applies to a path when no earlier driver of another kind
+/// claims it.
+#[derive(Clone, Debug, Default)]
+pub struct ExternalDriverSet {
+    entries: Vec<(Pattern, Option<ExternalDriver>)>,
+}
+
+impl ExternalDriverSet {
+    /// Build the set from the effective `[[merge.drivers]]` list.
+    ///
+    /// Invalid globs are skipped here; the BUILD phase reports them when it
+    /// compiles the same list.
+    #[must_use]
+    pub fn from_drivers(drivers: &[MergeDriver]) -> Self {
+        let entries = drivers
+            .iter()
+            .filter_map(|driver| {
+                let pattern = Pattern::new(&driver.match_glob).ok()?;
+                let external = (driver.kind == MergeDriverKind::External).then(|| ExternalDriver {
+                    match_glob: driver.match_glob.clone(),
+                    command: driver.command.clone().unwrap_or_default(),
+                    required: driver.required,
+                    timeout: Duration::from_secs(driver.timeout_seconds.into()),
+                });
+                Some((pattern, external))
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// The external driver selected for `path`, if any.
+    #[must_use]
+    pub fn for_path(&self, path: &Path) -> Option<&ExternalDriver> {
+        self.entries
+            .iter()
+            .find(|(pattern, _)| pattern.matches_path(path))
+            .and_then(|(_, driver)| driver.as_ref())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Invocation
+// ---------------------------------------------------------------------------
+
+/// Outcome of running an external driver over a path's sides.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExternalMerge {
+    /// The driver merged every side.
+    Merged(Vec<u8>),
+    /// The driver reported a conflict, with the atoms it described (possibly
+    /// none).
+    Conflict(Vec<ConflictAtom>),
+}
+
+/// Errors from running an external merge driver.
+#[derive(Debug)]
+pub enum ExternalDriverError {
+    /// The driver has no command configured.
+    MissingCommand { match_glob: String },
+    /// Scratch-file I/O or spawning the command failed.
+    Io {
+        command: String,
+        source: std::io::Error,
+    },
+    /// The command exited with a status other than 0 or 1.
+    Failed {
+        command: String,
+        exit_code: Option<i32>,
+        stderr: String,
+    },
+    /// The command ran past the driver's timeout and was killed.
+    TimedOut { command: String, seconds: u64 },
+    /// The command's conflict output was not a JSON array of conflict atoms.
+    InvalidConflicts { command: String, detail: String },
+}
+
+impl fmt::Display for ExternalDriverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingCommand { match_glob } => {
+                write!(
+                    f,
+                    "external driver for '{match_glob}' must set a non-empty command"
+                )
+            }
+            Self::Io { command, source } => {
+                write!(f, "external driver `{command}`: {source}")
+            }
+            Self::Failed {
+                command,
+                exit_code,
+                stderr,
+            } => {
+                write!(f, "external driver `{command}` failed")?;
+                if let Some(code) = exit_code {
+                    write!(f, " (exit {code})")?;
+                }
+                if !stderr.is_empty() {
+                    write!(f, ": {stderr}")?;
+                }
+                Ok(())
+            }
+            Self::TimedOut { command, seconds } => {
+                write!(f, "external driver `{command}` timed out after {seconds}s")
+            }
+            Self::InvalidConflicts { command, detail } => {
+                write!(
+                    f,
+                    "external driver `{command}` wrote invalid conflicts: {detail}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExternalDriverError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        if let Self::Io { source, .. } = self {
+            Some(source)
+        } else {
+            None
+        }
+    }
+}
+
+/// Merge `sides` (sorted by workspace) with one run of an external driver.
+///
+/// # Errors
+///
+/// Returns an [`ExternalDriverError`] when the driver has no command, cannot
+/// be spawned, exits with a status other than 0 or 1, runs past its
+/// timeout, or writes malformed conflict atoms.
+pub fn run_external_driver(
+    driver: &ExternalDriver,
+    path: &Path,
+    base: Option<&[u8]>,
+    sides: &[(WorkspaceId, Vec<u8>)],
+) -> Result<ExternalMerge, ExternalDriverError> {
+    if driver.command.trim().is_empty() {
+        return Err(ExternalDriverError::MissingCommand {
+            match_glob: driver.match_glob.clone(),
+        });
+    }
+    let Some((_, first)) = sides.first() else {
+        return Ok(ExternalMerge::Merged(base.unwrap_or_default().to_vec()));
+    };
+    let io_err = |source| ExternalDriverError::Io {
+        command: driver.command.clone(),
+        source,
+    };
+
+    let dir = Builder::new()
+        .prefix("maw-merge-driver")
+        .tempdir()
+        .map_err(io_err)?;
+    let base_file = dir.path().join("base");
+    let sides_dir = dir.path().join("sides");
+    let ours_file = dir.path().join("ours");
+    let theirs_file = dir.path().join("theirs");
+    let manifest_file = dir.path().join("manifest.json");
+    let conflicts_file = dir.path().join("conflicts.json");
+
+    fs::write(&base_file, base.unwrap_or_default()).map_err(io_err)?;
+    fs::create_dir(&sides_dir).map_err(io_err)?;
+    let mut side_entries = Vec::with_capacity(sides.len());
+    for (ws_id, content) in sides {
+        let file = sides_dir.join(ws_id.as_str());
+        fs::write(&file, content).map_err(io_err)?;
+        side_entries.push(serde_json::json!({
+            "workspace": ws_id.as_str(),
+            "file": file.to_string_lossy(),
+        }));
+    }
+    fs::write(&ours_file, first).map_err(io_err)?;
+    if let [_, (_, theirs)] = sides {
+        fs::write(&theirs_file, theirs).map_err(io_err)?;
+    }
+
+    let manifest = serde_json::json!({
+        "version": PROTOCOL_VERSION,
+        "path": path.to_string_lossy(),
+        "base": base.map(|_| base_file.to_string_lossy().into_owned()),
+        "sides": side_entries,
+        "result": ours_file.to_string_lossy(),
+        "conflicts": conflicts_file.to_string_lossy(),
+    });
+    let manifest_json =
+        serde_json::to_vec_pretty(&manifest).map_err(|e| io_err(std::io::Error::other(e)))?;
+    fs::write(&manifest_file, manifest_json).map_err(io_err)?;
+
+    let command = expand_placeholders(
+        &driver.command,
+        &[
+            ('O', base_file.as_path()),
+            ('A', ours_file.as_path()),
+            ('B', theirs_file.as_path()),
+            ('P', path),
+            ('M', manifest_file.as_path()),
+        ],
+    );
+    let output = output_with_timeout(
+        Command::new("sh")
+            .args(["-c", &command])
+            .current_dir(dir.path())
+            .env("MAW_MERGE_MANIFEST", &manifest_file),
+        None,
+        driver.timeout,
+    )
+    .map_err(io_err)?
+    .ok_or_else(|| ExternalDriverError::TimedOut {
+        command: driver.command.clone(),
+        seconds: driver.timeout.as_secs(),
+    })?;
+
+    match output.status.code() {
+        Some(0) => Ok(ExternalMerge::Merged(fs::read(&ours_file).map_err(io_err)?)),
+        Some(1) => read_conflicts(&driver.command, &conflicts_file).map(ExternalMerge::Conflict),
+        exit_code => Err(ExternalDriverError::Failed {
+            command: driver.command.clone(),
+            exit_code,
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        }),
+    }
+}
+
+/// Read the conflict atoms a driver wrote, if it wrote any.
+fn read_conflicts(command: &str, file: &Path) -> Result<Vec<ConflictAtom>, ExternalDriverError> {
+    let bytes = match fs::read(file) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => {
+            return Err(ExternalDriverError::Io {
+                command: command.to_owned(),
+                source,
+            });
+        }
+    };
+    serde_json::from_slice(&bytes).map_err(|e| ExternalDriverError::InvalidConflicts {
+        command: command.to_owned(),
+        detail: e.to_string(),
+    })
+}
+
+/// Run `command` with `stdin` as its input, killing it once `timeout`
+/// passes. `Ok(None)` means it was killed.
+///
+/// Input is written and output drained on separate threads, so a tool that
+/// streams output before it has read all its input cannot deadlock us.
+pub(super) fn output_with_timeout(
+    command: &mut Command,
+    stdin: Option<Vec<u8>>,
+    timeout: Duration,
+) -> std::io::Result<Option<Output>> {
+    let mut child = command
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let writer = child
+        .stdin
+        .take()
+        .zip(stdin)
+        .map(|(mut pipe, input)| std::thread::spawn(move || pipe.write_all(&input)));
+    let stdout = child.stdout.take().map(drain);
+    let stderr = child.stderr.take().map(drain);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            // The readers are left to finish on their own: a grandchild may
+            // still hold the pipes open.
+            return Ok(None);
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+    if let Some(writer) = writer {
+        writer.join().unwrap_or(Ok(()))?;
+    }
+    let collect = |reader: Option<JoinHandle<std::io::Result<Vec<u8>>>>| {
+        reader.map_or(Ok(Vec::new()), |r| {
+            r.join().unwrap_or_else(|_| Ok(Vec::new()))
+        })
+    };
+    Ok(Some(Output {
+        status,
+        stdout: collect(stdout)?,
+        stderr: collect(stderr)?,
+    }))
+}
+
+/// Read `pipe` to the end on its own thread.
+fn drain<R: Read + Send + 'static>(mut pipe: R) -> JoinHandle<std::io::Result<Vec<u8>>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        pipe.read_to_end(&mut buf).map(|_| buf)
+    })
+}
+
+/// Substitute `%X` placeholders with shell-quoted paths.
+pub(super) fn expand_placeholders(command: &str, values: &[(char, &Path)]) -> String {
+    let mut out = String::with_capacity(command.len());
+    let mut chars = command.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.peek().copied() {
+            Some('%') => {
+                chars.next();
+                out.push('%');
+            }
+            Some(key) => {
+                if let Some((_, value)) = values.iter().find(|(k, _)| *k == key) {
+                    chars.next();
+                    out.push_str(&shell_quote(&value.to_string_lossy()));
+                } else {
+                    out.push('%');
+                }
+            }
+            None => out.push('%'),
+        }
+    }
+    out
+}
+
+/// Quote `value` for `sh` using single quotes.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+mod tests {
+    use super::*;
+    use crate::model::conflict::{ConflictReason, Region};
+
+    fn ws(name: &str) -> WorkspaceId {
+        WorkspaceId::new(name).expect("operation should succeed")
+    }
+
+    fn driver(command: &str) -> ExternalDriver {
+        ExternalDriver {
+            match_glob: "*.sql".to_owned(),
+            command: command.to_owned(),
+            required: true,
+            timeout: Duration::from_secs(60),
+        }
+    }
+
+    fn config_driver(glob: &str, kind: MergeDriverKind) -> MergeDriver {
+        MergeDriver {
+            match_glob: glob.to_owned(),
+            kind,
+            command: Some("true".to_owned()),
+            required: true,
+            timeout_seconds: 60,
+        }
+    }
+
+    #[test]
+    fn first_matching_driver_wins() {
+        let set = ExternalDriverSet::from_drivers(&[
+            config_driver("generated/*.sql", MergeDriverKind::Theirs),
+            config_driver("*.sql", MergeDriverKind::External),
+        ]);
+        assert!(set.for_path(Path::new("generated/a.sql")).is_none());
+        assert_eq!(
+            set.for_path(Path::new("schema.sql"))
+                .map(|d| d.match_glob.as_str()),
+            Some("*.sql")
+        );
+        assert!(set.for_path(Path::new("main.rs")).is_none());
+    }
+
+    #[test]
+    fn placeholders_are_shell_quoted() {
+        let expanded = expand_placeholders(
+            "tool %O %A %B -p %P 100%% %Z",
+            &[
+                ('O', Path::new("/tmp/base")),
+                ('A', Path::new("/tmp/ours")),
+                ('B', Path::new("/tmp/theirs")),
+                ('P', Path::new("it's.sql")),
+            ],
+        );
+        assert_eq!(
+            expanded,
+            r"tool '/tmp/base' '/tmp/ours' '/tmp/theirs' -p 'it'\''s.sql' 100% %Z"
+        );
+    }
+
+    #[test]
+    fn driver_result_is_read_from_ours() {
+        let sides = vec![(ws("alice"), b"a\n".to_vec()), (ws("bob"), b"b\n".to_vec())];
+        let merged = run_external_driver(
+            &driver("cat %B >> %A"),
+            Path::new("db/schema.sql"),
+            Some(b""),
+            &sides,
+        )
+        .expect("operation should succeed");
+        assert_eq!(merged, ExternalMerge::Merged(b"a\nb\n".to_vec()));
+    }
+
+    #[test]
+    fn every_side_is_merged_in_one_run() {
+        let sides = vec![
+            (ws("alice"), b"a\n".to_vec()),
+            (ws("bob"), b"b\n".to_vec()),
+            (ws("carol"), b"c\n".to_vec()),
+        ];
+        let merged = run_external_driver(
+            &driver("cat sides/alice sides/bob sides/carol > %A"),
+            Path::new("db/schema.sql"),
+            Some(b""),
+            &sides,
+        )
+        .expect("operation should succeed");
+        assert_eq!(merged, ExternalMerge::Merged(b"a\nb\nc\n".to_vec()));
+    }
+
+    #[test]
+    fn three_way_tool_cannot_drop_a_side() {
+        let sides = vec![
+            (ws("alice"), b"a\n".to_vec()),
+            (ws("bob"), b"b\n".to_vec()),
+            (ws("carol"), b"c\n".to_vec()),
+        ];
+        let result = run_external_driver(
+            &driver("cat %B >> %A"),
+            Path::new("db/schema.sql"),
+            Some(b""),
+            &sides,
+        );
+        assert!(
+            !matches!(result, Ok(ExternalMerge::Merged(_))),
+            "got {result:?}"
+        );
+    }
+
+    #[test]
+    fn manifest_describes_sides() {
+        let sides = vec![(ws("alice"), b"a".to_vec()), (ws("bob"), b"b".to_vec())];
+        let merged = run_external_driver(
+            &driver("cp \"$MAW_MERGE_MANIFEST\" %A"),
+            Path::new("db/schema.sql"),
+            None,
+            &sides,
+        )
+        .expect("operation should succeed");
+        let ExternalMerge::Merged(bytes) = merged else {
+            panic!("expected merged output, got {merged:?}");
+        };
+        let manifest: serde_json::Value =
+            serde_json::from_slice(&bytes).expect("manifest should be JSON");
+        assert_eq!(manifest["version"], 1);
+        assert_eq!(manifest["path"], "db/schema.sql");
+        assert!(manifest["base"].is_null());
+        let sides = manifest["sides"]
+            .as_array()
+            .expect("sides should be a list");
+        let workspaces: Vec<_> = sides.iter().map(|side| &side["workspace"]).collect();
+        assert_eq!(workspaces, ["alice", "bob"]);
+        assert!(
+            sides[1]["file"]
+                .as_str()
+                .is_some_and(|f| f.ends_with("sides/bob"))
+        );
+        assert!(
+            manifest["result"]
+                .as_str()
+                .is_some_and(|f| f.ends_with("ours"))
+        );
+    }
+
+    #[test]
+    fn exit_one_returns_conflict_atoms() {
+        let atom = ConflictAtom::new(
+            Region::lines(1, 2),
+            vec![],
+            ConflictReason::same_ast_node("table users"),
+        );
+        let atoms_json = serde_json::to_string(&vec![atom.clone()]).expect("serialize");
+        let command = format!(
+            "printf '%s' {} > conflicts.json; exit 1",
+            shell_quote(&atoms_json)
+        )
+        .replace("%s", "%%s");
+        let sides = vec![(ws("alice"), b"a".to_vec()), (ws("bob"), b"b".to_vec())];
+        let result = run_external_driver(&driver(&command), Path::new("s.sql"), Some(b""), &sides)
+            .expect("operation should succeed");
+        assert_eq!(result, ExternalMerge::Conflict(vec![atom]));
+    }
+
+    #[test]
+    fn other_exit_codes_are_failures() {
+        let sides = vec![(ws("alice"), b"a".to_vec()), (ws("bob"), b"b".to_vec())];
+        let err = run_external_driver(
+            &driver("echo broken >&2; exit 3"),
+            Path::new("s.sql"),
+            None,
+            &sides,
+        )
+        .expect_err("driver should fail");
+        assert!(
+            matches!(&err, ExternalDriverError::Failed { exit_code: Some(3), stderr, .. } if stderr == "broken"),
+            "got {err:?}"
+        );
+    }
+
+    #[test]
+    fn slow_driver_times_out() {
+        let sides = vec![(ws("alice"), b"a".to_vec()), (ws("bob"), b"b".to_vec())];
+        let mut slow = driver("sleep 30");
+        slow.timeout = Duration::from_secs(1);
+        let started = Instant::now();
+        let err = run_external_driver(&slow, Path::new("s.sql"), None, &sides)
+            .expect_err("driver should time out");
+        assert!(
+            matches!(err, ExternalDriverError::TimedOut { seconds: 1, .. }),
+            "got {err:?}"
+        );
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+}
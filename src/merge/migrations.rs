@@ -0,0 +1,639 @@
+//! Migration numbering checks (`[[merge.migrations]]`).
+//!
+//! Numbered migrations form a chain, but the chain is invisible to a
+//! path-level merge: two workspaces that each add `migrations/0042_*.sql`
+//! touch different paths, so both are accepted and the result has two
+//! migrations numbered 42. After RESOLVE and the merge drivers, BUILD runs
+//! every configured check over the migrations the workspaces added:
+//!
+//! - An added migration whose sequence number is already taken — in the
+//!   epoch or by a workspace earlier in workspace order — becomes a
+//!   [`ConflictReason::MigrationOrder`] conflict.
+//! - A configured `latest` file (e.g. Django's `max_migration.txt`) that the
+//!   workspaces changed to different contents becomes a
+//!   [`ConflictReason::MigrationOrder`] conflict rather than a line conflict.
+//!
+//! With `renumber = true` the check acts as a driver instead: every migration
+//! the later workspace added is renumbered, in order, past the highest taken
+//! number, and references to the old names in those migrations and in the
+//! `latest` file are rewritten. Workspaces are processed in workspace-id
+//! order, so the result does not depend on the order sources were given.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use glob::Pattern;
+
+use crate::config::MigrationConfig;
+use crate::model::types::WorkspaceId;
+
+use super::build::ResolvedChange;
+use super::partition::{PartitionResult, PathEntry};
+use super::resolve::{
+    AutoResolution, AutoResolutionKind, ConflictReason, ConflictRecord, ConflictSide,
+};
+use super::types::ChangeKind;
+
+/// The compiled `[[merge.migrations]]` checks.
+#[derive(Clone, Debug, Default)]
+pub struct MigrationChecks {
+    checks: Vec<MigrationCheck>,
+}
+
+#[derive(Clone, Debug)]
+struct MigrationCheck {
+    pattern: Pattern,
+    numbering: Numbering,
+    latest: Option<PathBuf>,
+    renumber: bool,
+}
+
+/// A `numbering` file-name pattern, split around `{n}`.
+#[derive(Clone, Debug)]
+struct Numbering {
+    prefix: String,
+    rest: Pattern,
+}
+
+/// A sequence number as parsed from a migration file name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Sequence {
+    value: u64,
+    digits: String,
+}
+
+/// Old and new file stems of a workspace's renumbered migrations.
+type Renames = [(String, String)];
+
+/// A migration added by one workspace.
+struct Added<'a> {
+    sequence: Sequence,
+    path: &'a Path,
+    entry: &'a PathEntry,
+}
+
+/// Mutable merge state the checks rewrite.
+struct Outcome<'a> {
+    resolved: &'a mut Vec<ResolvedChange>,
+    conflicts: &'a mut Vec<ConflictRecord>,
+    auto_resolutions: Vec<AutoResolution>,
+}
+
+impl MigrationChecks {
+    /// Compile the `[[merge.migrations]]` entries.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first entry with an invalid glob or a
+    /// `numbering` pattern without exactly one `{n}`.
+    pub fn from_config(configs: &[MigrationConfig]) -> Result<Self, String> {
+        let checks = configs
+            .iter()
+            .map(|config| {
+                let pattern = Pattern::new(&config.match_glob)
+                    .map_err(|e| format!("invalid migration glob '{}': {e}", config.match_glob))?;
+                Ok(MigrationCheck {
+                    pattern,
+                    numbering: Numbering::parse(&config.numbering)?,
+                    latest: config.latest.as_ref().map(PathBuf::from),
+                    renumber: config.renumber,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self { checks })
+    }
+
+    /// Returns `true` if no checks are configured.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+
+    /// Whether `path` is a migration under any check.
+    #[must_use]
+    pub fn matches(&self, path: &Path) -> bool {
+        self.checks
+            .iter()
+            .any(|check| check.sequence(path).is_some())
+    }
+
+    /// Run every check over the merge result.
+    ///
+    /// `base_paths` lists the epoch's paths (only those [`Self::matches`]
+    /// accepts are needed); `base_contents` supplies the base of the
+    /// `latest` files. Colliding migrations and divergent `latest` files are
+    /// moved from `resolved` to `conflicts`, or renumbered when the check
+    /// allows it. Returns the renumberings performed.
+    pub fn apply(
+        &self,
+        partition: &PartitionResult,
+        base_paths: &[PathBuf],
+        base_contents: &BTreeMap<PathBuf, Vec<u8>>,
+        resolved: &mut Vec<ResolvedChange>,
+        conflicts: &mut Vec<ConflictRecord>,
+    ) -> Vec<AutoResolution> {
+        let mut outcome = Outcome {
+            resolved,
+            conflicts,
+            auto_resolutions: Vec::new(),
+        };
+        for check in &self.checks {
+            check.apply(partition, base_paths, base_contents, &mut outcome);
+        }
+        outcome.resolved.sort_by(|a, b| a.path().cmp(b.path()));
+        outcome.conflicts.sort_by(|a, b| a.path.cmp(&b.path));
+        outcome.auto_resolutions
+    }
+}
+
+impl MigrationCheck {
+    fn sequence(&self, path: &Path) -> Option<Sequence> {
+        if !self.pattern.matches_path(path) {
+            return None;
+        }
+        self.numbering.sequence(path.file_name()?.to_str()?)
+    }
+
+    fn apply(
+        &self,
+        partition: &PartitionResult,
+        base_paths: &[PathBuf],
+        base_contents: &BTreeMap<PathBuf, Vec<u8>>,
+        outcome: &mut Outcome<'_>,
+    ) {
+        // Sequence numbers already taken, and by which migrations.
+        let mut taken: BTreeMap<u64, Vec<PathBuf>> = BTreeMap::new();
+        for path in base_paths {
+            if let Some(sequence) = self.sequence(path) {
+                taken.entry(sequence.value).or_default().push(path.clone());
+            }
+        }
+
+        let mut added: BTreeMap<&WorkspaceId, Vec<Added<'_>>> = BTreeMap::new();
+        for (path, entry) in &partition.unique {
+            if entry.kind != ChangeKind::Added {
+                continue;
+            }
+            if let Some(sequence) = self.sequence(path) {
+                added.entry(&entry.workspace_id).or_default().push(Added {
+                    sequence,
+                    path,
+                    entry,
+                });
+            }
+        }
+
+        let mut renames: BTreeMap<&WorkspaceId, Vec<(String, String)>> = BTreeMap::new();
+        let mut newest: Option<(u64, &WorkspaceId)> = None;
+        for (workspace, mut migrations) in added {
+            migrations.sort_by(|a, b| (a.sequence.value, a.path).cmp(&(b.sequence.value, b.path)));
+            let collisions: Vec<(&Added<'_>, Vec<PathBuf>)> = migrations
+                .iter()
+                .filter_map(|m| Some((m, taken.get(&m.sequence.value)?.clone())))
+                .collect();
+
+            if collisions.is_empty() || !self.renumber {
+                for (migration, taken_by) in collisions {
+                    outcome.conflict(migration, taken_by);
+                }
+                for migration in &migrations {
+                    taken
+                        .entry(migration.sequence.value)
+                        .or_default()
+                        .push(migration.path.to_path_buf());
+                    if newest.is_none_or(|(value, _)| migration.sequence.value >= value) {
+                        newest = Some((migration.sequence.value, workspace));
+                    }
+                }
+                continue;
+            }
+
+            // Renumber all of this workspace's migrations, keeping their
+            // order, after everything taken so far.
+            let first = taken.keys().next_back().map_or(1, |max| max + 1);
+            let mut moves = Vec::with_capacity(migrations.len());
+            for (value, migration) in (first..).zip(&migrations) {
+                let new_path = self
+                    .numbering
+                    .renumber(migration.path, &migration.sequence, value);
+                taken.entry(value).or_default().push(new_path.clone());
+                newest = Some((value, workspace));
+                moves.push((migration, new_path));
+            }
+            let stems: Vec<(String, String)> = moves
+                .iter()
+                .filter_map(|(migration, new_path)| Some((stem(migration.path)?, stem(new_path)?)))
+                .collect();
+            for (migration, new_path) in moves {
+                outcome.renumber(migration, new_path, &stems);
+            }
+            renames.insert(workspace, stems);
+        }
+
+        if let Some(latest) = &self.latest {
+            let pick = newest.filter(|_| self.renumber).map(|(_, workspace)| {
+                let stems = renames.get(workspace).map_or(&[][..], Vec::as_slice);
+                (workspace, stems)
+            });
+            outcome.latest(partition, latest, base_contents.get(latest), pick);
+        }
+    }
+}
+
+impl Outcome<'_> {
+    fn remove(&mut self, path: &Path) {
+        self.resolved.retain(|change| change.path() != path);
+        self.conflicts.retain(|conflict| conflict.path != path);
+    }
+
+    fn conflict(&mut self, migration: &Added<'_>, taken_by: Vec<PathBuf>) {
+        self.remove(migration.path);
+        self.conflicts.push(ConflictRecord {
+            path: migration.path.to_path_buf(),
+            base: None,
+            sides: vec![side(migration.entry)],
+            reason: ConflictReason::MigrationOrder {
+                sequence: Some(migration.sequence.digits.clone()),
+                taken_by,
+            },
+            atoms: vec![],
+        });
+    }
+
+    fn renumber(&mut self, migration: &Added<'_>, new_path: PathBuf, stems: &Renames) {
+        let Some(content) = &migration.entry.content else {
+            return;
+        };
+        self.remove(migration.path);
+        self.auto_resolutions.push(AutoResolution {
+            path: migration.path.to_path_buf(),
+            kind: AutoResolutionKind::MigrationRenumber,
+            workspaces: vec![migration.entry.workspace_id.clone()],
+            details: vec![format!("renumbered to {}", new_path.display())],
+        });
+        self.resolved.push(ResolvedChange::Upsert {
+            path: new_path,
+            content: rewrite_references(content, stems),
+        });
+    }
+
+    /// Handle a `latest` file the workspaces changed to different contents.
+    ///
+    /// With `pick`, the file is taken from the workspace owning the newest
+    /// migration (with its renames applied); otherwise it conflicts.
+    fn latest(
+        &mut self,
+        partition: &PartitionResult,
+        latest: &Path,
+        base: Option<&Vec<u8>>,
+        pick: Option<(&WorkspaceId, &Renames)>,
+    ) {
+        let Some((_, entries)) = partition.shared.iter().find(|(path, _)| path == latest) else {
+            return;
+        };
+        if entries
+            .windows(2)
+            .all(|pair| pair[0].content == pair[1].content)
+        {
+            return;
+        }
+        self.remove(latest);
+
+        let picked = pick.and_then(|(workspace, stems)| {
+            let entry = entries.iter().find(|e| &e.workspace_id == workspace)?;
+            Some((entry, stems))
+        });
+        if let Some((entry, stems)) = picked
+            && let Some(content) = &entry.content
+        {
+            self.auto_resolutions.push(AutoResolution {
+                path: latest.to_path_buf(),
+                kind: AutoResolutionKind::MigrationRenumber,
+                workspaces: entries.iter().map(|e| e.workspace_id.clone()).collect(),
+                details: vec![format!(
+                    "took {}'s latest migration",
+                    entry.workspace_id.as_str()
+                )],
+            });
+            self.resolved.push(ResolvedChange::Upsert {
+                path: latest.to_path_buf(),
+                content: rewrite_references(content, stems),
+            });
+            return;
+        }
+
+        self.conflicts.push(ConflictRecord {
+            path: latest.to_path_buf(),
+            base: base.cloned(),
+            sides: entries.iter().map(side).collect(),
+            reason: ConflictReason::MigrationOrder {
+                sequence: None,
+                taken_by: vec![],
+            },
+            atoms: vec![],
+        });
+    }
+}
+
+impl Numbering {
+    fn parse(numbering: &str) -> Result<Self, String> {
+        let Some((prefix, rest)) = numbering.split_once("{n}") else {
+            return Err(format!("migration numbering '{numbering}' has no {{n}}"));
+        };
+        if prefix.contains(['*', '?', '[']) || rest.contains("{n}") {
+            return Err(format!(
+                "migration numbering '{numbering}' must be a literal prefix, one {{n}}, then a glob"
+            ));
+        }
+        let rest = Pattern::new(rest)
+            .map_err(|e| format!("invalid migration numbering '{numbering}': {e}"))?;
+        Ok(Self {
+            prefix: prefix.to_owned(),
+            rest,
+        })
+    }
+
+    fn sequence(&self, file_name: &str) -> Option<Sequence> {
+        let tail = file_name.strip_prefix(&self.prefix)?;
+        let len = tail.bytes().take_while(u8::is_ascii_digit).count();
+        if len == 0 || !self.rest.matches(&tail[len..]) {
+            return None;
+        }
+        Some(Sequence {
+            value: tail[..len].parse().ok()?,
+            digits: tail[..len].to_owned(),
+        })
+    }
+
+    /// `path` with its sequence number replaced by `value`, zero-padded to
+    /// the original width.
+    fn renumber(&self, path: &Path, sequence: &Sequence, value: u64) -> PathBuf {
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        let rest = &file_name[self.prefix.len() + sequence.digits.len()..];
+        let width = sequence.digits.len();
+        path.with_file_name(format!("{}{value:0width$}{rest}", self.prefix))
+    }
+}
+
+fn side(entry: &PathEntry) -> ConflictSide {
+    ConflictSide {
+        workspace_id: entry.workspace_id.clone(),
+        kind: entry.kind.clone(),
+        content: entry.content.clone(),
+    }
+}
+
+fn stem(path: &Path) -> Option<String> {
+    path.file_stem()?.to_str().map(str::to_owned)
+}
+
+/// Replace whole-word occurrences of each old migration name in `content`
+/// with its new name, in a single pass (so `0042_a -> 0043_a` and
+/// `0043_a -> 0044_a` do not chain). Non-UTF-8 content is left as is.
+fn rewrite_references(content: &[u8], stems: &Renames) -> Vec<u8> {
+    let Ok(text) = std::str::from_utf8(content) else {
+        return content.to_vec();
+    };
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut out = String::with_capacity(text.len());
+    let mut at = 0;
+    while let Some(c) = text[at..].chars().next() {
+        let starts_word = !text[..at].chars().next_back().is_some_and(is_word);
+        let renamed = stems.iter().find(|(old, _)| {
+            starts_word
+                && text[at..].starts_with(old.as_str())
+                && !text[at + old.len()..].chars().next().is_some_and(is_word)
+        });
+        if let Some((old, new)) = renamed {
+            out.push_str(new);
+            at += old.len();
+        } else {
+            out.push(c);
+            at += c.len_utf8();
+        }
+    }
+    out.into_bytes()
+}
+
+#[cfg(test)]
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+mod tests {
+    use super::*;
+
+    fn ws(name: &str) -> WorkspaceId {
+        WorkspaceId::new(name).expect("operation should succeed")
+    }
+
+    fn added(name: &str, content: &str) -> PathEntry {
+        PathEntry::new(
+            ws(name),
+            ChangeKind::Added,
+            Some(content.as_bytes().to_vec()),
+        )
+    }
+
+    fn checks(renumber: bool) -> MigrationChecks {
+        MigrationChecks::from_config(&[MigrationConfig {
+            match_glob: "migrations/*.py".into(),
+            numbering: "{n}_*".into(),
+            latest: Some("migrations/max_migration.txt".into()),
+            renumber,
+        }])
+        .unwrap()
+    }
+
+    fn upserts(resolved: &[ResolvedChange]) -> Vec<(String, String)> {
+        resolved
+            .iter()
+            .filter_map(|change| match change {
+                ResolvedChange::Upsert { path, content } => Some((
+                    path.display().to_string(),
+                    String::from_utf8(content.clone()).unwrap(),
+                )),
+                ResolvedChange::Delete { .. } => None,
+            })
+            .collect()
+    }
+
+    /// alice and bob both add migration 0002 on top of 0001; bob's second
+    /// migration depends on his first.
+    fn colliding() -> (PartitionResult, Vec<ResolvedChange>) {
+        let unique = vec![
+            (
+                PathBuf::from("migrations/0002_alice.py"),
+                added("alice", "deps = ['0001_init']\n"),
+            ),
+            (
+                PathBuf::from("migrations/0002_bob.py"),
+                added("bob", "deps = ['0001_init']\n"),
+            ),
+            (
+                PathBuf::from("migrations/0003_bob_more.py"),
+                added("bob", "deps = ['0002_bob']\n"),
+            ),
+        ];
+        let resolved = unique
+            .iter()
+            .map(|(path, entry)| ResolvedChange::Upsert {
+                path: path.clone(),
+                content: entry.content.clone().unwrap(),
+            })
+            .collect();
+        let partition = PartitionResult {
+            unique,
+            shared: vec![(
+                PathBuf::from("migrations/max_migration.txt"),
+                vec![
+                    PathEntry::new(
+                        ws("alice"),
+                        ChangeKind::Modified,
+                        Some(b"0002_alice\n".to_vec()),
+                    ),
+                    PathEntry::new(
+                        ws("bob"),
+                        ChangeKind::Modified,
+                        Some(b"0003_bob_more\n".to_vec()),
+                    ),
+                ],
+            )],
+            df_clashes: vec![],
+        };
+        (partition, resolved)
+    }
+
+    #[test]
+    fn numbering_patterns() {
+        let numbering = Numbering::parse("V{n}__*.sql").unwrap();
+        let seq = numbering.sequence("V012__add_users.sql").unwrap();
+        assert_eq!((seq.value, seq.digits.as_str()), (12, "012"));
+        assert!(numbering.sequence("V012_add_users.sql").is_none());
+        assert!(numbering.sequence("Vx__add_users.sql").is_none());
+        assert_eq!(
+            numbering.renumber(Path::new("db/V012__add_users.sql"), &seq, 7),
+            PathBuf::from("db/V007__add_users.sql")
+        );
+        assert!(Numbering::parse("*_{n}.sql").is_err());
+        assert!(Numbering::parse("no_number.sql").is_err());
+    }
+
+    #[test]
+    fn colliding_numbers_conflict_on_the_later_workspace() {
+        let (partition, mut resolved) = colliding();
+        let mut conflicts = vec![ConflictRecord {
+            path: PathBuf::from("migrations/max_migration.txt"),
+            base: Some(b"0001_init\n".to_vec()),
+            sides: vec![],
+            reason: ConflictReason::Diff3Conflict,
+            atoms: vec![],
+        }];
+        let base = [PathBuf::from("migrations/0001_init.py")];
+
+        let renumbered = checks(false).apply(
+            &partition,
+            &base,
+            &BTreeMap::new(),
+            &mut resolved,
+            &mut conflicts,
+        );
+        assert!(renumbered.is_empty());
+
+        let paths: Vec<_> = conflicts
+            .iter()
+            .map(|c| c.path.display().to_string())
+            .collect();
+        assert_eq!(
+            paths,
+            ["migrations/0002_bob.py", "migrations/max_migration.txt"]
+        );
+        assert_eq!(
+            conflicts[0].reason,
+            ConflictReason::MigrationOrder {
+                sequence: Some("0002".into()),
+                taken_by: vec![PathBuf::from("migrations/0002_alice.py")],
+            }
+        );
+        assert_eq!(conflicts[1].sides.len(), 2);
+        assert!(matches!(
+            conflicts[1].reason,
+            ConflictReason::MigrationOrder { sequence: None, .. }
+        ));
+        // bob's 0003 does not collide and still merges.
+        assert_eq!(resolved.len(), 2);
+    }
+
+    #[test]
+    fn renumber_moves_the_later_workspace_past_the_newest() {
+        let (partition, mut resolved) = colliding();
+        let mut conflicts = vec![];
+        let base = [PathBuf::from("migrations/0001_init.py")];
+
+        let renumbered = checks(true).apply(
+            &partition,
+            &base,
+            &BTreeMap::new(),
+            &mut resolved,
+            &mut conflicts,
+        );
+        assert!(conflicts.is_empty(), "{conflicts:?}");
+        assert_eq!(renumbered.len(), 3);
+        assert_eq!(
+            upserts(&resolved),
+            [
+                (
+                    "migrations/0002_alice.py".into(),
+                    "deps = ['0001_init']\n".into()
+                ),
+                (
+                    "migrations/0003_bob.py".into(),
+                    "deps = ['0001_init']\n".into()
+                ),
+                (
+                    "migrations/0004_bob_more.py".into(),
+                    "deps = ['0003_bob']\n".into()
+                ),
+                (
+                    "migrations/max_migration.txt".into(),
+                    "0004_bob_more\n".into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn collision_with_an_epoch_migration_is_detected() {
+        let unique = vec![(PathBuf::from("migrations/0002_bob.py"), added("bob", "x\n"))];
+        let partition = PartitionResult {
+            unique,
+            shared: vec![],
+            df_clashes: vec![],
+        };
+        let mut resolved = vec![];
+        let mut conflicts = vec![];
+        let base = [
+            PathBuf::from("migrations/0001_init.py"),
+            PathBuf::from("migrations/0002_alice.py"),
+        ];
+        checks(false).apply(
+            &partition,
+            &base,
+            &BTreeMap::new(),
+            &mut resolved,
+            &mut conflicts,
+        );
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].path, PathBuf::from("migrations/0002_bob.py"));
+    }
+
+    #[test]
+    fn references_are_rewritten_whole_word_and_once() {
+        let stems = vec![
+            ("0002_a".to_owned(), "0003_a".to_owned()),
+            ("0003_a".to_owned(), "0004_a".to_owned()),
+        ];
+        let out = rewrite_references(b"0002_a 0003_a 0002_ab x0002_a", &stems);
+        assert_eq!(out, b"0003_a 0004_a 0002_ab x0002_a");
+    }
+}
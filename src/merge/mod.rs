@@ -30,15 +30,33 @@ pub use maw_core::merge::types;
 // --- Modules that remain here (have cross-deps on backend/config/refs/merge_state/ast-merge) ---
 #[cfg(feature = "ast-merge")]
 pub mod ast_merge;
+pub mod auto_try;
+pub mod binary;
 pub mod build_phase;
 pub mod collect;
 pub mod commit;
+pub mod diff3;
 pub mod events;
+pub mod external_driver;
 pub mod last_conflict;
+pub mod migrations;
+pub mod normalize;
 pub mod prepare;
+pub mod provenance;
 pub mod quarantine;
+pub mod queue;
+pub mod rerere;
 pub mod resolve;
+pub mod schedule;
+#[cfg(feature = "ast-merge")]
+pub mod signature_drift;
+#[cfg(feature = "ast-merge")]
+pub mod structured_merge;
+#[cfg(feature = "ast-merge")]
+pub mod symbol_move;
+pub mod union_driver;
 pub mod validate;
+pub mod weather;
 
 #[allow(unused_imports)]
 pub use build_phase::run_build_phase_with_inputs;
@@ -0,0 +1,422 @@
+//! Whitespace, line-ending and formatter normalization for RESOLVE.
+//!
+//! Two agents making the same edit with different editors or formatters
+//! produce sides that differ only in trailing whitespace, CRLF vs LF, or
+//! formatter reflow — and diff3 reports those as conflicts. When
+//! `[merge.normalize]` enables it and the normal merge of a shared path
+//! conflicts, every side and the base are normalized and compared again:
+//!
+//! 1. **Line endings** — CRLF and LF are both read as LF, and the result is
+//!    written back in the base's style.
+//! 2. **Whitespace** — trailing spaces and tabs are stripped from every line.
+//! 3. **Formatter** — the first `[[merge.normalize.formatters]]` entry whose
+//!    glob matches the path formats every side (stdin → stdout).
+//!
+//! Sides that agree once normalized resolve to one side as written; sides
+//! that still differ are merged in normalized form. A formatter runs in the
+//! repo root and is killed after `formatter_timeout_seconds`. One that fails
+//! or times out on any side is skipped for the whole path, so sides are
+//! never compared across different normalizations. Binary content
+//! (containing NUL bytes) is left untouched.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use glob::Pattern;
+
+use super::external_driver::{expand_placeholders, output_with_timeout};
+use crate::config::NormalizeConfig;
+
+/// Normalization settings compiled for RESOLVE.
+#[derive(Clone, Debug, Default)]
+pub struct Normalizer {
+    line_endings: bool,
+    whitespace: bool,
+    formatters: Vec<(Pattern, String)>,
+    /// Where formatters run: the repo root.
+    root: PathBuf,
+    formatter_timeout: Duration,
+}
+
+/// Sides of a shared path after normalization.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Normalized {
+    /// The normalized base, if the path has one.
+    pub base: Option<Vec<u8>>,
+    /// The normalized sides, in input order.
+    pub variants: Vec<Vec<u8>>,
+    /// Indexes of the sides normalization changed.
+    pub changed: Vec<usize>,
+    /// The steps that changed at least one side or the base, e.g.
+    /// `"line endings"` or `"formatter `rustfmt`"`.
+    pub steps: Vec<String>,
+}
+
+impl Normalizer {
+    /// Compile `[merge.normalize]` for the repo at `root`. Formatters with
+    /// invalid globs are skipped.
+    #[must_use]
+    pub fn from_config(config: &NormalizeConfig, root: &Path) -> Self {
+        let formatters = config
+            .formatters
+            .iter()
+            .filter_map(|formatter| {
+                let pattern = Pattern::new(&formatter.match_glob).ok();
+                if pattern.is_none() {
+                    tracing::warn!(
+                        "ignoring merge formatter with invalid glob '{}'",
+                        formatter.match_glob
+                    );
+                }
+                Some((pattern?, formatter.command.clone()))
+            })
+            .collect();
+        Self {
+            line_endings: config.line_endings,
+            whitespace: config.whitespace,
+            formatters,
+            root: root.to_path_buf(),
+            formatter_timeout: Duration::from_secs(config.formatter_timeout_seconds.into()),
+        }
+    }
+
+    /// Normalize `base` and `variants` for `path`.
+    ///
+    /// Returns `None` when nothing is configured for the path, the content is
+    /// binary, or no step changed anything.
+    #[must_use]
+    pub fn normalize(
+        &self,
+        path: &Path,
+        base: Option<&[u8]>,
+        variants: &[Vec<u8>],
+    ) -> Option<Normalized> {
+        let formatter = self
+            .formatters
+            .iter()
+            .find(|(pattern, _)| pattern.matches_path(path))
+            .map(|(_, command)| command);
+        if !self.line_endings && !self.whitespace && formatter.is_none() {
+            return None;
+        }
+        if base
+            .into_iter()
+            .chain(variants.iter().map(Vec::as_slice))
+            .any(is_binary)
+        {
+            return None;
+        }
+
+        // Base first, then the sides.
+        let originals: Vec<&[u8]> = base
+            .into_iter()
+            .chain(variants.iter().map(Vec::as_slice))
+            .collect();
+        let mut contents: Vec<Vec<u8>> = originals.iter().map(|c| c.to_vec()).collect();
+        let mut steps = Vec::new();
+
+        let crlf = if self.line_endings {
+            let crlf = originals.first().is_some_and(|c| uses_crlf(c));
+            apply_step(&mut contents, &mut steps, "line endings", |c| {
+                Some(to_lf(c))
+            });
+            crlf
+        } else {
+            false
+        };
+        if self.whitespace {
+            apply_step(&mut contents, &mut steps, "trailing whitespace", |c| {
+                Some(strip_trailing_whitespace(c))
+            });
+        }
+        if let Some(command) = formatter {
+            let step = format!("formatter `{command}`");
+            apply_step(&mut contents, &mut steps, &step, |c| {
+                self.run_formatter(command, path, c)
+            });
+        }
+        if crlf {
+            for content in &mut contents {
+                *content = to_crlf(content);
+            }
+        }
+
+        let changed: Vec<usize> = contents
+            .iter()
+            .zip(&originals)
+            .skip(usize::from(base.is_some()))
+            .enumerate()
+            .filter(|(_, (content, original))| content.as_slice() != **original)
+            .map(|(index, _)| index)
+            .collect();
+        let base_changed = base.is_some_and(|b| contents[0] != b);
+        if changed.is_empty() && !base_changed {
+            return None;
+        }
+
+        let base = base.map(|_| contents.remove(0));
+        Some(Normalized {
+            base,
+            variants: contents,
+            changed,
+            steps,
+        })
+    }
+}
+
+impl Normalizer {
+    /// Run a formatter over `content` in the repo root; `None` (with a
+    /// warning) if it fails or times out.
+    fn run_formatter(&self, command: &str, path: &Path, content: &[u8]) -> Option<Vec<u8>> {
+        let script = expand_placeholders(command, &[('P', path)]);
+        let output = output_with_timeout(
+            Command::new("sh")
+                .arg("-c")
+                .arg(&script)
+                .current_dir(&self.root),
+            Some(content.to_vec()),
+            self.formatter_timeout,
+        );
+        match output {
+            Ok(Some(output)) if output.status.success() => Some(output.stdout),
+            Ok(Some(output)) => {
+                tracing::warn!(
+                    "merge formatter `{command}` failed on '{}' ({}): {}",
+                    path.display(),
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+                None
+            }
+            Ok(None) => {
+                tracing::warn!(
+                    "merge formatter `{command}` timed out on '{}' after {}s",
+                    path.display(),
+                    self.formatter_timeout.as_secs()
+                );
+                None
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "merge formatter `{command}` could not run on '{}': {e}",
+                    path.display()
+                );
+                None
+            }
+        }
+    }
+}
+
+/// Apply `step` to every content, all-or-nothing, and record it if it
+/// changed anything.
+fn apply_step(
+    contents: &mut [Vec<u8>],
+    steps: &mut Vec<String>,
+    name: &str,
+    step: impl Fn(&[u8]) -> Option<Vec<u8>>,
+) {
+    let Some(outputs) = contents.iter().map(|c| step(c)).collect::<Option<Vec<_>>>() else {
+        return;
+    };
+    if outputs
+        .iter()
+        .zip(contents.iter())
+        .any(|(new, old)| new != old)
+    {
+        steps.push(name.to_owned());
+        for (content, output) in contents.iter_mut().zip(outputs) {
+            *content = output;
+        }
+    }
+}
+
+fn is_binary(content: &[u8]) -> bool {
+    content.contains(&0)
+}
+
+fn uses_crlf(content: &[u8]) -> bool {
+    content.windows(2).any(|pair| pair == b"\r\n")
+}
+
+fn to_lf(content: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(content.len());
+    let mut bytes = content.iter().peekable();
+    while let Some(&byte) = bytes.next() {
+        if byte == b'\r' && bytes.peek() == Some(&&b'\n') {
+            continue;
+        }
+        out.push(byte);
+    }
+    out
+}
+
+fn to_crlf(content: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(content.len() + content.len() / 32);
+    for &byte in content {
+        if byte == b'\n' {
+            out.push(b'\r');
+        }
+        out.push(byte);
+    }
+    out
+}
+
+/// Strip spaces and tabs before each line ending, keeping a `\r` that is
+/// part of a CRLF.
+fn strip_trailing_whitespace(content: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(content.len());
+    for line in content.split_inclusive(|&b| b == b'\n') {
+        let eol_len = if line.ends_with(b"\r\n") {
+            2
+        } else {
+            usize::from(line.ends_with(b"\n"))
+        };
+        let (body, eol) = line.split_at(line.len() - eol_len);
+        let end = body
+            .iter()
+            .rposition(|&b| b != b' ' && b != b'\t')
+            .map_or(0, |i| i + 1);
+        out.extend_from_slice(&body[..end]);
+        out.extend_from_slice(eol);
+    }
+    out
+}
+
+#[cfg(test)]
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+mod tests {
+    use super::*;
+    use crate::config::FormatterConfig;
+
+    fn normalizer(line_endings: bool, whitespace: bool, formatters: &[(&str, &str)]) -> Normalizer {
+        Normalizer::from_config(
+            &NormalizeConfig {
+                line_endings,
+                whitespace,
+                formatters: formatters
+                    .iter()
+                    .map(|(glob, command)| FormatterConfig {
+                        match_glob: (*glob).to_owned(),
+                        command: (*command).to_owned(),
+                    })
+                    .collect(),
+                ..NormalizeConfig::default()
+            },
+            Path::new("."),
+        )
+    }
+
+    #[test]
+    fn disabled_normalizer_does_nothing() {
+        let variants = vec![b"a \n".to_vec(), b"a\r\n".to_vec()];
+        assert_eq!(
+            Normalizer::default().normalize(Path::new("a.txt"), Some(b"a\n"), &variants),
+            None
+        );
+    }
+
+    #[test]
+    fn line_endings_follow_the_base() {
+        let variants = vec![b"a\r\nb\r\n".to_vec(), b"a\nb\n".to_vec()];
+        let normalized = normalizer(true, false, &[])
+            .normalize(Path::new("a.txt"), Some(b"a\r\n"), &variants)
+            .expect("should normalize");
+        assert_eq!(normalized.variants, vec![b"a\r\nb\r\n".to_vec(); 2]);
+        assert_eq!(normalized.base, Some(b"a\r\n".to_vec()));
+        assert_eq!(normalized.changed, vec![1]);
+        assert_eq!(normalized.steps, vec!["line endings"]);
+    }
+
+    #[test]
+    fn trailing_whitespace_is_stripped_per_line() {
+        assert_eq!(
+            strip_trailing_whitespace(b"a  \r\n\tb\t\n  \nc "),
+            b"a\r\n\tb\n\nc".to_vec()
+        );
+        let variants = vec![b"x = 1  \n".to_vec(), b"x = 1\n".to_vec()];
+        let normalized = normalizer(false, true, &[])
+            .normalize(Path::new("a.py"), None, &variants)
+            .expect("should normalize");
+        assert_eq!(normalized.variants, vec![b"x = 1\n".to_vec(); 2]);
+        assert_eq!(normalized.changed, vec![0]);
+    }
+
+    #[test]
+    fn binary_content_is_left_alone() {
+        let variants = vec![b"a\0\r\n".to_vec(), b"a\0\n".to_vec()];
+        assert_eq!(
+            normalizer(true, true, &[]).normalize(Path::new("a.bin"), None, &variants),
+            None
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn matching_formatter_runs_on_every_side() {
+        let variants = vec![b"b\na\n".to_vec(), b"a\nb\n".to_vec()];
+        let normalized = normalizer(false, false, &[("*.md", "false"), ("*.txt", "sort")])
+            .normalize(Path::new("list.txt"), Some(b"b\n"), &variants)
+            .expect("should normalize");
+        assert_eq!(normalized.variants, vec![b"a\nb\n".to_vec(); 2]);
+        assert_eq!(normalized.changed, vec![0]);
+        assert_eq!(normalized.steps, vec!["formatter `sort`"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failing_formatter_is_skipped() {
+        let variants = vec![b"b\na\n".to_vec(), b"a\nb\n".to_vec()];
+        assert_eq!(
+            normalizer(false, false, &[("*.txt", "exit 3")]).normalize(
+                Path::new("list.txt"),
+                None,
+                &variants
+            ),
+            None
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn formatter_runs_in_the_repo_root() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("marker"), "root\n").unwrap();
+        let config = NormalizeConfig {
+            formatters: vec![FormatterConfig {
+                match_glob: "*.txt".to_owned(),
+                command: "cat marker".to_owned(),
+            }],
+            ..NormalizeConfig::default()
+        };
+        let variants = vec![b"a\n".to_vec(), b"b\n".to_vec()];
+        let normalized = Normalizer::from_config(&config, dir.path())
+            .normalize(Path::new("a.txt"), None, &variants)
+            .expect("should normalize");
+        assert_eq!(normalized.variants, vec![b"root\n".to_vec(); 2]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn slow_formatter_is_killed_and_skipped() {
+        let config = NormalizeConfig {
+            formatters: vec![FormatterConfig {
+                match_glob: "*.txt".to_owned(),
+                command: "sleep 30".to_owned(),
+            }],
+            formatter_timeout_seconds: 1,
+            ..NormalizeConfig::default()
+        };
+        let variants = vec![b"a\n".to_vec(), b"b\n".to_vec()];
+        let started = std::time::Instant::now();
+        assert_eq!(
+            Normalizer::from_config(&config, Path::new(".")).normalize(
+                Path::new("a.txt"),
+                None,
+                &variants
+            ),
+            None
+        );
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,197 @@
+//! Conflict provenance — which commits on a side touched a conflicted region.
+//!
+//! A [`ConflictSide`](super::resolve::ConflictSide) records a workspace and
+//! its final blob, which says *what* each side wants but not *why*. To give
+//! agents the intent behind a side, [`blame_regions`] replays the
+//! workspace's commits (`epoch..HEAD`, oldest first), tracks each conflict
+//! region from base coordinates through every commit's line diff, and
+//! collects the commits whose changes overlapped it.
+//!
+//! The tracking is line-based: a change touches a region when its replaced
+//! lines overlap it (or an insertion lands inside or at its edges), and the
+//! region then grows to cover the replacement. AST regions are widened to
+//! the lines they span.
+
+use std::ops::Range;
+
+use serde::Serialize;
+
+use maw_git::merge::{LineChange, line_changes};
+use maw_git::{GitError, GitOid, GitRepo, GixRepo};
+
+use crate::model::conflict::Region;
+
+/// A commit that touched a conflict region.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CommitRef {
+    /// Full commit OID.
+    pub oid: String,
+    /// First line of the commit message.
+    pub summary: String,
+    /// Full commit message.
+    pub message: String,
+    /// Author identity (`Name <email>`).
+    pub author: String,
+    /// Committer time (Unix seconds).
+    pub time: i64,
+}
+
+/// 0-indexed line range of `region` within `base`. [`Region::WholeFile`]
+/// covers every line.
+#[must_use]
+pub fn region_lines(region: &Region, base: &[u8]) -> Range<u32> {
+    let line_of = |byte: usize| -> u32 {
+        let end = byte.min(base.len());
+        let lines = base[..end].split(|&b| b == b'\n').count() - 1;
+        u32::try_from(lines).unwrap_or(u32::MAX)
+    };
+    match region {
+        Region::Lines { start, end } => start.saturating_sub(1)..end.saturating_sub(1),
+        Region::AstNode {
+            start_byte,
+            end_byte,
+            ..
+        } => {
+            let end = *end_byte as usize;
+            let spans_partial_line = end > 0 && base.get(end - 1).is_some_and(|&b| b != b'\n');
+            line_of(*start_byte as usize)..line_of(end) + u32::from(spans_partial_line)
+        }
+        Region::WholeFile => 0..u32::MAX,
+    }
+}
+
+/// Commits in `from..head` whose changes to `path` touched each of
+/// `regions` (base coordinates, as of `from`), newest first per region.
+///
+/// Merge commits are followed along their first parent.
+///
+/// # Errors
+/// Returns a [`GitError`] if the commit walk or an object read fails.
+pub fn blame_regions(
+    repo: &GixRepo,
+    from: GitOid,
+    head: GitOid,
+    path: &str,
+    regions: &[Range<u32>],
+) -> Result<Vec<Vec<CommitRef>>, GitError> {
+    let mut tracked: Vec<Range<u32>> = regions.to_vec();
+    let mut touched: Vec<Vec<CommitRef>> = vec![Vec::new(); regions.len()];
+
+    for oid in repo.walk_commits(from, head, true)? {
+        let info = repo.read_commit(oid)?;
+        let after = repo.read_blob_at_path(oid, path)?;
+        let before = match info.parents.first() {
+            Some(parent) => repo.read_blob_at_path(*parent, path)?,
+            None => None,
+        };
+        let (before_oid, after_oid) = (
+            before.as_ref().map(|(_, blob, _)| *blob),
+            after.as_ref().map(|(_, blob, _)| *blob),
+        );
+        if before_oid == after_oid {
+            continue;
+        }
+        let changes = line_changes(
+            before.as_ref().map_or(&[][..], |(_, _, bytes)| bytes),
+            after.as_ref().map_or(&[][..], |(_, _, bytes)| bytes),
+        );
+        for (region, commits) in tracked.iter_mut().zip(touched.iter_mut()) {
+            if changes.iter().any(|change| touches(change, region)) {
+                commits.push(CommitRef {
+                    oid: oid.to_string(),
+                    summary: info.message.lines().next().unwrap_or_default().to_owned(),
+                    message: info.message.trim_end().to_owned(),
+                    author: info.author.clone(),
+                    time: info.committer_time,
+                });
+            }
+            *region = remap(region, &changes);
+        }
+    }
+
+    for commits in &mut touched {
+        commits.reverse();
+    }
+    Ok(touched)
+}
+
+/// Whether `change` overlaps `region`. Pure insertions count when they land
+/// inside the region or at either edge.
+fn touches(change: &LineChange, region: &Range<u32>) -> bool {
+    if change.before.is_empty() {
+        region.start <= change.before.start && change.before.start <= region.end
+    } else {
+        change.before.start < region.end && region.start < change.before.end
+    }
+}
+
+/// Carry `region` through `changes`: shift it by the line delta of changes
+/// before it and widen it to cover the replacement of changes touching it.
+fn remap(region: &Range<u32>, changes: &[LineChange]) -> Range<u32> {
+    let delta = |change: &LineChange| {
+        i64::from(change.after.end - change.after.start)
+            - i64::from(change.before.end - change.before.start)
+    };
+    let mut start = i64::from(region.start);
+    let mut end = i64::from(region.end);
+    for change in changes {
+        if touches(change, region) {
+            continue;
+        }
+        if change.before.end <= region.start {
+            start += delta(change);
+            end += delta(change);
+        }
+    }
+    for change in changes.iter().filter(|change| touches(change, region)) {
+        end += delta(change);
+        start = start.min(i64::from(change.after.start));
+        end = end.max(i64::from(change.after.end));
+    }
+    let clamp = |n: i64| u32::try_from(n.max(0)).unwrap_or(u32::MAX);
+    clamp(start)..clamp(end.max(start))
+}
+
+#[cfg(test)]
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn region_lines_converts_line_and_ast_regions() {
+        let base = b"a\nfn f() {\n}\nb\n";
+        assert_eq!(region_lines(&Region::lines(2, 4), base), 1..3);
+        let ast = Region::AstNode {
+            node_kind: "function_item".into(),
+            name: Some("f".into()),
+            start_byte: 2,
+            end_byte: 12,
+        };
+        assert_eq!(region_lines(&ast, base), 1..3);
+    }
+
+    #[test]
+    fn remap_shifts_and_widens_regions() {
+        // Two lines inserted above the region shift it down.
+        let above = [LineChange {
+            before: 0..0,
+            after: 0..2,
+        }];
+        assert_eq!(remap(&(3..5), &above), 5..7);
+
+        // A touching change that grows the region widens it.
+        let inside = [LineChange {
+            before: 3..4,
+            after: 3..6,
+        }];
+        assert_eq!(remap(&(3..5), &inside), 3..7);
+
+        // Changes below the region leave it alone.
+        let below = [LineChange {
+            before: 9..10,
+            after: 9..9,
+        }];
+        assert_eq!(remap(&(3..5), &below), 3..5);
+        assert!(!touches(&below[0], &(3..5)));
+    }
+}
@@ -0,0 +1,542 @@
+//! Local merge queue with speculative validation.
+//!
+//! Post-merge validation runs once per merge, serially, so when it takes
+//! minutes the merge rate collapses to one per validation run. The queue
+//! accepts merge requests and, for the next K entries, speculatively builds
+//! the candidate epoch each would produce *on top of the entries ahead of
+//! it* — a stack of cumulative candidates — and validates all of them in
+//! parallel. Entries are then committed in queue order.
+//!
+//! ```text
+//! epoch ── c1 = epoch + e1
+//!       └─ c2 = epoch + e1 + e2
+//!       └─ c3 = epoch + e1 + e2 + e3      (validated concurrently)
+//! ```
+//!
+//! Because every prefix of the batch has its own candidate, a validation
+//! failure bisects the batch at the first failing candidate: the entries
+//! before it commit, the failing entry is ejected, and the entries after it
+//! (whose candidates included the ejected one) are re-speculated in the
+//! next round. An entry whose speculative build conflicts is ejected and
+//! left out of the stack, so it does not invalidate the entries behind it.
+//!
+//! The queue itself is persisted at `.manifold/merge-queue.json`. Callers
+//! hold the repo's epoch lock for a whole run, so the epoch the candidates
+//! were built on cannot move between speculation and commit.
+
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use maw_git::{GitRepo as _, GixRepo};
+use serde::{Deserialize, Serialize};
+
+use super::build_phase::{BuildPhaseError, run_speculative_build};
+use super::validate::{
+    ValidateError, ValidateOutcome, create_temp_worktree, remove_temp_worktree, resolve_commands,
+    run_validate_pipeline_in_dir,
+};
+use crate::backend::WorkspaceBackend;
+use crate::config::ValidationConfig;
+use crate::model::types::{EpochId, GitOid, WorkspaceId};
+
+/// File name of the persisted queue under `.manifold/`.
+const QUEUE_FILE: &str = "merge-queue.json";
+
+/// Subdirectory under `.manifold/` holding the speculative validation
+/// worktrees (`queue-validate/<slot>`).
+const VALIDATE_SUBDIR: &str = "queue-validate";
+
+// ---------------------------------------------------------------------------
+// MergeQueue
+// ---------------------------------------------------------------------------
+
+/// One merge request waiting in the queue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueEntry {
+    /// Short identifier (`q-<n>`), unique for the life of the queue file.
+    pub id: String,
+    /// Workspaces merged together by this entry.
+    pub workspaces: Vec<String>,
+    /// Commit message for the merge.
+    pub message: String,
+    /// Unix timestamp (seconds) when the entry was enqueued.
+    pub enqueued_at: u64,
+}
+
+/// The persisted merge queue, in merge order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeQueue {
+    /// Pending entries, head first.
+    pub entries: Vec<QueueEntry>,
+    /// Counter for the next entry id.
+    #[serde(default)]
+    pub next_id: u64,
+}
+
+impl MergeQueue {
+    /// Path of the queue file under `manifold_dir`.
+    #[must_use]
+    pub fn path(manifold_dir: &Path) -> PathBuf {
+        manifold_dir.join(QUEUE_FILE)
+    }
+
+    /// Load the queue; a missing file is an empty queue.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueueError::Io`] if the file cannot be read or parsed.
+    pub fn load(manifold_dir: &Path) -> Result<Self, QueueError> {
+        let path = Self::path(manifold_dir);
+        match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|e| QueueError::Io(format!("parse {}: {e}", path.display()))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(QueueError::Io(format!("read {}: {e}", path.display()))),
+        }
+    }
+
+    /// Write the queue atomically (write-tmp + fsync + rename).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueueError::Io`] on any filesystem failure.
+    pub fn save(&self, manifold_dir: &Path) -> Result<(), QueueError> {
+        fs::create_dir_all(manifold_dir)
+            .map_err(|e| QueueError::Io(format!("create dir {}: {e}", manifold_dir.display())))?;
+        let path = Self::path(manifold_dir);
+        let tmp = path.with_extension("json.tmp");
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| QueueError::Io(format!("serialize: {e}")))?;
+        let mut file = fs::File::create(&tmp)
+            .map_err(|e| QueueError::Io(format!("create {}: {e}", tmp.display())))?;
+        file.write_all(json.as_bytes())
+            .map_err(|e| QueueError::Io(format!("write {}: {e}", tmp.display())))?;
+        file.sync_all()
+            .map_err(|e| QueueError::Io(format!("fsync {}: {e}", tmp.display())))?;
+        fs::rename(&tmp, &path)
+            .map_err(|e| QueueError::Io(format!("rename {}: {e}", path.display())))
+    }
+
+    /// Append a merge request and return its entry id.
+    pub fn push(&mut self, workspaces: Vec<String>, message: String, now: u64) -> String {
+        self.next_id += 1;
+        let id = format!("q-{}", self.next_id);
+        self.entries.push(QueueEntry {
+            id: id.clone(),
+            workspaces,
+            message,
+            enqueued_at: now,
+        });
+        id
+    }
+
+    /// Remove the entry with `id`, returning it if present.
+    pub fn remove(&mut self, id: &str) -> Option<QueueEntry> {
+        let index = self.entries.iter().position(|e| e.id == id)?;
+        Some(self.entries.remove(index))
+    }
+
+    /// The workspace's pending entry, if any.
+    #[must_use]
+    pub fn entry_for_workspace(&self, workspace: &str) -> Option<&QueueEntry> {
+        self.entries
+            .iter()
+            .find(|e| e.workspaces.iter().any(|ws| ws == workspace))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// QueueError
+// ---------------------------------------------------------------------------
+
+/// Errors from the merge queue.
+#[derive(Debug)]
+pub enum QueueError {
+    /// Queue file I/O or serialization failure.
+    Io(String),
+    /// An entry names an invalid workspace.
+    InvalidWorkspace(String),
+    /// Building a speculative candidate failed.
+    Build(BuildPhaseError),
+    /// Setting up or tearing down a validation worktree failed.
+    Validate(ValidateError),
+}
+
+impl std::fmt::Display for QueueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(msg) => write!(f, "merge queue I/O error: {msg}"),
+            Self::InvalidWorkspace(msg) => write!(f, "merge queue: invalid workspace: {msg}"),
+            Self::Build(e) => write!(f, "merge queue: speculative {e}"),
+            Self::Validate(e) => write!(f, "merge queue: speculative {e}"),
+        }
+    }
+}
+
+impl std::error::Error for QueueError {}
+
+impl From<BuildPhaseError> for QueueError {
+    fn from(e: BuildPhaseError) -> Self {
+        Self::Build(e)
+    }
+}
+
+impl From<ValidateError> for QueueError {
+    fn from(e: ValidateError) -> Self {
+        Self::Validate(e)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Speculation
+// ---------------------------------------------------------------------------
+
+/// How an entry's speculative candidate fared.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// The candidate validated (or no validation is configured).
+    Passed(ValidateOutcome),
+    /// The candidate failed validation under a blocking or quarantine policy.
+    Failed(ValidateOutcome),
+    /// The speculative build had this many unresolved conflicts.
+    Conflicted(usize),
+}
+
+/// One entry's speculative candidate and its verdict.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Speculation {
+    /// The queue entry id.
+    pub entry_id: String,
+    /// Tree of the cumulative candidate (absent when the build conflicted).
+    pub tree: Option<GitOid>,
+    /// Validation verdict.
+    pub verdict: Verdict,
+}
+
+/// Build and validate cumulative candidates for `entries` onto `epoch`.
+///
+/// Candidates are built in order (each on top of the non-conflicting entries
+/// ahead of it) and then validated concurrently, one temporary worktree per
+/// candidate, with [`run_validate_pipeline_in_dir`].
+///
+/// # Errors
+///
+/// Returns [`QueueError`] if an entry names an invalid workspace, a build
+/// fails outright, or a validation worktree cannot be created.
+pub fn speculate<B: WorkspaceBackend>(
+    repo_root: &Path,
+    manifold_dir: &Path,
+    backend: &B,
+    epoch: &EpochId,
+    entries: &[QueueEntry],
+    validation: &ValidationConfig,
+) -> Result<Vec<Speculation>, QueueError> {
+    let repo = GixRepo::open(repo_root)
+        .map_err(|e| QueueError::Io(format!("open repo {}: {e}", repo_root.display())))?;
+
+    // 1. Build the stack.
+    let mut stack: Vec<WorkspaceId> = Vec::new();
+    let mut built: Vec<(String, Built)> = Vec::new();
+    for entry in entries {
+        let mut sources = stack.clone();
+        for ws in &entry.workspaces {
+            let id = WorkspaceId::new(ws)
+                .map_err(|e| QueueError::InvalidWorkspace(format!("{ws}: {e}")))?;
+            if !sources.contains(&id) {
+                sources.push(id);
+            }
+        }
+        let output = run_speculative_build(repo_root, manifold_dir, backend, epoch, &sources)?;
+        if output.conflicts.is_empty() {
+            let tree = commit_tree(&repo, &output.candidate)?;
+            built.push((
+                entry.id.clone(),
+                Built::Clean {
+                    candidate: output.candidate,
+                    tree,
+                },
+            ));
+            stack = sources;
+        } else {
+            built.push((entry.id.clone(), Built::Conflicted(output.conflicts.len())));
+        }
+    }
+
+    // 2. Validate every clean candidate concurrently.
+    let candidates: Vec<&GitOid> = built
+        .iter()
+        .filter_map(|(_, built)| match built {
+            Built::Clean { candidate, .. } => Some(candidate),
+            Built::Conflicted(_) => None,
+        })
+        .collect();
+    let mut outcomes = validate_concurrently(repo_root, &candidates, validation)?.into_iter();
+
+    Ok(built
+        .into_iter()
+        .map(|(entry_id, built)| match built {
+            Built::Conflicted(conflicts) => Speculation {
+                entry_id,
+                tree: None,
+                verdict: Verdict::Conflicted(conflicts),
+            },
+            Built::Clean { tree, .. } => {
+                let outcome = outcomes.next().unwrap_or(ValidateOutcome::Skipped);
+                let verdict = if outcome.may_proceed() && !outcome.needs_quarantine() {
+                    Verdict::Passed(outcome)
+                } else {
+                    Verdict::Failed(outcome)
+                };
+                Speculation {
+                    entry_id,
+                    tree: Some(tree),
+                    verdict,
+                }
+            }
+        })
+        .collect())
+}
+
+/// Result of building one entry's cumulative candidate.
+enum Built {
+    Clean { candidate: GitOid, tree: GitOid },
+    Conflicted(usize),
+}
+
+fn commit_tree(repo: &GixRepo, commit: &GitOid) -> Result<GitOid, QueueError> {
+    let oid: maw_git::GitOid = commit
+        .as_str()
+        .parse()
+        .map_err(|e| QueueError::Io(format!("parse candidate oid: {e}")))?;
+    let info = repo
+        .read_commit(oid)
+        .map_err(|e| QueueError::Io(format!("read candidate {commit}: {e}")))?;
+    GitOid::new(&info.tree_oid.to_string())
+        .map_err(|e| QueueError::Io(format!("candidate tree oid: {e}")))
+}
+
+/// Validate `candidates` in parallel, one worktree each, returning outcomes
+/// in input order.
+fn validate_concurrently(
+    repo_root: &Path,
+    candidates: &[&GitOid],
+    config: &ValidationConfig,
+) -> Result<Vec<ValidateOutcome>, QueueError> {
+    if candidates.is_empty() || (config.effective_commands().is_empty() && config.preset.is_none())
+    {
+        return Ok(vec![ValidateOutcome::Skipped; candidates.len()]);
+    }
+
+    // Worktrees are created and removed serially (they share the git admin
+    // directory); only the validation commands run concurrently.
+    let base = repo_root.join(".manifold").join(VALIDATE_SUBDIR);
+    let slots: Vec<(String, PathBuf)> = (0..candidates.len())
+        .map(|slot| {
+            (
+                format!("manifold-queue-validate-{slot}"),
+                base.join(slot.to_string()),
+            )
+        })
+        .collect();
+    let cleanup = |slots: &[(String, PathBuf)]| {
+        for (name, dir) in slots {
+            let _ = remove_temp_worktree(repo_root, name);
+            let _ = fs::remove_dir_all(dir);
+        }
+    };
+    cleanup(&slots);
+    for ((name, dir), candidate) in slots.iter().zip(candidates) {
+        if let Err(e) = create_temp_worktree(repo_root, name, candidate, dir) {
+            cleanup(&slots);
+            return Err(e.into());
+        }
+    }
+
+    let results: Vec<Result<ValidateOutcome, ValidateError>> = std::thread::scope(|scope| {
+        // Spawn every validation before joining any of them.
+        #[expect(
+            clippy::needless_collect,
+            reason = "collecting spawns all threads before the first join"
+        )]
+        let handles: Vec<_> = slots
+            .iter()
+            .map(|(_, dir)| {
+                scope.spawn(move || {
+                    let commands = resolve_commands(config, dir);
+                    if commands.is_empty() {
+                        return Ok(ValidateOutcome::Skipped);
+                    }
+                    let refs: Vec<&str> = commands.iter().map(String::as_str).collect();
+                    run_validate_pipeline_in_dir(
+                        &refs,
+                        dir,
+                        config.timeout_seconds,
+                        &config.on_failure,
+                    )
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle.join().unwrap_or_else(|_| {
+                    Err(ValidateError::CommandSpawn(
+                        "validation thread panicked".to_owned(),
+                    ))
+                })
+            })
+            .collect()
+    });
+    cleanup(&slots);
+
+    results
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .map_err(Into::into)
+}
+
+// ---------------------------------------------------------------------------
+// Decision
+// ---------------------------------------------------------------------------
+
+/// What to do with a speculated batch.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueueDecision {
+    /// Entries to commit, in order, with the validated candidate tree each
+    /// commit must reproduce.
+    pub commit: Vec<(String, GitOid)>,
+    /// Entries to drop from the queue, with the reason.
+    pub eject: Vec<(String, String)>,
+}
+
+/// Decide which entries of a speculated batch to commit and eject.
+///
+/// Walks the stack in order: passing entries commit until the first
+/// validation failure, which is ejected; entries behind it are neither
+/// committed nor ejected (their candidates included it). Conflicted
+/// entries are ejected wherever they sit, since they were left out of the
+/// stack.
+#[must_use]
+pub fn decide(speculations: &[Speculation]) -> QueueDecision {
+    let mut decision = QueueDecision::default();
+    for speculation in speculations {
+        match (&speculation.verdict, &speculation.tree) {
+            (Verdict::Conflicted(count), _) => decision.eject.push((
+                speculation.entry_id.clone(),
+                format!("{count} conflict(s) against the epoch and the entries ahead of it"),
+            )),
+            (Verdict::Passed(_), Some(tree)) => decision
+                .commit
+                .push((speculation.entry_id.clone(), tree.clone())),
+            (Verdict::Failed(_) | Verdict::Passed(_), _) => {
+                decision.eject.push((
+                    speculation.entry_id.clone(),
+                    "validation failed on its speculative candidate".to_owned(),
+                ));
+                break;
+            }
+        }
+    }
+    decision
+}
+
+#[cfg(test)]
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+mod tests {
+    use super::*;
+    use crate::merge_state::ValidationResult;
+
+    fn oid(c: char) -> GitOid {
+        GitOid::new(&c.to_string().repeat(40)).expect("operation should succeed")
+    }
+
+    fn failed() -> ValidateOutcome {
+        ValidateOutcome::Blocked(ValidationResult {
+            passed: false,
+            exit_code: Some(1),
+            stdout: String::new(),
+            stderr: "boom".to_owned(),
+            duration_ms: 1,
+            command_results: Vec::new(),
+        })
+    }
+
+    fn spec(id: &str, verdict: Verdict) -> Speculation {
+        let tree = match verdict {
+            Verdict::Conflicted(_) => None,
+            _ => Some(oid('a')),
+        };
+        Speculation {
+            entry_id: id.to_owned(),
+            tree,
+            verdict,
+        }
+    }
+
+    #[test]
+    fn queue_round_trips_and_assigns_ids() {
+        let dir = tempfile::tempdir().expect("operation should succeed");
+        let mut queue = MergeQueue::load(dir.path()).expect("operation should succeed");
+        assert!(queue.entries.is_empty());
+
+        queue.push(vec!["alice".to_owned()], "feat: a".to_owned(), 1);
+        queue.push(
+            vec!["bob".to_owned(), "carol".to_owned()],
+            "feat: b".to_owned(),
+            2,
+        );
+        queue.save(dir.path()).expect("operation should succeed");
+
+        let mut loaded = MergeQueue::load(dir.path()).expect("operation should succeed");
+        assert_eq!(loaded, queue);
+        assert_eq!(loaded.entries[1].id, "q-2");
+        assert_eq!(
+            loaded.entry_for_workspace("carol").map(|e| e.id.as_str()),
+            Some("q-2")
+        );
+
+        assert_eq!(
+            loaded.remove("q-1").map(|e| e.message),
+            Some("feat: a".to_owned())
+        );
+        loaded.push(vec!["dave".to_owned()], "feat: d".to_owned(), 3);
+        assert_eq!(loaded.entries[1].id, "q-3");
+    }
+
+    #[test]
+    fn all_passing_batch_commits_in_order() {
+        let decision = decide(&[
+            spec("q-1", Verdict::Passed(ValidateOutcome::Skipped)),
+            spec("q-2", Verdict::Passed(ValidateOutcome::Skipped)),
+        ]);
+        let ids: Vec<&str> = decision.commit.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["q-1", "q-2"]);
+        assert!(decision.eject.is_empty());
+    }
+
+    #[test]
+    fn validation_failure_bisects_the_batch() {
+        let decision = decide(&[
+            spec("q-1", Verdict::Passed(ValidateOutcome::Skipped)),
+            spec("q-2", Verdict::Failed(failed())),
+            spec("q-3", Verdict::Passed(ValidateOutcome::Skipped)),
+        ]);
+        let committed: Vec<&str> = decision.commit.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(committed, vec!["q-1"]);
+        assert_eq!(decision.eject.len(), 1);
+        assert_eq!(decision.eject[0].0, "q-2");
+    }
+
+    #[test]
+    fn conflicted_entry_is_ejected_without_blocking_the_rest() {
+        let decision = decide(&[
+            spec("q-1", Verdict::Conflicted(2)),
+            spec("q-2", Verdict::Passed(ValidateOutcome::Skipped)),
+        ]);
+        assert_eq!(decision.commit.len(), 1);
+        assert_eq!(decision.commit[0].0, "q-2");
+        assert_eq!(decision.eject[0].0, "q-1");
+        assert!(decision.eject[0].1.contains("2 conflict(s)"));
+    }
+}
@@ -0,0 +1,673 @@
+//! Conflict resolution memory ("rerere") for the merge engine.
+//!
+//! Agents keep resolving the same conflict: a merge fails, a sibling
+//! auto-rebase replays the same hunk, and the same `maw ws resolve` runs
+//! again by hand. This module records every resolution, keyed by a
+//! fingerprint of the conflict's *content*, and replays it the next time the
+//! same conflict appears — in the merge engine's RESOLVE step and in the
+//! sync rebase replay.
+//!
+//! # Fingerprints
+//!
+//! Two kinds of entries are recorded:
+//!
+//! - **file**: the whole-file `(base, sides)` set of a conflicted path mapped
+//!   to its resolved content. Works for any number of sides.
+//! - **hunk**: one diff3 conflict hunk `(base, [ours, theirs])` mapped to the
+//!   lines that replaced it. Hunk entries are derived from a whole-file
+//!   resolution by aligning the resolved file against the conflict's common
+//!   (non-conflicting) regions, so a resolution keeps working after the
+//!   surrounding file has moved on.
+//!
+//! Inputs are normalized before hashing — line endings and trailing
+//! whitespace are ignored and sides are sorted — so the same conflict seen
+//! from a merge (`alice` vs `bob`) and from a rebase (`epoch` vs `bob`)
+//! produces the same key. Only UTF-8 text is recorded.
+//!
+//! # Storage
+//!
+//! `<manifold_dir>/rerere/<fingerprint>.json`, one file per entry.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::build::ResolvedChange;
+use super::diff3::{Diff3Hunk, Diff3Segment, diff3_segments};
+use super::partition::PartitionResult;
+use super::resolve::{AutoResolution, AutoResolutionKind, ResolveResult};
+use crate::model::layout::LayoutFlavor;
+
+/// Subdirectory of the manifold directory holding recorded resolutions.
+pub const RERERE_DIR: &str = "rerere";
+
+/// Bump when the fingerprint normalization changes.
+const FINGERPRINT_VERSION: &str = "rerere-v1";
+
+/// Whether an entry covers a whole file or a single conflict hunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    /// Whole-file `(base, sides)` → resolved content.
+    File,
+    /// One diff3 conflict hunk → replacement lines.
+    Hunk,
+}
+
+/// One recorded resolution, as stored on disk.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedResolution {
+    /// Entry kind.
+    pub kind: EntryKind,
+    /// Path the resolution was first recorded for (informational only; the
+    /// fingerprint does not include it).
+    pub path: PathBuf,
+    /// Replacement text.
+    pub resolution: String,
+    /// Unix timestamp (seconds) of the recording.
+    pub recorded_at: u64,
+}
+
+/// A recorded resolution applied to a new conflict.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Replay {
+    /// The resolved file content.
+    pub content: Vec<u8>,
+    /// Number of hunks resolved from history; `0` for a whole-file match.
+    pub hunks: usize,
+}
+
+impl Replay {
+    /// One-line description for merge plans and rebase reports.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        if self.hunks == 0 {
+            "auto-resolved from history (whole file)".to_owned()
+        } else {
+            format!("auto-resolved from history ({} hunk(s))", self.hunks)
+        }
+    }
+}
+
+/// Errors from the resolution store.
+#[derive(Debug)]
+pub enum RerereError {
+    /// Reading or writing an entry failed.
+    Io(String),
+}
+
+impl std::fmt::Display for RerereError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(msg) => write!(f, "rerere I/O error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for RerereError {}
+
+// ---------------------------------------------------------------------------
+// ResolutionMemory
+// ---------------------------------------------------------------------------
+
+/// The on-disk store of recorded resolutions.
+#[derive(Clone, Debug, Default)]
+pub struct ResolutionMemory {
+    /// Store directory; `None` disables recording and replay.
+    dir: Option<PathBuf>,
+}
+
+impl ResolutionMemory {
+    /// The store under `manifold_dir`.
+    #[must_use]
+    pub fn open(manifold_dir: &Path) -> Self {
+        Self {
+            dir: Some(manifold_dir.join(RERERE_DIR)),
+        }
+    }
+
+    /// The store for the repository at `repo_root`.
+    #[must_use]
+    pub fn for_repo(repo_root: &Path) -> Self {
+        Self::open(&LayoutFlavor::detect_with_env(repo_root).manifold_dir(repo_root))
+    }
+
+    /// A store that records nothing and replays nothing.
+    #[must_use]
+    pub const fn disabled() -> Self {
+        Self { dir: None }
+    }
+
+    /// Record how a conflicted file was resolved.
+    ///
+    /// Stores a whole-file entry and, for two-sided text conflicts, one entry
+    /// per diff3 hunk whose replacement can be located in `resolved`.
+    /// Returns the number of entries written (zero for non-text input).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RerereError::Io`] if an entry cannot be written.
+    pub fn record(
+        &self,
+        path: &Path,
+        base: Option<&[u8]>,
+        sides: &[&[u8]],
+        resolved: &[u8],
+    ) -> Result<usize, RerereError> {
+        if self.dir.is_none() || sides.len() < 2 {
+            return Ok(0);
+        }
+        let Some((base, sides)) = texts(base, sides) else {
+            return Ok(0);
+        };
+        let Ok(resolved) = std::str::from_utf8(resolved) else {
+            return Ok(0);
+        };
+
+        self.store(
+            &file_fingerprint(base, &sides),
+            EntryKind::File,
+            path,
+            resolved,
+        )?;
+        let mut written = 1;
+
+        if let [ours, theirs] = sides.as_slice()
+            && let Some(segments) = diff3_segments(base.unwrap_or(""), ours, theirs)
+        {
+            for (hunk, resolution) in align_hunks(&segments, resolved) {
+                self.store(
+                    &hunk_fingerprint(&hunk.base, &hunk.ours, &hunk.theirs),
+                    EntryKind::Hunk,
+                    path,
+                    &resolution,
+                )?;
+                written += 1;
+            }
+        }
+        Ok(written)
+    }
+
+    /// Record the resolution of a single conflict hunk, as chosen for a
+    /// marker block (`<<<<<<<` … `>>>>>>>`) in a worktree file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RerereError::Io`] if the entry cannot be written.
+    pub fn record_hunk(
+        &self,
+        path: &Path,
+        base: &str,
+        sides: [&str; 2],
+        resolution: &str,
+    ) -> Result<bool, RerereError> {
+        if self.dir.is_none() {
+            return Ok(false);
+        }
+        let mut resolution = resolution.to_owned();
+        if !resolution.is_empty() && !resolution.ends_with('\n') {
+            resolution.push('\n');
+        }
+        self.store(
+            &hunk_fingerprint(
+                &split_lines(base),
+                &split_lines(sides[0]),
+                &split_lines(sides[1]),
+            ),
+            EntryKind::Hunk,
+            path,
+            &resolution,
+        )?;
+        Ok(true)
+    }
+
+    /// Resolve a conflict from history, if it (or every one of its hunks)
+    /// was resolved before.
+    #[must_use]
+    pub fn replay(&self, base: Option<&[u8]>, sides: &[&[u8]]) -> Option<Replay> {
+        self.dir.as_ref()?;
+        if sides.len() < 2 {
+            return None;
+        }
+        let (base, sides) = texts(base, sides)?;
+
+        if let Some(entry) = self.lookup(&file_fingerprint(base, &sides), EntryKind::File) {
+            return Some(Replay {
+                content: entry.resolution.into_bytes(),
+                hunks: 0,
+            });
+        }
+
+        let [ours, theirs] = sides.as_slice() else {
+            return None;
+        };
+        let segments = diff3_segments(base.unwrap_or(""), ours, theirs)?;
+        let mut content = String::new();
+        let mut hunks = 0;
+        for segment in &segments {
+            match segment {
+                Diff3Segment::Common(lines) => lines.iter().for_each(|line| content.push_str(line)),
+                Diff3Segment::Conflict(hunk) => {
+                    let entry = self.lookup(
+                        &hunk_fingerprint(&hunk.base, &hunk.ours, &hunk.theirs),
+                        EntryKind::Hunk,
+                    )?;
+                    content.push_str(&entry.resolution);
+                    hunks += 1;
+                }
+            }
+        }
+        (hunks > 0).then(|| Replay {
+            content: content.into_bytes(),
+            hunks,
+        })
+    }
+
+    fn entry_path(&self, fingerprint: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{fingerprint}.json")))
+    }
+
+    fn lookup(&self, fingerprint: &str, kind: EntryKind) -> Option<RecordedResolution> {
+        let path = self.entry_path(fingerprint)?;
+        let contents = fs::read_to_string(path).ok()?;
+        let entry: RecordedResolution = serde_json::from_str(&contents).ok()?;
+        (entry.kind == kind).then_some(entry)
+    }
+
+    fn store(
+        &self,
+        fingerprint: &str,
+        kind: EntryKind,
+        path: &Path,
+        resolution: &str,
+    ) -> Result<(), RerereError> {
+        let (Some(dir), Some(entry_path)) = (self.dir.as_ref(), self.entry_path(fingerprint))
+        else {
+            return Ok(());
+        };
+        fs::create_dir_all(dir)
+            .map_err(|e| RerereError::Io(format!("create dir {}: {e}", dir.display())))?;
+        let entry = RecordedResolution {
+            kind,
+            path: path.to_path_buf(),
+            resolution: resolution.to_owned(),
+            recorded_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        };
+        let json = serde_json::to_string_pretty(&entry)
+            .map_err(|e| RerereError::Io(format!("serialize: {e}")))?;
+        let tmp = entry_path.with_extension("json.tmp");
+        fs::write(&tmp, json)
+            .map_err(|e| RerereError::Io(format!("write {}: {e}", tmp.display())))?;
+        fs::rename(&tmp, &entry_path)
+            .map_err(|e| RerereError::Io(format!("rename {}: {e}", entry_path.display())))
+    }
+}
+
+/// Resolve conflicts in `result` from recorded history.
+///
+/// A conflict is replayed only when every side has content and its sides
+/// cover every workspace that touched the path — a record narrowed to the
+/// overlapping participants would otherwise drop the others' edits. Replayed
+/// paths move to `resolved` and are reported as [`AutoResolutionKind::Rerere`].
+pub fn apply_recorded_resolutions(
+    result: &mut ResolveResult,
+    partition: &PartitionResult,
+    memory: &ResolutionMemory,
+) {
+    if memory.dir.is_none() || result.conflicts.is_empty() {
+        return;
+    }
+    let touched: BTreeMap<&Path, usize> = partition
+        .shared
+        .iter()
+        .map(|(path, entries)| (path.as_path(), entries.len()))
+        .collect();
+
+    let mut remaining = Vec::with_capacity(result.conflicts.len());
+    for conflict in std::mem::take(&mut result.conflicts) {
+        let sides: Option<Vec<&[u8]>> = conflict
+            .sides
+            .iter()
+            .map(|side| side.content.as_deref())
+            .collect();
+        let replay = sides
+            .filter(|sides| touched.get(conflict.path.as_path()) == Some(&sides.len()))
+            .and_then(|sides| memory.replay(conflict.base.as_deref(), &sides));
+        match replay {
+            Some(replay) => {
+                result.auto_resolutions.push(AutoResolution {
+                    path: conflict.path.clone(),
+                    kind: AutoResolutionKind::Rerere,
+                    workspaces: conflict
+                        .sides
+                        .iter()
+                        .map(|side| side.workspace_id.clone())
+                        .collect(),
+                    details: vec![replay.describe()],
+                });
+                result.resolved.push(ResolvedChange::Upsert {
+                    path: conflict.path,
+                    content: replay.content,
+                });
+            }
+            None => remaining.push(conflict),
+        }
+    }
+    result.conflicts = remaining;
+    result.resolved.sort_by(|a, b| a.path().cmp(b.path()));
+}
+
+// ---------------------------------------------------------------------------
+// Fingerprints
+// ---------------------------------------------------------------------------
+
+fn texts<'a>(
+    base: Option<&'a [u8]>,
+    sides: &[&'a [u8]],
+) -> Option<(Option<&'a str>, Vec<&'a str>)> {
+    let base = match base {
+        Some(bytes) => Some(std::str::from_utf8(bytes).ok()?),
+        None => None,
+    };
+    let sides = sides
+        .iter()
+        .map(|side| std::str::from_utf8(side).ok())
+        .collect::<Option<Vec<_>>>()?;
+    Some((base, sides))
+}
+
+/// Lines with their terminators.
+fn split_lines(text: &str) -> Vec<&str> {
+    text.split_inclusive('\n').collect()
+}
+
+/// A line with line endings and trailing whitespace removed.
+fn normalize(line: &str) -> &str {
+    line.trim_end()
+}
+
+/// A block of lines in canonical form: one normalized line per `\n`.
+fn canonical<S: AsRef<str>>(lines: &[S]) -> String {
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(normalize(line.as_ref()));
+        out.push('\n');
+    }
+    out
+}
+
+fn fingerprint(kind: &str, base: Option<String>, mut sides: Vec<String>) -> String {
+    sides.sort();
+    let mut hasher = Sha256::new();
+    hasher.update(FINGERPRINT_VERSION.as_bytes());
+    hasher.update([0]);
+    hasher.update(kind.as_bytes());
+    hasher.update([0]);
+    match base {
+        Some(base) => {
+            hasher.update(b"base\0");
+            hasher.update(base.as_bytes());
+        }
+        None => hasher.update(b"no-base\0"),
+    }
+    for side in sides {
+        hasher.update(b"\0side\0");
+        hasher.update(side.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+fn file_fingerprint(base: Option<&str>, sides: &[&str]) -> String {
+    fingerprint(
+        "file",
+        base.map(|base| canonical(&split_lines(base))),
+        sides
+            .iter()
+            .map(|side| canonical(&split_lines(side)))
+            .collect(),
+    )
+}
+
+fn hunk_fingerprint<S: AsRef<str>>(base: &[S], ours: &[S], theirs: &[S]) -> String {
+    fingerprint(
+        "hunk",
+        Some(canonical(base)),
+        vec![canonical(ours), canonical(theirs)],
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Hunk alignment
+// ---------------------------------------------------------------------------
+
+/// Match each conflict hunk to the lines that replaced it in `resolved`.
+///
+/// Walks the common regions in order, locating each in `resolved`; the text
+/// between two located regions is the replacement of the hunk between them.
+/// Alignment stops at the first common region that cannot be found.
+fn align_hunks<'s>(segments: &'s [Diff3Segment], resolved: &str) -> Vec<(&'s Diff3Hunk, String)> {
+    let lines = split_lines(resolved);
+    let mut out = Vec::new();
+    let mut cursor = 0;
+    let mut pending: Option<&Diff3Hunk> = None;
+    for segment in segments {
+        match segment {
+            Diff3Segment::Conflict(hunk) => pending = Some(hunk),
+            Diff3Segment::Common(common) => {
+                let Some(at) = find_lines(&lines, cursor, common) else {
+                    return out;
+                };
+                match pending.take() {
+                    Some(hunk) => out.push((hunk, lines[cursor..at].concat())),
+                    None if at != cursor => return out,
+                    None => {}
+                }
+                cursor = at + common.len();
+            }
+        }
+    }
+    if let Some(hunk) = pending {
+        out.push((hunk, lines[cursor..].concat()));
+    }
+    out
+}
+
+fn find_lines(haystack: &[&str], from: usize, needle: &[String]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(from);
+    }
+    (from..=haystack.len().checked_sub(needle.len())?).find(|&start| {
+        haystack[start..start + needle.len()]
+            .iter()
+            .zip(needle)
+            .all(|(a, b)| normalize(a) == normalize(b))
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+mod tests {
+    use super::*;
+    use crate::merge::partition::PathEntry;
+    use crate::merge::resolve::resolve_partition;
+    use crate::merge::types::ChangeKind;
+    use crate::model::types::WorkspaceId;
+
+    fn ws(name: &str) -> WorkspaceId {
+        WorkspaceId::new(name).expect("operation should succeed")
+    }
+
+    const BASE: &str = "fn a() {}\nlet x = 1;\nfn b() {}\nlet y = 1;\nfn c() {}\n";
+    const OURS: &str = "fn a() {}\nlet x = 2;\nfn b() {}\nlet y = 2;\nfn c() {}\n";
+    const THEIRS: &str = "fn a() {}\nlet x = 3;\nfn b() {}\nlet y = 3;\nfn c() {}\n";
+    const RESOLVED: &str = "fn a() {}\nlet x = 5;\nfn b() {}\nlet y = 6;\nfn c() {}\n";
+
+    fn memory() -> (tempfile::TempDir, ResolutionMemory) {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let memory = ResolutionMemory::open(dir.path());
+        (dir, memory)
+    }
+
+    #[test]
+    fn fingerprints_ignore_side_order_and_line_endings() {
+        assert_eq!(
+            file_fingerprint(Some(BASE), &[OURS, THEIRS]),
+            file_fingerprint(Some(BASE), &[THEIRS, &OURS.replace('\n', "\r\n")]),
+        );
+        assert_ne!(
+            file_fingerprint(Some(BASE), &[OURS, THEIRS]),
+            file_fingerprint(None, &[OURS, THEIRS]),
+        );
+    }
+
+    #[test]
+    fn whole_file_resolution_replays_for_swapped_sides() {
+        let (_dir, memory) = memory();
+        let path = Path::new("src/lib.rs");
+        let written = memory
+            .record(
+                path,
+                Some(BASE.as_bytes()),
+                &[OURS.as_bytes(), THEIRS.as_bytes()],
+                RESOLVED.as_bytes(),
+            )
+            .expect("record");
+        assert_eq!(written, 3, "one file entry plus two hunks");
+
+        let replay = memory
+            .replay(Some(BASE.as_bytes()), &[THEIRS.as_bytes(), OURS.as_bytes()])
+            .expect("recorded conflict should replay");
+        assert_eq!(replay.content, RESOLVED.as_bytes());
+        assert_eq!(replay.hunks, 0);
+    }
+
+    #[test]
+    fn hunk_resolutions_replay_after_surrounding_code_moved_on() {
+        let (_dir, memory) = memory();
+        memory
+            .record(
+                Path::new("src/lib.rs"),
+                Some(BASE.as_bytes()),
+                &[OURS.as_bytes(), THEIRS.as_bytes()],
+                RESOLVED.as_bytes(),
+            )
+            .expect("record");
+
+        // Same two conflicts, but the file gained an unrelated function.
+        let grown = |text: &str| format!("{text}fn d() {{}}\n");
+        let replay = memory
+            .replay(
+                Some(grown(BASE).as_bytes()),
+                &[grown(OURS).as_bytes(), grown(THEIRS).as_bytes()],
+            )
+            .expect("both hunks should replay");
+        assert_eq!(replay.hunks, 2);
+        assert_eq!(
+            String::from_utf8(replay.content.clone()).expect("utf-8"),
+            grown(RESOLVED)
+        );
+        assert_eq!(replay.describe(), "auto-resolved from history (2 hunk(s))");
+    }
+
+    #[test]
+    fn partially_known_conflict_does_not_replay() {
+        let (_dir, memory) = memory();
+        memory
+            .record_hunk(
+                Path::new("src/lib.rs"),
+                "let x = 1;",
+                ["let x = 2;", "let x = 3;"],
+                "let x = 5;",
+            )
+            .expect("record");
+        assert!(
+            memory
+                .replay(Some(BASE.as_bytes()), &[OURS.as_bytes(), THEIRS.as_bytes()])
+                .is_none(),
+            "the `y` hunk was never resolved"
+        );
+    }
+
+    #[test]
+    fn disabled_memory_records_nothing() {
+        let memory = ResolutionMemory::disabled();
+        let written = memory
+            .record(
+                Path::new("a"),
+                Some(BASE.as_bytes()),
+                &[OURS.as_bytes(), THEIRS.as_bytes()],
+                RESOLVED.as_bytes(),
+            )
+            .expect("record");
+        assert_eq!(written, 0);
+    }
+
+    #[test]
+    fn recorded_resolution_clears_merge_conflict() {
+        let (_dir, memory) = memory();
+        let path = PathBuf::from("src/lib.rs");
+        let partition = PartitionResult {
+            unique: vec![],
+            shared: vec![(
+                path.clone(),
+                vec![
+                    PathEntry::new(
+                        ws("alice"),
+                        ChangeKind::Modified,
+                        Some(OURS.as_bytes().to_vec()),
+                    ),
+                    PathEntry::new(
+                        ws("bob"),
+                        ChangeKind::Modified,
+                        Some(THEIRS.as_bytes().to_vec()),
+                    ),
+                ],
+            )],
+            df_clashes: vec![],
+        };
+        let base = BTreeMap::from([(path.clone(), BASE.as_bytes().to_vec())]);
+
+        let mut result = resolve_partition(&partition, &base).expect("resolve");
+        assert_eq!(result.conflicts.len(), 1);
+        apply_recorded_resolutions(&mut result, &partition, &memory);
+        assert_eq!(result.conflicts.len(), 1, "nothing recorded yet");
+
+        // First resolution is recorded (as `maw ws resolve` would) ...
+        memory
+            .record_hunk(
+                &path,
+                "let x = 1;",
+                ["let x = 2;", "let x = 3;"],
+                "let x = 5;",
+            )
+            .expect("record");
+        memory
+            .record_hunk(
+                &path,
+                "let y = 1;",
+                ["let y = 3;", "let y = 2;"],
+                "let y = 6;",
+            )
+            .expect("record");
+
+        // ... and the repeat conflict resolves from history.
+        apply_recorded_resolutions(&mut result, &partition, &memory);
+        assert!(result.conflicts.is_empty());
+        assert_eq!(result.auto_resolutions.len(), 1);
+        let auto = &result.auto_resolutions[0];
+        assert_eq!(auto.kind, AutoResolutionKind::Rerere);
+        assert_eq!(auto.workspaces, vec![ws("alice"), ws("bob")]);
+        assert!(result.resolved.iter().any(|change| matches!(
+            change,
+            ResolvedChange::Upsert { path: p, content } if *p == path && content == RESOLVED.as_bytes()
+        )));
+    }
+}
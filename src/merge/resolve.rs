@@ -23,6 +23,9 @@
 //!    normalize variant block positions back toward base ordering, and retry
 //!    diff3 once before declaring conflict.
 //!
+//! With AST merge enabled, symbols moved across files are detected first
+//! ([`crate::merge::symbol_move`]) so edits to them follow the move.
+//!
 //! The function returns both successful resolutions and conflicts so callers can
 //! either proceed directly to BUILD or surface rich conflict diagnostics.
 
@@ -44,11 +47,20 @@ use crate::model::conflict::{
 use crate::model::types::WorkspaceId;
 
 #[cfg(feature = "ast-merge")]
-use super::ast_merge::{AstMergeConfig, AstMergeResult, try_ast_merge_with_config};
+use super::ast_merge::{AstMergeConfig, AstMergeReport, AstMergeResult, try_ast_merge_with_report};
+#[cfg(feature = "ast-merge")]
+use super::structured_merge::try_structured_merge;
 
 use super::build::ResolvedChange;
+use super::external_driver::{
+    ExternalDriver, ExternalDriverError, ExternalDriverSet, ExternalMerge, run_external_driver,
+};
+use super::normalize::Normalizer;
 use super::partition::{DfClash, PartitionResult, PathEntry};
+#[cfg(feature = "ast-merge")]
+use super::symbol_move::apply_symbol_moves;
 use super::types::ChangeKind;
+use super::union_driver::{UnionDriver, UnionDriverSet};
 
 /// Why a shared path could not be auto-resolved.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -77,6 +89,17 @@ pub enum ConflictReason {
         /// An example path under the directory side (for diagnostics).
         dir_child_example: PathBuf,
     },
+    /// A migration added by this workspace breaks the migration chain (see
+    /// [`super::migrations`]): its sequence number is already taken, or
+    /// workspaces pointed the configured "latest migration" file at
+    /// different migrations.
+    MigrationOrder {
+        /// The colliding sequence number as written in the file name;
+        /// `None` for a divergent "latest migration" file.
+        sequence: Option<String>,
+        /// Migrations that already hold the sequence number.
+        taken_by: Vec<PathBuf>,
+    },
 }
 
 impl std::fmt::Display for ConflictReason {
@@ -96,6 +119,23 @@ impl std::fmt::Display for ConflictReason {
                 file_side,
                 dir_child_example.display()
             ),
+            Self::MigrationOrder {
+                sequence: Some(sequence),
+                taken_by,
+            } => {
+                write!(f, "migration number {sequence} already used by ")?;
+                for (i, path) in taken_by.iter().enumerate() {
+                    let sep = if i == 0 { "" } else { ", " };
+                    write!(f, "{sep}'{}'", path.display())?;
+                }
+                Ok(())
+            }
+            Self::MigrationOrder { sequence: None, .. } => {
+                write!(
+                    f,
+                    "workspaces point the latest migration at different migrations"
+                )
+            }
         }
     }
 }
@@ -365,6 +405,69 @@ pub struct ResolveResult {
     pub resolved: Vec<ResolvedChange>,
     /// Paths that still need manual resolution.
     pub conflicts: Vec<ConflictRecord>,
+    /// Shared paths that diff3 could not merge but a structural resolver
+    /// did, recorded so the decision is auditable (e.g. in the merge plan).
+    pub auto_resolutions: Vec<AutoResolution>,
+}
+
+/// A shared path resolved automatically after diff3 reported a conflict.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AutoResolution {
+    /// Path relative to the repo root.
+    pub path: PathBuf,
+    /// Which resolver made the decision.
+    pub kind: AutoResolutionKind,
+    /// Workspaces whose edits were combined.
+    pub workspaces: Vec<WorkspaceId>,
+    /// One line per combined element, e.g. each unioned import.
+    pub details: Vec<String>,
+}
+
+/// A finding that does not block the merge, surfaced in the merge plan.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergeWarning {
+    /// Path relative to the repo root.
+    pub path: PathBuf,
+    /// Workspaces whose edits the warning is about.
+    pub workspaces: Vec<WorkspaceId>,
+    /// What was found, e.g. a [`ModelConflictReason::signature_drift`].
+    pub reason: ModelConflictReason,
+}
+
+/// The resolver behind an [`AutoResolution`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutoResolutionKind {
+    /// Import/use statements added by several workspaces were unioned into
+    /// the file's import groups.
+    ImportUnion,
+    /// A symbol one workspace moved to another file received another
+    /// workspace's edits at its new location.
+    SymbolMove,
+    /// Sides were normalized (whitespace, line endings, formatter) before
+    /// being compared and merged.
+    Normalized,
+    /// The same conflict was resolved before; the recorded resolution was
+    /// replayed (see [`super::rerere`]).
+    Rerere,
+    /// A configured union driver (`append`, `union-dedupe`, `union-sorted`)
+    /// combined the sides' conflicting hunks (see [`super::union_driver`]).
+    UnionDriver,
+    /// Migrations whose sequence numbers collided were renumbered after the
+    /// existing ones (see [`super::migrations`]).
+    MigrationRenumber,
+}
+
+impl std::fmt::Display for AutoResolutionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ImportUnion => write!(f, "import_union"),
+            Self::SymbolMove => write!(f, "symbol_move"),
+            Self::Normalized => write!(f, "normalized"),
+            Self::Rerere => write!(f, "rerere"),
+            Self::UnionDriver => write!(f, "union_driver"),
+            Self::MigrationRenumber => write!(f, "migration_renumber"),
+        }
+    }
 }
 
 impl ResolveResult {
@@ -389,6 +492,8 @@ pub enum ResolveError {
         /// Exit code if available.
         exit_code: Option<i32>,
     },
+    /// A required `external` merge driver failed.
+    ExternalDriver(ExternalDriverError),
 }
 
 impl std::fmt::Display for ResolveError {
@@ -409,16 +514,17 @@ impl std::fmt::Display for ResolveError {
                 }
                 Ok(())
             }
+            Self::ExternalDriver(e) => write!(f, "{e}"),
         }
     }
 }
 
 impl std::error::Error for ResolveError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        if let Self::Io(e) = self {
-            Some(e)
-        } else {
-            None
+        match self {
+            Self::Io(e) => Some(e),
+            Self::ExternalDriver(e) => Some(e),
+            Self::GitCommand { .. } => None,
         }
     }
 }
@@ -491,7 +597,14 @@ pub fn resolve_partition(
     partition: &PartitionResult,
     base_contents: &BTreeMap<PathBuf, Vec<u8>>,
 ) -> Result<ResolveResult, ResolveError> {
-    resolve_partition_with_attrs(partition, base_contents, None)
+    resolve_partition_with_attrs(
+        partition,
+        base_contents,
+        None,
+        &ExternalDriverSet::default(),
+        &UnionDriverSet::default(),
+        &Normalizer::default(),
+    )
 }
 
 /// Resolve all paths in a partition result, honoring `.gitattributes` merge
@@ -499,14 +612,22 @@ pub fn resolve_partition(
 /// is provided.
 ///
 /// Pass `None` for `attrs` to get the default diff3-only behavior.
+///
+/// Shared paths matched by an `external` or union driver (`append`,
+/// `union-dedupe`, `union-sorted`) are merged by that driver instead. Shared
+/// paths that conflict are retried on sides normalized by `normalizer`.
 #[allow(clippy::missing_errors_doc)]
 pub fn resolve_partition_with_attrs(
     partition: &PartitionResult,
     base_contents: &BTreeMap<PathBuf, Vec<u8>>,
     attrs: Option<&maw_lfs::AttrsMatcher>,
+    external_drivers: &ExternalDriverSet,
+    union_drivers: &UnionDriverSet,
+    normalizer: &Normalizer,
 ) -> Result<ResolveResult, ResolveError> {
     let mut resolved: Vec<ResolvedChange> = Vec::new();
     let mut conflicts: Vec<ConflictRecord> = Vec::new();
+    let mut auto_resolutions: Vec<AutoResolution> = Vec::new();
 
     // bn-2dy1: D/F clash paths must be emitted as FileDirectory conflicts
     // rather than resolved normally. Build a skip-set of all participating paths.
@@ -554,8 +675,22 @@ pub fn resolve_partition_with_attrs(
         }
 
         let base = base_contents.get(path).cloned();
-        match resolve_shared_path(path, entries, base.as_deref(), attrs)? {
+        let external = external_drivers.for_path(path);
+        let union = union_drivers.for_path(path);
+        match resolve_shared_path(
+            path,
+            entries,
+            base.as_deref(),
+            attrs,
+            external,
+            union,
+            normalizer,
+        )? {
             SharedOutcome::Resolved(change) => resolved.push(change),
+            SharedOutcome::AutoResolved(change, records) => {
+                resolved.push(change);
+                auto_resolutions.extend(records);
+            }
             SharedOutcome::Conflict(conflict) => conflicts.push(conflict),
         }
     }
@@ -566,6 +701,7 @@ pub fn resolve_partition_with_attrs(
     Ok(ResolveResult {
         resolved,
         conflicts,
+        auto_resolutions,
     })
 }
 
@@ -586,11 +722,22 @@ pub fn resolve_partition_with_ast(
     base_contents: &BTreeMap<PathBuf, Vec<u8>>,
     ast_config: &AstMergeConfig,
 ) -> Result<ResolveResult, ResolveError> {
-    resolve_partition_with_ast_and_attrs(partition, base_contents, ast_config, None)
+    resolve_partition_with_ast_and_attrs(
+        partition,
+        base_contents,
+        ast_config,
+        None,
+        &ExternalDriverSet::default(),
+        &UnionDriverSet::default(),
+        &Normalizer::default(),
+    )
 }
 
 /// Like [`resolve_partition_with_ast`] but also honors `.gitattributes` merge
 /// drivers (`union`, `ours`, `binary`) when an attrs matcher is provided.
+///
+/// Shared paths matched by an `external` or union merge driver are handed to
+/// that driver before any built-in merge layer runs.
 #[cfg(feature = "ast-merge")]
 #[allow(clippy::missing_errors_doc)]
 pub fn resolve_partition_with_ast_and_attrs(
@@ -598,9 +745,19 @@ pub fn resolve_partition_with_ast_and_attrs(
     base_contents: &BTreeMap<PathBuf, Vec<u8>>,
     ast_config: &AstMergeConfig,
     attrs: Option<&maw_lfs::AttrsMatcher>,
+    external_drivers: &ExternalDriverSet,
+    union_drivers: &UnionDriverSet,
+    normalizer: &Normalizer,
 ) -> Result<ResolveResult, ResolveError> {
     let mut resolved: Vec<ResolvedChange> = Vec::new();
     let mut conflicts: Vec<ConflictRecord> = Vec::new();
+    let mut auto_resolutions: Vec<AutoResolution> = Vec::new();
+
+    // Re-target edits to symbols another workspace moved to a different file.
+    let moves = apply_symbol_moves(partition, base_contents, ast_config)?;
+    let partition: &PartitionResult = &moves.partition;
+    conflicts.extend(moves.conflicts);
+    auto_resolutions.extend(moves.auto_resolutions);
 
     // bn-2dy1: D/F clash paths must be emitted as FileDirectory conflicts.
     let df_clash_skip = partition.df_clash_paths();
@@ -645,8 +802,23 @@ pub fn resolve_partition_with_ast_and_attrs(
         }
 
         let base = base_contents.get(path).cloned();
-        match resolve_shared_path_with_ast(path, entries, base.as_deref(), ast_config, attrs)? {
+        let external = external_drivers.for_path(path);
+        let union = union_drivers.for_path(path);
+        match resolve_shared_path_with_ast(
+            path,
+            entries,
+            base.as_deref(),
+            ast_config,
+            attrs,
+            external,
+            union,
+            normalizer,
+        )? {
             SharedOutcome::Resolved(change) => resolved.push(change),
+            SharedOutcome::AutoResolved(change, records) => {
+                resolved.push(change);
+                auto_resolutions.extend(records);
+            }
             SharedOutcome::Conflict(conflict) => conflicts.push(conflict),
         }
     }
@@ -657,11 +829,14 @@ pub fn resolve_partition_with_ast_and_attrs(
     Ok(ResolveResult {
         resolved,
         conflicts,
+        auto_resolutions,
     })
 }
 
 enum SharedOutcome {
     Resolved(ResolvedChange),
+    /// Resolved by merge layers whose decisions are recorded.
+    AutoResolved(ResolvedChange, Vec<AutoResolution>),
     Conflict(ConflictRecord),
 }
 
@@ -778,12 +953,126 @@ fn gitattr_driver_merge(
     Ok(None)
 }
 
+/// Merge a shared path with its configured union driver.
+///
+/// Returns `None` — leaving the path to the normal pipeline — when a side
+/// deletes the path (a modify/delete conflict is not a union) or the content
+/// is not text. Combining conflicting hunks is recorded as an
+/// auto-resolution so the decision shows up in the merge plan.
+#[cfg(not(kani))]
+fn union_driver_merge(
+    path: &Path,
+    entries: &[PathEntry],
+    base: Option<&[u8]>,
+    driver: &UnionDriver,
+) -> Option<SharedOutcome> {
+    let sides: Vec<&[u8]> = entries
+        .iter()
+        .map(|entry| entry.content.as_deref())
+        .collect::<Option<_>>()?;
+    let merged = super::union_driver::union_merge(driver.strategy, base, &sides)?;
+    let change = ResolvedChange::Upsert {
+        path: path.to_path_buf(),
+        content: merged.content,
+    };
+    if merged.combined_hunks == 0 {
+        return Some(SharedOutcome::Resolved(change));
+    }
+    Some(SharedOutcome::AutoResolved(
+        change,
+        vec![AutoResolution {
+            path: path.to_path_buf(),
+            kind: AutoResolutionKind::UnionDriver,
+            workspaces: entries.iter().map(|e| e.workspace_id.clone()).collect(),
+            details: vec![format!(
+                "{}: combined {} hunk(s) (match '{}')",
+                driver.strategy.name(),
+                merged.combined_hunks,
+                driver.match_glob
+            )],
+        }],
+    ))
+}
+
+#[cfg(kani)]
+fn union_driver_merge(
+    _path: &Path,
+    _entries: &[PathEntry],
+    _base: Option<&[u8]>,
+    _driver: &UnionDriver,
+) -> Option<SharedOutcome> {
+    None
+}
+
+/// Hand a shared path's `variants` to an `external` driver. `None` means a
+/// non-required driver failed and the path falls back to the normal merge.
+fn external_driver_merge(
+    path: &Path,
+    entries: &[PathEntry],
+    variants: &[Vec<u8>],
+    base: Option<&[u8]>,
+    driver: &ExternalDriver,
+) -> Result<Option<SharedOutcome>, ResolveError> {
+    let sides: Vec<(WorkspaceId, Vec<u8>)> = entries
+        .iter()
+        .zip(variants)
+        .map(|(entry, content)| (entry.workspace_id.clone(), content.clone()))
+        .collect();
+    match run_external_driver(driver, path, base, &sides) {
+        Ok(ExternalMerge::Merged(content)) => {
+            Ok(Some(SharedOutcome::Resolved(ResolvedChange::Upsert {
+                path: path.to_path_buf(),
+                content,
+            })))
+        }
+        Ok(ExternalMerge::Conflict(atoms)) => Ok(Some(SharedOutcome::Conflict(conflict_record(
+            path,
+            entries,
+            base,
+            ConflictReason::Diff3Conflict,
+            atoms,
+        )))),
+        Err(e) if driver.required => Err(ResolveError::ExternalDriver(e)),
+        Err(e) => {
+            tracing::warn!(
+                "external merge driver for '{}' failed, falling back to normal merge: {e}",
+                path.display()
+            );
+            Ok(None)
+        }
+    }
+}
+
 fn resolve_shared_path(
     path: &Path,
     entries: &[PathEntry],
     base: Option<&[u8]>,
     attrs: Option<&maw_lfs::AttrsMatcher>,
+    external: Option<&ExternalDriver>,
+    union: Option<&UnionDriver>,
+    normalizer: &Normalizer,
 ) -> Result<SharedOutcome, ResolveError> {
+    // A configured `external` driver claims modified/added paths whose sides
+    // differ; deletions and identical sides go through the normal algebra.
+    if let Some(driver) = external
+        && !all_blobs_equal(entries)
+        && let Some(variants) = entries
+            .iter()
+            .map(|e| e.content.clone())
+            .collect::<Option<Vec<_>>>()
+        && !all_equal(&variants)
+        && let Some(outcome) = external_driver_merge(path, entries, &variants, base, driver)?
+    {
+        return Ok(outcome);
+    }
+
+    // A configured union driver claims the path before `.gitattributes`.
+    if let Some(driver) = union
+        && let Some(outcome) = union_driver_merge(path, entries, base, driver)
+    {
+        return Ok(outcome);
+    }
+
     // Check .gitattributes merge driver before running the generic algebra.
     // For union/ours, we short-circuit through gix-merge directly since the
     // result is always a clean resolution.
@@ -818,7 +1107,44 @@ fn resolve_shared_path(
         }
     }
 
-    // Default diff3 path.
+    let outcome = merge_entries(path, entries, base)?;
+    if !matches!(outcome, SharedOutcome::Conflict(_)) || entries.iter().any(PathEntry::is_deletion)
+    {
+        return Ok(outcome);
+    }
+    let Some(variants) = entries
+        .iter()
+        .map(|e| e.content.clone())
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Ok(outcome);
+    };
+
+    // Optional whitespace / line-ending / formatter normalization, only for
+    // paths that do not merge as written.
+    Ok(
+        merge_normalized(path, entries, &variants, base, normalizer, |sides, base| {
+            let retry: Vec<PathEntry> = entries
+                .iter()
+                .zip(sides)
+                .map(|(entry, content)| PathEntry {
+                    content: Some(content.clone()),
+                    blob: None,
+                    ..entry.clone()
+                })
+                .collect();
+            merge_entries(path, &retry, base)
+        })?
+        .unwrap_or(outcome),
+    )
+}
+
+/// Default diff3 path: run `entries` through the generic merge algebra.
+fn merge_entries(
+    path: &Path,
+    entries: &[PathEntry],
+    base: Option<&[u8]>,
+) -> Result<SharedOutcome, ResolveError> {
     // Use blob OID equality as a fast path before falling into the generic
     // algebra. The generic resolve_entries only sees content bytes, so we
     // check OID equality here and feed the result through.
@@ -919,16 +1245,20 @@ fn resolve_shared_path(
 
 /// Resolve a shared path with AST-aware merge as fallback after diff3.
 ///
-/// Pipeline: hash eq → diff3 → AST merge → conflict.
+/// Pipeline: hash eq → external driver → gitattrs → diff3 → AST merge →
+/// normalized retry → conflict.
 /// If AST merge is not enabled for this path's language, falls back to diff3 conflict.
 #[cfg(feature = "ast-merge")]
-#[allow(clippy::too_many_lines)]
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
 fn resolve_shared_path_with_ast(
     path: &Path,
     entries: &[PathEntry],
     base: Option<&[u8]>,
     ast_config: &AstMergeConfig,
     attrs: Option<&maw_lfs::AttrsMatcher>,
+    external: Option<&ExternalDriver>,
+    union: Option<&UnionDriver>,
+    normalizer: &Normalizer,
 ) -> Result<SharedOutcome, ResolveError> {
     // delete/delete[/...] => resolved delete
     if entries.iter().all(PathEntry::is_deletion) {
@@ -973,6 +1303,20 @@ fn resolve_shared_path_with_ast(
         }));
     }
 
+    // A configured `external` driver replaces every merge layer below.
+    if let Some(driver) = external
+        && let Some(outcome) = external_driver_merge(path, entries, &variants, base, driver)?
+    {
+        return Ok(outcome);
+    }
+
+    // A configured union driver likewise replaces the layers below.
+    if let Some(driver) = union
+        && let Some(outcome) = union_driver_merge(path, entries, base, driver)
+    {
+        return Ok(outcome);
+    }
+
     // Check .gitattributes merge driver before any line/AST merging.
     // `merge=union` and `merge=ours` are always clean by construction;
     // `merge=binary` refuses text merging entirely.
@@ -1000,6 +1344,78 @@ fn resolve_shared_path_with_ast(
         PathMergeStrategy::Diff3 => {}
     }
 
+    let outcome = merge_text_variants(path, entries, &variants, base, ast_config)?;
+    if !matches!(outcome, SharedOutcome::Conflict(_)) {
+        return Ok(outcome);
+    }
+
+    // Optional whitespace / line-ending / formatter normalization, only for
+    // paths that do not merge as written.
+    Ok(
+        merge_normalized(path, entries, &variants, base, normalizer, |sides, base| {
+            merge_text_variants(path, entries, sides, base, ast_config)
+        })?
+        .unwrap_or(outcome),
+    )
+}
+
+/// Retry a conflicting shared path on its normalized sides, recording the
+/// normalization as an auto-resolution when the path resolves.
+///
+/// Sides that agree once normalized resolve to the first side as written;
+/// sides that still differ are merged in normalized form by `merge`. `None`
+/// means normalization did not help and the original conflict stands.
+fn merge_normalized(
+    path: &Path,
+    entries: &[PathEntry],
+    variants: &[Vec<u8>],
+    base: Option<&[u8]>,
+    normalizer: &Normalizer,
+    merge: impl FnOnce(&[Vec<u8>], Option<&[u8]>) -> Result<SharedOutcome, ResolveError>,
+) -> Result<Option<SharedOutcome>, ResolveError> {
+    let Some(sides) = normalizer.normalize(path, base, variants) else {
+        return Ok(None);
+    };
+    let record = AutoResolution {
+        path: path.to_path_buf(),
+        kind: AutoResolutionKind::Normalized,
+        workspaces: sides
+            .changed
+            .iter()
+            .map(|&i| entries[i].workspace_id.clone())
+            .collect(),
+        details: sides.steps.clone(),
+    };
+    if all_equal(&sides.variants) {
+        return Ok(Some(SharedOutcome::AutoResolved(
+            ResolvedChange::Upsert {
+                path: path.to_path_buf(),
+                content: variants[0].clone(),
+            },
+            vec![record],
+        )));
+    }
+    Ok(match merge(&sides.variants, sides.base.as_deref())? {
+        SharedOutcome::Resolved(change) => Some(SharedOutcome::AutoResolved(change, vec![record])),
+        SharedOutcome::AutoResolved(change, mut records) => {
+            records.insert(0, record);
+            Some(SharedOutcome::AutoResolved(change, records))
+        }
+        SharedOutcome::Conflict(_) => None,
+    })
+}
+
+/// Merge the (non-identical, non-deleted) text `variants` of a shared path:
+/// diff3 fold, then structured/AST merge, then conflict.
+#[cfg(feature = "ast-merge")]
+#[allow(clippy::too_many_lines)]
+fn merge_text_variants(
+    path: &Path,
+    entries: &[PathEntry],
+    variants: &[Vec<u8>],
+    base: Option<&[u8]>,
+    ast_config: &AstMergeConfig,
+) -> Result<SharedOutcome, ResolveError> {
     // Without base, differing non-delete variants are add/add.
     let Some(base_bytes) = base else {
         let reason = if entries.iter().all(|e| matches!(e.kind, ChangeKind::Added)) {
@@ -1066,20 +1482,38 @@ fn resolve_shared_path_with_ast(
     let theirs_label = trigger_ws.to_string();
     participants.push(trigger_ws);
 
-    // diff3 failed. Try AST merge if enabled for this language.
-    if let Some(lang) = ast_config.is_enabled_for(path) {
+    // diff3 failed. Try a key-path merge for structured data files, or an
+    // AST merge if enabled for this language.
+    let structured_format = ast_config.structured_format_for(path);
+    let ast_language = ast_config.is_enabled_for(path);
+    if structured_format.is_some() || ast_language.is_some() {
         let ast_variants: Vec<_> = entries
             .iter()
             .zip(variants.iter())
             .map(|(entry, content)| (entry.workspace_id.clone(), content.clone()))
             .collect();
 
-        match try_ast_merge_with_config(base_bytes, &ast_variants, lang, ast_config) {
+        let (layered, report) = match (structured_format, ast_language) {
+            (Some(format), _) => (
+                try_structured_merge(base_bytes, &ast_variants, format),
+                AstMergeReport::default(),
+            ),
+            (None, Some(lang)) => {
+                try_ast_merge_with_report(base_bytes, &ast_variants, lang, ast_config)
+            }
+            (None, None) => (AstMergeResult::Unsupported, AstMergeReport::default()),
+        };
+
+        match layered {
             AstMergeResult::Clean(ast_merged) => {
-                return Ok(SharedOutcome::Resolved(ResolvedChange::Upsert {
+                let change = ResolvedChange::Upsert {
                     path: path.to_path_buf(),
                     content: ast_merged,
-                }));
+                };
+                return Ok(match import_union_record(path, &report) {
+                    Some(record) => SharedOutcome::AutoResolved(change, vec![record]),
+                    None => SharedOutcome::Resolved(change),
+                });
             }
             AstMergeResult::Conflict { atoms } => {
                 // Use AST conflict atoms instead of diff3 atoms for better
@@ -1138,6 +1572,40 @@ fn resolve_shared_path_with_ast(
     )))
 }
 
+/// Describe the imports an AST merge unioned, if any.
+#[cfg(feature = "ast-merge")]
+fn import_union_record(path: &Path, report: &AstMergeReport) -> Option<AutoResolution> {
+    if report.merged_imports.is_empty() {
+        return None;
+    }
+    let mut workspaces: Vec<WorkspaceId> = report
+        .merged_imports
+        .iter()
+        .flat_map(|import| import.workspaces.iter().cloned())
+        .collect();
+    workspaces.sort();
+    workspaces.dedup();
+    let details = report
+        .merged_imports
+        .iter()
+        .map(|import| {
+            let from = import
+                .workspaces
+                .iter()
+                .map(WorkspaceId::as_str)
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{} ({from})", import.statement)
+        })
+        .collect();
+    Some(AutoResolution {
+        path: path.to_path_buf(),
+        kind: AutoResolutionKind::ImportUnion,
+        workspaces,
+        details,
+    })
+}
+
 /// bn-ztu6: pairwise exclusion probe.
 ///
 /// Of the workspaces folded cleanly into the `ours` composite before the
@@ -1421,6 +1889,33 @@ fn diff3_merge_bytes(
     }
 }
 
+/// Three-way merge of a text fragment (e.g. one item's source).
+///
+/// Returns `Ok(None)` when the edits overlap.
+#[cfg(feature = "ast-merge")]
+pub(super) fn merge_fragment(
+    base: &[u8],
+    ours: &[u8],
+    theirs: &[u8],
+) -> Result<Option<Vec<u8>>, ResolveError> {
+    // git merge-file is line-based; give every side a final newline so the
+    // last line of the fragment merges like any other.
+    let with_newline = |bytes: &[u8]| [bytes, b"\n"].concat();
+    match diff3_merge_bytes(
+        &with_newline(base),
+        &with_newline(ours),
+        &with_newline(theirs),
+    )? {
+        Diff3Outcome::Clean(mut merged) => {
+            if merged.last() == Some(&b'\n') {
+                merged.pop();
+            }
+            Ok(Some(merged))
+        }
+        Diff3Outcome::Conflict { .. } => Ok(None),
+    }
+}
+
 /// Retry a diff3 merge after normalizing shifted block positions.
 ///
 /// Returns:
@@ -1623,6 +2118,11 @@ fn split_blocks(text: &str) -> Vec<String> {
 /// <context lines>
 /// ```
 ///
+/// The other `[merge] conflict_style` layouts parse too: `merge` blocks
+/// (no `|||||||` section, so the base is taken as empty), `zdiff3` blocks
+/// (diff3 shape), and jj snapshot blocks, where the sides follow
+/// `+++++++` lines and the base a `-------` line.
+///
 /// `ws_ours` and `ws_theirs` are the workspace ID strings used to label each
 /// side's [`AtomEdit`].
 #[derive(Clone, Copy, PartialEq)]
@@ -1655,19 +2155,37 @@ pub fn parse_diff3_atoms(
 
     let mut atoms: Vec<ConflictAtom> = Vec::new();
 
+    // Whether the current block uses jj snapshot markers.
+    let mut jj = false;
+
     for line in &lines {
         if line.starts_with("<<<<<<<") {
             // Start of a new conflict block.
             state = Diff3ParseState::Ours;
+            jj = false;
             block_base_start = base_line;
             ours_lines.clear();
             base_lines.clear();
             theirs_lines.clear();
-        } else if line.starts_with("|||||||") && state == Diff3ParseState::Ours {
+        } else if line.starts_with("+++++++")
+            && state == Diff3ParseState::Ours
+            && !jj
+            && ours_lines.is_empty()
+        {
+            // jj: the first side's label line.
+            jj = true;
+        } else if jj && line.starts_with("-------") && state == Diff3ParseState::Ours {
+            state = Diff3ParseState::Base;
+        } else if jj && line.starts_with("+++++++") && state == Diff3ParseState::Base {
+            state = Diff3ParseState::Theirs;
+        } else if !jj && line.starts_with("|||||||") && state == Diff3ParseState::Ours {
             // Transition: ours → base section.
             state = Diff3ParseState::Base;
-        } else if *line == "=======" && state == Diff3ParseState::Base {
-            // Transition: base → theirs section.
+        } else if !jj
+            && *line == "======="
+            && matches!(state, Diff3ParseState::Ours | Diff3ParseState::Base)
+        {
+            // Transition: base (or ours, in `merge` style) → theirs section.
             state = Diff3ParseState::Theirs;
         } else if line.starts_with(">>>>>>>") && state == Diff3ParseState::Theirs {
             // End of conflict block — build the atom.
@@ -1819,6 +2337,122 @@ mod tests {
         assert_eq!(result.conflicts[0].reason, ConflictReason::Diff3Conflict);
     }
 
+    #[test]
+    fn union_driver_combines_overlapping_appends() {
+        use crate::config::{MergeDriver, MergeDriverKind};
+
+        let partition = shared_only(
+            "CHANGELOG.md",
+            vec![
+                entry("ws-a", ChangeKind::Modified, Some(b"- one\n- zeta\n")),
+                entry("ws-b", ChangeKind::Modified, Some(b"- one\n- alpha\n")),
+            ],
+        );
+        let mut base = BTreeMap::new();
+        base.insert(PathBuf::from("CHANGELOG.md"), b"- one\n".to_vec());
+        let drivers = UnionDriverSet::from_drivers(&[MergeDriver {
+            match_glob: "CHANGELOG.md".to_owned(),
+            kind: MergeDriverKind::UnionSorted,
+            command: None,
+            required: false,
+            timeout_seconds: 60,
+        }]);
+
+        let result = resolve_partition_with_attrs(
+            &partition,
+            &base,
+            None,
+            &ExternalDriverSet::default(),
+            &drivers,
+            &Normalizer::default(),
+        )
+        .expect("operation should succeed");
+        assert!(result.is_clean(), "conflicts: {:?}", result.conflicts);
+        assert_eq!(upsert_content(&result), b"- one\n- alpha\n- zeta\n");
+        assert_eq!(result.auto_resolutions.len(), 1);
+        assert_eq!(
+            result.auto_resolutions[0].kind,
+            AutoResolutionKind::UnionDriver
+        );
+    }
+
+    #[test]
+    fn external_driver_runs_without_ast_merge() {
+        use crate::config::{MergeDriver, MergeDriverKind};
+
+        let partition = shared_only(
+            "db/schema.sql",
+            vec![
+                entry("ws-a", ChangeKind::Modified, Some(b"a\n")),
+                entry("ws-b", ChangeKind::Modified, Some(b"b\n")),
+                entry("ws-c", ChangeKind::Modified, Some(b"c\n")),
+            ],
+        );
+        let mut base = BTreeMap::new();
+        base.insert(PathBuf::from("db/schema.sql"), b"base\n".to_vec());
+        let drivers = ExternalDriverSet::from_drivers(&[MergeDriver {
+            match_glob: "db/*.sql".to_owned(),
+            kind: MergeDriverKind::External,
+            command: Some("cat sides/* > %A".to_owned()),
+            required: true,
+            timeout_seconds: 60,
+        }]);
+
+        let result = resolve_partition_with_attrs(
+            &partition,
+            &base,
+            None,
+            &drivers,
+            &UnionDriverSet::default(),
+            &Normalizer::default(),
+        )
+        .expect("operation should succeed");
+        assert!(result.is_clean(), "conflicts: {:?}", result.conflicts);
+        assert_eq!(upsert_content(&result), b"a\nb\nc\n");
+    }
+
+    #[test]
+    fn diff3_conflict_retries_on_normalized_sides() {
+        use crate::config::NormalizeConfig;
+
+        let partition = shared_only(
+            "notes.txt",
+            vec![
+                entry("ws-a", ChangeKind::Modified, Some(b"one\r\nTWO\r\n")),
+                entry("ws-b", ChangeKind::Modified, Some(b"one \nTWO\n")),
+            ],
+        );
+        let mut base = BTreeMap::new();
+        base.insert(PathBuf::from("notes.txt"), b"one\ntwo\n".to_vec());
+        let normalizer = Normalizer::from_config(
+            &NormalizeConfig {
+                line_endings: true,
+                whitespace: true,
+                ..NormalizeConfig::default()
+            },
+            Path::new("."),
+        );
+
+        let plain = resolve_partition(&partition, &base).expect("operation should succeed");
+        assert_eq!(plain.conflicts.len(), 1);
+
+        let result = resolve_partition_with_attrs(
+            &partition,
+            &base,
+            None,
+            &ExternalDriverSet::default(),
+            &UnionDriverSet::default(),
+            &normalizer,
+        )
+        .expect("operation should succeed");
+        assert!(result.is_clean(), "conflicts: {:?}", result.conflicts);
+        assert_eq!(upsert_content(&result), b"one\r\nTWO\r\n");
+        assert_eq!(
+            result.auto_resolutions[0].kind,
+            AutoResolutionKind::Normalized
+        );
+    }
+
     #[test]
     fn add_add_different_without_base_conflicts() {
         let partition = shared_only(
@@ -2425,6 +3059,24 @@ mod tests {
         assert!(atoms.is_empty(), "clean output should produce no atoms");
     }
 
+    /// Every `[merge] conflict_style` layout yields the same atom.
+    #[test]
+    fn parse_diff3_atoms_recognises_all_marker_styles() {
+        let diff3: &[u8] =
+            b"a\n<<<<<<< ours\nB1\n||||||| base\nb\n=======\nB2\n>>>>>>> theirs\nc\n";
+        for style in [
+            maw_git::merge::MarkerStyle::Merge,
+            maw_git::merge::MarkerStyle::Zdiff3,
+            maw_git::merge::MarkerStyle::Jj,
+        ] {
+            let restyled = maw_git::merge::restyle_markers(diff3, style);
+            let atoms = parse_diff3_atoms(&restyled, "alice", "bob");
+            assert_eq!(atoms.len(), 1, "{style:?}");
+            let contents: Vec<&str> = atoms[0].edits.iter().map(|e| e.content.as_str()).collect();
+            assert_eq!(contents, ["B1", "B2"], "{style:?}");
+        }
+    }
+
     /// K=2 workspace labels appear correctly in atom edits.
     #[test]
     fn diff3_atoms_carry_workspace_labels_k2() {
@@ -2843,6 +3495,328 @@ mod tests {
             assert!(sides.contains(&"z2"), "z2 must be in sides; got: {sides:?}");
             assert_eq!(sides.len(), 2, "exactly [z1, z2] expected; got: {sides:?}");
         }
+
+        /// Structured merge resolves disjoint key additions to the same JSON
+        /// object, which diff3 reports as an overlapping insertion.
+        #[test]
+        fn structured_merge_resolves_disjoint_json_keys() {
+            let base = b"{\n  \"dependencies\": {\n    \"a\": \"1\"\n  }\n}\n";
+            let ws_a = b"{\n  \"dependencies\": {\n    \"a\": \"1\",\n    \"b\": \"2\"\n  }\n}\n";
+            let ws_b = b"{\n  \"dependencies\": {\n    \"a\": \"1\",\n    \"c\": \"3\"\n  }\n}\n";
+
+            let partition = shared_rs(
+                "package.json",
+                vec![
+                    entry("ws-a", ChangeKind::Modified, Some(ws_a)),
+                    entry("ws-b", ChangeKind::Modified, Some(ws_b)),
+                ],
+            );
+            let mut base_map = BTreeMap::new();
+            base_map.insert(PathBuf::from("package.json"), base.to_vec());
+
+            let plain = resolve_partition(&partition, &base_map).expect("operation should succeed");
+            assert_eq!(plain.conflicts.len(), 1, "diff3 alone should conflict");
+
+            let ast_config = AstMergeConfig::all_languages();
+            let result = resolve_partition_with_ast(&partition, &base_map, &ast_config)
+                .expect("operation should succeed");
+            assert!(result.is_clean(), "conflicts: {:?}", result.conflicts);
+            let merged = match &result.resolved[0] {
+                ResolvedChange::Upsert { content, .. } => content,
+                _ => panic!("expected upsert"),
+            };
+            assert_eq!(
+                std::str::from_utf8(merged).expect("operation should succeed"),
+                "{\n  \"dependencies\": {\n    \"a\": \"1\",\n    \"b\": \"2\",\n    \"c\": \"3\"\n  }\n}\n"
+            );
+        }
+
+        /// Imports added on both sides at the top of a file are unioned, and
+        /// the resolution is recorded for the merge plan.
+        #[test]
+        fn ast_import_union_is_recorded_as_auto_resolution() {
+            let base = b"use std::fs;\nuse std::path::Path;\n\nfn main() {}\n";
+            let ws_a = b"use std::fmt;\nuse std::fs;\nuse std::path::Path;\n\nfn main() {}\n";
+            let ws_b = b"use std::env;\nuse std::fs;\nuse std::path::Path;\n\nfn main() {}\n";
+
+            let partition = shared_rs(
+                "src/main.rs",
+                vec![
+                    entry("ws-a", ChangeKind::Modified, Some(ws_a)),
+                    entry("ws-b", ChangeKind::Modified, Some(ws_b)),
+                ],
+            );
+            let mut base_map = BTreeMap::new();
+            base_map.insert(PathBuf::from("src/main.rs"), base.to_vec());
+
+            let plain = resolve_partition(&partition, &base_map).expect("operation should succeed");
+            assert_eq!(plain.conflicts.len(), 1, "diff3 alone should conflict");
+            assert!(plain.auto_resolutions.is_empty());
+
+            let ast_config = AstMergeConfig::all_languages();
+            let result = resolve_partition_with_ast(&partition, &base_map, &ast_config)
+                .expect("operation should succeed");
+            assert!(result.is_clean(), "conflicts: {:?}", result.conflicts);
+            let merged = match &result.resolved[0] {
+                ResolvedChange::Upsert { content, .. } => content,
+                _ => panic!("expected upsert"),
+            };
+            assert_eq!(
+                std::str::from_utf8(merged).expect("operation should succeed"),
+                "use std::env;\nuse std::fmt;\nuse std::fs;\nuse std::path::Path;\n\nfn main() {}\n"
+            );
+
+            assert_eq!(result.auto_resolutions.len(), 1);
+            let record = &result.auto_resolutions[0];
+            assert_eq!(record.path, PathBuf::from("src/main.rs"));
+            assert_eq!(record.kind, AutoResolutionKind::ImportUnion);
+            assert_eq!(record.workspaces, vec![ws("ws-a"), ws("ws-b")]);
+            assert_eq!(
+                record.details,
+                vec!["use std::env; (ws-b)", "use std::fmt; (ws-a)"]
+            );
+        }
+
+        /// A shared path matched by an `external` driver is merged by the
+        /// driver instead of diff3; a failing optional driver falls back.
+        #[cfg(unix)]
+        #[test]
+        fn external_driver_resolves_shared_path() {
+            use crate::config::{MergeDriver, MergeDriverKind};
+            use crate::merge::external_driver::ExternalDriverSet;
+
+            let partition = shared_rs(
+                "src/main.rs",
+                vec![
+                    entry("ws-a", ChangeKind::Modified, Some(b"a\n")),
+                    entry("ws-b", ChangeKind::Modified, Some(b"b\n")),
+                ],
+            );
+            let mut base_map = BTreeMap::new();
+            base_map.insert(PathBuf::from("src/main.rs"), b"base\n".to_vec());
+            let ast_config = AstMergeConfig::all_languages();
+            let drivers = |command: &str, required: bool| {
+                ExternalDriverSet::from_drivers(&[MergeDriver {
+                    match_glob: "src/*.rs".to_owned(),
+                    kind: MergeDriverKind::External,
+                    command: Some(command.to_owned()),
+                    required,
+                    timeout_seconds: 60,
+                }])
+            };
+
+            let merged = resolve_partition_with_ast_and_attrs(
+                &partition,
+                &base_map,
+                &ast_config,
+                None,
+                &drivers("cat %B >> %A", true),
+                &UnionDriverSet::default(),
+                &Normalizer::default(),
+            )
+            .expect("operation should succeed");
+            assert!(merged.is_clean(), "conflicts: {:?}", merged.conflicts);
+            match &merged.resolved[0] {
+                ResolvedChange::Upsert { content, .. } => assert_eq!(content, b"a\nb\n"),
+                other => panic!("expected upsert, got {other:?}"),
+            }
+
+            let fallback = resolve_partition_with_ast_and_attrs(
+                &partition,
+                &base_map,
+                &ast_config,
+                None,
+                &drivers("exit 7", false),
+                &UnionDriverSet::default(),
+                &Normalizer::default(),
+            )
+            .expect("operation should succeed");
+            assert_eq!(fallback.conflicts.len(), 1);
+
+            let err = resolve_partition_with_ast_and_attrs(
+                &partition,
+                &base_map,
+                &ast_config,
+                None,
+                &drivers("exit 7", true),
+                &UnionDriverSet::default(),
+                &Normalizer::default(),
+            )
+            .expect_err("required driver failure should error");
+            assert!(matches!(err, ResolveError::ExternalDriver(_)), "{err}");
+        }
+
+        /// With `[merge.normalize]`, sides differing only in line endings and
+        /// trailing whitespace merge, and the path is reported as normalized.
+        #[test]
+        fn normalized_sides_merge_and_are_reported() {
+            use crate::config::NormalizeConfig;
+
+            let base = b"one\ntwo\nthree\nfour\n";
+            let ws_a = b"one\r\ntwo  \r\nthree\r\nFOUR\r\n";
+            let ws_b = b"ONE\ntwo\nthree\t\nfour\n";
+            let partition = shared_rs(
+                "notes.txt",
+                vec![
+                    entry("ws-a", ChangeKind::Modified, Some(ws_a)),
+                    entry("ws-b", ChangeKind::Modified, Some(ws_b)),
+                ],
+            );
+            let mut base_map = BTreeMap::new();
+            base_map.insert(PathBuf::from("notes.txt"), base.to_vec());
+            let ast_config = AstMergeConfig::all_languages();
+
+            let plain = resolve_partition_with_ast(&partition, &base_map, &ast_config)
+                .expect("operation should succeed");
+            assert_eq!(plain.conflicts.len(), 1, "CRLF vs LF should conflict");
+
+            let normalizer = Normalizer::from_config(
+                &NormalizeConfig {
+                    line_endings: true,
+                    whitespace: true,
+                    ..NormalizeConfig::default()
+                },
+                Path::new("."),
+            );
+            let result = resolve_partition_with_ast_and_attrs(
+                &partition,
+                &base_map,
+                &ast_config,
+                None,
+                &ExternalDriverSet::default(),
+                &UnionDriverSet::default(),
+                &normalizer,
+            )
+            .expect("operation should succeed");
+            assert!(result.is_clean(), "conflicts: {:?}", result.conflicts);
+            assert_eq!(upsert_content(&result), b"ONE\ntwo\nthree\nFOUR\n");
+
+            assert_eq!(result.auto_resolutions.len(), 1);
+            let record = &result.auto_resolutions[0];
+            assert_eq!(record.kind, AutoResolutionKind::Normalized);
+            assert_eq!(record.workspaces, vec![ws("ws-a"), ws("ws-b")]);
+            assert_eq!(record.details, vec!["line endings", "trailing whitespace"]);
+        }
+
+        /// Normalization is only a fallback: a path that merges as written
+        /// keeps every side's bytes, trailing whitespace included.
+        #[test]
+        fn clean_merge_is_not_normalized() {
+            use crate::config::NormalizeConfig;
+
+            let base = b"one\ntwo\nthree\n";
+            let ws_a = b"ONE  \ntwo\nthree\n";
+            let ws_b = b"one\ntwo\nTHREE\t\n";
+            let partition = shared_rs(
+                "notes.txt",
+                vec![
+                    entry("ws-a", ChangeKind::Modified, Some(ws_a)),
+                    entry("ws-b", ChangeKind::Modified, Some(ws_b)),
+                ],
+            );
+            let mut base_map = BTreeMap::new();
+            base_map.insert(PathBuf::from("notes.txt"), base.to_vec());
+            let normalizer = Normalizer::from_config(
+                &NormalizeConfig {
+                    line_endings: true,
+                    whitespace: true,
+                    ..NormalizeConfig::default()
+                },
+                Path::new("."),
+            );
+            let result = resolve_partition_with_ast_and_attrs(
+                &partition,
+                &base_map,
+                &AstMergeConfig::all_languages(),
+                None,
+                &ExternalDriverSet::default(),
+                &UnionDriverSet::default(),
+                &normalizer,
+            )
+            .expect("operation should succeed");
+            assert!(result.is_clean(), "conflicts: {:?}", result.conflicts);
+            assert_eq!(upsert_content(&result), b"ONE  \ntwo\nTHREE\t\n");
+            assert!(result.auto_resolutions.is_empty());
+        }
+
+        /// Sides that only agree once normalized resolve to the first side as
+        /// written, not to the normalized bytes.
+        #[test]
+        fn normalized_agreement_keeps_an_original_side() {
+            use crate::config::NormalizeConfig;
+
+            let base = b"fn f() {}\n";
+            let ws_a = b"fn g() {}  \r\n";
+            let ws_b = b"fn g() {}\n";
+            let partition = shared_rs(
+                "notes.txt",
+                vec![
+                    entry("ws-a", ChangeKind::Modified, Some(ws_a)),
+                    entry("ws-b", ChangeKind::Modified, Some(ws_b)),
+                ],
+            );
+            let mut base_map = BTreeMap::new();
+            base_map.insert(PathBuf::from("notes.txt"), base.to_vec());
+            let normalizer = Normalizer::from_config(
+                &NormalizeConfig {
+                    line_endings: true,
+                    whitespace: true,
+                    ..NormalizeConfig::default()
+                },
+                Path::new("."),
+            );
+            let result = resolve_partition_with_ast_and_attrs(
+                &partition,
+                &base_map,
+                &AstMergeConfig::all_languages(),
+                None,
+                &ExternalDriverSet::default(),
+                &UnionDriverSet::default(),
+                &normalizer,
+            )
+            .expect("operation should succeed");
+            assert!(result.is_clean(), "conflicts: {:?}", result.conflicts);
+            assert_eq!(upsert_content(&result), ws_a);
+            assert_eq!(result.auto_resolutions.len(), 1);
+            assert_eq!(
+                result.auto_resolutions[0].kind,
+                AutoResolutionKind::Normalized
+            );
+        }
+
+        /// Structured merge conflicts carry the key path as the region name.
+        #[test]
+        fn structured_merge_conflict_names_key_path() {
+            let base = b"[package]\nversion = \"0.1.0\"\n";
+            let ws_a = b"[package]\nversion = \"0.2.0\"\n";
+            let ws_b = b"[package]\nversion = \"0.1.1\"\n";
+
+            let partition = shared_rs(
+                "Cargo.toml",
+                vec![
+                    entry("ws-a", ChangeKind::Modified, Some(ws_a)),
+                    entry("ws-b", ChangeKind::Modified, Some(ws_b)),
+                ],
+            );
+            let mut base_map = BTreeMap::new();
+            base_map.insert(PathBuf::from("Cargo.toml"), base.to_vec());
+
+            let ast_config = AstMergeConfig::all_languages();
+            let result = resolve_partition_with_ast(&partition, &base_map, &ast_config)
+                .expect("operation should succeed");
+            assert_eq!(result.conflicts.len(), 1);
+            let atoms = &result.conflicts[0].atoms;
+            assert_eq!(atoms.len(), 1);
+            match &atoms[0].base_region {
+                Region::AstNode {
+                    node_kind, name, ..
+                } => {
+                    assert_eq!(node_kind, "toml_key");
+                    assert_eq!(name.as_deref(), Some("package.version"));
+                }
+                other => panic!("expected AstNode region, got {other:?}"),
+            }
+        }
     }
 
     // -----------------------------------------------------------------------
@@ -0,0 +1,220 @@
+//! Merge scheduling: order N ready workspaces into conflict-free batches.
+//!
+//! Merging workspaces one at a time in an arbitrary order makes every later
+//! workspace absorb the earlier ones through sibling auto-rebase, and each
+//! predicted conflict between an earlier and a later workspace turns into a
+//! conflicted rebase. The scheduler uses a [`MergeWeather`] forecast to group
+//! workspaces into batches that are pairwise conflict-free (each batch runs
+//! as one N-way merge) and to order them so the workspaces that conflict with
+//! the most others merge last, where they absorb those conflicts once.
+//!
+//! Scheduling is greedy and deterministic:
+//!
+//! 1. Workspaces are ranked by predicted conflicts with the others, then by
+//!    overlapping paths (from PARTITION), then by name — fewest first.
+//! 2. Each workspace joins the first batch none of whose members it is
+//!    predicted to conflict with, or opens a new batch.
+//!
+//! Pairwise-clear is necessary but not sufficient for a clean N-way merge;
+//! the merge itself remains the source of truth and the caller stops at the
+//! first batch that conflicts.
+
+use super::weather::{MergeWeather, conflict_matrix};
+use crate::model::types::WorkspaceId;
+
+/// One group of workspaces to merge together.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergeBatch {
+    /// Workspaces in the batch, in scheduling order.
+    pub workspaces: Vec<WorkspaceId>,
+    /// Predicted conflicts between this batch and earlier batches — the
+    /// conflicts its members will hit when auto-rebased onto their merge.
+    pub conflicts_with_earlier: usize,
+}
+
+/// An ordered sequence of merge batches.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MergeSchedule {
+    /// Batches in merge order.
+    pub batches: Vec<MergeBatch>,
+}
+
+impl MergeSchedule {
+    /// Total predicted conflicts the schedule leaves for sibling rebases.
+    #[must_use]
+    pub fn predicted_conflicts(&self) -> usize {
+        self.batches.iter().map(|b| b.conflicts_with_earlier).sum()
+    }
+}
+
+/// Compute a merge schedule from a weather forecast.
+#[must_use]
+pub fn schedule(weather: &MergeWeather) -> MergeSchedule {
+    let conflicts = conflict_matrix(weather);
+    let overlaps = overlap_matrix(weather);
+    let n = weather.workspaces.len();
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by_key(|&i| {
+        (
+            conflicts[i].iter().sum::<usize>(),
+            overlaps[i].iter().sum::<usize>(),
+            &weather.workspaces[i],
+        )
+    });
+
+    let mut batches: Vec<Vec<usize>> = Vec::new();
+    for i in order {
+        let slot = batches
+            .iter()
+            .position(|batch| batch.iter().all(|&j| conflicts[i][j] == 0));
+        match slot {
+            Some(slot) => batches[slot].push(i),
+            None => batches.push(vec![i]),
+        }
+    }
+
+    let mut merged: Vec<usize> = Vec::new();
+    let batches = batches
+        .into_iter()
+        .map(|batch| {
+            let conflicts_with_earlier = batch
+                .iter()
+                .flat_map(|&i| merged.iter().map(move |&j| (i, j)))
+                .map(|(i, j)| conflicts[i][j])
+                .sum();
+            merged.extend(&batch);
+            MergeBatch {
+                workspaces: batch
+                    .into_iter()
+                    .map(|i| weather.workspaces[i].clone())
+                    .collect(),
+                conflicts_with_earlier,
+            }
+        })
+        .collect();
+
+    MergeSchedule { batches }
+}
+
+/// Shared-path counts per pair, indexed like [`MergeWeather::workspaces`].
+fn overlap_matrix(weather: &MergeWeather) -> Vec<Vec<usize>> {
+    let n = weather.workspaces.len();
+    let mut matrix = vec![vec![0; n]; n];
+    for forecast in &weather.pairs {
+        if let [a, b] = forecast.workspaces.as_slice()
+            && let Some(i) = weather.workspaces.iter().position(|ws| ws == a)
+            && let Some(j) = weather.workspaces.iter().position(|ws| ws == b)
+        {
+            matrix[i][j] = forecast.shared_paths.len();
+            matrix[j][i] = forecast.shared_paths.len();
+        }
+    }
+    matrix
+}
+
+#[cfg(test)]
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+mod tests {
+    use super::*;
+    use crate::merge::resolve::{ConflictReason, ConflictRecord};
+    use crate::merge::weather::Forecast;
+    use crate::model::types::EpochId;
+    use std::path::PathBuf;
+
+    fn ws(name: &str) -> WorkspaceId {
+        WorkspaceId::new(name).expect("operation should succeed")
+    }
+
+    fn names(batch: &MergeBatch) -> Vec<&str> {
+        batch.workspaces.iter().map(WorkspaceId::as_str).collect()
+    }
+
+    /// Weather over `names` where `conflicting` pairs conflict and
+    /// `overlapping` pairs share a path cleanly.
+    fn weather(
+        names: &[&str],
+        conflicting: &[(&str, &str)],
+        overlapping: &[(&str, &str)],
+    ) -> MergeWeather {
+        let workspaces: Vec<WorkspaceId> = names.iter().map(|n| ws(n)).collect();
+        let has = |set: &[(&str, &str)], a: &WorkspaceId, b: &WorkspaceId| {
+            set.iter().any(|(x, y)| {
+                (a.as_str() == *x && b.as_str() == *y) || (a.as_str() == *y && b.as_str() == *x)
+            })
+        };
+        let mut pairs = Vec::new();
+        for (i, a) in workspaces.iter().enumerate() {
+            for b in &workspaces[i + 1..] {
+                let conflict = has(conflicting, a, b);
+                let shared = conflict || has(overlapping, a, b);
+                pairs.push(Forecast {
+                    workspaces: vec![a.clone(), b.clone()],
+                    shared_paths: if shared {
+                        vec![PathBuf::from("shared.txt")]
+                    } else {
+                        Vec::new()
+                    },
+                    conflicts: if conflict {
+                        vec![ConflictRecord {
+                            path: PathBuf::from("shared.txt"),
+                            base: None,
+                            sides: Vec::new(),
+                            reason: ConflictReason::Diff3Conflict,
+                            atoms: Vec::new(),
+                        }]
+                    } else {
+                        Vec::new()
+                    },
+                    auto_resolutions: Vec::new(),
+                });
+            }
+        }
+        MergeWeather {
+            epoch: EpochId::new(&"a".repeat(40)).expect("operation should succeed"),
+            workspaces,
+            pairs,
+            all: None,
+        }
+    }
+
+    #[test]
+    fn clear_weather_is_one_batch() {
+        let schedule = schedule(&weather(&["a", "b", "c"], &[], &[("a", "b")]));
+        assert_eq!(schedule.batches.len(), 1);
+        assert_eq!(names(&schedule.batches[0]), vec!["c", "a", "b"]);
+        assert_eq!(schedule.predicted_conflicts(), 0);
+    }
+
+    #[test]
+    fn hub_workspace_merges_last() {
+        // hub conflicts with a and b; a and b are independent.
+        let schedule = schedule(&weather(
+            &["a", "b", "hub"],
+            &[("hub", "a"), ("hub", "b")],
+            &[],
+        ));
+        assert_eq!(schedule.batches.len(), 2);
+        assert_eq!(names(&schedule.batches[0]), vec!["a", "b"]);
+        assert_eq!(names(&schedule.batches[1]), vec!["hub"]);
+        assert_eq!(schedule.batches[1].conflicts_with_earlier, 2);
+        assert_eq!(schedule.predicted_conflicts(), 2);
+    }
+
+    #[test]
+    fn conflicting_pairs_never_share_a_batch() {
+        let schedule = schedule(&weather(
+            &["a", "b", "c", "d"],
+            &[("a", "b"), ("c", "d"), ("a", "c")],
+            &[],
+        ));
+        for batch in &schedule.batches {
+            let members = names(batch);
+            for (x, y) in [("a", "b"), ("c", "d"), ("a", "c")] {
+                assert!(!(members.contains(&x) && members.contains(&y)));
+            }
+        }
+        let total: usize = schedule.batches.iter().map(|b| b.workspaces.len()).sum();
+        assert_eq!(total, 4);
+    }
+}
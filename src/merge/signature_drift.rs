@@ -0,0 +1,521 @@
+//! Cross-file signature drift detection for Rust.
+//!
+//! When one workspace changes a function's signature in `lib.rs` and another
+//! adds a call to that function in `main.rs`, both patches touch different
+//! files and merge cleanly — but the result calls the function the old way.
+//! This module finds those pairs and reports them as
+//! [`ConflictReason::signature_drift`] warnings on the call site's path. They
+//! are shown in the merge plan and never block the merge.
+//!
+//! The check is deliberately lightweight:
+//!
+//! 1. A symbol index of the merged tree: the functions (free functions and
+//!    `impl` methods, qualified by `mod` and `impl` type) of every Rust file
+//!    the merge rewrites, compared with the base. Every other file is
+//!    unchanged from the epoch, so none of its signatures can have drifted.
+//!    A function whose merged signature text differs from the base is a
+//!    signature change, attributed to the workspaces whose own version of the
+//!    file made it.
+//! 2. Call expressions in each workspace's patch whose line does not appear
+//!    in the base are new call sites, matched to functions by their last path
+//!    segment (`foo(..)`, `a::foo(..)`, `x.foo(..)`).
+//! 3. A new call site in one workspace to a function whose signature another
+//!    workspace changed is drift — unless the call already passes as many
+//!    arguments as the merged signature takes and the old one did not.
+//!
+//! Only paths with AST merge enabled for Rust take part.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use tree_sitter::{Node, Parser};
+
+use super::ast_merge::{AstLanguage, AstMergeConfig};
+use super::build::ResolvedChange;
+use super::partition::{PartitionResult, PathEntry};
+use super::resolve::MergeWarning;
+use crate::model::conflict::ConflictReason;
+use crate::model::types::WorkspaceId;
+
+/// A function definition in one file version.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct FunctionDef {
+    /// Last path segment, used to match call sites.
+    name: String,
+    /// Signature text up to the body, whitespace-collapsed.
+    signature: String,
+    /// Parameter count, excluding `self`.
+    params: usize,
+}
+
+/// A call expression in one file version.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CallSite {
+    name: String,
+    args: usize,
+    /// 1-based line.
+    line: usize,
+    /// The trimmed source line, for telling new call sites from old ones.
+    line_text: String,
+}
+
+/// A function whose signature differs between the base and the merged tree.
+struct SignatureChange<'a> {
+    path: &'a Path,
+    symbol: String,
+    old: FunctionDef,
+    new: FunctionDef,
+    /// Workspaces whose own version of `path` changed the signature.
+    changed_by: Vec<&'a WorkspaceId>,
+}
+
+/// A call site one workspace added.
+struct NewCall<'a> {
+    path: &'a Path,
+    workspace: &'a WorkspaceId,
+    call: CallSite,
+}
+
+/// Find call sites added by one workspace to Rust functions whose signature
+/// another workspace changed in the merged tree.
+///
+/// `resolved` is the merge result (the merged tree's changes against the
+/// epoch). Returns one warning per (call site, changed signature), sorted by
+/// path. `base_contents` must hold the base of every changed path that
+/// existed at the epoch.
+#[must_use]
+pub fn detect_signature_drift(
+    partition: &PartitionResult,
+    resolved: &[ResolvedChange],
+    base_contents: &BTreeMap<PathBuf, Vec<u8>>,
+    config: &AstMergeConfig,
+) -> Vec<MergeWarning> {
+    let mut entries: BTreeMap<&Path, Vec<&PathEntry>> = BTreeMap::new();
+    for (path, entry) in &partition.unique {
+        entries.entry(path).or_default().push(entry);
+    }
+    for (path, shared) in &partition.shared {
+        entries.entry(path).or_default().extend(shared);
+    }
+
+    let signature_changes = signature_changes(resolved, &entries, base_contents, config);
+    if signature_changes.is_empty() {
+        return Vec::new();
+    }
+    let new_calls = new_calls(&entries, base_contents, config);
+
+    // A new call against a signature someone else changed drifted.
+    let mut warnings: Vec<(usize, MergeWarning)> = Vec::new();
+    for change in &signature_changes {
+        for new_call in &new_calls {
+            let call = &new_call.call;
+            if call.name != change.new.name || change.changed_by.contains(&new_call.workspace) {
+                continue;
+            }
+            if call.args == change.new.params && call.args != change.old.params {
+                continue;
+            }
+            let changed_by = change
+                .changed_by
+                .iter()
+                .map(|ws| format!("'{ws}'"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let changed_by = if changed_by.is_empty() {
+                "the merge".to_owned()
+            } else {
+                changed_by
+            };
+            let mut workspaces = vec![new_call.workspace.clone()];
+            workspaces.extend(change.changed_by.iter().map(|ws| (*ws).clone()));
+            warnings.push((
+                call.line,
+                MergeWarning {
+                    path: new_call.path.to_path_buf(),
+                    workspaces,
+                    reason: ConflictReason::signature_drift(format!(
+                        "call to `{}` on line {} targets `{}`, which {changed_by} changed to \
+                         `{}` in {}",
+                        change.symbol,
+                        call.line,
+                        change.old.signature,
+                        change.new.signature,
+                        change.path.display()
+                    )),
+                },
+            ));
+        }
+    }
+    warnings.sort_by(|(a_line, a), (b_line, b)| (&a.path, a_line).cmp(&(&b.path, b_line)));
+    warnings.into_iter().map(|(_, warning)| warning).collect()
+}
+
+/// Functions whose signature in the merged tree differs from the base.
+fn signature_changes<'a>(
+    resolved: &'a [ResolvedChange],
+    entries: &BTreeMap<&Path, Vec<&'a PathEntry>>,
+    base_contents: &BTreeMap<PathBuf, Vec<u8>>,
+    config: &AstMergeConfig,
+) -> Vec<SignatureChange<'a>> {
+    let mut changes = Vec::new();
+    for change in resolved {
+        let ResolvedChange::Upsert { path, content } = change else {
+            continue;
+        };
+        let Some(base) = base_contents.get(path) else {
+            continue;
+        };
+        if !is_rust(config, path) {
+            continue;
+        }
+        let (Some(base_index), Some(merged_index)) =
+            (FileIndex::parse(base), FileIndex::parse(content))
+        else {
+            continue;
+        };
+        let versions: Vec<(&WorkspaceId, FileIndex)> = entries
+            .get(path.as_path())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let index = FileIndex::parse(entry.content.as_deref()?)?;
+                Some((&entry.workspace_id, index))
+            })
+            .collect();
+        for (symbol, new) in &merged_index.functions {
+            let Some(old) = base_index.functions.get(symbol) else {
+                continue;
+            };
+            if old.signature == new.signature {
+                continue;
+            }
+            let changed_by = versions
+                .iter()
+                .filter(|(_, index)| {
+                    index
+                        .functions
+                        .get(symbol)
+                        .is_some_and(|def| def.signature != old.signature)
+                })
+                .map(|(workspace, _)| *workspace)
+                .collect();
+            changes.push(SignatureChange {
+                path,
+                symbol: symbol.clone(),
+                old: old.clone(),
+                new: new.clone(),
+                changed_by,
+            });
+        }
+    }
+    changes
+}
+
+/// Call sites each workspace's patch adds: calls on lines not in the base.
+fn new_calls<'a>(
+    entries: &BTreeMap<&'a Path, Vec<&'a PathEntry>>,
+    base_contents: &BTreeMap<PathBuf, Vec<u8>>,
+    config: &AstMergeConfig,
+) -> Vec<NewCall<'a>> {
+    let mut calls = Vec::new();
+    for (&path, path_entries) in entries {
+        if !is_rust(config, path) {
+            continue;
+        }
+        let base = base_contents.get(path).map_or(&[][..], Vec::as_slice);
+        let Some(base_index) = FileIndex::parse(base) else {
+            continue;
+        };
+        let base_lines: BTreeSet<&str> = base_index
+            .calls
+            .iter()
+            .map(|c| c.line_text.as_str())
+            .collect();
+        for entry in path_entries {
+            let Some(index) = entry.content.as_deref().and_then(FileIndex::parse) else {
+                continue;
+            };
+            calls.extend(
+                index
+                    .calls
+                    .into_iter()
+                    .filter(|call| !base_lines.contains(call.line_text.as_str()))
+                    .map(|call| NewCall {
+                        path,
+                        workspace: &entry.workspace_id,
+                        call,
+                    }),
+            );
+        }
+    }
+    calls
+}
+
+fn is_rust(config: &AstMergeConfig, path: &Path) -> bool {
+    config.is_enabled_for(path) == Some(AstLanguage::Rust)
+}
+
+/// Functions and call sites of one Rust file version.
+#[derive(Debug, Default)]
+struct FileIndex {
+    /// Functions by qualified name. Names defined more than once (e.g.
+    /// under different `cfg`s) are dropped as ambiguous.
+    functions: BTreeMap<String, FunctionDef>,
+    calls: Vec<CallSite>,
+}
+
+impl FileIndex {
+    /// Index `source`, or `None` if it does not parse cleanly.
+    fn parse(source: &[u8]) -> Option<Self> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&AstLanguage::Rust.tree_sitter_language())
+            .ok()?;
+        let tree = parser.parse(source, None)?;
+        if tree.root_node().has_error() {
+            return None;
+        }
+
+        let mut index = Self::default();
+        let mut ambiguous = BTreeSet::new();
+        index.visit(tree.root_node(), source, &[], &mut ambiguous);
+        for symbol in ambiguous {
+            index.functions.remove(&symbol);
+        }
+        Some(index)
+    }
+
+    fn visit(
+        &mut self,
+        node: Node<'_>,
+        source: &[u8],
+        scope: &[String],
+        ambiguous: &mut BTreeSet<String>,
+    ) {
+        let mut scope = scope.to_vec();
+        match node.kind() {
+            "mod_item" | "impl_item" => {
+                let field = if node.kind() == "mod_item" {
+                    "name"
+                } else {
+                    "type"
+                };
+                if let Some(name) = node.child_by_field_name(field) {
+                    scope.push(text(name, source).to_owned());
+                }
+            }
+            "function_item" => {
+                if let Some(def) = function_def(node, source) {
+                    let mut symbol = scope.join("::");
+                    if !symbol.is_empty() {
+                        symbol.push_str("::");
+                    }
+                    symbol.push_str(&def.name);
+                    if self.functions.insert(symbol.clone(), def).is_some() {
+                        ambiguous.insert(symbol);
+                    }
+                }
+            }
+            "call_expression" => {
+                if let Some(call) = call_site(node, source) {
+                    self.calls.push(call);
+                }
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.visit(child, source, &scope, ambiguous);
+        }
+    }
+}
+
+fn function_def(node: Node<'_>, source: &[u8]) -> Option<FunctionDef> {
+    let name = text(node.child_by_field_name("name")?, source).to_owned();
+    let end = node
+        .child_by_field_name("body")
+        .map_or_else(|| node.end_byte(), |body| body.start_byte());
+    let signature = std::str::from_utf8(&source[node.start_byte()..end])
+        .ok()?
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let parameters = node.child_by_field_name("parameters")?;
+    let mut cursor = parameters.walk();
+    let params = parameters
+        .named_children(&mut cursor)
+        .filter(|param| param.kind() == "parameter")
+        .count();
+    Some(FunctionDef {
+        name,
+        signature,
+        params,
+    })
+}
+
+fn call_site(node: Node<'_>, source: &[u8]) -> Option<CallSite> {
+    let mut function = node.child_by_field_name("function")?;
+    if function.kind() == "generic_function" {
+        function = function.child_by_field_name("function")?;
+    }
+    let name = match function.kind() {
+        "identifier" => function,
+        "scoped_identifier" => function.child_by_field_name("name")?,
+        "field_expression" => function.child_by_field_name("field")?,
+        _ => return None,
+    };
+    let arguments = node.child_by_field_name("arguments")?;
+    let mut cursor = arguments.walk();
+    let args = arguments
+        .named_children(&mut cursor)
+        .filter(|arg| !arg.kind().ends_with("comment"))
+        .count();
+    let row = node.start_position().row;
+    let line_text = std::str::from_utf8(source)
+        .ok()?
+        .lines()
+        .nth(row)
+        .unwrap_or_default()
+        .trim()
+        .to_owned();
+    Some(CallSite {
+        name: text(name, source).to_owned(),
+        args,
+        line: row + 1,
+        line_text,
+    })
+}
+
+fn text<'a>(node: Node<'_>, source: &'a [u8]) -> &'a str {
+    std::str::from_utf8(&source[node.start_byte()..node.end_byte()]).unwrap_or("")
+}
+
+#[cfg(test)]
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+mod tests {
+    use super::*;
+    use crate::merge::types::ChangeKind;
+
+    const LIB: &str = "pub fn parse(s: &str) -> u32 {\n    s.len() as u32\n}\n";
+    const MAIN: &str = "fn main() {\n    let _ = lib::parse(\"a\");\n}\n";
+
+    fn ws(name: &str) -> WorkspaceId {
+        WorkspaceId::new(name).expect("operation should succeed")
+    }
+
+    fn modified(name: &str, content: &str) -> PathEntry {
+        PathEntry::new(
+            ws(name),
+            ChangeKind::Modified,
+            Some(content.as_bytes().to_vec()),
+        )
+    }
+
+    fn upsert(path: &str, content: &str) -> ResolvedChange {
+        ResolvedChange::Upsert {
+            path: PathBuf::from(path),
+            content: content.as_bytes().to_vec(),
+        }
+    }
+
+    fn detect(lib: Option<&str>, main: &str) -> Vec<MergeWarning> {
+        let mut unique = vec![(PathBuf::from("src/main.rs"), modified("bob", main))];
+        let mut resolved = vec![upsert("src/main.rs", main)];
+        if let Some(lib) = lib {
+            unique.push((PathBuf::from("src/lib.rs"), modified("alice", lib)));
+            resolved.push(upsert("src/lib.rs", lib));
+        }
+        let partition = PartitionResult {
+            unique,
+            shared: vec![],
+            df_clashes: vec![],
+        };
+        detect_signature_drift(
+            &partition,
+            &resolved,
+            &base(),
+            &AstMergeConfig::all_languages(),
+        )
+    }
+
+    fn base() -> BTreeMap<PathBuf, Vec<u8>> {
+        BTreeMap::from([
+            (PathBuf::from("src/lib.rs"), LIB.as_bytes().to_vec()),
+            (PathBuf::from("src/main.rs"), MAIN.as_bytes().to_vec()),
+        ])
+    }
+
+    const NEW_LIB: &str = "pub fn parse(s: &str, strict: bool) -> u32 {\n    if strict { 0 } else { s.len() as u32 }\n}\n";
+
+    #[test]
+    fn new_call_to_changed_signature_is_reported() {
+        let main =
+            "fn main() {\n    let _ = lib::parse(\"a\");\n    let _ = lib::parse(\"b\");\n}\n";
+        let warnings = detect(Some(NEW_LIB), main);
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        assert_eq!(warnings[0].path, PathBuf::from("src/main.rs"));
+        assert_eq!(warnings[0].workspaces, vec![ws("bob"), ws("alice")]);
+        assert_eq!(warnings[0].reason.variant_name(), "signature_drift");
+        assert_eq!(
+            warnings[0].reason.description(),
+            "call to `parse` on line 3 targets `pub fn parse(s: &str) -> u32`, which 'alice' \
+             changed to `pub fn parse(s: &str, strict: bool) -> u32` in src/lib.rs"
+        );
+    }
+
+    #[test]
+    fn existing_and_adapted_calls_are_not_reported() {
+        // Unchanged signature: nothing to report.
+        let main =
+            "fn main() {\n    let _ = lib::parse(\"a\");\n    let _ = lib::parse(\"b\");\n}\n";
+        assert!(detect(None, main).is_empty());
+
+        // Only pre-existing call sites.
+        assert!(detect(Some(NEW_LIB), MAIN).is_empty());
+
+        // The new call already matches the new arity.
+        let main = "fn main() {\n    let _ = lib::parse(\"a\");\n    let _ = lib::parse(\"b\", true);\n}\n";
+        assert!(detect(Some(NEW_LIB), main).is_empty());
+    }
+
+    #[test]
+    fn signatures_are_read_from_the_merged_tree() {
+        // Alice changed the signature, but the merged tree (e.g. after a
+        // driver picked another side) kept the old one: nothing drifted.
+        let main =
+            "fn main() {\n    let _ = lib::parse(\"a\");\n    let _ = lib::parse(\"b\");\n}\n";
+        let partition = PartitionResult {
+            unique: vec![
+                (PathBuf::from("src/lib.rs"), modified("alice", NEW_LIB)),
+                (PathBuf::from("src/main.rs"), modified("bob", main)),
+            ],
+            shared: vec![],
+            df_clashes: vec![],
+        };
+        let resolved = vec![upsert("src/lib.rs", LIB), upsert("src/main.rs", main)];
+        let warnings = detect_signature_drift(
+            &partition,
+            &resolved,
+            &base(),
+            &AstMergeConfig::all_languages(),
+        );
+        assert!(warnings.is_empty(), "{warnings:?}");
+    }
+
+    #[test]
+    fn methods_are_qualified_by_impl_type() {
+        let index = FileIndex::parse(
+            b"mod cfg {\n    impl Config {\n        pub fn load(&self, path: &str) {}\n    }\n}\nfn go(c: cfg::Config) { c.load(\"x\"); }\n",
+        )
+        .unwrap();
+        let def = &index.functions["cfg::Config::load"];
+        assert_eq!(def.params, 1);
+        assert_eq!(def.signature, "pub fn load(&self, path: &str)");
+        assert_eq!(index.calls.len(), 1);
+        assert_eq!(
+            (index.calls[0].name.as_str(), index.calls[0].args),
+            ("load", 1)
+        );
+    }
+}
@@ -0,0 +1,1096 @@
+//! Key-path-aware merge for structured data files (JSON, YAML, TOML).
+//!
+//! Configuration files (`package.json`, `Cargo.toml`, Kubernetes manifests)
+//! are the most common source of false-positive conflicts: two workspaces add
+//! different keys to the same table and diff3 sees overlapping line hunks.
+//! This layer sits next to [`super::ast_merge`] in the resolve pipeline and
+//! runs when diff3 reports a conflict on a structured file:
+//!
+//! 1. Parses base + all variants with tree-sitter (formatting is preserved —
+//!    nothing is re-serialized)
+//! 2. Views every mapping (JSON object, YAML block mapping, TOML table or
+//!    inline table) as a list of keyed entries
+//! 3. Per key: unchanged → base, changed by one side (or identically by
+//!    several) → that side, changed differently → recurse if every side kept
+//!    a nested mapping, otherwise conflict
+//! 4. Keys added by a variant are spliced in after the key that precedes them
+//!    in that variant
+//! 5. Conflicts are emitted as `AstNode` atoms whose name is the dotted key
+//!    path (e.g. `dependencies.serde`)
+//!
+//! Arrays and scalars are opaque: two different edits to the same list are a
+//! conflict on the key that holds it. Files whose non-entry text (leading
+//! comments, braces, document markers) changed are left to diff3.
+//!
+//! # Determinism guarantee
+//!
+//! Variants are processed in the order given (lexicographic by workspace ID
+//! in the resolve pipeline), so the same inputs always produce the same
+//! output and the same atom order.
+
+use std::path::Path;
+
+use tree_sitter::{Language, Node, Parser};
+
+use super::ast_merge::AstMergeResult;
+use crate::model::conflict::{AtomEdit, ConflictAtom, ConflictReason, Region};
+use crate::model::types::WorkspaceId;
+
+// ---------------------------------------------------------------------------
+// Format detection
+// ---------------------------------------------------------------------------
+
+/// Structured data formats supported by the key-path merge layer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum StructuredFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl StructuredFormat {
+    /// Detect the format from a file extension.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?;
+        match ext {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+
+    /// Map a config-level format to the merge-layer format.
+    #[must_use]
+    pub const fn from_config_format(format: crate::config::StructuredFormatConfig) -> Self {
+        match format {
+            crate::config::StructuredFormatConfig::Json => Self::Json,
+            crate::config::StructuredFormatConfig::Yaml => Self::Yaml,
+            crate::config::StructuredFormatConfig::Toml => Self::Toml,
+        }
+    }
+
+    fn tree_sitter_language(self) -> Language {
+        match self {
+            Self::Json => tree_sitter_json::LANGUAGE.into(),
+            Self::Yaml => tree_sitter_yaml::LANGUAGE.into(),
+            Self::Toml => tree_sitter_toml_ng::LANGUAGE.into(),
+        }
+    }
+
+    /// `node_kind` used for conflict regions.
+    const fn key_kind(self) -> &'static str {
+        match self {
+            Self::Json => "json_key",
+            Self::Yaml => "yaml_key",
+            Self::Toml => "toml_key",
+        }
+    }
+}
+
+impl std::fmt::Display for StructuredFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::Yaml => write!(f, "yaml"),
+            Self::Toml => write!(f, "toml"),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Mapping extraction
+// ---------------------------------------------------------------------------
+
+/// A keyed mapping located in a source file.
+#[derive(Clone, Debug)]
+struct Mapping {
+    /// Start of the text holding the entries (after any opening brace or
+    /// table header).
+    start: usize,
+    /// End of the text holding the entries (before any closing brace).
+    end: usize,
+    /// Entries are separated by `, ` rather than newlines (TOML inline
+    /// tables).
+    inline: bool,
+    entries: Vec<Entry>,
+}
+
+/// One key/value entry of a [`Mapping`].
+#[derive(Clone, Debug)]
+struct Entry {
+    /// Identity of the entry within its mapping. TOML tables are bracketed
+    /// (`[dependencies]`), repeated keys get an ` #n` occurrence suffix.
+    key: String,
+    start: usize,
+    end: usize,
+    /// Mapping held by the entry's value, if the value is a mapping.
+    nested: Option<Mapping>,
+}
+
+impl Mapping {
+    fn new(start: usize, end: usize, inline: bool, mut entries: Vec<Entry>) -> Self {
+        disambiguate_keys(&mut entries);
+        let end = entries.last().map_or(end, |last| end.max(last.end));
+        Self {
+            start,
+            end,
+            inline,
+            entries,
+        }
+    }
+}
+
+/// Suffix repeated keys (TOML `[[bin]]` elements, duplicate JSON keys) with
+/// their occurrence index so every key is unique within its mapping.
+fn disambiguate_keys(entries: &mut [Entry]) {
+    let mut seen: Vec<String> = Vec::new();
+    for entry in entries.iter_mut() {
+        let occurrence = seen.iter().filter(|k| **k == entry.key).count();
+        seen.push(entry.key.clone());
+        if occurrence > 0 {
+            entry.key = format!("{} #{occurrence}", entry.key);
+        }
+    }
+}
+
+/// Parse `source` into its top-level mappings (one per YAML document).
+///
+/// Returns `None` when the source has syntax errors or a document whose root
+/// is not a mapping.
+fn parse_documents(source: &[u8], format: StructuredFormat) -> Option<Vec<Mapping>> {
+    let mut parser = Parser::new();
+    parser.set_language(&format.tree_sitter_language()).ok()?;
+    let tree = parser.parse(source, None)?;
+    let root = tree.root_node();
+    if root.has_error() {
+        return None;
+    }
+
+    match format {
+        StructuredFormat::Json => {
+            let mut cursor = root.walk();
+            let mut values = root
+                .named_children(&mut cursor)
+                .filter(|n| n.kind() != "comment");
+            let object = values.next()?;
+            if object.kind() != "object" || values.next().is_some() {
+                return None;
+            }
+            Some(vec![json_object(object, source)])
+        }
+        StructuredFormat::Yaml => {
+            let mut cursor = root.walk();
+            let documents: Vec<Node<'_>> = root
+                .named_children(&mut cursor)
+                .filter(|n| n.kind() == "document")
+                .collect();
+            if documents.is_empty() {
+                return None;
+            }
+            documents
+                .into_iter()
+                .map(|doc| {
+                    let mut cursor = doc.walk();
+                    let block = doc
+                        .named_children(&mut cursor)
+                        .find(|n| n.kind() == "block_node")?;
+                    yaml_block_mapping(block, source)
+                })
+                .collect()
+        }
+        StructuredFormat::Toml => Some(vec![toml_document(root, source)]),
+    }
+}
+
+fn json_object(node: Node<'_>, source: &[u8]) -> Mapping {
+    let mut cursor = node.walk();
+    let entries = node
+        .named_children(&mut cursor)
+        .filter(|n| n.kind() == "pair")
+        .filter_map(|pair| {
+            let key_node = pair.child_by_field_name("key")?;
+            let key = json_string_content(key_node, source);
+            let nested = pair
+                .child_by_field_name("value")
+                .filter(|v| v.kind() == "object")
+                .map(|v| json_object(v, source));
+            Some(Entry {
+                key,
+                start: pair.start_byte(),
+                end: pair.end_byte(),
+                nested,
+            })
+        })
+        .collect();
+    Mapping::new(
+        node.start_byte() + 1,
+        node.end_byte().saturating_sub(1),
+        false,
+        entries,
+    )
+}
+
+fn json_string_content(node: Node<'_>, source: &[u8]) -> String {
+    let mut cursor = node.walk();
+    let content = node
+        .named_children(&mut cursor)
+        .find(|n| n.kind() == "string_content");
+    content.map_or_else(
+        || node_text(node, source).trim_matches('"').to_owned(),
+        |c| node_text(c, source),
+    )
+}
+
+/// Extract the block mapping held by a YAML `block_node`, if any.
+fn yaml_block_mapping(block: Node<'_>, source: &[u8]) -> Option<Mapping> {
+    let mut cursor = block.walk();
+    let mapping = block
+        .named_children(&mut cursor)
+        .find(|n| n.kind() == "block_mapping")?;
+
+    let mut cursor = mapping.walk();
+    let entries = mapping
+        .named_children(&mut cursor)
+        .filter(|n| n.kind() == "block_mapping_pair")
+        .filter_map(|pair| {
+            let key_node = pair.child_by_field_name("key")?;
+            let key = node_text(key_node, source)
+                .trim_matches(|c| c == '"' || c == '\'')
+                .to_owned();
+            let nested = pair
+                .child_by_field_name("value")
+                .filter(|v| v.kind() == "block_node")
+                .and_then(|v| yaml_block_mapping(v, source));
+            let mut end = extend_over_line_comment(source, pair.end_byte());
+            if let Some(nested) = &nested {
+                end = end.max(nested.end);
+            }
+            Some(Entry {
+                key,
+                start: pair.start_byte(),
+                end,
+                nested,
+            })
+        })
+        .collect();
+    Some(Mapping::new(
+        mapping.start_byte(),
+        mapping.end_byte(),
+        false,
+        entries,
+    ))
+}
+
+fn toml_document(root: Node<'_>, source: &[u8]) -> Mapping {
+    let mut cursor = root.walk();
+    let entries = root
+        .named_children(&mut cursor)
+        .filter_map(|node| match node.kind() {
+            "pair" => toml_pair(node, source),
+            "table" | "table_array_element" => toml_table(node, source),
+            _ => None,
+        })
+        .collect();
+    Mapping::new(0, source.len(), false, entries)
+}
+
+fn toml_pair(pair: Node<'_>, source: &[u8]) -> Option<Entry> {
+    let key_node = pair.named_child(0)?;
+    let nested = pair
+        .named_child(pair.named_child_count().checked_sub(1)?)
+        .filter(|v| v.kind() == "inline_table")
+        .map(|table| toml_pairs(table, source, table.start_byte() + 1, true));
+    Some(Entry {
+        key: node_text(key_node, source),
+        start: pair.start_byte(),
+        end: extend_over_line_comment(source, pair.end_byte()),
+        nested,
+    })
+}
+
+fn toml_table(table: Node<'_>, source: &[u8]) -> Option<Entry> {
+    let key_node = table.named_child(0)?;
+    let (open, close) = if table.kind() == "table" {
+        ("[", "]")
+    } else {
+        ("[[", "]]")
+    };
+    let mut cursor = table.walk();
+    let header_end = table
+        .children(&mut cursor)
+        .find(|n| n.kind() == close)?
+        .end_byte();
+    let header_end = extend_over_line_comment(source, header_end);
+    let end = trim_trailing_whitespace(source, table.start_byte(), table.end_byte());
+    let mut nested = toml_pairs(table, source, header_end, false);
+    nested.end = nested.end.min(end).max(nested.start);
+    Some(Entry {
+        key: format!("{open}{}{close}", node_text(key_node, source)),
+        start: table.start_byte(),
+        end,
+        nested: Some(nested),
+    })
+}
+
+/// Collect the `pair` children of a TOML table or inline table.
+fn toml_pairs(node: Node<'_>, source: &[u8], start: usize, inline: bool) -> Mapping {
+    let mut cursor = node.walk();
+    let entries = node
+        .named_children(&mut cursor)
+        .filter(|n| n.kind() == "pair")
+        .filter_map(|pair| toml_pair(pair, source))
+        .collect();
+    let end = if inline {
+        node.end_byte().saturating_sub(1)
+    } else {
+        trim_trailing_whitespace(source, start, node.end_byte())
+    };
+    Mapping::new(start, end, inline, entries)
+}
+
+fn node_text(node: Node<'_>, source: &[u8]) -> String {
+    String::from_utf8_lossy(&source[node.start_byte()..node.end_byte()]).into_owned()
+}
+
+/// Extend `end` over a `# comment` that follows on the same line, so a
+/// trailing comment travels with its entry.
+fn extend_over_line_comment(source: &[u8], end: usize) -> usize {
+    let mut pos = end;
+    while pos < source.len() && matches!(source[pos], b' ' | b'\t') {
+        pos += 1;
+    }
+    if source.get(pos) != Some(&b'#') {
+        return end;
+    }
+    while pos < source.len() && source[pos] != b'\n' {
+        pos += 1;
+    }
+    trim_trailing_whitespace(source, end, pos)
+}
+
+fn trim_trailing_whitespace(source: &[u8], start: usize, end: usize) -> usize {
+    let mut end = end;
+    while end > start && source[end - 1].is_ascii_whitespace() {
+        end -= 1;
+    }
+    end
+}
+
+// ---------------------------------------------------------------------------
+// Merge
+// ---------------------------------------------------------------------------
+
+/// A mapping together with the source text it was extracted from.
+#[derive(Clone, Copy)]
+struct Source<'a> {
+    text: &'a [u8],
+    mapping: &'a Mapping,
+}
+
+impl<'a> Source<'a> {
+    fn entry(&self, idx: usize) -> &'a Entry {
+        &self.mapping.entries[idx]
+    }
+
+    fn entry_text(&self, idx: usize) -> &'a [u8] {
+        let entry = self.entry(idx);
+        &self.text[entry.start..entry.end]
+    }
+
+    /// Separator text between entry `idx - 1` and entry `idx`.
+    fn sep_before(&self, idx: usize) -> Option<&'a [u8]> {
+        let prev = self.mapping.entries.get(idx.checked_sub(1)?)?;
+        let entry = self.mapping.entries.get(idx)?;
+        Some(&self.text[prev.end..entry.start])
+    }
+
+    fn prefix(&self) -> &'a [u8] {
+        let end = self
+            .mapping
+            .entries
+            .first()
+            .map_or(self.mapping.end, |e| e.start);
+        &self.text[self.mapping.start..end]
+    }
+
+    fn suffix(&self) -> &'a [u8] {
+        let start = self
+            .mapping
+            .entries
+            .last()
+            .map_or(self.mapping.end, |e| e.end);
+        &self.text[start..self.mapping.end]
+    }
+
+    fn find(&self, key: &str) -> Option<usize> {
+        self.mapping.entries.iter().position(|e| e.key == key)
+    }
+
+    /// The nested mapping of entry `idx`, viewed as its own source.
+    fn nested(&self, idx: usize) -> Option<Self> {
+        self.entry(idx).nested.as_ref().map(|mapping| Self {
+            text: self.text,
+            mapping,
+        })
+    }
+
+    /// Entry text before and after its nested mapping (key, braces, header).
+    fn entry_frame(&self, idx: usize) -> Option<(&'a [u8], &'a [u8])> {
+        let entry = self.entry(idx);
+        let nested = entry.nested.as_ref()?;
+        Some((
+            &self.text[entry.start..nested.start],
+            &self.text[nested.end..entry.end],
+        ))
+    }
+
+    fn region(&self, idx: usize, format: StructuredFormat, key_path: &str) -> Region {
+        let entry = self.entry(idx);
+        Region::ast_node(
+            format.key_kind(),
+            Some(key_path.to_owned()),
+            narrow_u32(entry.start),
+            narrow_u32(entry.end),
+        )
+    }
+}
+
+/// One entry of the merged mapping, in output order.
+struct Piece<'a> {
+    key: &'a str,
+    text: Vec<u8>,
+    sep: Option<&'a [u8]>,
+    added: bool,
+}
+
+/// A variant's version of an entry: `None` when the variant deleted it.
+type SideEntry<'a> = (&'a WorkspaceId, Option<(Source<'a>, usize)>);
+
+/// Attempt a key-path merge of a structured data file.
+///
+/// `variants` are `(workspace, content)` pairs in deterministic order.
+/// Returns [`AstMergeResult::Unsupported`] when any version fails to parse,
+/// is not mapping-shaped, or changed text outside the mapping entries.
+#[must_use]
+pub fn try_structured_merge(
+    base: &[u8],
+    variants: &[(WorkspaceId, Vec<u8>)],
+    format: StructuredFormat,
+) -> AstMergeResult {
+    let Some(base_docs) = parse_documents(base, format) else {
+        return AstMergeResult::Unsupported;
+    };
+    let mut variant_docs = Vec::with_capacity(variants.len());
+    for (_, content) in variants {
+        match parse_documents(content, format) {
+            Some(docs) if docs.len() == base_docs.len() => variant_docs.push(docs),
+            _ => return AstMergeResult::Unsupported,
+        }
+    }
+
+    // Text between documents (YAML `---` markers, leading comments, JSON
+    // trailing newline) must be untouched by every variant.
+    let outside = |source: &[u8], docs: &[Mapping]| -> Vec<Vec<u8>> {
+        let mut cursor = 0;
+        let mut gaps = Vec::with_capacity(docs.len() + 1);
+        for doc in docs {
+            gaps.push(source[cursor..doc.start].to_vec());
+            cursor = doc.end;
+        }
+        gaps.push(source[cursor..].to_vec());
+        gaps
+    };
+    let base_gaps = outside(base, &base_docs);
+    for ((_, content), docs) in variants.iter().zip(&variant_docs) {
+        if outside(content, docs) != base_gaps {
+            return AstMergeResult::Unsupported;
+        }
+    }
+
+    let multi_doc = base_docs.len() > 1;
+    let mut atoms = Vec::new();
+    let mut merged = Vec::with_capacity(base.len());
+    for (idx, base_doc) in base_docs.iter().enumerate() {
+        merged.extend_from_slice(&base_gaps[idx]);
+        let base_source = Source {
+            text: base,
+            mapping: base_doc,
+        };
+        let sides: Vec<(&WorkspaceId, Source<'_>)> = variants
+            .iter()
+            .zip(&variant_docs)
+            .map(|((ws, content), docs)| {
+                (
+                    ws,
+                    Source {
+                        text: content,
+                        mapping: &docs[idx],
+                    },
+                )
+            })
+            .collect();
+        let path = if multi_doc {
+            vec![format!("document {}", idx + 1)]
+        } else {
+            Vec::new()
+        };
+        let Some(text) = merge_mapping(format, &path, base_source, &sides, &mut atoms) else {
+            return AstMergeResult::Unsupported;
+        };
+        merged.extend_from_slice(&text);
+    }
+    merged.extend_from_slice(&base_gaps[base_docs.len()]);
+
+    if atoms.is_empty() {
+        AstMergeResult::Clean(merged)
+    } else {
+        AstMergeResult::Conflict { atoms }
+    }
+}
+
+/// Merge one mapping level. Returns the merged text of the mapping's entry
+/// region, or `None` if a variant changed text that isn't owned by an entry.
+fn merge_mapping<'a>(
+    format: StructuredFormat,
+    path: &[String],
+    base: Source<'a>,
+    sides: &[(&'a WorkspaceId, Source<'a>)],
+    atoms: &mut Vec<ConflictAtom>,
+) -> Option<Vec<u8>> {
+    if sides
+        .iter()
+        .any(|(_, side)| side.prefix() != base.prefix() || side.suffix() != base.suffix())
+    {
+        return None;
+    }
+
+    let mut pieces: Vec<Piece<'a>> = Vec::with_capacity(base.mapping.entries.len());
+
+    // Base entries, in base order.
+    for (idx, entry) in base.mapping.entries.iter().enumerate() {
+        let base_text = base.entry_text(idx);
+        let base_sep = base.sep_before(idx);
+        let mut changed: Vec<SideEntry<'a>> = Vec::new();
+        for (ws, side) in sides {
+            match side.find(&entry.key) {
+                None => changed.push((ws, None)),
+                Some(j) => {
+                    if side.entry_text(j) != base_text || !same_sep(side.sep_before(j), base_sep) {
+                        changed.push((ws, Some((*side, j))));
+                    }
+                }
+            }
+        }
+
+        let Some(((_, first), rest)) = changed.split_first() else {
+            pieces.push(Piece {
+                key: &entry.key,
+                text: base_text.to_vec(),
+                sep: base_sep,
+                added: false,
+            });
+            continue;
+        };
+
+        if rest.iter().all(|(_, other)| same_outcome(*first, *other)) {
+            if let Some((side, j)) = first {
+                pieces.push(Piece {
+                    key: &entry.key,
+                    text: side.entry_text(*j).to_vec(),
+                    sep: side.sep_before(*j).or(base_sep),
+                    added: false,
+                });
+            }
+            continue;
+        }
+
+        let mut key_path = path.to_vec();
+        key_path.push(path_segment(&entry.key).to_owned());
+        let text = match merge_nested(format, &key_path, base, idx, sides, &changed, atoms) {
+            Nested::Merged(text) => text,
+            Nested::Unsupported => return None,
+            Nested::NotMergeable => {
+                atoms.push(key_conflict(format, &key_path, Some((base, idx)), &changed));
+                base_text.to_vec()
+            }
+        };
+        pieces.push(Piece {
+            key: &entry.key,
+            text,
+            sep: base_sep,
+            added: false,
+        });
+    }
+
+    splice_additions(format, path, base, sides, &mut pieces, atoms);
+
+    let default_sep = default_separator(format, base);
+    let mut out = base.prefix().to_vec();
+    for (idx, piece) in pieces.iter().enumerate() {
+        if idx > 0 {
+            out.extend_from_slice(piece.sep.unwrap_or(&default_sep));
+        }
+        out.extend_from_slice(&piece.text);
+    }
+    out.extend_from_slice(base.suffix());
+    Some(out)
+}
+
+/// Insert the keys added by variants into `pieces`, grouped by key in
+/// first-seen order. Keys added with different content by several variants
+/// are reported as conflicts (the first variant's version is kept in place).
+fn splice_additions<'a>(
+    format: StructuredFormat,
+    path: &[String],
+    base: Source<'a>,
+    sides: &[(&'a WorkspaceId, Source<'a>)],
+    pieces: &mut Vec<Piece<'a>>,
+    atoms: &mut Vec<ConflictAtom>,
+) {
+    let mut additions: Vec<(&'a str, Vec<SideEntry<'a>>)> = Vec::new();
+    for (ws, side) in sides {
+        for (j, entry) in side.mapping.entries.iter().enumerate() {
+            if base.find(&entry.key).is_some() {
+                continue;
+            }
+            let pos = additions
+                .iter()
+                .position(|(k, _)| *k == entry.key)
+                .unwrap_or_else(|| {
+                    additions.push((&entry.key, Vec::new()));
+                    additions.len() - 1
+                });
+            additions[pos].1.push((ws, Some((*side, j))));
+        }
+    }
+
+    for (key, adders) in &additions {
+        let Some((_, Some((side, j)))) = adders.first() else {
+            continue;
+        };
+        let agreed = adders
+            .iter()
+            .all(|(_, other)| other.is_some_and(|(s, k)| s.entry_text(k) == side.entry_text(*j)));
+        if !agreed {
+            let mut key_path = path.to_vec();
+            key_path.push(path_segment(key).to_owned());
+            atoms.push(key_conflict(format, &key_path, None, adders));
+        }
+
+        // Anchor after the nearest preceding key of the adding variant that
+        // is already placed, skipping earlier additions anchored there.
+        let mut pos = (0..*j)
+            .rev()
+            .find_map(|k| {
+                let anchor = &side.entry(k).key;
+                pieces.iter().position(|p| p.key == anchor.as_str())
+            })
+            .map_or(0, |p| p + 1);
+        while pos < pieces.len() && pieces[pos].added {
+            pos += 1;
+        }
+        pieces.insert(
+            pos,
+            Piece {
+                key,
+                text: side.entry_text(*j).to_vec(),
+                sep: side.sep_before(*j),
+                added: true,
+            },
+        );
+    }
+}
+
+enum Nested {
+    Merged(Vec<u8>),
+    /// A variant changed framing text inside the nested mapping.
+    Unsupported,
+    /// The entry is not a mapping on every side (or was deleted).
+    NotMergeable,
+}
+
+/// Recurse into an entry changed differently by several variants when every
+/// side kept it as a mapping with unchanged framing.
+fn merge_nested<'a>(
+    format: StructuredFormat,
+    key_path: &[String],
+    base: Source<'a>,
+    idx: usize,
+    sides: &[(&'a WorkspaceId, Source<'a>)],
+    changed: &[SideEntry<'a>],
+    atoms: &mut Vec<ConflictAtom>,
+) -> Nested {
+    let (Some(base_nested), Some(base_frame)) = (base.nested(idx), base.entry_frame(idx)) else {
+        return Nested::NotMergeable;
+    };
+    let base_sep = base.sep_before(idx);
+    let key = &base.entry(idx).key;
+
+    let mut nested_sides = Vec::with_capacity(sides.len());
+    for (ws, side) in sides {
+        let Some(j) = side.find(key) else {
+            return Nested::NotMergeable;
+        };
+        let (Some(nested), Some(frame)) = (side.nested(j), side.entry_frame(j)) else {
+            return Nested::NotMergeable;
+        };
+        if frame != base_frame || !same_sep(side.sep_before(j), base_sep) {
+            return Nested::NotMergeable;
+        }
+        nested_sides.push((*ws, nested));
+    }
+    debug_assert!(changed.iter().all(|(_, c)| c.is_some()));
+
+    merge_mapping(format, key_path, base_nested, &nested_sides, atoms).map_or(
+        Nested::Unsupported,
+        |inner| {
+            let mut text = base_frame.0.to_vec();
+            text.extend_from_slice(&inner);
+            text.extend_from_slice(base_frame.1);
+            Nested::Merged(text)
+        },
+    )
+}
+
+/// Separators only count as changed when both sides have one — an entry
+/// that became (or stopped being) the first in its mapping has no separator.
+fn same_sep(a: Option<&[u8]>, b: Option<&[u8]>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a == b,
+        _ => true,
+    }
+}
+
+fn same_outcome(a: Option<(Source<'_>, usize)>, b: Option<(Source<'_>, usize)>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some((sa, ia)), Some((sb, ib))) => {
+            sa.entry_text(ia) == sb.entry_text(ib) && same_sep(sa.sep_before(ia), sb.sep_before(ib))
+        }
+        _ => false,
+    }
+}
+
+/// Separator for entries that have no separator of their own (e.g. the old
+/// first entry after a key was added in front of it).
+fn default_separator(format: StructuredFormat, base: Source<'_>) -> Vec<u8> {
+    if base.mapping.inline {
+        return b", ".to_vec();
+    }
+    if let Some(sep) = base.sep_before(1) {
+        return sep.to_vec();
+    }
+    let indent = base
+        .mapping
+        .entries
+        .first()
+        .map_or(&[][..], |e| line_indent(base.text, e.start));
+    let mut sep = match format {
+        StructuredFormat::Json if !base.prefix().contains(&b'\n') => return b", ".to_vec(),
+        StructuredFormat::Json => b",\n".to_vec(),
+        StructuredFormat::Yaml | StructuredFormat::Toml => b"\n".to_vec(),
+    };
+    sep.extend_from_slice(indent);
+    sep
+}
+
+/// Whitespace between the start of the line containing `pos` and `pos`.
+fn line_indent(text: &[u8], pos: usize) -> &[u8] {
+    let line_start = text[..pos]
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |p| p + 1);
+    let indent_len = text[line_start..pos]
+        .iter()
+        .take_while(|b| matches!(b, b' ' | b'\t'))
+        .count();
+    &text[line_start..line_start + indent_len]
+}
+
+/// Key as shown in a key path: TOML table brackets are dropped.
+fn path_segment(key: &str) -> &str {
+    key.trim_start_matches('[').trim_end_matches(']')
+}
+
+fn key_conflict(
+    format: StructuredFormat,
+    key_path: &[String],
+    base: Option<(Source<'_>, usize)>,
+    edits: &[SideEntry<'_>],
+) -> ConflictAtom {
+    let key_path = key_path.join(".");
+    let base_region = base
+        .or_else(|| edits.iter().find_map(|(_, e)| *e))
+        .map_or(Region::WholeFile, |(source, idx)| {
+            source.region(idx, format, &key_path)
+        });
+    let atom_edits = edits
+        .iter()
+        .map(|(ws, edit)| match edit {
+            Some((source, idx)) => AtomEdit::new(
+                ws.to_string(),
+                source.region(*idx, format, &key_path),
+                String::from_utf8_lossy(source.entry_text(*idx)),
+            ),
+            None => AtomEdit::new(ws.to_string(), base_region.clone(), ""),
+        })
+        .collect();
+    let workspaces = edits
+        .iter()
+        .map(|(ws, _)| ws.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let verb = if base.is_some() { "changed" } else { "added" };
+    let reason = ConflictReason::same_ast_node(format!(
+        "{format} key `{key_path}` {verb} differently by [{workspaces}]"
+    ));
+    ConflictAtom::new(base_region, atom_edits, reason)
+}
+
+fn narrow_u32(value: usize) -> u32 {
+    u32::try_from(value).unwrap_or(u32::MAX)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+mod tests {
+    use super::*;
+
+    fn ws(name: &str) -> WorkspaceId {
+        WorkspaceId::new(name).expect("operation should succeed")
+    }
+
+    fn merge(base: &str, a: &str, b: &str, format: StructuredFormat) -> AstMergeResult {
+        let variants = vec![
+            (ws("alice"), a.as_bytes().to_vec()),
+            (ws("bob"), b.as_bytes().to_vec()),
+        ];
+        try_structured_merge(base.as_bytes(), &variants, format)
+    }
+
+    fn clean(result: AstMergeResult) -> String {
+        match result {
+            AstMergeResult::Clean(bytes) => String::from_utf8(bytes).expect("utf8"),
+            other => panic!("expected clean merge, got {other:?}"),
+        }
+    }
+
+    fn conflict_names(result: AstMergeResult) -> Vec<String> {
+        match result {
+            AstMergeResult::Conflict { atoms } => atoms
+                .iter()
+                .map(|atom| match &atom.base_region {
+                    Region::AstNode { name, .. } => name.clone().unwrap_or_default(),
+                    other => panic!("expected AstNode region, got {other:?}"),
+                })
+                .collect(),
+            other => panic!("expected conflict, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn detect_structured_formats() {
+        assert_eq!(
+            StructuredFormat::from_path(Path::new("package.json")),
+            Some(StructuredFormat::Json)
+        );
+        assert_eq!(
+            StructuredFormat::from_path(Path::new("k8s/deploy.yml")),
+            Some(StructuredFormat::Yaml)
+        );
+        assert_eq!(
+            StructuredFormat::from_path(Path::new("config.yaml")),
+            Some(StructuredFormat::Yaml)
+        );
+        assert_eq!(
+            StructuredFormat::from_path(Path::new("Cargo.toml")),
+            Some(StructuredFormat::Toml)
+        );
+        assert_eq!(StructuredFormat::from_path(Path::new("main.rs")), None);
+    }
+
+    #[test]
+    fn json_disjoint_key_additions_merge() {
+        let base = "{\n  \"name\": \"app\",\n  \"dependencies\": {\n    \"a\": \"1\"\n  }\n}\n";
+        let a = "{\n  \"name\": \"app\",\n  \"dependencies\": {\n    \"a\": \"1\",\n    \"b\": \"2\"\n  }\n}\n";
+        let b = "{\n  \"name\": \"app\",\n  \"dependencies\": {\n    \"a\": \"1\",\n    \"c\": \"3\"\n  }\n}\n";
+        let merged = clean(merge(base, a, b, StructuredFormat::Json));
+        assert_eq!(
+            merged,
+            "{\n  \"name\": \"app\",\n  \"dependencies\": {\n    \"a\": \"1\",\n    \"b\": \"2\",\n    \"c\": \"3\"\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn json_edit_and_addition_in_same_object_merge() {
+        let base = "{\n  \"version\": \"1.0.0\",\n  \"private\": true\n}\n";
+        let a = "{\n  \"version\": \"1.1.0\",\n  \"private\": true\n}\n";
+        let b = "{\n  \"version\": \"1.0.0\",\n  \"private\": true,\n  \"license\": \"MIT\"\n}\n";
+        let merged = clean(merge(base, a, b, StructuredFormat::Json));
+        assert_eq!(
+            merged,
+            "{\n  \"version\": \"1.1.0\",\n  \"private\": true,\n  \"license\": \"MIT\"\n}\n"
+        );
+    }
+
+    #[test]
+    fn json_same_key_changed_differently_conflicts_with_key_path() {
+        let base = "{\n  \"scripts\": {\n    \"test\": \"jest\"\n  }\n}\n";
+        let a = "{\n  \"scripts\": {\n    \"test\": \"vitest\"\n  }\n}\n";
+        let b = "{\n  \"scripts\": {\n    \"test\": \"mocha\"\n  }\n}\n";
+        let result = merge(base, a, b, StructuredFormat::Json);
+        match &result {
+            AstMergeResult::Conflict { atoms } => {
+                assert_eq!(atoms.len(), 1);
+                assert_eq!(atoms[0].edits.len(), 2);
+                assert!(matches!(
+                    &atoms[0].reason,
+                    ConflictReason::SameAstNodeModified { description }
+                        if description.contains("scripts.test")
+                ));
+            }
+            other => panic!("expected conflict, got {other:?}"),
+        }
+        assert_eq!(conflict_names(result), vec!["scripts.test"]);
+    }
+
+    #[test]
+    fn json_identical_changes_merge() {
+        let base = "{\"a\": 1, \"b\": 2}";
+        let a = "{\"a\": 5, \"b\": 2}";
+        let merged = clean(merge(base, a, a, StructuredFormat::Json));
+        assert_eq!(merged, a);
+    }
+
+    #[test]
+    fn json_top_level_array_is_unsupported() {
+        let result = merge("[1]", "[1, 2]", "[1, 3]", StructuredFormat::Json);
+        assert!(matches!(result, AstMergeResult::Unsupported));
+    }
+
+    #[test]
+    fn yaml_nested_additions_merge() {
+        let base = "apiVersion: v1\nmetadata:\n  name: web\n  labels:\n    app: web\nspec:\n  replicas: 1\n";
+        let a = "apiVersion: v1\nmetadata:\n  name: web\n  labels:\n    app: web\n    tier: frontend\nspec:\n  replicas: 1\n";
+        let b = "apiVersion: v1\nmetadata:\n  name: web\n  labels:\n    app: web\n    team: core\nspec:\n  replicas: 3\n";
+        let merged = clean(merge(base, a, b, StructuredFormat::Yaml));
+        assert_eq!(
+            merged,
+            "apiVersion: v1\nmetadata:\n  name: web\n  labels:\n    app: web\n    tier: frontend\n    team: core\nspec:\n  replicas: 3\n"
+        );
+    }
+
+    #[test]
+    fn yaml_trailing_comment_travels_with_entry() {
+        let base = "a: 1 # keep\nb: 2\n";
+        let a = "a: 1 # keep\nb: 3\n";
+        let b = "z: 0\na: 1 # keep\nb: 2\n";
+        let merged = clean(merge(base, a, b, StructuredFormat::Yaml));
+        assert_eq!(merged, "z: 0\na: 1 # keep\nb: 3\n");
+    }
+
+    #[test]
+    fn yaml_scalar_conflict_names_key_path() {
+        let base = "spec:\n  replicas: 1\n";
+        let a = "spec:\n  replicas: 2\n";
+        let b = "spec:\n  replicas: 3\n";
+        assert_eq!(
+            conflict_names(merge(base, a, b, StructuredFormat::Yaml)),
+            vec!["spec.replicas"]
+        );
+    }
+
+    #[test]
+    fn yaml_multi_document_merges_per_document() {
+        let base = "kind: A\n---\nkind: B\n";
+        let a = "kind: A\nx: 1\n---\nkind: B\n";
+        let b = "kind: A\n---\nkind: B\ny: 2\n";
+        let merged = clean(merge(base, a, b, StructuredFormat::Yaml));
+        assert_eq!(merged, "kind: A\nx: 1\n---\nkind: B\ny: 2\n");
+    }
+
+    #[test]
+    fn toml_dependencies_added_to_same_table_merge() {
+        let base = "[package]\nname = \"demo\"\n\n[dependencies]\nserde = \"1\"\n";
+        let a = "[package]\nname = \"demo\"\n\n[dependencies]\nserde = \"1\"\nanyhow = \"1\"\n";
+        let b = "[package]\nname = \"demo\"\n\n[dependencies]\nserde = \"1\"\ntokio = \"1\"\n";
+        let merged = clean(merge(base, a, b, StructuredFormat::Toml));
+        assert_eq!(
+            merged,
+            "[package]\nname = \"demo\"\n\n[dependencies]\nserde = \"1\"\nanyhow = \"1\"\ntokio = \"1\"\n"
+        );
+    }
+
+    #[test]
+    fn toml_new_tables_and_inline_table_keys_merge() {
+        let base = "[dependencies]\nserde = { version = \"1\" }\n";
+        let a = "[dependencies]\nserde = { version = \"1\", features = [\"derive\"] }\n";
+        let b = "[dependencies]\nserde = { version = \"1\", default-features = false }\n\n[features]\ndefault = []\n";
+        let merged = clean(merge(base, a, b, StructuredFormat::Toml));
+        assert_eq!(
+            merged,
+            "[dependencies]\nserde = { version = \"1\", features = [\"derive\"], default-features = false }\n\n[features]\ndefault = []\n"
+        );
+    }
+
+    #[test]
+    fn toml_same_key_changed_differently_conflicts() {
+        let base = "[package]\nversion = \"0.1.0\"\n";
+        let a = "[package]\nversion = \"0.2.0\"\n";
+        let b = "[package]\nversion = \"0.1.1\"\n";
+        assert_eq!(
+            conflict_names(merge(base, a, b, StructuredFormat::Toml)),
+            vec!["package.version"]
+        );
+    }
+
+    #[test]
+    fn toml_same_key_added_differently_conflicts() {
+        let base = "[dependencies]\nserde = \"1\"\n";
+        let a = "[dependencies]\nserde = \"1\"\nrand = \"0.8\"\n";
+        let b = "[dependencies]\nserde = \"1\"\nrand = \"0.9\"\n";
+        assert_eq!(
+            conflict_names(merge(base, a, b, StructuredFormat::Toml)),
+            vec!["dependencies.rand"]
+        );
+    }
+
+    #[test]
+    fn modify_delete_on_key_conflicts() {
+        let base = "[a]\nx = 1\ny = 2\n";
+        let a = "[a]\nx = 1\n";
+        let b = "[a]\nx = 1\ny = 3\n";
+        assert_eq!(
+            conflict_names(merge(base, a, b, StructuredFormat::Toml)),
+            vec!["a.y"]
+        );
+    }
+
+    #[test]
+    fn changed_leading_comment_is_unsupported() {
+        let base = "# top\na: 1\n";
+        let a = "# changed\na: 1\n";
+        let b = "# top\na: 2\n";
+        assert!(matches!(
+            merge(base, a, b, StructuredFormat::Yaml),
+            AstMergeResult::Unsupported
+        ));
+    }
+
+    #[test]
+    fn syntax_error_is_unsupported() {
+        let result = merge(
+            "{\"a\": 1}",
+            "{\"a\": ",
+            "{\"a\": 2}",
+            StructuredFormat::Json,
+        );
+        assert!(matches!(result, AstMergeResult::Unsupported));
+    }
+}
@@ -0,0 +1,587 @@
+//! Cross-file symbol move detection.
+//!
+//! [`rename`](super::rename) follows whole files across paths by `FileId`.
+//! This module does the same one level down, for top-level items: when a
+//! workspace removes an item from one file and adds an item with the same
+//! identity to another file, the item moved. Edits another workspace made to
+//! the item at its old location are re-targeted to the new location instead
+//! of being dropped with the old copy or surfacing as a modify/delete
+//! conflict on the old file.
+//!
+//! For a move of item `I` from `a` to `b` by workspace `M`, and the other
+//! workspaces that edited `I` in `a`:
+//!
+//! 1. `I`'s base text, `M`'s text in `b` and each editor's text in `a` are
+//!    folded with diff3.
+//! 2. Clean → `M`'s copy in `b` is replaced with the merged text and each
+//!    editor's copy in `a` is reset to the base text, so `a` merges as a
+//!    plain removal. The re-target is recorded as an
+//!    [`AutoResolutionKind::SymbolMove`] on `b`.
+//! 3. Overlap → `a` becomes a conflict whose atom carries a
+//!    `symbol_lifecycle` reason naming both paths.
+//!
+//! Items are identified by (scope, kind, name) and must be unambiguous under
+//! that identity in every file version involved. Only paths with AST merge
+//! enabled take part; imports never count as moves.
+//!
+//! # Determinism
+//!
+//! Workspaces, paths and items are indexed by `BTreeMap`, and editors are
+//! folded in workspace order.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use super::ast_merge::{
+    AstLanguage, AstMergeConfig, TopLevelItem, extract_top_level_items, is_import_kind,
+};
+use super::partition::{PartitionResult, PathEntry};
+use super::resolve::{
+    AutoResolution, AutoResolutionKind, ConflictReason, ConflictRecord, ConflictSide, ResolveError,
+    merge_fragment,
+};
+use crate::model::conflict::{self, AtomEdit, ConflictAtom, Region, SemanticConflictExplanation};
+use crate::model::types::WorkspaceId;
+
+/// Identity of an item within one file: (scope, kind, name).
+type SymbolKey = (Vec<String>, String, String);
+
+/// Output of [`apply_symbol_moves`].
+#[derive(Clone, Debug)]
+pub struct SymbolMoveResult<'a> {
+    /// The partition with edits to moved symbols re-targeted. Borrowed
+    /// unchanged from the input unless a move was re-targeted or conflicted.
+    pub partition: Cow<'a, PartitionResult>,
+    /// One record per moved symbol whose edits were re-targeted.
+    pub auto_resolutions: Vec<AutoResolution>,
+    /// Old locations whose edits overlap the moved copy, one per path.
+    pub conflicts: Vec<ConflictRecord>,
+}
+
+/// A top-level item one workspace moved between files.
+#[derive(Clone, Debug)]
+struct SymbolMove {
+    mover: WorkspaceId,
+    from: PathBuf,
+    to: PathBuf,
+    lang: AstLanguage,
+    key: SymbolKey,
+    base_item: TopLevelItem,
+    moved_item: TopLevelItem,
+}
+
+impl SymbolMove {
+    fn label(&self) -> String {
+        let name = self
+            .moved_item
+            .qualified_name()
+            .unwrap_or_else(|| self.key.2.clone());
+        format!("{} `{name}`", self.key.1)
+    }
+}
+
+/// A byte range of one workspace's file content to replace.
+type Splice = (usize, usize, Vec<u8>);
+
+/// Detect symbols moved across files and re-target edits made to them at
+/// their old location.
+///
+/// Paths involved in a move that could not be re-targeted are removed from
+/// the partition and reported in [`SymbolMoveResult::conflicts`]. The
+/// partition is only copied when one of these rewrites applies.
+///
+/// # Errors
+///
+/// Returns [`ResolveError`] if running diff3 on an item fails.
+pub fn apply_symbol_moves<'a>(
+    partition: &'a PartitionResult,
+    base_contents: &BTreeMap<PathBuf, Vec<u8>>,
+    config: &AstMergeConfig,
+) -> Result<SymbolMoveResult<'a>, ResolveError> {
+    let moves = find_moves(partition, base_contents, config);
+
+    let mut splices: BTreeMap<(PathBuf, WorkspaceId), Vec<Splice>> = BTreeMap::new();
+    let mut auto_resolutions = Vec::new();
+    let mut conflicts: BTreeMap<PathBuf, ConflictRecord> = BTreeMap::new();
+
+    for mv in &moves {
+        let Some((_, entries)) = partition.shared.iter().find(|(path, _)| *path == mv.from) else {
+            continue;
+        };
+        let editors: Vec<(&PathEntry, TopLevelItem)> = entries
+            .iter()
+            .filter(|entry| entry.workspace_id != mv.mover)
+            .filter_map(|entry| {
+                let mut items = unique_items(entry.content.as_deref()?, mv.lang)?;
+                let item = items.remove(&mv.key)?;
+                (item.content != mv.base_item.content).then_some((entry, item))
+            })
+            .collect();
+        if editors.is_empty() {
+            continue;
+        }
+
+        let mut merged = Some(mv.moved_item.content.clone());
+        for (_, item) in &editors {
+            let Some(current) = merged else { break };
+            merged = merge_fragment(&mv.base_item.content, &current, &item.content)?;
+        }
+
+        let Some(merged) = merged else {
+            let atom = move_conflict_atom(mv, &editors);
+            conflicts
+                .entry(mv.from.clone())
+                .or_insert_with(|| ConflictRecord {
+                    path: mv.from.clone(),
+                    base: base_contents.get(&mv.from).cloned(),
+                    sides: entries
+                        .iter()
+                        .map(|entry| ConflictSide {
+                            workspace_id: entry.workspace_id.clone(),
+                            kind: entry.kind.clone(),
+                            content: entry.content.clone(),
+                        })
+                        .collect(),
+                    reason: ConflictReason::Diff3Conflict,
+                    atoms: Vec::new(),
+                })
+                .atoms
+                .push(atom);
+            continue;
+        };
+
+        splices
+            .entry((mv.to.clone(), mv.mover.clone()))
+            .or_default()
+            .push((mv.moved_item.start_byte, mv.moved_item.end_byte, merged));
+        for (entry, item) in &editors {
+            splices
+                .entry((mv.from.clone(), entry.workspace_id.clone()))
+                .or_default()
+                .push((item.start_byte, item.end_byte, mv.base_item.content.clone()));
+        }
+
+        let editor_ids: Vec<WorkspaceId> = editors
+            .iter()
+            .map(|(entry, _)| entry.workspace_id.clone())
+            .collect();
+        let mut workspaces = editor_ids.clone();
+        workspaces.push(mv.mover.clone());
+        workspaces.sort();
+        auto_resolutions.push(AutoResolution {
+            path: mv.to.clone(),
+            kind: AutoResolutionKind::SymbolMove,
+            workspaces,
+            details: vec![format!(
+                "{} moved from {} by {}; edits from {} applied here",
+                mv.label(),
+                mv.from.display(),
+                mv.mover,
+                join_ids(&editor_ids),
+            )],
+        });
+    }
+
+    let mut partition = Cow::Borrowed(partition);
+    if !splices.is_empty() || !conflicts.is_empty() {
+        rewrite_partition(partition.to_mut(), base_contents, splices, &conflicts);
+    }
+
+    Ok(SymbolMoveResult {
+        partition,
+        auto_resolutions,
+        conflicts: conflicts.into_values().collect(),
+    })
+}
+
+/// Find items each workspace removed from a shared path and added, under the
+/// same identity, to another path it changed.
+fn find_moves(
+    partition: &PartitionResult,
+    base_contents: &BTreeMap<PathBuf, Vec<u8>>,
+    config: &AstMergeConfig,
+) -> Vec<SymbolMove> {
+    let shared: BTreeSet<&Path> = partition
+        .shared
+        .iter()
+        .map(|(path, _)| path.as_path())
+        .collect();
+
+    let mut by_workspace: BTreeMap<&WorkspaceId, Vec<(&Path, &PathEntry, AstLanguage)>> =
+        BTreeMap::new();
+    let changes = partition
+        .unique
+        .iter()
+        .map(|(path, entry)| (path, entry))
+        .chain(
+            partition
+                .shared
+                .iter()
+                .flat_map(|(path, entries)| entries.iter().map(move |entry| (path, entry))),
+        );
+    for (path, entry) in changes {
+        if let Some(lang) = config.is_enabled_for(path) {
+            by_workspace.entry(&entry.workspace_id).or_default().push((
+                path.as_path(),
+                entry,
+                lang,
+            ));
+        }
+    }
+
+    let mut moves = Vec::new();
+    for (workspace, changes) in by_workspace {
+        // A move only matters if another workspace could have edited the
+        // old location, i.e. the workspace touched a shared path too.
+        if changes.len() < 2 || !changes.iter().any(|(path, ..)| shared.contains(path)) {
+            continue;
+        }
+
+        let mut removed: BTreeMap<SymbolKey, Vec<(&Path, AstLanguage, TopLevelItem)>> =
+            BTreeMap::new();
+        let mut added: BTreeMap<SymbolKey, Vec<(&Path, TopLevelItem)>> = BTreeMap::new();
+        for (path, entry, lang) in changes {
+            let base_items = base_contents
+                .get(path)
+                .and_then(|base| unique_items(base, lang))
+                .unwrap_or_default();
+            let new_items = entry
+                .content
+                .as_deref()
+                .and_then(|content| unique_items(content, lang))
+                .unwrap_or_default();
+            if shared.contains(path) {
+                for (key, item) in &base_items {
+                    if !new_items.contains_key(key) {
+                        removed
+                            .entry(key.clone())
+                            .or_default()
+                            .push((path, lang, item.clone()));
+                    }
+                }
+            }
+            for (key, item) in new_items {
+                if !base_items.contains_key(&key) {
+                    added.entry(key).or_default().push((path, item));
+                }
+            }
+        }
+
+        for (key, sources) in removed {
+            let Some(targets) = added.get(&key) else {
+                continue;
+            };
+            let ([(from, lang, base_item)], [(to, moved_item)]) =
+                (sources.as_slice(), targets.as_slice())
+            else {
+                continue;
+            };
+            moves.push(SymbolMove {
+                mover: workspace.clone(),
+                from: from.to_path_buf(),
+                to: to.to_path_buf(),
+                lang: *lang,
+                key,
+                base_item: base_item.clone(),
+                moved_item: moved_item.clone(),
+            });
+        }
+    }
+    moves
+}
+
+/// Named, non-import items of a file that are unambiguous under their key.
+fn unique_items(source: &[u8], lang: AstLanguage) -> Option<BTreeMap<SymbolKey, TopLevelItem>> {
+    let items = extract_top_level_items(source, lang).ok()?;
+    let mut unique = BTreeMap::new();
+    let mut ambiguous = BTreeSet::new();
+    for item in items {
+        let Some(name) = item.name.clone() else {
+            continue;
+        };
+        if is_import_kind(&item.kind) {
+            continue;
+        }
+        let key = (item.scope.clone(), item.kind.clone(), name);
+        if unique.insert(key.clone(), item).is_some() {
+            ambiguous.insert(key);
+        }
+    }
+    unique.retain(|key, _| !ambiguous.contains(key));
+    Some(unique)
+}
+
+fn move_conflict_atom(mv: &SymbolMove, editors: &[(&PathEntry, TopLevelItem)]) -> ConflictAtom {
+    let region = |item: &TopLevelItem| {
+        Region::ast_node(
+            &item.kind,
+            item.qualified_name(),
+            u32::try_from(item.start_byte).unwrap_or(u32::MAX),
+            u32::try_from(item.end_byte).unwrap_or(u32::MAX),
+        )
+    };
+    let editor_ids: Vec<WorkspaceId> = editors
+        .iter()
+        .map(|(entry, _)| entry.workspace_id.clone())
+        .collect();
+
+    let mut edits = vec![AtomEdit::new(
+        mv.mover.to_string(),
+        region(&mv.moved_item),
+        String::from_utf8_lossy(&mv.moved_item.content),
+    )];
+    edits.extend(editors.iter().map(|(entry, item)| {
+        AtomEdit::new(
+            entry.workspace_id.to_string(),
+            region(item),
+            String::from_utf8_lossy(&item.content),
+        )
+    }));
+
+    let reason = conflict::ConflictReason::symbol_lifecycle(format!(
+        "{} moved to {} by {} while {} edited it in {}",
+        mv.label(),
+        mv.to.display(),
+        mv.mover,
+        join_ids(&editor_ids),
+        mv.from.display(),
+    ));
+    let semantic = SemanticConflictExplanation::new(
+        "symbol_move",
+        92,
+        "edits at the old location overlap the moved copy",
+        vec![
+            format!("from={}", mv.from.display()),
+            format!("to={}", mv.to.display()),
+            format!("mover={}", mv.mover),
+            format!("editors={}", join_ids(&editor_ids)),
+        ],
+    );
+    ConflictAtom::new(region(&mv.base_item), edits, reason).with_semantic(semantic)
+}
+
+/// Apply item splices, drop conflicted paths, and re-partition entries whose
+/// content is back to base.
+fn rewrite_partition(
+    partition: &mut PartitionResult,
+    base_contents: &BTreeMap<PathBuf, Vec<u8>>,
+    mut splices: BTreeMap<(PathBuf, WorkspaceId), Vec<Splice>>,
+    conflicts: &BTreeMap<PathBuf, ConflictRecord>,
+) {
+    let mut apply = |path: &Path, entry: &mut PathEntry| {
+        let Some(mut edits) = splices.remove(&(path.to_path_buf(), entry.workspace_id.clone()))
+        else {
+            return;
+        };
+        let Some(content) = entry.content.as_mut() else {
+            return;
+        };
+        edits.sort_by_key(|(start, ..)| std::cmp::Reverse(*start));
+        for (start, end, replacement) in edits {
+            content.splice(start..end, replacement);
+        }
+        entry.blob = None;
+    };
+
+    for (path, entry) in &mut partition.unique {
+        apply(path, entry);
+    }
+
+    let shared = std::mem::take(&mut partition.shared);
+    for (path, mut entries) in shared {
+        if conflicts.contains_key(&path) {
+            continue;
+        }
+        for entry in &mut entries {
+            apply(&path, entry);
+        }
+        let base = base_contents.get(&path);
+        entries.retain(|entry| entry.content.is_none() || entry.content.as_ref() != base);
+        match entries.len() {
+            0 => {}
+            1 => partition
+                .unique
+                .push((path, entries.pop().expect("one entry"))),
+            _ => partition.shared.push((path, entries)),
+        }
+    }
+    partition.unique.sort_by(|a, b| a.0.cmp(&b.0));
+}
+
+fn join_ids(ids: &[WorkspaceId]) -> String {
+    ids.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+mod tests {
+    use super::*;
+    use crate::merge::build::ResolvedChange;
+    use crate::merge::resolve::resolve_partition_with_ast;
+    use crate::merge::types::ChangeKind;
+
+    fn ws(name: &str) -> WorkspaceId {
+        WorkspaceId::new(name).expect("operation should succeed")
+    }
+
+    fn entry(name: &str, content: &str) -> PathEntry {
+        PathEntry::new(
+            ws(name),
+            ChangeKind::Modified,
+            Some(content.as_bytes().to_vec()),
+        )
+    }
+
+    fn content<'a>(result: &'a crate::merge::resolve::ResolveResult, path: &str) -> &'a str {
+        result
+            .resolved
+            .iter()
+            .find_map(|change| match change {
+                ResolvedChange::Upsert { path: p, content } if p == Path::new(path) => {
+                    Some(std::str::from_utf8(content).expect("utf-8"))
+                }
+                _ => None,
+            })
+            .expect("path should be resolved")
+    }
+
+    const A_BASE: &str =
+        "fn keep() {}\n\nfn moved(x: u32) -> u32 {\n    let y = x + 1;\n    y * 2\n}\n";
+    const B_BASE: &str = "fn other() {}\n";
+
+    /// ws-a moves `moved` from a.rs to b.rs; ws-b edits it in a.rs.
+    fn move_partition(a_edit: &str, b_moved: &str) -> PartitionResult {
+        PartitionResult {
+            unique: vec![(
+                PathBuf::from("src/b.rs"),
+                entry("ws-a", &format!("{B_BASE}\n{b_moved}\n")),
+            )],
+            shared: vec![(
+                PathBuf::from("src/a.rs"),
+                vec![entry("ws-a", "fn keep() {}\n"), entry("ws-b", a_edit)],
+            )],
+            df_clashes: vec![],
+        }
+    }
+
+    fn base_map() -> BTreeMap<PathBuf, Vec<u8>> {
+        BTreeMap::from([
+            (PathBuf::from("src/a.rs"), A_BASE.as_bytes().to_vec()),
+            (PathBuf::from("src/b.rs"), B_BASE.as_bytes().to_vec()),
+        ])
+    }
+
+    #[test]
+    fn edit_to_moved_function_follows_it_to_the_new_file() {
+        let a_edit = A_BASE.replace("y * 2", "y * 3");
+        let b_moved = "pub fn moved(x: u32) -> u32 {\n    let y = x + 1;\n    y * 2\n}";
+        let partition = move_partition(&a_edit, b_moved);
+
+        let result =
+            resolve_partition_with_ast(&partition, &base_map(), &AstMergeConfig::all_languages())
+                .expect("operation should succeed");
+
+        assert!(result.is_clean(), "conflicts: {:?}", result.conflicts);
+        assert_eq!(content(&result, "src/a.rs"), "fn keep() {}\n");
+        assert_eq!(
+            content(&result, "src/b.rs"),
+            "fn other() {}\n\npub fn moved(x: u32) -> u32 {\n    let y = x + 1;\n    y * 3\n}\n"
+        );
+
+        assert_eq!(result.auto_resolutions.len(), 1);
+        let record = &result.auto_resolutions[0];
+        assert_eq!(record.kind, AutoResolutionKind::SymbolMove);
+        assert_eq!(record.path, PathBuf::from("src/b.rs"));
+        assert_eq!(record.workspaces, vec![ws("ws-a"), ws("ws-b")]);
+        assert_eq!(
+            record.details,
+            vec!["function_item `moved` moved from src/a.rs by ws-a; edits from ws-b applied here"]
+        );
+    }
+
+    #[test]
+    fn overlapping_edit_to_moved_function_is_a_symbol_lifecycle_conflict() {
+        let a_edit = A_BASE.replace("x + 1", "x + 10");
+        let b_moved = "fn moved(x: u32) -> u32 {\n    let y = x + 2;\n    y * 2\n}";
+        let partition = move_partition(&a_edit, b_moved);
+
+        let result =
+            resolve_partition_with_ast(&partition, &base_map(), &AstMergeConfig::all_languages())
+                .expect("operation should succeed");
+
+        assert_eq!(result.conflicts.len(), 1);
+        let conflict = &result.conflicts[0];
+        assert_eq!(conflict.path, PathBuf::from("src/a.rs"));
+        assert_eq!(conflict.sides.len(), 2);
+        let atom = &conflict.atoms[0];
+        assert_eq!(atom.reason.variant_name(), "symbol_lifecycle_divergence");
+        let description = atom.reason.description();
+        assert!(description.contains("src/b.rs"), "{description}");
+        assert!(description.contains("src/a.rs"), "{description}");
+        assert!(result.auto_resolutions.is_empty());
+        // The moved copy is still merged so both places are visible.
+        assert!(content(&result, "src/b.rs").contains("x + 2"));
+    }
+
+    #[test]
+    fn moves_without_edits_at_the_old_location_are_left_alone() {
+        let partition = PartitionResult {
+            unique: vec![(PathBuf::from("src/a.rs"), entry("ws-a", "fn keep() {}\n"))],
+            shared: vec![(
+                PathBuf::from("src/b.rs"),
+                vec![
+                    entry("ws-a", &format!("{B_BASE}\nfn moved() {{}}\n")),
+                    entry("ws-b", "fn other() {}\n\nfn more() {}\n"),
+                ],
+            )],
+            df_clashes: vec![],
+        };
+
+        let moves = apply_symbol_moves(&partition, &base_map(), &AstMergeConfig::all_languages())
+            .expect("operation should succeed");
+        assert!(moves.auto_resolutions.is_empty());
+        assert!(moves.conflicts.is_empty());
+        assert!(matches!(moves.partition, Cow::Borrowed(_)));
+        assert_eq!(moves.partition.unique, partition.unique);
+        assert_eq!(moves.partition.shared, partition.shared);
+    }
+
+    #[test]
+    fn python_function_moved_between_modules_keeps_edit() {
+        let a_base = "def keep():\n    pass\n\n\ndef moved(x):\n    return x + 1\n";
+        let b_base = "def other():\n    pass\n";
+        let partition = PartitionResult {
+            unique: vec![(
+                PathBuf::from("pkg/b.py"),
+                entry(
+                    "ws-a",
+                    &format!("{b_base}\n\n{}", "def moved(x):\n    return x + 1\n"),
+                ),
+            )],
+            shared: vec![(
+                PathBuf::from("pkg/a.py"),
+                vec![
+                    entry("ws-a", "def keep():\n    pass\n"),
+                    entry("ws-b", &a_base.replace("x + 1", "x + 2")),
+                ],
+            )],
+            df_clashes: vec![],
+        };
+        let base = BTreeMap::from([
+            (PathBuf::from("pkg/a.py"), a_base.as_bytes().to_vec()),
+            (PathBuf::from("pkg/b.py"), b_base.as_bytes().to_vec()),
+        ]);
+
+        let result =
+            resolve_partition_with_ast(&partition, &base, &AstMergeConfig::all_languages())
+                .expect("operation should succeed");
+
+        assert!(result.is_clean(), "conflicts: {:?}", result.conflicts);
+        assert_eq!(content(&result, "pkg/a.py"), "def keep():\n    pass\n");
+        assert!(content(&result, "pkg/b.py").contains("return x + 2"));
+    }
+}
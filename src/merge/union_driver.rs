@@ -0,0 +1,277 @@
+//! Union merge drivers for append-only files (`union-sorted`,
+//! `union-dedupe`, `append` in `[[merge.drivers]]`).
+//!
+//! Changelogs, migration lists and registry files grow by concurrent
+//! additions that almost always belong together. `merge=union` in
+//! `.gitattributes` already keeps both sides of every conflicting hunk; these
+//! drivers do the same from the maw config, with a choice of how the hunk's
+//! lines are combined:
+//!
+//! - `append` — every workspace's lines, in workspace order.
+//! - `union-dedupe` — like `append`, minus lines already in the hunk.
+//! - `union-sorted` — the hunk's distinct lines, sorted. The result does not
+//!   depend on workspace order at all.
+//!
+//! Regions only one side changed merge as usual. N-way merges fold the sides
+//! in workspace order, like the gitattribute drivers. Non-UTF-8 content and
+//! mixed deletions are left to the normal pipeline.
+
+use std::path::Path;
+
+use glob::Pattern;
+
+use crate::config::{MergeDriver, MergeDriverKind};
+
+use super::diff3::{Diff3Segment, diff3_segments};
+
+/// How a union driver combines the lines of a conflicting hunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnionStrategy {
+    /// Every side's lines, in workspace order.
+    Append,
+    /// Every side's lines in workspace order, skipping lines already present
+    /// in the hunk.
+    Dedupe,
+    /// The hunk's distinct lines, sorted bytewise.
+    Sorted,
+}
+
+impl UnionStrategy {
+    /// The strategy for a driver kind, if it is a union driver.
+    #[must_use]
+    pub const fn from_kind(kind: &MergeDriverKind) -> Option<Self> {
+        match kind {
+            MergeDriverKind::Append => Some(Self::Append),
+            MergeDriverKind::UnionDedupe => Some(Self::Dedupe),
+            MergeDriverKind::UnionSorted => Some(Self::Sorted),
+            _ => None,
+        }
+    }
+
+    /// The config name of the strategy (`append`, `union-dedupe`, ...).
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Append => "append",
+            Self::Dedupe => "union-dedupe",
+            Self::Sorted => "union-sorted",
+        }
+    }
+
+    fn combine(self, ours: &[String], theirs: &[String]) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::with_capacity(ours.len() + theirs.len());
+        for line in ours.iter().chain(theirs) {
+            let line = terminated(line);
+            if self == Self::Append || !lines.contains(&line) {
+                lines.push(line);
+            }
+        }
+        if self == Self::Sorted {
+            lines.sort();
+        }
+        lines
+    }
+}
+
+/// A union driver selected for a path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnionDriver {
+    /// The driver's glob, for diagnostics.
+    pub match_glob: String,
+    /// How conflicting hunks are combined.
+    pub strategy: UnionStrategy,
+}
+
+/// The configured union drivers, as seen by RESOLVE.
+///
+/// Drivers are matched in order and the first match wins — a union driver
+/// only applies to a path when no earlier driver of another kind claims it.
+#[derive(Clone, Debug, Default)]
+pub struct UnionDriverSet {
+    entries: Vec<(Pattern, Option<UnionDriver>)>,
+}
+
+impl UnionDriverSet {
+    /// Build the set from the effective `[[merge.drivers]]` list.
+    ///
+    /// Invalid globs are skipped here; the BUILD phase reports them when it
+    /// compiles the same list.
+    #[must_use]
+    pub fn from_drivers(drivers: &[MergeDriver]) -> Self {
+        let entries = drivers
+            .iter()
+            .filter_map(|driver| {
+                let pattern = Pattern::new(&driver.match_glob).ok()?;
+                let union = UnionStrategy::from_kind(&driver.kind).map(|strategy| UnionDriver {
+                    match_glob: driver.match_glob.clone(),
+                    strategy,
+                });
+                Some((pattern, union))
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// The union driver selected for `path`, if any.
+    #[must_use]
+    pub fn for_path(&self, path: &Path) -> Option<&UnionDriver> {
+        self.entries
+            .iter()
+            .find(|(pattern, _)| pattern.matches_path(path))
+            .and_then(|(_, driver)| driver.as_ref())
+    }
+}
+
+/// Result of [`union_merge`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnionMerge {
+    /// The merged content.
+    pub content: Vec<u8>,
+    /// Number of conflicting hunks the strategy combined (0 when the sides
+    /// merged cleanly on their own).
+    pub combined_hunks: usize,
+}
+
+/// Merge `sides` (in workspace order) against `base` with `strategy`.
+///
+/// Returns `None` when any input is not UTF-8 or a hunk cannot be split
+/// (e.g. a side already contains conflict markers); the caller falls back
+/// to the normal merge pipeline.
+#[must_use]
+pub fn union_merge(
+    strategy: UnionStrategy,
+    base: Option<&[u8]>,
+    sides: &[&[u8]],
+) -> Option<UnionMerge> {
+    let base = std::str::from_utf8(base.unwrap_or_default()).ok()?;
+    let (first, rest) = sides.split_first()?;
+    let mut acc = std::str::from_utf8(first).ok()?.to_owned();
+    let mut combined_hunks = 0;
+
+    for side in rest {
+        let theirs = std::str::from_utf8(side).ok()?;
+        let merged = maw_git::merge::merge_text(
+            base.as_bytes(),
+            acc.as_bytes(),
+            theirs.as_bytes(),
+            "ours",
+            "base",
+            "theirs",
+        )
+        .ok()?;
+        if let maw_git::merge::MergeResult::Clean(bytes) = merged {
+            acc = String::from_utf8(bytes).ok()?;
+            continue;
+        }
+
+        let mut out = String::with_capacity(acc.len() + theirs.len());
+        for segment in diff3_segments(base, &acc, theirs)? {
+            match segment {
+                Diff3Segment::Common(lines) => out.extend(lines),
+                Diff3Segment::Conflict(hunk) => {
+                    combined_hunks += 1;
+                    if !out.is_empty() && !out.ends_with('\n') {
+                        out.push('\n');
+                    }
+                    out.extend(strategy.combine(&hunk.ours, &hunk.theirs));
+                }
+            }
+        }
+        acc = out;
+    }
+
+    Some(UnionMerge {
+        content: acc.into_bytes(),
+        combined_hunks,
+    })
+}
+
+/// `line` with a trailing newline, so lines from different sides can be
+/// compared and concatenated.
+fn terminated(line: &str) -> String {
+    if line.ends_with('\n') {
+        line.to_owned()
+    } else {
+        format!("{line}\n")
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+mod tests {
+    use super::*;
+
+    fn merge(strategy: UnionStrategy, base: &str, sides: &[&str]) -> String {
+        let sides: Vec<&[u8]> = sides.iter().map(|s| s.as_bytes()).collect();
+        let merged = union_merge(strategy, Some(base.as_bytes()), &sides).unwrap();
+        String::from_utf8(merged.content).unwrap()
+    }
+
+    #[test]
+    fn append_keeps_every_side_in_workspace_order() {
+        let base = "# Changelog\n- one\n";
+        let out = merge(
+            UnionStrategy::Append,
+            base,
+            &[
+                "# Changelog\n- one\n- alice\n",
+                "# Changelog\n- one\n- bob\n",
+                "# Changelog\n- one\n- carol\n",
+            ],
+        );
+        assert_eq!(out, "# Changelog\n- one\n- alice\n- bob\n- carol\n");
+    }
+
+    #[test]
+    fn dedupe_drops_lines_both_sides_added() {
+        let base = "a\n";
+        let out = merge(
+            UnionStrategy::Dedupe,
+            base,
+            &["a\nshared\nalice\n", "a\nshared\nbob\n"],
+        );
+        assert_eq!(out, "a\nshared\nalice\nbob\n");
+    }
+
+    #[test]
+    fn sorted_is_independent_of_workspace_order() {
+        let base = "0001_init.sql\n";
+        let alice = "0001_init.sql\n0003_users.sql\n";
+        let bob = "0001_init.sql\n0002_orders.sql\n";
+        let ab = merge(UnionStrategy::Sorted, base, &[alice, bob]);
+        let ba = merge(UnionStrategy::Sorted, base, &[bob, alice]);
+        assert_eq!(ab, "0001_init.sql\n0002_orders.sql\n0003_users.sql\n");
+        assert_eq!(ab, ba);
+    }
+
+    #[test]
+    fn missing_trailing_newline_does_not_glue_lines() {
+        let out = merge(UnionStrategy::Append, "a\n", &["a\nb", "a\nc"]);
+        assert_eq!(out, "a\nb\nc\n");
+    }
+
+    #[test]
+    fn binary_sides_are_not_merged() {
+        let sides: Vec<&[u8]> = vec![b"a\xff\n", b"b\n"];
+        assert!(union_merge(UnionStrategy::Append, Some(b"a\n"), &sides).is_none());
+    }
+
+    #[test]
+    fn first_matching_driver_wins() {
+        let driver = |glob: &str, kind| MergeDriver {
+            match_glob: glob.into(),
+            kind,
+            command: None,
+            required: false,
+            timeout_seconds: 60,
+        };
+        let set = UnionDriverSet::from_drivers(&[
+            driver("CHANGELOG.md", MergeDriverKind::Ours),
+            driver("*.md", MergeDriverKind::UnionDedupe),
+        ]);
+        assert!(set.for_path(Path::new("CHANGELOG.md")).is_none());
+        let docs = set.for_path(Path::new("NOTES.md")).unwrap();
+        assert_eq!(docs.strategy, UnionStrategy::Dedupe);
+        assert_eq!(docs.match_glob, "*.md");
+    }
+}
@@ -151,19 +151,18 @@ impl From<MergeStateError> for ValidateError {
 /// admin name is fine; we prune any stale entry before re-creating.
 const VALIDATE_WORKTREE_NAME: &str = "manifold-validate-tmp";
 
-/// Create a temporary detached git worktree at the given commit.
-fn create_temp_worktree(
+/// Create a temporary detached git worktree at the given commit, registered
+/// under the admin name `name`.
+pub(super) fn create_temp_worktree(
     repo_root: &Path,
+    name: &str,
     candidate_oid: &GitOid,
     worktree_path: &Path,
 ) -> Result<(), ValidateError> {
     let repo = GixRepo::open(repo_root)
         .map_err(|e| ValidateError::WorktreeCreate(format!("open repo: {e}")))?;
     // Idempotent cleanup of any previous attempt's admin dir so worktree_add succeeds.
-    let admin_dir = repo
-        .common_dir()
-        .join("worktrees")
-        .join(VALIDATE_WORKTREE_NAME);
+    let admin_dir = repo.common_dir().join("worktrees").join(name);
     if admin_dir.exists() {
         let _ = std::fs::remove_dir_all(&admin_dir);
     }
@@ -171,24 +170,20 @@ fn create_temp_worktree(
         .as_str()
         .parse()
         .map_err(|e| ValidateError::WorktreeCreate(format!("parse candidate oid: {e}")))?;
-    repo.worktree_add(VALIDATE_WORKTREE_NAME, target, worktree_path)
+    repo.worktree_add(name, target, worktree_path)
         .map_err(|e| ValidateError::WorktreeCreate(e.to_string()))?;
     Ok(())
 }
 
-/// Remove a temporary git worktree.
-fn remove_temp_worktree(repo_root: &Path, worktree_path: &Path) -> Result<(), ValidateError> {
-    let _ = worktree_path; // path is fixed at the well-known admin name
+/// Remove a temporary git worktree registered under the admin name `name`.
+pub(super) fn remove_temp_worktree(repo_root: &Path, name: &str) -> Result<(), ValidateError> {
     let repo = GixRepo::open(repo_root)
         .map_err(|e| ValidateError::WorktreeRemove(format!("open repo: {e}")))?;
-    let admin_dir = repo
-        .common_dir()
-        .join("worktrees")
-        .join(VALIDATE_WORKTREE_NAME);
+    let admin_dir = repo.common_dir().join("worktrees").join(name);
     if !admin_dir.exists() {
         return Ok(());
     }
-    repo.worktree_remove(VALIDATE_WORKTREE_NAME)
+    repo.worktree_remove(name)
         .map_err(|e| ValidateError::WorktreeRemove(e.to_string()))?;
     Ok(())
 }
@@ -286,7 +281,7 @@ pub fn run_validate_phase(
     let worktree_dir = repo_root.join(".manifold").join("validate-tmp");
     // Clean up any stale worktree from a previous crash
     if worktree_dir.exists() {
-        let _ = remove_temp_worktree(repo_root, &worktree_dir);
+        let _ = remove_temp_worktree(repo_root, VALIDATE_WORKTREE_NAME);
         // Also try just removing the directory if git worktree remove failed
         let _ = fs::remove_dir_all(&worktree_dir);
     }
@@ -301,13 +296,18 @@ pub fn run_validate_phase(
         return Ok(ValidateOutcome::Skipped);
     }
 
-    create_temp_worktree(repo_root, candidate_oid, &worktree_dir)?;
+    create_temp_worktree(
+        repo_root,
+        VALIDATE_WORKTREE_NAME,
+        candidate_oid,
+        &worktree_dir,
+    )?;
 
     // Resolve the full command list (explicit wins; preset is fallback).
     let commands = resolve_commands(config, &worktree_dir);
     if commands.is_empty() {
         // Preset was configured but auto-detection found nothing.
-        let _ = remove_temp_worktree(repo_root, &worktree_dir);
+        let _ = remove_temp_worktree(repo_root, VALIDATE_WORKTREE_NAME);
         let _ = fs::remove_dir_all(&worktree_dir);
         return Ok(ValidateOutcome::Skipped);
     }
@@ -319,7 +319,7 @@ pub fn run_validate_phase(
     let result = run_commands_pipeline(&cmd_refs, &worktree_dir, config.timeout_seconds);
 
     // 4. Clean up worktree (best-effort)
-    let _ = remove_temp_worktree(repo_root, &worktree_dir);
+    let _ = remove_temp_worktree(repo_root, VALIDATE_WORKTREE_NAME);
     let _ = fs::remove_dir_all(&worktree_dir);
 
     let result = result?;
@@ -399,16 +399,32 @@ pub fn write_validation_artifact(
     manifold_dir: &Path,
     merge_id: &str,
     result: &ValidationResult,
+) -> Result<PathBuf, ValidateError> {
+    write_merge_artifact(manifold_dir, merge_id, "validation.json", result)
+}
+
+/// Write `value` as pretty JSON to
+/// `.manifold/artifacts/merge/<merge_id>/<file_name>`, atomically
+/// (write-to-temp + rename).
+///
+/// # Errors
+///
+/// Returns [`ValidateError::ArtifactWrite`] on I/O or serialization failure.
+pub fn write_merge_artifact<T: serde::Serialize>(
+    manifold_dir: &Path,
+    merge_id: &str,
+    file_name: &str,
+    value: &T,
 ) -> Result<PathBuf, ValidateError> {
     let artifact_dir = manifold_dir.join("artifacts").join("merge").join(merge_id);
     fs::create_dir_all(&artifact_dir).map_err(|e| {
         ValidateError::ArtifactWrite(format!("create dir {}: {e}", artifact_dir.display()))
     })?;
 
-    let artifact_path = artifact_dir.join("validation.json");
-    let tmp_path = artifact_dir.join(".validation.json.tmp");
+    let artifact_path = artifact_dir.join(file_name);
+    let tmp_path = artifact_dir.join(format!(".{file_name}.tmp"));
 
-    let json = serde_json::to_string_pretty(result)
+    let json = serde_json::to_string_pretty(value)
         .map_err(|e| ValidateError::ArtifactWrite(format!("serialize: {e}")))?;
 
     let mut file = fs::File::create(&tmp_path)
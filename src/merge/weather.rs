@@ -0,0 +1,324 @@
+//! Merge weather: predicted conflicts between live workspaces.
+//!
+//! `maw ws overlap` compares touched paths; this module runs the real
+//! PARTITION + RESOLVE steps — diff3, structured/AST merge, external drivers
+//! and normalization included — over every pair of workspaces and over all
+//! of them together. Everything happens in memory against the current epoch:
+//! no candidate commit is built, no merge-state is written and the epoch does
+//! not move.
+//!
+//! A coordinator uses the resulting matrix to schedule merges in a
+//! low-conflict order. Conflicts on paths claimed by an `ours`, `theirs` or
+//! `regenerate` merge driver are not reported, since BUILD resolves them
+//! deterministically.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use glob::Pattern;
+
+use super::build_phase::{
+    BuildPhaseError, load_attrs_at_epoch, read_base_contents, resolve_partition_for_build,
+};
+use super::partition::partition_by_path;
+use super::rerere::ResolutionMemory;
+use super::resolve::{AutoResolution, ConflictRecord};
+use super::types::PatchSet;
+use crate::config::{MergeConfig, MergeDriverKind};
+use crate::model::types::{EpochId, WorkspaceId};
+
+/// Predicted outcome of merging one group of workspaces.
+#[derive(Clone, Debug)]
+pub struct Forecast {
+    /// The workspaces in the group, sorted.
+    pub workspaces: Vec<WorkspaceId>,
+    /// Paths touched by more than one workspace of the group.
+    pub shared_paths: Vec<PathBuf>,
+    /// Conflicts RESOLVE would report, with hunk/AST-node atoms.
+    pub conflicts: Vec<ConflictRecord>,
+    /// Overlaps a structural resolver would merge cleanly.
+    pub auto_resolutions: Vec<AutoResolution>,
+}
+
+impl Forecast {
+    /// Returns `true` if the group would merge without conflicts.
+    #[must_use]
+    pub const fn is_clear(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Forecasts for every workspace pair and for the whole set.
+#[derive(Clone, Debug)]
+pub struct MergeWeather {
+    /// The epoch the forecast was computed against.
+    pub epoch: EpochId,
+    /// All workspaces considered, sorted.
+    pub workspaces: Vec<WorkspaceId>,
+    /// One forecast per unordered pair, in (a, b) order.
+    pub pairs: Vec<Forecast>,
+    /// Forecast for merging every workspace at once (`None` with fewer than
+    /// three workspaces, where it would repeat the single pair).
+    pub all: Option<Forecast>,
+}
+
+impl MergeWeather {
+    /// The forecast for a pair, in either order.
+    #[must_use]
+    pub fn pair(&self, a: &WorkspaceId, b: &WorkspaceId) -> Option<&Forecast> {
+        self.pairs.iter().find(|forecast| {
+            forecast.workspaces.len() == 2
+                && forecast.workspaces.contains(a)
+                && forecast.workspaces.contains(b)
+        })
+    }
+}
+
+/// Forecast merges of `patch_sets` (one per workspace) onto `epoch`.
+///
+/// # Errors
+///
+/// Returns [`BuildPhaseError`] if base contents cannot be read or RESOLVE
+/// fails (e.g. a required external driver errors).
+pub fn forecast(
+    repo_root: &std::path::Path,
+    epoch: &EpochId,
+    patch_sets: &[PatchSet],
+    merge_config: &MergeConfig,
+) -> Result<MergeWeather, BuildPhaseError> {
+    let mut patch_sets: Vec<&PatchSet> = patch_sets.iter().collect();
+    patch_sets.sort_by(|a, b| a.workspace_id.cmp(&b.workspace_id));
+
+    // Read every touched base file once; each group reads from this cache.
+    let everything: Vec<PatchSet> = patch_sets.iter().map(|ps| (*ps).clone()).collect();
+    let base_contents = read_base_contents(repo_root, epoch, &partition_by_path(&everything))?;
+    let attrs = load_attrs_at_epoch(repo_root, epoch);
+    let claimed = DriverClaims::new(merge_config);
+    let memory = ResolutionMemory::for_repo(repo_root);
+
+    let group = |members: &[&PatchSet]| -> Result<Forecast, BuildPhaseError> {
+        let owned: Vec<PatchSet> = members.iter().map(|ps| (*ps).clone()).collect();
+        let partition = partition_by_path(&owned);
+        let shared_paths: Vec<PathBuf> = partition
+            .shared
+            .iter()
+            .map(|(path, _)| path.clone())
+            .collect();
+        let mut forecast = Forecast {
+            workspaces: members.iter().map(|ps| ps.workspace_id.clone()).collect(),
+            shared_paths,
+            conflicts: Vec::new(),
+            auto_resolutions: Vec::new(),
+        };
+        if partition.shared.is_empty() && partition.df_clashes.is_empty() {
+            return Ok(forecast);
+        }
+        let result = resolve_partition_for_build(
+            repo_root,
+            &partition,
+            &base_contents,
+            merge_config,
+            Some(&attrs),
+            &memory,
+        )?;
+        forecast.conflicts = result
+            .conflicts
+            .into_iter()
+            .filter(|conflict| !claimed.claims(&conflict.path))
+            .collect();
+        forecast.auto_resolutions = result.auto_resolutions;
+        Ok(forecast)
+    };
+
+    let mut pairs = Vec::new();
+    for (i, a) in patch_sets.iter().enumerate() {
+        for b in &patch_sets[i + 1..] {
+            pairs.push(group(&[a, b])?);
+        }
+    }
+    let all = if patch_sets.len() > 2 {
+        Some(group(&patch_sets)?)
+    } else {
+        None
+    };
+
+    Ok(MergeWeather {
+        epoch: epoch.clone(),
+        workspaces: patch_sets
+            .iter()
+            .map(|ps| ps.workspace_id.clone())
+            .collect(),
+        pairs,
+        all,
+    })
+}
+
+/// Paths BUILD resolves with a deterministic merge driver.
+struct DriverClaims {
+    /// (glob, resolves-in-BUILD) in config order; the first match wins.
+    drivers: Vec<(Pattern, bool)>,
+}
+
+impl DriverClaims {
+    fn new(merge_config: &MergeConfig) -> Self {
+        let drivers = merge_config
+            .effective_drivers()
+            .iter()
+            .filter_map(|driver| {
+                let pattern = Pattern::new(&driver.match_glob).ok()?;
+                Some((pattern, driver.kind != MergeDriverKind::External))
+            })
+            .collect();
+        Self { drivers }
+    }
+
+    fn claims(&self, path: &std::path::Path) -> bool {
+        self.drivers
+            .iter()
+            .find(|(pattern, _)| pattern.matches_path(path))
+            .is_some_and(|(_, resolves)| *resolves)
+    }
+}
+
+/// Conflict counts per pair, indexed like [`MergeWeather::workspaces`].
+///
+/// The diagonal is zero.
+#[must_use]
+pub fn conflict_matrix(weather: &MergeWeather) -> Vec<Vec<usize>> {
+    let index: BTreeMap<&WorkspaceId, usize> = weather
+        .workspaces
+        .iter()
+        .enumerate()
+        .map(|(i, ws)| (ws, i))
+        .collect();
+    let n = weather.workspaces.len();
+    let mut matrix = vec![vec![0; n]; n];
+    for forecast in &weather.pairs {
+        if let [a, b] = forecast.workspaces.as_slice()
+            && let (Some(&i), Some(&j)) = (index.get(a), index.get(b))
+        {
+            matrix[i][j] = forecast.conflicts.len();
+            matrix[j][i] = forecast.conflicts.len();
+        }
+    }
+    matrix
+}
+
+#[cfg(test)]
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+mod tests {
+    use super::*;
+    use crate::merge::types::{ChangeKind, FileChange};
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn ws(name: &str) -> WorkspaceId {
+        WorkspaceId::new(name).expect("operation should succeed")
+    }
+
+    fn run_git(root: &Path, args: &[&str]) -> String {
+        let out = Command::new("git")
+            .args(args)
+            .current_dir(root)
+            .output()
+            .expect("operation should succeed");
+        assert!(out.status.success(), "git {} failed", args.join(" "));
+        String::from_utf8_lossy(&out.stdout).trim().to_owned()
+    }
+
+    /// Epoch with `notes.txt` (five lines) and `other.txt`.
+    fn setup_repo() -> (TempDir, EpochId) {
+        let dir = TempDir::new().expect("operation should succeed");
+        let root = dir.path();
+        run_git(root, &["init"]);
+        run_git(root, &["config", "user.name", "Test"]);
+        run_git(root, &["config", "user.email", "test@test.com"]);
+        run_git(root, &["config", "commit.gpgsign", "false"]);
+        fs::write(root.join("notes.txt"), "1\n2\n3\n4\n5\n").expect("operation should succeed");
+        fs::write(root.join("other.txt"), "x\n").expect("operation should succeed");
+        run_git(root, &["add", "."]);
+        run_git(root, &["commit", "-m", "epoch"]);
+        let epoch =
+            EpochId::new(&run_git(root, &["rev-parse", "HEAD"])).expect("operation should succeed");
+        (dir, epoch)
+    }
+
+    fn patch_set(name: &str, epoch: &EpochId, changes: &[(&str, &str)]) -> PatchSet {
+        PatchSet::new(
+            ws(name),
+            epoch.clone(),
+            changes
+                .iter()
+                .map(|(path, content)| {
+                    FileChange::new(
+                        PathBuf::from(path),
+                        ChangeKind::Modified,
+                        Some(content.as_bytes().to_vec()),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn forecast_reports_conflicting_and_clear_pairs() {
+        let (dir, epoch) = setup_repo();
+        let patch_sets = vec![
+            patch_set("alice", &epoch, &[("notes.txt", "ONE\n2\n3\n4\n5\n")]),
+            patch_set("bob", &epoch, &[("notes.txt", "uno\n2\n3\n4\n5\n")]),
+            patch_set("carol", &epoch, &[("notes.txt", "1\n2\n3\n4\nFIVE\n")]),
+            patch_set("dave", &epoch, &[("other.txt", "y\n")]),
+        ];
+
+        let weather = forecast(dir.path(), &epoch, &patch_sets, &MergeConfig::default())
+            .expect("operation should succeed");
+
+        assert_eq!(weather.pairs.len(), 6);
+        let alice_bob = weather.pair(&ws("bob"), &ws("alice")).expect("pair");
+        assert_eq!(alice_bob.shared_paths, vec![PathBuf::from("notes.txt")]);
+        assert_eq!(alice_bob.conflicts.len(), 1);
+        assert!(!alice_bob.conflicts[0].atoms.is_empty());
+
+        let alice_carol = weather.pair(&ws("alice"), &ws("carol")).expect("pair");
+        assert_eq!(alice_carol.shared_paths.len(), 1);
+        assert!(alice_carol.is_clear());
+
+        let alice_dave = weather.pair(&ws("alice"), &ws("dave")).expect("pair");
+        assert!(alice_dave.shared_paths.is_empty());
+        assert!(alice_dave.is_clear());
+
+        let all = weather.all.as_ref().expect("group forecast");
+        assert_eq!(all.workspaces.len(), 4);
+        assert_eq!(all.conflicts.len(), 1);
+
+        let matrix = conflict_matrix(&weather);
+        assert_eq!(matrix[0], vec![0, 1, 0, 0]);
+        assert_eq!(matrix[1], vec![1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn driver_claimed_paths_are_not_predicted_conflicts() {
+        let (dir, epoch) = setup_repo();
+        let patch_sets = vec![
+            patch_set("alice", &epoch, &[("notes.txt", "ONE\n2\n3\n4\n5\n")]),
+            patch_set("bob", &epoch, &[("notes.txt", "uno\n2\n3\n4\n5\n")]),
+        ];
+        let mut config = MergeConfig::default();
+        config.drivers.push(crate::config::MergeDriver {
+            match_glob: "*.txt".to_owned(),
+            kind: MergeDriverKind::Ours,
+            command: None,
+            required: false,
+            timeout_seconds: 60,
+        });
+
+        let weather =
+            forecast(dir.path(), &epoch, &patch_sets, &config).expect("operation should succeed");
+
+        assert!(weather.all.is_none());
+        assert!(weather.pairs[0].is_clear());
+        assert_eq!(weather.pairs[0].shared_paths.len(), 1);
+    }
+}
@@ -0,0 +1,79 @@
+//! Integration tests: `maw exec --sandbox`.
+//!
+//! The sandbox runs under bubblewrap; these tests are skipped when `bwrap`
+//! is not installed or cannot create namespaces on this machine.
+
+mod manifold_common;
+
+use std::process::Command;
+
+use manifold_common::TestRepo;
+
+fn bwrap_available() -> bool {
+    Command::new("bwrap")
+        .args(["--unshare-user", "--ro-bind", "/", "/", "true"])
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[test]
+fn git_commit_works_inside_a_sandboxed_worktree() {
+    if !bwrap_available() {
+        eprintln!("skipping: bubblewrap is not available");
+        return;
+    }
+    let repo = TestRepo::new();
+    repo.create_workspace("alice");
+    repo.create_workspace("bob");
+    repo.add_file("alice", "notes.txt", "hello\n");
+
+    let out = repo.maw_raw(&[
+        "exec",
+        "--sandbox",
+        "alice",
+        "--",
+        "git",
+        "add",
+        "notes.txt",
+    ]);
+    assert!(
+        out.status.success(),
+        "git add failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let out = repo.maw_raw(&[
+        "exec",
+        "--sandbox",
+        "alice",
+        "--",
+        "git",
+        "-c",
+        "user.name=Sandbox",
+        "-c",
+        "user.email=sandbox@example.com",
+        "commit",
+        "-m",
+        "sandboxed commit",
+    ]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(out.status.success(), "git commit failed: {stderr}");
+    assert!(!stderr.contains("sandbox: blocked"), "{stderr}");
+    assert_eq!(
+        repo.git_in_workspace("alice", &["log", "-1", "--format=%s"])
+            .trim(),
+        "sandboxed commit"
+    );
+
+    // Other worktrees' admin dirs stay read-only.
+    let bob_head = repo.root().join(".git/worktrees/bob/HEAD");
+    let out = repo.maw_raw(&[
+        "exec",
+        "--sandbox",
+        "alice",
+        "--",
+        "sh",
+        "-c",
+        &format!("echo x > {}", bob_head.display()),
+    ]);
+    assert!(!out.status.success(), "wrote to another worktree's HEAD");
+}
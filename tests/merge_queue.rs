@@ -0,0 +1,75 @@
+//! Integration tests: `maw merge queue run`.
+//!
+//! Speculation stacks the queued entries into cumulative candidates and
+//! validates them together; a failing candidate ejects its entry, the
+//! passing prefix commits, and the entries behind it are re-speculated.
+
+mod manifold_common;
+
+use manifold_common::TestRepo;
+
+/// Post-merge validation fails exactly when `bad.txt` is in the tree.
+fn fail_validation_on_bad_file(repo: &TestRepo) {
+    let manifold = repo.root().join(".manifold");
+    std::fs::create_dir_all(&manifold).expect("create .manifold");
+    std::fs::write(
+        manifold.join("config.toml"),
+        "[merge.validation]\ncommand = \"test ! -e bad.txt\"\n",
+    )
+    .expect("write .manifold/config.toml");
+}
+
+#[test]
+fn failing_middle_candidate_is_ejected_and_the_rest_respeculated() {
+    let repo = TestRepo::new();
+    repo.seed_files(&[("README.md", "# project\n")]);
+    fail_validation_on_bad_file(&repo);
+
+    for (ws, file) in [("alice", "a.txt"), ("bob", "bad.txt"), ("carol", "c.txt")] {
+        repo.create_workspace(ws);
+        repo.add_file(ws, file, &format!("{ws}\n"));
+        // Committed, so siblings are auto-rebased as earlier entries land.
+        repo.git_in_workspace(ws, &["add", file]);
+        repo.git_in_workspace(ws, &["commit", "-m", &format!("add {file}")]);
+        repo.maw_ok(&[
+            "merge",
+            "queue",
+            "add",
+            ws,
+            "--message",
+            &format!("feat: {ws}"),
+        ]);
+    }
+
+    // First round: q-1 passes; q-2 fails, and so does q-3 (its candidate
+    // includes q-2). Second round: q-3 alone, on top of q-1.
+    let stdout = repo.maw_ok(&["merge", "queue", "run", "--format", "json"]);
+    let output: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("stdout is not one JSON document ({e}):\n{stdout}"));
+
+    let merged: Vec<&str> = output["merged"]
+        .as_array()
+        .expect("merged")
+        .iter()
+        .map(|entry| entry["id"].as_str().expect("id"))
+        .collect();
+    assert_eq!(merged, ["q-1", "q-3"]);
+    for entry in output["merged"].as_array().expect("merged") {
+        assert_eq!(entry["merge"]["status"], "success", "{entry}");
+    }
+    let ejected = output["ejected"].as_array().expect("ejected");
+    assert_eq!(ejected.len(), 1);
+    assert_eq!(ejected[0]["id"], "q-2");
+    assert_eq!(ejected[0]["workspaces"][0], "bob");
+    assert!(
+        output["remaining"]
+            .as_array()
+            .expect("remaining")
+            .is_empty()
+    );
+
+    let tree = repo.git(&["ls-tree", "--name-only", "HEAD"]);
+    assert!(tree.contains("a.txt"), "{tree}");
+    assert!(tree.contains("c.txt"), "{tree}");
+    assert!(!tree.contains("bad.txt"), "{tree}");
+}
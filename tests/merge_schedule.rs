@@ -0,0 +1,39 @@
+//! Integration tests: `maw ws merge --schedule`.
+
+mod manifold_common;
+
+use manifold_common::TestRepo;
+
+#[test]
+fn scheduled_merge_prints_one_json_document_with_each_batch() {
+    let repo = TestRepo::new();
+    repo.seed_files(&[("shared.txt", "base\n")]);
+
+    // Both edit the same line, so they land in separate batches and the
+    // second batch cannot merge cleanly.
+    repo.create_workspace("alice");
+    repo.modify_file("alice", "shared.txt", "alice\n");
+    repo.create_workspace("bob");
+    repo.modify_file("bob", "shared.txt", "bob\n");
+
+    let out = repo.maw_raw(&[
+        "ws",
+        "merge",
+        "alice",
+        "bob",
+        "--schedule",
+        "--format",
+        "json",
+        "--message",
+        "feat: both",
+    ]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(!out.status.success(), "bob's batch should fail");
+    let output: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("stdout is not one JSON document ({e}):\n{stdout}"));
+
+    let batches = output["batches"].as_array().expect("batches");
+    assert_eq!(batches.len(), 2, "{output}");
+    assert_eq!(batches[0]["merge"]["status"], "success", "{output}");
+    assert!(batches[1]["error"].is_string(), "{output}");
+}